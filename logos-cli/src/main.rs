@@ -0,0 +1,244 @@
+//! <img src="https://raw.githubusercontent.com/maciejhirsz/logos/master/logos.svg?sanitize=true" alt="Logos logo" width="250" align="right">
+//!
+//! # Logos CLI
+//!
+//! Generate lexers ahead of time, without using the `#[derive(Logos)]` macro in your own builds.
+//!
+//! The input is a Rust source file containing one or more enums with `#[derive(Logos)]`. Each of those enums is
+//! written out as-is, minus the derive and all Logos-specific attributes, and is followed by its generated
+//! implementation of the `Logos` trait. Enums in inline modules are handled too, and every other item in the file is
+//! copied over unchanged.
+//!
+//! ```text
+//! logos-cli tokens.rs --output src/lexer.rs
+//! ```
+//!
+//! The output isn't formatted, so you'll probably want to run it through `rustfmt` before checking it in.
+//!
+//! The generated code still depends on the `logos` crate for the `Lexer` type and the `Logos` trait itself, but not on
+//! the derive macro, so it can be used with `default-features = false, features = ["std"]`.
+//!
+//! It also calls into `logos::internal`, which is hidden from the docs and can change in any release. Generated code is
+//! therefore tied to the exact version of `logos` that matches the version of `logos-cli` it was generated with, so pin
+//! that version (like `logos = "=0.13.0"`), and generate the code again whenever you upgrade. The version is recorded
+//! in the header of every generated file.
+
+use std::fs;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use proc_macro2::TokenStream;
+use quote::{quote, ToTokens};
+use syn::punctuated::Punctuated;
+use syn::{AttrStyle, Attribute, Item, ItemEnum, ItemMod, Meta, NestedMeta, Token};
+
+const USAGE: &str = "\
+Usage: logos-cli <INPUT> [--output <OUTPUT>]
+
+Generate Logos lexers ahead of time, from a file containing #[derive(Logos)] enums.
+
+Options:
+  -o, --output <OUTPUT>  Write the generated code to OUTPUT instead of stdout
+  -h, --help             Print this message
+";
+
+/// Attributes that are only meaningful to the derive macro, and have to be removed from its output.
+const ENUM_ATTRIBUTES: &[&str] = &["logos", "extras"];
+const VARIANT_ATTRIBUTES: &[&str] = &["logos", "token", "regex", "error", "end"];
+
+struct Args {
+    input: PathBuf,
+    output: Option<PathBuf>,
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut input = None;
+    let mut output = None;
+    let mut args = std::env::args_os().skip(1);
+
+    while let Some(arg) = args.next() {
+        match arg.to_str() {
+            Some("-h" | "--help") => {
+                print!("{}", USAGE);
+                std::process::exit(0);
+            }
+            Some("-o" | "--output") => match args.next() {
+                Some(path) => output = Some(PathBuf::from(path)),
+                None => return Err("Expected a path after --output".into()),
+            },
+            _ if input.is_none() => input = Some(PathBuf::from(arg)),
+            _ => return Err(format!("Unexpected argument: {}", arg.to_string_lossy())),
+        }
+    }
+
+    match input {
+        Some(input) => Ok(Args { input, output }),
+        None => Err("Missing the input file".into()),
+    }
+}
+
+fn main() -> ExitCode {
+    let args = match parse_args() {
+        Ok(args) => args,
+        Err(err) => {
+            eprint!("error: {}\n\n{}", err, USAGE);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match run(&args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(args: &Args) -> Result<(), String> {
+    let source = fs::read_to_string(&args.input)
+        .map_err(|err| format!("Failed to read {}: {}", args.input.display(), err))?;
+
+    let generated = generate_file(&source)
+        .map_err(|err| format!("Failed to parse {}: {}", args.input.display(), err))?;
+
+    let generated = format!(
+        "// Generated by logos-cli {} from `{}`. Do not edit by hand.\n\n{}\n",
+        env!("CARGO_PKG_VERSION"),
+        args.input.display(),
+        generated,
+    );
+
+    match &args.output {
+        Some(path) => fs::write(path, generated)
+            .map_err(|err| format!("Failed to write {}: {}", path.display(), err)),
+        None => {
+            print!("{}", generated);
+            Ok(())
+        }
+    }
+}
+
+/// Generate the code for an entire file, replacing every `#[derive(Logos)]` with its implementation.
+fn generate_file(source: &str) -> syn::Result<TokenStream> {
+    let file = syn::parse_file(source)?;
+    let mut out = TokenStream::new();
+
+    for attr in &file.attrs {
+        attr.to_tokens(&mut out);
+    }
+
+    generate_items(file.items, &mut out)?;
+
+    Ok(out)
+}
+
+/// Generate the code for `items`, including the items of inline modules.
+fn generate_items(items: Vec<Item>, out: &mut TokenStream) -> syn::Result<()> {
+    for item in items {
+        let mut item = match item {
+            Item::Enum(item) => item,
+            Item::Mod(ItemMod {
+                attrs,
+                vis,
+                mod_token,
+                ident,
+                content: Some((_, items)),
+                ..
+            }) => {
+                // The inner attributes of the module go inside of its braces.
+                let (inner, outer): (Vec<_>, Vec<_>) = attrs
+                    .into_iter()
+                    .partition(|attr| matches!(attr.style, AttrStyle::Inner(_)));
+                let mut content = TokenStream::new();
+
+                generate_items(items, &mut content)?;
+
+                out.extend(quote! {
+                    #(#outer)*
+                    #vis #mod_token #ident {
+                        #(#inner)*
+                        #content
+                    }
+                });
+                continue;
+            }
+            item => {
+                item.to_tokens(out);
+                continue;
+            }
+        };
+
+        if !strip_derive(&mut item.attrs)? {
+            item.to_tokens(out);
+            continue;
+        }
+
+        let generated = logos_codegen::generate(item.to_token_stream());
+
+        strip_attributes(&mut item);
+
+        out.extend(quote! {
+            #item
+
+            #generated
+        });
+    }
+
+    Ok(())
+}
+
+/// Remove `Logos` from any `#[derive(...)]` attributes, returning whether it was found.
+fn strip_derive(attrs: &mut Vec<Attribute>) -> syn::Result<bool> {
+    let mut found = false;
+    let mut stripped = Vec::with_capacity(attrs.len());
+
+    for mut attr in attrs.drain(..) {
+        if attr.path.is_ident("derive") {
+            if let Meta::List(list) = attr.parse_meta()? {
+                let len = list.nested.len();
+                let nested: Punctuated<NestedMeta, Token![,]> = list
+                    .nested
+                    .into_iter()
+                    .filter(|nested| !matches!(nested, NestedMeta::Meta(Meta::Path(path)) if is_logos(path)))
+                    .collect();
+
+                if nested.len() != len {
+                    found = true;
+
+                    // Don't leave an empty `#[derive()]` behind.
+                    if nested.is_empty() {
+                        continue;
+                    }
+
+                    attr.tokens = quote!((#nested));
+                }
+            }
+        }
+
+        stripped.push(attr);
+    }
+
+    *attrs = stripped;
+
+    Ok(found)
+}
+
+fn is_logos(path: &syn::Path) -> bool {
+    path.segments
+        .last()
+        .is_some_and(|segment| segment.ident == "Logos")
+}
+
+fn strip_attributes(item: &mut ItemEnum) {
+    let is_any =
+        |names: &[&str], attr: &Attribute| names.iter().any(|name| attr.path.is_ident(name));
+
+    item.attrs.retain(|attr| !is_any(ENUM_ATTRIBUTES, attr));
+
+    for variant in &mut item.variants {
+        variant
+            .attrs
+            .retain(|attr| !is_any(VARIANT_ATTRIBUTES, attr));
+    }
+}
@@ -0,0 +1,179 @@
+use std::path::PathBuf;
+use std::process::Command;
+
+fn fixture(name: &str, source: &str) -> PathBuf {
+    let dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR"));
+    let path = dir.join(name);
+
+    std::fs::write(&path, source).unwrap();
+
+    path
+}
+
+fn logos_cli(args: &[&std::ffi::OsStr]) -> std::process::Output {
+    Command::new(env!("CARGO_BIN_EXE_logos-cli"))
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn generates_impl() {
+    let input = fixture(
+        "generates_impl.rs",
+        r#"
+        use logos::Logos;
+
+        #[derive(Debug, Logos, PartialEq)]
+        #[logos(subpattern digit = "[0-9]")]
+        enum Token {
+            #[regex(r"[ \t\n]+", logos::skip)]
+            Whitespace,
+
+            #[token("let")]
+            Let,
+
+            #[regex("(?&digit)+")]
+            Number,
+        }
+
+        fn unrelated() {}
+        "#,
+    );
+
+    let output = logos_cli(&[input.as_os_str()]);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(output.status.success());
+    assert!(stdout.starts_with(&format!(
+        "// Generated by logos-cli {} from",
+        env!("CARGO_PKG_VERSION")
+    )));
+    assert!(stdout
+        .contains("# [derive (Debug , PartialEq)] enum Token { Whitespace , Let , Number , }"));
+    assert!(stdout.contains("impl < 's > :: logos :: Logos < 's > for Token"));
+    assert!(stdout.contains("fn unrelated () { }"));
+    assert!(!stdout.contains("# [token"));
+    assert!(!stdout.contains("# [logos"));
+}
+
+#[test]
+fn removes_empty_derive() {
+    let input = fixture(
+        "removes_empty_derive.rs",
+        r#"
+        #[derive(logos::Logos)]
+        enum Token {
+            #[token("a")]
+            A,
+        }
+        "#,
+    );
+    let output = input.with_extension("out.rs");
+
+    let status = logos_cli(&[input.as_os_str(), "-o".as_ref(), output.as_os_str()]).status;
+    let generated = std::fs::read_to_string(output).unwrap();
+
+    assert!(status.success());
+    assert!(!generated.contains("# [derive"));
+    assert!(generated.contains("enum Token { A , }"));
+}
+
+#[test]
+fn generated_output_compiles() {
+    let input = fixture(
+        "generated_output_compiles.rs",
+        r#"
+        use logos::Logos;
+
+        #[derive(Debug, Logos, PartialEq)]
+        enum Token {
+            #[regex(r"[ \t\n]+", logos::skip)]
+            Whitespace,
+
+            #[regex("[0-9]+")]
+            #[logos(name = "number")]
+            Number,
+
+            #[logos(eof)]
+            Eof,
+        }
+
+        mod words {
+            #![allow(dead_code)]
+
+            use logos::Logos;
+
+            #[derive(Debug, Logos, PartialEq)]
+            pub enum Word {
+                #[regex("[a-z]+")]
+                Word,
+            }
+        }
+
+        fn main() {
+            let tokens: Vec<_> = Token::lexer("1 2").collect();
+
+            assert_eq!(tokens, [Ok(Token::Number), Ok(Token::Number), Ok(Token::Eof)]);
+            assert_eq!(words::Word::lexer("abc").next(), Some(Ok(words::Word::Word)));
+        }
+        "#,
+    );
+
+    // Build the output as its own crate, against a `logos` without the derive macro.
+    let dir = input.with_extension("");
+    let logos = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../logos");
+
+    std::fs::create_dir_all(dir.join("src")).unwrap();
+    std::fs::write(
+        dir.join("Cargo.toml"),
+        format!(
+            r#"
+            [package]
+            name = "generated"
+            version = "0.0.0"
+            edition = "2021"
+
+            [dependencies]
+            logos = {{ path = {:?}, default-features = false, features = ["std"] }}
+
+            [workspace]
+            "#,
+            logos,
+        ),
+    )
+    .unwrap();
+
+    let output = dir.join("src/main.rs");
+    let status = logos_cli(&[input.as_os_str(), "-o".as_ref(), output.as_os_str()]).status;
+
+    assert!(status.success());
+    assert!(!std::fs::read_to_string(&output)
+        .unwrap()
+        .contains("# [logos"));
+
+    let cargo = std::env::var_os("CARGO").unwrap_or_else(|| "cargo".into());
+    let run = Command::new(cargo)
+        .args(["run", "--quiet", "--offline", "--manifest-path"])
+        .arg(dir.join("Cargo.toml"))
+        .output()
+        .unwrap();
+
+    assert!(
+        run.status.success(),
+        "{}",
+        String::from_utf8_lossy(&run.stderr)
+    );
+}
+
+#[test]
+fn invalid_input() {
+    let input = fixture("invalid_input.rs", "enum {");
+
+    let output = logos_cli(&[input.as_os_str()]);
+
+    assert!(!output.status.success());
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .starts_with("error: Failed to parse"));
+}
@@ -0,0 +1,476 @@
+use std::collections::{HashSet, VecDeque};
+
+use proc_macro2::TokenStream;
+use quote::{quote, ToTokens};
+use regex_syntax::utf8::Utf8Sequences;
+
+use crate::mir::{Class, Literal, Mir};
+
+/// A regex compiled to a small backtracking program, used at runtime to find the capture groups within a token that
+/// has already been matched by the state machine.
+#[derive(Debug)]
+pub struct Program {
+    insts: Vec<Inst>,
+    /// Names of the capture groups, with the implicit group 0 first.
+    names: Vec<Option<String>>,
+    /// Whether the program is compiled to read its input backwards, last byte first.
+    reverse: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Inst {
+    Range(u8, u8),
+    Split(usize, usize),
+    Jump(usize),
+    Save(usize),
+    Match,
+}
+
+impl Program {
+    /// Compile `mir` to a program, or return `None` if it doesn't contain any capture groups.
+    pub fn compile(mir: &Mir) -> Option<Program> {
+        let program = Program::lookahead(mir);
+
+        match program.names.len() {
+            1 => None,
+            _ => Some(program),
+        }
+    }
+
+    /// Compile `mir` to a program, whether or not it contains any capture groups. This is used for trailing context,
+    /// where the lexer only needs to know whether the program matches.
+    pub fn lookahead(mir: &Mir) -> Program {
+        Program::new(mir, false)
+    }
+
+    /// Compile `mir` to a program that reads its input backwards, so that running it from the start of a token
+    /// towards the start of the source checks the text before the token. This is used for `preceded_by`.
+    pub fn lookbehind(mir: &Mir) -> Program {
+        Program::new(mir, true)
+    }
+
+    fn new(mir: &Mir, reverse: bool) -> Program {
+        let mut names = vec![None];
+
+        collect_names(mir, &mut names);
+
+        let mut program = Program {
+            insts: Vec::new(),
+            names,
+            reverse,
+        };
+
+        program.push_mir(mir);
+        program.insts.push(Inst::Match);
+
+        program
+    }
+
+    /// Find a short input that both `self` and `other` match in their entirety, by searching through pairs of
+    /// positions in the two programs.
+    pub fn common_input(&self, other: &Program) -> Option<Vec<u8>> {
+        let start = pairs(self.closure(0), other.closure(0));
+        let mut seen: HashSet<(usize, usize)> = start.iter().copied().collect();
+        let mut queue: VecDeque<_> = start.into_iter().map(|pair| (pair, Vec::new())).collect();
+
+        while let Some(((a, b), input)) = queue.pop_front() {
+            match (self.insts[a], other.insts[b]) {
+                (Inst::Match, Inst::Match) if !input.is_empty() => return Some(input),
+                (Inst::Range(a_start, a_end), Inst::Range(b_start, b_end)) => {
+                    let (start, end) = (a_start.max(b_start), a_end.min(b_end));
+
+                    if start > end {
+                        continue;
+                    }
+
+                    for pair in pairs(self.closure(a + 1), other.closure(b + 1)) {
+                        if seen.insert(pair) {
+                            let mut input = input.clone();
+
+                            input.push(readable(start, end));
+                            queue.push_back((pair, input));
+                        }
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        None
+    }
+
+    /// The length of the longest prefix of `input` that the program matches, if it matches one.
+    pub fn longest_match(&self, input: &[u8]) -> Option<usize> {
+        let mut current = self.closure(0);
+        let mut longest = None;
+
+        for at in 0..=input.len() {
+            if current
+                .iter()
+                .any(|&pc| matches!(self.insts[pc], Inst::Match))
+            {
+                longest = Some(at);
+            }
+
+            let byte = match input.get(at) {
+                Some(&byte) => byte,
+                None => break,
+            };
+            let mut next: Vec<usize> = current
+                .iter()
+                .filter(|&&pc| matches!(self.insts[pc], Inst::Range(start, end) if (start..=end).contains(&byte)))
+                .flat_map(|&pc| self.closure(pc + 1))
+                .collect();
+
+            next.sort_unstable();
+            next.dedup();
+
+            if next.is_empty() {
+                break;
+            }
+
+            current = next;
+        }
+
+        longest
+    }
+
+    /// The inclusive ranges of bytes that a match can start with.
+    pub fn first_bytes(&self) -> Vec<(u8, u8)> {
+        self.closure(0)
+            .into_iter()
+            .filter_map(|pc| match self.insts[pc] {
+                Inst::Range(start, end) => Some((start, end)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Whether the program matches the empty string.
+    pub fn matches_empty(&self) -> bool {
+        self.closure(0)
+            .into_iter()
+            .any(|pc| matches!(self.insts[pc], Inst::Match))
+    }
+
+    /// The instructions that consume input or match, which can be reached from `pc` without consuming any input.
+    fn closure(&self, pc: usize) -> Vec<usize> {
+        let mut out = Vec::new();
+        let mut stack = vec![pc];
+        let mut seen = HashSet::new();
+
+        while let Some(pc) = stack.pop() {
+            if !seen.insert(pc) {
+                continue;
+            }
+
+            match self.insts[pc] {
+                Inst::Range(..) | Inst::Match => out.push(pc),
+                Inst::Split(first, second) => stack.extend([second, first]),
+                Inst::Jump(to) => stack.push(to),
+                Inst::Save(_) => stack.push(pc + 1),
+            }
+        }
+
+        out
+    }
+
+    fn push(&mut self, inst: Inst) -> usize {
+        self.insts.push(inst);
+        self.insts.len() - 1
+    }
+
+    /// Push a split that prefers the following instruction, to be patched with its alternative later.
+    fn push_split(&mut self) -> usize {
+        let next = self.insts.len() + 1;
+
+        self.push(Inst::Split(next, 0))
+    }
+
+    fn patch(&mut self, at: usize, target: usize) {
+        match &mut self.insts[at] {
+            Inst::Split(_, second) => *second = target,
+            Inst::Jump(to) => *to = target,
+            _ => unreachable!("Only splits and jumps can be patched"),
+        }
+    }
+
+    fn push_bytes(&mut self, bytes: &[u8]) {
+        self.push_ranges(bytes.iter().map(|&byte| (byte, byte)));
+    }
+
+    /// Push a sequence of byte ranges, in reverse order if the program reads its input backwards.
+    fn push_ranges<I>(&mut self, ranges: I)
+    where
+        I: DoubleEndedIterator<Item = (u8, u8)>,
+    {
+        let ranges: Box<dyn Iterator<Item = (u8, u8)>> = match self.reverse {
+            true => Box::new(ranges.rev()),
+            false => Box::new(ranges),
+        };
+
+        for (start, end) in ranges {
+            self.push(Inst::Range(start, end));
+        }
+    }
+
+    /// Push each alternative produced by `alts`, preferring earlier ones.
+    fn push_alternation<I, F>(&mut self, alts: I, mut push_alt: F)
+    where
+        I: IntoIterator,
+        I::IntoIter: ExactSizeIterator,
+        F: FnMut(&mut Self, I::Item),
+    {
+        let alts = alts.into_iter();
+        let count = alts.len();
+        let mut jumps = Vec::with_capacity(count);
+
+        for (index, alt) in alts.enumerate() {
+            if index + 1 == count {
+                push_alt(self, alt);
+                break;
+            }
+
+            let split = self.push_split();
+            push_alt(self, alt);
+            jumps.push(self.push(Inst::Jump(0)));
+
+            let next = self.insts.len();
+
+            self.patch(split, next);
+        }
+
+        let end = self.insts.len();
+
+        for jump in jumps {
+            self.patch(jump, end);
+        }
+    }
+
+    fn push_mir(&mut self, mir: &Mir) {
+        match mir {
+            Mir::Empty | Mir::Assertion(_) => (),
+            Mir::Literal(Literal::Unicode(unicode)) => {
+                self.push_bytes(unicode.encode_utf8(&mut [0; 4]).as_bytes());
+            }
+            Mir::Literal(Literal::Byte(byte)) => {
+                self.push(Inst::Range(*byte, *byte));
+            }
+            Mir::Class(Class::Unicode(class)) => {
+                let sequences = class
+                    .iter()
+                    .flat_map(|range| Utf8Sequences::new(range.start(), range.end()))
+                    .collect::<Vec<_>>();
+
+                self.push_alternation(sequences, |program, sequence| {
+                    program.push_ranges(
+                        sequence
+                            .as_slice()
+                            .iter()
+                            .map(|range| (range.start, range.end)),
+                    );
+                });
+            }
+            Mir::Class(Class::Bytes(class)) => {
+                self.push_alternation(class.ranges(), |program, range| {
+                    program.push(Inst::Range(range.start(), range.end()));
+                });
+            }
+            Mir::Concat(concat) if self.reverse => {
+                for mir in concat.iter().rev() {
+                    self.push_mir(mir);
+                }
+            }
+            Mir::Concat(concat) => {
+                for mir in concat {
+                    self.push_mir(mir);
+                }
+            }
+            Mir::Alternation(alternation) => {
+                self.push_alternation(alternation, |program, mir| program.push_mir(mir));
+            }
+            Mir::Maybe(mir) => {
+                let split = self.push_split();
+                self.push_mir(mir);
+
+                let end = self.insts.len();
+
+                self.patch(split, end);
+            }
+            Mir::Loop(mir) => {
+                let split = self.push_split();
+                self.push_mir(mir);
+                self.push(Inst::Jump(split));
+
+                let end = self.insts.len();
+
+                self.patch(split, end);
+            }
+            Mir::Capture(index, _, mir) => {
+                let slot = *index as usize * 2;
+
+                self.push(Inst::Save(slot));
+                self.push_mir(mir);
+                self.push(Inst::Save(slot + 1));
+            }
+        }
+    }
+}
+
+fn pairs(a: Vec<usize>, b: Vec<usize>) -> Vec<(usize, usize)> {
+    a.iter()
+        .flat_map(|&a| b.iter().map(move |&b| (a, b)))
+        .collect()
+}
+
+/// Pick a byte in the range that's easy to read in an error message, if there is one.
+fn readable(start: u8, end: u8) -> u8 {
+    [b'a', b'0', b' ']
+        .into_iter()
+        .find(|byte| (start..=end).contains(byte))
+        .unwrap_or_else(|| start.max(b'!').min(end))
+}
+
+fn collect_names(mir: &Mir, names: &mut Vec<Option<String>>) {
+    match mir {
+        Mir::Empty | Mir::Class(_) | Mir::Literal(_) | Mir::Assertion(_) => (),
+        Mir::Loop(mir) | Mir::Maybe(mir) => collect_names(mir, names),
+        Mir::Concat(mirs) | Mir::Alternation(mirs) => {
+            for mir in mirs {
+                collect_names(mir, names);
+            }
+        }
+        Mir::Capture(index, name, mir) => {
+            let index = *index as usize;
+
+            if names.len() <= index {
+                names.resize(index + 1, None);
+            }
+
+            if name.is_some() {
+                names[index] = name.clone();
+            }
+
+            collect_names(mir, names);
+        }
+    }
+}
+
+impl ToTokens for Program {
+    fn to_tokens(&self, out: &mut TokenStream) {
+        let insts = self.insts.iter().map(|inst| match *inst {
+            Inst::Range(start, end) => quote!(::logos::internal::CaptureInst::Range(#start, #end)),
+            Inst::Split(first, second) => {
+                quote!(::logos::internal::CaptureInst::Split(#first, #second))
+            }
+            Inst::Jump(to) => quote!(::logos::internal::CaptureInst::Jump(#to)),
+            Inst::Save(slot) => quote!(::logos::internal::CaptureInst::Save(#slot)),
+            Inst::Match => quote!(::logos::internal::CaptureInst::Match),
+        });
+        let names = self.names.iter().map(|name| match name {
+            Some(name) => quote!(::core::option::Option::Some(#name)),
+            None => quote!(::core::option::Option::None),
+        });
+
+        out.extend(quote! {
+            ::logos::internal::CaptureProgram {
+                insts: &[#(#insts),*],
+                names: &[#(#names),*],
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn no_captures() {
+        let mir = Mir::utf8("(?:foo)+|[a-z]").unwrap();
+
+        assert!(Program::compile(&mir).is_none());
+    }
+
+    #[test]
+    fn program() {
+        let mir = Mir::utf8("(?P<int>[0-9])(a|b)?").unwrap();
+        let program = Program::compile(&mir).unwrap();
+
+        assert_eq!(program.names, vec![None, Some("int".into()), None]);
+        assert_eq!(
+            program.insts,
+            vec![
+                Inst::Save(2),
+                Inst::Range(b'0', b'9'),
+                Inst::Save(3),
+                Inst::Split(4, 10),
+                Inst::Save(4),
+                Inst::Split(6, 8),
+                Inst::Range(b'a', b'a'),
+                Inst::Jump(9),
+                Inst::Range(b'b', b'b'),
+                Inst::Save(5),
+                Inst::Match,
+            ]
+        );
+    }
+
+    #[test]
+    fn common_input() {
+        let common = |a: &str, b: &str| {
+            let a = Program::lookahead(&Mir::utf8(a).unwrap());
+            let b = Program::lookahead(&Mir::utf8(b).unwrap());
+
+            a.common_input(&b)
+        };
+
+        assert_eq!(
+            common("fast", "(ridiculously)?fast(er|est)?"),
+            Some(b"fast".to_vec())
+        );
+        assert_eq!(common("[a-z]+", "[0-9a-f]+x"), Some(b"ax".to_vec()));
+        assert_eq!(common("[0-9]+", "0x[0-9]*"), None);
+        assert_eq!(common("[0-9]+", "[a-z]+"), None);
+        assert_eq!(common("a*", "b*"), None);
+    }
+
+    #[test]
+    fn longest_match() {
+        let program = Program::lookahead(&Mir::utf8("[a-z]+(-[a-z]+)?").unwrap());
+
+        assert_eq!(program.longest_match(b"foo-bar baz"), Some(7));
+        assert_eq!(program.longest_match(b"foo-"), Some(3));
+        assert_eq!(program.longest_match(b"foo"), Some(3));
+        assert_eq!(program.longest_match(b"-foo"), None);
+        assert_eq!(program.longest_match(b""), None);
+    }
+
+    #[test]
+    fn lookbehind() {
+        let program = Program::lookbehind(&Mir::utf8("[(=]ą? *").unwrap());
+        let reversed = |input: &str| input.bytes().rev().collect::<Vec<u8>>();
+
+        assert_eq!(program.longest_match(&reversed("x = ")), Some(2));
+        assert_eq!(program.longest_match(&reversed("(ą  ")), Some(5));
+        assert_eq!(program.longest_match(&reversed("x ")), None);
+        assert_eq!(program.longest_match(&reversed("=")), Some(1));
+    }
+
+    #[test]
+    fn first_bytes() {
+        let program = |source: &str| Program::lookahead(&Mir::utf8(source).unwrap());
+
+        assert_eq!(
+            program("(foo|[0-9])x").first_bytes(),
+            vec![(b'f', b'f'), (b'0', b'9')]
+        );
+        assert_eq!(
+            program("a?b").first_bytes(),
+            vec![(b'a', b'a'), (b'b', b'b')]
+        );
+        assert!(!program("a?b").matches_empty());
+        assert!(program("a*").matches_empty());
+    }
+}
@@ -0,0 +1,248 @@
+//! Full Unicode case folding for `ignore(case)`.
+//!
+//! `regex_syntax` only folds case simply, where every character folds to a single other character. Some characters
+//! fold to several though: `ß` folds to `ss`, and `ﬁ` to `fi`. [full] adds these to a pattern that's already been
+//! folded simply, so that `ß` also matches `SS`, and `ss` also matches `ß`.
+
+use regex_syntax::hir::{Class, ClassUnicode, ClassUnicodeRange, Literal};
+
+use crate::mir::Mir;
+
+/// The characters that fold to the same several characters.
+struct Fold {
+    to: Vec<char>,
+    from: ClassUnicode,
+}
+
+/// The characters that fold to several, grouped by what they fold to. This is the same as the "F" entries in Unicode's
+/// `CaseFolding.txt`, which are all in the Basic Multilingual Plane.
+fn folds() -> Vec<Fold> {
+    let mut folds: Vec<Fold> = Vec::new();
+
+    for ch in (0..=0xFFFF).filter_map(char::from_u32) {
+        let to: Vec<char> = ch.to_uppercase().flat_map(char::to_lowercase).collect();
+
+        if to.len() < 2 {
+            continue;
+        }
+
+        match folds.iter_mut().find(|fold| fold.to == to) {
+            Some(fold) => fold.from.union(&simple(ch)),
+            None => folds.push(Fold {
+                to,
+                from: simple(ch),
+            }),
+        }
+    }
+
+    folds
+}
+
+/// The characters that `ch` matches when case is folded simply, including itself.
+fn simple(ch: char) -> ClassUnicode {
+    let mut class = ClassUnicode::new([ClassUnicodeRange::new(ch, ch)]);
+
+    class.case_fold_simple();
+    class
+}
+
+fn contains(class: &ClassUnicode, ch: char) -> bool {
+    class
+        .ranges()
+        .iter()
+        .any(|range| range.start() <= ch && ch <= range.end())
+}
+
+/// Whether a part of a pattern that's been folded simply matches exactly the same characters as `ch` does.
+fn is_char(mir: &Mir, ch: char) -> bool {
+    match mir {
+        Mir::Literal(Literal::Unicode(literal)) => *literal == ch && simple(ch).ranges().len() == 1,
+        Mir::Class(Class::Unicode(class)) => *class == simple(ch),
+        _ => false,
+    }
+}
+
+/// Add the characters that fold to several characters to `mir`, which has already been folded simply.
+pub fn full(mir: Mir) -> Mir {
+    Folder { folds: folds() }.mir(mir)
+}
+
+struct Folder {
+    folds: Vec<Fold>,
+}
+
+impl Folder {
+    fn mir(&self, mir: Mir) -> Mir {
+        match mir {
+            Mir::Loop(mir) => Mir::Loop(Box::new(self.mir(*mir))),
+            Mir::Maybe(mir) => Mir::Maybe(Box::new(self.mir(*mir))),
+            Mir::Capture(index, name, mir) => Mir::Capture(index, name, Box::new(self.mir(*mir))),
+            Mir::Alternation(alternation) => {
+                Mir::Alternation(alternation.into_iter().map(|mir| self.mir(mir)).collect())
+            }
+            Mir::Concat(concat) => Mir::Concat(self.sequence(&concat)),
+            Mir::Class(Class::Unicode(class)) => self.class(class),
+            Mir::Literal(Literal::Unicode(ch))
+                if self.folds.iter().any(|fold| contains(&fold.from, ch)) =>
+            {
+                self.class(simple(ch))
+            }
+            mir => mir,
+        }
+    }
+
+    /// A class also matches what any of its characters fold to.
+    fn class(&self, class: ClassUnicode) -> Mir {
+        let mut alternation = Vec::new();
+
+        for fold in self.folds.iter() {
+            let mut from = fold.from.clone();
+
+            from.intersect(&class);
+
+            if from.ranges().is_empty() {
+                continue;
+            }
+
+            alternation.push(Mir::Concat(
+                fold.to
+                    .iter()
+                    .map(|&ch| Mir::Class(Class::Unicode(simple(ch))))
+                    .collect(),
+            ));
+        }
+
+        match alternation.is_empty() {
+            true => Mir::Class(Class::Unicode(class)),
+            false => {
+                alternation.insert(0, Mir::Class(Class::Unicode(class)));
+
+                Mir::Alternation(alternation)
+            }
+        }
+    }
+
+    /// A sequence of characters also matches a character that folds to them. Where there's more than one way to read a
+    /// sequence, like `sss`, each of them is tried.
+    fn sequence(&self, items: &[Mir]) -> Vec<Mir> {
+        let matches = |at: usize| {
+            self.folds.iter().filter(move |fold| {
+                let rest = &items[at..];
+
+                rest.len() >= fold.to.len()
+                    && rest
+                        .iter()
+                        .zip(fold.to.iter())
+                        .all(|(mir, &ch)| is_char(mir, ch))
+            })
+        };
+
+        let at = match (0..items.len()).find(|&at| matches(at).next().is_some()) {
+            Some(at) => at,
+            None => return items.iter().cloned().map(|mir| self.mir(mir)).collect(),
+        };
+
+        let mut out: Vec<Mir> = items[..at]
+            .iter()
+            .cloned()
+            .map(|mir| self.mir(mir))
+            .collect();
+        let mut first = vec![self.mir(items[at].clone())];
+
+        first.extend(self.sequence(&items[at + 1..]));
+
+        let mut alternation = vec![Mir::Concat(first)];
+
+        for fold in matches(at) {
+            let mut folded = vec![Mir::Class(Class::Unicode(fold.from.clone()))];
+
+            folded.extend(self.sequence(&items[at + fold.to.len()..]));
+            alternation.push(Mir::Concat(folded));
+        }
+
+        out.push(Mir::Alternation(alternation));
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matches(mir: &Mir, input: &str) -> bool {
+        fn rest<'a>(mir: &Mir, input: &'a str) -> Vec<&'a str> {
+            match mir {
+                Mir::Empty => vec![input],
+                Mir::Literal(Literal::Unicode(ch)) => input.strip_prefix(*ch).into_iter().collect(),
+                Mir::Class(Class::Unicode(class)) => input
+                    .chars()
+                    .next()
+                    .filter(|&ch| contains(class, ch))
+                    .map(|ch| &input[ch.len_utf8()..])
+                    .into_iter()
+                    .collect(),
+                Mir::Concat(concat) => concat.iter().fold(vec![input], |inputs, mir| {
+                    inputs
+                        .into_iter()
+                        .flat_map(|input| rest(mir, input))
+                        .collect()
+                }),
+                Mir::Alternation(alternation) => alternation
+                    .iter()
+                    .flat_map(|mir| rest(mir, input))
+                    .collect(),
+                mir => panic!("unexpected {:?}", mir),
+            }
+        }
+
+        rest(mir, input).contains(&"")
+    }
+
+    #[test]
+    fn multiple_characters() {
+        let folds = folds();
+        let to = |ch: char| {
+            &folds
+                .iter()
+                .find(|fold| is_char(&Mir::Class(Class::Unicode(fold.from.clone())), ch))
+                .unwrap()
+                .to
+        };
+
+        assert_eq!(to('ß'), &['s', 's']);
+        assert_eq!(to('ﬁ'), &['f', 'i']);
+        assert_eq!(to('İ'), &['i', '\u{307}']);
+        assert!(folds.iter().all(|fold| fold.to.len() > 1));
+    }
+
+    #[test]
+    fn literals() {
+        let mir = Mir::utf8_ignore_case("straße").unwrap();
+
+        for input in ["straße", "STRASSE", "Strasse", "STRAẞE", "strasſe"] {
+            assert!(matches(&mir, input), "{}", input);
+        }
+        for input in ["strase", "straßse", "STRAS"] {
+            assert!(!matches(&mir, input), "{}", input);
+        }
+
+        let mir = Mir::utf8_ignore_case("sss").unwrap();
+
+        for input in ["sss", "ßs", "sß", "SSS", "Sẞ"] {
+            assert!(matches(&mir, input), "{}", input);
+        }
+        assert!(!matches(&mir, "ßß"));
+    }
+
+    #[test]
+    fn classes() {
+        let mir = Mir::utf8_ignore_case("[aßﬁ]").unwrap();
+
+        for input in ["a", "A", "ß", "ẞ", "ss", "SS", "sS", "ﬁ", "fi", "FI"] {
+            assert!(matches(&mir, input), "{}", input);
+        }
+        for input in ["s", "f", "aa"] {
+            assert!(!matches(&mir, input), "{}", input);
+        }
+    }
+}
@@ -0,0 +1,245 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{Ident, LitStr};
+
+use crate::captures::Program;
+use crate::error::Errors;
+use crate::graph::{Graph, NodeId};
+use crate::leaf::Leaf;
+use crate::test_match::Pattern;
+use crate::witness;
+
+/// The most inputs that a conformance test checks.
+const MAX_CASES: usize = 2000;
+
+/// An input at the edge of the state machine, and the first token it's expected to be lexed as: the index of its
+/// variant and its length, or `None` for an error.
+pub struct Case {
+    pub input: Vec<u8>,
+    pub expected: Option<(usize, usize)>,
+}
+
+/// Generate a `#[test]` that lexes inputs at the edges of the state machine, and checks that the first token of each
+/// is the one that matching the definitions on their own says it should be. The cases are also written to `export`,
+/// if given, so that changes to them show up when the grammar is edited.
+#[allow(clippy::too_many_arguments)]
+pub fn generate(
+    name: &Ident,
+    source: &TokenStream,
+    kinds: &[Ident],
+    graph: &Graph<Leaf>,
+    root: NodeId,
+    rules: &[Leaf],
+    patterns: &[Pattern],
+    span: proc_macro2::Span,
+    export: Option<&LitStr>,
+    errors: &mut Errors,
+) -> TokenStream {
+    let utf8 = match source.to_string().as_str() {
+        "str" => true,
+        "[u8]" => false,
+        _ => {
+            errors.err(
+                "conformance_tests can only be used with `str` or `[u8]` sources",
+                span,
+            );
+            return TokenStream::new();
+        }
+    };
+
+    let inputs = graph
+        .edge_inputs(root, MAX_CASES)
+        .into_iter()
+        .filter(|input| !utf8 || std::str::from_utf8(input).is_ok());
+    let cases = cases(rules, patterns, inputs);
+
+    if let Some(path) = export {
+        if let Err(err) = crate::export(&path.value(), report(name, kinds, &cases)) {
+            errors.err(
+                format!("Failed to export the conformance cases: {}", err),
+                path.span(),
+            );
+        }
+    }
+
+    let cases = cases.iter().map(|case| {
+        let input = &case.input;
+        let expected = match case.expected {
+            Some((kind, len)) => quote!(::core::option::Option::Some((#kind, #len))),
+            None => quote!(::core::option::Option::None),
+        };
+
+        quote!((&[#(#input),*], #expected))
+    });
+    let kinds = kinds.iter().map(ToString::to_string);
+    let source = match utf8 {
+        true => quote!(::core::str::from_utf8(input).unwrap()),
+        false => quote!(input),
+    };
+    let test = format_ident!("__logos_conformance_{}", name);
+
+    quote! {
+        #[cfg(test)]
+        #[test]
+        #[allow(non_snake_case)]
+        fn #test() {
+            const KINDS: &[&str] = &[#(#kinds),*];
+            const CASES: &[(&[u8], ::core::option::Option<(usize, usize)>)] = &[#(#cases),*];
+
+            let show = |token: ::core::option::Option<(usize, usize)>| match token {
+                ::core::option::Option::Some((kind, len)) => ::std::format!("{} of {} bytes", KINDS[kind], len),
+                ::core::option::Option::None => ::std::string::String::from("an error"),
+            };
+            let mut failures = ::std::vec::Vec::<::std::string::String>::new();
+
+            for &(input, expected) in CASES {
+                let source = #source;
+                let first = ::core::iter::Iterator::next(&mut <#name as ::logos::Logos>::lexer(source).slices());
+                let found = match first {
+                    ::core::option::Option::Some((_, span, ::core::option::Option::Some(kind))) => {
+                        ::core::option::Option::Some((kind, span.end - span.start))
+                    }
+                    _ => ::core::option::Option::None,
+                };
+
+                if found != expected {
+                    failures.push(::std::format!(
+                        "{:?}: expected {}, found {}",
+                        source,
+                        show(expected),
+                        show(found),
+                    ));
+                }
+            }
+
+            if !failures.is_empty() {
+                ::core::panic!(
+                    "{} of {} conformance case(s) failed:\n{}",
+                    failures.len(),
+                    CASES.len(),
+                    failures.join("\n"),
+                );
+            }
+        }
+    }
+}
+
+/// Work out the first token that each input is expected to be lexed as, by matching all definitions on their own.
+///
+/// Inputs that a contextual definition, `requires(...)`, `not_preceded_by`, `preceded_by`, or an anchor or word
+/// boundary could match are left out, since what they're lexed as depends on more than the input itself.
+pub fn cases(
+    rules: &[Leaf],
+    patterns: &[Pattern],
+    inputs: impl Iterator<Item = Vec<u8>>,
+) -> Vec<Case> {
+    let programs: Vec<Program> = patterns
+        .iter()
+        .map(|pattern| Program::lookahead(&pattern.to_mir()))
+        .collect();
+
+    inputs
+        .filter_map(|input| {
+            let candidates = witness::candidates(rules, &programs, &input);
+
+            if candidates
+                .iter()
+                .any(|found| depends_on_context(&rules[found.rule]))
+            {
+                return None;
+            }
+
+            let expected =
+                witness::best(rules, &candidates).map(|found| (rules[found.rule].kind, found.len));
+
+            Some(Case { input, expected })
+        })
+        .collect()
+}
+
+fn depends_on_context(leaf: &Leaf) -> bool {
+    leaf.contextual.is_some()
+        || !leaf.assertions.is_empty()
+        || !leaf.requires.is_empty()
+        || leaf.not_preceded_by.is_some()
+        || leaf.preceded_by.is_some()
+}
+
+/// Render the cases one per line, as the input (escaped like a Rust byte string) and the token it's lexed as.
+fn report(name: &Ident, kinds: &[Ident], cases: &[Case]) -> String {
+    let mut report = format!("Conformance cases for the lexer of `{}`:\n", name);
+
+    for case in cases {
+        let input: String = case.input.escape_ascii().to_string();
+        let expected = match case.expected {
+            Some((kind, len)) => format!("{} {}", kinds[kind], len),
+            None => String::from("error"),
+        };
+
+        report.push_str(&format!("b\"{}\" => {}\n", input, expected));
+    }
+
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mir::Mir;
+    use proc_macro2::Span;
+
+    #[test]
+    fn expected_tokens() {
+        let keyword = Ident::new("Keyword", Span::call_site());
+        let word = Ident::new("Word", Span::call_site());
+        let rules = vec![
+            Leaf::new(&keyword, Span::call_site()).priority(6).kind(0),
+            Leaf::new(&word, Span::call_site()).priority(2).kind(1),
+        ];
+        let patterns = vec![
+            Pattern::Bytes(b"let".to_vec()),
+            Pattern::Regex(Mir::utf8("[a-z]+").unwrap()),
+        ];
+        let inputs = ["let", "lets", "le", "1", "a1"].map(|input| input.as_bytes().to_vec());
+
+        let cases = cases(&rules, &patterns, inputs.into_iter());
+        let expected: Vec<_> = cases.iter().map(|case| case.expected).collect();
+
+        assert_eq!(
+            expected,
+            &[Some((0, 3)), Some((1, 4)), Some((1, 2)), None, Some((1, 1))]
+        );
+
+        let report = report(
+            &Ident::new("Token", Span::call_site()),
+            &[keyword, word],
+            &cases,
+        );
+
+        assert!(report
+            .starts_with("Conformance cases for the lexer of `Token`:\nb\"let\" => Keyword 3\n"));
+        assert!(report.contains("b\"1\" => error\n"));
+    }
+
+    #[test]
+    fn contextual_inputs_are_left_out() {
+        let keyword = Ident::new("Keyword", Span::call_site());
+        let word = Ident::new("Word", Span::call_site());
+        let rules = vec![
+            Leaf::new(&keyword, Span::call_site())
+                .priority(6)
+                .contextual(Some(0)),
+            Leaf::new(&word, Span::call_site()).priority(2).kind(1),
+        ];
+        let patterns = vec![
+            Pattern::Bytes(b"let".to_vec()),
+            Pattern::Regex(Mir::utf8("[a-z]+").unwrap()),
+        ];
+        let inputs = ["let", "lets", "x"].map(|input| input.as_bytes().to_vec());
+
+        let cases = cases(&rules, &patterns, inputs.into_iter());
+        let inputs: Vec<_> = cases.iter().map(|case| &case.input[..]).collect();
+
+        assert_eq!(inputs, &[b"x"]);
+    }
+}
@@ -0,0 +1,54 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::Ident;
+
+use crate::parser::Generic;
+
+/// Generate a `NAME_{Variant}` constant for every variant, a `name` method returning the name of a token, and a
+/// `Display` implementation that writes it.
+pub fn generate(
+    name: &Ident,
+    this: &TokenStream,
+    generic: &Generic,
+    names: Vec<(Ident, String)>,
+) -> TokenStream {
+    let consts = names.iter().map(|(ident, display)| {
+        let constant = format_ident!("NAME_{}", ident);
+        let doc = format!("The display name of [`{}::{}`].", name, ident);
+
+        quote! {
+            #[doc = #doc]
+            pub const #constant: &'static str = #display;
+        }
+    });
+    let arms = names.iter().map(|(ident, _)| {
+        let constant = format_ident!("NAME_{}", ident);
+
+        quote!(#name::#ident { .. } => Self::#constant,)
+    });
+
+    let params = generic.params();
+    let where_clause = generic.where_clause();
+
+    quote! {
+        #[automatically_derived]
+        #[allow(non_upper_case_globals, dead_code)]
+        impl #params #this #where_clause {
+            #(#consts)*
+
+            /// The display name of this token, which is also what its `Display` implementation writes.
+            pub fn name(&self) -> &'static str {
+                match self {
+                    #(#arms)*
+                }
+            }
+        }
+
+        #[automatically_derived]
+        impl #params ::core::fmt::Display for #this #where_clause {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                f.write_str(self.name())
+            }
+        }
+    }
+}
@@ -0,0 +1,149 @@
+use fnv::FnvHashMap as Map;
+use proc_macro2::TokenStream;
+use quote::quote;
+
+use crate::generator::{Context, Generator};
+use crate::graph::{Node, NodeId};
+
+/// Transitions are stored as `u16`s, with `0` meaning no transition.
+const MAX_STATES: usize = u16::MAX as usize - 1;
+
+impl<'a> Generator<'a> {
+    /// Generate a `CompactTable` for the graph, and a function running the leaf of a state once it's been reached by
+    /// `run_compact`.
+    ///
+    /// Returns an error if the graph has too many states to fit in the table.
+    pub fn generate_compact(&mut self) -> Result<TokenStream, String> {
+        let order = self.states();
+
+        if order.len() > MAX_STATES {
+            return Err(format!(
+                "This enum needs {} states, but `codegen = \"compact\"` only supports up to {}",
+                order.len(),
+                MAX_STATES,
+            ));
+        }
+
+        let index: Map<NodeId, usize> = order.iter().enumerate().map(|(n, &id)| (id, n)).collect();
+        let state = |id: NodeId| index[&id] as u32;
+
+        // The target of every fork for every byte, as state index plus one.
+        let forks: Vec<[u16; 256]> = order
+            .iter()
+            .filter_map(|&id| match &self.graph[id] {
+                Node::Fork(fork) => {
+                    let mut row = [0; 256];
+
+                    for (range, then) in fork.branches() {
+                        for byte in range {
+                            row[byte as usize] = state(then) as u16 + 1;
+                        }
+                    }
+                    Some(row)
+                }
+                _ => None,
+            })
+            .collect();
+
+        // Bytes that lead to the same states from every fork can share a column.
+        let mut classes = [0u8; 256];
+        let mut columns: Map<Vec<u16>, u8> = Map::default();
+        let mut representatives = Vec::new();
+
+        for byte in 0..=255u8 {
+            let column: Vec<u16> = forks.iter().map(|row| row[byte as usize]).collect();
+            let count = columns.len();
+            let class = *columns.entry(column).or_insert_with(|| {
+                representatives.push(byte);
+                count as u8
+            });
+
+            classes[byte as usize] = class;
+        }
+
+        let class_count = representatives.len();
+        let transitions = forks
+            .iter()
+            .flat_map(|row| representatives.iter().map(move |&byte| row[byte as usize]));
+
+        let mut ropes = Vec::new();
+        let mut forks_seen = 0;
+        let mut leaves = Vec::new();
+
+        let states = order
+            .iter()
+            .enumerate()
+            .map(|(n, &id)| {
+                let meta = &self.meta[id];
+                let flags = if meta.loop_entry_from.is_empty() {
+                    0
+                } else {
+                    1 << 2
+                };
+                let min_read = meta.min_read as u32;
+                let miss = self.graph[id].miss().map_or(0, |miss| state(miss) + 1);
+
+                let [kind, a, b] = match &self.graph[id] {
+                    Node::Fork(_) => {
+                        let row = forks_seen * class_count;
+
+                        forks_seen += 1;
+                        [0, row as u32, 0]
+                    }
+                    Node::Rope(rope) => {
+                        let start = ropes.len() as u32;
+                        let len = rope.pattern.len() as u32;
+
+                        ropes.extend(rope.pattern.iter().map(|range| {
+                            let (lo, hi) = (range.start, range.end);
+
+                            quote!((#lo, #hi))
+                        }));
+                        [1 | len << 3, start, state(rope.then)]
+                    }
+                    Node::Leaf(leaf) => {
+                        let body = self.generate_leaf(leaf, Context::default());
+
+                        leaves.push(quote!(#n => { #body }));
+                        [2, 0, 0]
+                    }
+                };
+                let header = kind | flags;
+
+                quote!([#header, #min_read, #miss, #a, #b])
+            })
+            .collect::<Vec<_>>();
+
+        // The classes, then a `[u32; 5]` for each state, a `u16` for each transition and a `(u8, u8)` for each range.
+        self.table_bytes +=
+            256 + states.len() * 20 + forks.len() * class_count * 2 + ropes.len() * 2;
+
+        let params = self.generic.params();
+        let lexer = self.generic.lexer();
+        let where_clause = self.generic.where_clause();
+
+        Ok(quote! {
+            static __LOGOS_COMPACT: ::logos::internal::CompactTable = ::logos::internal::CompactTable {
+                classes: [#(#classes),*],
+                class_count: #class_count,
+                states: &[#(#states),*],
+                transitions: &[#(#transitions),*],
+                ropes: &[#(#ropes),*],
+            };
+
+            fn __logos_accept #params (lex: &mut #lexer, __logos_state: usize) #where_clause {
+                match __logos_state {
+                    #(#leaves)*
+                    _ => unreachable!(),
+                }
+            }
+
+            match ::logos::internal::run_compact(lex, &__LOGOS_COMPACT) {
+                ::logos::internal::CompactMatch::Leaf(state) => __logos_accept(lex, state),
+                ::logos::internal::CompactMatch::End => __logos_end(lex),
+                ::logos::internal::CompactMatch::Fail => __logos_fail(lex),
+                ::logos::internal::CompactMatch::Error => __logos_error(lex),
+            }
+        })
+    }
+}
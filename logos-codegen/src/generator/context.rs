@@ -105,8 +105,8 @@ impl Context {
         match (miss, self.backtrack) {
             (Some(id), _) => gen.goto(id, self).clone(),
             (_, Some(id)) => gen.goto(id, self.backtrack()).clone(),
-            _ if self.bumped => quote!(lex.error()),
-            _ => quote!(_error(lex)),
+            _ if self.bumped => quote!(__logos_fail(lex)),
+            _ => quote!(__logos_error(lex)),
         }
     }
 
@@ -5,11 +5,15 @@ use proc_macro2::TokenStream;
 use quote::quote;
 
 use crate::generator::{Context, Generator};
-use crate::graph::{Fork, NodeId, Range};
+use crate::graph::{complement, Fork, NodeId, Range};
 use crate::util::ToIdent;
 
 type Targets = Map<NodeId, Vec<Range>>;
 
+/// The most ranges a character class can have for its loop to be scanned with SIMD, since every range costs a few
+/// instructions per chunk.
+const MAX_SIMD_RANGES: usize = 6;
+
 impl<'a> Generator<'a> {
     pub fn generate_fork(&mut self, this: NodeId, fork: &Fork, mut ctx: Context) -> TokenStream {
         let mut targets: Targets = Map::default();
@@ -22,6 +26,7 @@ impl<'a> Generator<'a> {
         match targets.len() {
             1 if loops_to_self => return self.generate_fast_loop(fork, ctx),
             0..=2 => (),
+            _ if !self.strategy.jump_tables => (),
             _ => return self.generate_fork_jump_table(this, fork, targets, ctx),
         }
         let miss = ctx.miss(fork.miss, self);
@@ -87,6 +92,9 @@ impl<'a> Generator<'a> {
             })
             .collect::<TokenStream>();
 
+        // There are fewer than 256 jumps, so each of them is a byte.
+        self.table_bytes += 256;
+
         let jumps = &jumps;
         let table = table.iter().copied().map(|idx| &jumps[idx as usize]);
 
@@ -112,7 +120,7 @@ impl<'a> Generator<'a> {
 
     fn fork_end(&self, this: NodeId, miss: &TokenStream) -> TokenStream {
         if this == self.root {
-            quote!(_end(lex))
+            quote!(__logos_end(lex))
         } else {
             miss.clone()
         }
@@ -140,8 +148,8 @@ impl<'a> Generator<'a> {
                     quote!(byte),
                     quote! {
                         let byte = match #read {
-                            Some(byte) => byte,
-                            None => return #end,
+                            ::core::option::Option::Some(byte) => byte,
+                            ::core::option::Option::None => return #end,
                         };
                     },
                 )
@@ -153,8 +161,8 @@ impl<'a> Generator<'a> {
                     quote!(arr[0]),
                     quote! {
                         let arr = match #read {
-                            Some(arr) => arr,
-                            None => return #end,
+                            ::core::option::Option::Some(arr) => arr,
+                            ::core::option::Option::None => return #end,
                         };
                     },
                 )
@@ -165,34 +173,46 @@ impl<'a> Generator<'a> {
     fn generate_fast_loop(&mut self, fork: &Fork, ctx: Context) -> TokenStream {
         let miss = ctx.miss(fork.miss, self);
         let ranges = fork.branches().map(|(range, _)| range).collect::<Vec<_>>();
+
+        // A negated class like `[^"\\\n]` is scanned for the few bytes that stop it, rather than the many it matches.
+        let stops = complement(&ranges);
+        let (leading, bounds) = match stops.len() < ranges.len() {
+            true => (quote!(leading_outside_ranges), &stops),
+            false => (quote!(leading_in_ranges), &ranges),
+        };
+
+        if bounds.len() <= MAX_SIMD_RANGES {
+            let bounds = bounds.iter().map(|range| {
+                let (start, end) = (range.start, range.end);
+
+                quote!((#start, #end))
+            });
+            let bounds = quote!([#(#bounds),*]);
+            let test = self.generate_test(ranges);
+
+            return quote! {
+                __logos_simd_loop!(lex, #test, #leading, #bounds, #miss);
+            };
+        }
+
         let test = self.generate_test(ranges);
 
         quote! {
-            _fast_loop!(lex, #test, #miss);
+            __logos_fast_loop!(lex, #test, #miss);
         }
     }
 
-    pub fn fast_loop_macro() -> TokenStream {
+    pub fn fast_loop_macro(&self) -> TokenStream {
+        let unroll = self.strategy.unroll;
+        let checks = unrolled_checks(unroll, quote!($lex.bump_unchecked(#unroll); continue;));
+        let prefix = unrolled_checks(unroll, quote!($lex.bump_unchecked(#unroll);));
+
         quote! {
-            macro_rules! _fast_loop {
+            macro_rules! __logos_fast_loop {
                 ($lex:ident, $test:ident, $miss:expr) => {
                     // Do one bounds check for multiple bytes till EOF
-                    while let Some(arr) = $lex.read::<&[u8; 16]>() {
-                        if $test(arr[0])  { if $test(arr[1])  { if $test(arr[2])  { if $test(arr[3]) {
-                        if $test(arr[4])  { if $test(arr[5])  { if $test(arr[6])  { if $test(arr[7]) {
-                        if $test(arr[8])  { if $test(arr[9])  { if $test(arr[10]) { if $test(arr[11]) {
-                        if $test(arr[12]) { if $test(arr[13]) { if $test(arr[14]) { if $test(arr[15]) {
-
-                        $lex.bump_unchecked(16); continue;     } $lex.bump_unchecked(15); return $miss; }
-                        $lex.bump_unchecked(14); return $miss; } $lex.bump_unchecked(13); return $miss; }
-                        $lex.bump_unchecked(12); return $miss; } $lex.bump_unchecked(11); return $miss; }
-                        $lex.bump_unchecked(10); return $miss; } $lex.bump_unchecked(9); return $miss;  }
-                        $lex.bump_unchecked(8); return $miss;  } $lex.bump_unchecked(7); return $miss;  }
-                        $lex.bump_unchecked(6); return $miss;  } $lex.bump_unchecked(5); return $miss;  }
-                        $lex.bump_unchecked(4); return $miss;  } $lex.bump_unchecked(3); return $miss;  }
-                        $lex.bump_unchecked(2); return $miss;  } $lex.bump_unchecked(1); return $miss;  }
-
-                        return $miss;
+                    while let ::core::option::Option::Some(arr) = $lex.read::<&[u8; #unroll]>() {
+                        #checks
                     }
 
                     while $lex.test($test) {
@@ -202,6 +222,55 @@ impl<'a> Generator<'a> {
                     $miss
                 };
             }
+
+            // With the `simd` feature, runs of a small class (or of a class that few ranges stop) that are longer than
+            // 16 bytes are checked with vector instructions. The first few bytes (and the last few bytes of the source)
+            // still use the loop above.
+            macro_rules! __logos_simd_loop {
+                ($lex:ident, $test:ident, $leading:ident, $ranges:expr, $miss:expr) => {
+                    if ::logos::internal::SIMD {
+                        // Most runs are short, and those are quicker to check a byte at a time.
+                        if let ::core::option::Option::Some(arr) = $lex.read::<&[u8; #unroll]>() {
+                            #prefix
+                        }
+
+                        while let ::core::option::Option::Some(chunk) = $lex.read::<&[u8; 16]>() {
+                            let matched = ::logos::internal::$leading(chunk, &$ranges);
+
+                            // Bumping by a constant keeps the next read from waiting on this one.
+                            if matched == 16 {
+                                $lex.bump_unchecked(16);
+                                continue;
+                            }
+
+                            $lex.bump_unchecked(matched);
+
+                            return $miss;
+                        }
+                    }
+
+                    __logos_fast_loop!($lex, $test, $miss)
+                };
+            }
         }
     }
 }
+
+/// Nested checks of the first `n` bytes of `arr` against `$test`, running `matched` if all of them pass. Otherwise the
+/// bytes that passed are bumped, and `$miss` is returned.
+fn unrolled_checks(n: usize, matched: TokenStream) -> TokenStream {
+    (0..n).rev().fold(matched, |inner, at| {
+        let missed = match at {
+            0 => quote!(return $miss;),
+            at => quote!($lex.bump_unchecked(#at); return $miss;),
+        };
+
+        quote! {
+            if $test(arr[#at]) {
+                #inner
+            } else {
+                #missed
+            }
+        }
+    })
+}
@@ -0,0 +1,303 @@
+use std::rc::Rc;
+
+use proc_macro2::TokenStream;
+use quote::quote;
+
+use crate::generator::{Context, Generator};
+use crate::leaf::{Callback, Leaf};
+use crate::mir::{Assertion, Assertions};
+use crate::util::MaybeVoid;
+
+impl<'a> Generator<'a> {
+    pub fn generate_leaf(&mut self, leaf: &Leaf, mut ctx: Context) -> TokenStream {
+        if let Some(lookup) = &leaf.keywords {
+            // The table is looked up with the whole token, so the lexer is bumped first.
+            let bump = ctx.bump();
+            let table = &lookup.table;
+            let keywords = table
+                .slots()
+                .iter()
+                .enumerate()
+                .filter_map(|(slot, keyword)| {
+                    let slot = slot + 1;
+                    let body = self.generate_leaf(&lookup.leaves[(*keyword)?], ctx);
+
+                    Some(quote!(#slot => { #body }))
+                });
+            let keywords: Vec<_> = keywords.collect();
+            let identifier = self.generate_leaf(
+                &Leaf {
+                    keywords: None,
+                    ..leaf.clone()
+                },
+                ctx,
+            );
+
+            return quote! {
+                #bump
+                static __LOGOS_KEYWORDS: ::logos::internal::KeywordTable = #table;
+
+                match lex.keyword(&__LOGOS_KEYWORDS) {
+                    #(#keywords)*
+                    _ => { #identifier }
+                }
+            };
+        }
+
+        if leaf.has_checks() {
+            // The checks need to see the whole token, so the lexer is bumped before running them.
+            let bump = ctx.bump();
+            let lookahead = leaf.lookahead.as_deref().map(|program| {
+                quote! {
+                    static __LOGOS_LOOKAHEAD: ::logos::internal::CaptureProgram = #program;
+                }
+            });
+            let not_followed_by = leaf.not_followed_by.as_deref().map(|program| {
+                quote! {
+                    static __LOGOS_NEGATIVE_LOOKAHEAD: ::logos::internal::CaptureProgram = #program;
+                }
+            });
+            let preceded_by = leaf.preceded_by.as_deref().map(|program| {
+                quote! {
+                    static __LOGOS_LOOKBEHIND: ::logos::internal::CaptureProgram = #program;
+                }
+            });
+            let simulate = leaf.simulate.as_deref().map(|program| {
+                quote! {
+                    static __LOGOS_SIMULATE: ::logos::internal::CaptureProgram = #program;
+                }
+            });
+            let literals = leaf.literals.as_deref().map(|buckets| {
+                let buckets = buckets.iter().map(|bucket| {
+                    let bucket = bucket
+                        .iter()
+                        .map(|literal| proc_macro2::Literal::byte_string(literal));
+
+                    quote!(&[#(#bucket),*])
+                });
+
+                quote! {
+                    static __LOGOS_LITERALS: &[&[&[u8]]] = &[#(#buckets),*];
+                }
+            });
+            let checks = generate_checks(leaf);
+            let matched = self.generate_leaf(
+                &Leaf {
+                    assertions: Assertions::default(),
+                    lookahead: None,
+                    not_followed_by: None,
+                    not_preceded_by: None,
+                    preceded_by: None,
+                    simulate: None,
+                    literals: None,
+                    requires: Rc::from([]),
+                    ..leaf.clone()
+                },
+                ctx,
+            );
+            let failed = match &leaf.fallback {
+                Some(fallback) => self.generate_leaf(fallback, ctx),
+                // A token rejected by what follows it can still be lexed as a shorter token of another definition.
+                None if leaf.not_followed_by.is_some() => {
+                    let this = self.this;
+                    let rule = leaf.rule;
+
+                    quote! {
+                        match lex.back_off(__LOGOS_BACK_OFF, #rule) {
+                            ::core::option::Option::Some(rule) => <#this as ::logos::Logos<'s>>::reparse(lex, rule),
+                            ::core::option::Option::None => lex.error(),
+                        }
+                    }
+                }
+                None => quote!(lex.error();),
+            };
+
+            return quote! {
+                #bump
+                #lookahead
+                #not_followed_by
+                #preceded_by
+                #simulate
+                #literals
+
+                if #checks {
+                    #matched
+                } else {
+                    #failed
+                }
+            };
+        }
+
+        if let (Some(bit), Some(fallback)) = (leaf.contextual, &leaf.fallback) {
+            let enabled = self.generate_leaf(
+                &Leaf {
+                    contextual: None,
+                    fallback: None,
+                    ..leaf.clone()
+                },
+                ctx,
+            );
+            let disabled = self.generate_leaf(fallback, ctx);
+
+            return quote! {
+                if lex.contextual_enabled(#bit) {
+                    #enabled
+                } else {
+                    #disabled
+                }
+            };
+        }
+
+        let bump = ctx.bump();
+
+        // Tokens with capture groups hand their program to the lexer, so that callbacks can find the groups later.
+        let captures = leaf.captures.as_deref().map(|program| {
+            quote! {
+                static __LOGOS_CAPTURES: ::logos::internal::CaptureProgram = #program;
+
+                lex.set_captures(&__LOGOS_CAPTURES);
+            }
+        });
+        let variant_rule = leaf.variant_rule;
+        let bump = quote! {
+            #bump
+            #captures
+            lex.set_matched_rule(#variant_rule);
+        };
+
+        // Tokens that run a callback or carry a value aren't constructed at all when only the kinds of tokens are
+        // needed, see `Lexer::slices`.
+        let kind = leaf.kind;
+        let segment = quote! {
+            if lex.segmenting() {
+                return lex.segment(#kind);
+            }
+        };
+
+        let ident = &leaf.ident;
+        let name = self.name;
+        let this = self.this;
+        let ty = &leaf.field;
+
+        let constructor = match leaf.field {
+            MaybeVoid::Some(_) => quote!(#name::#ident),
+            MaybeVoid::Void => quote!(|()| #name::#ident),
+        };
+
+        match &leaf.callback {
+            Some(Callback::Label(callback)) => quote! {
+                #bump
+                #segment
+
+                let result = #callback(lex);
+
+                lex.apply(result, #constructor);
+            },
+            Some(Callback::Inline(inline)) => {
+                let arg = &inline.arg;
+                let body = &inline.body;
+                let params = self.generic.params();
+                let lexer = self.generic.lexer();
+                let where_clause = self.generic.where_clause();
+
+                quote! {
+                    #bump
+                    #segment
+
+                    #[inline]
+                    fn __logos_callback #params (#arg: &mut #lexer) -> impl ::logos::callback::CallbackResult<'s, #ty, #this> #where_clause {
+                        #body
+                    }
+
+                    let result = __logos_callback(lex);
+
+                    lex.apply(result, #constructor);
+                }
+            }
+            None if matches!(leaf.field, MaybeVoid::Void) => quote! {
+                #bump
+                lex.set(::core::result::Result::Ok(#name::#ident));
+            },
+            None => quote! {
+                #bump
+                #segment
+                let token = #name::#ident(::logos::internal::FromSlice::from_slice(lex));
+                lex.set(::core::result::Result::Ok(token));
+            },
+        }
+    }
+}
+
+fn generate_checks(leaf: &Leaf) -> TokenStream {
+    // Flags are the cheapest to check, so they go first.
+    let requires = leaf.requires.iter().map(|requirement| {
+        let flag = &requirement.flag;
+
+        match requirement.negated {
+            false => quote!(lex.options().contains(#flag)),
+            true => quote!(!lex.options().intersects(#flag)),
+        }
+    });
+    let start = leaf.assertions.start.iter().map(|assertion| {
+        let assertion = generate_assertion(*assertion);
+
+        quote!(lex.assert_start(#assertion))
+    });
+    let end = leaf.assertions.end.iter().map(|assertion| {
+        let assertion = generate_assertion(*assertion);
+
+        quote!(lex.assert_end(#assertion))
+    });
+    let lookahead = leaf
+        .lookahead
+        .as_ref()
+        .map(|_| quote!(lex.followed_by(&__LOGOS_LOOKAHEAD)));
+    let not_followed_by = leaf
+        .not_followed_by
+        .as_ref()
+        .map(|_| quote!(!lex.followed_by(&__LOGOS_NEGATIVE_LOOKAHEAD)));
+    let lookbehind = leaf.not_preceded_by.as_ref().map(|ranges| {
+        let ranges = ranges.iter().map(|(start, end)| quote!((#start, #end)));
+
+        quote!(!lex.preceded_by(&[#(#ranges),*]))
+    });
+    let preceded_by = leaf
+        .preceded_by
+        .as_ref()
+        .map(|_| quote!(lex.lookbehind(&__LOGOS_LOOKBEHIND)));
+    // The rest of a simulated token has to be matched before anything can look at where it ends.
+    let simulate = leaf
+        .simulate
+        .as_ref()
+        .map(|_| quote!(lex.simulate(&__LOGOS_SIMULATE)));
+    let literals = leaf
+        .literals
+        .as_ref()
+        .map(|_| quote!(lex.literal_set(__LOGOS_LITERALS)));
+    let checks = requires
+        .chain(start)
+        .chain(lookbehind)
+        .chain(preceded_by)
+        .chain(simulate)
+        .chain(literals)
+        .chain(end)
+        .chain(lookahead)
+        .chain(not_followed_by);
+
+    quote!(#(#checks)&&*)
+}
+
+fn generate_assertion(assertion: Assertion) -> TokenStream {
+    let variant = match assertion {
+        Assertion::StartText => quote!(StartText),
+        Assertion::EndText => quote!(EndText),
+        Assertion::StartLine => quote!(StartLine),
+        Assertion::EndLine => quote!(EndLine),
+        Assertion::WordBoundary => quote!(WordBoundary),
+        Assertion::NotWordBoundary => quote!(NotWordBoundary),
+        Assertion::AsciiWordBoundary => quote!(AsciiWordBoundary),
+        Assertion::AsciiNotWordBoundary => quote!(AsciiNotWordBoundary),
+    };
+
+    quote!(::logos::internal::Assertion::#variant)
+}
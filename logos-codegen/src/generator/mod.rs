@@ -3,10 +3,13 @@ use proc_macro2::TokenStream;
 use quote::{quote, ToTokens, TokenStreamExt};
 use syn::Ident;
 
-use crate::graph::{Graph, Meta, Node, NodeId, Range};
+use crate::graph::{complement, Graph, Meta, Node, NodeId, Range};
 use crate::leaf::Leaf;
+use crate::parser::{Generic, Strategy};
+use crate::stats::Stats;
 use crate::util::ToIdent;
 
+mod compact;
 mod context;
 mod fork;
 mod leaf;
@@ -21,6 +24,8 @@ pub struct Generator<'a> {
     name: &'a Ident,
     /// Name of the type with any generics it might need
     this: &'a TokenStream,
+    /// Type parameters that generated functions need to be generic over
+    generic: &'a Generic,
     /// Id to the root node
     root: NodeId,
     /// Reference to the graph with all of the nodes
@@ -41,45 +46,196 @@ pub struct Generator<'a> {
     tests: Map<Vec<Range>, Ident>,
     /// Related to above, table stack manages tables that need to be
     tables: TableStack,
+    /// Which kinds of tables and how much unrolling the generated code may use
+    strategy: Strategy,
+    /// Size of the jump tables and compact tables generated so far
+    table_bytes: usize,
+    /// States whose functions are never inlined, which split the state machine into several functions
+    splits: Set<NodeId>,
 }
 
 impl<'a> Generator<'a> {
     pub fn new(
         name: &'a Ident,
         this: &'a TokenStream,
+        generic: &'a Generic,
         root: NodeId,
         graph: &'a Graph<Leaf>,
     ) -> Self {
-        let rendered = Self::fast_loop_macro();
         let meta = Meta::analyze(root, graph);
 
         Generator {
             name,
             this,
+            generic,
             root,
             graph,
             meta,
-            rendered,
+            rendered: TokenStream::new(),
             fns: Set::default(),
             idents: Map::default(),
             gotos: Map::default(),
             tests: Map::default(),
             tables: TableStack::new(),
+            strategy: Strategy::default(),
+            table_bytes: 0,
+            splits: Set::default(),
         }
     }
 
-    pub fn generate(mut self) -> TokenStream {
+    pub fn strategy(mut self, strategy: Strategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
+    pub fn generate(&mut self) -> TokenStream {
+        self.splits = self.split();
+
         let root = self.goto(self.root, Context::default()).clone();
+        let macros = self.fast_loop_macro();
         let rendered = &self.rendered;
         let tables = &self.tables;
 
         quote! {
             #tables
+            #macros
             #rendered
             #root
         }
     }
 
+    /// Count the states and transitions of the graph, along with the size of what has been generated so far.
+    pub fn stats(&self) -> Stats {
+        let mut stats = Stats {
+            functions: self.fns.len(),
+            table_bytes: self.table_bytes + self.tables.bytes(),
+            ..Stats::default()
+        };
+
+        for id in self.states() {
+            stats.states += 1;
+
+            match &self.graph[id] {
+                Node::Fork(fork) => {
+                    stats.forks += 1;
+                    stats.transitions += fork.branches().count() + fork.miss.iter().count();
+                }
+                Node::Rope(rope) => {
+                    stats.ropes += 1;
+                    stats.transitions += 1 + rope.miss.first().iter().count();
+                }
+                Node::Leaf(_) => stats.leaves += 1,
+            }
+        }
+
+        stats
+    }
+
+    /// Every node reachable from the root, starting with the root.
+    fn states(&self) -> Vec<NodeId> {
+        let mut order = vec![self.root];
+        let mut seen: Set<NodeId> = Set::default();
+
+        seen.insert(self.root);
+
+        let mut next = 0;
+
+        while let Some(&id) = order.get(next) {
+            next += 1;
+
+            for target in self.targets(id) {
+                if seen.insert(target) {
+                    order.push(target);
+                }
+            }
+        }
+
+        order
+    }
+
+    /// The states that a state can go to next.
+    fn targets(&self, id: NodeId) -> Vec<NodeId> {
+        match &self.graph[id] {
+            Node::Fork(fork) => fork
+                .branches()
+                .map(|(_, then)| then)
+                .chain(fork.miss)
+                .collect(),
+            Node::Rope(rope) => std::iter::once(rope.then)
+                .chain(rope.miss.first())
+                .collect(),
+            Node::Leaf(_) => Vec::new(),
+        }
+    }
+
+    /// Split the states into regions of at most `max_inline` states, each grown breadth first from a state that isn't
+    /// in a region yet, and return the states that are entered from outside of their region. Their functions are never
+    /// inlined, so the function for a state only ever inlines the states of its own region.
+    fn split(&self) -> Set<NodeId> {
+        let limit = self.strategy.max_inline;
+        let mut splits = Set::default();
+
+        if limit == 0 {
+            return splits;
+        }
+
+        let states = self.states();
+        let mut regions: Map<NodeId, usize> = Map::default();
+
+        for (region, &start) in states.iter().enumerate() {
+            if regions.contains_key(&start) {
+                continue;
+            }
+
+            let mut queue = std::collections::VecDeque::from([start]);
+            let mut size = 0;
+
+            while let Some(id) = queue.pop_front() {
+                if size == limit {
+                    break;
+                }
+                if regions.contains_key(&id) {
+                    continue;
+                }
+
+                regions.insert(id, region);
+                size += 1;
+                queue.extend(self.targets(id));
+            }
+
+            splits.insert(start);
+        }
+
+        for &id in &states {
+            for target in self.targets(id) {
+                if regions[&target] != regions[&id] {
+                    splits.insert(target);
+                }
+            }
+        }
+
+        // The root is only entered from `lex`, which is small enough to inline it into.
+        splits.remove(&self.root);
+        splits
+    }
+
+    /// Generate a `match` over rule indices, running the accept action of each rule
+    /// without reading any input.
+    pub fn generate_reparse(&mut self, rules: &[Leaf]) -> TokenStream {
+        let arms = rules.iter().enumerate().map(|(index, leaf)| {
+            let body = self.generate_leaf(leaf, Context::default());
+
+            quote!(#index => { #body })
+        });
+
+        quote! {
+            match __logos_rule {
+                #(#arms)*
+                rule => panic!("{} is not a valid rule", rule),
+            }
+        }
+    }
+
     fn generate_fn(&mut self, id: NodeId, ctx: Context) {
         if self.fns.contains(&(id, ctx)) {
             return;
@@ -91,10 +247,17 @@ impl<'a> Generator<'a> {
             Node::Rope(rope) => self.generate_rope(rope, ctx),
             Node::Leaf(leaf) => self.generate_leaf(leaf, ctx),
         };
+        let params = self.generic.params();
+        let lexer = self.generic.lexer();
+        let where_clause = self.generic.where_clause();
+        let inline = match self.splits.contains(&id) {
+            true => quote!(#[inline(never)]),
+            false => quote!(#[inline]),
+        };
         let ident = self.generate_ident(id, ctx);
         let out = quote! {
-            #[inline]
-            fn #ident<'s>(lex: &mut Lexer<'s>) {
+            #inline
+            fn #ident #params (lex: &mut #lexer) #where_clause {
                 #body
             }
         };
@@ -145,7 +308,7 @@ impl<'a> Generator<'a> {
 
     fn generate_ident(&mut self, id: NodeId, ctx: Context) -> &Ident {
         self.idents.entry((id, ctx)).or_insert_with(|| {
-            let mut ident = format!("goto{}", id);
+            let mut ident = format!("__logos_goto{}", id);
 
             ctx.write_suffix(&mut ident);
 
@@ -159,20 +322,20 @@ impl<'a> Generator<'a> {
     fn generate_test(&mut self, ranges: Vec<Range>) -> &Ident {
         if !self.tests.contains_key(&ranges) {
             let idx = self.tests.len();
-            let ident = format!("pattern{}", idx).to_ident();
+            let ident = format!("__logos_pattern{}", idx).to_ident();
 
             let lo = ranges.first().unwrap().start;
             let hi = ranges.last().unwrap().end;
 
-            let body = match ranges.len() {
-                0..=2 => {
-                    quote! {
-                        match byte {
-                            #(#ranges)|* => true,
-                            _ => false,
-                        }
-                    }
+            let branches = quote! {
+                match byte {
+                    #(#ranges)|* => true,
+                    _ => false,
                 }
+            };
+
+            let body = match ranges.len() {
+                0..=2 => branches,
                 _ if hi - lo < 64 => {
                     let mut offset = hi.saturating_sub(63);
 
@@ -198,11 +361,29 @@ impl<'a> Generator<'a> {
                         const LUT: u64 = #table;
 
                         match 1u64.checked_shl(#search as u32) {
-                            Some(shift) => LUT & shift != 0,
-                            None => false,
+                            ::core::option::Option::Some(shift) => LUT & shift != 0,
+                            ::core::option::Option::None => false,
                         }
                     }
                 }
+                // A negated class like `[^"\\\n]` is tested against a bitmap of the bytes that stop it, which is
+                // small enough to be worth it even without lookup tables.
+                _ if complement(&ranges).len() < ranges.len() => {
+                    let mut stops = [0u64; 4];
+
+                    for byte in complement(&ranges).into_iter().flatten() {
+                        stops[byte as usize >> 6] |= 1 << (byte & 63);
+                    }
+
+                    self.table_bytes += 32;
+
+                    quote! {
+                        const STOP: [u64; 4] = [#(#stops),*];
+
+                        STOP[(byte >> 6) as usize] & (1u64 << (byte & 63)) == 0
+                    }
+                }
+                _ if !self.strategy.lookup_tables => branches,
                 _ => {
                     let mut view = self.tables.view();
 
@@ -19,12 +19,12 @@ impl<'a> Generator<'a> {
             }
         };
 
-        return quote! {
+        quote! {
             match #read {
-                Some(#pat) => #then,
+                ::core::option::Option::Some(#pat) => #then,
                 _ => #miss,
             }
-        };
+        }
     }
 }
 
@@ -17,12 +17,12 @@ pub struct TableView<'a> {
 impl TableStack {
     pub fn new() -> Self {
         TableStack {
-            tables: vec![("COMPACT_TABLE_0".to_ident(), [0; 256])],
+            tables: vec![("__LOGOS_TABLE_0".to_ident(), [0; 256])],
             shift: 0,
         }
     }
 
-    pub fn view(&mut self) -> TableView {
+    pub fn view(&mut self) -> TableView<'_> {
         let mask = if self.shift < 8 {
             // Reusing existing table with a shifted mask
             let mask = 1u8 << self.shift;
@@ -32,7 +32,7 @@ impl TableStack {
             mask
         } else {
             // Need to create a new table
-            let ident = format!("COMPACT_TABLE_{}", self.tables.len()).to_ident();
+            let ident = format!("__LOGOS_TABLE_{}", self.tables.len()).to_ident();
 
             self.tables.push((ident, [0; 256]));
             self.shift = 1;
@@ -44,6 +44,14 @@ impl TableStack {
 
         TableView { ident, table, mask }
     }
+
+    /// The size of the tables that have been used so far.
+    pub fn bytes(&self) -> usize {
+        match self.shift {
+            0 => 0,
+            _ => self.tables.len() * 256,
+        }
+    }
 }
 
 impl<'a> TableView<'a> {
@@ -0,0 +1,123 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+
+use crate::leaf::{Callback, Leaf};
+
+/// Generate a `Grammar` doc section listing every definition with its computed priority, highest priority first.
+/// `descriptions` holds the definition of each rule as it was written, in the same order as `rules`.
+pub fn generate(rules: &[Leaf], descriptions: &[String]) -> TokenStream {
+    let mut order: Vec<usize> = (0..rules.len()).collect();
+
+    // Stable, so definitions with the same priority stay in the order they were declared.
+    order.sort_by_key(|&index| std::cmp::Reverse(rules[index].priority));
+
+    let mut lines = vec![
+        String::from("# Grammar"),
+        String::new(),
+        String::from(
+            "The definitions of this token type, generated by `#[logos(doc_grammar)]`. When several definitions \
+            match the longest possible input, the one with the highest priority wins.",
+        ),
+        String::new(),
+        String::from("| Variant | Definition | Priority | Notes |"),
+        String::from("|---|---|---|---|"),
+    ];
+
+    for index in order {
+        let rule = &rules[index];
+
+        lines.push(format!(
+            "| `{}` | {} | {} | {} |",
+            rule.ident,
+            code(&descriptions[index]),
+            rule.priority,
+            notes(rule).join(", "),
+        ));
+    }
+
+    let lines = lines.iter().map(|line| format!(" {}", line));
+
+    quote! {
+        #(#[doc = #lines])*
+    }
+}
+
+fn notes(rule: &Leaf) -> Vec<String> {
+    let mut notes = Vec::new();
+
+    match &rule.callback {
+        Some(Callback::Label(label)) => {
+            notes.push(format!(
+                "callback {}",
+                code(&label.to_string().replace(' ', ""))
+            ));
+        }
+        Some(Callback::Inline(_)) => notes.push(String::from("inline callback")),
+        None => (),
+    }
+
+    if let Some(bit) = rule.contextual {
+        notes.push(format!("contextual (group {})", bit));
+    }
+
+    notes
+}
+
+/// Format `text` as inline code that can go in a table cell.
+fn code(text: &str) -> String {
+    let text = text.replace('|', "\\|");
+
+    match text.contains('`') {
+        true => format!("`` {} ``", text),
+        false => format!("`{}`", text),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proc_macro2::Span;
+    use syn::Ident;
+
+    #[test]
+    fn table() {
+        let word = Ident::new("Word", Span::call_site());
+        let keyword = Ident::new("Keyword", Span::call_site());
+        let rules = vec![
+            Leaf::new(&word, Span::call_site()).priority(2),
+            Leaf::new(&keyword, Span::call_site())
+                .priority(6)
+                .callback(Some(Callback::Label(quote!(logos::skip))))
+                .contextual(Some(0)),
+        ];
+        let definitions = vec![
+            String::from(r#"#[regex("[a-z]+|_")]"#),
+            String::from(r#"#[token("let")]"#),
+        ];
+
+        let docs = generate(&rules, &definitions).to_string();
+
+        assert!(docs.contains(
+            r#"" | `Keyword` | `#[token(\"let\")]` | 6 | callback `logos::skip`, contextual (group 0) |""#
+        ));
+        assert!(docs.contains(r#"" | `Word` | `#[regex(\"[a-z]+\\|_\")]` | 2 |  |""#));
+        assert!(docs.find("Keyword") < docs.find("Word"));
+    }
+
+    #[test]
+    fn literals() {
+        use crate::parser::Literal;
+        use syn::{LitByteStr, LitStr};
+
+        let utf8 = |value: &str| Literal::Utf8(LitStr::new(value, Span::call_site())).describe();
+        let bytes =
+            |value: &[u8]| Literal::Bytes(LitByteStr::new(value, Span::call_site())).describe();
+
+        assert_eq!(utf8("let"), r#""let""#);
+        assert_eq!(utf8("\n"), r#""\n""#);
+        assert_eq!(utf8(r"[ \t]+"), r#"r"[ \t]+""#);
+        assert_eq!(utf8(r#""[^"]*""#), r##"r#""[^"]*""#"##);
+        assert_eq!(bytes(b"(?-u)\\xFF"), r#"br"(?-u)\xFF""#);
+        assert_eq!(bytes(b"\xFF"), r#"b"\xff""#);
+    }
+}
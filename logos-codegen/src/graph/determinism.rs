@@ -0,0 +1,331 @@
+use std::fmt::{self, Debug, Write};
+
+use crate::graph::rope::Miss;
+use crate::graph::{Graph, Node, NodeId};
+
+/// Outcome of a state on a single byte.
+#[derive(Clone, Copy, PartialEq)]
+enum Outcome {
+    /// Consume the byte and go to the node.
+    Then(NodeId),
+    /// Consume the byte and go on to the next position of the same rope.
+    Next,
+    /// Go to the node without consuming the byte.
+    Miss(NodeId),
+    /// Stop with an error.
+    Error,
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Outcome::Then(id) => write!(f, "-> {}", id),
+            Outcome::Next => f.write_str("-> next"),
+            Outcome::Miss(id) => write!(f, "~> {}", id),
+            Outcome::Error => f.write_str("error"),
+        }
+    }
+}
+
+/// The properties checked by a [`DeterminismReport`].
+#[derive(Clone, Copy, PartialEq)]
+enum Check {
+    Deterministic,
+    Complete,
+    LoopFree,
+}
+
+/// A report on whether a state machine is deterministic, complete over the byte alphabet, and loop-free on empty
+/// input, along with the transitions of every state it was checked on.
+pub struct DeterminismReport {
+    /// The rendered report.
+    pub report: String,
+    /// Descriptions of the checks that failed, empty if all of them passed.
+    pub violations: Vec<String>,
+}
+
+impl<Leaf: Debug> Graph<Leaf> {
+    /// Check the state machine starting at `root`, and render every state with its transitions so that the checks can
+    /// be repeated by other tools.
+    ///
+    /// Each fork is listed with a partition of all bytes `00`-`FF` into ranges, mapped to the state consuming the byte
+    /// (`->`), the state taking over without consuming it (`~>`), or an error. Each position of a rope is listed the
+    /// same way.
+    pub fn determinism_report(&self, root: NodeId, name: &str) -> DeterminismReport {
+        let mut states = String::new();
+        let mut violations = Vec::new();
+
+        self.write_states(&mut states, &mut violations)
+            .expect("Writing to a String can't fail");
+
+        match self.get(root) {
+            None => violations.push((
+                Check::Complete,
+                format!("The root state {} doesn't exist", root),
+            )),
+            Some(Node::Leaf(leaf)) => violations.push((
+                Check::LoopFree,
+                format!("The root state {} accepts empty input as {:?}", root, leaf),
+            )),
+            Some(_) => (),
+        }
+
+        for cycle in self.empty_cycles() {
+            let cycle = cycle.iter().map(ToString::to_string).collect::<Vec<_>>();
+
+            violations.push((
+                Check::LoopFree,
+                format!("States {} loop without consuming input", cycle.join(" ~> ")),
+            ));
+        }
+
+        let check = |check: Check| match violations.iter().any(|(failed, _)| *failed == check) {
+            false => "yes",
+            true => "no",
+        };
+
+        let mut report = String::new();
+
+        report.push_str("logos determinism report v1\n");
+        report.push_str(&format!("lexer: {}\n", name));
+        report.push_str(&format!("root: {}\n", root));
+        report.push_str(&format!("deterministic: {}\n", check(Check::Deterministic)));
+        report.push_str(&format!("complete: {}\n", check(Check::Complete)));
+        report.push_str(&format!(
+            "loop-free on empty input: {}\n",
+            check(Check::LoopFree)
+        ));
+
+        for (_, violation) in &violations {
+            report.push_str(&format!("violation: {}\n", violation));
+        }
+
+        report.push_str(&states);
+
+        DeterminismReport {
+            report,
+            violations: violations
+                .into_iter()
+                .map(|(_, violation)| violation)
+                .collect(),
+        }
+    }
+
+    fn write_states(&self, out: &mut String, violations: &mut Vec<(Check, String)>) -> fmt::Result {
+        let nodes = self
+            .nodes()
+            .iter()
+            .enumerate()
+            .filter_map(|(id, node)| Some((id, node.as_ref()?)));
+
+        for (id, node) in nodes {
+            match node {
+                Node::Fork(fork) => {
+                    let mut outcomes = [None; 256];
+
+                    for (range, then) in fork.branches() {
+                        for byte in range {
+                            if outcomes[byte as usize]
+                                .replace(Outcome::Then(then))
+                                .is_some()
+                            {
+                                violations.push((
+                                    Check::Deterministic,
+                                    format!(
+                                        "State {} has more than one branch on {:02X}",
+                                        id, byte
+                                    ),
+                                ));
+                            }
+                        }
+                    }
+
+                    let otherwise = match fork.miss {
+                        Some(miss) => Outcome::Miss(miss),
+                        None => Outcome::Error,
+                    };
+
+                    writeln!(out, "state {} fork", id)?;
+                    self.write_partition(
+                        id,
+                        &outcomes.map(|o| o.unwrap_or(otherwise)),
+                        out,
+                        violations,
+                    )?;
+                }
+                Node::Rope(rope) => {
+                    writeln!(out, "state {} rope", id)?;
+
+                    for (position, range) in rope.pattern.iter().enumerate() {
+                        let then = match position + 1 == rope.pattern.len() {
+                            true => Outcome::Then(rope.then),
+                            false => Outcome::Next,
+                        };
+                        let otherwise = match (rope.miss, position) {
+                            (Miss::First(miss), 0) | (Miss::Any(miss), _) => Outcome::Miss(miss),
+                            _ => Outcome::Error,
+                        };
+                        let mut outcomes = [otherwise; 256];
+
+                        for byte in *range {
+                            outcomes[byte as usize] = then;
+                        }
+
+                        writeln!(out, "  position {}", position)?;
+                        self.write_partition(id, &outcomes, out, violations)?;
+                    }
+                }
+                Node::Leaf(leaf) => writeln!(out, "state {} leaf {:?}", id, leaf)?,
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Write `outcomes` as ranges of bytes with the same outcome, checking that the states they lead to exist.
+    fn write_partition(
+        &self,
+        id: usize,
+        outcomes: &[Outcome; 256],
+        out: &mut String,
+        violations: &mut Vec<(Check, String)>,
+    ) -> fmt::Result {
+        let mut start = 0;
+
+        for end in 0..256 {
+            if end < 255 && outcomes[end] == outcomes[end + 1] {
+                continue;
+            }
+
+            let outcome = outcomes[end];
+
+            writeln!(out, "  {:02X}-{:02X} {}", start, end, outcome)?;
+
+            if let Outcome::Then(target) | Outcome::Miss(target) = outcome {
+                if self.get(target).is_none() {
+                    violations.push((
+                        Check::Complete,
+                        format!(
+                            "State {} leads to state {}, which doesn't exist",
+                            id, target
+                        ),
+                    ));
+                }
+            }
+
+            start = end + 1;
+        }
+
+        Ok(())
+    }
+
+    /// Find the cycles of transitions that don't consume any input, each listed from its first state.
+    fn empty_cycles(&self) -> Vec<Vec<NodeId>> {
+        let mut cycles = Vec::new();
+        // 0 for unvisited, 1 for being visited, 2 for visited
+        let mut visited = vec![0u8; self.nodes().len()];
+
+        for id in 1..self.nodes().len() {
+            let mut path = Vec::new();
+            let mut next = self.get(NodeId::new(id)).map(|_| NodeId::new(id));
+
+            while let Some(id) = next {
+                match visited.get(id.get()).copied() {
+                    Some(0) => {
+                        visited[id.get()] = 1;
+                        path.push(id);
+                        next = self.get(id).and_then(Node::miss);
+                    }
+                    Some(1) => {
+                        let start = path.iter().position(|&other| other == id).unwrap_or(0);
+
+                        cycles.push(path[start..].to_vec());
+                        break;
+                    }
+                    _ => break,
+                }
+            }
+
+            for id in path {
+                visited[id.get()] = 2;
+            }
+        }
+
+        cycles
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::{Fork, Rope};
+
+    #[test]
+    fn deterministic() {
+        let mut graph = Graph::new();
+
+        let ident = graph.push(Node::Leaf("IDENT"));
+        let fn_ = graph.push(Node::Leaf("FN"));
+        let rope = graph.push(Rope::new("n", fn_).miss_any(ident));
+        let root = graph.push(
+            Fork::new()
+                .branch('a'..='e', ident)
+                .branch(b'f', rope)
+                .branch('g'..='z', ident),
+        );
+
+        let DeterminismReport { report, violations } = graph.determinism_report(root, "Token");
+
+        assert!(violations.is_empty(), "{:?}", violations);
+        assert!(
+            report.contains("deterministic: yes\ncomplete: yes\nloop-free on empty input: yes\n")
+        );
+        assert!(report.contains(&format!(
+            "state {} fork\n  00-60 error\n  61-65 -> {}\n  66-66 -> {}\n  67-7A -> {}\n  7B-FF error\n",
+            root, ident, rope, ident
+        )));
+        assert!(report.contains(&format!(
+            "state {} rope\n  position 0\n  00-6D ~> {}\n  6E-6E -> {}\n  6F-FF ~> {}\n",
+            rope, ident, fn_, ident
+        )));
+    }
+
+    #[test]
+    fn loops_on_empty_input() {
+        let mut graph = Graph::<&str>::new();
+
+        let a = graph.reserve();
+        let b = graph.push(Fork::new().branch(b'b', a.get()).miss(a.get()));
+        let a = graph.insert(a, Fork::new().branch(b'a', b).miss(b));
+
+        let DeterminismReport { report, violations } = graph.determinism_report(a, "Token");
+
+        assert_eq!(
+            violations,
+            &[format!(
+                "States {} ~> {} loop without consuming input",
+                a, b
+            )]
+        );
+        assert!(
+            report.contains("deterministic: yes\ncomplete: yes\nloop-free on empty input: no\n")
+        );
+    }
+
+    #[test]
+    fn accepts_empty_input() {
+        let mut graph = Graph::new();
+
+        let root = graph.push(Node::Leaf("EMPTY"));
+
+        let DeterminismReport { violations, .. } = graph.determinism_report(root, "Token");
+
+        assert_eq!(
+            violations,
+            &[format!(
+                "The root state {} accepts empty input as \"EMPTY\"",
+                root
+            )]
+        );
+    }
+}
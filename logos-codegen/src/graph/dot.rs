@@ -0,0 +1,200 @@
+use std::fmt::{self, Debug, Write};
+
+use crate::graph::rope::Miss;
+use crate::graph::{Graph, Node, NodeId, Range};
+
+impl<Leaf: Debug> Graph<Leaf> {
+    /// Render all nodes on the graph in the Graphviz DOT format, marking
+    /// `root` as the entry point of the state machine.
+    ///
+    /// Forks are drawn as circles, ropes as boxes, and leaves as double
+    /// circles labeled with the variant they produce. Dashed edges are
+    /// taken when no other edge matches.
+    pub fn to_dot(&self, root: NodeId) -> String {
+        let mut out = String::new();
+
+        self.write_dot(root, &mut out)
+            .expect("Writing to a String can't fail");
+
+        out
+    }
+
+    fn write_dot(&self, root: NodeId, out: &mut String) -> fmt::Result {
+        writeln!(out, "digraph logos {{")?;
+        writeln!(out, "    rankdir=LR;")?;
+        writeln!(out, "    node [fontname=monospace];")?;
+        writeln!(out, "    edge [fontname=monospace];")?;
+        writeln!(out, "    start [shape=point];")?;
+        writeln!(out, "    start -> n{};", root)?;
+
+        let nodes = self
+            .nodes()
+            .iter()
+            .enumerate()
+            .filter_map(|(id, node)| Some((id, node.as_ref()?)));
+
+        for (id, node) in nodes {
+            match node {
+                Node::Fork(fork) => {
+                    writeln!(out, "    n{} [shape=circle, label=\"{}\"];", id, id)?;
+
+                    // Group ranges by their target, so that each pair of
+                    // nodes is connected by at most one edge.
+                    let mut targets: Vec<(NodeId, Vec<Range>)> = Vec::new();
+
+                    for (range, then) in fork.branches() {
+                        match targets.iter_mut().find(|(id, _)| *id == then) {
+                            Some((_, ranges)) => ranges.push(range),
+                            None => targets.push((then, vec![range])),
+                        }
+                    }
+
+                    for (then, ranges) in targets {
+                        let label = ranges
+                            .iter()
+                            .map(ToString::to_string)
+                            .collect::<Vec<_>>()
+                            .join(" ");
+
+                        writeln!(
+                            out,
+                            "    n{} -> n{} [label=\"{}\"];",
+                            id,
+                            then,
+                            escape(&label)
+                        )?;
+                    }
+                    if let Some(miss) = fork.miss {
+                        writeln!(out, "    n{} -> n{} [style=dashed, label=\"_\"];", id, miss)?;
+                    }
+                }
+                Node::Rope(rope) => {
+                    let pattern = rope
+                        .pattern
+                        .iter()
+                        .map(ToString::to_string)
+                        .collect::<String>();
+
+                    writeln!(out, "    n{} [shape=box, label=\"{}\"];", id, id)?;
+                    writeln!(
+                        out,
+                        "    n{} -> n{} [label=\"{}\"];",
+                        id,
+                        rope.then,
+                        escape(&pattern)
+                    )?;
+
+                    match rope.miss {
+                        Miss::First(miss) => {
+                            writeln!(out, "    n{} -> n{} [style=dashed, label=\"_\"];", id, miss)?
+                        }
+                        Miss::Any(miss) => writeln!(
+                            out,
+                            "    n{} -> n{} [style=dashed, label=\"_*\"];",
+                            id, miss
+                        )?,
+                        Miss::None => (),
+                    }
+                }
+                Node::Leaf(leaf) => {
+                    writeln!(
+                        out,
+                        "    n{} [shape=doublecircle, label=\"{}\"];",
+                        id,
+                        escape(&format!("{:?}", leaf))
+                    )?;
+                }
+            }
+        }
+
+        writeln!(out, "}}")
+    }
+}
+
+/// Escape a string so that it can be used inside of a quoted DOT label.
+fn escape(label: &str) -> String {
+    let mut out = String::with_capacity(label.len());
+
+    for c in label.chars() {
+        match c {
+            '"' | '\\' => {
+                out.push('\\');
+                out.push(c);
+            }
+            '\n' => out.push_str("\\n"),
+            c => out.push(c),
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::{Fork, Rope};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn dot() {
+        let mut graph = Graph::new();
+
+        let leaf = graph.push(Node::Leaf("LEAF"));
+        let rope = graph.push(Rope::new("oo", leaf));
+        let root = graph.push(
+            Fork::new()
+                .branch(b'f', rope)
+                .branch('a'..='c', leaf)
+                .branch('x'..='z', leaf),
+        );
+
+        assert_eq!(
+            graph.to_dot(root),
+            "\
+            digraph logos {\n    \
+                rankdir=LR;\n    \
+                node [fontname=monospace];\n    \
+                edge [fontname=monospace];\n    \
+                start [shape=point];\n    \
+                start -> n3;\n    \
+                n1 [shape=doublecircle, label=\"\\\"LEAF\\\"\"];\n    \
+                n2 [shape=box, label=\"2\"];\n    \
+                n2 -> n1 [label=\"oo\"];\n    \
+                n3 [shape=circle, label=\"3\"];\n    \
+                n3 -> n1 [label=\"[a-c] [x-z]\"];\n    \
+                n3 -> n2 [label=\"f\"];\n\
+            }\n\
+            "
+        );
+    }
+
+    #[test]
+    fn exported_before_errors() {
+        let path = std::env::temp_dir().join("logos_exported_before_errors.dot");
+        let target = path.to_str().unwrap();
+
+        let _ = std::fs::remove_file(&path);
+
+        // Both definitions match "a" with the same priority, which is an error.
+        let output = crate::generate(quote::quote! {
+            #[logos(export_dot = #target)]
+            enum Token {
+                #[regex("[a-z]+")]
+                Word,
+
+                #[regex("[a-c]+")]
+                Abc,
+            }
+        });
+
+        assert!(output.to_string().contains("compile_error"));
+        assert!(std::fs::read_to_string(&path)
+            .unwrap()
+            .starts_with("digraph logos {"));
+    }
+
+    #[test]
+    fn escape_label() {
+        assert_eq!(escape(r#"a"b\c"#), r#"a\"b\\c"#);
+    }
+}
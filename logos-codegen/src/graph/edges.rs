@@ -0,0 +1,213 @@
+use std::collections::{HashSet, VecDeque};
+
+use crate::graph::{Graph, Node, NodeId};
+
+impl<Leaf> Graph<Leaf> {
+    /// Find up to `limit` inputs at the edges of the state machine starting at `root`.
+    ///
+    /// These are the shortest input reaching each leaf, and for every transition, inputs that take it on its first and
+    /// last byte, or on the bytes just outside of those. Each input continues along the shortest way to a leaf from
+    /// the state that the transition leads to, so that it makes a whole token where it can.
+    pub fn edge_inputs(&self, root: NodeId, limit: usize) -> Vec<Vec<u8>> {
+        let completions = self.completions();
+        let complete = |mut input: Vec<u8>, id: NodeId| {
+            if let Some(completion) = &completions[id.get()] {
+                input.extend_from_slice(completion);
+            }
+            input
+        };
+
+        let mut inputs = Vec::new();
+        let mut seen = HashSet::new();
+        let mut push = |input: Vec<u8>| {
+            if !input.is_empty() && inputs.len() < limit && seen.insert(input.clone()) {
+                inputs.push(input);
+            }
+        };
+
+        for (id, prefix) in self.prefixes(root) {
+            match &self[id] {
+                Node::Leaf(_) => push(prefix),
+                Node::Fork(fork) => {
+                    for (range, then) in fork.branches() {
+                        for byte in [range.start, range.end] {
+                            push(complete([&prefix[..], &[byte]].concat(), then));
+                        }
+
+                        let outside = [range.start.checked_sub(1), range.end.checked_add(1)];
+
+                        for byte in outside.into_iter().flatten() {
+                            let input = [&prefix[..], &[byte]].concat();
+
+                            match fork.contains(byte) {
+                                Some(other) if other != then => push(complete(input, other)),
+                                Some(_) => (),
+                                None => push(input),
+                            }
+                        }
+                    }
+                }
+                Node::Rope(rope) => {
+                    let starts: Vec<u8> = rope.pattern.iter().map(|range| range.start).collect();
+
+                    for (position, range) in rope.pattern.iter().enumerate() {
+                        let before = [&prefix[..], &starts[..position]].concat();
+
+                        for byte in [range.start, range.end] {
+                            let input = [&before[..], &[byte], &starts[position + 1..]].concat();
+
+                            push(complete(input, rope.then));
+                        }
+
+                        let outside = [range.start.checked_sub(1), range.end.checked_add(1)];
+
+                        for byte in outside.into_iter().flatten() {
+                            push([&before[..], &[byte]].concat());
+                        }
+                    }
+                }
+            }
+        }
+
+        inputs
+    }
+
+    /// The shortest input reaching each state from `root`, in the order that states are reached.
+    fn prefixes(&self, root: NodeId) -> Vec<(NodeId, Vec<u8>)> {
+        let mut prefixes = Vec::new();
+        let mut seen = vec![false; self.nodes().len()];
+        let mut queue = VecDeque::from([(root, Vec::new())]);
+
+        while let Some((id, prefix)) = queue.pop_front() {
+            if std::mem::replace(&mut seen[id.get()], true) {
+                continue;
+            }
+
+            // Misses don't consume any input, so the states they lead to go to the front of the queue.
+            match self.get(id) {
+                Some(Node::Fork(fork)) => {
+                    for (range, then) in fork.branches() {
+                        queue.push_back((then, [&prefix[..], &[range.start]].concat()));
+                    }
+                    if let Some(miss) = fork.miss {
+                        queue.push_front((miss, prefix.clone()));
+                    }
+                }
+                Some(Node::Rope(rope)) => {
+                    let pattern: Vec<u8> = rope.pattern.iter().map(|range| range.start).collect();
+
+                    queue.push_back((rope.then, [&prefix[..], &pattern[..]].concat()));
+
+                    if let Some(miss) = rope.miss.first() {
+                        queue.push_front((miss, prefix.clone()));
+                    }
+                }
+                Some(Node::Leaf(_)) => (),
+                None => continue,
+            }
+
+            prefixes.push((id, prefix));
+        }
+
+        prefixes
+    }
+
+    /// The shortest input taking each state to a leaf, indexed by node.
+    fn completions(&self) -> Vec<Option<Vec<u8>>> {
+        let mut completions: Vec<Option<Vec<u8>>> = self
+            .nodes()
+            .iter()
+            .map(|node| match node {
+                Some(Node::Leaf(_)) => Some(Vec::new()),
+                _ => None,
+            })
+            .collect();
+
+        let mut changed = true;
+
+        while changed {
+            changed = false;
+
+            for (id, node) in self.nodes().iter().enumerate() {
+                let mut candidates: Vec<Vec<u8>> = Vec::new();
+                let completion = |id: NodeId| completions[id.get()].as_ref();
+
+                match node {
+                    Some(Node::Fork(fork)) => {
+                        for (range, then) in fork.branches() {
+                            if let Some(rest) = completion(then) {
+                                candidates.push([&[range.start], &rest[..]].concat());
+                            }
+                        }
+                        candidates.extend(fork.miss.and_then(completion).cloned());
+                    }
+                    Some(Node::Rope(rope)) => {
+                        if let Some(rest) = completion(rope.then) {
+                            let pattern = rope.pattern.iter().map(|range| range.start);
+
+                            candidates.push(pattern.chain(rest.iter().copied()).collect());
+                        }
+                        candidates.extend(rope.miss.first().and_then(completion).cloned());
+                    }
+                    _ => continue,
+                }
+
+                let shortest = candidates
+                    .into_iter()
+                    .min_by(|a, b| (a.len(), a).cmp(&(b.len(), b)));
+
+                if let Some(shortest) = shortest {
+                    let shorter = match &completions[id] {
+                        Some(current) => (shortest.len(), &shortest) < (current.len(), current),
+                        None => true,
+                    };
+
+                    if shorter {
+                        completions[id] = Some(shortest);
+                        changed = true;
+                    }
+                }
+            }
+        }
+
+        completions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::{Fork, Rope};
+
+    #[test]
+    fn edges_of_a_keyword_and_identifiers() {
+        let mut graph = Graph::new();
+
+        let ident = graph.push(Node::Leaf("IDENT"));
+        let fn_ = graph.push(Node::Leaf("FN"));
+        let rest = graph.reserve();
+        let fork = Fork::new().branch(b'a'..=b'z', rest.get()).miss(ident);
+        let more = graph.insert(rest, fork);
+        let rope = graph.push(Rope::new("n", fn_).miss(more));
+        let root = graph.push(
+            Fork::new()
+                .branch(b'a'..=b'e', more)
+                .branch(b'f', rope)
+                .branch(b'g'..=b'z', more),
+        );
+
+        let inputs = graph.edge_inputs(root, 100);
+        let inputs: Vec<&str> = inputs
+            .iter()
+            .map(|input| std::str::from_utf8(input).unwrap())
+            .collect();
+
+        // The shortest input to each leaf, and the edges of the first fork
+        assert_eq!(&inputs[..7], &["a", "e", "`", "f", "g", "z", "{"]);
+        // The identifier loop, reached with the shortest input "a"
+        assert_eq!(&inputs[7..11], &["aa", "az", "a`", "a{"]);
+        // The rope after "f"
+        assert_eq!(&inputs[11..], &["fn", "fm", "fo"]);
+        assert_eq!(graph.edge_inputs(root, 5).len(), 5);
+    }
+}
@@ -7,6 +7,9 @@ use std::ops::Index;
 
 use fnv::FnvHasher;
 
+mod determinism;
+mod dot;
+mod edges;
 mod fork;
 mod impls;
 mod meta;
@@ -14,9 +17,10 @@ mod range;
 mod regex;
 mod rope;
 
+pub use self::determinism::DeterminismReport;
 pub use self::fork::Fork;
 pub use self::meta::Meta;
-pub use self::range::Range;
+pub use self::range::{complement, Range};
 pub use self::rope::Rope;
 
 /// Disambiguation error during the attempt to merge two leaf
@@ -54,6 +58,17 @@ pub struct Graph<Leaf> {
 /// between them.
 pub trait Disambiguate {
     fn cmp(left: &Self, right: &Self) -> Ordering;
+
+    /// Called when `winner` has been picked over `loser`, to optionally produce a replacement
+    /// for `winner` that remembers what it shadows.
+    fn shadow(winner: &Self, loser: &Self) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        let _ = (winner, loser);
+
+        None
+    }
 }
 
 /// Id of a Node in the graph. `NodeId` can be referencing an empty
@@ -293,15 +308,25 @@ impl<Leaf> Graph<Leaf> {
                 return id;
             }
             (Some(Node::Leaf(left)), Some(Node::Leaf(right))) => {
-                return match Disambiguate::cmp(left, right) {
-                    Ordering::Less => b,
-                    Ordering::Greater => a,
+                let shadow = match Disambiguate::cmp(left, right) {
+                    Ordering::Less => Disambiguate::shadow(right, left).ok_or(b),
+                    Ordering::Greater => Disambiguate::shadow(left, right).ok_or(a),
                     Ordering::Equal => {
                         self.errors.push(DisambiguationError(a, b));
 
-                        a
+                        Err(a)
                     }
                 };
+
+                return match shadow {
+                    Ok(leaf) => {
+                        let id = self.push(Node::Leaf(leaf));
+                        self.set_merged(a, b, id);
+
+                        id
+                    }
+                    Err(id) => id,
+                };
             }
             _ => (),
         }
@@ -445,6 +470,14 @@ impl<Leaf> Graph<Leaf> {
     pub fn get(&self, id: NodeId) -> Option<&Node<Leaf>> {
         self.nodes.get(id.get())?.as_ref()
     }
+
+    /// Get the leaf at `id` to change it in place, if there is one.
+    pub fn leaf_mut(&mut self, id: NodeId) -> Option<&mut Leaf> {
+        match self.nodes.get_mut(id.get())? {
+            Some(Node::Leaf(leaf)) => Some(leaf),
+            _ => None,
+        }
+    }
 }
 
 impl<Leaf> Index<NodeId> for Graph<Leaf> {
@@ -24,6 +24,35 @@ impl Range {
     }
 }
 
+/// The bytes that aren't in any of `ranges`, as sorted ranges.
+pub fn complement(ranges: &[Range]) -> Vec<Range> {
+    let mut ranges = ranges.to_vec();
+    let mut out = Vec::new();
+    let mut next = 0u16;
+
+    ranges.sort_unstable();
+
+    for range in ranges {
+        if u16::from(range.start) > next {
+            out.push(Range {
+                start: next as u8,
+                end: range.start - 1,
+            });
+        }
+
+        next = next.max(u16::from(range.end) + 1);
+    }
+
+    if next <= 0xFF {
+        out.push(Range {
+            start: next as u8,
+            end: 0xFF,
+        });
+    }
+
+    out
+}
+
 impl From<u8> for Range {
     fn from(byte: u8) -> Range {
         Range {
@@ -118,6 +147,28 @@ impl From<ClassBytesRange> for Range {
 mod tests {
     use super::*;
 
+    #[test]
+    fn complements() {
+        let range = |start, end| Range { start, end };
+        let ranges = [
+            range(0x23, 0x5B),
+            range(0x00, 0x09),
+            range(0x5D, 0xFF),
+            range(0x0B, 0x21),
+        ];
+
+        assert_eq!(
+            complement(&ranges),
+            &[range(b'\n', b'\n'), range(b'"', b'"'), range(b'\\', b'\\')]
+        );
+        assert_eq!(complement(&[]), &[range(0x00, 0xFF)]);
+        assert_eq!(complement(&[range(0x00, 0xFF)]), &[]);
+        assert_eq!(
+            complement(&[range(0x00, 0x10), range(0x05, 0x20)]),
+            &[range(0x21, 0xFF)]
+        );
+    }
+
     #[test]
     fn range_iter_one() {
         let byte = Range::from(b'!');
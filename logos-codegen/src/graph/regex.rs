@@ -18,7 +18,9 @@ impl<Leaf: Disambiguate + Debug> Graph<Leaf> {
         reserved: Option<ReservedId>,
     ) -> NodeId {
         match mir {
-            Mir::Empty => then,
+            // Assertions are checked by the leaf, see `Mir::take_assertions`.
+            Mir::Empty | Mir::Assertion(_) => then,
+            Mir::Capture(_, _, mir) => self.parse_mir(*mir, then, miss, reserved),
             Mir::Loop(mir) => {
                 let miss = match miss {
                     Some(id) => self.merge(id, then),
@@ -0,0 +1,318 @@
+use proc_macro2::TokenStream;
+use quote::{quote, ToTokens};
+use syn::LitStr;
+
+use crate::captures::Program;
+use crate::error::Errors;
+use crate::graph::{Graph, NodeId};
+use crate::leaf::Leaf;
+use crate::mir::Mir;
+use crate::parser::Generic;
+use crate::test_match::Pattern;
+
+/// The pattern that keywords have to match when `#[logos(keywords)]` isn't given one.
+const IDENTIFIER: &str = "[a-zA-Z_][a-zA-Z0-9_]*";
+
+/// Generate a `KEYWORDS` constant listing every `#[token]` literal that `pattern` matches in its entirety, in the order
+/// that they're defined.
+pub fn generate(
+    this: &TokenStream,
+    generic: &Generic,
+    pattern: Option<LitStr>,
+    literals: Vec<String>,
+    errors: &mut Errors,
+) -> TokenStream {
+    let (source, span) = match &pattern {
+        Some(pattern) => (pattern.value(), pattern.span()),
+        None => (IDENTIFIER.to_owned(), proc_macro2::Span::call_site()),
+    };
+    let program = match Mir::utf8(&source) {
+        Ok(mir) => Program::lookahead(&mir),
+        Err(err) => {
+            errors.err(err, span);
+
+            return quote!();
+        }
+    };
+
+    let mut keywords: Vec<String> = Vec::new();
+
+    for literal in literals {
+        if program.longest_match(literal.as_bytes()) == Some(literal.len())
+            && !keywords.contains(&literal)
+        {
+            keywords.push(literal);
+        }
+    }
+
+    let params = generic.params();
+    let where_clause = generic.where_clause();
+
+    quote! {
+        #[automatically_derived]
+        #[allow(dead_code)]
+        impl #params #this #where_clause {
+            /// Every `#[token]` literal that looks like a keyword, in the order that they're defined.
+            pub const KEYWORDS: &'static [&'static str] = &[#(#keywords),*];
+        }
+    }
+}
+
+/// The keywords looked up by an identifier's leaf once it's matched, instead of being in the state machine.
+pub struct Lookup<'t> {
+    pub table: Table,
+    /// The leaf of each keyword, in the same order as the keywords of the table.
+    pub leaves: Vec<Leaf<'t>>,
+}
+
+/// Give each identifier in `identifiers` (the ids of their leaves, and their definitions) a [Lookup] for the keywords
+/// it matches, if there are at least `threshold` of them. Returns the definitions of the keywords that were taken out
+/// of the state machine this way.
+///
+/// A `#[token]` in `tokens` is a keyword of an identifier if the identifier matches it in its entirety, it has a higher
+/// priority than the identifier, and nothing else matches it: in the state machine, the keyword would only ever
+/// replace the identifier's leaf where the identifier matched exactly the keyword.
+pub fn lookups<'t>(
+    graph: &mut Graph<Leaf<'t>>,
+    identifiers: &[(NodeId, usize)],
+    tokens: &[usize],
+    rules: &[Leaf<'t>],
+    patterns: &[Pattern],
+    threshold: usize,
+) -> Vec<usize> {
+    let programs: Vec<Program> = patterns
+        .iter()
+        .map(|pattern| Program::lookahead(&pattern.to_mir()))
+        .collect();
+    let matches =
+        |rule: usize, bytes: &[u8]| programs[rule].longest_match(bytes) == Some(bytes.len());
+    let mut claimed = Vec::new();
+
+    for &(id, identifier) in identifiers {
+        let keywords: Vec<(usize, &[u8])> = tokens
+            .iter()
+            .filter_map(|&rule| match &patterns[rule] {
+                Pattern::Bytes(bytes) => Some((rule, &bytes[..])),
+                Pattern::Regex(_) => None,
+            })
+            .filter(|&(rule, bytes)| {
+                let leaf = &rules[rule];
+
+                leaf.priority > rules[identifier].priority
+                    && leaf.contextual.is_none()
+                    && !leaf.has_checks()
+                    && !claimed.contains(&rule)
+                    && matches(identifier, bytes)
+            })
+            .filter(|&(rule, bytes)| {
+                (0..rules.len())
+                    .filter(|&other| other != rule && other != identifier)
+                    .all(|other| !matches(other, bytes))
+            })
+            .collect();
+
+        if keywords.len() < threshold || keywords.is_empty() {
+            continue;
+        }
+
+        let table = Table::new(keywords.iter().map(|&(_, bytes)| bytes.to_vec()).collect());
+        let leaves = keywords
+            .iter()
+            .map(|&(rule, _)| rules[rule].clone())
+            .collect();
+
+        claimed.extend(keywords.iter().map(|&(rule, _)| rule));
+
+        if let Some(leaf) = graph.leaf_mut(id) {
+            leaf.keywords = Some(std::rc::Rc::new(Lookup { table, leaves }));
+        }
+    }
+
+    claimed
+}
+
+/// A perfect hash table of keywords, built at compile time for `logos::internal::KeywordTable`, which has to hash
+/// and pick slots in exactly the same way.
+pub struct Table {
+    seed: u64,
+    displacements: Vec<u32>,
+    keywords: Vec<Vec<u8>>,
+    /// The index of the keyword in each slot.
+    slots: Vec<Option<usize>>,
+}
+
+/// The FNV-1a offset basis, which seeds are derived from.
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+
+/// How many displacements are tried for a bucket before trying another seed.
+const MAX_DISPLACEMENT: u32 = 1 << 16;
+
+impl Table {
+    /// Build a table of `keywords`, which must all be different, by hashing and displacing: keywords are put in
+    /// buckets of about four by their hash, and starting with the biggest bucket, each bucket gets the first
+    /// displacement that moves all of its keywords to free slots.
+    pub fn new(keywords: Vec<Vec<u8>>) -> Table {
+        let len = keywords.len().next_power_of_two();
+        let buckets = keywords.len().div_ceil(4).max(1);
+
+        for attempt in 0u64.. {
+            let mut table = Table {
+                seed: FNV_OFFSET ^ attempt.wrapping_mul(0x9e37_79b9_7f4a_7c15),
+                displacements: vec![0; buckets],
+                keywords: Vec::new(),
+                slots: vec![None; len],
+            };
+            let hashes: Vec<u64> = keywords.iter().map(|keyword| table.hash(keyword)).collect();
+            let mut members = vec![Vec::new(); buckets];
+
+            for (index, hash) in hashes.iter().enumerate() {
+                members[table.bucket(*hash)].push(index);
+            }
+
+            let mut order: Vec<usize> = (0..buckets).collect();
+
+            order.sort_by_key(|&bucket| std::cmp::Reverse(members[bucket].len()));
+
+            if order
+                .into_iter()
+                .all(|bucket| table.displace(bucket, &members[bucket], &hashes))
+            {
+                table.keywords = keywords;
+
+                return table;
+            }
+        }
+
+        unreachable!("Some seed always works")
+    }
+
+    /// The index of the keyword in each slot.
+    pub fn slots(&self) -> &[Option<usize>] {
+        &self.slots
+    }
+
+    /// Find a displacement for `bucket` that puts all of its `members` in free slots, and put them there.
+    fn displace(&mut self, bucket: usize, members: &[usize], hashes: &[u64]) -> bool {
+        for displacement in 0..MAX_DISPLACEMENT {
+            self.displacements[bucket] = displacement;
+
+            let mut slots: Vec<usize> = members
+                .iter()
+                .map(|&index| self.slot(hashes[index]))
+                .collect();
+
+            slots.sort_unstable();
+            slots.dedup();
+
+            if slots.len() == members.len() && slots.iter().all(|&slot| self.slots[slot].is_none())
+            {
+                for &index in members {
+                    let slot = self.slot(hashes[index]);
+
+                    self.slots[slot] = Some(index);
+                }
+
+                return true;
+            }
+        }
+
+        false
+    }
+
+    fn hash(&self, keyword: &[u8]) -> u64 {
+        keyword.iter().fold(self.seed, |hash, &byte| {
+            (hash ^ byte as u64).wrapping_mul(0x0000_0100_0000_01b3)
+        })
+    }
+
+    fn bucket(&self, hash: u64) -> usize {
+        (hash >> 32) as usize % self.displacements.len()
+    }
+
+    fn slot(&self, hash: u64) -> usize {
+        let mut mixed = hash ^ self.displacements[self.bucket(hash)] as u64;
+
+        mixed ^= mixed >> 30;
+        mixed = mixed.wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        mixed ^= mixed >> 27;
+        mixed = mixed.wrapping_mul(0x94d0_49bb_1331_11eb);
+        mixed ^= mixed >> 31;
+
+        mixed as usize & (self.slots.len() - 1)
+    }
+}
+
+impl ToTokens for Table {
+    fn to_tokens(&self, out: &mut TokenStream) {
+        let seed = self.seed;
+        let displacements = &self.displacements;
+        let slots = self.slots.iter().map(|slot| {
+            let keyword = match slot {
+                Some(index) => &self.keywords[*index][..],
+                None => &[],
+            };
+
+            proc_macro2::Literal::byte_string(keyword)
+        });
+
+        out.extend(quote! {
+            ::logos::internal::KeywordTable {
+                seed: #seed,
+                displacements: &[#(#displacements),*],
+                slots: &[#(#slots),*],
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keywords(pattern: Option<&str>, literals: &[&str]) -> String {
+        let pattern = pattern.map(|pattern| LitStr::new(pattern, proc_macro2::Span::call_site()));
+        let literals = literals.iter().map(|&literal| literal.to_owned()).collect();
+        let tokens = generate(
+            &quote!(Token),
+            &Generic::default(),
+            pattern,
+            literals,
+            &mut Errors::default(),
+        );
+
+        tokens.to_string()
+    }
+
+    #[test]
+    fn identifiers_by_default() {
+        let tokens = keywords(None, &["fn", "+", "let", "2x", "fn", "_"]);
+
+        assert!(tokens.contains(r#"& ["fn" , "let" , "_"]"#), "{}", tokens);
+    }
+
+    #[test]
+    fn custom_pattern() {
+        let tokens = keywords(Some("[a-z]+!?"), &["fn", "macro!", "Type", "a!!"]);
+
+        assert!(tokens.contains(r#"& ["fn" , "macro!"]"#), "{}", tokens);
+    }
+
+    #[test]
+    fn perfect_hash() {
+        let keywords: Vec<Vec<u8>> = (0..300)
+            .map(|n| format!("keyword{}", n).into_bytes())
+            .collect();
+        let table = Table::new(keywords.clone());
+
+        assert_eq!(table.slots().len(), 512);
+        assert_eq!(table.slots().iter().flatten().count(), 300);
+
+        for (index, keyword) in keywords.iter().enumerate() {
+            assert_eq!(table.slots()[table.slot(table.hash(keyword))], Some(index));
+        }
+
+        let single = Table::new(vec![b"fn".to_vec()]);
+
+        assert_eq!(single.slots(), &[Some(0)]);
+    }
+}
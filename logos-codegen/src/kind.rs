@@ -0,0 +1,84 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Ident, Visibility};
+
+use crate::parser::Generic;
+
+/// Generate a fieldless enum called `kind` with a variant for every variant of the token type, along with a `kind`
+/// method that strips the payload from a token, and `const` tables for converting kinds to and from indices and names.
+pub fn generate(
+    name: &Ident,
+    this: &TokenStream,
+    generic: &Generic,
+    vis: &Visibility,
+    kind: &Ident,
+    derive: Option<TokenStream>,
+    variants: &[Ident],
+) -> TokenStream {
+    let count = variants.len();
+    let names = variants.iter().map(|ident| ident.to_string());
+    let indices = 0..count;
+    let arms = variants
+        .iter()
+        .map(|ident| quote!(#name::#ident { .. } => #kind::#ident,));
+
+    let derive = derive.map(|derive| quote!(#[derive(#derive)]));
+    let doc = format!(
+        "The kind of a [`{}`], with the same variants but none of their fields.",
+        name
+    );
+    let method_doc = format!("The [`{}`] of this token, without its field.", kind);
+    let params = generic.params();
+    let where_clause = generic.where_clause();
+
+    quote! {
+        #[doc = #doc]
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+        #derive
+        #vis enum #kind {
+            #(#variants,)*
+        }
+
+        #[automatically_derived]
+        #[allow(dead_code)]
+        impl #kind {
+            /// The number of kinds.
+            pub const COUNT: usize = #count;
+
+            /// Every kind, in the order their variants are declared.
+            pub const ALL: [#kind; #count] = [#(#kind::#variants),*];
+
+            /// The name of every kind, in the same order as `ALL`.
+            pub const NAMES: [&'static str; #count] = [#(#names),*];
+
+            /// The position of this kind in `ALL`.
+            pub const fn index(self) -> usize {
+                self as usize
+            }
+
+            /// The kind at `index` in `ALL`, if there is one.
+            pub const fn from_index(index: usize) -> Option<#kind> {
+                match index {
+                    #(#indices => Some(#kind::#variants),)*
+                    _ => None,
+                }
+            }
+
+            /// The name of the variant for this kind.
+            pub const fn name(self) -> &'static str {
+                #kind::NAMES[self as usize]
+            }
+        }
+
+        #[automatically_derived]
+        #[allow(dead_code)]
+        impl #params #this #where_clause {
+            #[doc = #method_doc]
+            pub fn kind(&self) -> #kind {
+                match *self {
+                    #(#arms)*
+                }
+            }
+        }
+    }
+}
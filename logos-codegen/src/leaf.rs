@@ -0,0 +1,248 @@
+use std::cmp::{Ord, Ordering};
+use std::fmt::{self, Debug};
+use std::rc::Rc;
+
+use proc_macro2::{Span, TokenStream};
+use syn::{spanned::Spanned, Ident};
+
+use crate::captures::Program;
+use crate::graph::{Disambiguate, Node};
+use crate::keywords::Lookup;
+use crate::mir::Assertions;
+use crate::parser::Lookaround;
+use crate::util::MaybeVoid;
+
+#[derive(Clone)]
+pub struct Leaf<'t> {
+    pub ident: &'t Ident,
+    pub span: Span,
+    pub priority: usize,
+    pub field: MaybeVoid,
+    pub callback: Option<Callback>,
+    pub captures: Option<Rc<Program>>,
+    /// Bit in the lexer's set of enabled contextual tokens that controls this leaf.
+    pub contextual: Option<u32>,
+    /// Word boundaries and anchors that must hold at the start and end of the token.
+    pub assertions: Assertions,
+    /// Trailing context that must match after the token.
+    pub lookahead: Option<Rc<Program>>,
+    /// Trailing context that mustn't match after the token.
+    pub not_followed_by: Option<Rc<Program>>,
+    /// Inclusive byte ranges that the byte before the token mustn't be in.
+    pub not_preceded_by: Option<Rc<[(u8, u8)]>>,
+    /// A program that must match the text before the token, reading it backwards.
+    pub preceded_by: Option<Rc<Program>>,
+    /// The whole regex, for a leaf that's reached after only the first byte of its token and matches the rest by
+    /// simulating it.
+    pub simulate: Option<Rc<Program>>,
+    /// The literals of a large alternation, for a leaf that's reached after only the first byte of its token and
+    /// matches the rest by binary search. Sorted, and grouped by length from longest to shortest.
+    pub literals: Option<Rc<Vec<Vec<Vec<u8>>>>>,
+    /// The keywords that an identifier can be, looked up once it's matched instead of being in the state machine.
+    pub keywords: Option<Rc<Lookup<'t>>>,
+    /// Lexer flags that must be set, or unset, for the leaf to be used.
+    pub requires: Rc<[Requirement]>,
+    /// The leaf this one shadows, used instead when this leaf is contextual and not enabled, or when its checks
+    /// don't pass.
+    pub fallback: Option<Box<Leaf<'t>>>,
+    /// Index of the definition this leaf was made from, in the order that definitions are declared.
+    pub rule: usize,
+    /// Index of the definition among the definitions of its variant, reported by `Lexer::matched_rule`.
+    pub variant_rule: usize,
+    /// Index of the variant, in the order that variants are declared.
+    pub kind: usize,
+}
+
+/// A flag that `requires(...)` checks for, which must be set unless it's negated.
+#[derive(Clone)]
+pub struct Requirement {
+    pub flag: TokenStream,
+    pub negated: bool,
+}
+
+#[derive(Clone)]
+pub enum Callback {
+    Label(TokenStream),
+    Inline(Box<InlineCallback>),
+}
+
+#[derive(Clone)]
+pub struct InlineCallback {
+    pub arg: Ident,
+    pub body: TokenStream,
+    pub span: Span,
+}
+
+impl From<InlineCallback> for Callback {
+    fn from(inline: InlineCallback) -> Callback {
+        Callback::Inline(Box::new(inline))
+    }
+}
+
+impl Callback {
+    pub fn span(&self) -> Span {
+        match self {
+            Callback::Label(tokens) => tokens.span(),
+            Callback::Inline(inline) => inline.span,
+        }
+    }
+}
+
+impl<'t> Leaf<'t> {
+    pub fn new(ident: &'t Ident, span: Span) -> Self {
+        Leaf {
+            ident,
+            span,
+            priority: 0,
+            field: MaybeVoid::Void,
+            callback: None,
+            captures: None,
+            contextual: None,
+            assertions: Assertions::default(),
+            lookahead: None,
+            not_followed_by: None,
+            not_preceded_by: None,
+            preceded_by: None,
+            simulate: None,
+            literals: None,
+            keywords: None,
+            requires: Rc::from([]),
+            fallback: None,
+            rule: 0,
+            variant_rule: 0,
+            kind: 0,
+        }
+    }
+
+    pub fn callback(mut self, callback: Option<Callback>) -> Self {
+        self.callback = callback;
+        self
+    }
+
+    pub fn captures(mut self, captures: Option<Program>) -> Self {
+        self.captures = captures.map(Rc::new);
+        self
+    }
+
+    pub fn contextual(mut self, contextual: Option<u32>) -> Self {
+        self.contextual = contextual;
+        self
+    }
+
+    pub fn assertions(mut self, assertions: Assertions) -> Self {
+        self.assertions = assertions;
+        self
+    }
+
+    pub fn lookaround(mut self, lookaround: &Lookaround) -> Self {
+        self.lookahead = lookaround.followed_by.clone();
+        self.not_followed_by = lookaround.not_followed_by.clone();
+        self.not_preceded_by = lookaround.not_preceded_by.clone();
+        self.preceded_by = lookaround.preceded_by.clone();
+        self
+    }
+
+    pub fn simulate(mut self, simulate: Option<Program>) -> Self {
+        self.simulate = simulate.map(Rc::new);
+        self
+    }
+
+    pub fn requires(mut self, requires: Vec<Requirement>) -> Self {
+        self.requires = Rc::from(requires);
+        self
+    }
+
+    /// Group `literals` by length from longest to shortest, and sort each group for binary search.
+    pub fn literals(mut self, mut literals: Vec<Vec<u8>>) -> Self {
+        literals.sort_unstable_by(|a, b| b.len().cmp(&a.len()).then_with(|| a.cmp(b)));
+        literals.dedup();
+
+        let mut buckets: Vec<Vec<Vec<u8>>> = Vec::new();
+
+        for literal in literals {
+            match buckets.last_mut() {
+                Some(bucket) if bucket[0].len() == literal.len() => bucket.push(literal),
+                _ => buckets.push(vec![literal]),
+            }
+        }
+
+        self.literals = Some(Rc::new(buckets));
+        self
+    }
+
+    /// Whether the leaf has assertions, trailing context, preceding text, a simulated regex, a literal set or flags to
+    /// check before it can be used.
+    pub fn has_checks(&self) -> bool {
+        !self.assertions.is_empty()
+            || !self.requires.is_empty()
+            || self.lookahead.is_some()
+            || self.not_followed_by.is_some()
+            || self.not_preceded_by.is_some()
+            || self.preceded_by.is_some()
+            || self.simulate.is_some()
+            || self.literals.is_some()
+    }
+
+    pub fn field(mut self, field: MaybeVoid) -> Self {
+        self.field = field;
+        self
+    }
+
+    pub fn rule(mut self, rule: usize) -> Self {
+        self.rule = rule;
+        self
+    }
+
+    pub fn variant_rule(mut self, variant_rule: usize) -> Self {
+        self.variant_rule = variant_rule;
+        self
+    }
+
+    pub fn kind(mut self, kind: usize) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    pub fn priority(mut self, priority: usize) -> Self {
+        self.priority = priority;
+        self
+    }
+}
+
+impl Disambiguate for Leaf<'_> {
+    fn cmp(left: &Leaf, right: &Leaf) -> Ordering {
+        Ord::cmp(&left.priority, &right.priority)
+    }
+
+    fn shadow(winner: &Self, loser: &Self) -> Option<Self> {
+        if winner.contextual.is_none() && !winner.has_checks() {
+            return None;
+        }
+
+        match &winner.fallback {
+            Some(fallback) if Self::cmp(fallback, loser) != Ordering::Less => None,
+            _ => Some(Leaf {
+                fallback: Some(Box::new(loser.clone())),
+                ..winner.clone()
+            }),
+        }
+    }
+}
+
+impl<'t> From<Leaf<'t>> for Node<Leaf<'t>> {
+    fn from(leaf: Leaf<'t>) -> Self {
+        Node::Leaf(leaf)
+    }
+}
+
+impl Debug for Leaf<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "::{}", self.ident)?;
+
+        match self.callback {
+            Some(Callback::Label(ref label)) => write!(f, " ({})", label),
+            Some(Callback::Inline(_)) => f.write_str(" (<inline>)"),
+            None => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,1261 @@
+//! <img src="https://raw.githubusercontent.com/maciejhirsz/logos/master/logos.svg?sanitize=true" alt="Logos logo" width="250" align="right">
+//!
+//! # Logos
+//!
+//! This is the code generation crate behind the `#[derive(Logos)]` macro, [for documentation go to main
+//! crate](https://docs.rs/logos).
+//!
+//! Most people will never need to use this crate directly. It exists so that the lexer generator can be used outside
+//! of a procedural macro, for example by `logos-cli` to generate lexers ahead of time.
+
+// The `quote!` macro requires deep recursion.
+#![recursion_limit = "196"]
+#![doc(html_logo_url = "https://maciej.codes/kosz/logos.png")]
+
+mod captures;
+mod case_fold;
+mod conformance;
+mod display;
+mod error;
+mod generator;
+mod grammar;
+mod graph;
+mod keywords;
+mod kind;
+mod leaf;
+mod mir;
+mod owned;
+mod parser;
+mod rule_consts;
+mod sniff;
+mod stats;
+mod test_match;
+mod util;
+mod verify_corpus;
+mod witness;
+
+use captures::Program;
+use error::Errors;
+use generator::Generator;
+use graph::{DeterminismReport, DisambiguationError, Fork, Graph, Node, Rope};
+use leaf::{Callback, InlineCallback, Leaf};
+use mir::{Assertion, Assertions, Class, Mir};
+use parser::{Codegen, Generic, InvalidUtf8, Literal, Mode, Parser, VariantLogos};
+use stats::Stats;
+use test_match::Pattern;
+use util::MaybeVoid;
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use regex_syntax::hir::{ClassBytes, ClassBytesRange};
+use syn::spanned::Spanned;
+use syn::{Fields, Ident, ItemEnum};
+
+/// Generate an implementation of the `Logos` trait for the enum in `input`.
+///
+/// `input` should be the same tokens that the derive macro would receive - an enum definition, complete with its
+/// attributes. Any problems with the definition are reported as `compile_error!` invocations inside of the returned
+/// implementation.
+///
+/// # Panics
+///
+/// This function panics if `input` can't be parsed as an enum.
+pub fn generate(input: TokenStream) -> TokenStream {
+    let mut item: ItemEnum = syn::parse2(input).expect("Logos can be only be derived for enums");
+
+    let name = &item.ident;
+
+    let mut parser = Parser::default();
+
+    for param in item.generics.params {
+        parser.parse_generic(param);
+    }
+
+    if let Some(clause) = item.generics.where_clause.take() {
+        parser.parse_where_clause(clause);
+    }
+
+    for attr in &mut item.attrs {
+        parser.try_parse_logos(attr);
+
+        // TODO: Remove in future versions
+        if attr.path.is_ident("extras") {
+            parser.err(
+                "\
+                #[extras] attribute is deprecated. Use #[logos(extras = Type)] instead.\n\n\
+                For help with migration see release notes: \
+                https://github.com/maciejhirsz/logos/releases\
+                ",
+                attr.span(),
+            );
+        }
+    }
+
+    let mut ropes = Vec::new();
+    let mut regex_ids = Vec::new();
+    let mut rules = Vec::new();
+    let mut contextual = Vec::new();
+    let mut patterns = Vec::new();
+    let mut descriptions = Vec::new();
+    let mut unterminated_rules = Vec::new();
+    let mut witnesses = Vec::new();
+    let mut display_names = Vec::new();
+    let mut eof: Option<Ident> = None;
+    let mut owned_fields = Vec::new();
+    let mut literals = Vec::new();
+    let mut literal_sets = Vec::new();
+    // Plain `#[token]`s and regexes without checks, which are candidates for keyword tables.
+    let mut keyword_tokens = Vec::new();
+    let mut identifiers = Vec::new();
+    let kinds: Vec<_> = item
+        .variants
+        .iter()
+        .map(|variant| variant.ident.clone())
+        .collect();
+    let units: Vec<_> = item
+        .variants
+        .iter()
+        .map(|variant| matches!(variant.fields, Fields::Unit))
+        .collect();
+    let mut graph = Graph::new();
+
+    for (kind, variant) in item.variants.iter_mut().enumerate() {
+        let field = match &mut variant.fields {
+            Fields::Unit => {
+                owned_fields.push((variant.ident.clone(), None));
+
+                MaybeVoid::Void
+            }
+            Fields::Unnamed(fields) => {
+                if fields.unnamed.len() != 1 {
+                    parser.err(
+                        format!(
+                            "Logos currently only supports variants with one field, found {}",
+                            fields.unnamed.len(),
+                        ),
+                        fields.span(),
+                    );
+                }
+
+                let ty = &mut fields
+                    .unnamed
+                    .first_mut()
+                    .expect("Already checked len; qed")
+                    .ty;
+                let field = parser.get_type(ty);
+
+                owned_fields.push((variant.ident.clone(), Some(ty.clone())));
+
+                MaybeVoid::Some(field)
+            }
+            Fields::Named(fields) => {
+                parser.err("Logos doesn't support named fields yet.", fields.span());
+
+                MaybeVoid::Void
+            }
+        };
+
+        // Lazy leaf constructor to avoid cloning
+        let var_ident = &variant.ident;
+        let ty = field.clone();
+        let leaf = move |span| Leaf::new(var_ident, span).field(field.clone()).kind(kind);
+
+        // All contextual definitions of a variant are enabled together, so they share a bit.
+        let mut contextual_bit = |enabled: bool, ident: &Ident| -> Option<u32> {
+            if !enabled {
+                return None;
+            }
+
+            let bit = match contextual.iter().position(|other| other == ident) {
+                Some(bit) => bit,
+                None => {
+                    contextual.push(ident.clone());
+                    contextual.len() - 1
+                }
+            };
+
+            Some(bit as u32)
+        };
+
+        // Used to work out the display name, which is the literal of a lone `#[token]` unless it's given explicitly.
+        let mut settings = VariantLogos::default();
+        let mut definitions = 0;
+        let mut token_literal = None;
+
+        for attr in &mut variant.attrs {
+            let attr_name = match attr.path.get_ident() {
+                Some(ident) => ident.to_string(),
+                None => continue,
+            };
+
+            match attr_name.as_str() {
+                "error" => {
+                    // TODO: Remove in future versions
+                    parser.err(
+                        "\
+                        Since 0.13, tokens no longer require an #[error] variant.\n\n\
+                        For help with migration see release notes: \
+                        https://github.com/maciejhirsz/logos/releases\
+                        ",
+                        attr.span(),
+                    );
+                }
+                "end" => {
+                    // TODO: Remove in future versions
+                    parser.err(
+                        "\
+                        Since 0.11, tokens no longer require the #[end] variant.\n\n\
+                        For help with migration see release notes: \
+                        https://github.com/maciejhirsz/logos/releases\
+                        ",
+                        attr.span(),
+                    );
+                }
+                "logos" => {
+                    parser.parse_variant_logos(attr, &mut settings);
+                }
+                "token" => {
+                    let definition = match parser.parse_definition(attr) {
+                        Some(definition) => definition,
+                        None => {
+                            parser.err("Expected #[token(...)]", attr.span());
+                            continue;
+                        }
+                    };
+
+                    definitions += 1;
+                    token_literal = String::from_utf8(definition.literal.to_bytes()).ok();
+
+                    if let Literal::Utf8(literal) = &definition.literal {
+                        literals.push(literal.value());
+                    }
+
+                    if let Some(span) = definition.simulate {
+                        parser.err(
+                            "strategy = \"callback\" is only supported on #[regex], tokens are always cheap to match",
+                            span,
+                        );
+                    }
+
+                    let sentinel = definition
+                        .literal
+                        .to_bytes()
+                        .into_iter()
+                        .find(|byte| parser.sentinels.contains(byte));
+
+                    if let Some(byte) = sentinel {
+                        parser.err(
+                            format!(
+                                "This token contains the sentinel byte {:#04X}, so it can never be matched",
+                                byte
+                            ),
+                            definition.literal.span(),
+                        );
+                        continue;
+                    }
+
+                    let lookaround =
+                        match definition.lookaround(&parser.subpatterns, &mut parser.errors) {
+                            Some(lookaround) => lookaround,
+                            None => continue,
+                        };
+                    let description = definition.describe("token");
+                    witnesses.extend(
+                        definition
+                            .witnesses
+                            .into_iter()
+                            .map(|witness| (rules.len(), witness)),
+                    );
+
+                    if definition.ignore_flags.is_empty() {
+                        let bytes = definition.literal.to_bytes();
+                        let leaf = leaf(definition.literal.span())
+                            .priority(
+                                definition
+                                    .priority
+                                    .unwrap_or(bytes.len() * 2 + lookaround.priority),
+                            )
+                            .callback(definition.callback)
+                            .lookaround(&lookaround)
+                            .requires(definition.requires.clone())
+                            .contextual(contextual_bit(definition.contextual, var_ident))
+                            .rule(rules.len())
+                            .variant_rule(definitions - 1);
+
+                        rules.push(leaf.clone());
+                        patterns.push(Pattern::Bytes(bytes.clone()));
+                        descriptions.push(description);
+
+                        let then = graph.push(leaf);
+
+                        keyword_tokens.push(rules.len() - 1);
+                        ropes.push(Rope::new(bytes, then));
+                    } else {
+                        // An escaped literal is always a valid regex, but it can have characters that byte
+                        // semantics (`unicode = false`) don't allow.
+                        let mir = match definition.literal.escape_regex().to_mir(
+                            &Default::default(),
+                            definition.ignore_flags,
+                            definition.unicode,
+                            &mut parser.errors,
+                        ) {
+                            Ok(mir) => mir,
+                            Err(err) => {
+                                parser.err(err, definition.literal.span());
+                                continue;
+                            }
+                        };
+
+                        let leaf = leaf(definition.literal.span())
+                            .priority(
+                                definition
+                                    .priority
+                                    .unwrap_or_else(|| mir.priority() + lookaround.priority),
+                            )
+                            .callback(definition.callback)
+                            .lookaround(&lookaround)
+                            .requires(definition.requires.clone())
+                            .contextual(contextual_bit(definition.contextual, var_ident))
+                            .rule(rules.len())
+                            .variant_rule(definitions - 1);
+
+                        rules.push(leaf.clone());
+                        patterns.push(Pattern::Regex(mir.clone()));
+                        descriptions.push(description);
+
+                        let then = graph.push(leaf);
+                        let id = graph.regex(mir, then);
+
+                        regex_ids.push(id);
+                    }
+                }
+                "regex" => {
+                    let definition = match parser.parse_definition(attr) {
+                        Some(definition) => definition,
+                        None => {
+                            parser.err("Expected #[regex(...)]", attr.span());
+                            continue;
+                        }
+                    };
+
+                    definitions += 1;
+                    let mut mir = match definition.literal.to_mir(
+                        &parser.subpatterns,
+                        definition.ignore_flags,
+                        definition.unicode,
+                        &mut parser.errors,
+                    ) {
+                        Ok(mir) => mir,
+                        Err(err) => {
+                            parser.err(err, definition.literal.span());
+                            continue;
+                        }
+                    };
+                    let assertions = match mir.take_assertions() {
+                        Ok(assertions) => assertions,
+                        Err(err) => {
+                            parser.err(err, definition.literal.span());
+                            continue;
+                        }
+                    };
+                    let mir = match mir.exclude_bytes(&parser.sentinels) {
+                        Ok(mir) => mir,
+                        Err(err) => {
+                            parser.err(err, definition.literal.span());
+                            continue;
+                        }
+                    };
+                    let lookaround =
+                        match definition.lookaround(&parser.subpatterns, &mut parser.errors) {
+                            Some(lookaround) => lookaround,
+                            None => continue,
+                        };
+                    let description = definition.describe("regex");
+
+                    // Only the first byte of a simulated definition goes into the state machine.
+                    let simulate = definition.simulate.map(|_| Program::lookahead(&mir));
+                    let entry = match &simulate {
+                        Some(_)
+                            if lookaround.followed_by.is_some()
+                                || lookaround.not_followed_by.is_some()
+                                || !assertions.end.is_empty() =>
+                        {
+                            parser.err(
+                                "\
+                                strategy = \"callback\" doesn't support followed_by, not_followed_by, or assertions at \
+                                the end of the pattern\
+                                ",
+                                definition.literal.span(),
+                            );
+                            continue;
+                        }
+                        Some(program) if program.matches_empty() => {
+                            parser.err(
+                                "A definition with strategy = \"callback\" must not match the empty string",
+                                definition.literal.span(),
+                            );
+                            continue;
+                        }
+                        Some(program) => Mir::Class(Class::Bytes(ClassBytes::new(
+                            program
+                                .first_bytes()
+                                .into_iter()
+                                .map(|(start, end)| ClassBytesRange::new(start, end)),
+                        ))),
+                        None => mir.clone(),
+                    };
+                    let literal_set = match &simulate {
+                        None if parser.strategy.literal_sets > 0
+                            && lookaround.followed_by.is_none()
+                            && lookaround.not_preceded_by.is_none()
+                            && assertions.is_empty() =>
+                        {
+                            mir.literals()
+                                .filter(|set| set.len() >= parser.strategy.literal_sets)
+                        }
+                        _ => None,
+                    };
+                    witnesses.extend(
+                        definition
+                            .witnesses
+                            .into_iter()
+                            .map(|witness| (rules.len(), witness)),
+                    );
+
+                    // The input ending part way through the token is matched by a definition of its own, with the
+                    // lowest priority so that a whole token always wins.
+                    let unterminated = match (&definition.unterminated, &simulate) {
+                        (None, _) => None,
+                        (Some(_), Some(_)) => {
+                            parser.err(
+                                "strategy = \"callback\" doesn't support unterminated",
+                                definition.literal.span(),
+                            );
+                            None
+                        }
+                        (Some(unterminated), None) => match mir.unterminated() {
+                            Some(prefixes) => Some((unterminated.clone(), prefixes)),
+                            None => {
+                                parser.err(
+                                    "\
+                                    unterminated needs a pattern that continues after its opening, like the `/*` of a \
+                                    block comment\
+                                    ",
+                                    definition.literal.span(),
+                                );
+                                None
+                            }
+                        },
+                    };
+                    let unterminated = unterminated.and_then(|(unterminated, prefixes)| {
+                        let span = definition.literal.span();
+                        let target = syn::parse2::<Ident>(unterminated.clone())
+                            .ok()
+                            .and_then(|ident| kinds.iter().position(|kind| *kind == ident));
+                        let leaf = match target {
+                            Some(kind) if units[kind] => Leaf::new(&kinds[kind], span).kind(kind),
+                            Some(_) => {
+                                parser.err(
+                                    "unterminated must be a unit variant, or a value of the error type",
+                                    unterminated.span(),
+                                );
+                                return None;
+                            }
+                            None => leaf(span)
+                                .callback(Some(
+                                    InlineCallback {
+                                        arg: Ident::new("_", span),
+                                        body: quote!(::core::result::Result::<#ty, _>::Err(#unterminated)),
+                                        span,
+                                    }
+                                    .into(),
+                                ))
+                                .variant_rule(definitions - 1),
+                        };
+                        let leaf = leaf.assertions(Assertions {
+                            start: assertions.start.clone(),
+                            end: vec![Assertion::EndText],
+                        });
+
+                        let leaf = Leaf {
+                            not_preceded_by: lookaround.not_preceded_by.clone(),
+                            preceded_by: lookaround.preceded_by.clone(),
+                            ..leaf.requires(definition.requires.clone())
+                        };
+
+                        Some((leaf, prefixes))
+                    });
+
+                    let leaf = leaf(definition.literal.span())
+                        .priority(
+                            definition
+                                .priority
+                                .unwrap_or_else(|| mir.priority() + lookaround.priority),
+                        )
+                        .callback(definition.callback)
+                        .captures(Program::compile(&mir))
+                        .assertions(assertions)
+                        .lookaround(&lookaround)
+                        .requires(definition.requires.clone())
+                        .simulate(simulate)
+                        .contextual(contextual_bit(definition.contextual, var_ident))
+                        .rule(rules.len())
+                        .variant_rule(definitions - 1);
+
+                    rules.push(leaf.clone());
+                    patterns.push(Pattern::Regex(mir));
+                    descriptions.push(description.clone());
+
+                    if let Some((unterminated, prefixes)) = unterminated {
+                        let unterminated = unterminated.rule(rules.len());
+
+                        unterminated_rules.push(rules.len());
+                        rules.push(unterminated.clone());
+                        patterns.push(Pattern::Regex(prefixes.clone()));
+                        descriptions.push(format!("{} (unterminated)", description));
+
+                        let then = graph.push(unterminated);
+                        let id = graph.regex(prefixes, then);
+
+                        regex_ids.push(id);
+                    }
+
+                    // Whether a large alternation can skip the state machine depends on every other definition, so
+                    // it's pushed once they're all known.
+                    if let Some(set) = literal_set {
+                        literal_sets.push((leaf, entry, set));
+                        continue;
+                    }
+
+                    let plain = leaf.contextual.is_none() && !leaf.has_checks();
+                    let rule = leaf.rule;
+                    let then = graph.push(leaf);
+                    let id = graph.regex(entry, then);
+
+                    if plain {
+                        identifiers.push((then, rule));
+                    }
+                    regex_ids.push(id);
+                }
+                _ => (),
+            }
+        }
+
+        if let Some(span) = settings.eof {
+            if !matches!(variant.fields, Fields::Unit) {
+                parser.err("An eof variant can't have a field", variant.fields.span());
+            }
+            if definitions > 0 {
+                parser.err(
+                    "\
+                    An eof variant is produced once the input runs out, so it can't have \
+                    #[token] or #[regex] definitions\
+                    ",
+                    span,
+                );
+            }
+            match &eof {
+                Some(previous) => {
+                    parser
+                        .err("Only one variant can be marked as eof", span)
+                        .err("Previous eof variant here", previous.span());
+                }
+                None => eof = Some(variant.ident.clone()),
+            }
+        }
+
+        let display_name = settings
+            .display_name
+            .map(|name| name.value())
+            .or(token_literal.filter(|_| definitions == 1))
+            .unwrap_or_else(|| variant.ident.to_string());
+
+        display_names.push((variant.ident.clone(), display_name));
+    }
+
+    // The state machine only reads the first byte of a literal set before handing over to the binary search, which
+    // can't fall back to other definitions, so none of them may start with the same byte.
+    for (leaf, mir, set) in literal_sets {
+        let mut first_bytes: Vec<u8> = set.iter().map(|literal| literal[0]).collect();
+
+        first_bytes.sort_unstable();
+        first_bytes.dedup();
+
+        let overlaps = patterns
+            .iter()
+            .enumerate()
+            .filter(|&(rule, _)| rule != leaf.rule)
+            .flat_map(|(_, pattern)| Program::lookahead(&pattern.to_mir()).first_bytes())
+            .any(|(start, end)| first_bytes.iter().any(|byte| (start..=end).contains(byte)));
+
+        let (leaf, entry) = match overlaps || !first_bytes.iter().all(u8::is_ascii) {
+            true => (leaf, mir),
+            false => {
+                let entry = Mir::Class(Class::Bytes(ClassBytes::new(
+                    first_bytes
+                        .iter()
+                        .map(|&byte| ClassBytesRange::new(byte, byte)),
+                )));
+
+                (leaf.literals(set), entry)
+            }
+        };
+
+        let then = graph.push(leaf);
+        let id = graph.regex(entry, then);
+
+        regex_ids.push(id);
+    }
+
+    // Lots of keywords that an identifier also matches are looked up in a table once the identifier has been matched,
+    // which keeps them out of the state machine.
+    if parser.strategy.keyword_tables > 0 {
+        let claimed = keywords::lookups(
+            &mut graph,
+            &identifiers,
+            &keyword_tokens,
+            &rules,
+            &patterns,
+            parser.strategy.keyword_tables,
+        );
+
+        ropes = ropes
+            .into_iter()
+            .zip(&keyword_tokens)
+            .filter(|(_, rule)| !claimed.contains(rule))
+            .map(|(rope, _)| rope)
+            .collect();
+    }
+
+    let extras = parser.extras.take();
+    let source = match parser.source_type.take() {
+        // Sources can borrow too (like `Segmented<'s>`), so their lifetimes are fixed up the same way.
+        Some(source) => match syn::parse2::<syn::Type>(source.clone()) {
+            Ok(mut ty) => parser.get_type(&mut ty),
+            Err(_) => source,
+        },
+        None => match parser.mode {
+            Mode::Utf8 => quote!(str),
+            Mode::Binary => quote!([u8]),
+        },
+    };
+
+    let error = match parser.error_type.take() {
+        // Fix up lifetimes and generics the same way as for field types, if possible.
+        Some(error) => match syn::parse2::<syn::Type>(error.clone()) {
+            Ok(mut ty) => parser.get_type(&mut ty),
+            Err(_) => error,
+        },
+        None => quote!(::logos::UnknownToken),
+    };
+
+    let generics = parser.generics();
+    let generic = parser.generic();
+    let this = quote!(#name #generics);
+    let params = generic.params();
+    let where_clause = generic.where_clause();
+
+    let grammar = match parser.doc_grammar {
+        true => grammar::generate(&rules, &descriptions),
+        false => quote!(),
+    };
+
+    let kind_count = kinds.len();
+    let variants = kinds
+        .iter()
+        .zip(&display_names)
+        .zip(&units)
+        .enumerate()
+        .map(|(index, ((ident, (_, display_name)), unit))| {
+            let ident = ident.to_string();
+            let has_field = !unit;
+
+            quote! {
+                ::logos::VariantInfo {
+                    name: #ident,
+                    display_name: #display_name,
+                    index: #index,
+                    has_field: #has_field,
+                }
+            }
+        });
+    let indices = kinds
+        .iter()
+        .enumerate()
+        .map(|(kind, ident)| quote!(#name::#ident { .. } => #kind,));
+
+    // These are required by the trait, so even an impl that only reports errors needs them.
+    let variant_items = quote! {
+        const VARIANT_COUNT: usize = #kind_count;
+
+        const ALL: &'static [::logos::VariantInfo] = &[#(#variants),*];
+
+        fn index(&self) -> usize {
+            match *self {
+                #(#indices)*
+            }
+        }
+    };
+
+    let impl_logos = |body, items| {
+        quote! {
+            #grammar
+            #[automatically_derived]
+            #[allow(unused_braces)]
+            impl #params ::logos::Logos<'s> for #this #where_clause {
+                type Extras = #extras;
+
+                type Source = #source;
+
+                type Error = #error;
+
+                fn lex(lex: &mut ::logos::Lexer<'s, Self>) {
+                    #body
+                }
+
+                #variant_items
+
+                #items
+            }
+        }
+    };
+
+    let mut root = Fork::new();
+
+    merge_root(&mut root, &mut graph, regex_ids, ropes);
+
+    for &DisambiguationError(a, b) in graph.errors() {
+        let a = graph[a].unwrap_leaf();
+        let b = graph[b].unwrap_leaf();
+        let disambiguate = a.priority + 1;
+
+        let example = Program::lookahead(&patterns[a.rule].to_mir())
+            .common_input(&Program::lookahead(&patterns[b.rule].to_mir()))
+            .map(|input| {
+                format!(
+                    " For example, both definitions match {}.",
+                    describe_input(&input)
+                )
+            })
+            .unwrap_or_default();
+
+        let mut err = |a: &Leaf, b: &Leaf| {
+            parser.err(
+                format!(
+                    "\
+                    A definition of variant `{0}` can match the same input as another definition of variant `{1}`.\
+                    {2}\n\n\
+                    hint: Consider giving one definition a higher priority: \
+                    #[regex(..., priority = {3})]\
+                    ",
+                    a.ident,
+                    b.ident,
+                    example,
+                    disambiguate,
+                ),
+                a.span
+            );
+        };
+
+        err(a, b);
+        err(b, a);
+    }
+
+    witness::check(
+        &rules,
+        &patterns,
+        &descriptions,
+        &witnesses,
+        &mut parser.errors,
+    );
+
+    let invalid_utf8 = match &parser.invalid_utf8 {
+        None => quote!(),
+        Some(policy) => {
+            let handle = match policy {
+                InvalidUtf8::Error => quote!(lex.error()),
+                InvalidUtf8::Skip => quote!(lex.skip_token()),
+                InvalidUtf8::Replace(span) => {
+                    let programs: Vec<Program> = patterns
+                        .iter()
+                        .map(|pattern| Program::lookahead(&pattern.to_mir()))
+                        .collect();
+                    let replacement = char::REPLACEMENT_CHARACTER.to_string();
+
+                    match witness::winner(&rules, &programs, replacement.as_bytes()) {
+                        Some((rule, covered)) if covered == replacement.len() => {
+                            quote!(<#this as ::logos::Logos<'s>>::reparse(lex, #rule))
+                        }
+                        _ => {
+                            parser.err(
+                                "\
+                                invalid_utf8 = \"replace\" lexes invalid sequences like a lone \"\\u{FFFD}\", \
+                                but no definition matches that on its own.\n\n\
+                                hint: Add a definition that does, like #[regex(\".\")]\
+                                ",
+                                *span,
+                            );
+                            quote!()
+                        }
+                    }
+                }
+            };
+
+            quote! {
+                let invalid = lex.invalid_utf8();
+
+                if invalid > 0 {
+                    lex.bump_unchecked(invalid);
+
+                    return #handle;
+                }
+            }
+        }
+    };
+
+    if contextual.len() > 64 {
+        parser.err(
+            "At most 64 variants can have contextual definitions",
+            contextual[64].span(),
+        );
+    }
+
+    let root = graph.push(root);
+
+    graph.shake(root);
+
+    // The graph is exported before any errors are reported, so that it can help with debugging them.
+    if let Some(path) = parser.export_dot.take() {
+        if let Err(err) = export(&path.value(), graph.to_dot(root)) {
+            parser.err(
+                format!("Failed to export the state machine graph: {}", err),
+                path.span(),
+            );
+        }
+    }
+
+    for node in graph.nodes().iter().flatten() {
+        if let Node::Leaf(leaf) = node {
+            if leaf.contextual.is_some() && leaf.fallback.is_none() {
+                parser.err(
+                    format!(
+                        "\
+                        A contextual definition of variant `{}` has nothing to fall back to.\n\n\
+                        hint: Contextual definitions must also match another definition (such as an \
+                        identifier) with a lower priority, which is used while they are disabled\
+                        ",
+                        leaf.ident,
+                    ),
+                    leaf.span,
+                );
+            }
+        }
+    }
+
+    let corpus_test = match parser.verify_corpus.take() {
+        Some(pattern) if !generic.is_empty() => {
+            parser.err(
+                "verify_corpus needs a concrete type for every type parameter, set with #[logos(type T = ...)]",
+                pattern.span(),
+            );
+
+            quote!()
+        }
+        Some(pattern) => verify_corpus::generate(name, &source, &pattern, &mut parser.errors),
+        None => quote!(),
+    };
+
+    let conformance_tests = match parser.conformance_tests.take() {
+        Some((span, _)) if !generic.is_empty() => {
+            parser.err(
+                "conformance_tests needs a concrete type for every type parameter, set with #[logos(type T = ...)]",
+                span,
+            );
+
+            quote!()
+        }
+        Some((span, export)) => conformance::generate(
+            name,
+            &source,
+            &kinds,
+            &graph,
+            root,
+            &rules,
+            &patterns,
+            span,
+            export.as_ref(),
+            &mut parser.errors,
+        ),
+        None => quote!(),
+    };
+
+    let keywords = match parser.keywords.take() {
+        Some(pattern) => keywords::generate(&this, &generic, pattern, literals, &mut parser.errors),
+        None => quote!(),
+    };
+
+    let owned = match parser.owned.take() {
+        Some(owned) => owned::generate(
+            name,
+            &this,
+            &generic,
+            &item.vis,
+            &owned,
+            parser.owned_derive.take(),
+            owned_fields,
+            &mut parser.errors,
+        ),
+        None => quote!(),
+    };
+
+    let kind = match parser.kind.take() {
+        Some(kind) => kind::generate(
+            name,
+            &this,
+            &generic,
+            &item.vis,
+            &kind,
+            parser.kind_derive.take(),
+            &kinds,
+        ),
+        None => quote!(),
+    };
+
+    let rule_consts = rule_consts::generate(
+        name,
+        &this,
+        &generic,
+        &rules,
+        &descriptions,
+        &unterminated_rules,
+    );
+
+    if let Some(errors) = parser.errors.render() {
+        return impl_logos(errors, quote!());
+    }
+
+    if let Some(path) = parser.determinism_report.take() {
+        let DeterminismReport { report, violations } =
+            graph.determinism_report(root, &name.to_string());
+        let mut errors = Errors::default();
+
+        if let Err(err) = export(&path.value(), report) {
+            errors.err(
+                format!("Failed to export the determinism report: {}", err),
+                path.span(),
+            );
+        }
+        for violation in violations {
+            errors.err(
+                format!(
+                    "The state machine can't be proven deterministic: {}",
+                    violation
+                ),
+                path.span(),
+            );
+        }
+
+        if let Some(errors) = errors.render() {
+            return impl_logos(errors, quote!());
+        }
+    }
+
+    // panic!("{:#?}\n\n{} nodes", graph, graph.nodes().iter().filter_map(|n| n.as_ref()).count());
+
+    let mut generator =
+        Generator::new(name, &this, &generic, root, &graph).strategy(parser.strategy);
+
+    let reparse = generator.generate_reparse(&rules);
+    let body = match parser.codegen {
+        Codegen::Fast => generator.generate(),
+        Codegen::Compact => match generator.generate_compact() {
+            Ok(body) => body,
+            Err(message) => {
+                let mut errors = Errors::default();
+
+                errors.err(message, name.span());
+
+                return impl_logos(errors.render().unwrap(), quote!());
+            }
+        },
+    };
+    let fail = match parser.recover_to.as_slice() {
+        [] => quote!(lex.error();),
+        sync => {
+            let sync = sync.iter().map(|literal| {
+                let bytes = proc_macro2::Literal::byte_string(&literal.to_bytes());
+
+                quote!(#bytes as &[u8])
+            });
+            // Recovery mustn't skip over a sentinel byte, so that it's always reported on its own.
+            let sentinels = parser.sentinels.iter().map(|byte| {
+                let bytes = proc_macro2::Literal::byte_string(&[*byte]);
+
+                quote!(#bytes as &[u8])
+            });
+
+            quote!(lex.recover(&[#(#sync,)* #(#sentinels),*]);)
+        }
+    };
+    let sentinel = match parser.sentinels.as_slice() {
+        [] => quote!(),
+        sentinels => quote! {
+            if lex.test::<u8, _>(|byte| matches!(byte, #(#sentinels)|*)) {
+                lex.bump_unchecked(1);
+                lex.sentinel();
+                return;
+            }
+        },
+    };
+    let end = match &eof {
+        Some(ident) => quote!(lex.eof(#name::#ident)),
+        None => quote!(lex.end()),
+    };
+    // Tokens rejected by `not_followed_by` back off to the longest shorter match of any other definition. Contextual
+    // definitions are left out, since they might not be enabled.
+    let back_off = match rules.iter().any(|leaf| leaf.not_followed_by.is_some()) {
+        true => {
+            let mut order: Vec<usize> = (0..rules.len())
+                .filter(|&rule| rules[rule].contextual.is_none())
+                .collect();
+
+            order.sort_by_key(|&rule| std::cmp::Reverse(rules[rule].priority));
+
+            let rules = order.into_iter().map(|rule| {
+                let program = Program::lookahead(&patterns[rule].to_mir());
+
+                quote!((#rule, #program))
+            });
+
+            quote! {
+                static __LOGOS_BACK_OFF: &[(usize, ::logos::internal::CaptureProgram)] = &[#(#rules),*];
+            }
+        }
+        false => quote!(),
+    };
+    let prelude = prelude(&this, &generic, back_off);
+    let mut items = quote! {
+        fn reparse(lex: &mut ::logos::Lexer<'s, Self>, __logos_rule: usize) {
+            #prelude
+
+            #reparse
+        }
+    };
+
+    for (hook, callback) in std::mem::take(&mut parser.hooks) {
+        let body = match callback {
+            Callback::Label(label) => quote!(#label(lex);),
+            Callback::Inline(inline) => {
+                let InlineCallback { arg, body, .. } = *inline;
+
+                quote! {
+                    let #arg = lex;
+                    #body;
+                }
+            }
+        };
+
+        items.extend(quote! {
+            #[inline]
+            fn #hook(lex: &mut ::logos::Lexer<'s, Self>) {
+                #body
+            }
+        });
+    }
+
+    if parser.no_panic_error {
+        items.extend(quote! {
+            const NO_PANIC_ERROR: bool = ::logos::internal::no_panic_error::<'s, Self>();
+        });
+    }
+
+    if parser.lifecycle {
+        items.extend(quote! {
+            #[inline]
+            fn on_start(lex: &mut ::logos::Lexer<'s, Self>) {
+                let source = lex.source();
+
+                ::logos::Lifecycle::on_start(&mut lex.extras, source);
+            }
+
+            #[inline]
+            fn on_finish(lex: &mut ::logos::Lexer<'s, Self>) {
+                ::logos::Lifecycle::on_eof(&mut lex.extras);
+            }
+        });
+    }
+
+    if !contextual.is_empty() {
+        let bits = contextual.iter().enumerate().map(|(bit, ident)| {
+            let bit = bit as u32;
+
+            quote!(#name::#ident { .. } => ::core::option::Option::Some(#bit),)
+        });
+
+        items.extend(quote! {
+            #[allow(unreachable_patterns)]
+            fn contextual_bit(&self) -> ::core::option::Option<u32> {
+                match self {
+                    #(#bits)*
+                    _ => ::core::option::Option::None,
+                }
+            }
+        });
+    }
+    let handlers = handlers(&generic, end, fail, invalid_utf8);
+    let mut tokens = impl_logos(
+        quote! {
+            #prelude
+            #handlers
+            #sentinel
+            #body
+        },
+        items,
+    );
+
+    if parser.sniff {
+        tokens.extend(sniff::generate(&this, &generic, &source, &rules, &patterns));
+    }
+
+    if parser.test_match && !rules.is_empty() {
+        let test_rules = rules.into_iter().zip(patterns).collect();
+
+        tokens.extend(test_match::generate(&this, &generic, &source, test_rules));
+    }
+
+    if parser.display {
+        tokens.extend(display::generate(name, &this, &generic, display_names));
+    }
+
+    tokens.extend(keywords);
+    tokens.extend(owned);
+    tokens.extend(kind);
+    tokens.extend(rule_consts);
+    tokens.extend(corpus_test);
+    tokens.extend(conformance_tests);
+
+    if let Some(path) = parser.stats.take() {
+        let stats = Stats {
+            variants: kind_count,
+            tokens: Stats::count_tokens(tokens.clone()),
+            ..generator.stats()
+        };
+        let report = format!("Stats for the lexer of `{}`:\n{}", name, stats);
+
+        match path {
+            None => eprint!("{}", report),
+            Some(path) => {
+                if let Err(err) = export(&path.value(), report) {
+                    let mut errors = Errors::default();
+
+                    errors.err(format!("Failed to export the stats: {}", err), path.span());
+
+                    if let Some(errors) = errors.render() {
+                        return impl_logos(errors, quote!());
+                    }
+                }
+            }
+        }
+    }
+
+    // panic!("{}", tokens);
+
+    tokens
+}
+
+/// The items that every generated `lex` and `reparse` body starts with. Every item in here is visible to callbacks, so
+/// all of them are prefixed to avoid shadowing user code.
+fn prelude(this: &TokenStream, generic: &Generic, back_off: TokenStream) -> TokenStream {
+    let params = generic.params();
+
+    quote! {
+        use ::logos::internal::LexerInternal as _;
+
+        type __LogosLexer #params = ::logos::Lexer<'s, #this>;
+
+        #back_off
+    }
+}
+
+/// The functions that the generated state machine calls at the end of the input, when nothing matches, and on a byte
+/// that no definition can start with.
+fn handlers(
+    generic: &Generic,
+    end: TokenStream,
+    fail: TokenStream,
+    invalid_utf8: TokenStream,
+) -> TokenStream {
+    let params = generic.params();
+    let lexer = generic.lexer();
+    let where_clause = generic.where_clause();
+
+    quote! {
+        fn __logos_end #params (lex: &mut #lexer) #where_clause {
+            #end
+        }
+
+        #[inline]
+        fn __logos_fail #params (lex: &mut #lexer) #where_clause {
+            #fail
+        }
+
+        fn __logos_error #params (lex: &mut #lexer) #where_clause {
+            #invalid_utf8
+
+            lex.bump_unchecked(1);
+
+            __logos_fail(lex);
+        }
+    }
+}
+
+/// Merge the entry points of all definitions into `root`.
+fn merge_root<'t>(
+    root: &mut Fork,
+    graph: &mut Graph<Leaf<'t>>,
+    regex_ids: Vec<graph::NodeId>,
+    ropes: Vec<Rope>,
+) {
+    for id in regex_ids {
+        let fork = graph.fork_off(id);
+
+        root.merge(fork, graph);
+    }
+    for rope in ropes {
+        root.merge(rope.into_fork(graph), graph);
+    }
+    while let Some(id) = root.miss.take() {
+        let fork = graph.fork_off(id);
+
+        if fork.branches().next().is_some() {
+            root.merge(fork, graph);
+        } else {
+            break;
+        }
+    }
+}
+
+/// Write `contents` (like a rendered DOT graph) to `path`, which is relative to the directory
+/// containing the manifest of the crate being compiled.
+fn export(path: &str, contents: String) -> std::io::Result<()> {
+    let mut target = match std::env::var_os("CARGO_MANIFEST_DIR") {
+        Some(dir) => std::path::PathBuf::from(dir),
+        None => std::path::PathBuf::new(),
+    };
+
+    target.push(path);
+
+    if let Some(parent) = target.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    std::fs::write(target, contents)
+}
+
+/// Show an input in an error message, as a string if it's valid UTF-8 and as a byte string otherwise.
+fn describe_input(input: &[u8]) -> String {
+    match std::str::from_utf8(input) {
+        Ok(input) => format!("{input:?}"),
+        Err(_) => format!("b\"{}\"", input.escape_ascii()),
+    }
+}
@@ -0,0 +1,769 @@
+use std::convert::TryFrom;
+
+use regex_syntax::hir::{
+    Anchor, GroupKind, Hir, HirKind, RepetitionKind, RepetitionRange, WordBoundary,
+};
+use regex_syntax::ParserBuilder;
+
+pub use regex_syntax::hir::{Class, ClassUnicode, Literal};
+use regex_syntax::hir::{ClassBytes, ClassBytesRange, ClassUnicodeRange};
+
+use crate::case_fold;
+use crate::error::{Error, Result};
+
+/// The most nodes that a counted repetition like `x{2,4}` can expand to, so that large counts can't blow up the size
+/// of the state machine.
+const MAX_REPETITION_SIZE: usize = 1000;
+
+/// Middle Intermediate Representation of the regex, built from
+/// `regex_syntax`'s `Hir`. The goal here is to strip and canonicalize
+/// the tree, so that we don't have to do transformations later on the
+/// graph, with the potential of running into looping references.
+#[derive(Clone, Debug)]
+pub enum Mir {
+    Empty,
+    Loop(Box<Mir>),
+    Maybe(Box<Mir>),
+    Concat(Vec<Mir>),
+    Alternation(Vec<Mir>),
+    Class(Class),
+    Literal(Literal),
+    /// A capture group, with its index and optional name.
+    Capture(u32, Option<String>, Box<Mir>),
+    /// A zero-width assertion, which is only supported at the start or end of a pattern. These are removed with
+    /// [Mir::take_assertions] before the regex is turned into a graph.
+    Assertion(Assertion),
+}
+
+/// A word boundary or anchor.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Assertion {
+    StartText,
+    EndText,
+    StartLine,
+    EndLine,
+    WordBoundary,
+    NotWordBoundary,
+    AsciiWordBoundary,
+    AsciiNotWordBoundary,
+}
+
+/// The assertions found at the start and end of a pattern, which are checked at the start and end of a token.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Assertions {
+    pub start: Vec<Assertion>,
+    pub end: Vec<Assertion>,
+}
+
+impl Assertions {
+    pub fn is_empty(&self) -> bool {
+        self.start.is_empty() && self.end.is_empty()
+    }
+}
+
+impl Mir {
+    pub fn utf8(source: &str) -> Result<Mir> {
+        Mir::try_from(ParserBuilder::new().build().parse(source)?)
+    }
+
+    pub fn utf8_ignore_case(source: &str) -> Result<Mir> {
+        Mir::try_from(
+            ParserBuilder::new()
+                .case_insensitive(true)
+                .build()
+                .parse(source)?,
+        )
+        .map(case_fold::full)
+    }
+
+    pub fn binary(source: &str) -> Result<Mir> {
+        Mir::try_from(
+            ParserBuilder::new()
+                .allow_invalid_utf8(true)
+                .unicode(false)
+                .build()
+                .parse(source)?,
+        )
+    }
+
+    pub fn binary_ignore_case(source: &str) -> Result<Mir> {
+        Mir::try_from(
+            ParserBuilder::new()
+                .allow_invalid_utf8(true)
+                .unicode(false)
+                .case_insensitive(true)
+                .build()
+                .parse(source)?,
+        )
+    }
+
+    /// Classes and `.` match bytes, like in [Mir::binary], but the pattern mustn't match part of a character, since the
+    /// source is a `&str`. In practice that means they only match ASCII.
+    pub fn ascii(source: &str) -> Result<Mir> {
+        ParserBuilder::new()
+            .unicode(false)
+            .build()
+            .parse(source)
+            .map_err(byte_semantics_error)
+            .and_then(Mir::try_from)
+            .and_then(Mir::only_ascii)
+    }
+
+    pub fn ascii_ignore_case(source: &str) -> Result<Mir> {
+        ParserBuilder::new()
+            .unicode(false)
+            .case_insensitive(true)
+            .build()
+            .parse(source)
+            .map_err(byte_semantics_error)
+            .and_then(Mir::try_from)
+            .and_then(Mir::only_ascii)
+    }
+
+    /// Check that a pattern over bytes only matches whole characters of a `&str`. `regex_syntax` catches most patterns
+    /// that don't, but not all of them, like `(?-u)\W`.
+    fn only_ascii(self) -> Result<Mir> {
+        fn check(mir: &Mir) -> bool {
+            match mir {
+                Mir::Loop(mir) | Mir::Maybe(mir) | Mir::Capture(_, _, mir) => check(mir),
+                Mir::Concat(mirs) | Mir::Alternation(mirs) => mirs.iter().all(check),
+                Mir::Class(Class::Bytes(class)) => {
+                    class.ranges().iter().all(|range| range.end().is_ascii())
+                }
+                Mir::Literal(Literal::Byte(byte)) => byte.is_ascii(),
+                _ => true,
+            }
+        }
+
+        match check(&self) {
+            true => Ok(self),
+            false => Err(splits_characters()),
+        }
+    }
+
+    /// Split every class that has both ASCII and other characters in it, so that the other characters are always
+    /// matched as whole UTF-8 sequences. Otherwise a class that reaches to the end of Unicode, like `[^a]` or `.`, is
+    /// matched a byte at a time, which is quicker in loops but can stop in the middle of a character.
+    pub fn whole_characters(self) -> Mir {
+        match self {
+            Mir::Loop(mir) => Mir::Loop(Box::new(mir.whole_characters())),
+            Mir::Maybe(mir) => Mir::Maybe(Box::new(mir.whole_characters())),
+            Mir::Capture(index, name, mir) => {
+                Mir::Capture(index, name, Box::new(mir.whole_characters()))
+            }
+            Mir::Concat(concat) => {
+                Mir::Concat(concat.into_iter().map(Mir::whole_characters).collect())
+            }
+            Mir::Alternation(alternation) => {
+                Mir::Alternation(alternation.into_iter().map(Mir::whole_characters).collect())
+            }
+            Mir::Class(Class::Unicode(class)) => {
+                let mut ascii = class.clone();
+                let mut other = class.clone();
+
+                ascii.intersect(&ClassUnicode::new([ClassUnicodeRange::new('\0', '\x7F')]));
+                other.difference(&ascii);
+
+                match ascii.ranges().is_empty() || other.ranges().is_empty() {
+                    true => Mir::Class(Class::Unicode(class)),
+                    false => Mir::Alternation(vec![
+                        Mir::Class(Class::Unicode(ascii)),
+                        Mir::Class(Class::Unicode(other)),
+                    ]),
+                }
+            }
+            mir => mir,
+        }
+    }
+
+    /// The literals of an alternation that only has literals in it, like `US|GB|DE`, or `None` for any other regex.
+    pub fn literals(&self) -> Option<Vec<Vec<u8>>> {
+        fn literal(mir: &Mir, out: &mut Vec<u8>) -> bool {
+            match mir {
+                Mir::Literal(Literal::Unicode(ch)) => {
+                    out.extend_from_slice(ch.encode_utf8(&mut [0; 4]).as_bytes());
+                    true
+                }
+                Mir::Literal(Literal::Byte(byte)) => {
+                    out.push(*byte);
+                    true
+                }
+                Mir::Concat(concat) => concat.iter().all(|mir| literal(mir, out)),
+                _ => false,
+            }
+        }
+
+        match self {
+            Mir::Alternation(alternation) => alternation
+                .iter()
+                .map(|mir| {
+                    let mut out = Vec::new();
+
+                    match literal(mir, &mut out) && !out.is_empty() {
+                        true => Some(out),
+                        false => None,
+                    }
+                })
+                .collect(),
+            _ => None,
+        }
+    }
+
+    pub fn priority(&self) -> usize {
+        match self {
+            Mir::Empty | Mir::Loop(_) | Mir::Maybe(_) => 0,
+            Mir::Concat(concat) => concat.iter().map(Mir::priority).sum(),
+            Mir::Alternation(alt) => alt.iter().map(Mir::priority).min().unwrap_or(0),
+            Mir::Class(_) => 1,
+            Mir::Literal(_) => 2,
+            Mir::Capture(_, _, mir) => mir.priority(),
+            Mir::Assertion(_) => 0,
+        }
+    }
+
+    /// Expand a counted repetition like `x{2,4}` to copies of `x`: the required ones first, followed by nested
+    /// optional ones (`xx(x(x)?)?`) so that there's only one way to match each number of copies.
+    fn repeat(mir: Mir, range: RepetitionRange) -> Result<Mir> {
+        let (min, max) = match range {
+            RepetitionRange::Exactly(count) => (count, Some(count)),
+            RepetitionRange::AtLeast(min) => (min, None),
+            RepetitionRange::Bounded(min, max) => (min, Some(max)),
+        };
+        let copies = max.unwrap_or(min).max(1) as usize;
+
+        if copies.saturating_mul(mir.size()) > MAX_REPETITION_SIZE {
+            return Err(format!(
+                "#[regex]: a counted repetition can expand to at most {} parts of a pattern, but this one expands \
+                to {} copies of a pattern with {} parts. Try a smaller count, or a callback.",
+                MAX_REPETITION_SIZE,
+                copies,
+                mir.size()
+            )
+            .into());
+        }
+
+        let optional = match max {
+            Some(max) => (min..max).fold(Mir::Empty, |rest, _| {
+                Mir::Maybe(Box::new(match rest {
+                    Mir::Empty => mir.clone(),
+                    rest => Mir::Concat(vec![mir.clone(), rest]),
+                }))
+            }),
+            None => Mir::Loop(Box::new(mir.clone())),
+        };
+        let mut concat: Vec<Mir> = (0..min).map(|_| mir.clone()).collect();
+
+        if !matches!(optional, Mir::Empty) {
+            concat.push(optional);
+        }
+
+        Ok(match concat.len() {
+            0 => Mir::Empty,
+            1 => concat.pop().unwrap(),
+            _ => Mir::Concat(concat),
+        })
+    }
+
+    /// The number of nodes in the pattern, which limits how many times it can be copied by a counted repetition.
+    fn size(&self) -> usize {
+        match self {
+            Mir::Empty | Mir::Class(_) | Mir::Literal(_) | Mir::Assertion(_) => 1,
+            Mir::Loop(mir) | Mir::Maybe(mir) | Mir::Capture(_, _, mir) => 1 + mir.size(),
+            Mir::Concat(mirs) | Mir::Alternation(mirs) => {
+                1 + mirs.iter().map(Mir::size).sum::<usize>()
+            }
+        }
+    }
+
+    /// The inputs that start with the pattern's opening, but end before the rest of the pattern is matched, for
+    /// `unterminated`. The opening is the literal that the pattern starts with, or its first part if it doesn't start
+    /// with one. Returns `None` if there's nothing after the opening.
+    pub fn unterminated(&self) -> Option<Mir> {
+        let items = match self {
+            Mir::Concat(items) => items.as_slice(),
+            mir => std::slice::from_ref(mir),
+        };
+        let opening = match items
+            .iter()
+            .take_while(|mir| matches!(mir, Mir::Literal(_)))
+            .count()
+        {
+            0 => 1,
+            literal => literal,
+        };
+
+        if opening >= items.len() {
+            return None;
+        }
+
+        let mut out = items[..opening].to_vec();
+
+        out.push(Mir::Concat(items[opening..].to_vec()).prefixes());
+
+        Some(Mir::Concat(out))
+    }
+
+    /// Every prefix of the inputs that the pattern matches, including the empty one and the whole input.
+    fn prefixes(&self) -> Mir {
+        match self {
+            Mir::Empty | Mir::Assertion(_) => Mir::Empty,
+            Mir::Literal(_) | Mir::Class(_) => Mir::Maybe(Box::new(self.clone())),
+            Mir::Maybe(mir) | Mir::Capture(_, _, mir) => mir.prefixes(),
+            Mir::Loop(mir) => Mir::Concat(vec![self.clone(), mir.prefixes()]),
+            Mir::Alternation(alternation) => {
+                Mir::Alternation(alternation.iter().map(Mir::prefixes).collect())
+            }
+            Mir::Concat(concat) => Mir::Alternation(
+                (0..concat.len())
+                    .map(|at| {
+                        let mut out = concat[..at].to_vec();
+
+                        out.push(concat[at].prefixes());
+
+                        Mir::Concat(out)
+                    })
+                    .collect(),
+            ),
+        }
+    }
+
+    /// Remove the assertions at the start and end of the pattern, failing if there are any others.
+    pub fn take_assertions(&mut self) -> Result<Assertions> {
+        let mut assertions = Assertions::default();
+
+        match self {
+            Mir::Assertion(assertion) => {
+                assertions.start.push(*assertion);
+                *self = Mir::Empty;
+            }
+            Mir::Concat(concat) => {
+                while let Some(Mir::Assertion(assertion)) = concat.last() {
+                    assertions.end.insert(0, *assertion);
+                    concat.pop();
+                }
+
+                let start = concat
+                    .iter()
+                    .take_while(|mir| matches!(mir, Mir::Assertion(_)))
+                    .count();
+
+                for mir in concat.drain(..start) {
+                    if let Mir::Assertion(assertion) = mir {
+                        assertions.start.push(assertion);
+                    }
+                }
+            }
+            _ => (),
+        }
+
+        if self.has_assertions() {
+            return Err(
+                "#[regex]: word boundaries and anchors are only supported at the start or end of a pattern.".into(),
+            );
+        }
+
+        Ok(assertions)
+    }
+
+    /// Remove `bytes` from everything the regex can match, so that none of them can ever be part of a token.
+    pub fn exclude_bytes(self, bytes: &[u8]) -> Result<Mir> {
+        if bytes.is_empty() {
+            return Ok(self);
+        }
+
+        let exclude = |mir: Mir| mir.exclude_bytes(bytes);
+
+        Ok(match self {
+            Mir::Empty | Mir::Assertion(_) => self,
+            Mir::Loop(mir) => Mir::Loop(Box::new(exclude(*mir)?)),
+            Mir::Maybe(mir) => Mir::Maybe(Box::new(exclude(*mir)?)),
+            Mir::Capture(index, name, mir) => Mir::Capture(index, name, Box::new(exclude(*mir)?)),
+            Mir::Concat(concat) => {
+                Mir::Concat(concat.into_iter().map(exclude).collect::<Result<_>>()?)
+            }
+            Mir::Alternation(alternation) => Mir::Alternation(
+                alternation
+                    .into_iter()
+                    .map(exclude)
+                    .collect::<Result<_>>()?,
+            ),
+            Mir::Literal(literal) => {
+                let mut buf = [0; 4];
+                let encoded = match &literal {
+                    Literal::Unicode(unicode) => unicode.encode_utf8(&mut buf).as_bytes(),
+                    Literal::Byte(byte) => std::slice::from_ref(byte),
+                };
+
+                match encoded.iter().any(|byte| bytes.contains(byte)) {
+                    // An empty class never matches anything.
+                    true => Mir::Class(Class::Bytes(ClassBytes::empty())),
+                    false => Mir::Literal(literal),
+                }
+            }
+            Mir::Class(Class::Bytes(mut class)) => {
+                class.difference(&ClassBytes::new(
+                    bytes.iter().map(|&byte| ClassBytesRange::new(byte, byte)),
+                ));
+
+                Mir::Class(Class::Bytes(class))
+            }
+            Mir::Class(Class::Unicode(mut class)) => {
+                // Bytes that never appear in UTF-8 don't need to be removed, but the rest of the non-ASCII bytes
+                // are part of far too many characters to cut them out of a class.
+                let tangled = bytes
+                    .iter()
+                    .any(|&byte| matches!(byte, 0x80..=0xBF | 0xC2..=0xF4));
+
+                if tangled && class.iter().any(|range| range.end() > '\x7F') {
+                    return Err(
+                        "#[regex]: sentinel bytes in the range 0x80..=0xF4 can't be excluded from a class of Unicode \
+                        characters.\n\nhint: Use an ASCII class, or disable Unicode for the class with (?-u)."
+                            .into(),
+                    );
+                }
+
+                class.difference(&ClassUnicode::new(
+                    bytes
+                        .iter()
+                        .filter(|byte| byte.is_ascii())
+                        .map(|&byte| ClassUnicodeRange::new(byte as char, byte as char)),
+                ));
+
+                Mir::Class(Class::Unicode(class))
+            }
+        })
+    }
+
+    fn has_assertions(&self) -> bool {
+        match self {
+            Mir::Empty | Mir::Class(_) | Mir::Literal(_) => false,
+            Mir::Loop(mir) | Mir::Maybe(mir) | Mir::Capture(_, _, mir) => mir.has_assertions(),
+            Mir::Concat(mirs) | Mir::Alternation(mirs) => mirs.iter().any(Mir::has_assertions),
+            Mir::Assertion(_) => true,
+        }
+    }
+}
+
+/// Translate the errors `regex_syntax` gives for patterns over bytes that can match part of a character.
+fn byte_semantics_error(err: regex_syntax::Error) -> Error {
+    use regex_syntax::hir::ErrorKind;
+
+    match &err {
+        regex_syntax::Error::Translate(translate) => match translate.kind() {
+            ErrorKind::InvalidUtf8 => splits_characters(),
+            ErrorKind::UnicodeNotAllowed => Error::new(
+                "unicode = false: only ASCII can be written in the pattern, since its classes match bytes.",
+            ),
+            _ => err.into(),
+        },
+        _ => err.into(),
+    }
+}
+
+/// The error for a pattern over the bytes of a `&str` that can match part of a character.
+fn splits_characters() -> Error {
+    Error::new(
+        "unicode = false: this pattern can match part of a character, which would split a character in a string. \
+        Narrow it down to ASCII, like [\\x00-`b-\\x7F] instead of [^a], or use a byte string pattern.",
+    )
+}
+
+impl TryFrom<Hir> for Mir {
+    type Error = Error;
+
+    fn try_from(hir: Hir) -> Result<Mir> {
+        match hir.into_kind() {
+            HirKind::Empty => Ok(Mir::Empty),
+            HirKind::Concat(concat) => {
+                let mut out = Vec::with_capacity(concat.len());
+
+                fn extend(mir: Mir, out: &mut Vec<Mir>) {
+                    match mir {
+                        Mir::Concat(nested) => {
+                            for child in nested {
+                                extend(child, out);
+                            }
+                        }
+                        mir => out.push(mir),
+                    }
+                }
+
+                for hir in concat {
+                    extend(Mir::try_from(hir)?, &mut out);
+                }
+
+                Ok(Mir::Concat(out))
+            }
+            HirKind::Alternation(alternation) => {
+                let alternation = alternation
+                    .into_iter()
+                    .map(Mir::try_from)
+                    .collect::<Result<_>>()?;
+
+                Ok(Mir::Alternation(alternation))
+            }
+            HirKind::Literal(literal) => Ok(Mir::Literal(literal)),
+            HirKind::Class(class) => Ok(Mir::Class(class)),
+            HirKind::Repetition(repetition) => {
+                if !repetition.greedy {
+                    return Err("#[regex]: non-greedy parsing is currently unsupported.".into());
+                }
+
+                let kind = repetition.kind;
+                let mir = Mir::try_from(*repetition.hir)?;
+
+                match kind {
+                    RepetitionKind::ZeroOrOne => Ok(Mir::Maybe(Box::new(mir))),
+                    RepetitionKind::ZeroOrMore => Ok(Mir::Loop(Box::new(mir))),
+                    RepetitionKind::OneOrMore => {
+                        Ok(Mir::Concat(vec![mir.clone(), Mir::Loop(Box::new(mir))]))
+                    }
+                    RepetitionKind::Range(range) => Mir::repeat(mir, range),
+                }
+            }
+            HirKind::Group(group) => {
+                let mir = Mir::try_from(*group.hir)?;
+
+                match group.kind {
+                    GroupKind::CaptureIndex(index) => Ok(Mir::Capture(index, None, Box::new(mir))),
+                    GroupKind::CaptureName { name, index } => {
+                        Ok(Mir::Capture(index, Some(name), Box::new(mir)))
+                    }
+                    GroupKind::NonCapturing => Ok(mir),
+                }
+            }
+            HirKind::WordBoundary(boundary) => Ok(Mir::Assertion(match boundary {
+                WordBoundary::Unicode => Assertion::WordBoundary,
+                WordBoundary::UnicodeNegate => Assertion::NotWordBoundary,
+                WordBoundary::Ascii => Assertion::AsciiWordBoundary,
+                WordBoundary::AsciiNegate => Assertion::AsciiNotWordBoundary,
+            })),
+            HirKind::Anchor(anchor) => Ok(Mir::Assertion(match anchor {
+                Anchor::StartText => Assertion::StartText,
+                Anchor::EndText => Assertion::EndText,
+                Anchor::StartLine => Assertion::StartLine,
+                Anchor::EndLine => Assertion::EndLine,
+            })),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Assertion, Class, Mir};
+
+    #[test]
+    fn priorities() {
+        let regexes = [
+            ("[a-z]+", 1),
+            ("a|b", 2),
+            ("a|[b-z]", 1),
+            ("(foo)+", 6),
+            ("foobar", 12),
+            ("(fooz|bar)+qux", 12),
+        ];
+
+        for (regex, expected) in regexes.iter() {
+            let mir = Mir::utf8(regex).unwrap();
+            assert_eq!(mir.priority(), *expected);
+        }
+    }
+
+    #[test]
+    fn counted_repetitions() {
+        let longest = |regex: &str, input: &str| {
+            crate::captures::Program::lookahead(&Mir::utf8(regex).unwrap())
+                .longest_match(input.as_bytes())
+        };
+
+        assert_eq!(longest("[0-9]{1,3}", "12345"), Some(3));
+        assert_eq!(longest("[0-9]{2,3}", "1"), None);
+        assert_eq!(longest("a{3}", "aaaa"), Some(3));
+        assert_eq!(longest("a{2,}", "aaaa"), Some(4));
+        assert_eq!(longest("(ab){0,2}c", "ababc"), Some(5));
+        assert_eq!(longest("x{0}y", "y"), Some(1));
+        assert_eq!(Mir::utf8("[0-9]{1,3}").unwrap().priority(), 1);
+        assert_eq!(Mir::utf8("a{3}").unwrap().priority(), 6);
+    }
+
+    #[test]
+    fn counted_repetition_limit() {
+        assert!(Mir::utf8("a{1000}").is_ok());
+        assert!(Mir::utf8("(ab){1000}").is_err());
+        assert!(Mir::utf8("(a{100}){100}").is_err());
+    }
+
+    #[test]
+    fn assertions() {
+        let mut mir = Mir::utf8(r"^\bif\b$").unwrap();
+        let assertions = mir.take_assertions().unwrap();
+
+        assert_eq!(
+            assertions.start,
+            &[Assertion::StartText, Assertion::WordBoundary]
+        );
+        assert_eq!(
+            assertions.end,
+            &[Assertion::WordBoundary, Assertion::EndText]
+        );
+        assert_eq!(mir.priority(), 4);
+
+        let mut mir = Mir::binary(r"(?m)[0-9]+\B$").unwrap();
+        let assertions = mir.take_assertions().unwrap();
+
+        assert!(assertions.start.is_empty());
+        assert_eq!(
+            assertions.end,
+            &[Assertion::AsciiNotWordBoundary, Assertion::EndLine]
+        );
+
+        for regex in [r"a\bb", r"\ba|b", r"(\ba)+"] {
+            assert!(Mir::utf8(regex).unwrap().take_assertions().is_err());
+        }
+    }
+
+    #[test]
+    fn unterminated() {
+        let matches = |mir: &Mir, input: &str| {
+            crate::captures::Program::lookahead(mir).longest_match(input.as_bytes())
+                == Some(input.len())
+        };
+        let mir = Mir::utf8(r"/\*([^*]|\*[^/])*\*/")
+            .unwrap()
+            .unterminated()
+            .unwrap();
+
+        for input in ["/*", "/* abc", "/* a * b *", "/* a */"] {
+            assert!(matches(&mir, input), "{}", input);
+        }
+        for input in ["/", "", "/* a */ b"] {
+            assert!(!matches(&mir, input), "{}", input);
+        }
+
+        let mir = Mir::utf8(r#""([^"\\]|\\.)*""#)
+            .unwrap()
+            .unterminated()
+            .unwrap();
+
+        for input in [r#"""#, r#""abc"#, r#""a\"#, r#""a\"b"#] {
+            assert!(matches(&mir, input), "{}", input);
+        }
+
+        for regex in ["abc", "[a-z]", "a|b"] {
+            assert!(
+                Mir::utf8(regex).unwrap().unterminated().is_none(),
+                "{}",
+                regex
+            );
+        }
+    }
+
+    #[test]
+    fn literals() {
+        let literals = Mir::utf8("US|GB|ü|x").unwrap().literals();
+
+        assert_eq!(
+            literals,
+            Some(vec![
+                b"US".to_vec(),
+                b"GB".to_vec(),
+                "ü".as_bytes().to_vec(),
+                b"x".to_vec()
+            ])
+        );
+
+        for regex in ["US|G+", "US", "(?i)US|GB", "US|"] {
+            assert_eq!(Mir::utf8(regex).unwrap().literals(), None, "{}", regex);
+        }
+    }
+
+    #[test]
+    fn excluded_bytes() {
+        let mir = Mir::utf8(r"[^a]|b\x00")
+            .unwrap()
+            .exclude_bytes(b"\x00\xFF")
+            .unwrap();
+
+        match mir {
+            Mir::Alternation(alternation) => {
+                match &alternation[0] {
+                    Mir::Class(Class::Unicode(class)) => {
+                        assert_eq!(class.ranges()[0].start(), '\x01');
+                    }
+                    mir => panic!("unexpected {:?}", mir),
+                }
+                match &alternation[1] {
+                    Mir::Concat(concat) => match &concat[1] {
+                        Mir::Class(Class::Bytes(class)) => assert!(class.ranges().is_empty()),
+                        mir => panic!("unexpected {:?}", mir),
+                    },
+                    mir => panic!("unexpected {:?}", mir),
+                }
+            }
+            mir => panic!("unexpected {:?}", mir),
+        }
+
+        let mir = Mir::binary(r"[\x00-\xFF]")
+            .unwrap()
+            .exclude_bytes(b"\x00\xFF")
+            .unwrap();
+
+        match mir {
+            Mir::Class(Class::Bytes(class)) => {
+                assert_eq!(class.ranges().len(), 1);
+                assert_eq!(class.ranges()[0].start(), 0x01);
+                assert_eq!(class.ranges()[0].end(), 0xFE);
+            }
+            mir => panic!("unexpected {:?}", mir),
+        }
+
+        assert!(Mir::utf8(".").unwrap().exclude_bytes(b"\xA0").is_err());
+        assert!(Mir::utf8("[a-z]").unwrap().exclude_bytes(b"\xA0").is_ok());
+    }
+
+    #[test]
+    fn whole_characters() {
+        match Mir::utf8("[^a]").unwrap().whole_characters() {
+            Mir::Alternation(alternation) => match &alternation[..] {
+                [Mir::Class(Class::Unicode(ascii)), Mir::Class(Class::Unicode(other))] => {
+                    assert_eq!(ascii.ranges().last().unwrap().end(), '\x7F');
+                    assert_eq!(other.ranges()[0].start(), '\u{80}');
+                }
+                alternation => panic!("expected two classes, found {:?}", alternation),
+            },
+            mir => panic!("expected an alternation, found {:?}", mir),
+        }
+
+        assert!(matches!(
+            Mir::utf8("[a-z]").unwrap().whole_characters(),
+            Mir::Class(_)
+        ));
+    }
+
+    #[test]
+    fn ascii() {
+        for regex in [r"\w+", r"[\x00-`b-\x7F]", r"[[:alpha:]]", "(?i)k"] {
+            assert!(Mir::ascii(regex).is_ok(), "{}", regex);
+        }
+        for regex in ["[^a]", ".", r"\W", r"\xFF"] {
+            let err = Mir::ascii(regex).expect_err(regex).to_string();
+
+            assert!(err.contains("split a character"), "{}: {}", regex, err);
+        }
+        assert!(Mir::ascii("é")
+            .err()
+            .unwrap()
+            .to_string()
+            .contains("only ASCII"));
+        assert!(!Mir::ascii("(a")
+            .err()
+            .unwrap()
+            .to_string()
+            .contains("unicode = false"));
+
+        match Mir::ascii(r"\w").unwrap() {
+            Mir::Class(Class::Bytes(class)) => assert_eq!(class.ranges().len(), 4),
+            mir => panic!("expected a class of bytes, found {:?}", mir),
+        }
+    }
+}
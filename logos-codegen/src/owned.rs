@@ -0,0 +1,181 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::spanned::Spanned;
+use syn::{Ident, Type, Visibility};
+
+use crate::error::Errors;
+use crate::parser::Generic;
+
+/// How the field of a variant is turned into the field of its owned counterpart.
+enum Payload {
+    /// `&str` becomes a `String`.
+    Str,
+    /// `&[u8]` becomes a `Vec<u8>`.
+    Bytes,
+    /// A `SliceView` (or `Cow`) of a slice becomes the owned version of the slice.
+    View(TokenStream),
+    /// Anything that doesn't borrow from the source is cloned.
+    Cloned(TokenStream),
+}
+
+impl Payload {
+    fn new(ty: &Type, errors: &mut Errors) -> Option<Payload> {
+        if let Type::Reference(reference) = ty {
+            if reference.mutability.is_none() {
+                match &*reference.elem {
+                    Type::Path(path) if path.path.is_ident("str") => return Some(Payload::Str),
+                    Type::Slice(slice) if matches!(&*slice.elem, Type::Path(path) if path.path.is_ident("u8")) =>
+                    {
+                        return Some(Payload::Bytes);
+                    }
+                    _ => (),
+                }
+            }
+        }
+
+        // The slice in a view can't borrow from the source any further, so it's handled by the check below.
+        let elem = view_elem(ty);
+        let owned = match &elem {
+            Some(Type::Path(path)) if path.path.is_ident("str") => {
+                Some(quote!(::std::string::String))
+            }
+            Some(Type::Slice(slice)) if matches!(&*slice.elem, Type::Path(path) if path.path.is_ident("u8")) => {
+                Some(quote!(::std::vec::Vec<u8>))
+            }
+            Some(elem) => Some(quote!(<#elem as ::std::borrow::ToOwned>::Owned)),
+            None => None,
+        };
+        let ty = elem.as_ref().unwrap_or(ty);
+
+        // Field types have had their lifetimes replaced with `'s` by now.
+        if quote!(#ty).to_string().contains("'s") {
+            errors.err(
+                "Only &str, &[u8] and SliceView fields can be converted to an owned type, other fields can't borrow from \
+                 the source",
+                ty.span(),
+            );
+
+            return None;
+        }
+
+        Some(match owned {
+            Some(owned) => Payload::View(owned),
+            None => Payload::Cloned(quote!(#ty)),
+        })
+    }
+
+    fn ty(&self) -> TokenStream {
+        match self {
+            Payload::Str => quote!(::std::string::String),
+            Payload::Bytes => quote!(::std::vec::Vec<u8>),
+            Payload::View(owned) | Payload::Cloned(owned) => owned.clone(),
+        }
+    }
+
+    fn convert(&self) -> TokenStream {
+        match self {
+            Payload::Str | Payload::Bytes => quote!(::std::borrow::ToOwned::to_owned(*field)),
+            Payload::View(_) => quote!(::std::borrow::ToOwned::to_owned(&**field)),
+            Payload::Cloned(_) => quote!(::core::clone::Clone::clone(field)),
+        }
+    }
+}
+
+/// The type of slice in a `SliceView<'s, T>` or `Cow<'s, T>` field, where a `SliceView` is a slice of `str` by default.
+fn view_elem(ty: &Type) -> Option<Type> {
+    let segment = match ty {
+        Type::Path(path) if path.qself.is_none() => path.path.segments.last()?,
+        _ => return None,
+    };
+    let args = match &segment.arguments {
+        syn::PathArguments::AngleBracketed(args) => &args.args,
+        _ => return None,
+    };
+    let mut types = args.iter().filter_map(|arg| match arg {
+        syn::GenericArgument::Type(ty) => Some(ty.clone()),
+        _ => None,
+    });
+
+    match segment.ident.to_string().as_str() {
+        "SliceView" => Some(types.next().unwrap_or_else(|| syn::parse_quote!(str))),
+        "Cow" => types.next(),
+        _ => None,
+    }
+}
+
+/// Generate an enum called `owned` that mirrors the token type without borrowing from the source, and a `to_owned`
+/// method that converts tokens to it.
+#[allow(clippy::too_many_arguments)]
+pub fn generate(
+    name: &Ident,
+    this: &TokenStream,
+    generic: &Generic,
+    vis: &Visibility,
+    owned: &Ident,
+    derive: Option<TokenStream>,
+    variants: Vec<(Ident, Option<Type>)>,
+    errors: &mut Errors,
+) -> TokenStream {
+    if !generic.is_empty() {
+        errors.err(
+            "owned = ... can't be used with type parameters, unless they're given a type with #[logos(type T = ...)]",
+            owned.span(),
+        );
+
+        return quote!();
+    }
+
+    let mut defs = Vec::new();
+    let mut arms = Vec::new();
+
+    for (ident, field) in variants {
+        match field {
+            None => {
+                defs.push(quote!(#ident,));
+                arms.push(quote!(#name::#ident => #owned::#ident,));
+            }
+            Some(ty) => {
+                let payload = match Payload::new(&ty, errors) {
+                    Some(payload) => payload,
+                    None => continue,
+                };
+                let owned_ty = payload.ty();
+                let convert = payload.convert();
+
+                defs.push(quote!(#ident(#owned_ty),));
+                arms.push(quote!(#name::#ident(ref field) => #owned::#ident(#convert),));
+            }
+        }
+    }
+
+    let derive = derive.map(|derive| quote!(#[derive(#derive)]));
+    let doc = format!(
+        "An owned version of [`{}`], where `&str`, `&[u8]` and `SliceView` fields are replaced with owned values.",
+        name
+    );
+    let method_doc = format!(
+        "Convert this token into an owned [`{}`], which doesn't borrow from the source.",
+        owned
+    );
+    let params = generic.params();
+    let where_clause = generic.where_clause();
+
+    quote! {
+        #[doc = #doc]
+        #derive
+        #vis enum #owned {
+            #(#defs)*
+        }
+
+        #[automatically_derived]
+        #[allow(dead_code)]
+        impl #params #this #where_clause {
+            #[doc = #method_doc]
+            pub fn to_owned(&self) -> #owned {
+                match *self {
+                    #(#arms)*
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,666 @@
+use std::rc::Rc;
+
+use proc_macro2::{Ident, Span, TokenStream};
+use quote::ToTokens;
+use syn::parse::Parser as _;
+use syn::punctuated::Punctuated;
+use syn::{spanned::Spanned, Expr, ExprUnary, LitBool, LitByteStr, LitStr, Token, UnOp};
+
+use crate::captures::Program;
+use crate::error::{Errors, Result};
+use crate::leaf::{Callback, Requirement};
+use crate::mir::{Class, Literal as HirLiteral, Mir};
+use crate::parser::nested::NestedValue;
+use crate::parser::{IgnoreFlags, Parser, Subpatterns};
+
+use super::ignore_flags::ascii_case::MakeAsciiCaseInsensitive;
+
+pub struct Definition {
+    pub literal: Literal,
+    pub priority: Option<usize>,
+    pub callback: Option<Callback>,
+    pub ignore_flags: IgnoreFlags,
+    pub contextual: bool,
+    /// Trailing context that must follow the token, but isn't part of it.
+    pub followed_by: Option<Literal>,
+    /// Trailing context that mustn't match after the token.
+    pub not_followed_by: Option<Literal>,
+    /// A class of bytes that the byte before the token mustn't be in.
+    pub not_preceded_by: Option<Literal>,
+    /// A pattern that the text before the token must end with.
+    pub preceded_by: Option<Literal>,
+    /// Inputs that this definition must win, given with `disambiguate_with`.
+    pub witnesses: Vec<Literal>,
+    /// Set by `strategy = "callback"`: only the first byte of the token is in the state machine, and the rest is
+    /// matched by simulating the regex at runtime.
+    pub simulate: Option<Span>,
+    /// Set by `unterminated = ...`: the unit variant or error that's produced when the input ends part way through the
+    /// token.
+    pub unterminated: Option<TokenStream>,
+    /// Set by `unicode = ...`, or `#[logos(unicode = ...)]` on the enum: whether classes and `.` match characters or
+    /// bytes. By default they match characters in string patterns, and bytes in byte string patterns.
+    pub unicode: Option<bool>,
+    /// Lexer flags given with `requires(...)`, which must be set (or unset, if negated) for the definition to match.
+    pub requires: Vec<Requirement>,
+}
+
+pub enum Literal {
+    Utf8(LitStr),
+    Bytes(LitByteStr),
+}
+
+impl Definition {
+    pub fn new(literal: Literal) -> Self {
+        Definition {
+            literal,
+            priority: None,
+            callback: None,
+            ignore_flags: IgnoreFlags::Empty,
+            contextual: false,
+            followed_by: None,
+            not_followed_by: None,
+            not_preceded_by: None,
+            preceded_by: None,
+            witnesses: Vec::new(),
+            simulate: None,
+            unterminated: None,
+            unicode: None,
+            requires: Vec::new(),
+        }
+    }
+
+    /// Describe the definition the way it was written, for generated docs. Only the pattern and the options that change
+    /// what it matches are included.
+    pub fn describe(&self, kind: &str) -> String {
+        let mut out = format!("#[{}({}", kind, self.literal.describe());
+
+        if self.ignore_flags.contains(IgnoreFlags::IgnoreAsciiCase) {
+            out.push_str(", ignore(ascii_case)");
+        } else if self.ignore_flags.contains(IgnoreFlags::IgnoreCase) {
+            out.push_str(", ignore(case)");
+        }
+
+        if let Some(unicode) = self.unicode {
+            out.push_str(&format!(", unicode = {}", unicode));
+        }
+
+        if let Some(followed_by) = &self.followed_by {
+            out.push_str(", followed_by = ");
+            out.push_str(&followed_by.describe());
+        }
+
+        if let Some(not_followed_by) = &self.not_followed_by {
+            out.push_str(", not_followed_by = ");
+            out.push_str(&not_followed_by.describe());
+        }
+
+        if let Some(not_preceded_by) = &self.not_preceded_by {
+            out.push_str(", not_preceded_by = ");
+            out.push_str(&not_preceded_by.describe());
+        }
+
+        if let Some(preceded_by) = &self.preceded_by {
+            out.push_str(", preceded_by = ");
+            out.push_str(&preceded_by.describe());
+        }
+
+        if !self.requires.is_empty() {
+            let flags: Vec<String> = self
+                .requires
+                .iter()
+                .map(|requirement| match requirement.negated {
+                    false => requirement.flag.to_string(),
+                    true => format!("!{}", requirement.flag),
+                })
+                .collect();
+
+            out.push_str(&format!(", requires({})", flags.join(", ")));
+        }
+
+        if let Some(unterminated) = &self.unterminated {
+            out.push_str(", unterminated = ");
+            out.push_str(&unterminated.to_string());
+        }
+
+        out.push_str(")]");
+        out
+    }
+
+    pub fn named_attr(&mut self, name: Ident, value: NestedValue, parser: &mut Parser) {
+        match (name.to_string().as_str(), value) {
+            ("priority", NestedValue::Assign(tokens)) => {
+                let prio = match tokens.to_string().parse() {
+                    Ok(prio) => prio,
+                    Err(_) => {
+                        parser.err("Expected an unsigned integer", tokens.span());
+                        return;
+                    }
+                };
+
+                if self.priority.replace(prio).is_some() {
+                    parser.err("Resetting previously set priority", tokens.span());
+                }
+            }
+            ("priority", _) => {
+                parser.err("Expected: priority = <integer>", name.span());
+            }
+            ("callback", NestedValue::Assign(tokens)) => {
+                let span = tokens.span();
+                let callback = match parser.parse_callback(tokens) {
+                    Some(callback) => callback,
+                    None => {
+                        parser.err("Not a valid callback", span);
+                        return;
+                    }
+                };
+
+                if let Some(previous) = self.callback.replace(callback) {
+                    parser
+                        .err(
+                            "Callback has been already set",
+                            span.join(name.span()).unwrap(),
+                        )
+                        .err("Previous callback set here", previous.span());
+                }
+            }
+            ("callback", _) => {
+                parser.err("Expected: callback = ...", name.span());
+            }
+            ("ignore", NestedValue::Group(tokens)) => {
+                self.ignore_flags.parse_group(name, tokens, parser);
+            }
+            ("ignore", _) => {
+                parser.err("Expected: ignore(<flag>, ...)", name.span());
+            }
+            ("followed_by", NestedValue::Assign(tokens)) => {
+                let span = tokens.span();
+                let literal = match syn::parse2::<Literal>(tokens) {
+                    Ok(literal) => literal,
+                    Err(_) => {
+                        parser.err("Expected a &str or &[u8] slice", span);
+                        return;
+                    }
+                };
+
+                if self.followed_by.replace(literal).is_some() {
+                    parser.err("Resetting previously set followed_by", span);
+                }
+            }
+            ("followed_by", _) => {
+                parser.err("Expected: followed_by = \"...\"", name.span());
+            }
+            ("not_followed_by", NestedValue::Assign(tokens)) => {
+                let span = tokens.span();
+                let literal = match syn::parse2::<Literal>(tokens) {
+                    Ok(literal) => literal,
+                    Err(_) => {
+                        parser.err("Expected a &str or &[u8] slice", span);
+                        return;
+                    }
+                };
+
+                if self.not_followed_by.replace(literal).is_some() {
+                    parser.err("Resetting previously set not_followed_by", span);
+                }
+            }
+            ("not_followed_by", _) => {
+                parser.err("Expected: not_followed_by = \"...\"", name.span());
+            }
+            ("not_preceded_by", NestedValue::Assign(tokens)) => {
+                let span = tokens.span();
+                let literal = match syn::parse2::<Literal>(tokens) {
+                    Ok(literal) => literal,
+                    Err(_) => {
+                        parser.err("Expected a &str or &[u8] slice", span);
+                        return;
+                    }
+                };
+
+                if self.not_preceded_by.replace(literal).is_some() {
+                    parser.err("Resetting previously set not_preceded_by", span);
+                }
+            }
+            ("not_preceded_by", _) => {
+                parser.err("Expected: not_preceded_by = \"[...]\"", name.span());
+            }
+            ("preceded_by", NestedValue::Assign(tokens)) => {
+                let span = tokens.span();
+                let literal = match syn::parse2::<Literal>(tokens) {
+                    Ok(literal) => literal,
+                    Err(_) => {
+                        parser.err("Expected a &str or &[u8] slice", span);
+                        return;
+                    }
+                };
+
+                if self.preceded_by.replace(literal).is_some() {
+                    parser.err("Resetting previously set preceded_by", span);
+                }
+            }
+            ("preceded_by", _) => {
+                parser.err("Expected: preceded_by = \"...\"", name.span());
+            }
+            ("disambiguate_with", NestedValue::Assign(tokens)) => {
+                let span = tokens.span();
+                let literal = match syn::parse2::<Literal>(tokens) {
+                    Ok(literal) => literal,
+                    Err(_) => {
+                        parser.err("Expected a &str or &[u8] slice", span);
+                        return;
+                    }
+                };
+
+                if literal.to_bytes().is_empty() {
+                    parser.err("The witness can't be empty", span);
+                    return;
+                }
+
+                self.witnesses.push(literal);
+            }
+            ("disambiguate_with", _) => {
+                parser.err("Expected: disambiguate_with = \"...\"", name.span());
+            }
+            ("strategy", NestedValue::Assign(tokens)) => {
+                let span = tokens.span();
+                let strategy = match syn::parse2::<LitStr>(tokens) {
+                    Ok(strategy) => strategy,
+                    Err(err) => {
+                        parser.err(err.to_string(), err.span());
+                        return;
+                    }
+                };
+
+                self.simulate = match strategy.value().as_str() {
+                    "dfa" => None,
+                    "callback" => Some(span),
+                    _ => {
+                        parser.err(
+                            r#"Expected: strategy = "dfa" or strategy = "callback""#,
+                            span,
+                        );
+                        return;
+                    }
+                };
+            }
+            ("strategy", _) => {
+                parser.err(
+                    r#"Expected: strategy = "dfa" or strategy = "callback""#,
+                    name.span(),
+                );
+            }
+            ("unterminated", NestedValue::Assign(tokens)) => {
+                let span = tokens.span();
+
+                if self.unterminated.replace(tokens).is_some() {
+                    parser.err("Resetting previously set unterminated", span);
+                }
+            }
+            ("unterminated", _) => {
+                parser.err("Expected: unterminated = <variant or error>", name.span());
+            }
+            ("unicode", NestedValue::Assign(tokens)) => {
+                let unicode = match syn::parse2::<LitBool>(tokens) {
+                    Ok(unicode) => unicode,
+                    Err(err) => {
+                        parser.err(err.to_string(), err.span());
+                        return;
+                    }
+                };
+
+                self.unicode = Some(unicode.value);
+            }
+            ("unicode", _) => {
+                parser.err("Expected: unicode = true or unicode = false", name.span());
+            }
+            ("requires", NestedValue::Group(tokens)) => {
+                let flags = match Punctuated::<Expr, Token![,]>::parse_terminated.parse2(tokens) {
+                    Ok(flags) => flags,
+                    Err(err) => {
+                        parser.err(err.to_string(), err.span());
+                        return;
+                    }
+                };
+
+                self.requires
+                    .extend(flags.into_iter().map(|flag| match flag {
+                        Expr::Unary(ExprUnary {
+                            op: UnOp::Not(_),
+                            expr,
+                            ..
+                        }) => Requirement {
+                            flag: expr.into_token_stream(),
+                            negated: true,
+                        },
+                        flag => Requirement {
+                            flag: flag.into_token_stream(),
+                            negated: false,
+                        },
+                    }));
+            }
+            ("requires", _) => {
+                parser.err("Expected: requires(FLAG, !OTHER_FLAG, ...)", name.span());
+            }
+            (unknown, _) => {
+                parser.err(
+                    format!(
+                        "\
+                        Unknown nested attribute: {}\n\n\
+                        Expected one of: priority, callback, ignore, followed_by, not_followed_by, not_preceded_by, \
+                        preceded_by, disambiguate_with, strategy, unterminated, unicode, requires\
+                        ",
+                        unknown
+                    ),
+                    name.span(),
+                );
+            }
+        }
+    }
+}
+
+/// The context around a token that's checked once it's matched, given with `followed_by`, `not_followed_by`,
+/// `not_preceded_by` and `preceded_by`.
+pub struct Lookaround {
+    pub followed_by: Option<Rc<Program>>,
+    pub not_followed_by: Option<Rc<Program>>,
+    pub not_preceded_by: Option<Rc<[(u8, u8)]>>,
+    pub preceded_by: Option<Rc<Program>>,
+    /// Trailing context makes a definition more specific, so it counts towards the priority.
+    pub priority: usize,
+}
+
+impl Definition {
+    /// Parse all of the context around the token. Errors are reported with the span of the option they came from, in
+    /// which case this returns `None`.
+    pub fn lookaround(&self, subpatterns: &Subpatterns, errors: &mut Errors) -> Option<Lookaround> {
+        let followed_by = report(
+            self.lookahead(subpatterns, errors),
+            &self.followed_by,
+            errors,
+        )?;
+        let not_followed_by = report(
+            self.negative_lookahead(subpatterns, errors),
+            &self.not_followed_by,
+            errors,
+        )?;
+        let not_preceded_by = report(
+            self.lookbehind(subpatterns, errors),
+            &self.not_preceded_by,
+            errors,
+        )?;
+        let preceded_by = report(
+            self.preceding(subpatterns, errors),
+            &self.preceded_by,
+            errors,
+        )?;
+
+        Some(Lookaround {
+            priority: followed_by.as_ref().map_or(0, Mir::priority),
+            followed_by: followed_by.map(|mir| Rc::new(Program::lookahead(&mir))),
+            not_followed_by: not_followed_by.map(|mir| Rc::new(Program::lookahead(&mir))),
+            not_preceded_by: not_preceded_by.map(Rc::from),
+            preceded_by: preceded_by.map(|mir| Rc::new(Program::lookbehind(&mir))),
+        })
+    }
+
+    /// The regex for the trailing context given with `followed_by`, if there is any.
+    fn lookahead(&self, subpatterns: &Subpatterns, errors: &mut Errors) -> Result<Option<Mir>> {
+        let literal = match &self.followed_by {
+            Some(literal) => literal,
+            None => return Ok(None),
+        };
+        let mut mir = literal.to_mir(subpatterns, self.ignore_flags, self.unicode, errors)?;
+
+        if !mir.take_assertions()?.is_empty() {
+            return Err(
+                "followed_by: word boundaries and anchors are not supported in trailing context."
+                    .into(),
+            );
+        }
+
+        Ok(Some(mir))
+    }
+
+    /// The regex for the trailing context given with `not_followed_by`, if there is any.
+    fn negative_lookahead(
+        &self,
+        subpatterns: &Subpatterns,
+        errors: &mut Errors,
+    ) -> Result<Option<Mir>> {
+        let literal = match &self.not_followed_by {
+            Some(literal) => literal,
+            None => return Ok(None),
+        };
+        let mut mir = literal.to_mir(subpatterns, self.ignore_flags, self.unicode, errors)?;
+
+        if !mir.take_assertions()?.is_empty() {
+            return Err(
+                "not_followed_by: word boundaries and anchors are not supported in trailing context."
+                    .into(),
+            );
+        }
+
+        if Program::lookahead(&mir).matches_empty() {
+            return Err(
+                "not_followed_by: the pattern can't match empty text, since the token would never match."
+                    .into(),
+            );
+        }
+
+        Ok(Some(mir))
+    }
+
+    /// The regex for the text before the token given with `preceded_by`, if there is any.
+    fn preceding(&self, subpatterns: &Subpatterns, errors: &mut Errors) -> Result<Option<Mir>> {
+        let literal = match &self.preceded_by {
+            Some(literal) => literal,
+            None => return Ok(None),
+        };
+        let mut mir = literal.to_mir(subpatterns, self.ignore_flags, self.unicode, errors)?;
+
+        if !mir.take_assertions()?.is_empty() {
+            return Err(
+                "preceded_by: word boundaries and anchors are not supported in preceding context."
+                    .into(),
+            );
+        }
+
+        if Program::lookahead(&mir).matches_empty() {
+            return Err(
+                "preceded_by: the pattern can't match empty text, since it would always match."
+                    .into(),
+            );
+        }
+
+        Ok(Some(mir))
+    }
+
+    /// The inclusive byte ranges given with `not_preceded_by`, if there are any.
+    fn lookbehind(
+        &self,
+        subpatterns: &Subpatterns,
+        errors: &mut Errors,
+    ) -> Result<Option<Vec<(u8, u8)>>> {
+        let literal = match &self.not_preceded_by {
+            Some(literal) => literal,
+            None => return Ok(None),
+        };
+        let ranges = match literal.to_mir(subpatterns, self.ignore_flags, self.unicode, errors)? {
+            Mir::Literal(HirLiteral::Unicode(c)) if c.is_ascii() => vec![(c as u8, c as u8)],
+            Mir::Literal(HirLiteral::Byte(byte)) => vec![(byte, byte)],
+            Mir::Class(Class::Unicode(class)) => class
+                .iter()
+                .map(|range| match (u8::try_from(range.start()), u8::try_from(range.end())) {
+                    (Ok(start), Ok(end)) if end.is_ascii() => Some((start, end)),
+                    _ => None,
+                })
+                .collect::<Option<_>>()
+                .ok_or("not_preceded_by: only ASCII characters can be checked, since the check looks at one byte.")?,
+            Mir::Class(Class::Bytes(class)) => class.iter().map(|range| (range.start(), range.end())).collect(),
+            _ => return Err("not_preceded_by: expected a single byte or a class of bytes, like \"[a-zA-Z0-9_]\".".into()),
+        };
+
+        Ok(Some(ranges))
+    }
+}
+
+/// Report the error of parsing an option given with `literal`, if there is one.
+fn report<T>(result: Result<T>, literal: &Option<Literal>, errors: &mut Errors) -> Option<T> {
+    match result {
+        Ok(value) => Some(value),
+        Err(err) => {
+            errors.err(err, literal.as_ref().unwrap().span());
+            None
+        }
+    }
+}
+
+impl Literal {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        match self {
+            Literal::Utf8(string) => string.value().into_bytes(),
+            Literal::Bytes(bytes) => bytes.value(),
+        }
+    }
+
+    /// The literal as it would be written in Rust source. Patterns with backslashes or quotes are written as raw
+    /// strings, which is how they're usually written (and much easier to read).
+    pub fn describe(&self) -> String {
+        let (prefix, value) = match self {
+            Literal::Utf8(string) => ("", string.value()),
+            Literal::Bytes(bytes) => match String::from_utf8(bytes.value()) {
+                Ok(value) if value.is_ascii() => ("b", value),
+                _ => return format!("b\"{}\"", bytes.value().escape_ascii()),
+            },
+        };
+
+        if !value.contains(['\\', '"']) || value.contains(char::is_control) {
+            return match prefix {
+                "b" => format!("b\"{}\"", value.as_bytes().escape_ascii()),
+                _ => format!("{:?}", value),
+            };
+        }
+
+        let mut hashes = String::new();
+
+        while value.contains(&format!("\"{}", hashes)) {
+            hashes.push('#');
+        }
+
+        format!("{}r{}\"{}\"{}", prefix, hashes, value, hashes)
+    }
+
+    pub fn escape_regex(&self) -> Literal {
+        match self {
+            Literal::Utf8(string) => Literal::Utf8(LitStr::new(
+                regex_syntax::escape(&string.value()).as_str(),
+                self.span(),
+            )),
+            Literal::Bytes(bytes) => Literal::Bytes(LitByteStr::new(
+                regex_syntax::escape(&bytes_to_regex_string(bytes.value())).as_bytes(),
+                self.span(),
+            )),
+        }
+    }
+
+    /// Compile the literal as a regex. With `unicode = true`, classes and `.` only ever match whole characters, and
+    /// with `unicode = false` they match bytes. Otherwise string patterns match characters, except that classes
+    /// reaching to the end of Unicode (like `[^a]`) match their non-ASCII part a byte at a time, and byte string
+    /// patterns match bytes.
+    pub fn to_mir(
+        &self,
+        subpatterns: &Subpatterns,
+        ignore_flags: IgnoreFlags,
+        unicode: Option<bool>,
+        errors: &mut Errors,
+    ) -> Result<Mir> {
+        if let (Literal::Bytes(bytes), Some(true)) = (self, unicode) {
+            let string = String::from_utf8(bytes.value()).map_err(|_| {
+                "unicode = true: the pattern has to be valid UTF-8 for its classes to match characters."
+            })?;
+
+            return Literal::Utf8(LitStr::new(&string, bytes.span())).to_mir(
+                subpatterns,
+                ignore_flags,
+                unicode,
+                errors,
+            );
+        }
+
+        let value = subpatterns.fix(self, errors);
+
+        let mir = if ignore_flags.contains(IgnoreFlags::IgnoreAsciiCase) {
+            match (self, unicode) {
+                (Literal::Utf8(_), Some(false)) => Mir::ascii_ignore_case(&value),
+                (Literal::Utf8(_), _) => {
+                    Mir::utf8(&value).map(MakeAsciiCaseInsensitive::make_ascii_case_insensitive)
+                }
+                (Literal::Bytes(_), _) => Mir::binary_ignore_case(&value),
+            }
+        } else if ignore_flags.contains(IgnoreFlags::IgnoreCase) {
+            match (self, unicode) {
+                (Literal::Utf8(_), Some(false)) => Mir::ascii_ignore_case(&value),
+                (Literal::Utf8(_), _) => Mir::utf8_ignore_case(&value),
+                (Literal::Bytes(_), _) => Mir::binary_ignore_case(&value),
+            }
+        } else {
+            match (self, unicode) {
+                (Literal::Utf8(_), Some(false)) => Mir::ascii(&value),
+                (Literal::Utf8(_), _) => Mir::utf8(&value),
+                (Literal::Bytes(_), _) => Mir::binary(&value),
+            }
+        };
+
+        match unicode {
+            Some(true) => mir.map(Mir::whole_characters),
+            _ => mir,
+        }
+    }
+
+    pub fn span(&self) -> Span {
+        match self {
+            Literal::Utf8(string) => string.span(),
+            Literal::Bytes(bytes) => bytes.span(),
+        }
+    }
+}
+
+impl syn::parse::Parse for Literal {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let la = input.lookahead1();
+        if la.peek(LitStr) {
+            Ok(Literal::Utf8(input.parse()?))
+        } else if la.peek(LitByteStr) {
+            Ok(Literal::Bytes(input.parse()?))
+        } else {
+            Err(la.error())
+        }
+    }
+}
+
+pub fn bytes_to_regex_string(bytes: Vec<u8>) -> String {
+    if bytes.is_ascii() {
+        unsafe {
+            // Unicode values are prohibited, so we can't use
+            // safe version of String::from_utf8
+            //
+            // We can, however, construct a safe ASCII string
+            return String::from_utf8_unchecked(bytes);
+        }
+    }
+
+    let mut string = String::with_capacity(bytes.len() * 2);
+
+    for byte in bytes {
+        if byte < 0x80 {
+            string.push(byte as char);
+        } else {
+            static DIGITS: [u8; 16] = *b"0123456789abcdef";
+
+            string.push_str(r"\x");
+            string.push(DIGITS[(byte / 16) as usize] as char);
+            string.push(DIGITS[(byte % 16) as usize] as char);
+        }
+    }
+
+    string
+}
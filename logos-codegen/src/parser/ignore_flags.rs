@@ -92,6 +92,7 @@ impl IgnoreFlags {
         }
     }
 
+    #[allow(clippy::collapsible_match)]
     pub fn parse_group(&mut self, name: Ident, tokens: TokenStream, parser: &mut Parser) {
         // Little finite state machine to parse "<flag>(,<flag>)*,?"
 
@@ -206,12 +207,12 @@ pub mod ascii_case {
 
     impl MakeAsciiCaseInsensitive for u8 {
         fn make_ascii_case_insensitive(self) -> Mir {
-            if (b'a'..=b'z').contains(&self) {
+            if self.is_ascii_lowercase() {
                 Mir::Alternation(vec![
                     Mir::Literal(hir::Literal::Byte(self - 32)),
                     Mir::Literal(hir::Literal::Byte(self)),
                 ])
-            } else if (b'A'..=b'Z').contains(&self) {
+            } else if self.is_ascii_uppercase() {
                 Mir::Alternation(vec![
                     Mir::Literal(hir::Literal::Byte(self)),
                     Mir::Literal(hir::Literal::Byte(self + 32)),
@@ -264,7 +265,7 @@ pub mod ascii_case {
 
                 #[inline]
                 fn make_ascii(c: char) -> Option<u8> {
-                    c.is_ascii().then(|| c as u8)
+                    c.is_ascii().then_some(c as u8)
                 }
 
                 match (make_ascii(range.start()), make_ascii(range.end())) {
@@ -341,6 +342,7 @@ pub mod ascii_case {
         fn make_ascii_case_insensitive(self) -> Mir {
             match self {
                 Mir::Empty => Mir::Empty,
+                Mir::Assertion(a) => Mir::Assertion(a),
                 Mir::Loop(l) => Mir::Loop(Box::new(l.make_ascii_case_insensitive())),
                 Mir::Maybe(m) => Mir::Maybe(Box::new(m.make_ascii_case_insensitive())),
                 Mir::Concat(c) => Mir::Concat(
@@ -355,6 +357,9 @@ pub mod ascii_case {
                 ),
                 Mir::Class(c) => c.make_ascii_case_insensitive(),
                 Mir::Literal(l) => l.make_ascii_case_insensitive(),
+                Mir::Capture(index, name, m) => {
+                    Mir::Capture(index, name, Box::new(m.make_ascii_case_insensitive()))
+                }
             }
         }
     }
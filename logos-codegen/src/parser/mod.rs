@@ -0,0 +1,941 @@
+use beef::lean::Cow;
+use proc_macro2::{Ident, Span, TokenStream, TokenTree};
+use quote::quote;
+use syn::spanned::Spanned;
+use syn::{Attribute, GenericParam, Lit, LitBool, LitByteStr, LitInt, LitStr, Type, WhereClause};
+
+use crate::error::Errors;
+use crate::leaf::{Callback, InlineCallback};
+use crate::util::{expect_punct, MaybeVoid};
+
+mod definition;
+mod ignore_flags;
+mod nested;
+mod subpattern;
+mod type_params;
+
+pub use self::definition::{Definition, Literal, Lookaround};
+pub use self::ignore_flags::IgnoreFlags;
+use self::nested::{AttributeParser, Nested, NestedValue};
+pub use self::subpattern::Subpatterns;
+pub use self::type_params::Generic;
+use self::type_params::{replace_lifetime, traverse_type, TypeParams};
+
+#[derive(Default)]
+pub struct Parser {
+    pub errors: Errors,
+    pub mode: Mode,
+    pub codegen: Codegen,
+    pub strategy: Strategy,
+    pub invalid_utf8: Option<InvalidUtf8>,
+    /// Set by `#[logos(unicode = ...)]`: whether classes and `.` match characters or bytes, unless a definition says
+    /// otherwise.
+    pub unicode: Option<bool>,
+    pub source_type: Option<TokenStream>,
+    pub extras: MaybeVoid,
+    pub subpatterns: Subpatterns,
+    pub error_type: Option<TokenStream>,
+    pub export_dot: Option<LitStr>,
+    pub determinism_report: Option<LitStr>,
+    pub stats: Option<Option<LitStr>>,
+    pub conformance_tests: Option<(Span, Option<LitStr>)>,
+    pub recover_to: Vec<Literal>,
+    pub sentinels: Vec<u8>,
+    pub verify_corpus: Option<LitStr>,
+    pub test_match: bool,
+    pub sniff: bool,
+    pub display: bool,
+    pub doc_grammar: bool,
+    pub keywords: Option<Option<LitStr>>,
+    pub hooks: Vec<(Ident, Callback)>,
+    pub lifecycle: bool,
+    pub no_panic_error: bool,
+    pub owned: Option<Ident>,
+    pub owned_derive: Option<TokenStream>,
+    pub kind: Option<Ident>,
+    pub kind_derive: Option<TokenStream>,
+    types: TypeParams,
+}
+
+#[derive(Default)]
+pub enum Mode {
+    #[default]
+    Utf8,
+    Binary,
+}
+
+/// How the state machine is turned into code.
+#[derive(Default, PartialEq, Eq)]
+pub enum Codegen {
+    /// A function per state, which is as fast as it gets.
+    #[default]
+    Fast,
+    /// Transition tables run by a small interpreter, which is a lot less code.
+    Compact,
+}
+
+/// What to do with invalid UTF-8 that no definition matches.
+pub enum InvalidUtf8 {
+    /// Produce an error for each invalid sequence.
+    Error,
+    /// Skip invalid sequences, like whitespace.
+    Skip,
+    /// Lex invalid sequences as whichever definition matches a lone U+FFFD.
+    Replace(Span),
+}
+
+/// Knobs for the code generated with [Codegen::Fast], which trade speed for size.
+#[derive(Clone, Copy)]
+pub struct Strategy {
+    /// Whether forks with many branches can use a 256 entry jump table, instead of a `match`.
+    pub jump_tables: bool,
+    /// Whether bytes can be tested against wide classes with a 256 entry lookup table, instead of a `match`.
+    pub lookup_tables: bool,
+    /// How many bytes loops over a class check per bounds check.
+    pub unroll: usize,
+    /// How many literals an alternation needs before it's matched with a binary search over its literals, instead of
+    /// being compiled into the state machine. Zero never uses a binary search.
+    pub literal_sets: usize,
+    /// How many `#[token]` keywords an identifier regex needs to fully match before they're looked up in a perfect hash
+    /// table once the identifier has been matched, instead of being compiled into the state machine. Zero never uses
+    /// a table.
+    pub keyword_tables: usize,
+    /// How many states the function for a state can inline, before the state machine is split into functions that
+    /// are never inlined. Zero inlines everything.
+    pub max_inline: usize,
+}
+
+/// The settings of a variant's `#[logos(...)]` attribute.
+#[derive(Default)]
+pub struct VariantLogos {
+    /// The name the variant is displayed with, given by `name = "..."`.
+    pub display_name: Option<LitStr>,
+    /// Whether the variant is produced once at the end of input, marked with `eof`.
+    pub eof: Option<Span>,
+}
+
+impl Default for Strategy {
+    fn default() -> Self {
+        Strategy {
+            jump_tables: true,
+            lookup_tables: true,
+            unroll: 16,
+            literal_sets: 128,
+            keyword_tables: 64,
+            max_inline: 0,
+        }
+    }
+}
+
+impl Parser {
+    pub fn parse_generic(&mut self, param: GenericParam) {
+        match param {
+            GenericParam::Lifetime(lt) => {
+                self.types.explicit_lifetime(lt, &mut self.errors);
+            }
+            GenericParam::Type(ty) => {
+                self.types.add(ty);
+            }
+            GenericParam::Const(c) => {
+                self.err("Logos doesn't support const generics.", c.span());
+            }
+        }
+    }
+
+    pub fn parse_where_clause(&mut self, clause: WhereClause) {
+        self.types.where_clause(clause);
+    }
+
+    pub fn generics(&mut self) -> Option<TokenStream> {
+        self.types.generics()
+    }
+
+    /// The type parameters that the implementation is generic over.
+    pub fn generic(&self) -> Generic {
+        self.types.generic()
+    }
+
+    fn parse_attr(&mut self, attr: &mut Attribute) -> Option<AttributeParser> {
+        let mut tokens = std::mem::replace(&mut attr.tokens, TokenStream::new()).into_iter();
+
+        match tokens.next() {
+            Some(TokenTree::Group(group)) => Some(AttributeParser::new(group.stream())),
+            _ => None,
+        }
+    }
+
+    /// Try to parse the main `#[logos(...)]`, does nothing if
+    /// the attribute's name isn't `logos`.
+    pub fn try_parse_logos(&mut self, attr: &mut Attribute) {
+        if !attr.path.is_ident("logos") {
+            return;
+        }
+
+        let nested = match self.parse_attr(attr) {
+            Some(tokens) => tokens,
+            None => {
+                self.err("Expected #[logos(...)]", attr.span());
+                return;
+            }
+        };
+
+        // The subpattern defined by the previous nested attribute, which `ignore(...)` applies to.
+        let mut subpattern: Option<Ident> = None;
+
+        for nested in nested {
+            let previous = subpattern.take();
+            let (name, value) = match nested {
+                Nested::Named(name, value) => (name, value),
+                Nested::Unnamed(tokens) if tokens.to_string() == "test_match" => {
+                    if self.test_match {
+                        self.err("test_match can only be enabled once", tokens.span());
+                    }
+
+                    self.test_match = true;
+                    continue;
+                }
+                Nested::Unnamed(tokens) if tokens.to_string() == "sniff" => {
+                    if self.sniff {
+                        self.err("sniff can only be enabled once", tokens.span());
+                    }
+
+                    self.sniff = true;
+                    continue;
+                }
+                Nested::Unnamed(tokens) if tokens.to_string() == "display" => {
+                    if self.display {
+                        self.err("display can only be enabled once", tokens.span());
+                    }
+
+                    self.display = true;
+                    continue;
+                }
+                Nested::Unnamed(tokens) if tokens.to_string() == "keywords" => {
+                    if self.keywords.replace(None).is_some() {
+                        self.err("keywords can only be enabled once", tokens.span());
+                    }
+
+                    continue;
+                }
+                Nested::Unnamed(tokens) if tokens.to_string() == "lifecycle" => {
+                    if self.lifecycle {
+                        self.err("lifecycle can only be enabled once", tokens.span());
+                    }
+
+                    self.lifecycle = true;
+                    continue;
+                }
+                Nested::Unnamed(tokens) if tokens.to_string() == "conformance_tests" => {
+                    let span = tokens.span();
+
+                    if self.conformance_tests.replace((span, None)).is_some() {
+                        self.err("conformance_tests can only be enabled once", span);
+                    }
+
+                    continue;
+                }
+                Nested::Unnamed(tokens) if tokens.to_string() == "stats" => {
+                    if self.stats.replace(None).is_some() {
+                        self.err("stats can only be enabled once", tokens.span());
+                    }
+
+                    continue;
+                }
+                Nested::Unnamed(tokens) if tokens.to_string() == "no_panic_error" => {
+                    if self.no_panic_error {
+                        self.err("no_panic_error can only be enabled once", tokens.span());
+                    }
+
+                    self.no_panic_error = true;
+                    continue;
+                }
+                Nested::Unnamed(tokens) if tokens.to_string() == "doc_grammar" => {
+                    if self.doc_grammar {
+                        self.err("doc_grammar can only be enabled once", tokens.span());
+                    }
+
+                    self.doc_grammar = true;
+                    continue;
+                }
+                Nested::Unexpected(tokens) | Nested::Unnamed(tokens) => {
+                    self.err("Invalid nested attribute", tokens.span());
+                    continue;
+                }
+            };
+
+            match (name.to_string().as_str(), value) {
+                ("source", NestedValue::Assign(value)) => {
+                    let span = value.span();
+
+                    if let Some(previous) = self.source_type.replace(value) {
+                        self.err("The source type can only be defined only once", span)
+                            .err("Previous definition here", previous.span());
+                    }
+                }
+                ("source", _) => {
+                    self.err("Expected: source = SomeType", name.span());
+                }
+                ("error", NestedValue::Assign(value)) => {
+                    let span = value.span();
+
+                    if let Some(previous) = self.error_type.replace(value) {
+                        self.err("The error type can only be defined only once", span)
+                            .err("Previous definition here", previous.span());
+                    }
+                }
+                ("error", _) => {
+                    self.err("Expected: error = SomeType", name.span());
+                }
+                ("extras", NestedValue::Assign(value)) => {
+                    let span = value.span();
+
+                    if let MaybeVoid::Some(previous) = self.extras.replace(value) {
+                        self.err("Extras can be defined only once", span)
+                            .err("Previous definition here", previous.span());
+                    }
+                }
+                ("extras", _) => {
+                    self.err("Expected: extras = SomeType", name.span());
+                }
+                ("type", NestedValue::KeywordAssign(generic, ty)) => {
+                    self.types.set(generic, ty, &mut self.errors);
+                }
+                ("type", _) => {
+                    self.err("Expected: type T = SomeType", name.span());
+                }
+                ("trivia", _) => {
+                    // TODO: Remove in future versions
+                    self.err(
+                        "\
+                        trivia are no longer supported.\n\n\
+                        For help with migration see release notes: \
+                        https://github.com/maciejhirsz/logos/releases\
+                        ",
+                        name.span(),
+                    );
+                }
+                ("subpattern", NestedValue::KeywordAssign(name, value)) => {
+                    subpattern = Some(name.clone());
+                    self.subpatterns.add(name, value, &mut self.errors);
+                }
+                ("subpattern", _) => {
+                    self.err(r#"Expected: subpattern name = r"regex""#, name.span());
+                }
+                ("ignore", NestedValue::Group(tokens)) => {
+                    let previous = match previous {
+                        Some(previous) => previous,
+                        None => {
+                            self.err(
+                                "ignore(...) must directly follow the subpattern that it applies to",
+                                name.span(),
+                            );
+                            continue;
+                        }
+                    };
+                    let mut flags = IgnoreFlags::Empty;
+
+                    flags.parse_group(name, tokens, self);
+                    self.subpatterns.ignore(&previous, flags, &mut self.errors);
+                }
+                ("ignore", _) => {
+                    self.err("Expected: ignore(<flag>, ...)", name.span());
+                }
+                ("on_token" | "on_error" | "on_eof", NestedValue::Assign(value)) => {
+                    if let Some((previous, _)) = self.hooks.iter().find(|(hook, _)| *hook == name) {
+                        let span = previous.span();
+
+                        self.err(format!("{} can be defined only once", name), name.span())
+                            .err("Previous definition here", span);
+                        continue;
+                    }
+
+                    if let Some(callback) = self.parse_callback(value) {
+                        self.hooks.push((name, callback));
+                    }
+                }
+                ("on_token" | "on_error" | "on_eof", _) => {
+                    self.err(format!("Expected: {} = callback", name), name.span());
+                }
+                ("keywords", NestedValue::Assign(value)) => {
+                    let span = value.span();
+                    let pattern = match syn::parse2::<LitStr>(value) {
+                        Ok(pattern) => pattern,
+                        Err(_) => {
+                            self.err(r#"Expected: keywords = "regex""#, span);
+                            continue;
+                        }
+                    };
+
+                    if self.keywords.replace(Some(pattern)).is_some() {
+                        self.err("keywords can only be enabled once", span);
+                    }
+                }
+                ("keywords", _) => {
+                    self.err(r#"Expected: keywords = "regex""#, name.span());
+                }
+                ("owned", NestedValue::Assign(value)) => {
+                    let span = value.span();
+                    let ident = match syn::parse2::<Ident>(value) {
+                        Ok(ident) => ident,
+                        Err(_) => {
+                            self.err("Expected: owned = OwnedName", span);
+                            continue;
+                        }
+                    };
+
+                    if let Some(previous) = self.owned.replace(ident) {
+                        self.err("The owned type can be defined only once", span)
+                            .err("Previous definition here", previous.span());
+                    }
+                }
+                ("owned", _) => {
+                    self.err("Expected: owned = OwnedName", name.span());
+                }
+                ("owned_derive", NestedValue::Group(tokens)) => {
+                    let span = tokens.span();
+
+                    if let Some(previous) = self.owned_derive.replace(tokens) {
+                        self.err("owned_derive(...) can be used only once", span)
+                            .err("Previous definition here", previous.span());
+                    }
+                }
+                ("owned_derive", _) => {
+                    self.err("Expected: owned_derive(Trait, ...)", name.span());
+                }
+                ("kind", NestedValue::Assign(value)) => {
+                    let span = value.span();
+                    let ident = match syn::parse2::<Ident>(value) {
+                        Ok(ident) => ident,
+                        Err(_) => {
+                            self.err("Expected: kind = KindName", span);
+                            continue;
+                        }
+                    };
+
+                    if let Some(previous) = self.kind.replace(ident) {
+                        self.err("The kind type can be defined only once", span)
+                            .err("Previous definition here", previous.span());
+                    }
+                }
+                ("kind", _) => {
+                    self.err("Expected: kind = KindName", name.span());
+                }
+                ("kind_derive", NestedValue::Group(tokens)) => {
+                    let span = tokens.span();
+
+                    if let Some(previous) = self.kind_derive.replace(tokens) {
+                        self.err("kind_derive(...) can be used only once", span)
+                            .err("Previous definition here", previous.span());
+                    }
+                }
+                ("kind_derive", _) => {
+                    self.err("Expected: kind_derive(Trait, ...)", name.span());
+                }
+                ("export_dot", NestedValue::Assign(value)) => {
+                    let path = match syn::parse2::<LitStr>(value) {
+                        Ok(path) => path,
+                        Err(err) => {
+                            self.err(err.to_string(), err.span());
+                            continue;
+                        }
+                    };
+                    let span = path.span();
+
+                    if let Some(previous) = self.export_dot.replace(path) {
+                        self.err("The DOT export path can only be defined once", span)
+                            .err("Previous definition here", previous.span());
+                    }
+                }
+                ("export_dot", _) => {
+                    self.err(r#"Expected: export_dot = "path/to/file.dot""#, name.span());
+                }
+                ("determinism_report", NestedValue::Assign(value)) => {
+                    let path = match syn::parse2::<LitStr>(value) {
+                        Ok(path) => path,
+                        Err(err) => {
+                            self.err(err.to_string(), err.span());
+                            continue;
+                        }
+                    };
+                    let span = path.span();
+
+                    if let Some(previous) = self.determinism_report.replace(path) {
+                        self.err("The determinism report path can only be defined once", span)
+                            .err("Previous definition here", previous.span());
+                    }
+                }
+                ("determinism_report", _) => {
+                    self.err(
+                        r#"Expected: determinism_report = "path/to/file.txt""#,
+                        name.span(),
+                    );
+                }
+                ("conformance_tests", NestedValue::Assign(value)) => {
+                    let span = value.span();
+                    let path = match syn::parse2::<LitStr>(value) {
+                        Ok(path) => path,
+                        Err(_) => {
+                            self.err(r#"Expected: conformance_tests = "path/to/file.txt""#, span);
+                            continue;
+                        }
+                    };
+
+                    if self.conformance_tests.replace((span, Some(path))).is_some() {
+                        self.err("conformance_tests can only be enabled once", span);
+                    }
+                }
+                ("conformance_tests", _) => {
+                    self.err(
+                        r#"Expected: conformance_tests = "path/to/file.txt""#,
+                        name.span(),
+                    );
+                }
+                ("stats", NestedValue::Assign(value)) => {
+                    let span = value.span();
+                    let path = match syn::parse2::<LitStr>(value) {
+                        Ok(path) => path,
+                        Err(_) => {
+                            self.err(r#"Expected: stats = "path/to/file.txt""#, span);
+                            continue;
+                        }
+                    };
+
+                    if self.stats.replace(Some(path)).is_some() {
+                        self.err("stats can only be enabled once", span);
+                    }
+                }
+                ("stats", _) => {
+                    self.err(r#"Expected: stats = "path/to/file.txt""#, name.span());
+                }
+                ("recover_to", NestedValue::Assign(value)) => {
+                    let literal = match syn::parse2::<Lit>(value) {
+                        Ok(Lit::Str(string)) => Literal::Utf8(string),
+                        Ok(Lit::ByteStr(bytes)) => Literal::Bytes(bytes),
+                        Ok(lit) => {
+                            self.err("Expected a &str or &[u8] slice", lit.span());
+                            continue;
+                        }
+                        Err(err) => {
+                            self.err(err.to_string(), err.span());
+                            continue;
+                        }
+                    };
+
+                    if literal.to_bytes().is_empty() {
+                        self.err("Synchronization points can't be empty", literal.span());
+                        continue;
+                    }
+
+                    self.recover_to.push(literal);
+                }
+                ("recover_to", _) => {
+                    self.err(r#"Expected: recover_to = ";""#, name.span());
+                }
+                ("sentinel", NestedValue::Assign(value)) => {
+                    let bytes = match syn::parse2::<LitByteStr>(value) {
+                        Ok(bytes) => bytes,
+                        Err(err) => {
+                            self.err(err.to_string(), err.span());
+                            continue;
+                        }
+                    };
+
+                    if bytes.value().is_empty() {
+                        self.err("Expected at least one sentinel byte", bytes.span());
+                        continue;
+                    }
+
+                    self.sentinels.extend(bytes.value());
+                }
+                ("sentinel", _) => {
+                    self.err(r#"Expected: sentinel = b"\0""#, name.span());
+                }
+                ("verify_corpus", NestedValue::Assign(value)) => {
+                    let pattern = match syn::parse2::<LitStr>(value) {
+                        Ok(pattern) => pattern,
+                        Err(err) => {
+                            self.err(err.to_string(), err.span());
+                            continue;
+                        }
+                    };
+                    let span = pattern.span();
+
+                    if let Some(previous) = self.verify_corpus.replace(pattern) {
+                        self.err("The corpus can only be defined once", span)
+                            .err("Previous definition here", previous.span());
+                    }
+                }
+                ("verify_corpus", _) => {
+                    self.err(
+                        r#"Expected: verify_corpus = "path/to/corpus/*.src""#,
+                        name.span(),
+                    );
+                }
+                ("codegen", NestedValue::Assign(value)) => {
+                    let mode = match syn::parse2::<LitStr>(value) {
+                        Ok(mode) => mode,
+                        Err(err) => {
+                            self.err(err.to_string(), err.span());
+                            continue;
+                        }
+                    };
+
+                    self.codegen = match mode.value().as_str() {
+                        "fast" => Codegen::Fast,
+                        "compact" => Codegen::Compact,
+                        unknown => {
+                            self.err(
+                                format!(
+                                    r#"Unknown codegen mode "{}", expected "fast" or "compact""#,
+                                    unknown
+                                ),
+                                mode.span(),
+                            );
+                            continue;
+                        }
+                    };
+                }
+                ("codegen", _) => {
+                    self.err(r#"Expected: codegen = "compact""#, name.span());
+                }
+                ("invalid_utf8", NestedValue::Assign(value)) => {
+                    let policy = match syn::parse2::<LitStr>(value) {
+                        Ok(policy) => policy,
+                        Err(err) => {
+                            self.err(err.to_string(), err.span());
+                            continue;
+                        }
+                    };
+                    let span = policy.span();
+                    let policy = match policy.value().as_str() {
+                        "error" => InvalidUtf8::Error,
+                        "skip" => InvalidUtf8::Skip,
+                        "replace" => InvalidUtf8::Replace(span),
+                        unknown => {
+                            self.err(
+                                format!(
+                                    r#"Unknown invalid_utf8 policy "{}", expected "error", "skip" or "replace""#,
+                                    unknown
+                                ),
+                                span,
+                            );
+                            continue;
+                        }
+                    };
+
+                    if self.invalid_utf8.replace(policy).is_some() {
+                        self.err("The invalid_utf8 policy can only be defined once", span);
+                    }
+                }
+                ("invalid_utf8", _) => {
+                    self.err(r#"Expected: invalid_utf8 = "error""#, name.span());
+                }
+                ("jump_tables", NestedValue::Assign(value)) => {
+                    match syn::parse2::<LitBool>(value) {
+                        Ok(enabled) => self.strategy.jump_tables = enabled.value,
+                        Err(err) => {
+                            self.err(err.to_string(), err.span());
+                        }
+                    }
+                }
+                ("jump_tables", _) => {
+                    self.err("Expected: jump_tables = false", name.span());
+                }
+                ("lookup_tables", NestedValue::Assign(value)) => {
+                    match syn::parse2::<LitBool>(value) {
+                        Ok(enabled) => self.strategy.lookup_tables = enabled.value,
+                        Err(err) => {
+                            self.err(err.to_string(), err.span());
+                        }
+                    }
+                }
+                ("lookup_tables", _) => {
+                    self.err("Expected: lookup_tables = false", name.span());
+                }
+                ("unicode", NestedValue::Assign(value)) => match syn::parse2::<LitBool>(value) {
+                    Ok(unicode) => {
+                        if self.unicode.replace(unicode.value).is_some() {
+                            self.err("unicode can only be set once", unicode.span());
+                        }
+                    }
+                    Err(err) => {
+                        self.err(err.to_string(), err.span());
+                    }
+                },
+                ("unicode", _) => {
+                    self.err("Expected: unicode = true or unicode = false", name.span());
+                }
+                ("unroll", NestedValue::Assign(value)) => {
+                    let unroll = match syn::parse2::<LitInt>(value) {
+                        Ok(unroll) => unroll,
+                        Err(err) => {
+                            self.err(err.to_string(), err.span());
+                            continue;
+                        }
+                    };
+
+                    match unroll.base10_parse::<usize>() {
+                        Ok(n @ 1..=16) => self.strategy.unroll = n,
+                        _ => {
+                            self.err("Expected a number of bytes from 1 to 16", unroll.span());
+                        }
+                    }
+                }
+                ("unroll", _) => {
+                    self.err("Expected: unroll = 4", name.span());
+                }
+                ("literal_sets", NestedValue::Assign(value)) => {
+                    let literal_sets = match syn::parse2::<LitInt>(value) {
+                        Ok(literal_sets) => literal_sets,
+                        Err(err) => {
+                            self.err(err.to_string(), err.span());
+                            continue;
+                        }
+                    };
+
+                    match literal_sets.base10_parse::<usize>() {
+                        Ok(n) => self.strategy.literal_sets = n,
+                        Err(err) => {
+                            self.err(err.to_string(), err.span());
+                        }
+                    }
+                }
+                ("literal_sets", _) => {
+                    self.err("Expected: literal_sets = 256", name.span());
+                }
+                ("keyword_tables", NestedValue::Assign(value)) => {
+                    let keyword_tables = match syn::parse2::<LitInt>(value) {
+                        Ok(keyword_tables) => keyword_tables,
+                        Err(err) => {
+                            self.err(err.to_string(), err.span());
+                            continue;
+                        }
+                    };
+
+                    match keyword_tables.base10_parse::<usize>() {
+                        Ok(n) => self.strategy.keyword_tables = n,
+                        Err(err) => {
+                            self.err(err.to_string(), err.span());
+                        }
+                    }
+                }
+                ("keyword_tables", _) => {
+                    self.err("Expected: keyword_tables = 64", name.span());
+                }
+                ("max_inline", NestedValue::Assign(value)) => {
+                    let max_inline = match syn::parse2::<LitInt>(value) {
+                        Ok(max_inline) => max_inline,
+                        Err(err) => {
+                            self.err(err.to_string(), err.span());
+                            continue;
+                        }
+                    };
+
+                    match max_inline.base10_parse::<usize>() {
+                        Ok(n) => self.strategy.max_inline = n,
+                        Err(err) => {
+                            self.err(err.to_string(), err.span());
+                        }
+                    }
+                }
+                ("max_inline", _) => {
+                    self.err("Expected: max_inline = 256", name.span());
+                }
+                (unknown, _) => {
+                    self.err(
+                        format!("Unknown nested attribute: {}", unknown),
+                        name.span(),
+                    );
+                }
+            }
+        }
+    }
+
+    /// Parse the `#[logos(...)]` attribute of a variant into `settings`:
+    ///
+    /// + `#[logos(name = "...")]`
+    /// + `#[logos(eof)]`
+    pub fn parse_variant_logos(&mut self, attr: &mut Attribute, settings: &mut VariantLogos) {
+        let nested = match self.parse_attr(attr) {
+            Some(tokens) => tokens,
+            None => {
+                self.err("Expected #[logos(...)]", attr.span());
+                return;
+            }
+        };
+        for nested in nested {
+            match nested {
+                Nested::Named(name, NestedValue::Assign(value)) if name == "name" => {
+                    let value = match syn::parse2::<LitStr>(value) {
+                        Ok(value) => value,
+                        Err(err) => {
+                            self.err(err.to_string(), err.span());
+                            continue;
+                        }
+                    };
+                    let span = value.span();
+
+                    if let Some(previous) = settings.display_name.replace(value) {
+                        self.err("The display name can only be defined once", span)
+                            .err("Previous definition here", previous.span());
+                    }
+                }
+                Nested::Named(name, _) if name == "name" => {
+                    self.err(r#"Expected: name = "...""#, name.span());
+                }
+                Nested::Named(name, _) => {
+                    self.err(format!("Unknown nested attribute: {}", name), name.span());
+                }
+                Nested::Unnamed(tokens) if tokens.to_string() == "eof" => {
+                    if settings.eof.replace(tokens.span()).is_some() {
+                        self.err("A variant can only be marked as eof once", tokens.span());
+                    }
+                }
+                Nested::Unexpected(tokens) | Nested::Unnamed(tokens) => {
+                    self.err("Invalid nested attribute", tokens.span());
+                }
+            }
+        }
+    }
+
+    /// Parse attribute definition of a token:
+    ///
+    /// + `#[token(literal[, callback])]`
+    /// + `#[regex(literal[, callback])]`
+    pub fn parse_definition(&mut self, attr: &mut Attribute) -> Option<Definition> {
+        let mut nested = self.parse_attr(attr)?;
+
+        let literal = match nested.parsed::<Lit>()? {
+            Ok(lit) => match lit {
+                Lit::Str(string) => Literal::Utf8(string),
+                Lit::ByteStr(bytes) => {
+                    self.mode = Mode::Binary;
+
+                    Literal::Bytes(bytes)
+                }
+                _ => {
+                    self.err("Expected a &str or &[u8] slice", lit.span());
+
+                    return None;
+                }
+            },
+            Err(err) => {
+                self.err(err.to_string(), err.span());
+
+                return None;
+            }
+        };
+
+        let mut def = Definition::new(literal);
+
+        def.unicode = self.unicode;
+
+        for (position, next) in nested.enumerate() {
+            match next {
+                Nested::Unexpected(tokens) => {
+                    self.err("Unexpected token in attribute", tokens.span());
+                }
+                Nested::Unnamed(tokens) if tokens.to_string() == "contextual" => {
+                    if def.contextual {
+                        self.err("Token is already contextual", tokens.span());
+                    }
+
+                    def.contextual = true;
+                }
+                Nested::Unnamed(tokens) => match position {
+                    0 => def.callback = self.parse_callback(tokens),
+                    _ => {
+                        self.err(
+                            "\
+                            Expected a named argument at this position\n\n\
+                            hint: If you are trying to define a callback here use: callback = ...\
+                            ",
+                            tokens.span(),
+                        );
+                    }
+                },
+                Nested::Named(name, value) => {
+                    def.named_attr(name, value, self);
+                }
+            }
+        }
+
+        Some(def)
+    }
+
+    fn parse_callback(&mut self, tokens: TokenStream) -> Option<Callback> {
+        let span = tokens.span();
+        let mut tokens = tokens.into_iter();
+
+        if let Some(tt) = expect_punct(tokens.next(), '|') {
+            let mut label = TokenStream::from(tt);
+
+            label.extend(tokens);
+
+            return Some(Callback::Label(label));
+        }
+
+        let first = tokens.next();
+        let error = expect_punct(tokens.next(), '|');
+
+        let arg = match (error, first) {
+            (None, Some(TokenTree::Ident(arg))) => arg,
+            _ => {
+                self.err(
+                    "Inline callbacks must use closure syntax with exactly one parameter",
+                    span,
+                );
+                return None;
+            }
+        };
+
+        let body = match tokens.next() {
+            Some(TokenTree::Group(group)) => group.stream(),
+            Some(first) => {
+                let mut body = TokenStream::from(first);
+
+                body.extend(tokens);
+                body
+            }
+            None => {
+                self.err("Callback missing a body", span);
+                return None;
+            }
+        };
+
+        let inline = InlineCallback { arg, body, span };
+
+        Some(inline.into())
+    }
+
+    /// Checks if `ty` is a declared generic param, if so replaces it
+    /// with a concrete type defined using #[logos(type T = Type)]
+    ///
+    /// If no matching generic param is found, all lifetimes are fixed
+    /// to the source lifetime
+    pub fn get_type(&self, ty: &mut Type) -> TokenStream {
+        traverse_type(ty, &mut |ty| {
+            if let Type::Path(tp) = ty {
+                // Skip types that begin with `self::`
+                if tp.qself.is_none() {
+                    // If `ty` is a generic type parameter, try to find
+                    // its concrete type defined with #[logos(type T = Type)]
+                    if let Some(substitue) = self.types.find(&tp.path) {
+                        *ty = substitue;
+                    }
+                }
+            }
+            // If `ty` is a concrete type, fix its lifetimes to 'source
+            replace_lifetime(ty);
+        });
+
+        quote!(#ty)
+    }
+
+    pub fn err<M>(&mut self, message: M, span: Span) -> &mut Errors
+    where
+        M: Into<Cow<'static, str>>,
+    {
+        self.errors.err(message, span)
+    }
+}
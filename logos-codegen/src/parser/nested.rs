@@ -0,0 +1,240 @@
+use proc_macro2::token_stream::IntoIter as TokenIter;
+use proc_macro2::{Ident, Spacing, TokenStream, TokenTree};
+use quote::quote;
+
+use crate::util::{expect_punct, is_punct};
+
+pub enum NestedValue {
+    /// `name = ...`
+    Assign(TokenStream),
+    /// `name(...)`
+    Group(TokenStream),
+    /// `name ident = ...`
+    KeywordAssign(Ident, TokenStream),
+}
+
+pub enum Nested {
+    /// Unnamed nested attribute, such as a string,
+    /// callback closure, or a lone ident/path
+    ///
+    /// Note: a lone ident will be Named with no value instead
+    Unnamed(TokenStream),
+    /// Named: name ...
+    Named(Ident, NestedValue),
+    /// Unexpected token,
+    Unexpected(TokenStream),
+}
+
+pub struct AttributeParser {
+    inner: TokenIter,
+}
+
+pub struct Empty;
+
+impl From<Empty> for TokenStream {
+    fn from(_: Empty) -> TokenStream {
+        TokenStream::new()
+    }
+}
+
+impl AttributeParser {
+    pub fn new(stream: TokenStream) -> Self {
+        AttributeParser {
+            inner: stream.into_iter(),
+        }
+    }
+
+    pub fn parsed<T>(&mut self) -> Option<syn::Result<T>>
+    where
+        T: syn::parse::Parse,
+    {
+        let tokens = self.collect_tail(TokenStream::new());
+
+        if tokens.is_empty() {
+            return None;
+        }
+
+        Some(syn::parse2(tokens))
+    }
+
+    fn next_tt(&mut self) -> Option<TokenTree> {
+        expect_punct(self.inner.next(), ',')
+    }
+
+    /// Collect everything up to the next comma, except for commas in the generic arguments of a type, like
+    /// `Segmented<'s, [u8]>`.
+    fn collect_tail<T>(&mut self, first: T) -> TokenStream
+    where
+        T: Into<TokenStream>,
+    {
+        let mut out = first.into();
+        let mut prev = out.clone().into_iter().last();
+        let mut depth = 0usize;
+        // Closures can compare values, so a `<` in one can't be told apart from generic arguments.
+        let mut closure = None;
+
+        for tt in self.inner.by_ref() {
+            if depth == 0 && is_punct(&tt, ',') {
+                break;
+            }
+
+            let closure = *closure.get_or_insert_with(|| {
+                match out.clone().into_iter().next().as_ref().unwrap_or(&tt) {
+                    TokenTree::Punct(punct) => punct.as_char() == '|',
+                    TokenTree::Ident(ident) => ident == "move",
+                    _ => false,
+                }
+            });
+            let (after_path, after_arrow) = match &prev {
+                Some(TokenTree::Ident(_)) => (true, false),
+                Some(TokenTree::Punct(punct)) => (
+                    punct.as_char() == ':',
+                    matches!(punct.as_char(), '-' | '=') && punct.spacing() == Spacing::Joint,
+                ),
+                _ => (false, false),
+            };
+
+            if !closure {
+                // Only a `<` after a path starts generic arguments, and `->` or `=>` don't end them.
+                if is_any_punct(&tt, '<') && after_path {
+                    depth += 1;
+                } else if is_any_punct(&tt, '>') && depth > 0 && !after_arrow {
+                    depth -= 1;
+                }
+            }
+
+            prev = Some(tt.clone());
+            out.extend(Some(tt));
+        }
+
+        out
+    }
+
+    fn parse_unnamed(&mut self, first: Ident, next: TokenTree) -> Nested {
+        let mut out = TokenStream::from(TokenTree::Ident(first));
+
+        out.extend(self.collect_tail(next));
+
+        Nested::Unnamed(out.into_iter().collect())
+    }
+
+    fn parse_assign(&mut self, name: Ident) -> Nested {
+        let value = self.collect_tail(Empty);
+
+        Nested::Named(name, NestedValue::Assign(value))
+    }
+
+    fn parse_group(&mut self, name: Ident, group: TokenStream) -> Nested {
+        // The group has to be followed by a comma or the end of the attribute, which is consumed along with it.
+        let rest = self.collect_tail(Empty);
+
+        if !rest.is_empty() {
+            return Nested::Unexpected(rest);
+        }
+
+        Nested::Named(name, NestedValue::Group(group))
+    }
+
+    fn parse_keyword(&mut self, keyword: Ident, name: Ident) -> Nested {
+        let error = expect_punct(self.next_tt(), '=');
+
+        match error {
+            Some(error) => {
+                let error = self.collect_tail(error);
+
+                Nested::Unexpected(error)
+            }
+            None => {
+                let value = self.collect_tail(Empty);
+
+                Nested::Named(keyword, NestedValue::KeywordAssign(name, value))
+            }
+        }
+    }
+}
+
+impl Iterator for AttributeParser {
+    type Item = Nested;
+
+    fn next(&mut self) -> Option<Nested> {
+        let first = self.inner.next()?;
+
+        let name = match first {
+            TokenTree::Ident(ident) => ident,
+            tt => {
+                let stream = self.collect_tail(tt);
+
+                return Some(Nested::Unnamed(stream.into_iter().collect()));
+            }
+        };
+
+        match self.next_tt() {
+            Some(tt) if is_punct(&tt, '=') => Some(self.parse_assign(name)),
+            Some(TokenTree::Group(group)) => Some(self.parse_group(name, group.stream())),
+            Some(TokenTree::Ident(next)) => Some(self.parse_keyword(name, next)),
+            Some(next) => Some(self.parse_unnamed(name, next)),
+            None => Some(Nested::Unnamed(quote!(#name))),
+        }
+    }
+}
+
+/// Like [is_punct], but regardless of spacing.
+fn is_any_punct(tt: &TokenTree, expect: char) -> bool {
+    matches!(tt, TokenTree::Punct(punct) if punct.as_char() == expect)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values(stream: TokenStream) -> Vec<String> {
+        AttributeParser::new(stream)
+            .map(|nested| match nested {
+                Nested::Named(_, NestedValue::Assign(value)) => value.to_string(),
+                Nested::Unnamed(tokens) => tokens.to_string(),
+                _ => panic!("unexpected nested attribute"),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn commas_in_generic_arguments() {
+        assert_eq!(
+            values(quote!(source = Segmented<'s, [u8]>, extras = Vec<HashMap<u8, u8>>, error = E)),
+            &["Segmented < 's , [u8] >", "Vec < HashMap < u8 , u8 >>", "E"],
+        );
+        assert_eq!(
+            values(quote!(callback = foo::<A, B>, priority = 2)),
+            &["foo :: < A , B >", "2"],
+        );
+    }
+
+    #[test]
+    fn commas_after_groups() {
+        let names: Vec<String> =
+            AttributeParser::new(quote!(ignore(case), priority = 2, requires(A, !B)))
+                .map(|nested| match nested {
+                    Nested::Named(name, _) => name.to_string(),
+                    _ => panic!("unexpected nested attribute"),
+                })
+                .collect();
+
+        assert_eq!(names, &["ignore", "priority", "requires"]);
+    }
+
+    #[test]
+    fn commas_after_comparisons() {
+        assert_eq!(
+            values(quote!(|lex| lex.len() < 3, priority = 2)),
+            &["| lex | lex . len () < 3", "2"],
+        );
+        assert_eq!(
+            values(quote!(callback = |lex| n < 3 && n > 1, priority = 2)),
+            &["| lex | n < 3 && n > 1", "2"],
+        );
+        assert_eq!(
+            values(quote!(a = |x| -> u8 { 1 }, b = 2)),
+            &["| x | -> u8 { 1 }", "2"]
+        );
+    }
+}
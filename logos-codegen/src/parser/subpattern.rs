@@ -0,0 +1,159 @@
+use proc_macro2::TokenStream;
+use syn::{Ident, LitByteStr, LitStr};
+
+use crate::error::Errors;
+use crate::mir::Mir;
+use crate::parser::definition::{bytes_to_regex_string, Literal};
+use crate::parser::IgnoreFlags;
+
+#[derive(Default)]
+pub struct Subpatterns {
+    map: Vec<(Ident, Literal, IgnoreFlags)>,
+}
+
+impl Subpatterns {
+    pub fn add(&mut self, param: Ident, pattern: TokenStream, errors: &mut Errors) {
+        let lit = match syn::parse2::<Literal>(pattern) {
+            Ok(lit) => lit,
+            Err(e) => {
+                errors.err(e.to_string(), e.span());
+                return;
+            }
+        };
+
+        if let Some((name, ..)) = self.map.iter().find(|(name, ..)| *name == param) {
+            errors
+                .err(format!("{} can only be assigned once", param), param.span())
+                .err("Previously assigned here", name.span());
+            return;
+        }
+
+        // References to the subpatterns defined before this one are expanded right away.
+        let pattern = self.fix(&lit, errors);
+        let lit = match &lit {
+            Literal::Utf8(s) => Literal::Utf8(LitStr::new(&pattern, s.span())),
+            Literal::Bytes(b) => Literal::Bytes(LitByteStr::new(pattern.as_bytes(), b.span())),
+        };
+
+        // Validate the literal as proper regex. If it's not, error and manufacture a substitute.
+        let lit = match &lit {
+            Literal::Utf8(s) => match Mir::utf8(&s.value()) {
+                Ok(_) => lit,
+                Err(err) => {
+                    errors.err(err, lit.span());
+                    Literal::Utf8(LitStr::new(&param.to_string(), lit.span()))
+                }
+            },
+            Literal::Bytes(b) => {
+                let source = bytes_to_regex_string(b.value());
+                match Mir::binary(&source) {
+                    Ok(_) => lit,
+                    Err(err) => {
+                        errors.err(err, lit.span());
+                        Literal::Bytes(LitByteStr::new(param.to_string().as_bytes(), lit.span()))
+                    }
+                }
+            }
+        };
+
+        self.map.push((param, lit, IgnoreFlags::Empty));
+    }
+
+    /// Make the subpattern named `name` ignore case, using `flags`.
+    pub fn ignore(&mut self, name: &Ident, flags: IgnoreFlags, errors: &mut Errors) {
+        let (_, lit, ignore) = match self.map.iter_mut().find(|(def, ..)| def == name) {
+            Some(subpattern) => subpattern,
+            None => return,
+        };
+
+        *ignore = flags;
+
+        // Disabling Unicode can make a pattern invalid (for example, `.` could then match invalid UTF-8), so check
+        // that the subpattern still makes sense on its own.
+        let pattern = group(flags, &subpattern_string(lit));
+        let result = match lit {
+            Literal::Utf8(_) => Mir::utf8(&pattern),
+            Literal::Bytes(_) => Mir::binary(&pattern),
+        };
+
+        if let Err(err) = result {
+            errors.err(err, lit.span());
+        }
+    }
+
+    pub fn fix(&self, lit: &Literal, errors: &mut Errors) -> String {
+        let mut i = 0;
+        let mut pattern = subpattern_string(lit);
+
+        while let Some(f) = pattern[i..].find("(?&") {
+            i += f;
+
+            let subref_end = if let Some(f) = pattern[i + 3..].find(')') {
+                i + 3 + f
+            } else {
+                pattern.replace_range(i..i + 3, "(?:");
+                pattern.truncate(i + 3); // truncate so latter error doesn't suppress
+                break; // regex-syntax will report the unclosed group
+            };
+
+            let name = &pattern[i + 3..subref_end];
+            let name = match syn::parse_str::<Ident>(name) {
+                Ok(name) => name,
+                Err(_) => {
+                    errors.err(
+                        format!("subpattern reference `{}` is not an identifier", name),
+                        lit.span(),
+                    );
+                    // we emitted the error; make something up and continue
+                    pattern.replace_range(i..subref_end, "(?:_");
+                    i += 5;
+                    continue;
+                }
+            };
+
+            match self.map.iter().find(|(def, ..)| *def == name) {
+                Some((_, val, flags)) => {
+                    let subpattern = group(*flags, &subpattern_string(val));
+
+                    // The closing parenthesis of the reference closes the group.
+                    pattern.replace_range(i..subref_end, &subpattern[..subpattern.len() - 1]);
+                    i += subpattern.len();
+                }
+                None => {
+                    errors.err(
+                        format!("subpattern reference `{}` has not been defined", name),
+                        lit.span(),
+                    );
+                    // leaving `(?:name)` is fine
+                    pattern.replace_range(i..i + 3, "(?:");
+                    i = subref_end + 1;
+                }
+            }
+        }
+
+        pattern
+    }
+}
+
+fn subpattern_string(lit: &Literal) -> String {
+    match lit {
+        Literal::Utf8(s) => s.value(),
+        Literal::Bytes(b) => bytes_to_regex_string(b.value()),
+    }
+}
+
+/// Wrap `pattern` in a non-capturing group that applies `flags` to it.
+///
+/// Case is ignored using the `i` flag. For `ascii_case`, Unicode is also disabled within the group, which limits case
+/// folding to ASCII letters, in the same way as for byte patterns.
+fn group(flags: IgnoreFlags, pattern: &str) -> String {
+    let prefix = if flags.contains(IgnoreFlags::IgnoreAsciiCase) {
+        "(?i-u:"
+    } else if flags.contains(IgnoreFlags::IgnoreCase) {
+        "(?i:"
+    } else {
+        "(?:"
+    };
+
+    format!("{}{})", prefix, pattern)
+}
@@ -1,7 +1,7 @@
-use proc_macro2::{Ident, Span, TokenStream};
-use quote::quote;
+use proc_macro2::{Ident, Span, TokenStream, TokenTree};
+use quote::{quote, ToTokens};
 use syn::spanned::Spanned;
-use syn::{Lifetime, LifetimeDef, Path, Type};
+use syn::{Lifetime, LifetimeDef, Path, Type, TypeParam, WhereClause};
 
 use crate::error::Errors;
 
@@ -9,6 +9,46 @@ use crate::error::Errors;
 pub struct TypeParams {
     lifetime: bool,
     type_params: Vec<(Ident, Option<Type>)>,
+    /// Bounds on the type parameters, from their declarations and the enum's `where` clause.
+    bounds: Vec<TokenStream>,
+}
+
+/// The type parameters that don't have a concrete type, which the implementation is generic over.
+///
+/// Functions generated inside of the implementation can't use its type parameters, so they have to declare (and
+/// bound) them again. With no type parameters, these are just `<'s>`, `__LogosLexer<'s>` and nothing.
+#[derive(Default)]
+pub struct Generic {
+    params: Vec<Ident>,
+    bounds: Vec<TokenStream>,
+}
+
+impl Generic {
+    pub fn is_empty(&self) -> bool {
+        self.params.is_empty()
+    }
+
+    /// Generic parameters for an item that's also generic over the source lifetime: `<'s, T>`.
+    pub fn params(&self) -> TokenStream {
+        let params = &self.params;
+
+        quote!(<'s #(, #params)*>)
+    }
+
+    /// The lexer type inside of generated functions: `__LogosLexer<'s, T>`.
+    pub fn lexer(&self) -> TokenStream {
+        let params = &self.params;
+
+        quote!(__LogosLexer<'s #(, #params)*>)
+    }
+
+    /// The `where` clause with the bounds on the type parameters, if there are any.
+    pub fn where_clause(&self) -> TokenStream {
+        match self.bounds.as_slice() {
+            [] => quote!(),
+            bounds => quote!(where #(#bounds,)*),
+        }
+    }
 }
 
 impl TypeParams {
@@ -22,8 +62,23 @@ impl TypeParams {
         self.lifetime = true;
     }
 
-    pub fn add(&mut self, param: Ident) {
-        self.type_params.push((param, None));
+    pub fn add(&mut self, param: TypeParam) {
+        if !param.bounds.is_empty() {
+            let ident = &param.ident;
+            let bounds = &param.bounds;
+
+            self.bounds
+                .push(replace_lifetime_tokens(quote!(#ident: #bounds)));
+        }
+
+        self.type_params.push((param.ident, None));
+    }
+
+    pub fn where_clause(&mut self, clause: WhereClause) {
+        for predicate in clause.predicates {
+            self.bounds
+                .push(replace_lifetime_tokens(predicate.into_token_stream()));
+        }
     }
 
     pub fn set(&mut self, param: Ident, ty: TokenStream, errors: &mut Errors) {
@@ -68,7 +123,7 @@ impl TypeParams {
         None
     }
 
-    pub fn generics(&self, errors: &mut Errors) -> Option<TokenStream> {
+    pub fn generics(&self) -> Option<TokenStream> {
         if !self.lifetime && self.type_params.is_empty() {
             return None;
         }
@@ -79,28 +134,90 @@ impl TypeParams {
             generics.push(quote!('s));
         }
 
-        for (ty, replace) in self.type_params.iter() {
+        for (param, replace) in self.type_params.iter() {
             match replace {
                 Some(ty) => generics.push(quote!(#ty)),
-                None => {
-                    errors.err(
-                        format!(
-                            "Generic type parameter without a concrete type\n\n\
-                            Define a concrete type Logos can use: #[logos(type {} = Type)]",
-                            ty,
-                        ),
-                        ty.span(),
-                    );
-                }
+                // Type parameters without a concrete type are left generic.
+                None => generics.push(quote!(#param)),
             }
         }
 
-        if generics.is_empty() {
-            None
-        } else {
-            Some(quote!(<#(#generics),*>))
+        Some(quote!(<#(#generics),*>))
+    }
+
+    /// The type parameters without a concrete type, and the bounds that don't mention any of the type parameters that
+    /// have been given one.
+    pub fn generic(&self) -> Generic {
+        let mut params = Vec::new();
+        let mut replaced = Vec::new();
+
+        for (param, ty) in &self.type_params {
+            match ty {
+                Some(_) => replaced.push(param),
+                None => params.push(param.clone()),
+            }
+        }
+
+        if params.is_empty() {
+            return Generic::default();
+        }
+
+        let bounds = self
+            .bounds
+            .iter()
+            .filter(|bound| !replaced.iter().any(|param| mentions(bound, param)))
+            .cloned()
+            .collect();
+
+        Generic { params, bounds }
+    }
+}
+
+fn mentions(tokens: &TokenStream, ident: &Ident) -> bool {
+    tokens.clone().into_iter().any(|tt| match tt {
+        TokenTree::Ident(other) => other == *ident,
+        TokenTree::Group(group) => mentions(&group.stream(), ident),
+        _ => false,
+    })
+}
+
+/// Replace every lifetime other than `'static` with `'s`, which is what the enum's lifetime is called in the
+/// implementation.
+fn replace_lifetime_tokens(tokens: TokenStream) -> TokenStream {
+    let mut out = TokenStream::new();
+    let mut tokens = tokens.into_iter();
+
+    while let Some(tt) = tokens.next() {
+        match tt {
+            TokenTree::Punct(punct) if punct.as_char() == '\'' => {
+                let lifetime = match tokens.next() {
+                    Some(TokenTree::Ident(ident)) if ident != "static" => {
+                        Lifetime::new("'s", ident.span())
+                    }
+                    Some(TokenTree::Ident(ident)) => Lifetime::new("'static", ident.span()),
+                    other => {
+                        out.extend([TokenTree::Punct(punct)]);
+                        out.extend(other);
+                        continue;
+                    }
+                };
+
+                lifetime.to_tokens(&mut out);
+            }
+            TokenTree::Group(group) => {
+                let mut replaced = proc_macro2::Group::new(
+                    group.delimiter(),
+                    replace_lifetime_tokens(group.stream()),
+                );
+
+                replaced.set_span(group.span());
+                out.extend([TokenTree::Group(replaced)]);
+            }
+            tt => out.extend([tt]),
         }
     }
+
+    out
 }
 
 pub fn replace_lifetimes(ty: &mut Type) {
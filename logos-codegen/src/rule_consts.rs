@@ -0,0 +1,51 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::Ident;
+
+use crate::leaf::Leaf;
+use crate::parser::Generic;
+
+/// Generate a `RULE_{Variant}` constant for the definition of every variant, for callbacks to return in
+/// `Output::Reparse`. Variants with more than one definition get a `RULE_{Variant}_{n}` constant for each of them
+/// instead. `unterminated` are the rules that the derive added itself, which aren't named.
+pub fn generate(
+    name: &Ident,
+    this: &TokenStream,
+    generic: &Generic,
+    rules: &[Leaf],
+    descriptions: &[String],
+    unterminated: &[usize],
+) -> TokenStream {
+    let declared: Vec<&Leaf> = rules
+        .iter()
+        .filter(|leaf| !unterminated.contains(&leaf.rule))
+        .collect();
+    let consts = declared.iter().map(|leaf| {
+        let ident = leaf.ident;
+        let constant = match declared.iter().filter(|other| other.ident == ident).count() {
+            1 => format_ident!("RULE_{}", ident),
+            _ => format_ident!("RULE_{}_{}", ident, leaf.variant_rule),
+        };
+        let doc = format!(
+            "The `{}` definition of [`{}::{}`].",
+            descriptions[leaf.rule], name, ident,
+        );
+        let rule = leaf.rule;
+
+        quote! {
+            #[doc = #doc]
+            pub const #constant: ::logos::callback::Rule<Self> = ::logos::callback::Rule::from_index(#rule);
+        }
+    });
+
+    let params = generic.params();
+    let where_clause = generic.where_clause();
+
+    quote! {
+        #[automatically_derived]
+        #[allow(non_upper_case_globals, dead_code)]
+        impl #params #this #where_clause {
+            #(#consts)*
+        }
+    }
+}
@@ -0,0 +1,205 @@
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote};
+use syn::Ident;
+
+use crate::captures::Program;
+use crate::generator::Generator;
+use crate::graph::{Fork, Graph, Rope};
+use crate::leaf::{Callback, Leaf};
+use crate::parser::Generic;
+use crate::test_match::Pattern;
+use crate::util::MaybeVoid;
+
+/// Generate `sniff` and `sniff_skipping`, which match a single token at the start of a source with a state machine of
+/// their own. It has a unit variant for every definition and no extras or hooks, so that no lexer of the token type is
+/// ever built.
+pub fn generate(
+    this: &TokenStream,
+    generic: &Generic,
+    source: &TokenStream,
+    rules: &[Leaf],
+    patterns: &[Pattern],
+) -> TokenStream {
+    let name = Ident::new("__LogosSniff", Span::call_site());
+    // Contextual definitions are disabled in a new lexer, so they're left out.
+    let rules: Vec<(&Leaf, &Pattern)> = rules
+        .iter()
+        .zip(patterns)
+        .filter(|(leaf, _)| leaf.contextual.is_none())
+        .collect();
+    let variants: Vec<Ident> = (0..rules.len())
+        .map(|index| format_ident!("__Rule{}", index))
+        .collect();
+
+    let arms = rules.iter().zip(&variants).map(|((leaf, _), variant)| {
+        let ident = leaf.ident;
+        let token = match (&leaf.callback, &leaf.field) {
+            (Some(Callback::Label(label)), _) if is_skip(label) => {
+                return quote! {
+                    #name::#variant if skipping => continue,
+                    #name::#variant => return ::core::option::Option::None,
+                };
+            }
+            // Other callbacks need the lexer of the token type, so their tokens can't be sniffed.
+            (Some(_), _) => quote!(return ::core::option::Option::None),
+            (None, MaybeVoid::Void) => quote!(Self::#ident),
+            (None, MaybeVoid::Some(_)) => {
+                quote!(Self::#ident(::logos::internal::FromSlice::from_slice(&lex)))
+            }
+        };
+
+        quote!(#name::#variant => #token,)
+    });
+
+    let mut graph = Graph::new();
+    let mut regex_ids = Vec::new();
+    let mut ropes = Vec::new();
+    let mut leaves = Vec::new();
+
+    for (index, ((leaf, pattern), variant)) in rules.iter().zip(&variants).enumerate() {
+        // Only the pattern (and its checks) matters here, so the leaf is stripped down to a plain unit variant.
+        let leaf = Leaf {
+            assertions: leaf.assertions.clone(),
+            lookahead: leaf.lookahead.clone(),
+            not_followed_by: leaf.not_followed_by.clone(),
+            not_preceded_by: leaf.not_preceded_by.clone(),
+            preceded_by: leaf.preceded_by.clone(),
+            requires: leaf.requires.clone(),
+            ..Leaf::new(variant, leaf.span)
+                .priority(leaf.priority)
+                .field(MaybeVoid::Void)
+                .rule(index)
+        };
+        let then = graph.push(leaf.clone());
+
+        leaves.push(leaf);
+
+        match pattern {
+            Pattern::Bytes(bytes) => ropes.push(Rope::new(bytes.clone(), then)),
+            Pattern::Regex(mir) => regex_ids.push(graph.regex(mir.clone(), then)),
+        }
+    }
+
+    let mut root = Fork::new();
+
+    crate::merge_root(&mut root, &mut graph, regex_ids, ropes);
+
+    let root = graph.push(root);
+
+    graph.shake(root);
+
+    let helper = quote!(#name);
+    let no_generics = Generic::default();
+    let mut generator = Generator::new(&name, &helper, &no_generics, root, &graph);
+    let back_off = match leaves.iter().any(|leaf| leaf.not_followed_by.is_some()) {
+        true => {
+            let mut order: Vec<usize> = (0..rules.len()).collect();
+
+            order.sort_by_key(|&rule| std::cmp::Reverse(rules[rule].0.priority));
+
+            let programs = order.into_iter().map(|rule| {
+                let program = Program::lookahead(&rules[rule].1.to_mir());
+
+                quote!((#rule, #program))
+            });
+
+            quote! {
+                static __LOGOS_BACK_OFF: &[(usize, ::logos::internal::CaptureProgram)] = &[#(#programs),*];
+            }
+        }
+        false => quote!(),
+    };
+    let reparse = match back_off.is_empty() {
+        true => quote!(),
+        false => {
+            let prelude = crate::prelude(&helper, &no_generics, back_off.clone());
+            let reparse = generator.generate_reparse(&leaves);
+
+            quote! {
+                fn reparse(lex: &mut ::logos::Lexer<'s, Self>, __logos_rule: usize) {
+                    #prelude
+
+                    #reparse
+                }
+            }
+        }
+    };
+    let body = generator.generate();
+    let prelude = crate::prelude(&helper, &no_generics, back_off);
+    let handlers = crate::handlers(
+        &no_generics,
+        quote!(lex.end()),
+        quote!(lex.error();),
+        quote!(),
+    );
+
+    let params = generic.params();
+    let where_clause = generic.where_clause();
+
+    quote! {
+        #[automatically_derived]
+        #[allow(non_camel_case_types, dead_code)]
+        impl #params #this #where_clause {
+            /// Match a single token at the very start of `source`, returning it along with its length.
+            ///
+            /// Returns `None` if `source` doesn't start with a token, or starts with one that's skipped or made by a
+            /// callback.
+            pub fn sniff(source: &'s #source) -> ::core::option::Option<(Self, usize)> {
+                match Self::__logos_sniff(source, false) {
+                    ::core::option::Option::Some((token, span)) => ::core::option::Option::Some((token, span.end)),
+                    ::core::option::Option::None => ::core::option::Option::None,
+                }
+            }
+
+            /// Like `sniff`, but skips over any tokens that are skipped with `logos::skip` first, and returns the
+            /// span of the first token.
+            pub fn sniff_skipping(source: &'s #source) -> ::core::option::Option<(Self, ::logos::Span)> {
+                Self::__logos_sniff(source, true)
+            }
+
+            fn __logos_sniff(source: &'s #source, skipping: bool) -> ::core::option::Option<(Self, ::logos::Span)> {
+                enum #name {
+                    #(#variants,)*
+                }
+
+                impl<'s> ::logos::Logos<'s> for #name {
+                    type Extras = ();
+
+                    type Source = #source;
+
+                    type Error = ::logos::UnknownToken;
+
+                    fn lex(lex: &mut ::logos::Lexer<'s, Self>) {
+                        #prelude
+                        #handlers
+
+                        #body
+                    }
+
+                    #reparse
+                }
+
+                let mut lex = ::logos::Lexer::<#name>::new(source);
+
+                loop {
+                    let rule = match lex.next() {
+                        ::core::option::Option::Some(::core::result::Result::Ok(rule)) => rule,
+                        _ => return ::core::option::Option::None,
+                    };
+                    let token = match rule {
+                        #(#arms)*
+                    };
+
+                    return ::core::option::Option::Some((token, lex.span()));
+                }
+            }
+        }
+    }
+}
+
+/// Whether a callback is `logos::skip`, which makes a definition trivia.
+fn is_skip(label: &TokenStream) -> bool {
+    let path = label.to_string().replace(' ', "");
+
+    path == "logos::skip" || path == "::logos::skip"
+}
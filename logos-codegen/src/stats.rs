@@ -0,0 +1,93 @@
+use std::fmt::{self, Display, Formatter};
+
+/// The size of the state machine generated for a token type, reported with `#[logos(stats)]`.
+#[derive(Default, Debug, PartialEq, Eq)]
+pub struct Stats {
+    /// The number of variants in the enum.
+    pub variants: usize,
+    /// The number of states reachable from the root, which are either forks, ropes (sequences of bytes) or leaves.
+    pub states: usize,
+    pub forks: usize,
+    pub ropes: usize,
+    pub leaves: usize,
+    /// The number of edges between states, including the ones taken when nothing else matches.
+    pub transitions: usize,
+    /// The size of the jump tables, lookup tables and compact tables in the generated code.
+    pub table_bytes: usize,
+    /// The number of functions generated for the states, which is `0` for compact code.
+    pub functions: usize,
+    /// The number of tokens in the generated code, which grows with the size of the compiled code.
+    pub tokens: usize,
+}
+
+impl Stats {
+    /// Count the tokens in `stream`, including the ones inside groups.
+    pub fn count_tokens(stream: proc_macro2::TokenStream) -> usize {
+        stream
+            .into_iter()
+            .map(|tree| match tree {
+                proc_macro2::TokenTree::Group(group) => 2 + Stats::count_tokens(group.stream()),
+                _ => 1,
+            })
+            .sum()
+    }
+}
+
+impl Display for Stats {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(f, "variants:    {}", self.variants)?;
+        writeln!(
+            f,
+            "states:      {} ({} forks, {} ropes, {} leaves)",
+            self.states, self.forks, self.ropes, self.leaves
+        )?;
+        writeln!(f, "transitions: {}", self.transitions)?;
+        writeln!(f, "table bytes: {}", self.table_bytes)?;
+        writeln!(f, "functions:   {}", self.functions)?;
+        writeln!(f, "code tokens: {}", self.tokens)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use quote::quote;
+
+    #[test]
+    fn counts_tokens() {
+        assert_eq!(Stats::count_tokens(quote!()), 0);
+        // Groups count for their delimiters too.
+        assert_eq!(
+            Stats::count_tokens(quote!(
+                fn foo(a: u8) {}
+            )),
+            9
+        );
+    }
+
+    #[test]
+    fn display() {
+        let stats = Stats {
+            variants: 2,
+            states: 4,
+            forks: 1,
+            ropes: 1,
+            leaves: 2,
+            transitions: 3,
+            table_bytes: 256,
+            functions: 4,
+            tokens: 1000,
+        };
+
+        assert_eq!(
+            stats.to_string(),
+            "variants:    2\n\
+             states:      4 (1 forks, 1 ropes, 2 leaves)\n\
+             transitions: 3\n\
+             table bytes: 256\n\
+             functions:   4\n\
+             code tokens: 1000\n"
+        );
+    }
+}
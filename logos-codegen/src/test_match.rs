@@ -0,0 +1,143 @@
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote};
+use syn::Ident;
+
+use crate::generator::Generator;
+use crate::graph::{Fork, Graph, Rope};
+use crate::leaf::Leaf;
+use crate::mir::{Literal, Mir};
+use crate::parser::Generic;
+use crate::util::MaybeVoid;
+
+/// The pattern of a single definition, kept around so that it can be matched on its own, or compared with other
+/// definitions when they conflict.
+pub enum Pattern {
+    Bytes(Vec<u8>),
+    Regex(Mir),
+}
+
+impl Pattern {
+    pub fn to_mir(&self) -> Mir {
+        match self {
+            Pattern::Bytes(bytes) => Mir::Concat(
+                bytes
+                    .iter()
+                    .map(|&byte| Mir::Literal(Literal::Byte(byte)))
+                    .collect(),
+            ),
+            Pattern::Regex(mir) => mir.clone(),
+        }
+    }
+}
+
+/// Generate a `test_match_{Variant}` function for every variant with definitions, which returns the length of the
+/// longest match of just that variant's definitions at the start of the source.
+pub fn generate(
+    this: &TokenStream,
+    generic: &Generic,
+    source: &TokenStream,
+    rules: Vec<(Leaf, Pattern)>,
+) -> TokenStream {
+    let mut variants: Vec<(&Ident, Vec<(Leaf, Pattern)>)> = Vec::new();
+
+    for (leaf, pattern) in rules {
+        match variants.iter_mut().find(|(ident, _)| *ident == leaf.ident) {
+            Some((_, rules)) => rules.push((leaf, pattern)),
+            None => variants.push((leaf.ident, vec![(leaf, pattern)])),
+        }
+    }
+
+    let name = Ident::new("__LogosTestMatch", Span::call_site());
+    let prelude = crate::prelude(&quote!(#name), &Generic::default(), quote!());
+    let handlers = crate::handlers(
+        &Generic::default(),
+        quote!(lex.end()),
+        quote!(lex.error();),
+        quote!(),
+    );
+    let fns = variants.into_iter().map(|(ident, rules)| {
+        let body = generate_lexer(&name, rules);
+        let test_match = format_ident!("test_match_{}", ident);
+        let doc = format!(
+            "Returns the length of the longest match of the definitions of `{}` alone at the start of `source`.",
+            ident,
+        );
+
+        quote! {
+            #[doc = #doc]
+            pub fn #test_match(source: &#source) -> ::core::option::Option<usize> {
+                enum #name {
+                    #ident,
+                }
+
+                impl<'s> ::logos::Logos<'s> for #name {
+                    type Extras = ();
+
+                    type Source = #source;
+
+                    type Error = ::logos::UnknownToken;
+
+                    fn lex(lex: &mut ::logos::Lexer<'s, Self>) {
+                        #prelude
+                        #handlers
+
+                        #body
+                    }
+                }
+
+                let mut lex = ::logos::Lexer::<#name>::new(source);
+
+                match lex.next() {
+                    ::core::option::Option::Some(::core::result::Result::Ok(_)) => ::core::option::Option::Some(lex.span().end),
+                    _ => ::core::option::Option::None,
+                }
+            }
+        }
+    });
+
+    let params = generic.params();
+    let where_clause = generic.where_clause();
+
+    quote! {
+        #[automatically_derived]
+        #[allow(non_snake_case, dead_code)]
+        impl #params #this #where_clause {
+            #(#fns)*
+        }
+    }
+}
+
+fn generate_lexer<'a>(name: &'a Ident, rules: Vec<(Leaf<'a>, Pattern)>) -> TokenStream {
+    let mut graph = Graph::new();
+    let mut regex_ids = Vec::new();
+    let mut ropes = Vec::new();
+
+    for (leaf, pattern) in rules {
+        // Only the pattern (and its checks) matters here, so the leaf is stripped down to a plain unit variant.
+        let leaf = Leaf {
+            assertions: leaf.assertions,
+            lookahead: leaf.lookahead,
+            ..Leaf::new(leaf.ident, leaf.span)
+                .priority(leaf.priority)
+                .field(MaybeVoid::Void)
+        };
+        let then = graph.push(leaf);
+
+        match pattern {
+            Pattern::Bytes(bytes) => ropes.push(Rope::new(bytes, then)),
+            Pattern::Regex(mir) => regex_ids.push(graph.regex(mir, then)),
+        }
+    }
+
+    let mut root = Fork::new();
+
+    crate::merge_root(&mut root, &mut graph, regex_ids, ropes);
+
+    let root = graph.push(root);
+
+    graph.shake(root);
+
+    let this = quote!(#name);
+
+    Generator::new(name, &this, &Generic::default(), root, &graph).generate()
+}
@@ -4,18 +4,13 @@ use syn::Ident;
 
 /// Analog to Option<TokenStream>, except when put into the quote!
 /// macro, `MaybeVoid::Void` will produce `()`
-#[derive(Clone)]
+#[derive(Clone, Default)]
 pub enum MaybeVoid {
     Some(TokenStream),
+    #[default]
     Void,
 }
 
-impl Default for MaybeVoid {
-    fn default() -> MaybeVoid {
-        MaybeVoid::Void
-    }
-}
-
 impl MaybeVoid {
     pub fn replace(&mut self, stream: TokenStream) -> MaybeVoid {
         std::mem::replace(self, MaybeVoid::Some(stream))
@@ -50,14 +45,10 @@ impl ToTokens for MaybeVoid {
 }
 
 pub fn is_punct(tt: &TokenTree, expect: char) -> bool {
-    match tt {
-        TokenTree::Punct(punct)
-            if punct.as_char() == expect && punct.spacing() == Spacing::Alone =>
-        {
-            true
-        }
-        _ => false,
-    }
+    matches!(
+        tt,
+        TokenTree::Punct(punct) if punct.as_char() == expect && punct.spacing() == Spacing::Alone
+    )
 }
 
 /// If supplied `tt` is a punct matching a char, returns `None`, else returns `tt`
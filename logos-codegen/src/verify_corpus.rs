@@ -0,0 +1,217 @@
+use std::io;
+use std::path::{Path, PathBuf};
+
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote, quote_spanned};
+use syn::{Ident, LitStr};
+
+use crate::error::Errors;
+
+/// Generate a module with a `#[test]` that lexes every file matching the `verify_corpus` pattern, and fails if any of
+/// them produce an error.
+pub fn generate(
+    name: &Ident,
+    source: &TokenStream,
+    pattern: &LitStr,
+    errors: &mut Errors,
+) -> TokenStream {
+    let utf8 = match source.to_string().as_str() {
+        "str" => true,
+        "[u8]" => false,
+        _ => {
+            errors.err(
+                "verify_corpus can only be used with `str` or `[u8]` sources",
+                pattern.span(),
+            );
+            return TokenStream::new();
+        }
+    };
+
+    let root = match std::env::var_os("CARGO_MANIFEST_DIR") {
+        Some(dir) => PathBuf::from(dir),
+        None => PathBuf::new(),
+    };
+
+    let files = match expand(&root, &pattern.value()) {
+        Ok(files) if files.is_empty() => {
+            errors.err(
+                format!("No files match the corpus pattern {:?}", pattern.value()),
+                pattern.span(),
+            );
+            return TokenStream::new();
+        }
+        Ok(files) => files,
+        Err(err) => {
+            errors.err(
+                format!("Failed to read the corpus: {}", err),
+                pattern.span(),
+            );
+            return TokenStream::new();
+        }
+    };
+
+    let corpus = files.iter().map(|file| {
+        let display = file
+            .strip_prefix(&root)
+            .unwrap_or(file)
+            .display()
+            .to_string();
+        let absolute = file.display().to_string();
+
+        // Including the files makes the test rebuild whenever one of them changes.
+        quote!((#display, include_bytes!(#absolute) as &[u8]))
+    });
+
+    let source = match utf8 {
+        true => quote! {
+            match ::core::str::from_utf8(bytes) {
+                ::core::result::Result::Ok(source) => source,
+                ::core::result::Result::Err(err) => {
+                    failures.push(::std::format!("{}: {}", path, err));
+                    continue;
+                }
+            }
+        },
+        false => quote!(bytes),
+    };
+    let test = format_ident!("__logos_verify_corpus_{}", name);
+    let import = quote_spanned!(pattern.span()=> use super::#name;);
+
+    // The test is put in a module that imports the enum from its parent. The test harness can't collect tests declared
+    // inside a function body, so for an enum declared in one the import fails to resolve instead of the test silently
+    // never running. The module exists outside of `cfg(test)` so that the error doesn't only show up in test builds.
+    quote! {
+        #[doc(hidden)]
+        #[allow(non_snake_case)]
+        mod #test {
+            #[allow(unused_imports)]
+            #import
+
+            #[cfg(test)]
+            #[test]
+            fn verify_corpus() {
+                const CORPUS: &[(&str, &[u8])] = &[#(#corpus),*];
+
+                let mut failures = ::std::vec::Vec::<::std::string::String>::new();
+
+                for &(path, bytes) in CORPUS {
+                    let source = #source;
+                    let mut lex = <#name as ::logos::Logos>::lexer(source);
+
+                    while let ::core::option::Option::Some(token) = ::core::iter::Iterator::next(&mut lex) {
+                        if ::core::result::Result::is_err(&token) {
+                            let span = lex.span();
+                            let before = &bytes[..span.start];
+                            let line = before.iter().filter(|&&byte| byte == b'\n').count() + 1;
+                            let column = match before.iter().rposition(|&byte| byte == b'\n') {
+                                ::core::option::Option::Some(newline) => span.start - newline,
+                                ::core::option::Option::None => span.start + 1,
+                            };
+
+                            failures.push(::std::format!(
+                                "{}:{}:{}: unexpected {:?}",
+                                path,
+                                line,
+                                column,
+                                lex.slice(),
+                            ));
+                        }
+                    }
+                }
+
+                if !failures.is_empty() {
+                    ::core::panic!(
+                        "{} error(s) while lexing the corpus:\n{}",
+                        failures.len(),
+                        failures.join("\n"),
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// Find all files under `root` matching `pattern`, in sorted order.
+///
+/// Each component of the pattern can use `*` to match any number of characters, and `?` to match a single
+/// character.
+fn expand(root: &Path, pattern: &str) -> io::Result<Vec<PathBuf>> {
+    let mut paths = vec![root.to_path_buf()];
+
+    for component in pattern.split(['/', '\\']).filter(|c| !c.is_empty()) {
+        let mut next = Vec::new();
+
+        for path in paths {
+            if !component.contains(['*', '?']) {
+                let path = path.join(component);
+
+                if path.exists() {
+                    next.push(path);
+                }
+                continue;
+            }
+
+            if !path.is_dir() {
+                continue;
+            }
+
+            for entry in std::fs::read_dir(&path)? {
+                let entry = entry?;
+
+                if let Some(name) = entry.file_name().to_str() {
+                    if matches(component.as_bytes(), name.as_bytes()) {
+                        next.push(entry.path());
+                    }
+                }
+            }
+        }
+
+        paths = next;
+    }
+
+    paths.retain(|path| path.is_file());
+    paths.sort();
+
+    Ok(paths)
+}
+
+/// Check whether `name` matches a single component of a glob pattern.
+fn matches(pattern: &[u8], name: &[u8]) -> bool {
+    match (pattern.split_first(), name.split_first()) {
+        (None, None) => true,
+        (Some((b'*', rest)), _) => {
+            matches(rest, name) || (!name.is_empty() && matches(pattern, &name[1..]))
+        }
+        (Some((b'?', rest)), Some((_, name))) => matches(rest, name),
+        (Some((expected, rest)), Some((byte, name))) => expected == byte && matches(rest, name),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wildcards() {
+        assert!(matches(b"*.src", b"hello.src"));
+        assert!(matches(b"*.src", b".src"));
+        assert!(matches(b"a?c", b"abc"));
+        assert!(matches(b"*", b"anything"));
+        assert!(matches(b"a*b*c", b"aXXbYYc"));
+        assert!(!matches(b"*.src", b"hello.src.bak"));
+        assert!(!matches(b"a?c", b"ac"));
+        assert!(!matches(b"abc", b"abd"));
+    }
+
+    #[test]
+    fn expands_relative_to_root() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+        let files = expand(root, "src/parser/*.rs").unwrap();
+
+        assert!(files.contains(&root.join("src/parser/mod.rs")));
+        assert!(files.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!(files.iter().all(|file| file.extension().unwrap() == "rs"));
+        assert!(expand(root, "src/*/nope.*").unwrap().is_empty());
+    }
+}
@@ -0,0 +1,187 @@
+use crate::captures::Program;
+use crate::error::Errors;
+use crate::leaf::Leaf;
+use crate::parser::Literal;
+use crate::test_match::Pattern;
+
+/// Check that each witness given with `disambiguate_with` is lexed as a single token by the definition it was given
+/// for. `witnesses` pairs each witness with the index of its definition in `rules`.
+///
+/// Contextual definitions are treated as enabled, and word boundaries and anchors are assumed to hold.
+pub fn check(
+    rules: &[Leaf],
+    patterns: &[Pattern],
+    descriptions: &[String],
+    witnesses: &[(usize, Literal)],
+    errors: &mut Errors,
+) {
+    if witnesses.is_empty() {
+        return;
+    }
+
+    let programs: Vec<Program> = patterns
+        .iter()
+        .map(|pattern| Program::lookahead(&pattern.to_mir()))
+        .collect();
+
+    for (rule, witness) in witnesses {
+        let input = witness.to_bytes();
+        let shown = crate::describe_input(&input);
+
+        match winner(rules, &programs, &input) {
+            None => {
+                errors.err(
+                    format!("No definition matches the start of the witness {}", shown),
+                    witness.span(),
+                );
+            }
+            Some((index, _)) if index != *rule => {
+                errors.err(
+                    format!(
+                        "\
+                        The witness {} is lexed as `{}` by {}, instead of by this definition.\n\n\
+                        hint: Consider giving this definition a higher priority: \
+                        #[regex(..., priority = {})]\
+                        ",
+                        shown,
+                        rules[index].ident,
+                        descriptions[index],
+                        rules[index].priority + 1,
+                    ),
+                    witness.span(),
+                );
+            }
+            Some((_, covered)) if covered < input.len() => {
+                errors.err(
+                    format!(
+                        "This definition wins the witness {}, but only matches the first {} of its {} bytes",
+                        shown,
+                        covered,
+                        input.len(),
+                    ),
+                    witness.span(),
+                );
+            }
+            Some(_) => (),
+        }
+    }
+}
+
+/// The definition that lexes the start of `input` as a token, and how many bytes of `input` it covers.
+/// `programs` holds the compiled pattern of each definition in `rules`.
+pub fn winner(rules: &[Leaf], programs: &[Program], input: &[u8]) -> Option<(usize, usize)> {
+    best(rules, &candidates(rules, programs, input)).map(|found| (found.rule, found.covered))
+}
+
+/// A definition matching the start of an input.
+pub struct Match {
+    /// Index of the definition in `rules`.
+    pub rule: usize,
+    /// The length of the token.
+    pub len: usize,
+    /// The length of the token and its trailing context.
+    pub covered: usize,
+}
+
+/// All definitions in `rules` that match the start of `input`, with their longest matches.
+pub fn candidates(rules: &[Leaf], programs: &[Program], input: &[u8]) -> Vec<Match> {
+    rules
+        .iter()
+        .zip(programs)
+        .enumerate()
+        .filter_map(|(rule, (leaf, program))| {
+            let len = program.longest_match(input)?;
+
+            if let Some(negative) = &leaf.not_followed_by {
+                if negative.longest_match(&input[len..]).is_some() {
+                    return None;
+                }
+            }
+
+            // The witness of a definition with trailing context includes the context.
+            let covered = match &leaf.lookahead {
+                Some(lookahead) => len + lookahead.longest_match(&input[len..])?,
+                None => len,
+            };
+
+            Some(Match { rule, len, covered })
+        })
+        .collect()
+}
+
+/// The candidate that wins: the longest, then the one with the highest priority.
+pub fn best<'m>(rules: &[Leaf], candidates: &'m [Match]) -> Option<&'m Match> {
+    candidates
+        .iter()
+        // Earlier definitions win ties, which are reported as ambiguities anyway.
+        .max_by_key(|found| {
+            (
+                found.len,
+                rules[found.rule].priority,
+                std::cmp::Reverse(found.rule),
+            )
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mir::Mir;
+    use proc_macro2::Span;
+    use syn::{Ident, LitStr};
+
+    fn errors(witnesses: &[(usize, &str)]) -> Vec<String> {
+        let keyword = Ident::new("Keyword", Span::call_site());
+        let word = Ident::new("Word", Span::call_site());
+        let rules = vec![
+            Leaf::new(&keyword, Span::call_site()).priority(6),
+            Leaf::new(&word, Span::call_site()).priority(2),
+        ];
+        let patterns = vec![
+            Pattern::Bytes(b"let".to_vec()),
+            Pattern::Regex(Mir::utf8("[a-z]+").unwrap()),
+        ];
+        let descriptions = vec![
+            String::from(r#"#[token("let")]"#),
+            String::from(r#"#[regex("[a-z]+")]"#),
+        ];
+        let witnesses: Vec<_> = witnesses
+            .iter()
+            .map(|&(rule, witness)| (rule, Literal::Utf8(LitStr::new(witness, Span::call_site()))))
+            .collect();
+
+        let mut errors = Errors::default();
+
+        check(&rules, &patterns, &descriptions, &witnesses, &mut errors);
+
+        errors
+            .render()
+            .map(|errors| errors.to_string())
+            .into_iter()
+            .flat_map(|errors| {
+                errors
+                    .split("compile_error !")
+                    .skip(1)
+                    .map(String::from)
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn winning_witnesses() {
+        assert!(errors(&[(0, "let"), (1, "lets"), (1, "le")]).is_empty());
+    }
+
+    #[test]
+    fn losing_witnesses() {
+        let errors = errors(&[(0, "lets"), (1, "let"), (0, "let1"), (1, "1")]);
+
+        assert_eq!(errors.len(), 4);
+        assert!(errors[0].contains("is lexed as `Word`"));
+        assert!(errors[1].contains("is lexed as `Keyword`"));
+        assert!(errors[1].contains("priority = 7"));
+        assert!(errors[2].contains("only matches the first 3 of its 4 bytes"));
+        assert!(errors[3].contains("No definition matches"));
+    }
+}
@@ -0,0 +1,224 @@
+//! A compact binary encoding for lexed tokens, so that they can be cached between runs.
+//!
+//! Lexing is fast, but tools like indexers often see the same files over and over again. This module lets you store the
+//! result of lexing a source in a compact form, and later turn it back into tokens without running the lexer again.
+//!
+//! Each token is stored as a *kind* (usually the index of its variant), its span, and an optional payload. Spans are
+//! stored relative to the end of the previous token as variable-length integers, so most tokens take up only three or
+//! four bytes. Payloads that are just a slice of the source - like the `&'source str` in an identifier token - don't
+//! need to be stored at all, since they can be recovered from the span when decoding.
+//!
+//! To use this module, implement [Cacheable] for your token type:
+//!
+//! ```
+//! use logos::{Logos, cache::{self, Cacheable}};
+//!
+//! #[derive(Logos, Debug, PartialEq)]
+//! enum Token<'a> {
+//!     #[regex(r"[ \n\t]+", logos::skip)]
+//!     Whitespace,
+//!
+//!     #[regex("[a-z]+")]
+//!     Ident(&'a str),
+//!
+//!     #[regex("[0-9]+", |lex| lex.slice().parse().ok())]
+//!     Number(u32),
+//! }
+//!
+//! impl<'a> Cacheable<'a> for Token<'a> {
+//!     fn kind(&self) -> u32 {
+//!         match self {
+//!             Token::Whitespace => 0,
+//!             Token::Ident(_) => 1,
+//!             Token::Number(_) => 2,
+//!         }
+//!     }
+//!
+//!     fn write_payload(&self, out: &mut Vec<u8>) {
+//!         if let Token::Number(n) = self {
+//!             out.extend_from_slice(&n.to_le_bytes());
+//!         }
+//!     }
+//!
+//!     fn read(kind: u32, slice: &'a str, payload: &[u8]) -> Option<Self> {
+//!         match kind {
+//!             0 => Some(Token::Whitespace),
+//!             1 => Some(Token::Ident(slice)),
+//!             2 => Some(Token::Number(u32::from_le_bytes(payload.try_into().ok()?))),
+//!             _ => None,
+//!         }
+//!     }
+//! }
+//!
+//! let source = "foo 42 bar";
+//! let tokens: Vec<_> = Token::lexer(source).spanned().flatten().collect();
+//!
+//! let bytes = cache::encode(&tokens);
+//! let decoded = cache::decode::<Token>(source, &bytes).unwrap();
+//!
+//! assert_eq!(decoded, tokens);
+//! ```
+
+use crate::source::Source;
+use crate::varint;
+use crate::{Logos, Span};
+
+use alloc::vec::Vec;
+use std::fmt::{self, Display, Formatter};
+
+/// The first bytes of every encoded token stream, used to reject data that wasn't produced by [encode].
+const MAGIC: &[u8; 4] = b"LGS\x01";
+
+type Slice<'s, T> = <<T as Logos<'s>>::Source as Source>::Slice;
+
+/// Tokens that can be stored using [encode], and later restored with [decode].
+pub trait Cacheable<'source>: Logos<'source> {
+    /// A number identifying which kind of token this is. This is usually the index of the variant.
+    fn kind(&self) -> u32;
+
+    /// Write any data needed to restore this token that can't be recovered from its slice of the source.
+    ///
+    /// The default implementation writes nothing, which is all that's needed for tokens without fields, or whose
+    /// fields are borrowed from the source.
+    #[inline]
+    fn write_payload(&self, out: &mut Vec<u8>) {
+        let _ = out;
+    }
+
+    /// Restore a token of the given `kind`, which covers `slice` of the source and was stored with `payload`.
+    ///
+    /// Returning `None` causes decoding to fail with [DecodeError::InvalidToken].
+    fn read(kind: u32, slice: &'source Slice<'source, Self>, payload: &[u8]) -> Option<Self>;
+}
+
+/// Encode a sequence of spanned tokens, such as those produced by [Lexer::spanned][crate::Lexer::spanned].
+///
+/// Tokens should be in the order they appear in the source, and shouldn't overlap.
+pub fn encode<'source, 'a, Token, I>(tokens: I) -> Vec<u8>
+where
+    Token: Cacheable<'source> + 'a,
+    I: IntoIterator<Item = &'a (Token, Span)>,
+{
+    let mut out = MAGIC.to_vec();
+    let mut payload = Vec::new();
+    let mut cursor = 0;
+
+    for (token, span) in tokens {
+        payload.clear();
+        token.write_payload(&mut payload);
+
+        varint::write(&mut out, token.kind() as u64);
+        varint::write(&mut out, span.start.wrapping_sub(cursor) as u64);
+        varint::write(&mut out, (span.end - span.start) as u64);
+        varint::write(&mut out, payload.len() as u64);
+        out.extend_from_slice(&payload);
+
+        cursor = span.end;
+    }
+
+    out
+}
+
+/// Decode a sequence of tokens produced by [encode], restoring their payloads using `source`.
+///
+/// `source` must be the same source that the tokens were originally lexed from.
+pub fn decode<'source, Token>(
+    source: &'source Token::Source,
+    bytes: &[u8],
+) -> Result<Vec<(Token, Span)>, DecodeError>
+where
+    Token: Cacheable<'source>,
+{
+    let mut tokens = Vec::new();
+
+    decode_into(source, bytes, &mut tokens)?;
+
+    Ok(tokens)
+}
+
+/// Like [decode], but adds the tokens to `collection` instead of returning a [Vec].
+///
+/// If decoding fails, `collection` may already contain some of the tokens that came before the error.
+pub fn decode_into<'source, Token, C>(
+    source: &'source Token::Source,
+    mut bytes: &[u8],
+    collection: &mut C,
+) -> Result<(), DecodeError>
+where
+    Token: Cacheable<'source>,
+    C: Extend<(Token, Span)>,
+{
+    bytes = bytes
+        .strip_prefix(MAGIC.as_slice())
+        .ok_or(DecodeError::InvalidHeader)?;
+
+    let mut cursor = 0usize;
+
+    while !bytes.is_empty() {
+        let kind = read_varint(&mut bytes)?;
+        let start = cursor.wrapping_add(read_varint(&mut bytes)? as usize);
+        let end = start
+            .checked_add(read_varint(&mut bytes)? as usize)
+            .ok_or(DecodeError::InvalidSpan)?;
+        let len = read_varint(&mut bytes)? as usize;
+
+        if len > bytes.len() {
+            return Err(DecodeError::UnexpectedEnd);
+        }
+
+        let (payload, rest) = bytes.split_at(len);
+        let slice = source.slice(start..end).ok_or(DecodeError::InvalidSpan)?;
+        let kind = u32::try_from(kind).map_err(|_| DecodeError::InvalidToken)?;
+        let token = Token::read(kind, slice, payload).ok_or(DecodeError::InvalidToken)?;
+
+        collection.extend(Some((token, start..end)));
+
+        bytes = rest;
+        cursor = end;
+    }
+
+    Ok(())
+}
+
+fn read_varint(bytes: &mut &[u8]) -> Result<u64, DecodeError> {
+    varint::read(bytes).map_err(|err| match err {
+        varint::Error::UnexpectedEnd => DecodeError::UnexpectedEnd,
+        varint::Error::TooLong => DecodeError::InvalidVarint,
+    })
+}
+
+/// An error produced when [decode] fails.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DecodeError {
+    /// The data doesn't start with the header written by [encode].
+    InvalidHeader,
+
+    /// The data ended in the middle of a token.
+    UnexpectedEnd,
+
+    /// A number in the data is too large.
+    InvalidVarint,
+
+    /// A span is out of bounds for the source.
+    InvalidSpan,
+
+    /// [Cacheable::read] could not restore a token.
+    InvalidToken,
+}
+
+impl Display for DecodeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let message = match self {
+            DecodeError::InvalidHeader => "data is not an encoded token stream",
+            DecodeError::UnexpectedEnd => "unexpected end of data",
+            DecodeError::InvalidVarint => "encoded integer is too large",
+            DecodeError::InvalidSpan => "span is out of bounds for the source",
+            DecodeError::InvalidToken => "could not restore token",
+        };
+
+        f.write_str(message)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for DecodeError {}
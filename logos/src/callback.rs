@@ -10,21 +10,27 @@
 //!
 //! For **unit variants** (variants like `Token::Variant`, which don't contain data):
 //!
-//! | Type            | Effect                                                                                         |
-//! |-----------------|------------------------------------------------------------------------------------------------|
-//! | `bool`          | If `true`, emits `Token::Unit`. Otherwise, emits a generic "unknown token" error.              |
-//! | `Skip`          | Skips the matched token                                                                        |
-//! | `Filter<()>`    | If `Filter::Accept(())`, emits `Token::Unit`. Otherwise, skips the matched token.              |
-//! | `Option<()>`    | If `Some(())`, emits `Token::Unit`. Otherwise, emits a generic "unknown token" error.          |
-//! | `Result<(), E>` | If `Ok(())`, emits `Token::Unit`. If `Err(E)`, emits the contained error value.                |
+//! | Type                    | Effect                                                                                |
+//! |-------------------------|---------------------------------------------------------------------------------------|
+//! | `bool`                  | If `true`, emits `Token::Unit`. Otherwise, emits a generic "unknown token" error.     |
+//! | `Skip`                  | Skips the matched token                                                               |
+//! | `Filter<()>`            | If `Filter::Accept(())`, emits `Token::Unit`. Otherwise, skips the matched token.     |
+//! | `FilterResult<(), E>`   | Like `Filter<()>`, but `FilterResult::Error(E)` emits the contained error value.      |
+//! | `Option<()>`            | If `Some(())`, emits `Token::Unit`. Otherwise, emits a generic "unknown token" error. |
+//! | `Result<(), E>`         | If `Ok(())`, emits `Token::Unit`. If `Err(E)`, emits the contained error value.       |
+//! | `Result<Option<()>, E>` | Like `Result<(), E>`, but `Ok(None)` skips the matched token.                         |
+//! | `ControlFlow<E, ()>`    | Like `Result<(), E>`, with `Continue` for `Ok` and `Break` for `Err`.                 |
 //!
 //! For **value variants** (variants like `Token::Value(C)`, which contain one piece of data):
 //!
-//! | Type           | Effect                                                                                           |
-//! |----------------|--------------------------------------------------------------------------------------------------|
-//! | `Filter<C>`    | If `Filter::Accept(C)`, creates and emits `Token::Value(C)`. Otherwise, skips the matched token. |
-//! | `Option<()>`   | If `Some(C)`, emits `Token::Value(C)`. Otherwise, emits a generic "unknown token" error.         |
-//! | `Result<C, E>` | If `Ok(C)`, creates and emits `Token::Value(C)`. If `Err(E)`, emits the contained error value.   |
+//! | Type                   | Effect                                                                               |
+//! |------------------------|--------------------------------------------------------------------------------------|
+//! | `Filter<C>`            | If `Filter::Accept(C)`, emits `Token::Value(C)`. Otherwise, skips the matched token. |
+//! | `FilterResult<C, E>`   | Like `Filter<C>`, but `FilterResult::Error(E)` emits the contained error value.      |
+//! | `Option<C>`            | If `Some(C)`, emits `Token::Value(C)`. Otherwise, emits an "unknown token" error.    |
+//! | `Result<C, E>`         | If `Ok(C)`, emits `Token::Value(C)`. If `Err(E)`, emits the contained error value.   |
+//! | `Result<Option<C>, E>` | Like `Result<C, E>`, but `Ok(None)` skips the matched token.                         |
+//! | `ControlFlow<E, C>`    | Like `Result<C, E>`, with `Continue` for `Ok` and `Break` for `Err`.                 |
 //!
 //! The [Output] type can also be returned from callbacks, and how it behaves depends on the data inside:
 //! - If `Output::Skip`, skips the matched token.
@@ -32,12 +38,18 @@
 //! - If `Output::Construct(C)`, creates and emits `Token::Value(C)`.
 //! - If `Output::Emit(T)`, emits the token `T` as-is.
 //! - If `Output::Error(E)`, emits the error value.
+//! - If `Output::Reparse(rule)`, handles the matched token as if the definition `rule` had matched instead.
 //!
 //! In order to support more sophisticated lexing strategies, Logos also allows callbacks to manipulate the lexer
 //! itself. The [Lexer] documentation contains more details, but you're most likely interested in [Lexer::remainder] and
 //! [Lexer::bump].
 
-use crate::{Filter, Lexer, Logos, Skip};
+use crate::{Filter, FilterResult, Lexer, Logos, Skip};
+
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::marker::PhantomData;
+use core::ops::ControlFlow;
 
 /// Represents actions the lexer can take.
 ///
@@ -53,6 +65,104 @@ pub enum Output<C, T, E> {
     Emit(T),
     /// Emit an error of type `E`
     Error(E),
+    /// Handle the matched token as if another definition had matched it, running that definition's callback (if any)
+    /// and constructing its variant. The matched input isn't lexed again, so the span and slice stay the same.
+    ///
+    /// The derive names every `#[token]` and `#[regex]` definition with a [Rule] constant on the token type:
+    /// `RULE_{Variant}` if the variant has a single definition, or `RULE_{Variant}_{n}` for each of its definitions
+    /// otherwise, numbered from zero in the order that they're declared. Naming a definition that doesn't exist is a
+    /// compile error.
+    ///
+    /// This is useful for contextual keywords, which should only be treated as keywords in some positions:
+    ///
+    /// ```
+    /// use logos::{Logos, Lexer, callback::Output};
+    ///
+    /// #[derive(Default)]
+    /// struct Extras {
+    ///     in_async: bool,
+    /// }
+    ///
+    /// fn await_keyword<'s>(lex: &mut Lexer<'s, Token<'s>>) -> Output<(), Token<'s>, logos::UnknownToken> {
+    ///     match lex.extras.in_async {
+    ///         true => Output::Construct(()),
+    ///         false => Output::Reparse(Token::RULE_Ident),
+    ///     }
+    /// }
+    ///
+    /// #[derive(Logos, Debug, PartialEq)]
+    /// #[logos(extras = Extras)]
+    /// enum Token<'s> {
+    ///     #[regex(r"\s+", logos::skip)]
+    ///     Whitespace,
+    ///
+    ///     #[token("await", await_keyword)]
+    ///     Await,
+    ///
+    ///     #[regex("[a-z]+", |lex| lex.slice())]
+    ///     Ident(&'s str),
+    /// }
+    ///
+    /// let mut lex = Token::lexer("await");
+    /// assert_eq!(lex.next(), Some(Ok(Token::Ident("await"))));
+    ///
+    /// let mut lex = Token::lexer_with_extras("await", Extras { in_async: true });
+    /// assert_eq!(lex.next(), Some(Ok(Token::Await)));
+    /// ```
+    Reparse(Rule<T>),
+}
+
+/// A `#[token]` or `#[regex]` definition of the token type `T`, for [Output::Reparse].
+///
+/// Rules are only made by the derive, which generates a constant for every definition. See [Output::Reparse] for how
+/// they're named.
+pub struct Rule<T> {
+    index: usize,
+    token: PhantomData<fn() -> T>,
+}
+
+impl<T> Rule<T> {
+    #[doc(hidden)]
+    pub const fn from_index(index: usize) -> Self {
+        Rule {
+            index,
+            token: PhantomData,
+        }
+    }
+
+    /// The index of the definition among all definitions of the token type, in the order that they're declared.
+    pub const fn index(self) -> usize {
+        self.index
+    }
+}
+
+// These are implemented by hand, since deriving them would require `T` to implement them too.
+impl<T> Clone for Rule<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Rule<T> {}
+
+impl<T> PartialEq for Rule<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.index == other.index
+    }
+}
+
+impl<T> Eq for Rule<T> {}
+
+impl<T> Hash for Rule<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.index.hash(state);
+    }
+}
+
+impl<T> fmt::Debug for Rule<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Rule").field(&self.index).finish()
+    }
 }
 
 /// Types that can be returned from lexer callbacks.
@@ -150,3 +260,47 @@ where
         }
     }
 }
+
+impl<'s, C, T, E> CallbackResult<'s, C, T> for FilterResult<C, E>
+where
+    T: Logos<'s>,
+    E: Into<T::Error>,
+{
+    #[inline]
+    fn construct(self, _lex: &Lexer<'s, T>) -> Output<C, T, T::Error> {
+        match self {
+            FilterResult::Accept(contents) => Output::Construct(contents),
+            FilterResult::Skip => Output::Skip,
+            FilterResult::Error(error) => Output::Error(error.into()),
+        }
+    }
+}
+
+impl<'s, C, T, E> CallbackResult<'s, C, T> for Result<Option<C>, E>
+where
+    T: Logos<'s>,
+    E: Into<T::Error>,
+{
+    #[inline]
+    fn construct(self, _lex: &Lexer<'s, T>) -> Output<C, T, T::Error> {
+        match self {
+            Ok(Some(contents)) => Output::Construct(contents),
+            Ok(None) => Output::Skip,
+            Err(error) => Output::Error(error.into()),
+        }
+    }
+}
+
+impl<'s, C, T, E> CallbackResult<'s, C, T> for ControlFlow<E, C>
+where
+    T: Logos<'s>,
+    E: Into<T::Error>,
+{
+    #[inline]
+    fn construct(self, _lex: &Lexer<'s, T>) -> Output<C, T, T::Error> {
+        match self {
+            ControlFlow::Continue(contents) => Output::Construct(contents),
+            ControlFlow::Break(error) => Output::Error(error.into()),
+        }
+    }
+}
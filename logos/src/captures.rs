@@ -0,0 +1,324 @@
+//! Capture groups within tokens matched by a `#[regex]`.
+//!
+//! Some tokens have structure of their own - a version number like `1.42`, or a key-value pair like `name=value`. If
+//! the regex for such a token contains capture groups, a callback can use [Lexer::captures] to get at the parts of the
+//! token, instead of parsing its slice all over again.
+//!
+//! ```
+//! use logos::Logos;
+//!
+//! #[derive(Logos, Debug, PartialEq)]
+//! enum Token<'a> {
+//!     #[regex(r"[ \t\n]+", logos::skip)]
+//!     Whitespace,
+//!
+//!     #[regex(r"([0-9]+)\.([0-9]+)", |lex| {
+//!         let captures = lex.captures()?;
+//!
+//!         Some((captures.get(1)?.parse().ok()?, captures.get(2)?.parse().ok()?))
+//!     })]
+//!     Version((u32, u32)),
+//!
+//!     #[regex(r"(?P<key>[a-z]+)=(?P<value>[a-z]*)", |lex| {
+//!         let captures = lex.captures()?;
+//!
+//!         Some((captures.name("key")?, captures.name("value")?))
+//!     })]
+//!     Pair((&'a str, &'a str)),
+//! }
+//!
+//! let tokens: Vec<_> = Token::lexer("1.42 name=logos").collect();
+//!
+//! assert_eq!(
+//!     tokens,
+//!     &[
+//!         Ok(Token::Version((1, 42))),
+//!         Ok(Token::Pair(("name", "logos"))),
+//!     ],
+//! );
+//! ```
+//!
+//! The groups are found by running a small backtracking matcher over the token after it has been lexed, so there is
+//! no cost to tokens that don't ask for their captures.
+
+use crate::internal::{CaptureInst, CaptureProgram};
+use crate::source::Source;
+use crate::Span;
+use alloc::vec;
+use alloc::vec::Vec;
+
+#[cfg(doc)]
+use crate::Lexer;
+
+/// The capture groups of a token, returned by [Lexer::captures].
+///
+/// Group 0 is always the whole token, and the other groups are numbered by the position of their opening parenthesis
+/// in the regex, starting from 1.
+#[derive(Debug, Clone)]
+pub struct Captures<'source, S: Source + ?Sized> {
+    source: &'source S,
+    slots: Vec<Option<usize>>,
+    names: &'static [Option<&'static str>],
+    /// Added to the spans of groups, like the lexer's [span offset](Lexer::with_span_offset).
+    offset: usize,
+}
+
+impl<'source, S: Source + ?Sized> Captures<'source, S> {
+    /// The slice matched by group `n`, or `None` if that group didn't take part in the match.
+    pub fn get(&self, n: usize) -> Option<&'source S::Slice> {
+        self.source.slice(self.position(n)?)
+    }
+
+    /// The position of group `n`, or `None` if that group didn't take part in the match. Like [Lexer::span], this
+    /// includes the span offset of the lexer.
+    pub fn span(&self, n: usize) -> Option<Span> {
+        let span = self.position(n)?;
+
+        Some(span.start + self.offset..span.end + self.offset)
+    }
+
+    /// The slice matched by the group called `name`, or `None` if there is no such group or it didn't take part in
+    /// the match.
+    pub fn name(&self, name: &str) -> Option<&'source S::Slice> {
+        self.get(self.index_of(name)?)
+    }
+
+    /// The position of the group called `name`, or `None` if there is no such group or it didn't take part in the
+    /// match. Like [Lexer::span], this includes the span offset of the lexer.
+    pub fn name_span(&self, name: &str) -> Option<Span> {
+        self.span(self.index_of(name)?)
+    }
+
+    /// The number of groups in the regex, including group 0.
+    pub fn groups(&self) -> usize {
+        self.names.len()
+    }
+
+    /// The position of group `n` in the source itself, without the span offset.
+    fn position(&self, n: usize) -> Option<Span> {
+        let start = (*self.slots.get(n * 2)?)?;
+        let end = (*self.slots.get(n * 2 + 1)?)?;
+
+        Some(start..end)
+    }
+
+    fn index_of(&self, name: &str) -> Option<usize> {
+        self.names.iter().position(|n| *n == Some(name))
+    }
+}
+
+enum Frame {
+    Step(usize, usize),
+    Restore(usize, Option<usize>),
+}
+
+/// Run `program` over `span` of `source`, which must match it in its entirety. The spans of the groups are reported
+/// with `offset` added to them.
+pub(crate) fn find<'source, S: Source + ?Sized>(
+    source: &'source S,
+    span: Span,
+    offset: usize,
+    program: &'static CaptureProgram,
+) -> Option<Captures<'source, S>> {
+    let mut slots = run(source, span.clone(), program, true)?;
+
+    slots[0] = Some(span.start);
+    slots[1] = Some(span.end);
+
+    Some(Captures {
+        source,
+        slots,
+        names: program.names,
+        offset,
+    })
+}
+
+/// Check whether `program` matches a prefix of the source starting at `at`. This is used for trailing context.
+pub(crate) fn lookahead<S: Source + ?Sized>(
+    source: &S,
+    at: usize,
+    program: &'static CaptureProgram,
+) -> bool {
+    run(source, at..source.len(), program, false).is_some()
+}
+
+/// Check whether `program`, compiled to read backwards, matches a suffix of the source ending at `at`. This is used
+/// for `preceded_by`, and like [longest] follows every path through the program at once.
+pub(crate) fn lookbehind<S: Source + ?Sized>(
+    source: &S,
+    at: usize,
+    program: &'static CaptureProgram,
+) -> bool {
+    let insts = program.insts;
+
+    // The position each instruction was last added at, so that it's only added once per position.
+    let mut added = vec![usize::MAX; insts.len()];
+    let mut threads = Vec::new();
+    let mut stack = vec![0];
+    let mut pos = at;
+
+    loop {
+        while let Some(pc) = stack.pop() {
+            if added[pc] == pos {
+                continue;
+            }
+
+            added[pc] = pos;
+
+            match insts[pc] {
+                CaptureInst::Range(..) => threads.push(pc),
+                CaptureInst::Split(first, second) => stack.extend([second, first]),
+                CaptureInst::Jump(to) => stack.push(to),
+                CaptureInst::Save(_) => stack.push(pc + 1),
+                CaptureInst::Match => return true,
+            }
+        }
+
+        let byte = match pos
+            .checked_sub(1)
+            .and_then(|before| source.read::<u8>(before))
+        {
+            Some(byte) => byte,
+            None => return false,
+        };
+
+        for pc in threads.drain(..) {
+            if let CaptureInst::Range(start, end) = insts[pc] {
+                if (start..=end).contains(&byte) {
+                    stack.push(pc + 1);
+                }
+            }
+        }
+
+        if stack.is_empty() {
+            return false;
+        }
+
+        pos -= 1;
+    }
+}
+
+/// The end of the longest match of `program` that starts at the start of `span` and doesn't go past its end, if there
+/// is one. Unlike [find] and [lookahead], this follows every path through the program at once, so it takes time
+/// proportional to the length of the match times the size of the program, no matter how the regex is written. This is
+/// used for definitions that are too big to go into the state machine, and for backing off from a token that was
+/// rejected by `not_followed_by`.
+pub(crate) fn longest<S: Source + ?Sized>(
+    source: &S,
+    span: Span,
+    program: &'static CaptureProgram,
+) -> Option<usize> {
+    let insts = program.insts;
+
+    // The position each instruction was last added at, so that it's only added once per position.
+    let mut added = vec![usize::MAX; insts.len()];
+    let mut threads = Vec::new();
+    let mut stack = vec![0];
+    let mut longest = None;
+    let mut pos = span.start;
+
+    loop {
+        while let Some(pc) = stack.pop() {
+            if added[pc] == pos {
+                continue;
+            }
+
+            added[pc] = pos;
+
+            match insts[pc] {
+                CaptureInst::Range(..) => threads.push(pc),
+                CaptureInst::Split(first, second) => stack.extend([second, first]),
+                CaptureInst::Jump(to) => stack.push(to),
+                CaptureInst::Save(_) => stack.push(pc + 1),
+                CaptureInst::Match => longest = Some(pos),
+            }
+        }
+
+        let byte = match source.read::<u8>(pos) {
+            Some(byte) if pos < span.end => byte,
+            _ => break,
+        };
+
+        for pc in threads.drain(..) {
+            if let CaptureInst::Range(start, end) = insts[pc] {
+                if (start..=end).contains(&byte) {
+                    stack.push(pc + 1);
+                }
+            }
+        }
+
+        if stack.is_empty() {
+            break;
+        }
+
+        pos += 1;
+    }
+
+    longest
+}
+
+/// Run `program` over `span` of `source`, returning the slots of the first match. If `whole` is set, the match has to
+/// cover all of `span`, otherwise any prefix of it will do.
+fn run<S: Source + ?Sized>(
+    source: &S,
+    span: Span,
+    program: &'static CaptureProgram,
+    whole: bool,
+) -> Option<Vec<Option<usize>>> {
+    let insts = program.insts;
+
+    // Every (instruction, position) pair is tried at most once. The first visit always comes from the path with the
+    // highest priority, so if that failed, any later visit would fail too. The set only grows as far as the program
+    // gets, since trailing context is run against the rest of the source.
+    let mut visited: Vec<u64> = Vec::new();
+    let mut slots = vec![None; program.names.len() * 2];
+    let mut stack = vec![Frame::Step(0, span.start)];
+
+    while let Some(frame) = stack.pop() {
+        let (mut pc, mut pos) = match frame {
+            Frame::Step(pc, pos) => (pc, pos),
+            Frame::Restore(slot, value) => {
+                slots[slot] = value;
+                continue;
+            }
+        };
+
+        loop {
+            let bit = (pos - span.start) * insts.len() + pc;
+
+            if visited.len() <= bit / 64 {
+                visited.resize(bit / 64 + 1, 0);
+            }
+
+            if visited[bit / 64] & (1 << (bit % 64)) != 0 {
+                break;
+            }
+
+            visited[bit / 64] |= 1 << (bit % 64);
+
+            match insts[pc] {
+                CaptureInst::Range(start, end) => match source.read::<u8>(pos) {
+                    Some(byte) if pos < span.end && (start..=end).contains(&byte) => {
+                        pc += 1;
+                        pos += 1;
+                    }
+                    _ => break,
+                },
+                CaptureInst::Split(first, second) => {
+                    stack.push(Frame::Step(second, pos));
+                    pc = first;
+                }
+                CaptureInst::Jump(to) => pc = to,
+                CaptureInst::Save(slot) => {
+                    stack.push(Frame::Restore(slot, slots[slot]));
+                    slots[slot] = Some(pos);
+                    pc += 1;
+                }
+                CaptureInst::Match if pos == span.end || !whole => return Some(slots),
+                CaptureInst::Match => break,
+            }
+        }
+    }
+
+    None
+}
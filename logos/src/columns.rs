@@ -0,0 +1,192 @@
+//! Lexing for fixed-width formats, where only some columns of each line are meaningful.
+//!
+//! Some older formats give special meaning to where text appears on a line. In fixed-form FORTRAN, for example, only
+//! columns 1 to 72 contain code, and anything from column 73 onwards is a comment. The [Columns] type lexes only the
+//! text that falls inside a *window* of columns on each line, and ignores the rest of the line entirely. Spans are
+//! still reported as byte ranges into the original input, so they can be used just like the spans of a [Lexer].
+//!
+//! ```
+//! use logos::{Logos, columns::Columns};
+//!
+//! #[derive(Logos, Debug, PartialEq)]
+//! enum Token {
+//!     #[regex(r"[ \t]+", logos::skip)]
+//!     Whitespace,
+//!
+//!     #[regex("[A-Z]+")]
+//!     Word,
+//! }
+//!
+//! // Only columns 1 to 8 (that is, bytes 0 to 7 of each line) are lexed.
+//! let source = "  HELLO SEQ0001\n  WORLD SEQ0002";
+//! let tokens: Vec<_> = Columns::<Token>::new(source, 0..8).collect();
+//!
+//! assert_eq!(
+//!     tokens,
+//!     &[
+//!         Ok((Token::Word, 2..7)),
+//!         Ok((Token::Word, 18..23)),
+//!     ],
+//! );
+//! ```
+
+use crate::lexer::LexerParts;
+use crate::source::Source;
+use crate::{Lexer, Logos, Span};
+
+use std::ops::Range;
+
+/// A lexer that only reads the text within a window of columns on each line.
+///
+/// Lines are separated by `\n`, and columns are counted in bytes, starting from zero. Since every line is lexed on its
+/// own, tokens never continue from one line onto the next. Lifecycle hooks still treat the whole input as one, so
+/// `on_start` sees the original input, and the end of input is only reached after the last line.
+///
+/// This iterator produces the same items as [Lexer::spanned], but with spans relative to the start of the original
+/// input.
+pub struct Columns<'source, Token: Logos<'source>> {
+    source: &'source Token::Source,
+    window: Range<usize>,
+    lexer: Lexer<'source, Token>,
+
+    /// Offset of the lexer's source in `source`.
+    offset: usize,
+
+    /// Offset of the line following the current one.
+    next_line: usize,
+}
+
+impl<'source, Token> Columns<'source, Token>
+where
+    Token: Logos<'source>,
+    Token::Source: Source<Slice = Token::Source>,
+{
+    /// Create a new `Columns` lexer that reads from `source`, only looking at the bytes in `window` on each line.
+    pub fn new(source: &'source Token::Source, window: Range<usize>) -> Self
+    where
+        Token::Extras: Default,
+    {
+        Self::with_extras(source, window, Default::default())
+    }
+
+    /// Create a new `Columns` lexer that reads from `source` with the provided extras, only looking at the bytes in
+    /// `window` on each line.
+    pub fn with_extras(
+        source: &'source Token::Source,
+        window: Range<usize>,
+        extras: Token::Extras,
+    ) -> Self {
+        // Lifecycle hooks see the whole input, rather than a single line.
+        let mut lexer = Lexer::from_parts(source, LexerParts::new(extras));
+        Token::on_start(&mut lexer);
+
+        // SAFETY: An empty range at the start is always in bounds.
+        lexer.source = unsafe { source.slice_unchecked(0..0) };
+
+        // The end of a window is only the end of input on the last line, see `advance_line`.
+        lexer.eof_emitted = true;
+
+        Columns {
+            source,
+            window,
+            lexer,
+            offset: 0,
+            next_line: 0,
+        }
+    }
+
+    /// The source position of the current token, relative to the start of the original input.
+    #[inline]
+    pub fn span(&self) -> Span {
+        let span = self.lexer.span();
+
+        span.start + self.offset..span.end + self.offset
+    }
+
+    /// A slice containing the current token.
+    #[inline]
+    pub fn slice(&self) -> &'source Token::Source {
+        self.lexer.slice()
+    }
+
+    /// The original input that tokens are being read from.
+    #[inline]
+    pub fn source(&self) -> &'source Token::Source {
+        self.source
+    }
+
+    /// Get a reference to the lexer's extras.
+    #[inline]
+    pub fn extras(&self) -> &Token::Extras {
+        &self.lexer.extras
+    }
+
+    /// Get a mutable reference to the lexer's extras.
+    #[inline]
+    pub fn extras_mut(&mut self) -> &mut Token::Extras {
+        &mut self.lexer.extras
+    }
+
+    /// Move on to the next line, returning `false` if there are no lines left.
+    fn advance_line(&mut self) -> bool {
+        let len = self.source.len();
+        let start = self.next_line;
+
+        if start > len {
+            return false;
+        }
+
+        let mut end = start;
+
+        while let Some(byte) = self.source.read::<u8>(end) {
+            if byte == b'\n' {
+                break;
+            }
+
+            end += 1;
+        }
+
+        let window_start = self
+            .source
+            .find_boundary((start + self.window.start).min(end));
+        let window_end = self
+            .source
+            .find_boundary((start + self.window.end).min(end))
+            .max(window_start);
+
+        // SAFETY: Both ends of the window are clamped to the end of the line, which is within bounds, and have been
+        // adjusted to valid boundaries.
+        self.lexer.source = unsafe { self.source.slice_unchecked(window_start..window_end) };
+        self.lexer.token_start = 0;
+        self.lexer.token_end = 0;
+        self.lexer.trivia_start = 0;
+        self.lexer.eof_emitted = end < len;
+        self.offset = window_start;
+        self.next_line = end + 1;
+
+        true
+    }
+}
+
+impl<'source, Token> Iterator for Columns<'source, Token>
+where
+    Token: Logos<'source>,
+    Token::Source: Source<Slice = Token::Source>,
+{
+    type Item = Result<(Token, Span), Token::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(result) = self.lexer.lex_token() {
+                return Some(result.map(|token| (token, self.span())));
+            }
+
+            // The end of a line isn't the end of input, so the lexer's own hooks for it only run after the last line.
+            if !self.advance_line() {
+                self.lexer.reach_end();
+
+                return None;
+            }
+        }
+    }
+}
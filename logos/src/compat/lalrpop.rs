@@ -0,0 +1,164 @@
+//! Using a Logos lexer as an external lexer for a [LALRPOP](https://lalrpop.github.io/lalrpop/) grammar.
+//!
+//! LALRPOP parsers that use an external lexer expect an iterator of `Result<(Loc, Tok, Loc), Error>`, where the two
+//! `Loc`s are the start and end of each token. [Tokens] turns any lexer into an iterator of that shape, using byte
+//! offsets for locations, and wraps errors in a [LexicalError] that records where they happened.
+//!
+//! The grammar then declares the lexer's types in its `extern` block:
+//!
+//! ```text
+//! extern {
+//!     type Location = usize;
+//!     type Error = logos::compat::lalrpop::LexicalError<logos::UnknownToken>;
+//!
+//!     enum Token {
+//!         "let" => Token::Let,
+//!         "ident" => Token::Ident(<String>),
+//!         // ...
+//!     }
+//! }
+//! ```
+//!
+//! and the parser is given the adaptor as its input:
+//!
+//! ```
+//! use logos::Logos;
+//! use logos::compat::lalrpop::{LexicalError, Tokens};
+//!
+//! #[derive(Logos, Debug, Clone, PartialEq)]
+//! enum Token {
+//!     #[regex(r"[ \t\n]+", logos::skip)]
+//!     Whitespace,
+//!
+//!     #[token("let")]
+//!     Let,
+//!
+//!     #[regex("[a-z]+", |lex| lex.slice().to_owned())]
+//!     Ident(String),
+//! }
+//!
+//! // With a generated parser, this would be `Parser::new().parse(Tokens::new(lexer))`.
+//! let tokens: Vec<_> = Tokens::new(Token::lexer("let x ?")).collect();
+//!
+//! assert_eq!(
+//!     tokens,
+//!     &[
+//!         Ok((0, Token::Let, 3)),
+//!         Ok((4, Token::Ident("x".to_owned()), 5)),
+//!         Err(LexicalError { error: logos::UnknownToken, span: 6..7 }),
+//!     ],
+//! );
+//! ```
+//!
+//! If the grammar uses an error type of its own, [Tokens::with_error] converts lexer errors into it instead.
+
+use crate::{Lexer, LexerExt, Logos, Span};
+
+use std::fmt::{self, Debug, Display, Formatter};
+use std::marker::PhantomData;
+
+/// The item type that LALRPOP expects from an external lexer.
+pub type Spanned<Tok, Loc, Error> = Result<(Loc, Tok, Loc), Error>;
+
+type ErrorOf<'s, T> = <T as Logos<'s>>::Error;
+type ErrorFn<'s, T> = fn(ErrorOf<'s, T>, Span) -> LexicalError<ErrorOf<'s, T>>;
+
+/// An error produced by the lexer, along with where it happened.
+///
+/// This is the error type used by [Tokens::new].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LexicalError<E> {
+    /// The error produced by the lexer.
+    pub error: E,
+
+    /// The source position of the token that caused the error.
+    pub span: Span,
+}
+
+impl<E: Display> Display for LexicalError<E> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at {:?}", self.error, self.span)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<E: Debug + Display> std::error::Error for LexicalError<E> {}
+
+/// An iterator producing the `(start, token, end)` triples that LALRPOP expects from an external lexer.
+///
+/// Since this type contains a [Lexer], it implements the [LexerExt] trait, and allows you to access information from
+/// the underlying lexer. See the [module documentation][self] for an example.
+pub struct Tokens<'source, L, F> {
+    inner: L,
+    map_err: F,
+    phantom: PhantomData<&'source ()>,
+}
+
+impl<'source, L> Tokens<'source, L, ErrorFn<'source, L::Token>>
+where
+    L: LexerExt<'source>,
+{
+    /// Wrap `lexer`, turning its errors into [LexicalError]s.
+    pub fn new(lexer: L) -> Self {
+        Tokens::with_error(lexer, |error, span| LexicalError { error, span })
+    }
+}
+
+impl<'source, L, F> Tokens<'source, L, F> {
+    /// Wrap `lexer`, turning its errors into another type by calling `map_err` with the error and the span of the
+    /// token that caused it.
+    pub fn with_error(lexer: L, map_err: F) -> Self {
+        Tokens {
+            inner: lexer,
+            map_err,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<'source, L, F, Token, E> Iterator for Tokens<'source, L, F>
+where
+    L: LexerExt<'source, Token = Token> + Iterator<Item = Result<Token, Token::Error>>,
+    Token: Logos<'source>,
+    F: FnMut(Token::Error, Span) -> E,
+{
+    type Item = Spanned<Token, usize, E>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let result = self.inner.next()?;
+        let span = self.inner.span();
+
+        Some(match result {
+            Ok(token) => Ok((span.start, token, span.end)),
+            Err(error) => Err((self.map_err)(error, span)),
+        })
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'source, L, F> LexerExt<'source> for Tokens<'source, L, F>
+where
+    L: LexerExt<'source>,
+{
+    type Token = L::Token;
+
+    #[inline]
+    fn as_lexer(&self) -> &Lexer<'source, Self::Token> {
+        self.inner.as_lexer()
+    }
+
+    #[inline]
+    fn as_lexer_mut(&mut self) -> &mut Lexer<'source, Self::Token> {
+        self.inner.as_lexer_mut()
+    }
+
+    #[inline]
+    fn into_lexer(self) -> Lexer<'source, Self::Token> {
+        self.inner.into_lexer()
+    }
+}
@@ -0,0 +1,8 @@
+//! Glue for using Logos with other parsing libraries.
+//!
+//! Each module here adapts a lexer to the interface that a particular library expects, so that you don't need to write
+//! (and test) the same boilerplate in every project.
+
+pub mod lalrpop;
+#[cfg(feature = "proc-macro2")]
+pub mod proc_macro2;
@@ -0,0 +1,294 @@
+//! Lexing the string literals inside a [proc_macro2](https://docs.rs/proc-macro2) `TokenStream`, for procedural macros
+//! with an embedded language.
+//!
+//! A macro like `sql!("SELECT * FROM users")` or `asm!("mov eax, 1")` gets its input as a token stream, where the
+//! language to lex is hidden inside string literals, with escapes still in them. [str_literals] finds the string
+//! literals in a stream (including the ones nested inside groups) and works out their values, and
+//! [StrLiteral::lexer] lexes a value while mapping the span of each token back to the part of the literal that it came
+//! from, so that errors can point at the right place in the macro's input.
+//!
+//! This module needs the `proc-macro2` feature.
+//!
+//! ```
+//! use logos::Logos;
+//! use logos::compat::proc_macro2::str_literals;
+//! use proc_macro2::TokenStream;
+//!
+//! #[derive(Logos, Debug, PartialEq)]
+//! enum Token {
+//!     #[regex(r"[ \t\n]+", logos::skip)]
+//!     Whitespace,
+//!
+//!     #[regex("[a-z]+")]
+//!     Word,
+//! }
+//!
+//! let input: TokenStream = r#"query("select\n ?", [r"from"])"#.parse().unwrap();
+//! let literals = str_literals(input);
+//!
+//! assert_eq!(literals.len(), 2);
+//! assert_eq!(literals[0].value(), "select\n ?");
+//!
+//! let mut tokens = literals[0].lexer::<Token>();
+//!
+//! // Each token comes with the `proc_macro2::Span` to report problems at.
+//! let (token, _span) = tokens.next().unwrap();
+//!
+//! assert_eq!(token, Ok(Token::Word));
+//!
+//! // The `?` is at 8..9 in the value, but the `\n` escape takes up two characters of the literal, which starts with
+//! // its opening quote.
+//! let (token, _span) = tokens.next().unwrap();
+//!
+//! assert!(token.is_err());
+//! assert_eq!(literals[0].source_range(8..9), 10..11);
+//! ```
+//!
+//! Whether a span can point inside a literal depends on the compiler: where it can't, the span of the whole literal is
+//! used instead.
+
+use crate::{Lexer, LexerExt, Logos, Span};
+
+use ::proc_macro2::{Literal, TokenStream, TokenTree};
+
+/// A string literal from a token stream, along with its value.
+#[derive(Debug, Clone)]
+pub struct StrLiteral {
+    literal: Literal,
+    value: String,
+
+    /// Where each byte of the value comes from in the literal's source text, followed by where the closing quote is.
+    /// All the bytes of an escaped character point at its backslash.
+    offsets: Vec<usize>,
+}
+
+impl StrLiteral {
+    /// Work out the value of `literal`, returning `None` if it isn't a string literal. Both normal and raw strings are
+    /// supported, but byte strings and C strings aren't.
+    pub fn new(literal: Literal) -> Option<Self> {
+        let (value, offsets) = unescape(&literal.to_string())?;
+
+        Some(StrLiteral {
+            literal,
+            value,
+            offsets,
+        })
+    }
+
+    /// The literal that this value came from.
+    #[inline]
+    pub fn literal(&self) -> &Literal {
+        &self.literal
+    }
+
+    /// The value of the literal, with its escapes resolved.
+    #[inline]
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+
+    /// Convert a range of the value into the range of the literal's source text that it came from, counting from the
+    /// start of the literal (so the opening quote is at `0`).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `range` goes past the end of the value.
+    pub fn source_range(&self, range: Span) -> Span {
+        self.offsets[range.start]..self.offsets[range.end]
+    }
+
+    /// The [proc_macro2::Span] of a range of the value, or the span of the whole literal if the compiler can't point
+    /// inside it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `range` goes past the end of the value.
+    pub fn span(&self, range: Span) -> ::proc_macro2::Span {
+        self.literal
+            .subspan(self.source_range(range))
+            .unwrap_or_else(|| self.literal.span())
+    }
+
+    /// Lex the value, pairing each token with its [proc_macro2::Span].
+    pub fn lexer<'source, Token>(&'source self) -> Tokens<'source, Lexer<'source, Token>>
+    where
+        Token: Logos<'source, Source = str>,
+        Token::Extras: Default,
+    {
+        Tokens::new(self, Token::lexer(&self.value))
+    }
+}
+
+/// Find the string literals in `stream`, including the ones inside groups, in the order that they appear.
+pub fn str_literals(stream: TokenStream) -> Vec<StrLiteral> {
+    fn collect(stream: TokenStream, literals: &mut Vec<StrLiteral>) {
+        for tree in stream {
+            match tree {
+                TokenTree::Literal(literal) => literals.extend(StrLiteral::new(literal)),
+                TokenTree::Group(group) => collect(group.stream(), literals),
+                TokenTree::Ident(_) | TokenTree::Punct(_) => (),
+            }
+        }
+    }
+
+    let mut literals = Vec::new();
+
+    collect(stream, &mut literals);
+
+    literals
+}
+
+/// An iterator pairing the tokens lexed from a [StrLiteral] with their [proc_macro2::Span]s.
+///
+/// Since this type contains a [Lexer], it implements the [LexerExt] trait, and allows you to access information from
+/// the underlying lexer. See the [module documentation][self] for an example.
+pub struct Tokens<'source, L> {
+    inner: L,
+    literal: &'source StrLiteral,
+}
+
+impl<'source, L> Tokens<'source, L>
+where
+    L: LexerExt<'source>,
+{
+    /// Wrap `lexer`, which has to be lexing the [value](StrLiteral::value) of `literal`. Use this instead of
+    /// [StrLiteral::lexer] to give the lexer some extras, or to wrap it in other adaptors first.
+    pub fn new(literal: &'source StrLiteral, lexer: L) -> Self {
+        Tokens {
+            inner: lexer,
+            literal,
+        }
+    }
+}
+
+impl<'source, L, Token> Iterator for Tokens<'source, L>
+where
+    L: LexerExt<'source, Token = Token> + Iterator<Item = Result<Token, Token::Error>>,
+    Token: Logos<'source>,
+{
+    type Item = (Result<Token, Token::Error>, ::proc_macro2::Span);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let result = self.inner.next()?;
+        let offset = self.inner.as_lexer().span_offset();
+        let span = self.inner.span();
+
+        Some((
+            result,
+            self.literal.span(span.start - offset..span.end - offset),
+        ))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'source, L> LexerExt<'source> for Tokens<'source, L>
+where
+    L: LexerExt<'source>,
+{
+    type Token = L::Token;
+
+    #[inline]
+    fn as_lexer(&self) -> &Lexer<'source, Self::Token> {
+        self.inner.as_lexer()
+    }
+
+    #[inline]
+    fn as_lexer_mut(&mut self) -> &mut Lexer<'source, Self::Token> {
+        self.inner.as_lexer_mut()
+    }
+
+    #[inline]
+    fn into_lexer(self) -> Lexer<'source, Self::Token> {
+        self.inner.into_lexer()
+    }
+}
+
+/// Resolve the escapes in the source text of a string literal, returning its value along with where each byte of the
+/// value came from.
+fn unescape(text: &str) -> Option<(String, Vec<usize>)> {
+    if let Some(rest) = text.strip_prefix('r') {
+        let hashes = rest.len() - rest.trim_start_matches('#').len();
+        let start = 1 + hashes + 1;
+
+        if text.as_bytes().get(start - 1) != Some(&b'"') {
+            return None;
+        }
+
+        let closing = format!("\"{}", &rest[..hashes]);
+        let end = start + text[start..].find(&closing)?;
+
+        return Some((text[start..end].to_owned(), (start..=end).collect()));
+    }
+
+    let mut chars = text.strip_prefix('"')?.char_indices().peekable();
+    let mut value = String::new();
+    let mut offsets = Vec::new();
+
+    while let Some((at, ch)) = chars.next() {
+        // Indices are relative to the text after the opening quote.
+        let at = at + 1;
+        let (ch, escaped) = match ch {
+            '"' => {
+                offsets.push(at);
+
+                return Some((value, offsets));
+            }
+            '\\' => match chars.next()?.1 {
+                'n' => ('\n', true),
+                'r' => ('\r', true),
+                't' => ('\t', true),
+                '0' => ('\0', true),
+                '\\' => ('\\', true),
+                '\'' => ('\'', true),
+                '"' => ('"', true),
+                'x' => {
+                    let hex: String = (0..2)
+                        .map(|_| chars.next().map(|(_, ch)| ch))
+                        .collect::<Option<_>>()?;
+                    let byte = u8::from_str_radix(&hex, 16).ok().filter(u8::is_ascii)?;
+
+                    (char::from(byte), true)
+                }
+                'u' => {
+                    if chars.next()?.1 != '{' {
+                        return None;
+                    }
+
+                    let mut hex = String::new();
+
+                    loop {
+                        match chars.next()?.1 {
+                            '}' => break,
+                            '_' => (),
+                            ch => hex.push(ch),
+                        }
+                    }
+
+                    (char::from_u32(u32::from_str_radix(&hex, 16).ok()?)?, true)
+                }
+                // A backslash at the end of a line skips the line break and the whitespace after it.
+                '\n' => {
+                    while chars.next_if(|(_, ch)| ch.is_whitespace()).is_some() {}
+
+                    continue;
+                }
+                _ => return None,
+            },
+            ch => (ch, false),
+        };
+
+        for n in 0..ch.len_utf8() {
+            offsets.push(if escaped { at } else { at + n });
+        }
+
+        value.push(ch);
+    }
+
+    // The closing quote is missing.
+    None
+}
@@ -0,0 +1,317 @@
+//! Collecting warnings and errors while lexing.
+//!
+//! Usually, a lexer either produces a token or an error. Sometimes, though, a token is perfectly usable but still
+//! worth complaining about - a deprecated escape sequence, say, or a number literal with a redundant prefix. Instead
+//! of making a second pass over the tokens to find these, callbacks can report them as warnings using [Lexer::warn],
+//! while still producing the token.
+//!
+//! Diagnostics are stored in a [Diagnostics] collection that lives in the lexer's extras, which must implement
+//! `AsMut<Diagnostics>`. The [LexerExt::diagnose] adaptor records any errors produced by the lexer in the same
+//! collection, so that warnings and errors can be reported together, in the order that they appear in the source.
+//!
+//! ```
+//! use logos::{Lexer, LexerExt, Logos};
+//! use logos::diagnostics::{Diagnostics, Severity};
+//!
+//! #[derive(Debug, Clone, PartialEq)]
+//! enum Error {
+//!     UnknownToken,
+//!     OctalEscape,
+//! }
+//!
+//! impl<'s> logos::Error<'s, Token> for Error {
+//!     fn unknown_token(_: &Lexer<'s, Token>) -> Self {
+//!         Error::UnknownToken
+//!     }
+//! }
+//!
+//! fn escape(lex: &mut Lexer<Token>) -> char {
+//!     if lex.slice().starts_with("\\0") && lex.slice().len() > 2 {
+//!         lex.warn(Error::OctalEscape);
+//!     }
+//!
+//!     '?'
+//! }
+//!
+//! #[derive(Logos, Debug, PartialEq)]
+//! #[logos(error = Error, extras = Diagnostics<Error>)]
+//! enum Token {
+//!     #[regex(r"[ \t\n]+", logos::skip)]
+//!     Whitespace,
+//!
+//!     #[regex(r"\\[0-9]+", escape)]
+//!     Escape(char),
+//!
+//!     #[regex("[a-z]+")]
+//!     Word,
+//! }
+//!
+//! let mut lexer = Token::lexer(r"abc \012 ! \0").diagnose();
+//! let tokens: Vec<_> = lexer.by_ref().collect();
+//!
+//! assert_eq!(tokens, &[Token::Word, Token::Escape('?'), Token::Escape('?')]);
+//!
+//! let diagnostics: Vec<_> = lexer
+//!     .extras()
+//!     .iter()
+//!     .map(|d| (d.severity, d.span.clone(), d.error.clone()))
+//!     .collect();
+//!
+//! assert_eq!(
+//!     diagnostics,
+//!     &[
+//!         (Severity::Warning, 4..8, Error::OctalEscape),
+//!         (Severity::Error, 9..10, Error::UnknownToken),
+//!     ],
+//! );
+//! ```
+
+use crate::iter::Morph;
+use crate::{Lexer, LexerExt, Logos, Span};
+
+use alloc::vec::{self, Vec};
+use std::fmt::{self, Display, Formatter};
+use std::marker::PhantomData;
+
+/// How serious a [Diagnostic] is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    /// Something that is worth pointing out, but didn't stop a token from being produced.
+    Warning,
+
+    /// Something that did stop a token from being produced.
+    Error,
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Warning => f.write_str("warning"),
+            Severity::Error => f.write_str("error"),
+        }
+    }
+}
+
+/// A warning or error, along with where it occurred in the source.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Diagnostic<E> {
+    /// How serious this diagnostic is.
+    pub severity: Severity,
+
+    /// The source position that this diagnostic refers to.
+    pub span: Span,
+
+    /// The error value describing what went wrong.
+    pub error: E,
+}
+
+impl<E: Display> Display for Diagnostic<E> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {} at {:?}", self.severity, self.error, self.span)
+    }
+}
+
+/// A collection of [Diagnostic]s, kept in the order that they appear in the source.
+///
+/// Diagnostics that start at the same position are kept in the order that they were reported, so iterating over the
+/// collection always produces the same sequence for the same input. See the [module documentation][self] for an
+/// example.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Diagnostics<E> {
+    list: Vec<Diagnostic<E>>,
+}
+
+impl<E> Diagnostics<E> {
+    /// Create an empty collection.
+    pub fn new() -> Self {
+        Diagnostics { list: Vec::new() }
+    }
+
+    /// Add a diagnostic to the collection.
+    pub fn push(&mut self, diagnostic: Diagnostic<E>) {
+        // Diagnostics are nearly always reported in order, so this is usually the same as pushing to the end.
+        let start = diagnostic.span.start;
+        let at = self.list.partition_point(|d| d.span.start <= start);
+
+        self.list.insert(at, diagnostic);
+    }
+
+    /// Add a warning for `span`.
+    pub fn warn(&mut self, span: Span, error: E) {
+        self.push(Diagnostic {
+            severity: Severity::Warning,
+            span,
+            error,
+        });
+    }
+
+    /// Add an error for `span`.
+    pub fn error(&mut self, span: Span, error: E) {
+        self.push(Diagnostic {
+            severity: Severity::Error,
+            span,
+            error,
+        });
+    }
+
+    /// The number of diagnostics in the collection.
+    pub fn len(&self) -> usize {
+        self.list.len()
+    }
+
+    /// Returns `true` if the collection is empty.
+    pub fn is_empty(&self) -> bool {
+        self.list.is_empty()
+    }
+
+    /// Returns `true` if any of the diagnostics are errors.
+    pub fn has_errors(&self) -> bool {
+        self.errors().next().is_some()
+    }
+
+    /// Iterate over all diagnostics, in source order.
+    pub fn iter(&self) -> std::slice::Iter<'_, Diagnostic<E>> {
+        self.list.iter()
+    }
+
+    /// Iterate over the warnings, in source order.
+    pub fn warnings(&self) -> impl Iterator<Item = &Diagnostic<E>> {
+        self.iter().filter(|d| d.severity == Severity::Warning)
+    }
+
+    /// Iterate over the errors, in source order.
+    pub fn errors(&self) -> impl Iterator<Item = &Diagnostic<E>> {
+        self.iter().filter(|d| d.severity == Severity::Error)
+    }
+
+    /// Remove all diagnostics from the collection.
+    pub fn clear(&mut self) {
+        self.list.clear();
+    }
+
+    /// Remove all diagnostics from the collection, returning them in source order.
+    pub fn take(&mut self) -> Vec<Diagnostic<E>> {
+        std::mem::take(&mut self.list)
+    }
+}
+
+impl<E> Default for Diagnostics<E> {
+    fn default() -> Self {
+        Diagnostics::new()
+    }
+}
+
+impl<E> AsMut<Diagnostics<E>> for Diagnostics<E> {
+    fn as_mut(&mut self) -> &mut Diagnostics<E> {
+        self
+    }
+}
+
+impl<E> IntoIterator for Diagnostics<E> {
+    type Item = Diagnostic<E>;
+    type IntoIter = vec::IntoIter<Diagnostic<E>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.list.into_iter()
+    }
+}
+
+impl<'d, E> IntoIterator for &'d Diagnostics<E> {
+    type Item = &'d Diagnostic<E>;
+    type IntoIter = std::slice::Iter<'d, Diagnostic<E>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'source, Token> Lexer<'source, Token>
+where
+    Token: Logos<'source>,
+    Token::Extras: AsMut<Diagnostics<Token::Error>>,
+{
+    /// Report a warning for the current token. This is meant to be called from callbacks, which can then go on to
+    /// produce a token as usual.
+    pub fn warn(&mut self, error: Token::Error) {
+        let span = self.span();
+
+        self.extras.as_mut().warn(span, error);
+    }
+}
+
+/// An iterator that records errors in the lexer's [Diagnostics], and only produces tokens.
+///
+/// Since this type contains a [Lexer], it implements the [LexerExt] trait, and allows you to access information from
+/// the underlying lexer. See the [trait's documentation][LexerExt] for more information.
+///
+/// This struct is created by the [LexerExt::diagnose] method. See its documentation for more details.
+pub struct Diagnose<'source, L> {
+    pub(crate) inner: L,
+    phantom: PhantomData<&'source ()>,
+}
+
+impl<'source, L> Diagnose<'source, L> {
+    pub(crate) fn new(inner: L) -> Self {
+        Diagnose {
+            inner,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<'source, L, Token> Iterator for Diagnose<'source, L>
+where
+    L: LexerExt<'source, Token = Token> + Iterator<Item = Result<Token, Token::Error>>,
+    Token: Logos<'source>,
+    Token::Extras: AsMut<Diagnostics<Token::Error>>,
+{
+    type Item = Token;
+
+    fn next(&mut self) -> Option<Token> {
+        loop {
+            match self.inner.next()? {
+                Ok(token) => return Some(token),
+                Err(error) => {
+                    let span = self.inner.span();
+
+                    self.inner.extras_mut().as_mut().error(span, error);
+                }
+            }
+        }
+    }
+}
+
+impl<'source, L> LexerExt<'source> for Diagnose<'source, L>
+where
+    L: LexerExt<'source>,
+{
+    type Token = L::Token;
+
+    #[inline]
+    fn as_lexer(&self) -> &Lexer<'source, Self::Token> {
+        self.inner.as_lexer()
+    }
+
+    #[inline]
+    fn as_lexer_mut(&mut self) -> &mut Lexer<'source, Self::Token> {
+        self.inner.as_lexer_mut()
+    }
+
+    #[inline]
+    fn into_lexer(self) -> Lexer<'source, Self::Token> {
+        self.inner.into_lexer()
+    }
+}
+
+impl<'source, L, Token2> Morph<'source, Token2> for Diagnose<'source, L>
+where
+    L: LexerExt<'source> + Morph<'source, Token2>,
+    Token2: Logos<'source>,
+{
+    type Output = Diagnose<'source, L::Output>;
+
+    #[inline]
+    fn morph(self) -> Self::Output {
+        Diagnose::new(self.inner.morph())
+    }
+}
@@ -14,11 +14,15 @@
 //! specific cause or provide more information along with the error. To that end, Logos allows you to use your own error
 //! type when lexing, and additionally allows you to control *how* an error is constructed when a lexer encounters an error.
 //!
+//! If you just want to know *where* things went wrong, [SpannedError] is a ready-made error type that records the span
+//! and slice of the unknown token, and whether it ran into the end of the input.
+//!
 //! See [Error]'s documentation if you'd like to implement it for your own type. Otherwise, you may be interested in the [Logos]
 //! trait's documentation, which covers how to use a type implementing `Error` with Logos.
 
-use crate::{Lexer, Logos};
-use std::fmt::{Display, Formatter};
+use crate::source::Source;
+use crate::{Lexer, Logos, Span};
+use std::fmt::{Debug, Display, Formatter};
 
 /// A trait for representing errors that occur during lexing.
 ///
@@ -36,6 +40,67 @@ where
     /// Creates an error value representing an unknown token. This error value may optionally use context from the
     /// lexer to provide more useful diagnostics.
     fn unknown_token(lex: &Lexer<'source, T>) -> Self;
+
+    /// Creates an error value representing a sentinel byte declared with `#[logos(sentinel = b"...")]`. The byte is
+    /// the current token's slice.
+    ///
+    /// By default, this is the same as [Error::unknown_token]. Implement it to tell sentinel bytes apart from other
+    /// errors.
+    fn sentinel(lex: &Lexer<'source, T>) -> Self
+    where
+        Self: Sized,
+    {
+        Self::unknown_token(lex)
+    }
+}
+
+/// A marker for [Error] types that are constructed without panicking or allocating, no matter what the lexer's state
+/// is.
+///
+/// Lexers declared with `#[logos(no_panic_error)]` only compile if their error type implements this trait, which makes
+/// the guarantee part of the lexer's definition, where it can be checked (and can't be lost in a refactor). On top of
+/// that, a panic while constructing an error aborts the process in debug builds, instead of unwinding out of the lexer
+/// where it could be caught and ignored.
+///
+/// Implementing this trait is a promise about [Error::unknown_token] and [Error::sentinel], which can't be checked by
+/// the compiler: keep them to copying values out of the lexer, and avoid arithmetic that can overflow, indexing, and
+/// anything that allocates (like formatting a [String]). Errors produced by callbacks aren't covered, since they're
+/// constructed by the callbacks themselves.
+///
+/// ```
+/// use logos::{Lexer, Logos};
+/// use logos::error::{Error, NoPanicError};
+///
+/// #[derive(Debug, Clone, Copy, PartialEq)]
+/// struct Unexpected(Option<char>);
+///
+/// impl<'s> Error<'s, Token> for Unexpected {
+///     fn unknown_token(lex: &Lexer<'s, Token>) -> Self {
+///         Unexpected(lex.slice().chars().next())
+///     }
+/// }
+///
+/// impl<'s> NoPanicError<'s, Token> for Unexpected {}
+///
+/// #[derive(Logos, Debug, PartialEq)]
+/// #[logos(error = Unexpected, no_panic_error)]
+/// enum Token {
+///     #[regex("[a-z]+")]
+///     Word,
+/// }
+///
+/// let mut lex = Token::lexer("abc?");
+///
+/// assert_eq!(lex.next(), Some(Ok(Token::Word)));
+/// assert_eq!(lex.next(), Some(Err(Unexpected(Some('?')))));
+/// ```
+///
+/// [SpannedError] doesn't implement this trait, since its span is shifted by the lexer's
+/// [span offset][Lexer::with_span_offset], which can overflow.
+pub trait NoPanicError<'source, T>: Error<'source, T>
+where
+    T: Logos<'source>,
+{
 }
 
 /// The primary error case when lexing, and the default error type in Logos.
@@ -57,6 +122,8 @@ where
     }
 }
 
+impl<'source, T> NoPanicError<'source, T> for UnknownToken where T: Logos<'source> {}
+
 impl Display for UnknownToken {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(f, "unknown token encountered while lexing")
@@ -65,3 +132,81 @@ impl Display for UnknownToken {
 
 #[cfg(feature = "std")]
 impl std::error::Error for UnknownToken {}
+
+/// An error type that records where an unknown token was found.
+///
+/// Unlike [UnknownToken], this keeps hold of the span and slice of the offending input, as well as whether the error
+/// reaches the end of the input. This is usually enough to produce a useful diagnostic without implementing [Error]
+/// yourself.
+///
+/// Note that `at_end` only looks at the error's own bytes. An error in the middle of the input is never at the end,
+/// even if it's the start of a token that the end of the input cut short - use `#[regex(..., unterminated = ...)]` to
+/// report those.
+///
+/// ```
+/// use logos::{Logos, error::SpannedError};
+///
+/// #[derive(Logos, Debug, PartialEq)]
+/// #[logos(error = SpannedError<'s>)]
+/// enum Token {
+///     #[regex(r"[ \t\n]+", logos::skip)]
+///     Whitespace,
+///
+///     #[token("fn")]
+///     Fn,
+///
+///     #[token("->")]
+///     Arrow,
+/// }
+///
+/// let mut lex = Token::lexer("fn ? -");
+///
+/// assert_eq!(lex.next(), Some(Ok(Token::Fn)));
+/// assert_eq!(lex.next(), Some(Err(SpannedError { span: 3..4, slice: "?", at_end: false })));
+/// assert_eq!(lex.next(), Some(Err(SpannedError { span: 5..6, slice: "-", at_end: true })));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SpannedError<'source, S: ?Sized = str> {
+    /// The position of the unknown token in the source.
+    pub span: Span,
+
+    /// The slice of the source containing the unknown token.
+    pub slice: &'source S,
+
+    /// Whether the unknown token runs up to the end of the source.
+    pub at_end: bool,
+}
+
+impl<'source, T> Error<'source, T> for SpannedError<'source, <T::Source as Source>::Slice>
+where
+    T: Logos<'source>,
+{
+    #[inline]
+    fn unknown_token(lex: &Lexer<'source, T>) -> Self {
+        let span = lex.span();
+
+        SpannedError {
+            at_end: lex.token_end >= lex.source().len(),
+            slice: lex.slice(),
+            span,
+        }
+    }
+}
+
+impl<'source, S> Display for SpannedError<'source, S>
+where
+    S: Debug + ?Sized,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "unknown token {:?} at {:?}", self.slice, self.span)?;
+
+        if self.at_end {
+            write!(f, " (at end of input)")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'source, S> std::error::Error for SpannedError<'source, S> where S: Debug + ?Sized {}
@@ -1,5 +1,17 @@
+#[cfg(feature = "alloc")]
+use crate::diagnostics::Diagnose;
+#[cfg(feature = "alloc")]
+use crate::iter::BoxedLexer;
+#[cfg(feature = "alloc")]
+use crate::region::Checkpointed;
+#[cfg(feature = "std")]
+use crate::replay::Trace;
 use crate::{
-    iter::{BoxedLexer, Lookahead, MapWithLexer},
+    iter::{
+        DebugValidate, Inject, InspectWithLexer, Lookahead, MapWithLexer, Slices,
+        TakeWhileWithLexer, Triples, Trivia,
+    },
+    lexer::{SpannedAsFn, SpannedFn},
     Lexer, Logos, Span,
 };
 
@@ -83,10 +95,163 @@ pub trait LexerExt<'source> {
         MapWithLexer::new(self, op)
     }
 
+    /// Wrap the lexer in an [Iterator] that calls `op` with each item and a reference to the lexer, before passing the
+    /// item on unchanged. This is handy for printing tokens along with their spans while debugging.
+    ///
+    /// ```
+    /// use logos::{Logos, LexerExt};
+    ///
+    /// #[derive(Logos, Debug, PartialEq)]
+    /// enum Token {
+    ///     #[regex(r"[ \t\n]+", logos::skip)]
+    ///     Whitespace,
+    ///
+    ///     #[regex("[a-z]+")]
+    ///     Ident,
+    /// }
+    ///
+    /// let mut log = Vec::new();
+    /// let tokens: Vec<_> = Token::lexer("a bc")
+    ///     .inspect_with_lexer(|token, lexer| log.push(format!("{:?} at {:?}", token, lexer.span())))
+    ///     .collect();
+    ///
+    /// assert_eq!(tokens, &[Ok(Token::Ident), Ok(Token::Ident)]);
+    /// assert_eq!(log, &["Ok(Ident) at 0..1", "Ok(Ident) at 2..4"]);
+    /// ```
+    #[inline]
+    fn inspect_with_lexer<F>(self, op: F) -> InspectWithLexer<'source, Self, F>
+    where
+        Self: Sized + Iterator,
+        F: FnMut(&Self::Item, &Lexer<'source, Self::Token>),
+    {
+        InspectWithLexer::new(self, op)
+    }
+
+    /// Wrap the lexer in an [Iterator] that produces items for as long as `op` returns `true`, given each item and a
+    /// reference to the lexer.
+    ///
+    /// Like [Iterator::take_while], the item that `op` returns `false` for is consumed. The lexer still points at it
+    /// afterwards though, so its [span][LexerExt::span] and [slice][LexerExt::slice] can be used to find out where the
+    /// iterator stopped, and the lexer can carry on from there.
+    ///
+    /// ```
+    /// use logos::{Logos, LexerExt};
+    ///
+    /// #[derive(Logos, Debug, PartialEq)]
+    /// enum Token {
+    ///     #[regex(r"[ \t\n]+", logos::skip)]
+    ///     Whitespace,
+    ///
+    ///     #[regex("[a-z]+")]
+    ///     Ident,
+    ///
+    ///     #[token(";")]
+    ///     Semicolon,
+    /// }
+    ///
+    /// let mut statement = Token::lexer("a b; c").take_while_with_lexer(|_, lexer| lexer.slice() != ";");
+    ///
+    /// assert_eq!(statement.by_ref().count(), 2);
+    /// assert_eq!(statement.span(), 3..4);
+    ///
+    /// let mut lexer = statement.into_lexer();
+    ///
+    /// assert_eq!(lexer.next(), Some(Ok(Token::Ident)));
+    /// assert_eq!(lexer.slice(), "c");
+    /// ```
+    #[inline]
+    fn take_while_with_lexer<F>(self, op: F) -> TakeWhileWithLexer<'source, Self, F>
+    where
+        Self: Sized + Iterator,
+        F: FnMut(&Self::Item, &Lexer<'source, Self::Token>) -> bool,
+    {
+        TakeWhileWithLexer::new(self, op)
+    }
+
+    /// Wrap the lexer in an [Iterator] that pairs tokens with their source positions. This is the same as
+    /// [Lexer::spanned], but works with any adaptor that produces [Result]s, in any order.
+    ///
+    /// ```
+    /// use logos::{Logos, LexerExt, UnknownToken};
+    ///
+    /// #[derive(Logos, Debug, PartialEq)]
+    /// enum Token {
+    ///     #[regex(r"[ \t\n]+", logos::skip)]
+    ///     Whitespace,
+    ///
+    ///     #[regex("[a-z]+")]
+    ///     Ident,
+    /// }
+    ///
+    /// let mut lexer = Token::lexer("a bc ?").lookahead();
+    ///
+    /// assert_eq!(lexer.peek(), Some(&Ok(Token::Ident)));
+    ///
+    /// let tokens: Vec<_> = lexer.spanned().collect();
+    ///
+    /// assert_eq!(tokens, &[Ok((Token::Ident, 0..1)), Ok((Token::Ident, 2..4)), Err(UnknownToken)]);
+    /// ```
+    #[inline]
+    fn spanned<T, E>(self) -> MapWithLexer<'source, Self, SpannedFn<'source, Self::Token, T, E>>
+    where
+        Self: Sized + Iterator<Item = Result<T, E>>,
+    {
+        self.map_with_lexer(|result, lexer| result.map(|token| (token, lexer.span())))
+    }
+
+    /// Wrap the lexer in an [Iterator] that pairs tokens with their source positions, like [LexerExt::spanned], but
+    /// converts each span to `S` first. This makes it possible to store spans in a more compact type, like
+    /// [Span32](crate::Span32).
+    ///
+    /// ```
+    /// use logos::{LexerExt, Logos, Span32};
+    ///
+    /// #[derive(Logos, Debug, PartialEq)]
+    /// enum Token {
+    ///     #[regex(r"[ \t\n]+", logos::skip)]
+    ///     Whitespace,
+    ///
+    ///     #[regex("[a-z]+")]
+    ///     Ident,
+    /// }
+    ///
+    /// let tokens: Vec<_> = Token::lexer("a bc").spanned_as::<Span32, _, _>().collect();
+    ///
+    /// assert_eq!(tokens, &[Ok((Token::Ident, Span32::new(0, 1))), Ok((Token::Ident, Span32::new(2, 4)))]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// The iterator panics if a span can't be converted to `S`, like when a source is too long for `Span32`.
+    #[inline]
+    fn spanned_as<S, T, E>(
+        self,
+    ) -> MapWithLexer<'source, Self, SpannedAsFn<'source, Self::Token, T, E, S>>
+    where
+        Self: Sized + Iterator<Item = Result<T, E>>,
+        S: TryFrom<Span>,
+    {
+        self.map_with_lexer(|result, lexer| {
+            result.map(|token| {
+                let span = lexer.span();
+
+                match S::try_from(span.clone()) {
+                    Ok(span) => (token, span),
+                    Err(_) => panic!(
+                        "the span {:?} can't be converted to {}",
+                        span,
+                        core::any::type_name::<S>()
+                    ),
+                }
+            })
+        })
+    }
+
     /// Box the lexer, returning a type-erased [BoxedLexer].
     ///
     /// This incurs a small performance penalty from dynamic dispatch, but makes it possible to name the type of the
     /// lexer when you might otherwise be unable to.
+    #[cfg(feature = "alloc")]
     #[inline]
     fn boxed(self) -> BoxedLexer<'source, Self::Token, Self::Item>
     where
@@ -107,6 +272,217 @@ pub trait LexerExt<'source> {
     {
         Lookahead::new(self)
     }
+
+    /// Wrap the lexer in an [Iterator] that produces `(start, token, end)` triples, which is the shape that LALRPOP and
+    /// many other parser generators expect from their input.
+    ///
+    /// Errors are passed through as they are, and [LexerExt::span] can be used to find out where they happened. This
+    /// also works with any other adaptor that produces [Result]s, so the errors don't have to be the lexer's own. See the
+    /// [lalrpop module](crate::compat::lalrpop) for an adaptor that attaches spans to errors too.
+    ///
+    /// ```
+    /// use logos::{Logos, LexerExt, UnknownToken};
+    ///
+    /// #[derive(Logos, Debug, PartialEq)]
+    /// enum Token {
+    ///     #[regex(r"[ \t\n]+", logos::skip)]
+    ///     Whitespace,
+    ///
+    ///     #[regex("[a-z]+")]
+    ///     Ident,
+    ///
+    ///     #[token("=")]
+    ///     Equals,
+    /// }
+    ///
+    /// let mut lexer = Token::lexer("x = ?").triples();
+    ///
+    /// assert_eq!(lexer.next(), Some(Ok((0, Token::Ident, 1))));
+    /// assert_eq!(lexer.next(), Some(Ok((2, Token::Equals, 3))));
+    /// assert_eq!(lexer.next(), Some(Err(UnknownToken)));
+    /// assert_eq!(lexer.span(), 4..5);
+    /// assert_eq!(lexer.next(), None);
+    /// ```
+    #[inline]
+    fn triples(self) -> Triples<'source, Self>
+    where
+        Self: Sized + Iterator,
+    {
+        Triples::new(self)
+    }
+
+    /// Wrap the lexer in an [Iterator] that can insert synthetic tokens between the tokens that it produces, which is
+    /// useful for things like automatic semicolon insertion, or implicit multiplication.
+    ///
+    /// Before each token from the lexer, `op` is called with the previous token (if any), the next token, and the
+    /// lexer. If it returns a token, that token is produced first. `op` is also called once after the last token,
+    /// with `None` as the next token, so that tokens can be inserted at the end of the input as well. At most one
+    /// token is inserted in each position.
+    ///
+    /// While `op` runs, the lexer's span covers the gap between the two tokens, so [Lexer::slice] returns whatever was
+    /// skipped in between them - such as whitespace and comments. Inserted tokens have a zero-length span at the end of
+    /// the previous token. Errors are passed through as they are, and the token after an error has no previous token.
+    ///
+    /// ```
+    /// use logos::{Logos, LexerExt};
+    ///
+    /// #[derive(Logos, Debug, Clone, PartialEq)]
+    /// enum Token {
+    ///     #[regex(r"[ \t\n]+", logos::skip)]
+    ///     Whitespace,
+    ///
+    ///     #[regex("[a-z]+")]
+    ///     Ident,
+    ///
+    ///     #[token(";")]
+    ///     Semicolon,
+    /// }
+    ///
+    /// // Insert a semicolon wherever a line ends with an identifier.
+    /// let mut lexer = Token::lexer("foo\nbar;\nbaz").inject_with(|prev, _next, lexer| {
+    ///     let line_ended = lexer.slice().contains('\n') || lexer.remainder().is_empty();
+    ///
+    ///     match prev {
+    ///         Some(Token::Ident) if line_ended => Some(Token::Semicolon),
+    ///         _ => None,
+    ///     }
+    /// });
+    ///
+    /// let mut tokens = Vec::new();
+    ///
+    /// while let Some(token) = lexer.next() {
+    ///     tokens.push((token.unwrap(), lexer.span()));
+    /// }
+    ///
+    /// assert_eq!(
+    ///     tokens,
+    ///     &[
+    ///         (Token::Ident, 0..3),
+    ///         (Token::Semicolon, 3..3),
+    ///         (Token::Ident, 4..7),
+    ///         (Token::Semicolon, 7..8),
+    ///         (Token::Ident, 9..12),
+    ///         (Token::Semicolon, 12..12),
+    ///     ],
+    /// );
+    /// ```
+    #[inline]
+    fn inject_with<F>(self, op: F) -> Inject<'source, Self, F>
+    where
+        Self: Sized + Iterator,
+        F: FnMut(
+            Option<&Self::Token>,
+            Option<&Self::Token>,
+            &Lexer<'source, Self::Token>,
+        ) -> Option<Self::Token>,
+    {
+        Inject::new(self, op)
+    }
+
+    /// Wrap the lexer in an [Iterator] that keeps whatever was skipped between tokens, such as whitespace and comments,
+    /// by attaching it to the tokens around it. This is handy for formatters and concrete syntax trees, which need to
+    /// give back exactly the text they were given.
+    ///
+    /// Each item is a [TokenWithTrivia][crate::iter::TokenWithTrivia]. The skipped text after a token, up to and
+    /// including the end of its line, is its *trailing* trivia. The rest is *leading* trivia of the next token, so a
+    /// comment on its own line belongs to the token after it. Anything after the last token is trailing trivia of that
+    /// token. Errors are treated like any other token, and together the spans cover the whole source.
+    ///
+    /// ```
+    /// use logos::{Logos, LexerExt};
+    ///
+    /// #[derive(Logos, Debug, PartialEq)]
+    /// enum Token {
+    ///     #[regex(r"[ \t\n]+", logos::skip)]
+    ///     #[regex(r"#[^\n]*", logos::skip)]
+    ///     Trivia,
+    ///
+    ///     #[regex("[a-z]+")]
+    ///     Word,
+    /// }
+    ///
+    /// let source = "foo # one\n# two\nbar ";
+    /// let tokens: Vec<_> = Token::lexer(source)
+    ///     .with_trivia()
+    ///     .map(|token| (&source[token.leading], &source[token.span], &source[token.trailing]))
+    ///     .collect();
+    ///
+    /// assert_eq!(tokens, &[("", "foo", " # one\n"), ("# two\n", "bar", " ")]);
+    /// ```
+    #[inline]
+    fn with_trivia(self) -> Trivia<'source, Self>
+    where
+        Self: Sized + Iterator,
+    {
+        Trivia::new(self)
+    }
+
+    /// Wrap the lexer in an [Iterator] that checks every token in debug builds, panicking if its span ends before it
+    /// starts, goes past the end of the source, overlaps the previous token, or doesn't start and end on boundaries of
+    /// the source, or if [Lexer::slice] doesn't match what the source has there. This catches bugs in custom
+    /// [Source][crate::Source] types and adaptors early. In release builds, tokens are passed through unchanged.
+    ///
+    /// Tokens are checked against the positions the lexer reports before they're shifted by its
+    /// [span offset][Lexer::with_span_offset], so panic messages refer to bytes of the source itself. Moving the lexer
+    /// back with [Lexer::seek] while it's being validated counts as overlapping the previous token.
+    ///
+    /// ```
+    /// use logos::{LexerExt, Logos};
+    ///
+    /// #[derive(Logos, Debug, PartialEq)]
+    /// enum Token {
+    ///     #[regex(r"[ \t]+", logos::skip)]
+    ///     Whitespace,
+    ///
+    ///     #[regex("[a-z]+")]
+    ///     Word,
+    /// }
+    ///
+    /// let tokens: Vec<_> = Token::lexer("foo bar").debug_validate().collect();
+    ///
+    /// assert_eq!(tokens, &[Ok(Token::Word), Ok(Token::Word)]);
+    /// ```
+    #[inline]
+    fn debug_validate(self) -> DebugValidate<'source, Self>
+    where
+        Self: Sized + Iterator,
+    {
+        DebugValidate::new(self)
+    }
+
+    /// Wrap the lexer in an [Iterator] that records the [state][crate::replay::LexerState] of the lexer after each
+    /// item, so that it can be compared with a later run. See the [replay module][crate::replay] for more details.
+    #[cfg(feature = "std")]
+    #[inline]
+    fn trace(self) -> Trace<'source, Self>
+    where
+        Self: Sized + Iterator,
+    {
+        Trace::new(self)
+    }
+
+    /// Wrap the lexer in an [Iterator] that records [checkpoints][crate::region::Checkpoints] to restart lexing from,
+    /// at least `interval` bytes apart. See the [region module][crate::region] for more details.
+    #[cfg(feature = "alloc")]
+    #[inline]
+    fn checkpointed(self, interval: usize) -> Checkpointed<'source, Self>
+    where
+        Self: Sized + Iterator,
+    {
+        Checkpointed::new(self, interval)
+    }
+
+    /// Wrap the lexer in an [Iterator] that only produces tokens, and records any errors in the lexer's
+    /// [Diagnostics][crate::diagnostics::Diagnostics] instead. See the [diagnostics module][crate::diagnostics] for
+    /// more details.
+    #[cfg(feature = "alloc")]
+    #[inline]
+    fn diagnose(self) -> Diagnose<'source, Self>
+    where
+        Self: Sized + Iterator,
+    {
+        Diagnose::new(self)
+    }
 }
 
 impl<'source, Token> LexerExt<'source> for Lexer<'source, Token>
@@ -131,6 +507,7 @@ where
     }
 }
 
+#[cfg(feature = "alloc")]
 impl<'source, Token, Item> LexerExt<'source> for BoxedLexer<'source, Token, Item>
 where
     Token: Logos<'source>,
@@ -175,6 +552,72 @@ where
     }
 }
 
+impl<'source, L, F> LexerExt<'source> for InspectWithLexer<'source, L, F>
+where
+    L: LexerExt<'source>,
+{
+    type Token = L::Token;
+
+    #[inline]
+    fn as_lexer(&self) -> &Lexer<'source, Self::Token> {
+        self.inner.as_lexer()
+    }
+
+    #[inline]
+    fn as_lexer_mut(&mut self) -> &mut Lexer<'source, Self::Token> {
+        self.inner.as_lexer_mut()
+    }
+
+    #[inline]
+    fn into_lexer(self) -> Lexer<'source, Self::Token> {
+        self.inner.into_lexer()
+    }
+}
+
+impl<'source, L, F> LexerExt<'source> for TakeWhileWithLexer<'source, L, F>
+where
+    L: LexerExt<'source>,
+{
+    type Token = L::Token;
+
+    #[inline]
+    fn as_lexer(&self) -> &Lexer<'source, Self::Token> {
+        self.inner.as_lexer()
+    }
+
+    #[inline]
+    fn as_lexer_mut(&mut self) -> &mut Lexer<'source, Self::Token> {
+        self.inner.as_lexer_mut()
+    }
+
+    #[inline]
+    fn into_lexer(self) -> Lexer<'source, Self::Token> {
+        self.inner.into_lexer()
+    }
+}
+
+impl<'source, L, F> LexerExt<'source> for Inject<'source, L, F>
+where
+    L: LexerExt<'source>,
+{
+    type Token = L::Token;
+
+    #[inline]
+    fn as_lexer(&self) -> &Lexer<'source, Self::Token> {
+        self.inner.as_lexer()
+    }
+
+    #[inline]
+    fn as_lexer_mut(&mut self) -> &mut Lexer<'source, Self::Token> {
+        self.inner.as_lexer_mut()
+    }
+
+    #[inline]
+    fn into_lexer(self) -> Lexer<'source, Self::Token> {
+        self.inner.into_lexer()
+    }
+}
+
 impl<'source, L> LexerExt<'source> for Lookahead<'source, L>
 where
     L: LexerExt<'source> + Iterator,
@@ -196,3 +639,92 @@ where
         self.inner.into_lexer()
     }
 }
+
+impl<'source, L> LexerExt<'source> for Triples<'source, L>
+where
+    L: LexerExt<'source>,
+{
+    type Token = L::Token;
+
+    #[inline]
+    fn as_lexer(&self) -> &Lexer<'source, Self::Token> {
+        self.inner.as_lexer()
+    }
+
+    #[inline]
+    fn as_lexer_mut(&mut self) -> &mut Lexer<'source, Self::Token> {
+        self.inner.as_lexer_mut()
+    }
+
+    #[inline]
+    fn into_lexer(self) -> Lexer<'source, Self::Token> {
+        self.inner.into_lexer()
+    }
+}
+
+impl<'source, L> LexerExt<'source> for Trivia<'source, L>
+where
+    L: LexerExt<'source> + Iterator,
+{
+    type Token = L::Token;
+
+    #[inline]
+    fn as_lexer(&self) -> &Lexer<'source, Self::Token> {
+        self.inner.as_lexer()
+    }
+
+    #[inline]
+    fn as_lexer_mut(&mut self) -> &mut Lexer<'source, Self::Token> {
+        self.inner.as_lexer_mut()
+    }
+
+    #[inline]
+    fn into_lexer(self) -> Lexer<'source, Self::Token> {
+        self.inner.into_lexer()
+    }
+}
+
+impl<'source, L> LexerExt<'source> for DebugValidate<'source, L>
+where
+    L: LexerExt<'source> + Iterator,
+{
+    type Token = L::Token;
+
+    #[inline]
+    fn as_lexer(&self) -> &Lexer<'source, Self::Token> {
+        self.inner.as_lexer()
+    }
+
+    #[inline]
+    fn as_lexer_mut(&mut self) -> &mut Lexer<'source, Self::Token> {
+        self.inner.as_lexer_mut()
+    }
+
+    #[inline]
+    fn into_lexer(self) -> Lexer<'source, Self::Token> {
+        self.inner.into_lexer()
+    }
+}
+
+impl<'source, Token> LexerExt<'source> for Slices<'source, Token>
+where
+    Token: Logos<'source>,
+{
+    type Token = Token;
+
+    #[inline]
+    fn as_lexer(&self) -> &Lexer<'source, Self::Token> {
+        &self.lexer
+    }
+
+    #[inline]
+    fn as_lexer_mut(&mut self) -> &mut Lexer<'source, Self::Token> {
+        &mut self.lexer
+    }
+
+    #[inline]
+    fn into_lexer(mut self) -> Lexer<'source, Self::Token> {
+        self.lexer.segmenting = false;
+        self.lexer
+    }
+}
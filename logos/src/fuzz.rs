@@ -0,0 +1,242 @@
+//! Checking the invariants of a lexer on arbitrary input, for fuzz targets.
+//!
+//! A lexer built from a handful of regexes and callbacks is easy to get subtly wrong: a callback that [bumps][bump]
+//! past the end of the input, or that moves the lexer back to an earlier state and gets it stuck. [roundtrip] lexes a
+//! source to the end and checks that:
+//!
+//! - nothing panics,
+//! - every token's span is within the source, and can be sliced out of it,
+//! - every token starts where the previous one ended, possibly after some skipped trivia,
+//! - the lexer keeps moving forward, and stops at the end of the source.
+//!
+//! Together, these mean that the skipped trivia and the slices of the tokens (errors included) add up to the whole
+//! source. A violation panics with a [Violation] describing it, which is exactly what fuzzers look for.
+//!
+//! A [cargo fuzz](https://rust-fuzz.github.io/book/cargo-fuzz.html) target for a lexer is then a one-liner, using
+//! [roundtrip_utf8] to turn the fuzzer's bytes into a string:
+//!
+//! ```text
+//! #![no_main]
+//!
+//! libfuzzer_sys::fuzz_target!(|data: &[u8]| logos::fuzz::roundtrip_utf8::<my_crate::Token>(data));
+//! ```
+//!
+//! [check] reports violations as a [Result] instead, to use in tests.
+//!
+//! ```
+//! use logos::Logos;
+//! use logos::fuzz::{check, roundtrip};
+//!
+//! #[derive(Logos, Debug, PartialEq)]
+//! enum Token {
+//!     #[regex(r"[ \t\n]+", logos::skip)]
+//!     Whitespace,
+//!
+//!     #[regex("[a-z]+")]
+//!     Word,
+//!
+//!     // Oops: this callback expects a closing quote to follow.
+//!     #[token("\"", |lex| lex.bump(1))]
+//!     Quoted,
+//! }
+//!
+//! assert_eq!(check::<Token>("some \"\" words ?"), Ok(4));
+//!
+//! // The input that a fuzzer would find soon enough.
+//! assert!(std::panic::catch_unwind(|| roundtrip::<Token>("\"")).is_err());
+//! ```
+//!
+//! [bump]: crate::Lexer::bump
+
+use crate::{Lexer, Logos, Source, Span};
+
+use std::fmt::{self, Display, Formatter};
+
+/// A broken invariant, found by [check] or [roundtrip]. Tokens are counted from `0`, including errors.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Violation {
+    /// The span of a token isn't a range within the source.
+    OutOfBounds {
+        /// The index of the token.
+        token: usize,
+        /// The span of the token.
+        span: Span,
+    },
+
+    /// The span of a token is within the source, but the source can't be sliced there, usually because it's in the
+    /// middle of a character.
+    NotSliceable {
+        /// The index of the token.
+        token: usize,
+        /// The span of the token.
+        span: Span,
+    },
+
+    /// A token (with its leading trivia) doesn't start where the previous token ended.
+    Gap {
+        /// The index of the token.
+        token: usize,
+        /// Where the previous token ended.
+        expected: usize,
+        /// Where the trivia before the token starts.
+        found: usize,
+    },
+
+    /// The lexer produced a token without moving forward, before reaching the end of the source.
+    Stuck {
+        /// The index of the token.
+        token: usize,
+        /// The span of the token.
+        span: Span,
+    },
+
+    /// The lexer kept producing tokens at the end of the source.
+    Endless {
+        /// How many tokens the lexer produced before giving up on it.
+        tokens: usize,
+    },
+
+    /// The lexer stopped before reaching the end of the source.
+    Unfinished {
+        /// Where the lexer stopped.
+        end: usize,
+    },
+}
+
+impl Display for Violation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Violation::OutOfBounds { token, span } => {
+                write!(
+                    f,
+                    "token {} has a span of {:?}, outside of the source",
+                    token, span
+                )
+            }
+            Violation::NotSliceable { token, span } => {
+                write!(
+                    f,
+                    "token {} has a span of {:?}, which can't be sliced",
+                    token, span
+                )
+            }
+            Violation::Gap {
+                token,
+                expected,
+                found,
+            } => write!(
+                f,
+                "token {} starts at {}, but the previous token ended at {}",
+                token, found, expected
+            ),
+            Violation::Stuck { token, span } => {
+                write!(
+                    f,
+                    "token {} at {:?} didn't move the lexer forward",
+                    token, span
+                )
+            }
+            Violation::Endless { tokens } => {
+                write!(f, "the lexer kept going after {} tokens", tokens)
+            }
+            Violation::Unfinished { end } => {
+                write!(
+                    f,
+                    "the lexer stopped at {}, before the end of the source",
+                    end
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for Violation {}
+
+/// Lex `source` to the end, and check the invariants in the [module documentation](self). Returns how many tokens
+/// (including errors) were produced, or the first violation.
+pub fn check<'source, Token>(source: &'source Token::Source) -> Result<usize, Violation>
+where
+    Token: Logos<'source>,
+    Token::Extras: Default,
+{
+    let mut lexer = Lexer::<Token>::new(source);
+    let len = source.len();
+    let mut end = 0;
+    let mut tokens = 0;
+    // At most one token per byte, plus a `#[logos(eof)]` variant.
+    let limit = len + 1;
+
+    while lexer.next().is_some() {
+        let token = tokens;
+        let span = lexer.token_start..lexer.token_end;
+
+        tokens += 1;
+
+        if span.start > span.end || span.end > len || lexer.trivia_start > span.start {
+            return Err(Violation::OutOfBounds { token, span });
+        }
+        if source.slice(lexer.trivia_start..span.start).is_none()
+            || source.slice(span.clone()).is_none()
+        {
+            return Err(Violation::NotSliceable { token, span });
+        }
+        if lexer.trivia_start != end {
+            return Err(Violation::Gap {
+                token,
+                expected: end,
+                found: lexer.trivia_start,
+            });
+        }
+        if span.end == end && end < len {
+            return Err(Violation::Stuck { token, span });
+        }
+        if tokens > limit {
+            return Err(Violation::Endless { tokens });
+        }
+
+        end = span.end;
+    }
+
+    // Trivia after the last token is skipped without producing anything.
+    let end = lexer.token_end.max(end);
+
+    match end == len {
+        true => Ok(tokens),
+        false => Err(Violation::Unfinished { end }),
+    }
+}
+
+/// Lex `source` to the end, and panic if any of the invariants in the [module documentation](self) don't hold.
+///
+/// # Panics
+///
+/// Panics with the [Violation] if an invariant doesn't hold, or if a callback panics.
+pub fn roundtrip<'source, Token>(source: &'source Token::Source)
+where
+    Token: Logos<'source>,
+    Token::Extras: Default,
+{
+    if let Err(violation) = check::<Token>(source) {
+        panic!("{}", violation);
+    }
+}
+
+/// Like [roundtrip], for lexers of `str` fed with arbitrary bytes. Only the part of `data` before the first invalid
+/// UTF-8 sequence is lexed.
+///
+/// # Panics
+///
+/// Panics with the [Violation] if an invariant doesn't hold, or if a callback panics.
+pub fn roundtrip_utf8<'source, Token>(data: &'source [u8])
+where
+    Token: Logos<'source, Source = str>,
+    Token::Extras: Default,
+{
+    let source = match std::str::from_utf8(data) {
+        Ok(source) => source,
+        // SAFETY: Everything up to `valid_up_to` is valid UTF-8.
+        Err(err) => unsafe { std::str::from_utf8_unchecked(&data[..err.valid_up_to()]) },
+    };
+
+    roundtrip::<Token>(source);
+}
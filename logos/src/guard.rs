@@ -0,0 +1,275 @@
+//! Guarding against inputs that produce a flood of tiny tokens.
+//!
+//! Most sources average a few bytes per token. An input that's mostly garbage can produce an error for every single
+//! byte though, so a service lexing untrusted input might end up with many times more tokens than it expected to
+//! handle. A [Guard] keeps a [Histogram] of token lengths while lexing, and once the average token length falls below
+//! a threshold, it switches to coalescing errors: each run of errors, along with anything skipped between them, is
+//! produced as a single error that spans the whole run.
+//!
+//! ```
+//! use logos::{LexerExt, Logos};
+//!
+//! #[derive(Logos, Debug, PartialEq)]
+//! enum Token {
+//!     #[regex(r"[ \t\n]+", logos::skip)]
+//!     Whitespace,
+//!
+//!     #[regex("[a-z]+")]
+//!     Word,
+//! }
+//!
+//! // Average at least 2 bytes per token, judged once 4 tokens have been lexed.
+//! let mut lexer = Token::lexer("hello !!!!!! world ####").guard(2, 4);
+//! let mut tokens = Vec::new();
+//!
+//! while let Some(token) = lexer.next() {
+//!     tokens.push((token.is_ok(), lexer.span()));
+//! }
+//!
+//! // Errors are produced one by one until there have been enough of them to bring the average down.
+//! assert_eq!(
+//!     tokens,
+//!     &[
+//!         (true, 0..5),
+//!         (false, 6..7),
+//!         (false, 7..8),
+//!         (false, 8..9),
+//!         (false, 9..12),
+//!         (true, 13..18),
+//!         (false, 19..23),
+//!     ],
+//! );
+//! assert!(lexer.is_coalescing());
+//!
+//! // Every error was counted, even if it was coalesced.
+//! assert_eq!(lexer.histogram().errors(), 10);
+//! assert_eq!(lexer.histogram().bucket(1), 10);
+//! assert_eq!(lexer.histogram().bucket(3), 2);
+//! ```
+
+use crate::{Lexer, LexerExt, Logos};
+
+/// How many tokens of each length have been lexed, in buckets that double in size: bucket `0` counts empty tokens,
+/// bucket `1` tokens of one byte, bucket `2` tokens of two or three bytes, bucket `3` tokens of four to seven bytes,
+/// and so on. The last bucket counts everything that's longer.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Histogram {
+    buckets: [usize; Histogram::BUCKETS],
+    tokens: usize,
+    errors: usize,
+    bytes: usize,
+}
+
+impl Histogram {
+    /// The number of buckets.
+    pub const BUCKETS: usize = 16;
+
+    /// Create an empty histogram.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The bucket that counts tokens that are `len` bytes long.
+    #[inline]
+    pub fn bucket_of(len: usize) -> usize {
+        ((usize::BITS - len.leading_zeros()) as usize).min(Self::BUCKETS - 1)
+    }
+
+    /// Count a token that's `len` bytes long, which is an error if `error` is true.
+    #[inline]
+    pub fn record(&mut self, len: usize, error: bool) {
+        self.buckets[Self::bucket_of(len)] += 1;
+        self.tokens += 1;
+        self.errors += error as usize;
+        self.bytes += len;
+    }
+
+    /// The number of tokens counted in `bucket`, or `0` if there's no such bucket.
+    #[inline]
+    pub fn bucket(&self, bucket: usize) -> usize {
+        self.buckets.get(bucket).copied().unwrap_or(0)
+    }
+
+    /// The number of tokens counted in every bucket.
+    #[inline]
+    pub fn buckets(&self) -> &[usize; Histogram::BUCKETS] {
+        &self.buckets
+    }
+
+    /// The number of tokens that have been counted, including errors.
+    #[inline]
+    pub fn tokens(&self) -> usize {
+        self.tokens
+    }
+
+    /// The number of errors that have been counted.
+    #[inline]
+    pub fn errors(&self) -> usize {
+        self.errors
+    }
+
+    /// The total length of the tokens that have been counted.
+    #[inline]
+    pub fn bytes(&self) -> usize {
+        self.bytes
+    }
+
+    /// The average length of the tokens that have been counted, or `None` if there aren't any.
+    #[inline]
+    pub fn average(&self) -> Option<f64> {
+        match self.tokens {
+            0 => None,
+            tokens => Some(self.bytes as f64 / tokens as f64),
+        }
+    }
+
+    /// Whether the tokens average less than `min_average` bytes, once at least `warmup` tokens have been counted.
+    #[inline]
+    pub fn is_degenerate(&self, min_average: usize, warmup: usize) -> bool {
+        self.tokens >= warmup.max(1) && self.bytes < self.tokens.saturating_mul(min_average)
+    }
+}
+
+/// An iterator that watches for inputs that produce a flood of tiny tokens, and coalesces errors once it finds one.
+///
+/// Since this type contains a [Lexer], it implements the [LexerExt] trait, and allows you to access information from
+/// the underlying lexer. See the [trait's documentation][LexerExt] for more information.
+///
+/// This struct is created by the [Lexer::guard] method. See the [module documentation](self) for more details.
+///
+/// # Note
+///
+/// To find where a run of errors ends, the token after it has to be lexed, and is then lexed again when it's produced.
+/// Callbacks and [Lifecycle](crate::Lifecycle) hooks for that token run twice, so side effects that they perform -
+/// such as mutating the `extras` value - may also be performed twice.
+pub struct Guard<'source, Token: Logos<'source>> {
+    lexer: Lexer<'source, Token>,
+    histogram: Histogram,
+    min_average: usize,
+    warmup: usize,
+    coalescing: bool,
+}
+
+/// The state of a lexer that's restored after looking past the end of a run of errors.
+struct Position {
+    token_start: usize,
+    token_end: usize,
+    trivia_start: usize,
+    contextual: u64,
+    eof_emitted: bool,
+    finished: bool,
+}
+
+impl Position {
+    fn of<'source, Token: Logos<'source>>(lexer: &Lexer<'source, Token>) -> Self {
+        Position {
+            token_start: lexer.token_start,
+            token_end: lexer.token_end,
+            trivia_start: lexer.trivia_start,
+            contextual: lexer.contextual,
+            eof_emitted: lexer.eof_emitted,
+            finished: lexer.finished,
+        }
+    }
+
+    fn restore<'source, Token: Logos<'source>>(self, lexer: &mut Lexer<'source, Token>) {
+        lexer.token_start = self.token_start;
+        lexer.token_end = self.token_end;
+        lexer.trivia_start = self.trivia_start;
+        lexer.contextual = self.contextual;
+        lexer.eof_emitted = self.eof_emitted;
+        lexer.finished = self.finished;
+    }
+}
+
+impl<'source, Token: Logos<'source>> Guard<'source, Token> {
+    pub(crate) fn new(lexer: Lexer<'source, Token>, min_average: usize, warmup: usize) -> Self {
+        Guard {
+            lexer,
+            histogram: Histogram::new(),
+            min_average,
+            warmup,
+            coalescing: false,
+        }
+    }
+
+    /// The lengths of the tokens that have been lexed so far, before errors were coalesced.
+    #[inline]
+    pub fn histogram(&self) -> &Histogram {
+        &self.histogram
+    }
+
+    /// Whether errors are being coalesced.
+    #[inline]
+    pub fn is_coalescing(&self) -> bool {
+        self.coalescing
+    }
+
+    /// Start or stop coalescing errors, regardless of the average token length. Once errors stop being coalesced, they
+    /// start again if the average token length is still too low.
+    #[inline]
+    pub fn set_coalescing(&mut self, coalescing: bool) {
+        self.coalescing = coalescing;
+    }
+
+    fn record(&mut self, error: bool) {
+        self.histogram
+            .record(self.lexer.token_end - self.lexer.token_start, error);
+
+        if self.histogram.is_degenerate(self.min_average, self.warmup) {
+            self.coalescing = true;
+        }
+    }
+}
+
+impl<'source, Token: Logos<'source>> Iterator for Guard<'source, Token> {
+    type Item = Result<Token, Token::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let token = self.lexer.next()?;
+
+        self.record(token.is_err());
+
+        if token.is_ok() || !self.coalescing {
+            return Some(token);
+        }
+
+        let start = self.lexer.token_start;
+
+        loop {
+            let position = Position::of(&self.lexer);
+
+            match self.lexer.next() {
+                Some(Err(_)) => self.record(true),
+                _ => {
+                    position.restore(&mut self.lexer);
+                    break;
+                }
+            }
+        }
+
+        self.lexer.token_start = start;
+
+        Some(token)
+    }
+}
+
+impl<'source, Token: Logos<'source>> LexerExt<'source> for Guard<'source, Token> {
+    type Token = Token;
+
+    #[inline]
+    fn as_lexer(&self) -> &Lexer<'source, Self::Token> {
+        &self.lexer
+    }
+
+    #[inline]
+    fn as_lexer_mut(&mut self) -> &mut Lexer<'source, Self::Token> {
+        &mut self.lexer
+    }
+
+    #[inline]
+    fn into_lexer(self) -> Lexer<'source, Self::Token> {
+        self.lexer
+    }
+}
@@ -35,13 +35,90 @@ pub trait LexerInternal<'source> {
     /// Reset `token_start` to `token_end`.
     fn trivia(&mut self);
 
+    /// Skip the current token, and lex the next one in its place.
+    fn skip_token(&mut self);
+
+    /// The length of the invalid UTF-8 sequence at the current position, or `0` if there's a valid character (or
+    /// nothing) there. A sequence is as long as the longest prefix of a character it starts with, or a single byte,
+    /// which is how [String::from_utf8_lossy] splits invalid input too.
+    fn invalid_utf8(&self) -> usize;
+
     /// Set the current token to the appropriate error value, and guarantee that `token_end` is valid for the source
     /// type. In the case of `&str`, we verify that `token_end` is a valid character boundary.
     fn error(&mut self);
 
+    /// Set the current token to the error for a sentinel byte.
+    fn sentinel(&mut self);
+
+    /// Like [error][LexerInternal::error], but first skip ahead until the remaining source starts with one of the
+    /// byte sequences in `sync`, or the end of the source is reached.
+    fn recover(&mut self, sync: &[&[u8]]);
+
+    /// Remember that the current token was matched by a regex with capture groups, which can be found by running
+    /// `program` over it.
+    fn set_captures(&mut self, program: &'static CaptureProgram);
+
+    /// Remember which of its variant's definitions matched the current token.
+    fn set_matched_rule(&mut self, rule: usize);
+
+    /// Check whether contextual definitions using `bit` have been enabled.
+    fn contextual_enabled(&self, bit: u32) -> bool;
+
+    /// Check whether tokens are only being segmented, in which case they shouldn't be constructed.
+    fn segmenting(&self) -> bool;
+
+    /// Record the kind of the current token instead of constructing it, while segmenting.
+    fn segment(&mut self, kind: usize);
+
+    /// Check whether `assertion` holds at the start of the current token.
+    fn assert_start(&self, assertion: Assertion) -> bool;
+
+    /// Check whether `assertion` holds at the end of the current token.
+    fn assert_end(&self, assertion: Assertion) -> bool;
+
+    /// Check whether the byte before the current token is in any of the inclusive `ranges`.
+    fn preceded_by(&self, ranges: &[(u8, u8)]) -> bool;
+
+    /// Check whether the trailing context matched by `program` follows the current token.
+    #[cfg(feature = "alloc")]
+    fn followed_by(&self, program: &'static CaptureProgram) -> bool;
+
+    /// Check whether the text before the current token ends with a match of `program`, which reads it backwards.
+    #[cfg(feature = "alloc")]
+    fn lookbehind(&self, program: &'static CaptureProgram) -> bool;
+
+    /// Extend the current token to the longest match of `program` starting at the start of the token, returning
+    /// `false` (and leaving the token alone) if it doesn't match anything longer than the part already lexed.
+    #[cfg(feature = "alloc")]
+    fn simulate(&mut self, program: &'static CaptureProgram) -> bool;
+
+    /// Shorten the current token to the longest match of any of `rules` other than `rejected` that ends before it does,
+    /// returning the rule that matched, or `None` (and leaving the token alone) if none of them do. Each rule comes
+    /// with the program for its pattern, and they go from the highest priority to the lowest, so that earlier rules win
+    /// ties. This is used when the token matched by `rejected` is ruled out by `not_followed_by`.
+    #[cfg(feature = "alloc")]
+    fn back_off(
+        &mut self,
+        rules: &'static [(usize, CaptureProgram)],
+        rejected: usize,
+    ) -> Option<usize>;
+
+    /// Extend the current token to the longest literal in `buckets` found at the start of the token, returning `false`
+    /// (and leaving the token alone) if none of them are. Each bucket holds sorted literals of the same length, and the
+    /// buckets go from the longest literals to the shortest.
+    fn literal_set(&mut self, buckets: &'static [&'static [&'static [u8]]]) -> bool;
+
+    /// Look the current token up in a table of keywords, returning its slot plus one if it's one of them, or `0` if it
+    /// isn't.
+    fn keyword(&self, table: &'static KeywordTable) -> usize;
+
     /// Modify lexer state to represent EOF
     fn end(&mut self);
 
+    /// Set the current token to `token` the first time the end of input is reached, and modify lexer state to
+    /// represent EOF after that. Used for the variant marked with `#[logos(eof)]`.
+    fn eof(&mut self, token: Self::Token);
+
     /// Set the lexer's current token to `token`.
     fn set(&mut self, token: Result<Self::Token, Self::Error>);
 
@@ -90,7 +167,7 @@ where
         C: Chunk<'source>,
         F: FnOnce(C) -> bool,
     {
-        self.source.read::<C>(self.token_end).map_or(false, test)
+        self.source.read::<C>(self.token_end).is_some_and(test)
     }
 
     #[inline]
@@ -99,9 +176,7 @@ where
         C: Chunk<'source>,
         F: FnOnce(C) -> bool,
     {
-        self.source
-            .read::<C>(self.token_end + n)
-            .map_or(false, test)
+        self.source.read::<C>(self.token_end + n).is_some_and(test)
     }
 
     #[inline]
@@ -119,10 +194,208 @@ where
         self.token_start = self.token_end;
     }
 
+    #[inline]
+    fn skip_token(&mut self) {
+        self.trivia();
+        Token::lex(self);
+    }
+
+    fn invalid_utf8(&self) -> usize {
+        let byte = |n: usize| self.source.read::<u8>(self.token_end + n);
+
+        // The length of the character, and the range of its second byte.
+        let (len, second) = match byte(0) {
+            None | Some(0x00..=0x7F) => return 0,
+            Some(0xC2..=0xDF) => (2, 0x80..=0xBF),
+            Some(0xE0) => (3, 0xA0..=0xBF),
+            Some(0xE1..=0xEC | 0xEE..=0xEF) => (3, 0x80..=0xBF),
+            Some(0xED) => (3, 0x80..=0x9F),
+            Some(0xF0) => (4, 0x90..=0xBF),
+            Some(0xF1..=0xF3) => (4, 0x80..=0xBF),
+            Some(0xF4) => (4, 0x80..=0x8F),
+            Some(_) => return 1,
+        };
+
+        if !byte(1).is_some_and(|byte| second.contains(&byte)) {
+            return 1;
+        }
+
+        // A complete character is valid.
+        (2..len)
+            .find(|&n| !matches!(byte(n), Some(0x80..=0xBF)))
+            .unwrap_or(0)
+    }
+
     #[inline]
     fn error(&mut self) {
         self.token_end = self.source.find_boundary(self.token_end);
-        self.set(Err(Token::Error::unknown_token(self)))
+        self.set(Err(construct_error(self, Token::Error::unknown_token)))
+    }
+
+    #[inline]
+    fn sentinel(&mut self) {
+        self.set(Err(construct_error(self, Token::Error::sentinel)))
+    }
+
+    fn recover(&mut self, sync: &[&[u8]]) {
+        let len = self.source.len();
+        let starts_with = |at: usize, bytes: &[u8]| {
+            bytes
+                .iter()
+                .enumerate()
+                .all(|(n, byte)| self.source.read::<u8>(at + n) == Some(*byte))
+        };
+
+        let mut end = self.source.find_boundary(self.token_end);
+
+        while end < len && !sync.iter().any(|bytes| starts_with(end, bytes)) {
+            end = self.source.find_boundary(end + 1);
+        }
+
+        self.token_end = end;
+        self.set(Err(construct_error(self, Token::Error::unknown_token)))
+    }
+
+    #[inline]
+    fn set_captures(&mut self, program: &'static CaptureProgram) {
+        self.captures = Some((self.token_start, self.token_end, program));
+    }
+
+    #[inline]
+    fn set_matched_rule(&mut self, rule: usize) {
+        self.matched_rule = rule;
+    }
+
+    #[inline]
+    fn segmenting(&self) -> bool {
+        self.segmenting
+    }
+
+    #[inline]
+    fn segment(&mut self, kind: usize) {
+        self.segment = Some(kind);
+        self.token = ManuallyDrop::new(None);
+    }
+
+    #[inline]
+    fn contextual_enabled(&self, bit: u32) -> bool {
+        self.contextual & (1 << bit) != 0
+    }
+
+    #[inline]
+    fn preceded_by(&self, ranges: &[(u8, u8)]) -> bool {
+        let before = self.token_start.checked_sub(1);
+
+        before
+            .and_then(|at| self.source.read::<u8>(at))
+            .is_some_and(|byte| ranges.iter().any(|&(lo, hi)| lo <= byte && byte <= hi))
+    }
+
+    #[inline]
+    fn assert_start(&self, assertion: Assertion) -> bool {
+        assertion.holds(self.source, self.token_start)
+    }
+
+    #[inline]
+    fn assert_end(&self, assertion: Assertion) -> bool {
+        assertion.holds(self.source, self.token_end)
+    }
+
+    #[cfg(feature = "alloc")]
+    fn followed_by(&self, program: &'static CaptureProgram) -> bool {
+        crate::captures::lookahead(self.source, self.token_end, program)
+    }
+
+    #[cfg(feature = "alloc")]
+    fn lookbehind(&self, program: &'static CaptureProgram) -> bool {
+        crate::captures::lookbehind(self.source, self.token_start, program)
+    }
+
+    #[cfg(feature = "alloc")]
+    fn simulate(&mut self, program: &'static CaptureProgram) -> bool {
+        match crate::captures::longest(self.source, self.token_start..self.source.len(), program) {
+            Some(end) if end >= self.token_end => {
+                self.token_end = end;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    fn back_off(
+        &mut self,
+        rules: &'static [(usize, CaptureProgram)],
+        rejected: usize,
+    ) -> Option<usize> {
+        let span = self.token_start..self.token_end - 1;
+        let mut best: Option<(usize, usize)> = None;
+
+        for (rule, program) in rules.iter().filter(|(rule, _)| *rule != rejected) {
+            let end = match crate::captures::longest(self.source, span.clone(), program) {
+                Some(end) if end > self.token_start && self.source.is_boundary(end) => end,
+                _ => continue,
+            };
+
+            match best {
+                Some((longest, _)) if longest >= end => (),
+                _ => best = Some((end, *rule)),
+            }
+        }
+
+        let (end, rule) = best?;
+
+        self.token_end = end;
+
+        Some(rule)
+    }
+
+    fn keyword(&self, table: &'static KeywordTable) -> usize {
+        let (start, end) = (self.token_start, self.token_end);
+        let hash = (start..end).fold(table.seed, |hash, at| {
+            keyword_hash(hash, self.source.read::<u8>(at).unwrap_or(0))
+        });
+        let slot = table.slot(hash);
+        let keyword = table.slots[slot];
+
+        let found = keyword.len() == end - start
+            && keyword
+                .iter()
+                .enumerate()
+                .all(|(n, &byte)| self.source.read::<u8>(start + n) == Some(byte));
+
+        match found {
+            true => slot + 1,
+            false => 0,
+        }
+    }
+
+    fn literal_set(&mut self, buckets: &'static [&'static [&'static [u8]]]) -> bool {
+        let start = self.token_start;
+
+        for bucket in buckets {
+            let len = match bucket.first() {
+                Some(literal) => literal.len(),
+                None => continue,
+            };
+
+            if start + len > self.source.len() {
+                continue;
+            }
+
+            let found = bucket.binary_search_by(|literal| {
+                let source = (0..len).map(|n| self.source.read::<u8>(start + n).unwrap_or(0));
+
+                literal.iter().copied().cmp(source)
+            });
+
+            if found.is_ok() {
+                self.token_end = start + len;
+                return true;
+            }
+        }
+
+        false
     }
 
     #[inline]
@@ -130,6 +403,16 @@ where
         self.token = ManuallyDrop::new(None);
     }
 
+    #[inline]
+    fn eof(&mut self, token: Token) {
+        if self.eof_emitted {
+            self.end();
+        } else {
+            self.eof_emitted = true;
+            self.set(Ok(token));
+        }
+    }
+
     #[inline]
     fn set(&mut self, token: Result<Token, Token::Error>) {
         self.token = ManuallyDrop::new(Some(token));
@@ -146,10 +429,502 @@ where
             Output::Construct(contents) => self.set(Ok(constructor(contents))),
             Output::Emit(token) => self.set(Ok(token)),
             Output::Error(error) => self.set(Err(error)),
-            Output::Skip => {
-                self.trivia();
-                Self::Token::lex(self);
+            Output::Reparse(rule) => Self::Token::reparse(self, rule.index()),
+            Output::Skip => self.skip_token(),
+        }
+    }
+}
+
+/// Panics when dropped, which aborts the process if it's dropped while a panic is unwinding.
+struct AbortOnPanic;
+
+impl Drop for AbortOnPanic {
+    fn drop(&mut self) {
+        panic!("constructing an error panicked, but its type implements NoPanicError");
+    }
+}
+
+/// Construct an error with `construct`. If the error type was declared with `#[logos(no_panic_error)]`, a panic while
+/// constructing it aborts the process in debug builds, rather than unwinding out of the lexer.
+#[inline(always)]
+fn construct_error<'source, Token, F>(lex: &Lexer<'source, Token>, construct: F) -> Token::Error
+where
+    Token: Logos<'source>,
+    F: FnOnce(&Lexer<'source, Token>) -> Token::Error,
+{
+    if !(cfg!(debug_assertions) && Token::NO_PANIC_ERROR) {
+        return construct(lex);
+    }
+
+    let guard = AbortOnPanic;
+    let error = construct(lex);
+
+    std::mem::forget(guard);
+
+    error
+}
+
+/// Checks that the error type of `Token` implements [NoPanicError][crate::error::NoPanicError], for
+/// `#[logos(no_panic_error)]`.
+pub const fn no_panic_error<'source, Token>() -> bool
+where
+    Token: Logos<'source>,
+    Token::Error: crate::error::NoPanicError<'source, Token>,
+{
+    true
+}
+
+/// Types that the field of a variant without a callback can be filled with, from the slice of the current token.
+///
+/// This is `&Source::Slice`, or a `SliceView` for segmented sources.
+pub trait FromSlice<'source, Token: Logos<'source>> {
+    /// Make the field from the current token.
+    fn from_slice(lex: &Lexer<'source, Token>) -> Self;
+}
+
+impl<'source, Token> FromSlice<'source, Token> for &'source <Token::Source as Source>::Slice
+where
+    Token: Logos<'source>,
+{
+    #[inline]
+    fn from_slice(lex: &Lexer<'source, Token>) -> Self {
+        lex.slice()
+    }
+}
+
+/// A regex compiled by the [Logos] derive macro, used to find the capture groups within a token after it has been
+/// matched.
+///
+/// Capture group `n` is stored in slots `2 * n` and `2 * n + 1`. Group 0 is the whole token, and is never saved.
+#[derive(Debug)]
+pub struct CaptureProgram {
+    /// The instructions of the program. Execution starts at the first instruction.
+    pub insts: &'static [CaptureInst],
+
+    /// Names of the capture groups, starting with group 0.
+    pub names: &'static [Option<&'static str>],
+}
+
+/// A single instruction in a [CaptureProgram].
+#[derive(Debug, Clone, Copy)]
+pub enum CaptureInst {
+    /// Match a single byte in the inclusive range.
+    Range(u8, u8),
+
+    /// Continue at the first instruction, and if that fails, at the second.
+    Split(usize, usize),
+
+    /// Continue at the given instruction.
+    Jump(usize),
+
+    /// Store the current position in a slot.
+    Save(usize),
+
+    /// Succeed, if the end of the token has been reached.
+    Match,
+}
+
+/// A word boundary or anchor at the start or end of a `#[regex]`, which is checked once a token has been matched.
+#[derive(Debug, Clone, Copy)]
+pub enum Assertion {
+    /// `^`, the start of the source.
+    StartText,
+
+    /// `$`, the end of the source.
+    EndText,
+
+    /// `(?m:^)`, the start of the source or of a line.
+    StartLine,
+
+    /// `(?m:$)`, the end of the source or of a line.
+    EndLine,
+
+    /// `\b`, where a word character is anything alphanumeric or `_`.
+    WordBoundary,
+
+    /// `\B`, where a word character is anything alphanumeric or `_`.
+    NotWordBoundary,
+
+    /// `(?-u:\b)`, where a word character is an ASCII letter, digit or `_`.
+    AsciiWordBoundary,
+
+    /// `(?-u:\B)`, where a word character is an ASCII letter, digit or `_`.
+    AsciiNotWordBoundary,
+}
+
+impl Assertion {
+    fn holds<S: Source + ?Sized>(self, source: &S, at: usize) -> bool {
+        let byte = |at: usize| source.read::<u8>(at);
+        let before = at.checked_sub(1).and_then(byte);
+        let after = byte(at);
+
+        match self {
+            Assertion::StartText => at == 0,
+            Assertion::EndText => at == source.len(),
+            Assertion::StartLine => at == 0 || before == Some(b'\n'),
+            Assertion::EndLine => at == source.len() || after == Some(b'\n'),
+            Assertion::WordBoundary => word_before(source, at) != word_after(source, at),
+            Assertion::NotWordBoundary => word_before(source, at) == word_after(source, at),
+            Assertion::AsciiWordBoundary => is_ascii_word(before) != is_ascii_word(after),
+            Assertion::AsciiNotWordBoundary => is_ascii_word(before) == is_ascii_word(after),
+        }
+    }
+}
+
+fn is_ascii_word(byte: Option<u8>) -> bool {
+    byte.is_some_and(|byte| byte.is_ascii_alphanumeric() || byte == b'_')
+}
+
+fn is_word(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// Whether the character ending at `at` is a word character.
+fn word_before<S: Source + ?Sized>(source: &S, at: usize) -> bool {
+    // UTF-8 characters are at most 4 bytes long, and continuation bytes all look like `0b10xxxxxx`.
+    let start = (at.saturating_sub(4)..at).rev().find(|&start| {
+        source
+            .read::<u8>(start)
+            .is_some_and(|byte| byte & 0xC0 != 0x80)
+    });
+
+    match start {
+        Some(start) => decode(source, start, at - start).is_some_and(is_word),
+        None => false,
+    }
+}
+
+/// Whether the character starting at `at` is a word character.
+fn word_after<S: Source + ?Sized>(source: &S, at: usize) -> bool {
+    let len = match source.read::<u8>(at) {
+        Some(byte) if byte < 0x80 => 1,
+        Some(byte) if byte >= 0xF0 => 4,
+        Some(byte) if byte >= 0xE0 => 3,
+        Some(_) => 2,
+        None => return false,
+    };
+
+    decode(source, at, len).is_some_and(is_word)
+}
+
+fn decode<S: Source + ?Sized>(source: &S, at: usize, len: usize) -> Option<char> {
+    let mut buf = [0; 4];
+
+    for (n, byte) in buf[..len].iter_mut().enumerate() {
+        *byte = source.read::<u8>(at + n)?;
+    }
+
+    core::str::from_utf8(&buf[..len]).ok()?.chars().next()
+}
+
+/// Whether the derive macro should scan character class loops with [leading_in_ranges]. This is only the case with the
+/// `simd` feature, on targets where it has a vectorised implementation.
+pub const SIMD: bool = cfg!(all(
+    feature = "simd",
+    any(
+        all(
+            any(target_arch = "x86", target_arch = "x86_64"),
+            target_feature = "sse2"
+        ),
+        all(target_arch = "aarch64", target_feature = "neon"),
+    )
+));
+
+/// Count the bytes at the start of `chunk` that fall in any of the inclusive `ranges`, 16 at a time.
+#[inline(always)]
+pub fn leading_in_ranges<const N: usize>(chunk: &[u8; 16], ranges: &[(u8, u8); N]) -> usize {
+    leading::<N, false>(chunk, ranges)
+}
+
+/// Count the bytes at the start of `chunk` that fall outside all of the inclusive `ranges`, 16 at a time. The derive
+/// macro uses this for negated classes like `[^"\\\n]`, which stop at fewer ranges than they match.
+#[inline(always)]
+pub fn leading_outside_ranges<const N: usize>(chunk: &[u8; 16], ranges: &[(u8, u8); N]) -> usize {
+    leading::<N, true>(chunk, ranges)
+}
+
+/// Count the bytes at the start of `chunk` up to the first one that's in `ranges` if `STOP` is true, or the first one
+/// that isn't if it's false.
+#[inline(always)]
+fn leading<const N: usize, const STOP: bool>(chunk: &[u8; 16], ranges: &[(u8, u8); N]) -> usize {
+    #[cfg(all(
+        any(target_arch = "x86", target_arch = "x86_64"),
+        target_feature = "sse2"
+    ))]
+    {
+        #[cfg(target_arch = "x86")]
+        use core::arch::x86::*;
+        #[cfg(target_arch = "x86_64")]
+        use core::arch::x86_64::*;
+
+        // SAFETY: SSE2 is available, and unaligned loads can read any 16 bytes.
+        unsafe {
+            let bytes = _mm_loadu_si128(chunk.as_ptr() as *const __m128i);
+            let mut hits = _mm_setzero_si128();
+
+            for &(lo, hi) in ranges {
+                let inside = if lo == hi {
+                    _mm_cmpeq_epi8(bytes, _mm_set1_epi8(lo as i8))
+                } else {
+                    // `byte - lo` wraps around for bytes below the range, so one unsigned comparison covers both ends.
+                    let shifted = _mm_sub_epi8(bytes, _mm_set1_epi8(lo as i8));
+                    let width = _mm_set1_epi8((hi - lo) as i8);
+
+                    _mm_cmpeq_epi8(_mm_min_epu8(shifted, width), shifted)
+                };
+
+                hits = _mm_or_si128(hits, inside);
+            }
+
+            let hits = _mm_movemask_epi8(hits) as u32;
+            let stops = if STOP { hits } else { !hits & 0xFFFF };
+
+            // The extra bit caps the count at 16 when nothing in the chunk stops it.
+            (stops | 1 << 16).trailing_zeros() as usize
+        }
+    }
+
+    #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+    {
+        use core::arch::aarch64::*;
+
+        // SAFETY: NEON is available, and the loads and stores stay within 16 byte arrays.
+        unsafe {
+            let bytes = vld1q_u8(chunk.as_ptr());
+            let mut hits = vdupq_n_u8(0);
+
+            for &(lo, hi) in ranges {
+                let inside = if lo == hi {
+                    vceqq_u8(bytes, vdupq_n_u8(lo))
+                } else {
+                    vcleq_u8(vsubq_u8(bytes, vdupq_n_u8(lo)), vdupq_n_u8(hi - lo))
+                };
+
+                hits = vorrq_u8(hits, inside);
+            }
+
+            let stops = if STOP { hits } else { vmvnq_u8(hits) };
+
+            if vmaxvq_u8(stops) == 0 {
+                return 16;
+            }
+
+            let mut lanes = [0; 16];
+
+            vst1q_u8(lanes.as_mut_ptr(), stops);
+
+            lanes.iter().position(|&lane| lane != 0).unwrap_or(16)
+        }
+    }
+
+    #[cfg(not(any(
+        all(
+            any(target_arch = "x86", target_arch = "x86_64"),
+            target_feature = "sse2"
+        ),
+        all(target_arch = "aarch64", target_feature = "neon"),
+    )))]
+    {
+        chunk
+            .iter()
+            .position(|&byte| ranges.iter().any(|&(lo, hi)| lo <= byte && byte <= hi) == STOP)
+            .unwrap_or(16)
+    }
+}
+
+/// The state machine of a `#[logos(codegen = "compact")]` enum, run by [run_compact] instead of being generated as
+/// code.
+///
+/// Each state is stored as five words:
+///
+/// 0. The kind of state (`0` for a fork, `1` for a rope, `2` for a leaf), bit 2 set if entering it enters a loop, and
+///    for ropes the length of the rope from bit 3 up.
+/// 1. The fewest bytes that have to be left for the state to match anything.
+/// 2. The state to continue with when this one doesn't match, plus one, or `0` if there is none.
+/// 3. For forks the offset of their row in `transitions`, and for ropes the offset of their ranges in `ropes`.
+/// 4. For ropes the state to continue with once all of them matched.
+///
+/// State `0` is the root.
+#[derive(Debug)]
+pub struct CompactTable {
+    /// Bytes that every fork treats the same share a class, and each row of `transitions` has one entry per class.
+    pub classes: [u8; 256],
+
+    /// The number of classes, and so the length of each row.
+    pub class_count: usize,
+
+    /// The states of the machine, as described above.
+    pub states: &'static [[u32; 5]],
+
+    /// The state each fork continues with for each class, plus one, or `0` if the byte doesn't match.
+    pub transitions: &'static [u16],
+
+    /// The inclusive byte ranges matched by the ropes.
+    pub ropes: &'static [(u8, u8)],
+}
+
+/// A perfect hash table of the keywords an identifier can be, which is used instead of compiling them into the state
+/// machine when there are lots of them (see `keyword_tables` in the documentation of the derive macro).
+///
+/// A token is hashed by folding its bytes into `seed` with [keyword_hash]. The upper half of the hash picks one of the
+/// `displacements`, which is mixed into the hash to pick the token's slot. The derive macro picks the seed and
+/// displacements so that no two keywords end up in the same slot.
+#[derive(Debug)]
+pub struct KeywordTable {
+    /// The hash of an empty token.
+    pub seed: u64,
+
+    /// The displacement of each bucket of keywords.
+    pub displacements: &'static [u32],
+
+    /// The keyword in each slot, or an empty slice if the slot is free. There's a power of two slots.
+    pub slots: &'static [&'static [u8]],
+}
+
+impl KeywordTable {
+    /// The slot that a token with this hash could be in.
+    #[inline]
+    pub fn slot(&self, hash: u64) -> usize {
+        let bucket = (hash >> 32) as usize % self.displacements.len();
+        let mut mixed = hash ^ self.displacements[bucket] as u64;
+
+        // The finalizer of SplitMix64, so that every displacement moves the keywords of a bucket somewhere else.
+        mixed ^= mixed >> 30;
+        mixed = mixed.wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        mixed ^= mixed >> 27;
+        mixed = mixed.wrapping_mul(0x94d0_49bb_1331_11eb);
+        mixed ^= mixed >> 31;
+
+        mixed as usize & (self.slots.len() - 1)
+    }
+}
+
+/// Fold a byte of a token into its FNV-1a hash, for looking it up in a [KeywordTable].
+#[inline]
+pub fn keyword_hash(hash: u64, byte: u8) -> u64 {
+    (hash ^ byte as u64).wrapping_mul(0x0000_0100_0000_01b3)
+}
+
+/// How a run of a [CompactTable] ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompactMatch {
+    /// The leaf with this state index matched, and `token_end` was moved to the end of it.
+    Leaf(usize),
+
+    /// The root state had nothing to read.
+    End,
+
+    /// Some bytes matched but no leaf was reached, and `token_end` was moved past them.
+    Fail,
+
+    /// Not even the first byte matched.
+    Error,
+}
+
+const COMPACT_FORK: u32 = 0;
+const COMPACT_ROPE: u32 = 1;
+const COMPACT_LOOP: u32 = 1 << 2;
+
+/// Run the state machine in `table` from the start of the current token.
+///
+/// This backtracks exactly like the generated code does: entering a state that enters a loop (or entering a state with
+/// a fallback when there's nothing to backtrack to) saves a checkpoint, and a miss without a fallback of its own
+/// returns to the last one.
+#[inline]
+pub fn run_compact<'source, Token>(
+    lex: &mut Lexer<'source, Token>,
+    table: &CompactTable,
+) -> CompactMatch
+where
+    Token: Logos<'source>,
+{
+    let source = lex.source;
+    let start = lex.token_end;
+    let mut checkpoint = start;
+    let mut backtrack = None;
+    let mut state = 0;
+    let mut at = start;
+
+    'enter: loop {
+        let [header, min_read, miss, a, b] = table.states[state];
+        let miss = (miss as usize).checked_sub(1);
+        let enters_loop = header & COMPACT_LOOP != 0;
+
+        if (enters_loop || backtrack.is_none()) && miss.is_some() {
+            backtrack = miss;
+            checkpoint = at;
+        } else if enters_loop || min_read == 0 {
+            checkpoint = at;
+        }
+
+        let matched = match header & 0b11 {
+            COMPACT_FORK => {
+                let need = (min_read as usize).max(1);
+
+                if at + need > source.len() {
+                    if state == 0 {
+                        return CompactMatch::End;
+                    }
+                    None
+                } else {
+                    let row = &table.transitions[a as usize..a as usize + table.class_count];
+                    let target = |at: usize| {
+                        let byte = source.read::<u8>(at).unwrap_or_default();
+
+                        row[table.classes[byte as usize] as usize] as usize
+                    };
+                    let mut next = target(at);
+
+                    // Going around a loop on the same state only ever moves the checkpoint, so it's done in place.
+                    while enters_loop && next == state + 1 && at + 1 + need <= source.len() {
+                        at += 1;
+                        checkpoint = at;
+                        next = target(at);
+                    }
+
+                    next.checked_sub(1).map(|next| (next, at + 1))
+                }
+            }
+            COMPACT_ROPE => {
+                let len = (header >> 3) as usize;
+                let ranges = &table.ropes[a as usize..a as usize + len];
+                let matches = ranges.iter().enumerate().all(|(n, &(lo, hi))| {
+                    source
+                        .read::<u8>(at + n)
+                        .is_some_and(|byte| lo <= byte && byte <= hi)
+                });
+
+                matches.then(|| (b as usize, at + len))
+            }
+            _ => {
+                lex.token_end = at;
+
+                return CompactMatch::Leaf(state);
+            }
+        };
+
+        if let Some((next, next_at)) = matched {
+            state = next;
+            at = next_at;
+
+            continue 'enter;
+        }
+
+        match (miss, backtrack.take()) {
+            (Some(miss), backtrack_to) => {
+                backtrack = backtrack_to;
+                state = miss;
+            }
+            (None, Some(backtrack_to)) => {
+                state = backtrack_to;
+                at = checkpoint;
+            }
+            (None, None) if checkpoint > start => {
+                lex.token_end = checkpoint;
+
+                return CompactMatch::Fail;
             }
+            (None, None) => return CompactMatch::Error,
         }
     }
 }
@@ -31,7 +31,10 @@
 //! [Lexer] after using an iterator adaptor from the standard library, since those types don't implement [LexerExt]
 //!
 
-use crate::{Lexer, LexerExt, Logos};
+use crate::lexer::SpanFn;
+use crate::{Lexer, LexerExt, Logos, Source, Span};
+#[cfg(feature = "alloc")]
+use alloc::boxed::Box;
 use std::{marker::PhantomData, mem::ManuallyDrop};
 
 // This is where the magic happens.
@@ -43,7 +46,26 @@ where
 
     #[inline]
     fn next(&mut self) -> Option<Self::Item> {
+        let token = self.lex_token();
+
+        if token.is_none() && self.segment.is_none() {
+            self.reach_end();
+        }
+
+        token
+    }
+}
+
+impl<'source, Token> Lexer<'source, Token>
+where
+    Token: Logos<'source>,
+{
+    /// Lex the next token, and run the `on_token` or `on_error` hooks. The hooks for the end of input are left to the
+    /// caller, see [Lexer::reach_end].
+    #[inline]
+    pub(crate) fn lex_token(&mut self) -> Option<Result<Token, Token::Error>> {
         self.token_start = self.token_end;
+        self.trivia_start = self.token_end;
 
         Token::lex(self);
 
@@ -51,7 +73,26 @@ where
         // Since we always immediately return a newly set token here,
         // we don't have to replace it with `None` or manually drop
         // it later.
-        unsafe { ManuallyDrop::take(&mut self.token) }
+        let token = unsafe { ManuallyDrop::take(&mut self.token) };
+
+        // Segments aren't constructed, but they're still tokens.
+        match (&token, self.segment) {
+            (Some(Ok(_)), _) | (None, Some(_)) => Token::on_token(self),
+            (Some(Err(_)), _) => Token::on_error(self),
+            (None, None) => {}
+        }
+
+        token
+    }
+
+    /// Run the hooks for the end of input, the first time it's reached.
+    #[inline]
+    pub(crate) fn reach_end(&mut self) {
+        if !self.finished {
+            self.finished = true;
+            Token::on_finish(self);
+            Token::on_eof(self);
+        }
     }
 }
 
@@ -62,6 +103,7 @@ pub trait LexIterator<'source>: LexerExt<'source> + Iterator {}
 impl<'source, T> LexIterator<'source> for T where T: LexerExt<'source> + Iterator {}
 
 // This makes our lives a little easier.
+#[cfg(feature = "alloc")]
 type DynLex<'s, T, I> = dyn LexIterator<'s, Token = T, Item = I> + 's;
 
 /// A boxed and type-erased lexer.
@@ -70,7 +112,7 @@ type DynLex<'s, T, I> = dyn LexIterator<'s, Token = T, Item = I> + 's;
 /// the underlying lexer. See the [trait's documentation][LexerExt] for more information.
 ///
 /// This struct is created by the [LexerExt::boxed] method. See its documentation for more details.
-#[cfg(feature = "std")]
+#[cfg(feature = "alloc")]
 pub struct BoxedLexer<'source, Token, Item>
 where
     Token: Logos<'source>,
@@ -79,7 +121,7 @@ where
     pub(crate) unbox: fn(Box<DynLex<'source, Token, Item>>) -> Lexer<'source, Token>,
 }
 
-#[cfg(feature = "std")]
+#[cfg(feature = "alloc")]
 impl<'source, Token, Item> BoxedLexer<'source, Token, Item>
 where
     Token: Logos<'source>,
@@ -108,7 +150,10 @@ where
             // `ManuallyDrop`.
             //
             // Unfortunately, this requires a transmute. `ManuallyDrop` is `repr(transparent)`, so this is okay.
-            let me = std::mem::transmute::<_, *mut ManuallyDrop<DynLex<'source, Token, Item>>>(ptr);
+            let me = std::mem::transmute::<
+                *mut DynLex<'source, L::Token, Item>,
+                *mut ManuallyDrop<DynLex<'source, Token, Item>>,
+            >(ptr);
 
             // Then we can drop the box and free the allocation.
             drop(Box::from_raw(me));
@@ -124,7 +169,7 @@ where
     }
 }
 
-#[cfg(feature = "std")]
+#[cfg(feature = "alloc")]
 impl<'source, Token, Item> Iterator for BoxedLexer<'source, Token, Item>
 where
     Token: Logos<'source>,
@@ -176,6 +221,289 @@ where
     }
 }
 
+/// An iterator that calls a function with each item and the lexer, before passing the item on.
+///
+/// Since this type contains a [Lexer], it implements the [LexerExt] trait, and allows you to access information from
+/// the underlying lexer. See the [trait's documentation][LexerExt] for more information.
+///
+/// This struct is created by the [LexerExt::inspect_with_lexer] method. See its documentation for more details.
+pub struct InspectWithLexer<'source, L, F> {
+    pub(crate) inner: L,
+    op: F,
+    phantom: PhantomData<&'source ()>,
+}
+
+impl<'source, L, F> InspectWithLexer<'source, L, F>
+where
+    L: LexerExt<'source> + Iterator,
+{
+    pub(crate) fn new(inner: L, op: F) -> Self {
+        Self {
+            inner,
+            op,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<'source, L, F> Iterator for InspectWithLexer<'source, L, F>
+where
+    L: LexerExt<'source> + Iterator,
+    F: FnMut(&L::Item, &Lexer<'source, L::Token>),
+{
+    type Item = L::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let value = self.inner.next()?;
+
+        (self.op)(&value, self.inner.as_lexer());
+
+        Some(value)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+/// An iterator that produces items until a predicate, given each item and the lexer, returns `false`.
+///
+/// Since this type contains a [Lexer], it implements the [LexerExt] trait, and allows you to access information from
+/// the underlying lexer. See the [trait's documentation][LexerExt] for more information.
+///
+/// This struct is created by the [LexerExt::take_while_with_lexer] method. See its documentation for more details.
+pub struct TakeWhileWithLexer<'source, L, F> {
+    pub(crate) inner: L,
+    op: F,
+    done: bool,
+    phantom: PhantomData<&'source ()>,
+}
+
+impl<'source, L, F> TakeWhileWithLexer<'source, L, F>
+where
+    L: LexerExt<'source> + Iterator,
+{
+    pub(crate) fn new(inner: L, op: F) -> Self {
+        Self {
+            inner,
+            op,
+            done: false,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Whether the predicate has returned `false`, or the lexer has run out of tokens.
+    #[inline]
+    pub fn is_done(&self) -> bool {
+        self.done
+    }
+}
+
+impl<'source, L, F> Iterator for TakeWhileWithLexer<'source, L, F>
+where
+    L: LexerExt<'source> + Iterator,
+    F: FnMut(&L::Item, &Lexer<'source, L::Token>) -> bool,
+{
+    type Item = L::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        match self.inner.next() {
+            Some(value) if (self.op)(&value, self.inner.as_lexer()) => Some(value),
+            _ => {
+                self.done = true;
+                None
+            }
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.done {
+            true => (0, Some(0)),
+            false => (0, self.inner.size_hint().1),
+        }
+    }
+}
+
+/// An iterator that can insert synthetic tokens between the tokens produced by the lexer.
+///
+/// Since this type contains a [Lexer], it implements the [LexerExt] trait, and allows you to access information from
+/// the underlying lexer. See the [trait's documentation][LexerExt] for more information.
+///
+/// This struct is created by the [LexerExt::inject_with] method. See its documentation for more details.
+pub struct Inject<'source, L, F>
+where
+    L: LexerExt<'source>,
+{
+    pub(crate) inner: L,
+    op: F,
+
+    /// The last token produced by the lexer, and where it ended.
+    prev: Option<L::Token>,
+    prev_end: usize,
+
+    /// A token from the lexer that's waiting for an injected token to be produced first, along with its span.
+    pending: Option<(L::Token, Span)>,
+    done: bool,
+}
+
+impl<'source, L, F> Inject<'source, L, F>
+where
+    L: LexerExt<'source>,
+{
+    pub(crate) fn new(inner: L, op: F) -> Self {
+        let prev_end = inner.as_lexer().token_end;
+
+        Self {
+            inner,
+            op,
+            prev: None,
+            prev_end,
+            pending: None,
+            done: false,
+        }
+    }
+
+    /// The span of the inner lexer's current token, without its [span offset][Lexer::with_span_offset].
+    #[inline]
+    fn raw_span(&self) -> Span {
+        let lexer = self.inner.as_lexer();
+
+        lexer.token_start..lexer.token_end
+    }
+
+    #[inline]
+    fn set_span(&mut self, span: Span) {
+        let lexer = self.inner.as_lexer_mut();
+
+        lexer.token_start = span.start;
+        lexer.token_end = span.end;
+    }
+
+    /// Call `op` with the lexer's span set to the gap between the previous token and `next_start`, and set up the
+    /// lexer for the injected token if there is one.
+    fn inject(&mut self, next: Option<&L::Token>, next_start: usize) -> Option<L::Token>
+    where
+        F: FnMut(
+            Option<&L::Token>,
+            Option<&L::Token>,
+            &Lexer<'source, L::Token>,
+        ) -> Option<L::Token>,
+    {
+        self.set_span(self.prev_end..next_start);
+
+        let injected = (self.op)(self.prev.as_ref(), next, self.inner.as_lexer());
+
+        if injected.is_some() {
+            self.set_span(self.prev_end..self.prev_end);
+        }
+
+        injected
+    }
+}
+
+impl<'source, L, F, Token> Iterator for Inject<'source, L, F>
+where
+    L: LexerExt<'source, Token = Token> + Iterator<Item = Result<Token, Token::Error>>,
+    Token: Logos<'source> + Clone,
+    F: FnMut(Option<&Token>, Option<&Token>, &Lexer<'source, Token>) -> Option<Token>,
+{
+    type Item = Result<Token, Token::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (token, span) = match self.pending.take() {
+            Some((token, span)) => {
+                self.set_span(span.clone());
+
+                (token, span)
+            }
+            None if self.done => return None,
+            None => match self.inner.next() {
+                Some(Ok(token)) => {
+                    let span = self.raw_span();
+
+                    if let Some(injected) = self.inject(Some(&token), span.start) {
+                        self.pending = Some((token, span));
+
+                        return Some(Ok(injected));
+                    }
+
+                    self.set_span(span.clone());
+
+                    (token, span)
+                }
+                Some(Err(error)) => {
+                    // The token before an error isn't really next to the one after it.
+                    self.prev = None;
+                    self.prev_end = self.raw_span().end;
+
+                    return Some(Err(error));
+                }
+                None => {
+                    self.done = true;
+
+                    let end = self.raw_span().end;
+
+                    return self.inject(None, end).map(Ok);
+                }
+            },
+        };
+
+        self.prev = Some(token.clone());
+        self.prev_end = span.end;
+
+        Some(Ok(token))
+    }
+}
+
+/// An iterator that pairs each token with its source position, as a `(start, token, end)` triple.
+///
+/// Since this type contains a [Lexer], it implements the [LexerExt] trait, and allows you to access information from
+/// the underlying lexer. See the [trait's documentation][LexerExt] for more information.
+///
+/// This struct is created by the [LexerExt::triples] method. See its documentation for more details.
+pub struct Triples<'source, L> {
+    pub(crate) inner: L,
+    phantom: PhantomData<&'source ()>,
+}
+
+impl<'source, L> Triples<'source, L>
+where
+    L: LexerExt<'source> + Iterator,
+{
+    pub(crate) fn new(inner: L) -> Self {
+        Self {
+            inner,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<'source, L, Token, E> Iterator for Triples<'source, L>
+where
+    L: LexerExt<'source> + Iterator<Item = Result<Token, E>>,
+{
+    type Item = Result<(usize, Token, usize), E>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let token = self.inner.next()?;
+        let span = self.inner.span();
+
+        Some(token.map(|token| (span.start, token, span.end)))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
 /// An iterator with a `peek()` method that can look into the future.
 ///
 /// Since this type contains a [Lexer], it implements the [LexerExt] trait, and allows you to access information from
@@ -348,3 +676,412 @@ where
 }
 
 // This concludes the source taken from the Rust standard library.
+
+/// A token along with the trivia around it, produced by the [Trivia] iterator.
+///
+/// All spans include the lexer's [span offset][Lexer::with_span_offset], like [Lexer::span].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenWithTrivia<T> {
+    /// The item produced by the lexer, which is a token or an error.
+    pub token: T,
+    /// The source position of the token.
+    pub span: Span,
+    /// Trivia before the token that isn't trailing trivia of the previous token.
+    pub leading: Span,
+    /// Trivia after the token, up to and including the end of its line.
+    pub trailing: Span,
+}
+
+/// An iterator that attaches the trivia skipped around each token to it.
+///
+/// Since this type contains a [Lexer], it implements the [LexerExt] trait, and allows you to access information from
+/// the underlying lexer. See the [trait's documentation][LexerExt] for more information. Note that the lexer is always
+/// one token ahead, so the spans in each [TokenWithTrivia] should be used instead of [LexerExt::span].
+///
+/// This struct is created by the [LexerExt::with_trivia] method. See its documentation for more details.
+pub struct Trivia<'source, L>
+where
+    L: LexerExt<'source> + Iterator,
+{
+    pub(crate) inner: L,
+
+    /// The next item from the lexer, along with its span and leading trivia, without the span offset.
+    pending: Option<(L::Item, Span, Span)>,
+    phantom: PhantomData<&'source ()>,
+}
+
+impl<'source, L> Trivia<'source, L>
+where
+    L: LexerExt<'source> + Iterator,
+{
+    pub(crate) fn new(inner: L) -> Self {
+        Self {
+            inner,
+            pending: None,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Lex the next item, with its span and everything skipped before it, which still has to be split up between the
+    /// previous token and this one.
+    #[inline]
+    fn lex(&mut self) -> Option<(L::Item, Span, Span)> {
+        let item = self.inner.next()?;
+        let lexer = self.inner.as_lexer();
+
+        Some((
+            item,
+            lexer.token_start..lexer.token_end,
+            lexer.trivia_start..lexer.token_start,
+        ))
+    }
+}
+
+impl<'source, L> Iterator for Trivia<'source, L>
+where
+    L: LexerExt<'source> + Iterator,
+{
+    type Item = TokenWithTrivia<L::Item>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (token, span, leading) = match self.pending.take() {
+            Some(pending) => pending,
+            None => self.lex()?,
+        };
+
+        let trailing = match self.lex() {
+            Some((item, next, skipped)) => {
+                let source = self.inner.source();
+                let newline = skipped
+                    .clone()
+                    .find(|&at| source.read::<u8>(at) == Some(b'\n'))
+                    .map_or(skipped.end, |at| at + 1);
+
+                self.pending = Some((item, next, newline..skipped.end));
+                skipped.start..newline
+            }
+            // Everything after the last token is its trailing trivia.
+            None => span.end..self.inner.source().len(),
+        };
+
+        let offset = self.inner.as_lexer().span_offset;
+        let shift = |span: Span| span.start + offset..span.end + offset;
+
+        Some(TokenWithTrivia {
+            token,
+            span: shift(span),
+            leading: shift(leading),
+            trailing: shift(trailing),
+        })
+    }
+}
+
+/// An iterator that checks the span of every token in debug builds, and passes tokens through unchanged otherwise.
+///
+/// Since this type contains a [Lexer], it implements the [LexerExt] trait, and allows you to access information from
+/// the underlying lexer. See the [trait's documentation][LexerExt] for more information.
+///
+/// This struct is created by the [LexerExt::debug_validate] method. See its documentation for more details.
+pub struct DebugValidate<'source, L>
+where
+    L: LexerExt<'source> + Iterator,
+{
+    pub(crate) inner: L,
+
+    /// The end of the previous token, without the span offset.
+    #[cfg(debug_assertions)]
+    prev_end: usize,
+    phantom: PhantomData<&'source ()>,
+}
+
+impl<'source, L> DebugValidate<'source, L>
+where
+    L: LexerExt<'source> + Iterator,
+{
+    pub(crate) fn new(inner: L) -> Self {
+        Self {
+            #[cfg(debug_assertions)]
+            prev_end: inner.as_lexer().token_end,
+            inner,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Check the span of the token the lexer just produced, panicking if it's wrong.
+    #[cfg(debug_assertions)]
+    fn validate(&mut self) {
+        let lexer = self.inner.as_lexer();
+        let source = lexer.source;
+        let span = lexer.token_start..lexer.token_end;
+
+        assert!(
+            span.start <= span.end,
+            "debug_validate: the span {:?} ends before it starts",
+            span
+        );
+        assert!(
+            span.end <= source.len(),
+            "debug_validate: the span {:?} is out of bounds for a source of {} bytes",
+            span,
+            source.len()
+        );
+        assert!(
+            span.start >= self.prev_end,
+            "debug_validate: the span {:?} starts before the end of the previous token at {}",
+            span,
+            self.prev_end
+        );
+
+        let slice = match source.slice(span.clone()) {
+            Some(slice) => slice,
+            None => panic!(
+                "debug_validate: the span {:?} doesn't start and end on boundaries of the source",
+                span
+            ),
+        };
+
+        assert!(
+            lexer.slice() == slice,
+            "debug_validate: the slice {:?} of the span {:?} doesn't match the source, which has {:?} there",
+            lexer.slice(),
+            span,
+            slice
+        );
+
+        self.prev_end = span.end;
+    }
+}
+
+impl<'source, L> Iterator for DebugValidate<'source, L>
+where
+    L: LexerExt<'source> + Iterator,
+{
+    type Item = L::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.inner.next()?;
+
+        #[cfg(debug_assertions)]
+        self.validate();
+
+        Some(item)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+/// Lexers and adaptors that can switch to another token type, like [Lexer::morph] does for a [Lexer].
+///
+/// This makes it possible to switch modes without unwrapping an adaptor with [LexerExt::into_lexer] and wrapping the
+/// new lexer up again. The adaptor keeps its structure, and the lexer inside it is morphed. Adaptors that are holding
+/// on to a token they've lexed ahead of time, like [Lookahead] after a call to [peek][Lookahead::peek], go back to the
+/// start of that token so that the new token type lexes it again.
+///
+/// ```
+/// use logos::iter::Morph;
+/// use logos::{LexerExt, Logos};
+///
+/// #[derive(Logos, Debug, PartialEq)]
+/// enum Outer {
+///     #[token(" ", logos::skip)]
+///     Space,
+///
+///     #[token("\"")]
+///     Quote,
+///
+///     #[regex("[a-z]+")]
+///     Word,
+/// }
+///
+/// #[derive(Logos, Debug, PartialEq)]
+/// enum Inner {
+///     #[regex(r#"[^"]+"#)]
+///     Text,
+///
+///     #[token("\"")]
+///     Quote,
+/// }
+///
+/// let mut outer = Outer::lexer("say \"a b\"").spanned().lookahead();
+///
+/// assert_eq!(outer.next(), Some(Ok((Outer::Word, 0..3))));
+/// assert_eq!(outer.next(), Some(Ok((Outer::Quote, 4..5))));
+///
+/// // Peeking lexes `a` as a word, but it's lexed again once the lexer is morphed.
+/// assert_eq!(outer.peek(), Some(&Ok((Outer::Word, 5..6))));
+///
+/// let mut inner = Morph::<Inner>::morph(outer);
+///
+/// assert_eq!(inner.next(), Some(Ok((Inner::Text, 5..8))));
+/// assert_eq!(inner.next(), Some(Ok((Inner::Quote, 8..9))));
+/// ```
+pub trait Morph<'source, Token2>
+where
+    Token2: Logos<'source>,
+{
+    /// The same adaptor, wrapping a lexer for `Token2`.
+    type Output: LexerExt<'source, Token = Token2>;
+
+    /// Switch the lexer inside to `Token2`.
+    fn morph(self) -> Self::Output;
+}
+
+impl<'source, Token, Token2> Morph<'source, Token2> for Lexer<'source, Token>
+where
+    Token: Logos<'source>,
+    Token2: Logos<'source, Source = Token::Source>,
+    Token::Extras: Into<Token2::Extras>,
+{
+    type Output = Lexer<'source, Token2>;
+
+    #[inline]
+    fn morph(self) -> Self::Output {
+        Lexer::morph(self)
+    }
+}
+
+// Only the iterator returned by `Lexer::spanned` can be morphed, since any other function is tied to the old token type.
+// See `MapWithLexer::morph_with` for the rest.
+impl<'source, L, Token2> Morph<'source, Token2>
+    for MapWithLexer<'source, L, SpanFn<'source, L::Token>>
+where
+    L: LexerExt<'source> + Morph<'source, Token2>,
+    L::Output: Iterator,
+    Token2: Logos<'source>,
+{
+    type Output = MapWithLexer<'source, L::Output, SpanFn<'source, Token2>>;
+
+    #[inline]
+    fn morph(self) -> Self::Output {
+        MapWithLexer::new(self.inner.morph(), |result, lexer| {
+            result.map(|token| (token, lexer.span()))
+        })
+    }
+}
+
+impl<'source, L, F> MapWithLexer<'source, L, F>
+where
+    L: LexerExt<'source> + Iterator,
+{
+    /// Switch the lexer inside to `Token2`, and map its tokens with `op` from now on. See [Morph] for details.
+    #[inline]
+    pub fn morph_with<Token2, F2, O>(self, op: F2) -> MapWithLexer<'source, L::Output, F2>
+    where
+        L: Morph<'source, Token2>,
+        L::Output: Iterator,
+        Token2: Logos<'source>,
+        F2: FnMut(<L::Output as Iterator>::Item, &Lexer<'source, Token2>) -> O,
+    {
+        MapWithLexer::new(self.inner.morph(), op)
+    }
+}
+
+impl<'source, L, F> Inject<'source, L, F>
+where
+    L: LexerExt<'source>,
+{
+    /// Switch the lexer inside to `Token2`, and inject tokens with `op` from now on. See [Morph] for details.
+    ///
+    /// The previous token passed to `op` is the one before the switch, so the first call has no previous token.
+    pub fn morph_with<Token2, F2>(self, op: F2) -> Inject<'source, L::Output, F2>
+    where
+        L: Morph<'source, Token2>,
+        Token2: Logos<'source>,
+        F2: FnMut(Option<&Token2>, Option<&Token2>, &Lexer<'source, Token2>) -> Option<Token2>,
+    {
+        // A token waiting behind an injected one is dropped. The lexer is still at the injected token, which is at
+        // the end of the previous token, so the new token type lexes whatever comes after it again.
+        let mut inject = Inject::new(self.inner.morph(), op);
+
+        inject.prev_end = self.prev_end;
+        inject.done = self.done;
+        inject
+    }
+}
+
+impl<'source, L, Token2> Morph<'source, Token2> for Triples<'source, L>
+where
+    L: LexerExt<'source> + Iterator + Morph<'source, Token2>,
+    L::Output: Iterator,
+    Token2: Logos<'source>,
+{
+    type Output = Triples<'source, L::Output>;
+
+    #[inline]
+    fn morph(self) -> Self::Output {
+        Triples::new(self.inner.morph())
+    }
+}
+
+impl<'source, L, Token2> Morph<'source, Token2> for DebugValidate<'source, L>
+where
+    L: LexerExt<'source> + Iterator + Morph<'source, Token2>,
+    L::Output: Iterator,
+    Token2: Logos<'source>,
+{
+    type Output = DebugValidate<'source, L::Output>;
+
+    #[inline]
+    fn morph(self) -> Self::Output {
+        DebugValidate::new(self.inner.morph())
+    }
+}
+
+impl<'source, L, Token2> Morph<'source, Token2> for Lookahead<'source, L>
+where
+    L: LexerExt<'source> + Iterator + Morph<'source, Token2>,
+    L::Output: Iterator + 'source,
+    Token2: Logos<'source>,
+{
+    type Output = Lookahead<'source, L::Output>;
+
+    fn morph(mut self) -> Self::Output {
+        // Anything skipped before the peeked token is lexed again too, just like it would be if it hadn't been peeked.
+        if let Some(Some(_)) = self.peeked {
+            let lexer = self.inner.as_lexer_mut();
+
+            lexer.token_start = lexer.trivia_start;
+            lexer.token_end = lexer.trivia_start;
+        }
+
+        Lookahead::new(self.inner.morph())
+    }
+}
+
+/// An iterator that splits the source into tokens, without constructing them.
+///
+/// Since this type contains a [Lexer], it implements the [LexerExt] trait, and allows you to access information from
+/// the underlying lexer. See the [trait's documentation][LexerExt] for more information.
+///
+/// This struct is created by the [Lexer::slices] method. See its documentation for more details.
+pub struct Slices<'source, Token: Logos<'source>> {
+    pub(crate) lexer: Lexer<'source, Token>,
+}
+
+impl<'source, Token> Iterator for Slices<'source, Token>
+where
+    Token: Logos<'source>,
+{
+    type Item = (
+        &'source <Token::Source as Source>::Slice,
+        Span,
+        Option<usize>,
+    );
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let lexer = &mut self.lexer;
+        let kind = match (lexer.next(), lexer.segment.take()) {
+            (_, Some(kind)) => Some(kind),
+            (Some(Ok(token)), None) => Some(token.index()),
+            (Some(Err(_)), None) => None,
+            (None, None) => return None,
+        };
+
+        Some((lexer.slice(), lexer.span(), kind))
+    }
+}
@@ -1,7 +1,14 @@
+#[cfg(feature = "alloc")]
+use crate::captures::{self, Captures};
 use crate::error::Error;
-use crate::iter::MapWithLexer;
+use crate::guard::Guard;
+use crate::internal::CaptureProgram;
+use crate::iter::{MapWithLexer, Slices};
+use crate::owned::OwnedToken;
+#[cfg(feature = "std")]
+use crate::replay::LexerState;
 use crate::source::Source;
-use crate::Logos;
+use crate::{LexerOptions, Logos};
 
 use core::fmt::{self, Debug};
 use core::mem::ManuallyDrop;
@@ -9,13 +16,73 @@ use core::mem::ManuallyDrop;
 /// A byte range in the source.
 pub type Span = core::ops::Range<usize>;
 
+/// A byte range in a source shorter than 4 GiB, which takes half the space of a [Span] on 64-bit targets.
+///
+/// Spans can be converted to this (or any other type that implements `TryFrom<Span>`) as they're produced with
+/// [LexerExt::spanned_as](crate::LexerExt::spanned_as).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Span32 {
+    /// The position of the first byte in the range.
+    pub start: u32,
+    /// The position just after the last byte in the range.
+    pub end: u32,
+}
+
+impl Span32 {
+    /// Create a `Span32` from `start` to `end`.
+    #[inline]
+    pub const fn new(start: u32, end: u32) -> Self {
+        Span32 { start, end }
+    }
+
+    /// The number of bytes in the range.
+    #[inline]
+    pub const fn len(&self) -> u32 {
+        self.end - self.start
+    }
+
+    /// Returns `true` if the range contains no bytes.
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+
+    /// Convert the range back into a [Span], for indexing into a source.
+    #[inline]
+    pub const fn range(&self) -> Span {
+        self.start as usize..self.end as usize
+    }
+}
+
+impl From<Span32> for Span {
+    #[inline]
+    fn from(span: Span32) -> Span {
+        span.range()
+    }
+}
+
+impl TryFrom<Span> for Span32 {
+    type Error = core::num::TryFromIntError;
+
+    /// Convert a [Span], failing if either end doesn't fit in a `u32`.
+    #[inline]
+    fn try_from(span: Span) -> Result<Span32, Self::Error> {
+        Ok(Span32 {
+            start: u32::try_from(span.start)?,
+            end: u32::try_from(span.end)?,
+        })
+    }
+}
+
 // This is basically a slightly less ugly way of writing `fn(Result<Token, Token::Error>, Lexer<'source, Token>) ->
-// Result<(Token, Span), Token::Error>`. It also looks like a cute little type-level function.
+// Result<(Token, Span), Token::Error>`, or the same for any other item type. It also looks like a cute little
+// type-level function.
 //
 // Being forced to disambiguate associated types truly is of the Devil.
 type ErrorOf<'s, T> = <T as Logos<'s>>::Error;
-type ResultOf<'s, T, U> = Result<U, ErrorOf<'s, T>>;
-type SpanFn<'s, T> = fn(ResultOf<'s, T, T>, &Lexer<'s, T>) -> ResultOf<'s, T, (T, Span)>;
+pub(crate) type SpanFn<'s, T> = SpannedFn<'s, T, T, ErrorOf<'s, T>>;
+pub(crate) type SpannedFn<'s, T, U, E> = fn(Result<U, E>, &Lexer<'s, T>) -> Result<(U, Span), E>;
+pub(crate) type SpannedAsFn<'s, T, U, E, S> = fn(Result<U, E>, &Lexer<'s, T>) -> Result<(U, S), E>;
 
 /// A `Lexer` allows you to read through a source (a type implementing the [Source] trait, like a string
 /// slice) and produce tokens using the [Logos] trait. It's important to note that you should *not* implement [Logos]
@@ -56,6 +123,34 @@ pub struct Lexer<'source, Token: Logos<'source>> {
     pub(crate) token_start: usize,
     pub(crate) token_end: usize,
 
+    /// Where the previous token ended, so that anything skipped since then can be found again.
+    pub(crate) trivia_start: usize,
+
+    /// The span of the last token matched by a regex with capture groups, and the program that finds them.
+    pub(crate) captures: Option<(usize, usize, &'static CaptureProgram)>,
+
+    /// Index of the definition that matched the last token, among the definitions of its variant.
+    pub(crate) matched_rule: usize,
+
+    /// Bit set of the contextual tokens that are currently enabled.
+    pub(crate) contextual: u64,
+
+    pub(crate) options: LexerOptions,
+
+    /// Added to every span the lexer reports, see [Lexer::with_span_offset].
+    pub(crate) span_offset: usize,
+
+    /// Whether the variant marked with `#[logos(eof)]` has been produced yet.
+    pub(crate) eof_emitted: bool,
+
+    /// Whether the lexer has reached the end of its input yet, and run its `on_eof` hooks.
+    pub(crate) finished: bool,
+
+    /// Whether tokens are only being segmented, see [Lexer::slices], and the kind of the last token if it wasn't
+    /// constructed.
+    pub(crate) segmenting: bool,
+    pub(crate) segment: Option<usize>,
+
     /// The "extras" associated with `Token`.
     pub extras: Token::Extras,
 }
@@ -74,6 +169,54 @@ where
     }
 }
 
+/// Everything in a [Lexer] apart from its source and current token, so that a lexer can be taken apart and put back
+/// together around a source that's borrowed again later, like [OwnedLexer](crate::owned::OwnedLexer) does.
+pub(crate) struct LexerParts<Extras> {
+    pub token_start: usize,
+    pub token_end: usize,
+    pub trivia_start: usize,
+    pub captures: Option<(usize, usize, &'static CaptureProgram)>,
+    pub matched_rule: usize,
+    pub contextual: u64,
+    pub options: LexerOptions,
+    pub span_offset: usize,
+    pub eof_emitted: bool,
+    pub finished: bool,
+    pub segmenting: bool,
+    pub segment: Option<usize>,
+    pub extras: Extras,
+}
+
+impl<Extras> LexerParts<Extras> {
+    /// The parts of a lexer that hasn't lexed anything yet.
+    pub fn new(extras: Extras) -> Self {
+        LexerParts {
+            token_start: 0,
+            token_end: 0,
+            trivia_start: 0,
+            captures: None,
+            matched_rule: 0,
+            contextual: 0,
+            options: LexerOptions::EMPTY,
+            span_offset: 0,
+            eof_emitted: false,
+            finished: false,
+            segmenting: false,
+            segment: None,
+            extras,
+        }
+    }
+}
+
+impl<Extras: Clone> Clone for LexerParts<Extras> {
+    fn clone(&self) -> Self {
+        LexerParts {
+            extras: self.extras.clone(),
+            ..*self
+        }
+    }
+}
+
 impl<'source, Token: Logos<'source>> Lexer<'source, Token> {
     /// Create a new `Lexer`.
     ///
@@ -96,13 +239,175 @@ impl<'source, Token: Logos<'source>> Lexer<'source, Token> {
     /// In most cases, you can use [Lexer::new] instead. You should only use this function if you need to set up your
     /// lexer in a way that doesn't play nicely with the [Default] trait.
     pub fn with_extras(source: &'source Token::Source, extras: Token::Extras) -> Self {
-        Lexer {
+        let mut lexer = Lexer {
             source,
             token: ManuallyDrop::new(None),
             extras,
             token_start: 0,
             token_end: 0,
-        }
+            trivia_start: 0,
+            captures: None,
+            matched_rule: 0,
+            contextual: 0,
+            options: LexerOptions::EMPTY,
+            span_offset: 0,
+            eof_emitted: false,
+            finished: false,
+            segmenting: false,
+            segment: None,
+        };
+
+        Token::on_start(&mut lexer);
+
+        lexer
+    }
+
+    /// Create a new `Lexer` that starts lexing at byte `start` of `source`, instead of at the beginning. Spans are
+    /// still positions in the whole of `source`, which makes this useful for lexing a part of a document (like the
+    /// body of a single function) again after it was edited.
+    ///
+    /// ```
+    /// use logos::Logos;
+    ///
+    /// #[derive(Logos, Debug, PartialEq)]
+    /// enum Token {
+    ///     #[regex(r"[ \n]+", logos::skip)]
+    ///     Whitespace,
+    ///
+    ///     #[regex("[a-z]+")]
+    ///     Word,
+    /// }
+    ///
+    /// let mut lexer = logos::Lexer::<Token>::with_offset("one two", 3);
+    ///
+    /// assert_eq!(lexer.next(), Some(Ok(Token::Word)));
+    /// assert_eq!(lexer.span(), 4..7);
+    /// assert_eq!(lexer.next(), None);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `start` isn't a boundary in the source, see [Lexer::seek].
+    pub fn with_offset(source: &'source Token::Source, start: usize) -> Self
+    where
+        Token::Extras: Default,
+    {
+        let mut lexer = Self::new(source);
+
+        lexer.seek(start);
+        lexer
+    }
+
+    /// Replace the lexer's [options][LexerOptions], returning the lexer. This is meant to be used right after creating
+    /// a lexer.
+    ///
+    /// ```
+    /// # use logos::{LexerOptions, Logos};
+    /// # #[derive(Logos)]
+    /// # enum Token {}
+    /// const TRAILING_COMMAS: LexerOptions = LexerOptions::bit(3);
+    ///
+    /// let lexer = Token::lexer("").with_options(TRAILING_COMMAS);
+    ///
+    /// assert!(lexer.options().contains(TRAILING_COMMAS));
+    /// ```
+    #[inline]
+    pub fn with_options(mut self, options: LexerOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// The lexer's current [options][LexerOptions]. No options are set by default.
+    #[inline]
+    pub fn options(&self) -> LexerOptions {
+        self.options
+    }
+
+    /// Get a mutable reference to the lexer's [options][LexerOptions], so that they can be changed while lexing.
+    #[inline]
+    pub fn options_mut(&mut self) -> &mut LexerOptions {
+        &mut self.options
+    }
+
+    /// Set or unset `flag` in the lexer's [options][LexerOptions]. Parsers can use this to tell the lexer about their
+    /// context between tokens, which definitions check with `requires(...)`. See the documentation on `requires` in the
+    /// [Logos] trait for an example.
+    #[inline]
+    pub fn set_flag(&mut self, flag: LexerOptions, value: bool) {
+        self.options.set(flag, value);
+    }
+
+    /// Check whether all of `flag` is set in the lexer's [options][LexerOptions].
+    #[inline]
+    pub fn flag(&self, flag: LexerOptions) -> bool {
+        self.options.contains(flag)
+    }
+
+    /// Set the lexer's span offset, returning the lexer. This is meant to be used right after creating a lexer.
+    ///
+    /// The offset is added to every span the lexer reports, which is useful when `source` is a snippet embedded in a
+    /// larger document (a code block in a markdown file, say), and spans should point into the whole document. It
+    /// doesn't affect [Lexer::slice] or [Lexer::remainder], which still index into `source`.
+    ///
+    /// ```
+    /// use logos::Logos;
+    ///
+    /// #[derive(Logos, Debug, PartialEq)]
+    /// enum Token {
+    ///     #[token(" ", logos::skip)]
+    ///     Space,
+    ///
+    ///     #[regex("[a-z]+")]
+    ///     Word,
+    /// }
+    ///
+    /// let document = "```\nfoo bar\n```";
+    /// let snippet = &document[4..11];
+    /// let mut lexer = Token::lexer(snippet).with_span_offset(4);
+    ///
+    /// assert_eq!(lexer.next(), Some(Ok(Token::Word)));
+    /// assert_eq!(lexer.next(), Some(Ok(Token::Word)));
+    /// assert_eq!(lexer.span(), 8..11);
+    /// assert_eq!(lexer.slice(), "bar");
+    /// assert_eq!(&document[lexer.span()], "bar");
+    /// ```
+    #[inline]
+    pub fn with_span_offset(mut self, offset: usize) -> Self {
+        self.span_offset = offset;
+        self
+    }
+
+    /// The lexer's span offset, which is `0` unless it was set with [Lexer::with_span_offset] or
+    /// [Lexer::set_span_offset].
+    #[inline]
+    pub fn span_offset(&self) -> usize {
+        self.span_offset
+    }
+
+    /// Set the lexer's span offset, like [Lexer::with_span_offset] but without taking the lexer by value. This can be
+    /// done at any point: the spans reported from then on (including the current token's) use the new offset.
+    ///
+    /// ```
+    /// use logos::Logos;
+    ///
+    /// #[derive(Logos, Debug, PartialEq)]
+    /// enum Token {
+    ///     #[regex("[a-z]+")]
+    ///     Word,
+    /// }
+    ///
+    /// let document = "text\n```\nfoo\n```";
+    /// let mut lexer = Token::lexer("");
+    ///
+    /// lexer.reset(&document[9..12]);
+    /// lexer.set_span_offset(9);
+    ///
+    /// assert_eq!(lexer.next(), Some(Ok(Token::Word)));
+    /// assert_eq!(&document[lexer.span()], "foo");
+    /// ```
+    #[inline]
+    pub fn set_span_offset(&mut self, offset: usize) {
+        self.span_offset = offset;
     }
 
     /// Wrap the lexer in an [Iterator] that pairs tokens with their source positions.
@@ -113,9 +418,11 @@ impl<'source, Token: Logos<'source>> Lexer<'source, Token> {
     ///
     /// This method is a shorthand for using [LexerExt::map_with_lexer] and a callback that returns (`Token`, [Span])
     /// tuples. If you'd like to use a different span type, or wish to perform any other sort of processing, you should
-    /// use the [LexerExt::map_with_lexer] method directly.
+    /// use the [LexerExt::map_with_lexer] method directly. [LexerExt::spanned] does the same for adaptors like
+    /// [Lookahead][crate::iter::Lookahead].
     ///
     /// [LexerExt::map_with_lexer]: crate::LexerExt::map_with_lexer
+    /// [LexerExt::spanned]: crate::LexerExt::spanned
     ///
     /// # Example
     ///
@@ -148,9 +455,58 @@ impl<'source, Token: Logos<'source>> Lexer<'source, Token> {
     /// ```
     #[inline]
     pub fn spanned(self) -> MapWithLexer<'source, Self, SpanFn<'source, Token>> {
-        use crate::LexerExt;
+        crate::LexerExt::spanned(self)
+    }
+
+    /// Wrap the lexer in an [Iterator] that only splits the source into tokens, without constructing them.
+    ///
+    /// The iterator produces `(slice, span, kind)` triples, where `kind` is the index of the token's variant in the
+    /// order that variants are declared, or `None` for input that wasn't recognised. Callbacks aren't run and tokens
+    /// with fields aren't constructed, which saves a lot of work for tools that only need to know where tokens are,
+    /// like word counters and syntax-aware diffs. Since callbacks aren't run, tokens that are normally skipped with a
+    /// callback like [skip][crate::skip] are produced too.
+    ///
+    /// ```
+    /// use logos::Logos;
+    ///
+    /// #[derive(Logos, Debug, PartialEq)]
+    /// enum Token {
+    ///     #[regex(r"[ \t\n]+", logos::skip)]
+    ///     Whitespace,
+    ///
+    ///     #[regex("[a-z]+", |lex| lex.slice().to_uppercase())]
+    ///     Word(String),
+    ///
+    ///     #[token(".")]
+    ///     Period,
+    /// }
+    ///
+    /// let slices: Vec<_> = Token::lexer("hi there.!").slices().collect();
+    ///
+    /// assert_eq!(
+    ///     slices,
+    ///     &[
+    ///         ("hi", 0..2, Some(1)),
+    ///         (" ", 2..3, Some(0)),
+    ///         ("there", 3..8, Some(1)),
+    ///         (".", 8..9, Some(2)),
+    ///         ("!", 9..10, None),
+    ///     ],
+    /// );
+    /// ```
+    #[inline]
+    pub fn slices(mut self) -> Slices<'source, Token> {
+        self.segmenting = true;
 
-        self.map_with_lexer(|result, lexer| result.map(|token| (token, lexer.span())))
+        Slices { lexer: self }
+    }
+
+    /// Wrap the lexer in an [Iterator] that starts coalescing runs of errors into single errors once tokens average
+    /// less than `min_average` bytes, which is judged once at least `warmup` tokens have been lexed. See the
+    /// [guard module](crate::guard) for more details.
+    #[inline]
+    pub fn guard(self, min_average: usize, warmup: usize) -> Guard<'source, Token> {
+        Guard::new(self, min_average, warmup)
     }
 
     #[inline]
@@ -160,10 +516,10 @@ impl<'source, Token: Logos<'source>> Lexer<'source, Token> {
         self.span()
     }
 
-    /// The source position of the current token.
+    /// The source position of the current token, shifted by the lexer's [span offset][Lexer::with_span_offset].
     #[inline]
     pub fn span(&self) -> Span {
-        self.token_start..self.token_end
+        self.token_start + self.span_offset..self.token_end + self.span_offset
     }
 
     /// The source that tokens are being read from. The return type of this method is determined by [Logos::Source], and
@@ -177,7 +533,10 @@ impl<'source, Token: Logos<'source>> Lexer<'source, Token> {
     /// be [&str][str] for most lexers.
     #[inline]
     pub fn slice(&self) -> &'source <Token::Source as Source>::Slice {
-        unsafe { self.source.slice_unchecked(self.span()) }
+        unsafe {
+            self.source
+                .slice_unchecked(self.token_start..self.token_end)
+        }
     }
 
     /// A slice containing the remaining source. This is similar to [Lexer::source], but starts  at the end of the
@@ -191,6 +550,104 @@ impl<'source, Token: Logos<'source>> Lexer<'source, Token> {
         }
     }
 
+    /// A slice containing everything that was skipped between the previous token and the current one, such as
+    /// whitespace and comments. This is empty if nothing was skipped. See
+    /// [LexerExt::with_trivia][crate::LexerExt::with_trivia] for an iterator that attaches this to every token.
+    ///
+    /// ```
+    /// use logos::Logos;
+    ///
+    /// #[derive(Logos, Debug, PartialEq)]
+    /// enum Token {
+    ///     #[regex(r"[ \t\n]+", logos::skip)]
+    ///     #[regex(r"#[^\n]*", logos::skip)]
+    ///     Trivia,
+    ///
+    ///     #[regex("[a-z]+")]
+    ///     Word,
+    /// }
+    ///
+    /// let mut lexer = Token::lexer("foo # comment\n  bar");
+    ///
+    /// assert_eq!(lexer.next(), Some(Ok(Token::Word)));
+    /// assert_eq!(lexer.leading_trivia(), "");
+    /// assert_eq!(lexer.next(), Some(Ok(Token::Word)));
+    /// assert_eq!(lexer.leading_trivia(), " # comment\n  ");
+    /// ```
+    #[inline]
+    pub fn leading_trivia(&self) -> &'source <Token::Source as Source>::Slice {
+        unsafe {
+            self.source
+                .slice_unchecked(self.trivia_start..self.token_start)
+        }
+    }
+
+    /// The capture groups of the current token, or `None` if it wasn't matched by a `#[regex]` containing capture
+    /// groups.
+    ///
+    /// Groups are always found within the token as it was matched, even if a callback has since bumped the span.
+    /// See the [captures module](crate::captures) for an example.
+    #[cfg(feature = "alloc")]
+    pub fn captures(&self) -> Option<Captures<'source, Token::Source>> {
+        match self.captures {
+            Some((start, end, program)) if start == self.token_start => {
+                captures::find(self.source, start..end, self.span_offset, program)
+            }
+            _ => None,
+        }
+    }
+
+    /// Which of its variant's definitions matched the current token, counting each `#[token]` and `#[regex]` attribute
+    /// of the variant from `0` in the order they're written. This is always `0` for variants with a single definition.
+    ///
+    /// Callbacks shared by several definitions can use this to tell them apart without looking at the slice again.
+    /// The value isn't meaningful for errors.
+    ///
+    /// ```
+    /// use logos::{Lexer, Logos};
+    ///
+    /// #[derive(Debug, PartialEq)]
+    /// enum Radix {
+    ///     Hex,
+    ///     Octal,
+    ///     Binary,
+    /// }
+    ///
+    /// fn radix(lex: &mut Lexer<Token>) -> Radix {
+    ///     match lex.matched_rule() {
+    ///         0 => Radix::Hex,
+    ///         1 => Radix::Octal,
+    ///         _ => Radix::Binary,
+    ///     }
+    /// }
+    ///
+    /// #[derive(Logos, Debug, PartialEq)]
+    /// enum Token {
+    ///     #[regex(r"[ \t\n]+", logos::skip)]
+    ///     Whitespace,
+    ///
+    ///     #[regex("0x[0-9a-f]+", radix)]
+    ///     #[regex("0o[0-7]+", radix)]
+    ///     #[regex("0b[01]+", radix)]
+    ///     Number(Radix),
+    /// }
+    ///
+    /// let tokens: Vec<_> = Token::lexer("0b10 0xff 0o7").collect();
+    ///
+    /// assert_eq!(
+    ///     tokens,
+    ///     &[
+    ///         Ok(Token::Number(Radix::Binary)),
+    ///         Ok(Token::Number(Radix::Hex)),
+    ///         Ok(Token::Number(Radix::Octal)),
+    ///     ]
+    /// );
+    /// ```
+    #[inline]
+    pub fn matched_rule(&self) -> usize {
+        self.matched_rule
+    }
+
     /// Create a new error value representing a generic "unknown token" error.
     ///
     /// This is a convenience method intended for use within lexer callbacks. You can customise the behaviour of this
@@ -201,6 +658,44 @@ impl<'source, Token: Logos<'source>> Lexer<'source, Token> {
         Token::Error::unknown_token(self)
     }
 
+    /// Enable the contextual definitions of `token`'s variant, so that they produce `token`'s variant instead of
+    /// whatever they shadow.
+    ///
+    /// See the documentation on `contextual` in the [Logos] trait for an example.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `token`'s variant doesn't have any contextual definitions.
+    pub fn enable_contextual(&mut self, token: Token) {
+        self.contextual |= 1 << Self::contextual_bit(&token);
+    }
+
+    /// Disable the contextual definitions of `token`'s variant, so that they produce whatever they shadow. This is
+    /// the default for all contextual definitions.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `token`'s variant doesn't have any contextual definitions.
+    pub fn disable_contextual(&mut self, token: Token) {
+        self.contextual &= !(1 << Self::contextual_bit(&token));
+    }
+
+    /// Check whether the contextual definitions of `token`'s variant are enabled.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `token`'s variant doesn't have any contextual definitions.
+    pub fn is_contextual_enabled(&self, token: Token) -> bool {
+        self.contextual & (1 << Self::contextual_bit(&token)) != 0
+    }
+
+    fn contextual_bit(token: &Token) -> u32 {
+        match token.contextual_bit() {
+            Some(bit) => bit,
+            None => panic!("token does not have any contextual definitions"),
+        }
+    }
+
     /// Turn this lexer into a lexer for a new token type.
     ///
     /// The new lexer points at the same span as this one, but the current token will be replaced with an error.
@@ -215,9 +710,171 @@ impl<'source, Token: Logos<'source>> Lexer<'source, Token> {
             extras: self.extras.into(),
             token_start: self.token_start,
             token_end: self.token_end,
+            trivia_start: self.trivia_start,
+            captures: self.captures,
+            matched_rule: 0,
+            contextual: 0,
+            options: self.options,
+            span_offset: self.span_offset,
+            eof_emitted: false,
+            finished: self.finished,
+            segmenting: false,
+            segment: None,
         }
     }
 
+    /// Start lexing `source` from the beginning, keeping the lexer's extras and options.
+    ///
+    /// This is useful when the extras are expensive to set up (an interner, say) and are shared by many small inputs.
+    /// Any contextual definitions that were enabled are disabled again, and the [span offset][Lexer::with_span_offset]
+    /// goes back to `0`.
+    ///
+    /// ```
+    /// use logos::Logos;
+    ///
+    /// #[derive(Logos, Debug, PartialEq)]
+    /// #[logos(extras = usize)]
+    /// enum Token {
+    ///     #[regex("[a-z]+", |lex| lex.extras += 1)]
+    ///     Word,
+    /// }
+    ///
+    /// let mut lexer = Token::lexer("abc");
+    ///
+    /// assert_eq!(lexer.next(), Some(Ok(Token::Word)));
+    ///
+    /// lexer.reset("de");
+    ///
+    /// assert_eq!(lexer.span(), 0..0);
+    /// assert_eq!(lexer.next(), Some(Ok(Token::Word)));
+    /// assert_eq!(lexer.slice(), "de");
+    /// assert_eq!(lexer.extras, 2);
+    /// ```
+    pub fn reset(&mut self, source: &'source Token::Source) {
+        self.source = source;
+        self.token = ManuallyDrop::new(None);
+        self.token_start = 0;
+        self.token_end = 0;
+        self.trivia_start = 0;
+        self.captures = None;
+        self.matched_rule = 0;
+        self.contextual = 0;
+        self.span_offset = 0;
+        self.eof_emitted = false;
+        self.finished = false;
+        self.segment = None;
+
+        Token::on_start(self);
+    }
+
+    /// Move the lexer to `position`, so that the next token is lexed from there. Like spans, the position includes the
+    /// lexer's [span offset](Lexer::span_offset), so `lexer.seek(lexer.span().start)` lexes the current token again.
+    ///
+    /// Only the lexer's position is changed: the extras, [options](Lexer::options) and
+    /// [contextual definitions](Lexer::enable_contextual) are left as they are. If the lexer had reached the end of
+    /// its input, it carries on from `position` as if it hadn't.
+    ///
+    /// ```
+    /// use logos::Logos;
+    ///
+    /// #[derive(Logos, Debug, PartialEq)]
+    /// enum Token {
+    ///     #[regex(r"[ \n]+", logos::skip)]
+    ///     Whitespace,
+    ///
+    ///     #[regex("[a-z]+")]
+    ///     Word,
+    /// }
+    ///
+    /// let mut lexer = Token::lexer("one two");
+    ///
+    /// assert_eq!(lexer.next(), Some(Ok(Token::Word)));
+    /// assert_eq!(lexer.next(), Some(Ok(Token::Word)));
+    /// assert_eq!(lexer.next(), None);
+    ///
+    /// lexer.seek(4);
+    ///
+    /// assert_eq!(lexer.next(), Some(Ok(Token::Word)));
+    /// assert_eq!(lexer.span(), 4..7);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `position` isn't a boundary in the source (like the middle of a UTF-8 code point in a `&str`), or is
+    /// outside of it.
+    pub fn seek(&mut self, position: usize) {
+        let at = match position
+            .checked_sub(self.span_offset)
+            .filter(|&at| self.source.is_boundary(at))
+        {
+            Some(at) => at,
+            None => panic!(
+                "cannot seek to byte {} as it is not a valid index for the source",
+                position
+            ),
+        };
+
+        self.token = ManuallyDrop::new(None);
+        self.token_start = at;
+        self.token_end = at;
+        self.trivia_start = at;
+        self.captures = None;
+        self.eof_emitted = false;
+        self.finished = false;
+        self.segment = None;
+    }
+
+    /// Take a comparable snapshot of the lexer's state. See the [replay module][crate::replay] for details.
+    #[cfg(feature = "std")]
+    pub fn state(&self) -> LexerState
+    where
+        Token::Extras: core::hash::Hash,
+    {
+        LexerState::new(self)
+    }
+
+    /// Put the lexer back into a `state` taken with [Lexer::state], so that lexing carries on from the token it was
+    /// taken after. The extras are left as they are, since a state only has a digest of them.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the state's positions aren't valid indices for the source, which can happen if it was taken from a
+    /// lexer with a different source or span offset.
+    #[cfg(feature = "std")]
+    pub fn restore(&mut self, state: &LexerState) {
+        let valid = |position: usize| {
+            position
+                .checked_sub(self.span_offset)
+                .filter(|&position| self.source.is_boundary(position))
+        };
+
+        match (
+            valid(state.span.start),
+            valid(state.span.end),
+            valid(state.trivia_start),
+        ) {
+            (Some(start), Some(end), Some(trivia_start))
+                if trivia_start <= start && start <= end =>
+            {
+                self.token_start = start;
+                self.token_end = end;
+                self.trivia_start = trivia_start;
+            }
+            _ => panic!(
+                "the state {:?} doesn't belong to this lexer's source",
+                state
+            ),
+        }
+
+        self.token = ManuallyDrop::new(None);
+        self.captures = None;
+        self.contextual = state.contextual;
+        self.options = state.options;
+        self.eof_emitted = false;
+        self.finished = false;
+        self.segment = None;
+    }
+
     /// Bump the current span by `n` bytes.
     ///
     /// # Panics
@@ -240,6 +897,125 @@ impl<'source, Token: Logos<'source>> Lexer<'source, Token> {
     }
 }
 
+impl<'source, Token: OwnedToken> Lexer<'source, Token> {
+    /// Like [Lexer::reset], but for a source with a different lifetime. This consumes the lexer and returns a new one
+    /// for the new source, with the same extras and options.
+    ///
+    /// This lets a lexer be kept around (in a pool, for example) and reused for inputs that don't live as long as it
+    /// does. It's only available for token types that don't borrow from the source - see [OwnedToken].
+    ///
+    /// ```
+    /// use logos::{Lexer, Logos};
+    ///
+    /// #[derive(Logos, Debug, PartialEq)]
+    /// #[logos(extras = Vec<String>)]
+    /// enum Token {
+    ///     #[regex("[a-z]+", |lex| lex.extras.push(lex.slice().to_owned()))]
+    ///     Word,
+    /// }
+    ///
+    /// let mut lexer: Lexer<'static, Token> = Token::lexer("");
+    ///
+    /// for input in ["abc", "de"] {
+    ///     let source = String::from(input);
+    ///     let mut borrowed = lexer.rebind(&source);
+    ///
+    ///     assert_eq!(borrowed.next(), Some(Ok(Token::Word)));
+    ///
+    ///     lexer = borrowed.rebind("");
+    /// }
+    ///
+    /// assert_eq!(lexer.extras, &["abc", "de"]);
+    /// ```
+    pub fn rebind<'new>(self, source: &'new Token::OwnedSource) -> Lexer<'new, Token> {
+        let mut lexer = Lexer {
+            source,
+            token: ManuallyDrop::new(None),
+            extras: self.extras,
+            token_start: 0,
+            token_end: 0,
+            trivia_start: 0,
+            captures: None,
+            matched_rule: 0,
+            contextual: 0,
+            options: self.options,
+            span_offset: 0,
+            eof_emitted: false,
+            finished: false,
+            segmenting: false,
+            segment: None,
+        };
+
+        Token::on_start(&mut lexer);
+
+        lexer
+    }
+}
+
+impl<'source, Token: Logos<'source>> Lexer<'source, Token> {
+    /// Put a lexer back together from its parts, without running any [Lifecycle](crate::Lifecycle) hooks.
+    pub(crate) fn from_parts(
+        source: &'source Token::Source,
+        parts: LexerParts<Token::Extras>,
+    ) -> Self {
+        Lexer {
+            source,
+            token: ManuallyDrop::new(None),
+            token_start: parts.token_start,
+            token_end: parts.token_end,
+            trivia_start: parts.trivia_start,
+            captures: parts.captures,
+            matched_rule: parts.matched_rule,
+            contextual: parts.contextual,
+            options: parts.options,
+            span_offset: parts.span_offset,
+            eof_emitted: parts.eof_emitted,
+            finished: parts.finished,
+            segmenting: parts.segmenting,
+            segment: parts.segment,
+            extras: parts.extras,
+        }
+    }
+
+    /// Take the lexer apart, forgetting its source and current token.
+    pub(crate) fn into_parts(self) -> LexerParts<Token::Extras> {
+        // The current token has already been moved out by `next`, even though it's still in the `ManuallyDrop`.
+        let Lexer {
+            source: _,
+            token: _,
+            token_start,
+            token_end,
+            trivia_start,
+            captures,
+            matched_rule,
+            contextual,
+            options,
+            span_offset,
+            eof_emitted,
+            finished,
+            segmenting,
+            segment,
+            extras,
+        } = self;
+
+        LexerParts {
+            token_start,
+            token_end,
+            trivia_start,
+            captures,
+            matched_rule,
+            contextual,
+            options,
+            span_offset,
+            eof_emitted,
+            finished,
+            segmenting,
+            segment,
+            extras,
+        }
+    }
+}
+
 impl<'source, Token> Clone for Lexer<'source, Token>
 where
     Token: Logos<'source> + Clone,
@@ -159,6 +159,18 @@
 //! case, the definition using `"[abc]+"` now has a higher priority, so all sequences of `c` will match the `[abc]+`
 //! definition instead.
 //!
+//! ## `no_std`
+//!
+//! Logos works without the standard library, by turning off the default `std` feature. Generated lexers, the [Lexer]
+//! and most of its adaptors only need `core`. Everything that allocates - like [BoxedLexer][iter::BoxedLexer],
+//! captures, `followed_by` checks, diagnostics and token storage - is available with the `alloc` feature instead, so it
+//! can be used on targets that have an allocator but no operating system. The few pieces that need the standard
+//! library itself, like the `fuzz` and `replay` modules, stay behind `std`.
+//!
+//! ```toml
+//! logos = { version = "0.13", default-features = false, features = ["export_derive", "alloc"] }
+//! ```
+//!
 //! ## How fast?
 //!
 //! Ridiculously fast!
@@ -169,6 +181,10 @@
 //! test strings                            ... bench:         553 ns/iter (+/- 34) = 1575 MB/s
 //! ```
 //!
+//! Long runs of a small character class, like long identifiers or deep indentation, can be lexed faster still with the
+//! `simd` feature. With it, Logos checks the rest of a run 16 bytes at a time once it's longer than 16 bytes, using SSE2
+//! (on x86) or NEON (on ARM). Other targets check bytes one at a time, just like without the feature.
+//!
 //! ### What about the changes you made?
 //!
 //! I ran benchmarks on both this fork and the upstream repository, and rest assured that even *after* my changes, Logos is
@@ -195,19 +211,60 @@
 #[cfg(not(feature = "std"))]
 extern crate core as std;
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+// The derive macro refers to everything through `::logos`, so this makes it usable inside the crate too.
+#[cfg(feature = "export_derive")]
+extern crate self as logos;
+
 #[doc(hidden)]
 pub mod internal;
 
+#[cfg(feature = "alloc")]
+pub mod cache;
 pub mod callback;
+#[cfg(feature = "alloc")]
+pub mod captures;
+pub mod columns;
+pub mod compat;
+#[cfg(feature = "alloc")]
+pub mod diagnostics;
 pub mod error;
 mod ext;
+#[cfg(feature = "std")]
+pub mod fuzz;
+pub mod guard;
 pub mod iter;
 mod lexer;
+mod macros;
+mod options;
+pub mod owned;
+#[cfg(feature = "parallel")]
+pub mod parallel;
+#[cfg(feature = "alloc")]
+pub mod region;
+#[cfg(feature = "std")]
+pub mod replay;
+#[cfg(feature = "alloc")]
+pub mod rewrite;
+pub mod ring;
+#[cfg(feature = "segmented")]
+pub mod segmented;
+#[cfg(feature = "alloc")]
+pub mod semantic;
+#[cfg(all(feature = "alloc", feature = "export_derive"))]
+pub mod shell;
+#[cfg(feature = "alloc")]
+pub mod soa;
 pub mod source;
+#[cfg(feature = "alloc")]
+mod varint;
 
-pub use crate::error::{Error, UnknownToken};
+pub use crate::error::{Error, SpannedError, UnknownToken};
 pub use crate::ext::LexerExt;
-pub use crate::lexer::{Lexer, Span};
+pub use crate::lexer::{Lexer, Span, Span32};
+pub use crate::options::LexerOptions;
 pub use crate::source::Source;
 #[cfg(feature = "export_derive")]
 pub use logos_derive::Logos;
@@ -242,10 +299,39 @@ pub use logos_derive::Logos;
 /// Logos will use this type to report lexing errors - namely, encountering an unknown token - but you can also use this
 /// type within callbacks to emit more detailed errors.
 ///
-/// The error type is [UnknownToken] by default.
+/// The error type is [UnknownToken] by default. If you'd like errors to record where they happened, use
+/// `#[logos(error = SpannedError<'s>)]` (see [SpannedError]). Lifetimes in the error type are treated the same way as
+/// lifetimes in the enum's fields.
 ///
 /// See the [documentation on callbacks](./callback/index.html) for details not covered here.
 ///
+/// ### `#[logos(no_panic_error)]`
+///
+/// Require the error type to implement [NoPanicError][error::NoPanicError], a promise that constructing it can't panic
+/// or allocate. A panic while constructing an error then aborts the process in debug builds. This is for lexers that
+/// need to guarantee how they fail, see the trait's documentation for an example.
+///
+/// ```compile_fail
+/// use logos::{Lexer, Logos};
+///
+/// #[derive(Debug, Clone, PartialEq)]
+/// struct Message(String);
+///
+/// impl<'s> logos::error::Error<'s, Token> for Message {
+///     fn unknown_token(lex: &Lexer<'s, Token>) -> Self {
+///         Message(format!("unexpected {:?}", lex.slice()))
+///     }
+/// }
+///
+/// // `Message` doesn't implement `NoPanicError`, since it allocates.
+/// #[derive(Logos, Debug, PartialEq)]
+/// #[logos(error = Message, no_panic_error)]
+/// enum Token {
+///     #[regex("[a-z]+")]
+///     Word,
+/// }
+/// ```
+///
 /// ### `#[logos(extras = SomeType)]`
 ///
 /// Sets the [extras type][Logos::Extras] for this [Logos] implementation.
@@ -259,52 +345,81 @@ pub use logos_derive::Logos;
 /// See the documentation on [callbacks](./callback/index.html), the [extras type][Logos::Extras] and the [Lexer] type
 /// for details not covered here.
 ///
+/// ### `#[logos(source = SomeType)]`
+///
+/// Sets the [source type][Logos::Source] for this [Logos] implementation, which must implement [Source].
+///
+/// The source type is `str` by default, or `[u8]` if any of the patterns can match bytes that aren't valid UTF-8.
+/// Lifetimes in the source type are treated the same way as lifetimes in the enum's fields, so a source that borrows
+/// (like `Segmented<'s>` from the `segmented` module, behind the `segmented` feature) can be used too.
+///
+/// Owned byte buffers, `Vec<u8>` and `Box<[u8]>`, can be used as sources directly, with `[u8]` slices. This lexes a
+/// buffer received from elsewhere, like a network payload, without converting it first.
+///
+/// ```
+/// use logos::Logos;
+///
+/// #[derive(Logos, Debug, Clone, PartialEq)]
+/// #[logos(source = Vec<u8>)]
+/// enum Frame<'s> {
+///     #[regex(b"\\x01[^\\x00]*\\x00", |lex| &lex.slice()[1..lex.slice().len() - 1])]
+///     Text(&'s [u8]),
+///
+///     #[token(b"\xFF")]
+///     Ping,
+/// }
+///
+/// let payload: Vec<u8> = vec![0x01, b'h', b'i', 0x00, 0xFF];
+/// let lexer = Frame::lexer(&payload);
+///
+/// // Lexers only borrow their source, so they're cheap to clone.
+/// assert_eq!(lexer.clone().count(), 2);
+/// assert_eq!(lexer.collect::<Vec<_>>(), &[Ok(Frame::Text(b"hi")), Ok(Frame::Ping)]);
+/// ```
+///
 /// ### `#[logos(type T = SomeType)]`
 ///
 /// Specify the concrete type to use for the type parameter `T`.
 ///
-/// If your lexer contains generic type parameters, Logos will emit an error - it doesn't know what type should be used
-/// to fill in those type parameters. As an example, the following snippet fails to compile:
+/// Token types can be generic, as long as their patterns don't depend on the type parameters. The derive implements
+/// [Logos] for every choice of type parameters that satisfies the bounds declared on the enum (including its `where`
+/// clause):
 ///
-/// ```compile_fail
+/// ```
 /// use logos::{Logos, Lexer};
 ///
-/// // A magical type!
-/// #[derive(Default)]
-/// struct SuperMagic;
-///
 /// // A callback that produces a default value for `T`.
 /// fn make_magic<'source, T>(lexer: &mut Lexer<'source, Token<T>>) -> T
 /// where
-///     T: Default + 'source,
-///     Token<T>: Logos<'source>
+///     T: Default,
 /// {
 ///     T::default()
 /// }
 ///
-/// #[derive(Logos)]
+/// #[derive(Logos, Debug, PartialEq)]
 /// enum Token<T: Default> {
 ///     #[token("magic", make_magic)]
-///     Magic(T)
+///     Magic(T),
 /// }
+///
+/// assert_eq!(Token::<u8>::lexer("magic").next(), Some(Ok(Token::Magic(0))));
+/// assert_eq!(Token::<String>::lexer("magic").next(), Some(Ok(Token::Magic(String::new()))));
 /// ```
 ///
-/// However, we can use the `type` option to tell Logos what type `T` (or any other type parameter) should be:
+/// Sometimes that's more general than you need - for example, when a callback only works for one particular type. The
+/// `type` option tells Logos which type `T` (or any other type parameter) should be, and [Logos] is then only
+/// implemented for that type:
 ///
 /// ```
 /// # use logos::{Logos, Lexer};
 /// #
-/// # #[derive(Default)]
-/// # struct SuperMagic;
-/// #
-/// # fn make_magic<'source, T>(lexer: &mut Lexer<'source, Token<T>>) -> T
-/// # where
-/// #     T: Default + 'source,
-/// #     Token<T>: Logos<'source>
-/// # {
-/// #     T::default()
-/// # }
-/// #
+/// #[derive(Default)]
+/// struct SuperMagic;
+///
+/// fn make_magic<'source>(lexer: &mut Lexer<'source, Token<SuperMagic>>) -> SuperMagic {
+///     SuperMagic
+/// }
+///
 /// #[derive(Logos)]
 /// #[logos(type T = SuperMagic)] // This is important!
 /// enum Token<T: Default> {
@@ -313,8 +428,8 @@ pub use logos_derive::Logos;
 /// }
 /// ```
 ///
-/// At present, the derive macro does not perform *generic implementations* of the [Logos] trait, so you must always
-/// specify replacements for type parameters. This is likely to change in the future.
+/// Inline callbacks are generic over the same type parameters as the implementation, so they can use them (like
+/// `|lex| T::from(lex.slice())`) - but they can't use type parameters that have been given a concrete type.
 ///
 /// ### `#[logos(subpattern NAME = "...")]`
 ///
@@ -324,7 +439,671 @@ pub use logos_derive::Logos;
 /// additionally help readability by making it possible to "name" a regular expression's component parts.
 ///
 /// Inside of regular expressions, subpatterns are used by wrapping `?&` and a *subpattern name* within parenthesis. For
-/// example, the expression `(?&IDENTIFIER)` refers to a subpattern named `IDENTIFIER`.
+/// example, the expression `(?&IDENTIFIER)` refers to a subpattern named `IDENTIFIER`. Subpatterns can also use the
+/// subpatterns that are defined before them.
+///
+/// A subpattern can be made to ignore case by following it with `ignore(...)`, which accepts the same flags as for
+/// `#[regex(...)]` (see below). Unlike an `ignore(...)` on a whole definition, this only applies to the subpattern, so
+/// the rest of any expression that uses it stays case-sensitive.
+///
+/// ```
+/// use logos::Logos;
+///
+/// #[derive(Logos, Debug, PartialEq)]
+/// #[logos(subpattern hex = "0x[0-9a-f]+", ignore(ascii_case))]
+/// enum Token {
+///     #[regex("(?&hex)_[a-z]+")]
+///     Tagged,
+/// }
+///
+/// assert_eq!(Token::lexer("0XfF_tag").next(), Some(Ok(Token::Tagged)));
+/// assert_eq!(Token::lexer("0xff_TAG").next(), Some(Err(logos::UnknownToken)));
+/// ```
+///
+/// For `ascii_case`, Unicode is disabled within the subpattern, so it can't contain anything that would then match
+/// invalid UTF-8 - such as `.`, or a negated class like `[^"]`.
+///
+/// Subpatterns that are needed by several enums (like the modes of a lexer) can be declared once with the
+/// [subpatterns!] macro.
+///
+/// ### `#[logos(recover_to = "...")]`
+///
+/// Add a *synchronization point* for error recovery. This option can be used more than once, and accepts both string
+/// and byte string literals.
+///
+/// By default, the lexer produces an error for every byte of input that it doesn't recognise, which can quickly flood
+/// you with errors. When synchronization points are defined, the lexer instead skips ahead after an error until the
+/// remaining input starts with one of them, and reports everything it skipped as a single error. The synchronization
+/// point itself isn't skipped, so it's lexed as usual afterwards.
+///
+/// ```
+/// use logos::{Logos, UnknownToken};
+///
+/// #[derive(Logos, Debug, PartialEq)]
+/// #[logos(recover_to = ";", recover_to = "\n")]
+/// enum Token {
+///     #[regex("[a-z]+")]
+///     Word,
+///
+///     #[token(";")]
+///     Semicolon,
+/// }
+///
+/// let mut lex = Token::lexer("foo%@# bar;baz");
+///
+/// assert_eq!(lex.next(), Some(Ok(Token::Word)));
+/// assert_eq!(lex.next(), Some(Err(UnknownToken)));
+/// assert_eq!(lex.slice(), "%@# bar");
+/// assert_eq!(lex.next(), Some(Ok(Token::Semicolon)));
+/// assert_eq!(lex.next(), Some(Ok(Token::Word)));
+/// ```
+///
+/// ### `#[logos(sentinel = b"...")]`
+///
+/// Declare bytes that can never be part of a token, like the NUL byte. This option can be used more than once, and
+/// every byte in the byte string becomes a sentinel.
+///
+/// Sentinel bytes are removed from every `#[regex(...)]`, so a token always ends right before one, and a
+/// `#[token(...)]` containing one is rejected. When the lexer reaches a sentinel byte, it produces an error for just
+/// that byte using [Error::sentinel], which can be implemented to tell it apart from other
+/// errors. Error recovery with `recover_to` also stops at sentinel bytes.
+///
+/// ```
+/// use logos::{Lexer, Logos};
+///
+/// #[derive(Debug, PartialEq)]
+/// enum Error {
+///     Unknown,
+///     Sentinel(u8),
+/// }
+///
+/// impl<'s> logos::Error<'s, Token> for Error {
+///     fn unknown_token(_: &Lexer<'s, Token>) -> Self {
+///         Error::Unknown
+///     }
+///
+///     fn sentinel(lex: &Lexer<'s, Token>) -> Self {
+///         Error::Sentinel(lex.slice()[0])
+///     }
+/// }
+///
+/// #[derive(Logos, Debug, PartialEq)]
+/// #[logos(error = Error, sentinel = b"\0\xFF")]
+/// enum Token {
+///     #[regex(b"[^\n]+")]
+///     Line,
+///
+///     #[token(b"\n")]
+///     Newline,
+/// }
+///
+/// let tokens: Vec<_> = Token::lexer(b"abc\0def\n\xFF").collect();
+///
+/// assert_eq!(
+///     tokens,
+///     &[
+///         Ok(Token::Line),
+///         Err(Error::Sentinel(0)),
+///         Ok(Token::Line),
+///         Ok(Token::Newline),
+///         Err(Error::Sentinel(0xFF)),
+///     ]
+/// );
+/// ```
+///
+/// ### `#[logos(invalid_utf8 = "...")]`
+///
+/// Lexers over `[u8]` can run into invalid UTF-8, which `.`, unicode classes and text never match. By default the
+/// lexer produces an error for each byte of it that no definition matches. With `invalid_utf8`, each invalid sequence
+/// is instead handled as a whole, where a sequence is split from the rest of the input the same way
+/// [String::from_utf8_lossy] splits it into replacement characters:
+///
+/// - `"error"` produces a single error for the sequence.
+/// - `"skip"` skips the sequence, just like [skip] skips a token.
+/// - `"replace"` lexes the sequence as whichever definition would match a lone `"\u{FFFD}"`, so callbacks see the
+///   invalid bytes as the slice. It's a compile error if no definition matches that.
+///
+/// Only sequences at the start of a token are affected: an invalid sequence ends any token in front of it, and
+/// definitions that match the invalid bytes themselves (like `#[token(b"\xFF")]`) still take precedence. This has no
+/// effect on `str` sources, which are always valid UTF-8.
+///
+/// ```rust
+/// use logos::Logos;
+///
+/// #[derive(Logos, Debug, PartialEq)]
+/// #[logos(source = [u8], invalid_utf8 = "replace")]
+/// enum Token {
+///     #[regex(r"\w+")]
+///     Word,
+///
+///     #[regex(r"[^\w]")]
+///     Other,
+/// }
+///
+/// let mut lex = Token::lexer(b"ok\xE2\x82!");
+///
+/// assert_eq!(lex.next(), Some(Ok(Token::Word)));
+/// assert_eq!(lex.next(), Some(Ok(Token::Other)));
+/// assert_eq!(lex.slice(), b"\xE2\x82");
+/// assert_eq!(lex.next(), Some(Ok(Token::Other)));
+/// assert_eq!(lex.next(), None);
+/// ```
+///
+/// ### `#[logos(unicode = ...)]`
+///
+/// Sets [`unicode = ...`](#unicode--) for every definition of the enum, except the ones that set it themselves.
+///
+/// ### `#[logos(export_dot = "path/to/file.dot")]`
+///
+/// Write the compiled state machine to a file in the [Graphviz DOT](https://graphviz.org/doc/info/lang.html) format.
+/// The path is relative to the directory containing your crate's `Cargo.toml`, and any missing parent directories are
+/// created.
+///
+/// In the rendered graph, forks are drawn as circles, sequences of bytes as boxes, and the tokens produced as double
+/// circles. Dashed edges are taken when no other edge matches. This is mostly useful for debugging unexpected matches
+/// or disambiguation errors, and is best removed once you're done.
+///
+/// ### `#[logos(determinism_report = "path/to/file.txt")]`
+///
+/// Check that the compiled state machine is deterministic, complete over the byte alphabet, and can't loop on empty
+/// input, and write a report of the checks to a file (relative to the directory containing your crate's
+/// `Cargo.toml`) that can be archived alongside a release. Compilation fails if any of the checks fail.
+///
+/// The report lists every state with its transitions, so that the checks can be repeated by other tools. Forks list a
+/// partition of all bytes from `00` to `FF`, and sequences of bytes list one for each position, mapping each range of
+/// bytes to the state that consumes it (`->`), the state that takes over without consuming it (`~>`), or an error:
+///
+/// ```text
+/// logos determinism report v1
+/// lexer: Token
+/// root: 4
+/// deterministic: yes
+/// complete: yes
+/// loop-free on empty input: yes
+/// state 1 leaf ::Ident
+/// state 2 leaf ::Fn
+/// state 3 rope
+///   position 0
+///   00-6D ~> 5
+///   6E-6E -> 2
+///   6F-FF ~> 5
+/// state 4 fork
+///   00-60 error
+///   ...
+/// ```
+///
+/// ### `#[logos(stats)]` and `#[logos(stats = "path/to/file.txt")]`
+///
+/// Report how big the generated lexer is while compiling it: the number of states in the state machine (split into
+/// forks, sequences of bytes and leaves producing tokens), the transitions between them, the size of the tables in the
+/// generated code, the number of generated functions, and the number of tokens in the generated code. The last two
+/// grow with the compile time and the size of the binary, so they're the ones to watch when a lexer gets unexpectedly
+/// slow to build.
+///
+/// ```text
+/// Stats for the lexer of `Token`:
+/// variants:    3
+/// states:      8 (4 forks, 1 ropes, 3 leaves)
+/// transitions: 14
+/// table bytes: 256
+/// functions:   8
+/// code tokens: 2779
+/// ```
+///
+/// The report is printed to the standard error (which Cargo only shows when the crate is rebuilt), or written to a
+/// file relative to the directory containing your crate's `Cargo.toml` if a path is given.
+///
+/// ### `#[logos(test_match)]`
+///
+/// Generate a `test_match_{Variant}` function for each variant, which returns the length of the longest match of that
+/// variant's definitions at the start of the input - as if no other definitions existed. This makes it easy to test a
+/// single regex without crafting input that avoids every other definition. Callbacks aren't run.
+///
+/// These functions are only meant for tests, so you'll usually want to enable them with
+/// `#[cfg_attr(test, logos(test_match))]`.
+///
+/// ```
+/// use logos::Logos;
+///
+/// #[derive(Logos, Debug, PartialEq)]
+/// #[logos(test_match)]
+/// enum Token {
+///     #[token("fast")]
+///     Fast,
+///
+///     #[regex("[a-z]+")]
+///     Ident,
+/// }
+///
+/// // `Fast` would normally win here, but it's ignored by `test_match_Ident`.
+/// assert_eq!(Token::test_match_Ident("fast!"), Some(4));
+/// assert_eq!(Token::test_match_Fast("faster"), Some(4));
+/// assert_eq!(Token::test_match_Fast("slow"), None);
+/// ```
+///
+/// ### `#[logos(sniff)]`
+///
+/// Generate `sniff` and `sniff_skipping` functions, which match a single token at the very start of the input. They're
+/// meant for dispatching on what an input starts with - like checking whether it starts with a keyword or a number -
+/// without setting up a [Lexer]. Matching starts at offset 0, with a state machine of its own that needs no extras and
+/// runs no hooks.
+///
+/// `sniff` returns the token along with its length, or `None` if the input doesn't start with a token, including when
+/// it starts with one that's skipped with [skip]. `sniff_skipping` looks past skipped tokens first, and returns the
+/// span of the token instead. Contextual definitions are never enabled, and since other callbacks need a lexer to run,
+/// tokens made by them can't be sniffed either - both functions return `None` for them.
+///
+/// ```
+/// use logos::Logos;
+///
+/// #[derive(Logos, Debug, PartialEq)]
+/// #[logos(sniff)]
+/// enum Token<'a> {
+///     #[regex(r"[ \t\n]+", logos::skip)]
+///     Whitespace,
+///
+///     #[token("fn")]
+///     Fn,
+///
+///     #[regex("[0-9]+")]
+///     Number(&'a str),
+/// }
+///
+/// assert_eq!(Token::sniff("fn main()"), Some((Token::Fn, 2)));
+/// assert_eq!(Token::sniff("123 + 4"), Some((Token::Number("123"), 3)));
+/// assert_eq!(Token::sniff(" fn"), None);
+/// assert_eq!(Token::sniff("?"), None);
+///
+/// assert_eq!(Token::sniff_skipping("  fn main()"), Some((Token::Fn, 2..4)));
+/// assert_eq!(Token::sniff_skipping("   "), None);
+/// ```
+///
+/// ### `#[logos(display)]`
+///
+/// Give every variant a display name, for use in messages like "expected `)`, found `+`". The name of a variant is the
+/// literal of its `#[token(...)]` if that's its only definition, and the name of the variant otherwise. It can also be
+/// set explicitly with `#[logos(name = "...")]` on the variant.
+///
+/// The names are available as a `NAME_{Variant}` constant for each variant, and through a `name` method. The derive
+/// also implements [Display][core::fmt::Display], which writes the name of the token.
+///
+/// ```
+/// use logos::Logos;
+///
+/// #[derive(Logos, Debug, PartialEq)]
+/// #[logos(display)]
+/// enum Token {
+///     #[token(")")]
+///     Close,
+///
+///     #[regex("[a-z]+")]
+///     Ident,
+///
+///     #[regex("[0-9]+")]
+///     #[logos(name = "number")]
+///     Number,
+/// }
+///
+/// assert_eq!(Token::NAME_Close, ")");
+/// assert_eq!(Token::Ident.name(), "Ident");
+/// assert_eq!(format!("expected `{}`, found {}", Token::Close, Token::Number), "expected `)`, found number");
+/// ```
+///
+/// ### `#[logos(on_token = ..., on_error = ..., on_eof = ...)]`
+///
+/// Run a callback whenever the lexer produces a token, produces an error, or reaches the end of its input. The callbacks
+/// take a `&mut Lexer`, just like the callbacks of variants, and run after the token (or error) has been lexed - so
+/// [Lexer::span] and [Lexer::slice] refer to it. This is handy for logging, collecting metrics or keeping track of
+/// lines, without having to wrap the callback of every variant. Skipped tokens don't count as tokens, and `on_eof` only
+/// runs the first time the lexer reaches the end of its input, not on every `None` after that - just like the `on_eof`
+/// of [Lifecycle], and of a [RingLexer](ring::RingLexer). Moving the lexer back with [Lexer::seek] or [Lexer::reset],
+/// or restoring a recorded state, lets it run again.
+///
+/// ```
+/// use logos::{Lexer, Logos};
+///
+/// #[derive(Default)]
+/// struct Stats {
+///     tokens: usize,
+///     errors: Vec<std::ops::Range<usize>>,
+///     done: bool,
+/// }
+///
+/// fn count(lex: &mut Lexer<Token>) {
+///     lex.extras.tokens += 1;
+/// }
+///
+/// #[derive(Logos, Debug, PartialEq)]
+/// #[logos(extras = Stats)]
+/// #[logos(on_token = count, on_error = |lex| lex.extras.errors.push(lex.span()))]
+/// #[logos(on_eof = |lex| lex.extras.done = true)]
+/// enum Token {
+///     #[regex(r"[ \t\n]+", logos::skip)]
+///     Whitespace,
+///
+///     #[regex("[a-z]+")]
+///     Word,
+/// }
+///
+/// let mut lexer = Token::lexer("a bc ? d");
+///
+/// lexer.by_ref().for_each(drop);
+///
+/// assert_eq!(lexer.extras.tokens, 3);
+/// assert_eq!(lexer.extras.errors, &[5..6]);
+/// assert!(lexer.extras.done);
+/// ```
+///
+/// ### `#[logos(lifecycle)]`
+///
+/// Call the [Lifecycle] hooks of the extras, which have to implement the trait for the source type. `on_start` runs when
+/// a lexer is created, and whenever it's [reset](Lexer::reset) to another source, while `on_eof` only runs the first
+/// time the lexer reaches the end of its input (before any `on_eof = ...` callback). This lets extras set up data about
+/// each source, and sum up what they found, without wrapping every use of the lexer in setup and teardown code.
+///
+/// ```
+/// use logos::{Lifecycle, Logos};
+///
+/// #[derive(Default)]
+/// struct Lines {
+///     starts: Vec<usize>,
+///     words: usize,
+///     summary: Option<String>,
+/// }
+///
+/// impl<'s> Lifecycle<'s, str> for Lines {
+///     fn on_start(&mut self, source: &'s str) {
+///         self.starts = std::iter::once(0).chain(source.match_indices('\n').map(|(at, _)| at + 1)).collect();
+///     }
+///
+///     fn on_eof(&mut self) {
+///         self.summary = Some(format!("{} words on {} lines", self.words, self.starts.len()));
+///     }
+/// }
+///
+/// #[derive(Logos, Debug, PartialEq)]
+/// #[logos(extras = Lines, lifecycle)]
+/// enum Token {
+///     #[regex(r"[ \t\n]+", logos::skip)]
+///     Whitespace,
+///
+///     #[regex("[a-z]+", |lex| lex.extras.words += 1)]
+///     Word,
+/// }
+///
+/// let mut lexer = Token::lexer("one two\nthree");
+///
+/// assert_eq!(lexer.extras.starts, &[0, 8]);
+///
+/// lexer.by_ref().for_each(drop);
+///
+/// assert_eq!(lexer.extras.summary.as_deref(), Some("3 words on 2 lines"));
+/// ```
+///
+/// ### `#[logos(keywords)]`
+///
+/// Generate a `KEYWORDS` constant listing the literal of every `#[token(...)]` that looks like an identifier, in the
+/// order that they're defined. This lets features like autocompletion and spell checking use the keywords that the
+/// lexer actually knows about, instead of a list that has to be kept in sync by hand. What counts as an identifier is
+/// `[a-zA-Z_][a-zA-Z0-9_]*` by default, and a different pattern can be given with `#[logos(keywords = "...")]`. A
+/// literal is only listed if the pattern matches all of it.
+///
+/// ```
+/// use logos::Logos;
+///
+/// #[derive(Logos, Debug, PartialEq)]
+/// #[logos(keywords)]
+/// enum Token {
+///     #[token("fn")]
+///     Fn,
+///
+///     #[token("let")]
+///     #[token("const")]
+///     Binding,
+///
+///     #[token("+")]
+///     Plus,
+///
+///     #[regex("[a-z]+")]
+///     Ident,
+/// }
+///
+/// assert_eq!(Token::KEYWORDS, &["fn", "let", "const"]);
+/// ```
+///
+/// ### `#[logos(owned = OwnedName)]`
+///
+/// Generate a copy of the token type called `OwnedName`, which doesn't borrow from the source, along with a `to_owned`
+/// method that converts tokens to it. Fields of type `&str` become [String]s, fields of type `&[u8]` become
+/// [Vec]`<u8>`s, `SliceView`s (or `Cow`s) become the owned version of their slice, and fields that don't borrow from
+/// the source are cloned. This is useful for tokens that have to outlive their source, like ones that are cached or
+/// sent to another task, without maintaining a mirror of the token type by hand. Derives for the owned type can be
+/// listed with `#[logos(owned_derive(...))]`.
+///
+/// ```
+/// use logos::Logos;
+///
+/// #[derive(Logos, Debug, PartialEq)]
+/// #[logos(owned = OwnedToken, owned_derive(Debug, Clone, PartialEq))]
+/// enum Token<'a> {
+///     #[regex("[a-z]+")]
+///     Ident(&'a str),
+///
+///     #[regex("[0-9]+", |lex| lex.slice().parse().ok())]
+///     Number(u64),
+///
+///     #[token("+")]
+///     Plus,
+/// }
+///
+/// let tokens: Vec<OwnedToken> = {
+///     let source = String::from("x+1");
+///
+///     Token::lexer(&source).map(|token| token.unwrap().to_owned()).collect()
+/// };
+///
+/// assert_eq!(tokens, &[OwnedToken::Ident("x".to_owned()), OwnedToken::Plus, OwnedToken::Number(1)]);
+/// ```
+///
+/// ### `#[logos(kind = KindName)]`
+///
+/// Generate a fieldless enum called `KindName` with the same variants as the token type, along with a `kind` method
+/// that strips the field from a token. Parsers often only care about which token they're looking at, and this saves
+/// maintaining a mirror of the token type by hand. The kind enum always derives `Clone`, `Copy`, `Debug`, `PartialEq`,
+/// `Eq` and `Hash`, further derives can be listed with `#[logos(kind_derive(...))]`. It also comes with `const` tables
+/// for converting between kinds, their indices and their names: `KindName::COUNT`, `KindName::ALL`,
+/// `KindName::NAMES`, `index`, `from_index` and `name`.
+///
+/// ```
+/// use logos::Logos;
+///
+/// #[derive(Logos, Debug, PartialEq)]
+/// #[logos(kind = TokenKind, kind_derive(PartialOrd, Ord))]
+/// enum Token<'a> {
+///     #[regex("[a-z]+")]
+///     Ident(&'a str),
+///
+///     #[regex("[0-9]+", |lex| lex.slice().parse().ok())]
+///     Number(u64),
+///
+///     #[token("+")]
+///     Plus,
+/// }
+///
+/// let kinds: Vec<TokenKind> = Token::lexer("x+1").map(|token| token.unwrap().kind()).collect();
+///
+/// assert_eq!(kinds, &[TokenKind::Ident, TokenKind::Plus, TokenKind::Number]);
+/// assert_eq!(TokenKind::ALL, [TokenKind::Ident, TokenKind::Number, TokenKind::Plus]);
+/// assert_eq!(TokenKind::Number.index(), 1);
+/// assert_eq!(TokenKind::from_index(2), Some(TokenKind::Plus));
+/// assert_eq!(TokenKind::Plus.name(), "Plus");
+/// ```
+///
+/// ### `#[logos(doc_grammar)]`
+///
+/// Add a "Grammar" section to the documentation of the [Logos] implementation, so that the rendered docs of your token
+/// type show the grammar it actually lexes. The section is a table listing every `#[token(...)]` and `#[regex(...)]`
+/// definition with its variant and its computed priority (see `priority` below), highest priority first, along with
+/// any callbacks and contextual groups.
+///
+/// ```
+/// use logos::Logos;
+///
+/// /// The tokens of a small expression language.
+/// #[derive(Logos, Debug, PartialEq)]
+/// #[logos(doc_grammar)]
+/// enum Token {
+///     #[token("let")]
+///     Let,
+///
+///     #[regex("[a-z]+")]
+///     Ident,
+/// }
+/// ```
+///
+/// The docs for `impl Logos for Token` will then include:
+///
+/// | Variant | Definition | Priority | Notes |
+/// |---|---|---|---|
+/// | `Let` | `#[token("let")]` | 6 | |
+/// | `Ident` | `#[regex("[a-z]+")]` | 2 | |
+///
+/// ### `#[logos(verify_corpus = "path/to/corpus/*.src")]`
+///
+/// Generate a `#[test]` that lexes every file matching the pattern, and fails if any of them produce an error. Each
+/// error is reported with its file, line and column, so regressions in a grammar show up in `cargo test` without a
+/// hand-written harness. The test only exists when compiling with `cfg(test)`, and requires the extras type to
+/// implement [Default].
+///
+/// The pattern is relative to the directory containing your crate's `Cargo.toml`, and each component of it can use
+/// `*` and `?` wildcards. The matching files are embedded into the test when it's compiled, so changes to them are
+/// picked up automatically - but adding a new file requires the crate to be rebuilt. Only `str` and `[u8]` sources are
+/// supported.
+///
+/// The test harness can only collect tests declared at module level, so the enum can't be declared inside a function.
+/// Since the test would silently never run, that's a compile error instead:
+///
+/// ```compile_fail
+/// use logos::Logos;
+///
+/// fn main() {
+///     #[derive(Logos)]
+///     #[logos(verify_corpus = "src/*.rs")]
+///     enum Token {
+///         #[regex("[a-z]+")]
+///         Word,
+///     }
+/// }
+/// ```
+///
+/// ### `#[logos(conformance_tests)]` and `#[logos(conformance_tests = "path/to/file.txt")]`
+///
+/// Generate a `#[test]` that lexes inputs at the edges of the compiled state machine, and checks the first token of
+/// each. The inputs are the shortest input producing each token, and for every transition, inputs that take it on its
+/// first and last byte or on the bytes just outside of those. The token each input should produce is worked out by
+/// matching every definition on its own (the same way [`disambiguate_with`](#disambiguate_with--) checks its
+/// witnesses), so the test catches places where the generated code doesn't do what the definitions say, like in a
+/// regression in Logos itself. The test only exists when compiling with `cfg(test)`, and requires the extras type to
+/// implement [Default].
+///
+/// If a path is given, the inputs are also written to it (relative to the directory containing your crate's
+/// `Cargo.toml`), one per line with the token they're lexed as, so that a change to a grammar that changes how inputs
+/// at its edges are lexed shows up in a diff:
+///
+/// ```text
+/// Conformance cases for the lexer of `Token`:
+/// b"fn" => Fn 2
+/// b"fo" => Ident 2
+/// b"\x08" => error
+/// ```
+///
+/// Inputs that could be matched by a contextual definition, [`requires(...)`](#requires), `not_preceded_by`,
+/// `preceded_by`, or an anchor or word boundary are left out, since what they're lexed as depends on more than the
+/// input itself. At most 2000 inputs are checked, and only `str` and `[u8]` sources are supported.
+///
+/// ### `#[logos(codegen = "compact")]`
+///
+/// By default every state of the state machine becomes its own function, which is as fast as it gets but can add up to
+/// a lot of code (and compile time) for large grammars. With `codegen = "compact"`, the state machine is instead stored
+/// as tables of transitions, and a small interpreter shared by every lexer walks them. Only the callbacks and token
+/// constructors are still generated as code.
+///
+/// The tokens produced are exactly the same in either mode, but lexing is typically a few times slower, so this is best
+/// kept to lexers that are large or aren't on a hot path. `codegen = "fast"` selects the default explicitly.
+///
+/// ```rust
+/// use logos::Logos;
+///
+/// #[derive(Logos, Debug, PartialEq)]
+/// #[logos(codegen = "compact")]
+/// enum Token {
+///     #[regex(r"[ \t\n\f]+", logos::skip)]
+///     Whitespace,
+///
+///     #[token("let")]
+///     Let,
+///
+///     #[regex("[a-z]+")]
+///     Ident,
+///
+///     #[regex("[0-9]+", |lex| lex.slice().parse().ok())]
+///     Number(u64),
+/// }
+///
+/// let tokens: Vec<_> = Token::lexer("let x 42").collect();
+///
+/// assert_eq!(tokens, &[Ok(Token::Let), Ok(Token::Ident), Ok(Token::Number(42))]);
+/// ```
+///
+/// ### `#[logos(jump_tables = false, lookup_tables = false, unroll = N, ...)]`
+///
+/// Without `codegen = "compact"`, Logos picks how to match each part of the state machine by itself, going for speed
+/// over size. Where flash or cache space is tight (say, on an embedded target), these knobs trade some of that speed
+/// back, and others keep compile times down:
+///
+/// - `jump_tables = false` matches states with many branches with a `match`, instead of a 256 entry jump table.
+/// - `lookup_tables = false` tests bytes against wide character classes with a `match`, instead of a 256 byte lookup
+///   table shared between classes. Narrow classes still use a single 64 bit mask, and negated classes like `[^"\\\n]`
+///   a 32 byte bitmap of the bytes that stop them.
+/// - `unroll = N` checks `N` bytes per bounds check (from 1 to 16, 16 by default) in loops over a character class.
+/// - `literal_sets = N` matches a `#[regex]` that's an alternation of at least `N` literals (128 by default), like a
+///   list of a few thousand country or currency codes, with a binary search over the literals instead of compiling
+///   them into the state machine, which keeps compile times and code size down. This only happens when no other
+///   definition can start with the same byte as one of the literals, and `literal_sets = 0` turns it off.
+/// - `keyword_tables = N` looks keywords up in a perfect hash table once an identifier has been matched, instead of
+///   compiling them into the state machine, when a `#[regex]` fully matches at least `N` `#[token]` literals (64 by
+///   default) that win over it. This keeps the state machine of a language with hundreds of keywords small. A keyword
+///   is only looked up if no other definition matches it and it has no checks like `requires(...)`, and
+///   `keyword_tables = 0` turns it off.
+/// - `max_inline = N` splits the state machine into functions of at most `N` states each. Every state is generated as
+///   its own `#[inline]` function, so by default all of them end up inlined into one enormous function, which can take
+///   rustc and LLVM a long time to compile for grammars with thousands of states. With `max_inline`, the states are
+///   grouped into regions of at most `N` states, and the functions for states that are entered from another region are
+///   `#[inline(never)]`, which costs a function call whenever lexing crosses into another region. `max_inline = 0` (the
+///   default) inlines everything.
+///
+/// The tokens produced are the same either way.
+///
+/// ```rust
+/// use logos::Logos;
+///
+/// #[derive(Logos, Debug, PartialEq)]
+/// #[logos(jump_tables = false, lookup_tables = false, unroll = 4)]
+/// enum Token {
+///     #[regex(r"[ \t\n\f]+", logos::skip)]
+///     Whitespace,
+///
+///     #[regex("[a-zA-Z_][a-zA-Z0-9_]*")]
+///     Ident,
+///
+///     #[token("+")]
+///     Plus,
+/// }
+///
+/// let tokens: Vec<_> = Token::lexer("a + b_2").collect();
+///
+/// assert_eq!(tokens, &[Ok(Token::Ident), Ok(Token::Plus), Ok(Token::Ident)]);
+/// ```
 ///
 /// ## Variant attributes
 ///
@@ -356,55 +1135,262 @@ pub use logos_derive::Logos;
 /// }
 /// ```
 ///
-/// For tokens that may be represented by *any* input that fits a certain criteria (such as string literals or
-/// identifiers) you should use a `#[regex(...)]` definition instead.
+/// For tokens that may be represented by *any* input that fits a certain criteria (such as string literals or
+/// identifiers) you should use a `#[regex(...)]` definition instead.
+///
+/// #### `#[regex("PATTERN", ...)]`
+///
+/// Produce this variant when the provided regular expression matches.
+///
+/// A `#[regex(...)]` definition should be used for tokens that are represented by *any* input that fits a certain
+/// criteria - such as integer literals, identifiers, and similar. Integer literals could be nearly *any* sequence of
+/// the numbers 0 through 9, and writing out every possible number using `#[token(...)]` definitions would be a
+/// *colossal* waste of time.
+///
+/// We can use a `#[regex(...)]` definition for this instead, like so:
+///
+/// ```
+/// use logos::Logos;
+///
+/// #[derive(Logos, Debug, PartialEq)]
+/// enum Token {
+///     // Callbacks are explained further below, but for now all you need to know is that we use the `logos::skip`
+///     // callback here so that we can ignore whitespace.
+///     #[regex(r"\s", logos::skip)]
+///     Whitespace,
+///
+///     #[regex(r"[1-9]+[0-9]*")]
+///     Integer,
+///     // ... and so on. See below for more details on what you can and can't do.
+/// }
+///
+/// let mut lexer = Token::lexer("89 144 233");
+///
+/// lexer.next();
+/// assert_eq!(lexer.slice(), "89");
+///
+/// lexer.next();
+/// assert_eq!(lexer.slice(), "144");
+///
+/// lexer.next();
+/// assert_eq!(lexer.slice(), "233");
+///
+/// // We've reached the end of our input, so calling `next` again returns `None`
+/// assert_eq!(lexer.next(), None);
+/// ```
+///
+/// The regular expression used within a `#[regex(...)]` definition is subject to some limitations. Most notably:
+/// - Look-around is not supported
+/// - Backreferences are not supported
+/// - Word boundaries (`\b`, `\B`) and anchors (`^`, `$`) may only be used at the start or end of the regex
+/// - Counted repetitions (`{n}`, `{n,}` and `{n,m}`) are expanded into copies of the repeated part of the regex, and
+///   can't expand to more than 1000 parts of a pattern
+///
+/// Word boundaries and anchors are checked once a token has been matched, against the source around it. If they don't
+/// hold, the lexer falls back to a lower priority definition that matched the same input, or produces an error if
+/// there isn't one. Word characters are those that are alphanumeric or `_`, or just the ASCII ones in `(?-u:\b)`.
+///
+/// ```
+/// use logos::{Logos, UnknownToken};
+///
+/// #[derive(Logos, Debug, PartialEq)]
+/// enum Token {
+///     #[regex(r"[ \t\n]+", logos::skip)]
+///     Whitespace,
+///
+///     // Numbers that run straight into a word are errors, rather than two separate tokens.
+///     #[regex(r"[0-9]+\b")]
+///     Number,
+///
+///     #[regex("[a-z_]+")]
+///     Ident,
+///
+///     #[regex(r"(?m)^#[a-z]+")]
+///     Directive,
+/// }
+///
+/// let mut lexer = Token::lexer("#define x 12\n42nd #undef");
+/// let tokens: Vec<_> = std::iter::from_fn(|| Some((lexer.next()?, lexer.slice()))).collect();
+///
+/// assert_eq!(
+///     tokens,
+///     &[
+///         (Ok(Token::Directive), "#define"),
+///         (Ok(Token::Ident), "x"),
+///         (Ok(Token::Number), "12"),
+///         (Err(UnknownToken), "42"),
+///         (Ok(Token::Ident), "nd"),
+///         (Err(UnknownToken), "#undef"),
+///     ]
+/// );
+/// ```
+///
+/// A definition can also require some *trailing context* with `followed_by = "..."`, which is a regex that has to
+/// match right after the token, but isn't made part of it. Like assertions, this is checked once the token has been
+/// matched, and the lexer falls back to another definition (or an error) if it doesn't match. Trailing context uses
+/// the same matcher as capture groups, so it needs the `alloc` feature.
+///
+/// ```
+/// use logos::Logos;
+///
+/// #[derive(Logos, Debug, PartialEq)]
+/// enum Token {
+///     #[regex(r"[ \t\n]+", logos::skip)]
+///     Whitespace,
+///
+///     // A name is a function call if it's followed by an opening parenthesis.
+///     #[regex("[a-z]+", followed_by = r"[ \t]*\(")]
+///     Call,
+///
+///     #[regex("[a-z]+")]
+///     Name,
+///
+///     #[token("(")]
+///     Open,
+///
+///     #[token(")")]
+///     Close,
+/// }
+///
+/// let tokens: Vec<_> = Token::lexer("print (x)").collect();
+///
+/// assert_eq!(
+///     tokens,
+///     &[
+///         Ok(Token::Call),
+///         Ok(Token::Open),
+///         Ok(Token::Name),
+///         Ok(Token::Close),
+///     ]
+/// );
+/// ```
+///
+/// `not_followed_by = "..."` is the opposite, and only lets a definition match where the regex *doesn't* match right
+/// after the token. The end of the source counts as not being followed by anything. It's written as its own attribute
+/// since regexes in Logos don't support lookaround like `(?!...)`.
+///
+/// When a token is ruled out this way, the lexer backs off to the longest shorter token that any other definition
+/// matches at the same position, or reports an error if there isn't one. This is how `1..2` can be a range, even though
+/// a float like `1.` matches its start:
+///
+/// ```
+/// use logos::Logos;
+///
+/// #[derive(Logos, Debug, PartialEq)]
+/// enum Token {
+///     #[regex(r"[0-9]+\.[0-9]*", not_followed_by = r"\.")]
+///     Float,
+///
+///     #[regex("[0-9]+")]
+///     Int,
+///
+///     #[token("..")]
+///     Range,
+/// }
+///
+/// let tokens: Vec<_> = Token::lexer("1..2").collect();
+///
+/// assert_eq!(tokens, &[Ok(Token::Int), Ok(Token::Range), Ok(Token::Int)]);
+/// ```
+///
+/// A token that's ruled out by what follows it can also be handled by a definition of the same text, like a keyword
+/// that is only sometimes a name:
+///
+/// ```
+/// use logos::Logos;
+///
+/// #[derive(Logos, Debug, PartialEq)]
+/// enum Token {
+///     #[regex(r"[ \t\n]+", logos::skip)]
+///     Whitespace,
+///
+///     // `type` is only a keyword where it isn't used as a field name.
+///     #[token("type", not_followed_by = r"[ \t]*:")]
+///     Type,
+///
+///     #[regex("[a-z]+")]
+///     Name,
+///
+///     #[token(":")]
+///     Colon,
+/// }
+///
+/// let tokens: Vec<_> = Token::lexer("type: type").collect();
+///
+/// assert_eq!(tokens, &[Ok(Token::Name), Ok(Token::Colon), Ok(Token::Type)]);
+/// ```
+///
+/// Similarly, `not_preceded_by = "[...]"` makes a definition only match where the byte *before* the token isn't in
+/// the given class, which has to be a single byte or a class of bytes (or ASCII characters). The start of the source
+/// counts as not being preceded by anything. This is useful for things like numbers that mustn't be glued to the end of
+/// an identifier, which longest match alone can't rule out once the identifier has been lexed.
+///
+/// ```
+/// use logos::{Logos, UnknownToken};
+///
+/// #[derive(Logos, Debug, PartialEq)]
+/// enum Token {
+///     #[regex(r"[ \t\n]+", logos::skip)]
+///     Whitespace,
+///
+///     #[regex("[a-z]+")]
+///     Name,
 ///
-/// #### `#[regex("PATTERN", ...)]`
+///     #[regex("[0-9]+", not_preceded_by = "[a-zA-Z_]")]
+///     Number,
+/// }
 ///
-/// Produce this variant when the provided regular expression matches.
+/// let tokens: Vec<_> = Token::lexer("1 x2").collect();
 ///
-/// A `#[regex(...)]` definition should be used for tokens that are represented by *any* input that fits a certain
-/// criteria - such as integer literals, identifiers, and similar. Integer literals could be nearly *any* sequence of
-/// the numbers 0 through 9, and writing out every possible number using `#[token(...)]` definitions would be a
-/// *colossal* waste of time.
+/// assert_eq!(
+///     tokens,
+///     &[Ok(Token::Number), Ok(Token::Name), Err(UnknownToken)]
+/// );
+/// ```
 ///
-/// We can use a `#[regex(...)]` definition for this instead, like so:
+/// `preceded_by = "..."` goes the other way, making a definition only match where the text before the token ends with
+/// a match of the given regex. The regex can look back any distance, but can't match empty text or contain anchors or
+/// word boundaries, and nothing precedes the start of the source. It's checked once the token has been matched, so
+/// pairing it with `strategy = "callback"`, described below, lets a shorter token win when the check fails. This
+/// is how a JavaScript lexer can tell a regex literal from a division:
 ///
 /// ```
 /// use logos::Logos;
 ///
 /// #[derive(Logos, Debug, PartialEq)]
 /// enum Token {
-///     // Callbacks are explained further below, but for now all you need to know is that we use the `logos::skip`
-///     // callback here so that we can ignore whitespace.
-///     #[regex(r"\s", logos::skip)]
+///     #[regex(r"[ \t\n]+", logos::skip)]
 ///     Whitespace,
 ///
-///     #[regex(r"[1-9]+[0-9]*")]
-///     Integer,
-///     // ... and so on. See below for more details on what you can and can't do.
-/// }
+///     #[regex("[a-z]+")]
+///     Name,
 ///
-/// let mut lexer = Token::lexer("89 144 233");
+///     #[regex(r"/[^/\n]+/", preceded_by = r"[(=][ \t]*", strategy = "callback")]
+///     Regex,
 ///
-/// lexer.next();
-/// assert_eq!(lexer.slice(), "89");
+///     #[token("/")]
+///     Slash,
 ///
-/// lexer.next();
-/// assert_eq!(lexer.slice(), "144");
+///     #[token("=")]
+///     Assign,
+/// }
 ///
-/// lexer.next();
-/// assert_eq!(lexer.slice(), "233");
+/// let tokens: Vec<_> = Token::lexer("a = /b/ / c").collect();
 ///
-/// // We've reached the end of our input, so calling `next` again returns `None`
-/// assert_eq!(lexer.next(), None);
+/// assert_eq!(
+///     tokens,
+///     &[
+///         Ok(Token::Name),
+///         Ok(Token::Assign),
+///         Ok(Token::Regex),
+///         Ok(Token::Slash),
+///         Ok(Token::Name),
+///     ]
+/// );
 /// ```
 ///
-/// The regular expression used within a `#[regex(...)]` definition is subject to some limitations. Most notably:
-/// - Look-around is not supported
-/// - Backreferences are not supported
-/// - Line anchors may not be used
-/// - Capture groups cannot be used to extract portions of the matched input.
+/// Capture groups don't affect what a regex matches, but callbacks can use [Lexer::captures] to extract the portions
+/// of the matched input that they cover. See the [captures module](./captures/index.html) for an example.
 ///
 /// If you'd like to perform more complicated lexing, you can use *lexer callbacks*, which are described below and in
 /// the [documentation on callbacks](./callback/index.html).
@@ -489,12 +1475,13 @@ pub use logos_derive::Logos;
 /// Both the `#[regex("(ridiculously)?fast[er|est]?")]` and `#[token("fast")]` definitions could match the input "fast",
 /// and both have the same priority.
 ///
-/// When you encounter a conflict like this, Logos will also use the compile error to suggest a priority value that
-/// isn't ambiguous. In this case, `fast` has a priority of 8, so Logos suggested that we use a priority of 9 to
-/// disambiguate:
+/// When you encounter a conflict like this, Logos will use the compile error to point out an input that both definitions
+/// match, and to suggest a priority value that isn't ambiguous. In this case, `fast` has a priority of 8, so Logos
+/// suggested that we use a priority of 9 to disambiguate:
 ///
 /// ```no_rust
-/// error: A definition of variant `RidiculouslyFast` can match the same input as another definition of variant `Fast`.
+/// error: A definition of variant `RidiculouslyFast` can match the same input as another definition of variant `Fast`. For
+///        example, both definitions match "fast".
 ///
 ///        hint: Consider giving one definition a higher priority: #[regex(..., priority = 9)]
 ///   -> src\example.rs:8:13
@@ -502,7 +1489,8 @@ pub use logos_derive::Logos;
 /// 5 |     #[regex("fast[er|est]?")]
 ///   |             ^^^^^^^^^^^^^^^
 ///
-/// error: A definition of variant `Fast` can match the same input as another definition of variant `RidiculouslyFast`.
+/// error: A definition of variant `Fast` can match the same input as another definition of variant `RidiculouslyFast`. For
+///        example, both definitions match "fast".
 ///
 ///        hint: Consider giving one definition a higher priority: #[regex(..., priority = 9)]
 ///  --> src\example.rs:8:13
@@ -546,6 +1534,47 @@ pub use logos_derive::Logos;
 /// assert_eq!(lexer.next(), None);
 /// ```
 ///
+/// ##### `disambiguate_with = "..."`
+///
+/// Checks, at compile time, that an input is lexed as a single token by this definition. This argument's value should be
+/// a string (or byte string) literal, and it can be given more than once.
+///
+/// Priorities are easy to get subtly wrong, especially once a few definitions overlap. A witness pins down the
+/// intended outcome next to the definition itself, and Logos will issue a compiler error if the witness would be lexed
+/// as another definition, or if this definition would only match part of it. The witness of a definition with
+/// `followed_by` should include the trailing context. Contextual definitions are treated as enabled, and word
+/// boundaries and anchors are assumed to hold.
+///
+/// ```
+/// use logos::Logos;
+///
+/// #[derive(Logos, Debug, PartialEq)]
+/// enum Token {
+///     #[token("fast", priority = 9, disambiguate_with = "fast")]
+///     Fast,
+///
+///     #[regex("(ridiculously)?fast(er|est)?", disambiguate_with = "faster")]
+///     RidiculouslyFast,
+/// }
+///
+/// assert_eq!(Token::lexer("faster").next(), Some(Ok(Token::RidiculouslyFast)));
+/// ```
+///
+/// Here `"fast"` would be lexed as `Fast` instead, so this fails to compile:
+///
+/// ```compile_fail
+/// use logos::Logos;
+///
+/// #[derive(Logos)]
+/// enum Token {
+///     #[token("fast", priority = 9)]
+///     Fast,
+///
+///     #[regex("(ridiculously)?fast(er|est)?", disambiguate_with = "fast")]
+///     RidiculouslyFast,
+/// }
+/// ```
+///
 /// ##### `callback = ...`
 ///
 /// Sets the callback attached to this definition. This argument's value should be a closure or a path to a function
@@ -575,7 +1604,8 @@ pub use logos_derive::Logos;
 ///
 /// Valid flags are
 /// * `case` - Comparisons between characters will be entirely **case-insensitive**. This flag may not be used with
-///   `ascii_case`.
+///   `ascii_case`. Case is folded fully, so characters that fold to several characters match them too: `straße`
+///   matches `STRASSE`, and `[ß]` matches `ss`.
 /// * `ascii_case` - Comparisons between **ASCII** characters will be **case-insensitive**. This flag may not be used
 ///   with `case`.
 ///
@@ -608,6 +1638,218 @@ pub use logos_derive::Logos;
 /// assert_eq!(lexer.next(), None);
 /// ```
 ///
+/// ##### `contextual`
+///
+/// Makes the definition *contextual*: it only produces its own variant while it has been enabled with
+/// [Lexer::enable_contextual], and otherwise lexes as whichever definition it shadows - usually an identifier. This is
+/// handy for keywords that are only reserved in some places, where the parser knows better than the lexer.
+///
+/// A contextual definition must have a higher priority than the definition it shadows, which is the case for literal
+/// tokens and most identifier regexes. Logos will issue a compiler error if there is nothing for it to fall back to.
+/// Since `contextual` is a lone flag, a callback function that happens to be called `contextual` must be passed using
+/// `callback = contextual`.
+///
+/// ```
+/// use logos::Logos;
+///
+/// #[derive(Logos, Debug, PartialEq)]
+/// enum Token {
+///     #[regex(r"\s", logos::skip)]
+///     Whitespace,
+///
+///     #[token("await", contextual)]
+///     Await,
+///
+///     #[regex("[a-z]+")]
+///     Identifier,
+/// }
+///
+/// let mut lexer = Token::lexer("await await");
+///
+/// assert_eq!(lexer.next(), Some(Ok(Token::Identifier)));
+///
+/// lexer.enable_contextual(Token::Await);
+///
+/// assert_eq!(lexer.next(), Some(Ok(Token::Await)));
+/// assert_eq!(lexer.next(), None);
+/// ```
+///
+/// ##### `requires(...)`
+///
+/// Only use the definition while the given [flags][LexerOptions] are set on the lexer, or unset if they're negated
+/// with `!`. The flags are checked once the token has been matched, which costs a single comparison. This gives a
+/// parser a channel to tell the lexer about its context with [Lexer::set_flag], for grammars where what a token is
+/// depends on where it appears, like contextual keywords or C's casts.
+///
+/// Like `contextual`, a definition that requires flags falls back to whatever it shadows while they're not set, or
+/// produces an error if it doesn't shadow anything.
+///
+/// ```
+/// use logos::{LexerOptions, Logos};
+///
+/// struct Flag;
+///
+/// impl Flag {
+///     const ITEM_POSITION: LexerOptions = LexerOptions::bit(0);
+/// }
+///
+/// #[derive(Logos, Debug, PartialEq)]
+/// enum Token {
+///     #[token(" ", logos::skip)]
+///     Whitespace,
+///
+///     // `union` is only a keyword where an item can start.
+///     #[token("union", requires(Flag::ITEM_POSITION))]
+///     Union,
+///
+///     #[regex("[a-z]+")]
+///     Ident,
+/// }
+///
+/// let mut lexer = Token::lexer("union union");
+///
+/// lexer.set_flag(Flag::ITEM_POSITION, true);
+/// assert_eq!(lexer.next(), Some(Ok(Token::Union)));
+///
+/// lexer.set_flag(Flag::ITEM_POSITION, false);
+/// assert_eq!(lexer.next(), Some(Ok(Token::Ident)));
+/// ```
+///
+/// ##### `strategy = "callback"`
+///
+/// Some regexes turn into huge state machines - patterns like `[ab]*a[ab][ab][ab][ab][ab][ab]` that need to remember
+/// what they've seen have a state for every combination of it. Rather than slowing down compilation and bloating the
+/// lexer for a single definition, a `#[regex(...)]` can opt out of the state machine with `strategy = "callback"`.
+/// Only the first byte of its tokens is lexed as usual, and the rest is matched by simulating the regex at runtime,
+/// taking the longest match. Everything else is lexed exactly as it would be otherwise.
+///
+/// Since the state machine only sees the first byte, the definition is picked whenever that byte is the longest
+/// match - it's best suited to tokens that start with something no other definition does. If the simulated regex
+/// doesn't match, the lexer falls back to a definition that matches just the first byte, or produces an error. This
+/// needs the `alloc` feature, and can't be combined with `followed_by`, `not_followed_by` or assertions at the end of
+/// the regex.
+///
+/// ```
+/// use logos::Logos;
+///
+/// #[derive(Logos, Debug, PartialEq)]
+/// enum Token {
+///     #[regex(r"\s+", logos::skip)]
+///     Whitespace,
+///
+///     #[regex("#[ab]*a[ab][ab][ab][ab][ab][ab]", strategy = "callback")]
+///     Tail,
+///
+///     #[token("#")]
+///     Hash,
+/// }
+///
+/// let mut lexer = Token::lexer("#bbabbbbbb #bbb");
+///
+/// assert_eq!(lexer.next(), Some(Ok(Token::Tail)));
+/// assert_eq!(lexer.span(), 0..10);
+/// assert_eq!(lexer.next(), Some(Ok(Token::Hash)));
+/// ```
+///
+/// ##### `unterminated = ...`
+///
+/// Block comments and strings that are still open when the input ends would otherwise come out as a string of unknown
+/// tokens. With `unterminated`, a `#[regex(...)]` produces a single token from its opening to the end of the input
+/// instead, which is either the given unit variant, or an error if given a value of the error type. The opening is the
+/// literal that the regex starts with, like `/*`, or just its first character if it doesn't start with one.
+///
+/// A whole token always wins over an unterminated one. Note that if the regex can fail part way through a token
+/// before the end of the input, like a string that can't contain newlines, that part of the input is an error.
+///
+/// ```
+/// use logos::Logos;
+///
+/// #[derive(Logos, Debug, PartialEq)]
+/// enum Token {
+///     #[regex(r"\s+", logos::skip)]
+///     Whitespace,
+///
+///     #[regex(r"/\*([^*]|\*[^/])*\*/", unterminated = UnterminatedComment)]
+///     Comment,
+///
+///     UnterminatedComment,
+/// }
+///
+/// let mut lexer = Token::lexer("/* one */ /* two");
+///
+/// assert_eq!(lexer.next(), Some(Ok(Token::Comment)));
+/// assert_eq!(lexer.next(), Some(Ok(Token::UnterminatedComment)));
+/// assert_eq!(lexer.span(), 10..16);
+/// ```
+///
+/// ##### `unicode = ...`
+///
+/// By default, classes in string patterns match characters, with one shortcut: a class that reaches to the end of
+/// Unicode, like `[^a]` or `.`, matches everything but ASCII a byte at a time. That's quicker, and makes no difference
+/// in loops like `[^"]*`, but a single `[^a]` can then stop in the middle of a character. Classes in byte string
+/// patterns match bytes.
+///
+/// - `unicode = true` makes classes and `.` only ever match whole characters, in byte string patterns too. It's a
+///   compile error if the pattern isn't valid UTF-8.
+/// - `unicode = false` makes classes and `.` match bytes, as if the pattern started with `(?-u)`. In a string
+///   pattern, that must not match part of a character, so it's a compile error unless every class only matches ASCII:
+///   `[\x00-`b-\x7F]` is fine where `[^a]` isn't. Use a byte string pattern for classes that match any byte.
+///
+/// ```
+/// use logos::Logos;
+///
+/// #[derive(Logos, Debug, PartialEq)]
+/// enum Token {
+///     #[token(" ", logos::skip)]
+///     Whitespace,
+///
+///     // Only ASCII letters, digits and underscores.
+///     #[regex(r"\w+", unicode = false, priority = 2)]
+///     Ident,
+///
+///     #[regex("[^ ]", unicode = true)]
+///     Other,
+/// }
+///
+/// let mut lexer = Token::lexer("naïve");
+///
+/// assert_eq!(lexer.next(), Some(Ok(Token::Ident)));
+/// assert_eq!(lexer.next(), Some(Ok(Token::Other)));
+/// assert_eq!(lexer.slice(), "ï");
+/// assert_eq!(lexer.next(), Some(Ok(Token::Ident)));
+/// ```
+///
+/// ### `#[logos(eof)]`
+///
+/// Marks a unit variant that is produced once at the end of input, instead of the lexer returning `None` straight
+/// away. Its span is the empty span at the end of the source, after anything that was skipped. Parsers often find it
+/// easier to handle a real token that carries a position than to deal with running out of tokens.
+///
+/// An eof variant can't have any `#[token(...)]` or `#[regex(...)]` definitions, and only one variant can be marked.
+///
+/// ```
+/// use logos::Logos;
+///
+/// #[derive(Logos, Debug, PartialEq)]
+/// enum Token {
+///     #[regex(r"\s+", logos::skip)]
+///     Whitespace,
+///
+///     #[regex("[a-z]+")]
+///     Word,
+///
+///     #[logos(eof)]
+///     Eof,
+/// }
+///
+/// let mut lexer = Token::lexer("hello ");
+///
+/// assert_eq!(lexer.next(), Some(Ok(Token::Word)));
+/// assert_eq!(lexer.next(), Some(Ok(Token::Eof)));
+/// assert_eq!(lexer.span(), 6..6);
+/// assert_eq!(lexer.next(), None);
+/// ```
+///
 pub trait Logos<'source>: Sized {
     /// The "extras" type, used to add state to a lexer.
     ///
@@ -634,12 +1876,103 @@ pub trait Logos<'source>: Sized {
     /// error type by passing a type implementing the [Error] trait to the derive macro's `error` option.
     type Error: Error<'source, Self>;
 
+    /// The number of variants of the token type.
+    ///
+    /// ```
+    /// use logos::Logos;
+    ///
+    /// #[derive(Logos, Debug, PartialEq)]
+    /// enum Token<'a> {
+    ///     #[regex("[a-z]+")]
+    ///     Ident(&'a str),
+    ///
+    ///     #[token("+")]
+    ///     Plus,
+    ///
+    ///     #[token("*")]
+    ///     Star,
+    /// }
+    ///
+    /// // The binding power of every token, indexed by `Logos::index`.
+    /// const PRECEDENCE: [u8; <Token as Logos>::VARIANT_COUNT] = [0, 1, 2];
+    ///
+    /// let tokens: Vec<u8> = Token::lexer("a+b*c")
+    ///     .map(|token| PRECEDENCE[token.unwrap().index()])
+    ///     .collect();
+    ///
+    /// assert_eq!(tokens, &[0, 1, 0, 2, 0]);
+    /// assert_eq!(Token::ALL[2].name, "Star");
+    /// assert!(Token::ALL[0].has_field);
+    /// ```
+    const VARIANT_COUNT: usize = 0;
+
+    /// Information about every variant of the token type, in the order that variants are declared, so that
+    /// `Self::ALL[token.index()]` describes `token`.
+    const ALL: &'static [VariantInfo] = &[];
+
+    /// Whether the error type has to implement [NoPanicError][crate::error::NoPanicError], with
+    /// `#[logos(no_panic_error)]`. If it does, panics while constructing errors abort in debug builds.
+    #[doc(hidden)]
+    const NO_PANIC_ERROR: bool = false;
+
     /// The heart of Logos.
     ///
     /// This method is called during the lexing process, and is implemented by the `logos-derive` crate. As a reminder,
     /// you should **never implement this trait yourself**. Use the derive macro!
     fn lex(lexer: &mut Lexer<'source, Self>);
 
+    /// Run the accept action of the rule numbered `rule`, as if it had just matched the current token.
+    ///
+    /// This is used to implement [Output::Reparse][crate::callback::Output::Reparse], and is implemented by the
+    /// `logos-derive` crate.
+    #[doc(hidden)]
+    fn reparse(lexer: &mut Lexer<'source, Self>, rule: usize) {
+        let _ = lexer;
+
+        panic!("{} is not a valid rule", rule);
+    }
+
+    /// The index of this token's variant, in the order that variants are declared.
+    ///
+    /// Indices are dense, from `0` up to [Logos::VARIANT_COUNT], so they can be used to index tables of per-token data
+    /// directly, like precedence tables or first and follow sets. [Logos::ALL] is indexed the same way.
+    fn index(&self) -> usize {
+        panic!("index is implemented by the derive macro")
+    }
+
+    /// Called after the lexer produces a token, with `#[logos(on_token = ...)]`.
+    #[doc(hidden)]
+    #[inline(always)]
+    fn on_token(_lex: &mut Lexer<'source, Self>) {}
+
+    /// Called after the lexer produces an error, with `#[logos(on_error = ...)]`.
+    #[doc(hidden)]
+    #[inline(always)]
+    fn on_error(_lex: &mut Lexer<'source, Self>) {}
+
+    /// Called the first time the lexer reaches the end of its input, with `#[logos(on_eof = ...)]`.
+    #[doc(hidden)]
+    #[inline(always)]
+    fn on_eof(_lex: &mut Lexer<'source, Self>) {}
+
+    /// Called when a lexer starts on a source, with `#[logos(lifecycle)]`.
+    #[doc(hidden)]
+    #[inline(always)]
+    fn on_start(_lex: &mut Lexer<'source, Self>) {}
+
+    /// Called the first time the lexer reaches the end of its input, with `#[logos(lifecycle)]`.
+    #[doc(hidden)]
+    #[inline(always)]
+    fn on_finish(_lex: &mut Lexer<'source, Self>) {}
+
+    /// The bit used to enable this token's contextual definitions, if it has any.
+    ///
+    /// This is used to implement [Lexer::enable_contextual], and is implemented by the `logos-derive` crate.
+    #[doc(hidden)]
+    fn contextual_bit(&self) -> Option<u32> {
+        None
+    }
+
     /// Create a new [Lexer] for this token type.
     fn lexer(source: &'source Self::Source) -> Lexer<'source, Self>
     where
@@ -660,6 +1993,74 @@ pub trait Logos<'source>: Sized {
     ) -> Lexer<'source, Self> {
         Lexer::with_extras(source, extras)
     }
+
+    /// Lex all of `source`, adding the tokens to `collection`, and return the lexer's extras once it's done.
+    ///
+    /// `collection` can be any type that implements [Extend] - a [Vec], a `SmallVec`, an arena-backed vector, or a
+    /// collection of your own - so tokens don't need to pass through an intermediate [Vec] first.
+    ///
+    /// ```
+    /// use logos::Logos;
+    ///
+    /// #[derive(Logos, Debug, PartialEq)]
+    /// #[logos(extras = usize)]
+    /// enum Token {
+    ///     #[regex(r"[ \t\n]+", logos::skip)]
+    ///     Whitespace,
+    ///
+    ///     #[regex("[a-z]+", |lex| lex.extras += 1)]
+    ///     Word,
+    /// }
+    ///
+    /// let mut tokens = Vec::with_capacity(8);
+    /// let words = Token::lex_into("foo bar", &mut tokens);
+    ///
+    /// assert_eq!(tokens, &[Ok(Token::Word), Ok(Token::Word)]);
+    /// assert_eq!(words, 2);
+    /// ```
+    fn lex_into<C>(source: &'source Self::Source, collection: &mut C) -> Self::Extras
+    where
+        Self::Extras: Default,
+        C: Extend<Result<Self, Self::Error>>,
+    {
+        let mut lexer = Self::lexer(source);
+
+        collection.extend(&mut lexer);
+        lexer.extras
+    }
+
+    /// Like [Logos::lex_into], but pairs each token with its source position, the same way as [Lexer::spanned].
+    fn lex_spanned_into<C>(source: &'source Self::Source, collection: &mut C) -> Self::Extras
+    where
+        Self::Extras: Default,
+        C: Extend<Result<(Self, Span), Self::Error>>,
+    {
+        let mut lexer = Self::lexer(source);
+
+        collection.extend(core::iter::from_fn(|| {
+            let token = lexer.next()?;
+
+            Some(token.map(|token| (token, lexer.span())))
+        }));
+        lexer.extras
+    }
+}
+
+/// Information about a variant of a token type, from [Logos::ALL].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct VariantInfo {
+    /// The name of the variant.
+    pub name: &'static str,
+
+    /// The name of the variant for display purposes, which is set with `#[logos(name = "...")]` on the variant, or
+    /// is the literal of its only `#[token(...)]`, or otherwise its name.
+    pub display_name: &'static str,
+
+    /// The position of the variant in [Logos::ALL], which is also what [Logos::index] returns for it.
+    pub index: usize,
+
+    /// Whether the variant has a field.
+    pub has_field: bool,
 }
 
 /// Used within callbacks to instruct the lexer to skip a token match.
@@ -741,6 +2142,74 @@ pub enum Filter<C> {
     Skip,
 }
 
+/// A type that can be used within callbacks to either produce a field for a token, skip a token match, or produce an
+/// error. This is like [Filter], but with an extra arm for errors.
+///
+/// # Example
+///
+/// ```rust
+/// use logos::{Lexer, Logos, FilterResult};
+///
+/// #[derive(Debug, Clone, PartialEq)]
+/// struct Malformed;
+///
+/// impl<'s> logos::Error<'s, Token> for Malformed {
+///     fn unknown_token(_: &Lexer<'s, Token>) -> Self {
+///         Malformed
+///     }
+/// }
+///
+/// #[derive(Logos, Debug, PartialEq)]
+/// #[logos(error = Malformed)]
+/// enum Token {
+///     #[regex(r"[ \n\f\t]+", logos::skip)]
+///     Whitespace,
+///
+///     #[regex("[0-9][0-9a-z]*", |lex| match lex.slice().parse::<u64>() {
+///         Ok(n) if n % 2 == 0 => FilterResult::Skip,
+///         Ok(n) => FilterResult::Accept(n),
+///         Err(_) => FilterResult::Error(Malformed),
+///     })]
+///     OddNumber(u64),
+/// }
+///
+/// let tokens: Vec<_> = Token::lexer("20 11 4x 23").collect();
+///
+/// assert_eq!(
+///     tokens,
+///     &[
+///         // 20 is an even number, so it's skipped
+///         Ok(Token::OddNumber(11)),
+///         Err(Malformed),
+///         Ok(Token::OddNumber(23)),
+///     ]
+/// );
+/// ```
+pub enum FilterResult<C, E> {
+    /// Construct and emit a variant containing a value of type `C`.
+    Accept(C),
+    /// Skip this token match.
+    Skip,
+    /// Emit an error of type `E`.
+    Error(E),
+}
+
+/// Hooks that a lexer runs on its extras when it starts on a source, and when it reaches the end of it.
+///
+/// They're only run for token types with `#[logos(lifecycle)]`, see the [Logos] trait for an example. Both methods do
+/// nothing by default.
+pub trait Lifecycle<'source, S: ?Sized> {
+    /// Called when a lexer is created for `source`, or [reset](Lexer::reset) to it.
+    #[inline]
+    fn on_start(&mut self, source: &'source S) {
+        let _ = source;
+    }
+
+    /// Called the first time the lexer reaches the end of its source.
+    #[inline]
+    fn on_eof(&mut self) {}
+}
+
 /// A predefined callback that unconditionally skips a token match.
 ///
 /// When lexing, you often run into situations where you simply *do not care* about certain parts of your input. Notable
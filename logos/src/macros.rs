@@ -0,0 +1,118 @@
+/// Declare a set of subpatterns once, to share them between token enums.
+///
+/// This defines a macro called `NAME`, which adds the subpatterns to the enum that it's wrapped around, just like
+/// writing out their `#[logos(subpattern NAME = "...")]` attributes there - before the enum's own, so that its
+/// subpatterns can use the shared ones. A subpattern can be followed by
+/// `ignore(...)` to make it ignore case, as with the attribute.
+///
+/// ```
+/// use logos::Logos;
+///
+/// logos::subpatterns! {
+///     common {
+///         digit = "[0-9]",
+///         ident = "[a-zA-Z_][a-zA-Z0-9_]*",
+///         hex = "0x[0-9a-f]+" ignore(ascii_case),
+///     }
+/// }
+///
+/// common! {
+///     #[derive(Logos, Debug, PartialEq)]
+///     enum Outer {
+///         #[regex(r"[ \t\n]+", logos::skip)]
+///         Whitespace,
+///
+///         #[regex("(?&ident)")]
+///         Ident,
+///
+///         #[regex("(?&digit)+")]
+///         Number,
+///
+///         #[token("{")]
+///         Open,
+///     }
+/// }
+///
+/// common! {
+///     #[derive(Logos, Debug, PartialEq)]
+///     #[logos(subpattern number = "[+-]?(?&digit)+")]
+///     enum Inner {
+///         #[regex("(?&number)")]
+///         Number,
+///
+///         #[regex("(?&hex)")]
+///         Hex,
+///
+///         #[token("}")]
+///         Close,
+///     }
+/// }
+///
+/// assert_eq!(Outer::lexer("foo 42").collect::<Vec<_>>(), &[Ok(Outer::Ident), Ok(Outer::Number)]);
+/// assert_eq!(Inner::lexer("-42").next(), Some(Ok(Inner::Number)));
+/// assert_eq!(Inner::lexer("0XFF").next(), Some(Ok(Inner::Hex)));
+/// ```
+///
+/// The macro that's defined is scoped like any other `macro_rules!` macro: it can be used after it's defined in the
+/// same module and its child modules, or anywhere once it's exported with `#[macro_export]`, which can be put before
+/// its name.
+#[macro_export]
+macro_rules! subpatterns {
+    (
+        $(#[$meta:meta])*
+        $name:ident {
+            $($subpattern:ident = $pattern:literal $(ignore $flags:tt)?),* $(,)?
+        }
+    ) => {
+        $crate::__subpatterns! {
+            ($)
+            $(#[$meta])*
+            $name {
+                $($subpattern = $pattern $(ignore $flags)?),*
+            }
+        }
+    };
+}
+
+/// The implementation of [subpatterns!], which is given a `$` token so that it can define a macro of its own.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __subpatterns {
+    (
+        ($d:tt)
+        $(#[$meta:meta])*
+        $name:ident {
+            $($subpattern:ident = $pattern:literal $(ignore $flags:tt)?),*
+        }
+    ) => {
+        $(#[$meta])*
+        macro_rules! $name {
+            ($d(#$d attr:tt)* $d vis:vis enum $d($d rest:tt)*) => {
+                $crate::__sort_attributes! {
+                    [] []
+                    [$(#[logos(subpattern $subpattern = $pattern $(, ignore $flags)?)])*]
+                    $d(#$d attr)* ; $d vis enum $d($d rest)*
+                }
+            };
+        }
+    };
+}
+
+/// Put the shared subpatterns before the enum's own `#[logos(...)]` attributes, so that its subpatterns can use them,
+/// but after every other attribute, so that they still come after `#[derive(Logos)]`.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __sort_attributes {
+    ([$($other:tt)*] [$($logos:tt)*] [$($shared:tt)*] ; $($item:tt)*) => {
+        $($other)*
+        $($shared)*
+        $($logos)*
+        $($item)*
+    };
+    ([$($other:tt)*] [$($logos:tt)*] $shared:tt #[logos $($args:tt)*] $($rest:tt)*) => {
+        $crate::__sort_attributes! { [$($other)*] [$($logos)* #[logos $($args)*]] $shared $($rest)* }
+    };
+    ([$($other:tt)*] [$($logos:tt)*] $shared:tt #$attr:tt $($rest:tt)*) => {
+        $crate::__sort_attributes! { [$($other)* #$attr] [$($logos)*] $shared $($rest)* }
+    };
+}
@@ -0,0 +1,220 @@
+use core::fmt::{self, Debug};
+use core::ops::{
+    BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not, Sub, SubAssign,
+};
+
+/// A set of runtime options for a [Lexer][crate::Lexer], stored as bit flags.
+///
+/// Logos doesn't assign any meaning to these flags itself. Instead, you can define your own flags as constants, set
+/// them when creating a lexer with [Lexer::with_options][crate::Lexer::with_options], and check them from within
+/// callbacks using [Lexer::options][crate::Lexer::options]. Since all of the flags live in a single integer, checking
+/// them is about as cheap as it gets.
+///
+/// ```
+/// use logos::{Filter, Lexer, LexerOptions, Logos};
+///
+/// const SIGNIFICANT_NEWLINES: LexerOptions = LexerOptions::bit(0);
+///
+/// fn newline(lex: &mut Lexer<Token>) -> Filter<()> {
+///     match lex.options().contains(SIGNIFICANT_NEWLINES) {
+///         true => Filter::Accept(()),
+///         false => Filter::Skip,
+///     }
+/// }
+///
+/// #[derive(Logos, Debug, PartialEq)]
+/// enum Token {
+///     #[regex(r"[ \t]+", logos::skip)]
+///     Whitespace,
+///
+///     #[token("\n", newline)]
+///     Newline,
+///
+///     #[regex("[a-z]+")]
+///     Word,
+/// }
+///
+/// let tokens: Vec<_> = Token::lexer("a\nb").collect();
+///
+/// assert_eq!(tokens, &[Ok(Token::Word), Ok(Token::Word)]);
+///
+/// let tokens: Vec<_> = Token::lexer("a\nb").with_options(SIGNIFICANT_NEWLINES).collect();
+///
+/// assert_eq!(tokens, &[Ok(Token::Word), Ok(Token::Newline), Ok(Token::Word)]);
+/// ```
+#[derive(Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LexerOptions(u64);
+
+impl LexerOptions {
+    /// No flags set.
+    pub const EMPTY: LexerOptions = LexerOptions(0);
+
+    /// All flags set.
+    pub const ALL: LexerOptions = LexerOptions(u64::MAX);
+
+    /// The single flag numbered `n`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is 64 or more.
+    #[inline]
+    pub const fn bit(n: u32) -> LexerOptions {
+        assert!(n < 64, "lexer options only have 64 bits");
+
+        LexerOptions(1 << n)
+    }
+
+    /// Create a set of flags from its raw bits.
+    #[inline]
+    pub const fn from_bits(bits: u64) -> LexerOptions {
+        LexerOptions(bits)
+    }
+
+    /// The raw bits of this set of flags.
+    #[inline]
+    pub const fn bits(self) -> u64 {
+        self.0
+    }
+
+    /// Returns `true` if no flags are set.
+    #[inline]
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Returns `true` if all of the flags in `other` are set.
+    #[inline]
+    pub const fn contains(self, other: LexerOptions) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Returns `true` if any of the flags in `other` are set.
+    #[inline]
+    pub const fn intersects(self, other: LexerOptions) -> bool {
+        self.0 & other.0 != 0
+    }
+
+    /// The flags that are set in either `self` or `other`.
+    #[inline]
+    pub const fn union(self, other: LexerOptions) -> LexerOptions {
+        LexerOptions(self.0 | other.0)
+    }
+
+    /// The flags that are set in both `self` and `other`.
+    #[inline]
+    pub const fn intersection(self, other: LexerOptions) -> LexerOptions {
+        LexerOptions(self.0 & other.0)
+    }
+
+    /// The flags that are set in `self`, but not in `other`.
+    #[inline]
+    pub const fn difference(self, other: LexerOptions) -> LexerOptions {
+        LexerOptions(self.0 & !other.0)
+    }
+
+    /// Set all of the flags in `other`.
+    #[inline]
+    pub fn insert(&mut self, other: LexerOptions) {
+        self.0 |= other.0;
+    }
+
+    /// Clear all of the flags in `other`.
+    #[inline]
+    pub fn remove(&mut self, other: LexerOptions) {
+        self.0 &= !other.0;
+    }
+
+    /// Flip all of the flags in `other`.
+    #[inline]
+    pub fn toggle(&mut self, other: LexerOptions) {
+        self.0 ^= other.0;
+    }
+
+    /// Set or clear all of the flags in `other`, depending on `value`.
+    #[inline]
+    pub fn set(&mut self, other: LexerOptions, value: bool) {
+        match value {
+            true => self.insert(other),
+            false => self.remove(other),
+        }
+    }
+}
+
+impl Debug for LexerOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "LexerOptions({:#b})", self.0)
+    }
+}
+
+impl BitOr for LexerOptions {
+    type Output = LexerOptions;
+
+    #[inline]
+    fn bitor(self, other: LexerOptions) -> LexerOptions {
+        self.union(other)
+    }
+}
+
+impl BitOrAssign for LexerOptions {
+    #[inline]
+    fn bitor_assign(&mut self, other: LexerOptions) {
+        self.insert(other);
+    }
+}
+
+impl BitAnd for LexerOptions {
+    type Output = LexerOptions;
+
+    #[inline]
+    fn bitand(self, other: LexerOptions) -> LexerOptions {
+        self.intersection(other)
+    }
+}
+
+impl BitAndAssign for LexerOptions {
+    #[inline]
+    fn bitand_assign(&mut self, other: LexerOptions) {
+        *self = self.intersection(other);
+    }
+}
+
+impl BitXor for LexerOptions {
+    type Output = LexerOptions;
+
+    #[inline]
+    fn bitxor(self, other: LexerOptions) -> LexerOptions {
+        LexerOptions(self.0 ^ other.0)
+    }
+}
+
+impl BitXorAssign for LexerOptions {
+    #[inline]
+    fn bitxor_assign(&mut self, other: LexerOptions) {
+        self.toggle(other);
+    }
+}
+
+impl Sub for LexerOptions {
+    type Output = LexerOptions;
+
+    #[inline]
+    fn sub(self, other: LexerOptions) -> LexerOptions {
+        self.difference(other)
+    }
+}
+
+impl SubAssign for LexerOptions {
+    #[inline]
+    fn sub_assign(&mut self, other: LexerOptions) {
+        self.remove(other);
+    }
+}
+
+impl Not for LexerOptions {
+    type Output = LexerOptions;
+
+    #[inline]
+    fn not(self) -> LexerOptions {
+        LexerOptions(!self.0)
+    }
+}
@@ -0,0 +1,328 @@
+//! Lexers that own their source.
+//!
+//! A [Lexer] borrows its source for the `'source` lifetime, which makes it awkward to store a lexer in a struct next to
+//! the text it reads, or to send it to another thread. An [OwnedLexer] instead holds on to an *owner* of the source -
+//! like a [String], a `Box<str>` or an `Arc<str>` - and borrows from it only while a token is being lexed.
+//!
+//! The catch is that tokens can't borrow from the source either, since they may outlive the lexer. This means that
+//! `OwnedLexer` only works with token types that don't have a `'source` lifetime, and whose [Logos] implementation
+//! uses the same extras and error types for every lifetime - which is true of most token types.
+//!
+//! ```
+//! use logos::{Logos, owned::OwnedLexer};
+//! use std::sync::Arc;
+//!
+//! #[derive(Logos, Debug, PartialEq)]
+//! enum Token {
+//!     #[regex(r"[ \t\n]+", logos::skip)]
+//!     Whitespace,
+//!
+//!     #[regex("[a-z]+")]
+//!     Word,
+//!
+//!     #[regex("[0-9]+", |lex| lex.slice().parse().ok())]
+//!     Number(u64),
+//! }
+//!
+//! let source: Arc<str> = Arc::from("abc 42");
+//! let mut lexer = OwnedLexer::<Token, _>::new(source);
+//!
+//! // The lexer (and its tokens) can be sent to another thread, since nothing is borrowed.
+//! let tokens = std::thread::spawn(move || {
+//!     let mut tokens = Vec::new();
+//!
+//!     while let Some(token) = lexer.next() {
+//!         tokens.push((token, lexer.span()));
+//!     }
+//!
+//!     tokens
+//! })
+//! .join()
+//! .unwrap();
+//!
+//! assert_eq!(tokens, &[(Ok(Token::Word), 0..3), (Ok(Token::Number(42)), 4..6)]);
+//! ```
+
+use crate::lexer::LexerParts;
+use crate::source::Source;
+use crate::{Lexer, LexerOptions, Logos, Span};
+
+use core::fmt::{self, Debug};
+use core::ops::Deref;
+
+type SourceOf<T> = <T as OwnedToken>::OwnedSource;
+type ExtrasOf<T> = <T as OwnedToken>::OwnedExtras;
+type ErrorOf<T> = <T as OwnedToken>::OwnedError;
+
+/// Token types that can be used with an [OwnedLexer].
+///
+/// This trait is implemented automatically for all token types that implement [Logos] for every lifetime, with the
+/// same source, extras and error types. You shouldn't need to implement it yourself.
+pub trait OwnedToken:
+    for<'s> Logos<
+    's,
+    Source = <Self as OwnedToken>::OwnedSource,
+    Extras = <Self as OwnedToken>::OwnedExtras,
+    Error = <Self as OwnedToken>::OwnedError,
+>
+{
+    /// The [Logos::Source] of this token type.
+    type OwnedSource: Source + ?Sized;
+
+    /// The [Logos::Extras] of this token type.
+    type OwnedExtras;
+
+    /// The [Logos::Error] of this token type.
+    type OwnedError;
+}
+
+impl<T, S, E, R> OwnedToken for T
+where
+    T: for<'s> Logos<'s, Source = S, Extras = E, Error = R>,
+    S: Source + ?Sized,
+{
+    type OwnedSource = S;
+    type OwnedExtras = E;
+    type OwnedError = R;
+}
+
+/// A lexer that owns its source. See the [module documentation](self) for details.
+///
+/// `Owner` can be any type that dereferences to the source type, such as `String`, `Box<str>`, `Rc<str>` or
+/// `Arc<str>` for a token type that reads from a `str`.
+pub struct OwnedLexer<Token, Owner>
+where
+    Token: OwnedToken,
+    Owner: Deref<Target = SourceOf<Token>>,
+{
+    owner: Owner,
+
+    /// The state of the lexer, which is taken out while a [Lexer] is borrowing the source.
+    parts: Option<LexerParts<ExtrasOf<Token>>>,
+}
+
+impl<Token, Owner> OwnedLexer<Token, Owner>
+where
+    Token: OwnedToken,
+    Owner: Deref<Target = SourceOf<Token>>,
+{
+    /// Create a new `OwnedLexer` that reads from the source owned by `owner`.
+    pub fn new(owner: Owner) -> Self
+    where
+        ExtrasOf<Token>: Default,
+    {
+        Self::with_extras(owner, Default::default())
+    }
+
+    /// Create a new `OwnedLexer` that reads from the source owned by `owner`, with the provided extras.
+    pub fn with_extras(owner: Owner, extras: ExtrasOf<Token>) -> Self {
+        let mut lexer = OwnedLexer {
+            owner,
+            parts: Some(LexerParts::new(extras)),
+        };
+
+        lexer.with_lexer(|lexer| Token::on_start(lexer));
+
+        lexer
+    }
+
+    /// Replace the lexer's [options][LexerOptions], returning the lexer.
+    #[inline]
+    pub fn with_options(mut self, options: LexerOptions) -> Self {
+        self.parts_mut().options = options;
+        self
+    }
+
+    /// The source position of the current token, including the [span offset](Lexer::span_offset).
+    #[inline]
+    pub fn span(&self) -> Span {
+        let parts = self.parts();
+
+        parts.token_start + parts.span_offset..parts.token_end + parts.span_offset
+    }
+
+    /// The source that tokens are being read from.
+    #[inline]
+    pub fn source(&self) -> &SourceOf<Token> {
+        &self.owner
+    }
+
+    /// A slice containing the current token.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the owner dereferences to a different source than it did while the token was lexed.
+    #[inline]
+    pub fn slice(&self) -> &<SourceOf<Token> as Source>::Slice {
+        let parts = self.parts();
+
+        self.owner
+            .slice(parts.token_start..parts.token_end)
+            .expect(SOURCE_CHANGED)
+    }
+
+    /// A slice containing the remaining source, starting at the end of the current token.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the owner dereferences to a different source than it did while the token was lexed.
+    #[inline]
+    pub fn remainder(&self) -> &<SourceOf<Token> as Source>::Slice {
+        let source = &*self.owner;
+
+        source
+            .slice(self.parts().token_end..source.len())
+            .expect(SOURCE_CHANGED)
+    }
+
+    /// Which of its variant's definitions matched the current token. See [Lexer::matched_rule].
+    #[inline]
+    pub fn matched_rule(&self) -> usize {
+        self.parts().matched_rule
+    }
+
+    /// The owner of the source.
+    #[inline]
+    pub fn owner(&self) -> &Owner {
+        &self.owner
+    }
+
+    /// Get a reference to the lexer's extras.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a callback panicked while this lexer was lexing a token.
+    #[inline]
+    pub fn extras(&self) -> &ExtrasOf<Token> {
+        &self.parts().extras
+    }
+
+    /// Get a mutable reference to the lexer's extras.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a callback panicked while this lexer was lexing a token.
+    #[inline]
+    pub fn extras_mut(&mut self) -> &mut ExtrasOf<Token> {
+        &mut self.parts_mut().extras
+    }
+
+    /// Start lexing the source owned by `owner` from the beginning, keeping the lexer's extras and options, and return
+    /// the previous owner. See [Lexer::reset] for details.
+    pub fn reset(&mut self, owner: Owner) -> Owner {
+        let parts = self.parts.take().expect(POISONED);
+
+        self.parts = Some(LexerParts {
+            options: parts.options,
+            ..LexerParts::new(parts.extras)
+        });
+
+        let previous = core::mem::replace(&mut self.owner, owner);
+
+        self.with_lexer(|lexer| Token::on_start(lexer));
+
+        previous
+    }
+
+    /// Split the lexer into the owner of its source and its extras.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a callback panicked while this lexer was lexing a token.
+    pub fn into_parts(self) -> (Owner, ExtrasOf<Token>) {
+        (self.owner, self.parts.expect(POISONED).extras)
+    }
+
+    /// Run `f` with a [Lexer] borrowing the source, which is in the same state as this lexer. Any changes that `f`
+    /// makes to the lexer are kept.
+    ///
+    /// This is useful for calling any of [Lexer]'s methods that `OwnedLexer` doesn't have.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a callback panicked while this lexer was lexing a token, or if the owner dereferences to a different
+    /// source than it did before.
+    pub fn with_lexer<F, R>(&mut self, f: F) -> R
+    where
+        F: for<'s> FnOnce(&mut Lexer<'s, Token>) -> R,
+    {
+        let parts = self.parts.take().expect(POISONED);
+        let source = &*self.owner;
+
+        // `Deref` is a safe trait, so nothing stops the owner from handing out a different source each time. The
+        // lexer reads its source without checking the positions it has stored, so they're checked here instead.
+        assert!(
+            parts.trivia_start <= parts.token_start
+                && source
+                    .slice(parts.trivia_start..parts.token_start)
+                    .is_some()
+                && source.slice(parts.token_start..parts.token_end).is_some(),
+            "{}",
+            SOURCE_CHANGED,
+        );
+
+        let mut lexer = Lexer::from_parts(source, parts);
+        let result = f(&mut lexer);
+
+        self.parts = Some(lexer.into_parts());
+
+        result
+    }
+
+    #[inline]
+    fn parts(&self) -> &LexerParts<ExtrasOf<Token>> {
+        self.parts.as_ref().expect(POISONED)
+    }
+
+    #[inline]
+    fn parts_mut(&mut self) -> &mut LexerParts<ExtrasOf<Token>> {
+        self.parts.as_mut().expect(POISONED)
+    }
+}
+
+pub(crate) const POISONED: &str = "a callback panicked while this lexer was lexing a token";
+
+const SOURCE_CHANGED: &str =
+    "the owner dereferenced to a different source than the one being lexed";
+
+impl<Token, Owner> Iterator for OwnedLexer<Token, Owner>
+where
+    Token: OwnedToken,
+    Owner: Deref<Target = SourceOf<Token>>,
+{
+    type Item = Result<Token, ErrorOf<Token>>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.with_lexer(|lexer| lexer.next())
+    }
+}
+
+impl<Token, Owner> Clone for OwnedLexer<Token, Owner>
+where
+    Token: OwnedToken,
+    Owner: Deref<Target = SourceOf<Token>> + Clone,
+    ExtrasOf<Token>: Clone,
+{
+    fn clone(&self) -> Self {
+        OwnedLexer {
+            owner: self.owner.clone(),
+            parts: self.parts.clone(),
+        }
+    }
+}
+
+impl<Token, Owner> Debug for OwnedLexer<Token, Owner>
+where
+    Token: OwnedToken,
+    Owner: Deref<Target = SourceOf<Token>>,
+    SourceOf<Token>: Debug,
+    ExtrasOf<Token>: Debug,
+{
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_map()
+            .entry(&"source", &self.source())
+            .entry(&"extras", &self.parts.as_ref().map(|parts| &parts.extras))
+            .finish()
+    }
+}
@@ -0,0 +1,204 @@
+//! Lexing large sources on several threads at once.
+//!
+//! [lex] splits a source into chunks, lexes every chunk on its own thread, and stitches the tokens back together. The
+//! result is exactly what lexing the whole source from the start would produce, even if a chunk starts in the middle of
+//! a token, like a string or a block comment: each chunk is lexed speculatively, and where two chunks meet, the tokens
+//! are checked by lexing on from the end of the last token that's known to be right, until it produces a token that
+//! starts where one of the next chunk's tokens does. From there on, both of them are lexing from the same place, so the
+//! rest of the chunk can be used as it is. Chunks start at the beginning of a line where possible, which is where a
+//! token usually starts, so this rarely takes more than a token.
+//!
+//! ```
+//! use logos::{LexerExt, Logos};
+//!
+//! #[derive(Logos, Debug, PartialEq)]
+//! enum Token<'s> {
+//!     #[regex(r"[ \t\n]+", logos::skip)]
+//!     Whitespace,
+//!
+//!     #[regex("[a-z]+")]
+//!     Ident(&'s str),
+//!
+//!     #[regex(r#""[^"]*""#)]
+//!     String(&'s str),
+//! }
+//!
+//! let source = "let \"a\nstring\"\nover lines\n".repeat(1000);
+//! let tokens = logos::parallel::lex_chunked::<Token>(&source, 8);
+//!
+//! assert_eq!(tokens.len(), 4000);
+//! assert_eq!(tokens[1], (Ok(Token::String("\"a\nstring\"")), 4..14));
+//!
+//! // The same tokens as lexing it from start to end.
+//! let serial: Vec<_> = Token::lexer(&source).map_with_lexer(|token, lexer| (token, lexer.span())).collect();
+//!
+//! assert_eq!(tokens, serial);
+//! ```
+//!
+//! Since lexing starts over at arbitrary positions, the tokens can't depend on anything but the source from where they
+//! start. Every chunk is lexed with [Default] extras that are thrown away, and without any
+//! [contextual definitions](crate::Lexer::enable_contextual) enabled.
+
+use std::num::NonZeroUsize;
+use std::thread;
+
+use crate::{Logos, Source, Span};
+
+type Tokens<'source, Token> = Vec<(Result<Token, <Token as Logos<'source>>::Error>, Span)>;
+
+/// Chunks are at least this many bytes long when [lex] picks the number of chunks, so that small sources aren't split
+/// into more chunks than it's worth starting threads for.
+pub const MIN_CHUNK_LEN: usize = 64 * 1024;
+
+/// Lex all of `source` on as many threads as there are CPUs available, with chunks of at least [MIN_CHUNK_LEN] bytes.
+/// See the [module documentation](self) for more details.
+pub fn lex<'source, Token>(
+    source: &'source Token::Source,
+) -> Vec<(Result<Token, Token::Error>, Span)>
+where
+    Token: Logos<'source> + Send,
+    Token::Source: Sync,
+    Token::Error: Send,
+    Token::Extras: Default,
+{
+    let threads = thread::available_parallelism().map_or(1, NonZeroUsize::get);
+    let chunks = threads.min(source.len() / MIN_CHUNK_LEN);
+
+    lex_chunked(source, chunks)
+}
+
+/// Lex all of `source` in `chunks` chunks of about the same length, each on its own thread. See the
+/// [module documentation](self) for more details.
+pub fn lex_chunked<'source, Token>(
+    source: &'source Token::Source,
+    chunks: usize,
+) -> Vec<(Result<Token, Token::Error>, Span)>
+where
+    Token: Logos<'source> + Send,
+    Token::Source: Sync,
+    Token::Error: Send,
+    Token::Extras: Default,
+{
+    let starts = split(source, chunks);
+
+    if starts.len() <= 1 {
+        return lex_from(source, 0, None);
+    }
+
+    let speculative: Vec<_> = thread::scope(|scope| {
+        let threads: Vec<_> = starts
+            .iter()
+            .enumerate()
+            .map(|(index, &start)| {
+                let end = starts.get(index + 1).copied();
+
+                scope.spawn(move || lex_from::<Token>(source, start, end))
+            })
+            .collect();
+
+        threads
+            .into_iter()
+            .map(|thread| {
+                thread
+                    .join()
+                    .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+            })
+            .collect()
+    });
+
+    stitch(source, &starts, speculative)
+}
+
+/// Where each chunk starts, which is the start of the first line after the point that splits the source evenly, or the
+/// first boundary after it if there's no line break before the next chunk. The first chunk always starts at `0`, and
+/// chunks that would be empty are left out.
+fn split<S: Source + ?Sized>(source: &S, chunks: usize) -> Vec<usize> {
+    let len = source.len();
+    let mut starts = vec![0];
+
+    for chunk in 1..chunks.min(len) {
+        let target = len * chunk / chunks;
+        let next = len * (chunk + 1) / chunks;
+        let previous = *starts.last().unwrap();
+        let start = (target..next)
+            .find(|&at| source.read::<u8>(at) == Some(b'\n'))
+            .map(|newline| newline + 1)
+            .or_else(|| (target..next).find(|&at| source.is_boundary(at)))
+            .filter(|&start| start > previous && start < len);
+
+        starts.extend(start);
+    }
+
+    starts
+}
+
+/// Lex the tokens of `source` that start from `start` up to `end`, or up to the end of the source.
+fn lex_from<'source, Token>(
+    source: &'source Token::Source,
+    start: usize,
+    end: Option<usize>,
+) -> Tokens<'source, Token>
+where
+    Token: Logos<'source>,
+    Token::Extras: Default,
+{
+    let mut lexer = Token::lexer(source);
+    let mut tokens = Vec::new();
+
+    lexer.seek(start);
+
+    while let Some(token) = lexer.next() {
+        let span = lexer.span();
+
+        if end.is_some_and(|end| span.start >= end) {
+            break;
+        }
+
+        tokens.push((token, span));
+    }
+
+    tokens
+}
+
+/// Join the tokens of every chunk, checking where each chunk meets the one before it.
+fn stitch<'source, Token>(
+    source: &'source Token::Source,
+    starts: &[usize],
+    mut chunks: Vec<Tokens<'source, Token>>,
+) -> Tokens<'source, Token>
+where
+    Token: Logos<'source>,
+    Token::Extras: Default,
+{
+    // The first chunk starts at the start of the source, so it's always right.
+    let mut tokens = std::mem::take(&mut chunks[0]);
+    let mut lexer = Token::lexer(source);
+    let mut chunk = 0;
+
+    lexer.seek(tokens.last().map_or(0, |(_, span)| span.end));
+
+    // Lex on from the last token that's known to be right, until a token starts where one of the chunk it's in does.
+    while let Some(token) = lexer.next() {
+        let span = lexer.span();
+
+        while chunk + 1 < starts.len() && starts[chunk + 1] <= span.start {
+            chunk += 1;
+        }
+
+        if let Ok(found) = chunks[chunk].binary_search_by_key(&span.start, |(_, span)| span.start) {
+            tokens.extend(chunks[chunk].drain(found..));
+
+            // The last chunk goes all the way to the end of the source.
+            if chunk + 1 == starts.len() {
+                break;
+            }
+
+            lexer.seek(tokens.last().map_or(0, |(_, span)| span.end));
+            continue;
+        }
+
+        tokens.push((token, span));
+    }
+
+    tokens
+}
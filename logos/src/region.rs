@@ -0,0 +1,375 @@
+//! Lexing only a region of a source, for syntax highlighting the part of a document that's on screen.
+//!
+//! An editor showing a few dozen lines of a large file only needs the tokens on those lines. Lexing always has to start
+//! somewhere that a token starts though, since starting in the middle of a string or a comment gets everything after
+//! it wrong. [lex_region] starts at the beginning of the line that the region starts on, which is a safe place to start
+//! for languages where tokens can't span lines, and lexes until just past the end of the region. The tokens that
+//! overlap the region are returned with their spans in the whole source.
+//!
+//! ```
+//! use logos::Logos;
+//! use logos::region::lex_region;
+//!
+//! #[derive(Logos, Debug, PartialEq)]
+//! enum Token {
+//!     #[regex(r"[ \t\n]+", logos::skip)]
+//!     Whitespace,
+//!
+//!     #[token("let")]
+//!     Let,
+//!
+//!     #[regex("[a-z]+")]
+//!     Ident,
+//! }
+//!
+//! let source = "let foo\nlet barbaz\nlet qux";
+//!
+//! // The region starts and ends in the middle of identifiers, which are returned whole.
+//! assert_eq!(
+//!     lex_region::<Token>(source, 14..22),
+//!     &[(Ok(Token::Ident), 12..18), (Ok(Token::Let), 19..22)],
+//! );
+//! ```
+//!
+//! Where tokens can span lines (like block comments), the line that a region starts on might be in the middle of one.
+//! [lex_region_from] starts from a position that's known to be safe instead, like the start of a token from an earlier
+//! full lex of the source, with a lexer that's already in the right state for it.
+//!
+//! [Checkpoints] keep track of such positions. They're recorded every so often while the whole source is lexed (with
+//! [LexerExt::checkpointed]), along with everything else that the lexer needs to carry on from there, so that any
+//! region can then be lexed by starting from the checkpoint before it.
+//!
+//! ```
+//! use logos::{LexerExt, Logos};
+//!
+//! #[derive(Logos, Debug, PartialEq)]
+//! enum Token {
+//!     #[regex(r"[ \t\n]+", logos::skip)]
+//!     Whitespace,
+//!
+//!     #[regex("[a-z]+")]
+//!     Word,
+//!
+//!     #[regex(r"/\*([^*]|\*[^/])*\*/")]
+//!     Comment,
+//! }
+//!
+//! let source = "one two /* three\nfour */ five\nsix";
+//! let mut lexer = Token::lexer(source).checkpointed(8);
+//!
+//! lexer.by_ref().for_each(drop);
+//!
+//! let checkpoints = lexer.into_checkpoints();
+//!
+//! // Checkpoints are only taken between tokens, at least 8 bytes apart, so there isn't one in the comment.
+//! assert_eq!(checkpoints.positions().collect::<Vec<_>>(), &[0, 24]);
+//!
+//! // `four` is in the middle of a comment, which starting from the line it's on would get wrong.
+//! assert_eq!(checkpoints.lex_region::<Token>(source, 17..21), &[(Ok(Token::Comment), 8..24)]);
+//! ```
+
+use crate::{Lexer, LexerExt, LexerOptions, Logos, Source, Span};
+
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+
+/// Lex the tokens overlapping `region` of `source`, starting from the beginning of the line that it starts on.
+///
+/// See the [module documentation](self) for an example, and for when the start of the line isn't a safe place to start.
+///
+/// # Panics
+///
+/// Panics if `region` isn't within the source.
+pub fn lex_region<'source, Token>(
+    source: &'source Token::Source,
+    region: Span,
+) -> Vec<(Result<Token, Token::Error>, Span)>
+where
+    Token: Logos<'source>,
+    Token::Extras: Default,
+{
+    assert!(
+        region.start <= region.end && region.end <= source.len(),
+        "the region {:?} isn't within the source",
+        region,
+    );
+
+    let start = (0..region.start)
+        .rev()
+        .find(|&at| source.read::<u8>(at) == Some(b'\n'))
+        .map_or(0, |newline| newline + 1);
+
+    lex_region_from(Token::lexer(source), start, region)
+}
+
+/// Lex the tokens overlapping `region` with `lexer`, starting at `start`. Positions include the lexer's
+/// [span offset](Lexer::span_offset), like the returned spans do.
+///
+/// The lexer is used as it is, apart from its position, so it can be set up with the extras and
+/// [contextual definitions](Lexer::enable_contextual) that it would have at `start`.
+///
+/// # Panics
+///
+/// Panics if `start` is after the start of the region, if `region` isn't within the source, or if either of them isn't
+/// a boundary in the source.
+pub fn lex_region_from<'source, Token>(
+    mut lexer: Lexer<'source, Token>,
+    start: usize,
+    region: Span,
+) -> Vec<(Result<Token, Token::Error>, Span)>
+where
+    Token: Logos<'source>,
+{
+    let offset = lexer.span_offset;
+    let valid = |position: usize| {
+        position
+            .checked_sub(offset)
+            .filter(|&position| lexer.source.is_boundary(position))
+    };
+
+    match (valid(start), valid(region.start), valid(region.end)) {
+        (Some(at), Some(_), Some(_)) if start <= region.start && region.start <= region.end => {
+            lexer.token_start = at;
+            lexer.token_end = at;
+            lexer.trivia_start = at;
+        }
+        _ => panic!(
+            "can't lex the region {:?} starting at {} of this lexer's source",
+            region, start
+        ),
+    }
+
+    let mut tokens = Vec::new();
+
+    while let Some(token) = lexer.next() {
+        let span = lexer.span();
+
+        if span.start >= region.end {
+            break;
+        }
+        if span.end > region.start || span.start >= region.start {
+            tokens.push((token, span));
+        }
+    }
+
+    tokens
+}
+
+/// Everything a lexer needs to start lexing from a position in its source, as if it had lexed the source up to there.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Checkpoint<Extras> {
+    /// Where the lexer carries on from, including the lexer's [span offset](Lexer::span_offset).
+    pub position: usize,
+
+    /// The bits of the contextual definitions that were enabled.
+    pub contextual: u64,
+
+    /// The lexer's options.
+    pub options: LexerOptions,
+
+    /// The lexer's extras.
+    pub extras: Extras,
+}
+
+/// Checkpoints recorded while lexing a whole source, for lexing regions of it later on.
+///
+/// This is created by [Checkpointed::into_checkpoints]. See the [module documentation](self) for an example.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Checkpoints<Extras> {
+    interval: usize,
+    span_offset: usize,
+    checkpoints: Vec<Checkpoint<Extras>>,
+}
+
+impl<Extras> Checkpoints<Extras> {
+    /// The checkpoints, in the order that they were recorded.
+    #[inline]
+    pub fn as_slice(&self) -> &[Checkpoint<Extras>] {
+        &self.checkpoints
+    }
+
+    /// The positions of the checkpoints.
+    #[inline]
+    pub fn positions(&self) -> impl Iterator<Item = usize> + '_ {
+        self.checkpoints
+            .iter()
+            .map(|checkpoint| checkpoint.position)
+    }
+
+    /// The minimum number of bytes between two checkpoints.
+    #[inline]
+    pub fn interval(&self) -> usize {
+        self.interval
+    }
+
+    /// The last checkpoint at or before `position`, if there is one.
+    pub fn before(&self, position: usize) -> Option<&Checkpoint<Extras>> {
+        let index = self
+            .checkpoints
+            .partition_point(|checkpoint| checkpoint.position <= position);
+
+        index.checked_sub(1).map(|index| &self.checkpoints[index])
+    }
+
+    /// Create a lexer for `source` that carries on from the last checkpoint at or before `position`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there's no checkpoint at or before `position`, or if the checkpoint doesn't belong to `source`.
+    pub fn lexer<'source, Token>(
+        &self,
+        source: &'source Token::Source,
+        position: usize,
+    ) -> Lexer<'source, Token>
+    where
+        Token: Logos<'source, Extras = Extras>,
+        Extras: Clone,
+    {
+        let checkpoint = match self.before(position) {
+            Some(checkpoint) => checkpoint,
+            None => panic!("there's no checkpoint before {}", position),
+        };
+        let at = checkpoint.position - self.span_offset;
+
+        assert!(
+            source.is_boundary(at),
+            "the checkpoint at {} doesn't belong to this source",
+            checkpoint.position
+        );
+
+        let mut lexer = Lexer::with_extras(source, checkpoint.extras.clone())
+            .with_span_offset(self.span_offset);
+
+        lexer.token_start = at;
+        lexer.token_end = at;
+        lexer.trivia_start = at;
+        lexer.contextual = checkpoint.contextual;
+        lexer.options = checkpoint.options;
+        lexer
+    }
+
+    /// Lex the tokens overlapping `region` of `source`, starting from the last checkpoint before it. Positions include
+    /// the span offset of the lexer that the checkpoints were recorded with.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there's no checkpoint at or before the start of `region`, or if `region` isn't within the source.
+    pub fn lex_region<'source, Token>(
+        &self,
+        source: &'source Token::Source,
+        region: Span,
+    ) -> Vec<(Result<Token, Token::Error>, Span)>
+    where
+        Token: Logos<'source, Extras = Extras>,
+        Extras: Clone,
+    {
+        let lexer = self.lexer(source, region.start);
+        let start = lexer.token_end + self.span_offset;
+
+        lex_region_from(lexer, start, region)
+    }
+}
+
+/// An iterator that records [Checkpoints] while lexing.
+///
+/// Since this type contains a [Lexer], it implements the [LexerExt] trait, and allows you to access information from
+/// the underlying lexer. See the [trait's documentation][LexerExt] for more information.
+///
+/// This struct is created by the [LexerExt::checkpointed] method. See the [module documentation](self) for more
+/// details.
+pub struct Checkpointed<'source, L>
+where
+    L: LexerExt<'source>,
+{
+    inner: L,
+    checkpoints: Checkpoints<<L::Token as Logos<'source>>::Extras>,
+    phantom: PhantomData<&'source ()>,
+}
+
+impl<'source, L> Checkpointed<'source, L>
+where
+    L: LexerExt<'source>,
+{
+    pub(crate) fn new(inner: L, interval: usize) -> Self {
+        let span_offset = inner.as_lexer().span_offset;
+
+        Checkpointed {
+            inner,
+            checkpoints: Checkpoints {
+                interval,
+                span_offset,
+                checkpoints: Vec::new(),
+            },
+            phantom: PhantomData,
+        }
+    }
+
+    /// The checkpoints that have been recorded so far.
+    #[inline]
+    pub fn checkpoints(&self) -> &Checkpoints<<L::Token as Logos<'source>>::Extras> {
+        &self.checkpoints
+    }
+
+    /// Consume the iterator, and return the checkpoints that were recorded.
+    #[inline]
+    pub fn into_checkpoints(self) -> Checkpoints<<L::Token as Logos<'source>>::Extras> {
+        self.checkpoints
+    }
+}
+
+impl<'source, L> Iterator for Checkpointed<'source, L>
+where
+    L: LexerExt<'source> + Iterator,
+    <L::Token as Logos<'source>>::Extras: Clone,
+{
+    type Item = L::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let lexer = self.inner.as_lexer();
+        let position = lexer.span_offset + lexer.token_end;
+        let checkpoints = &mut self.checkpoints;
+        let due = match checkpoints.checkpoints.last() {
+            Some(last) => position >= last.position + checkpoints.interval,
+            None => true,
+        };
+
+        if due && position < lexer.source.len() + lexer.span_offset {
+            checkpoints.checkpoints.push(Checkpoint {
+                position,
+                contextual: lexer.contextual,
+                options: lexer.options,
+                extras: lexer.extras.clone(),
+            });
+        }
+
+        self.inner.next()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'source, L> LexerExt<'source> for Checkpointed<'source, L>
+where
+    L: LexerExt<'source>,
+{
+    type Token = L::Token;
+
+    #[inline]
+    fn as_lexer(&self) -> &Lexer<'source, Self::Token> {
+        self.inner.as_lexer()
+    }
+
+    #[inline]
+    fn as_lexer_mut(&mut self) -> &mut Lexer<'source, Self::Token> {
+        self.inner.as_lexer_mut()
+    }
+
+    #[inline]
+    fn into_lexer(self) -> Lexer<'source, Self::Token> {
+        self.inner.into_lexer()
+    }
+}
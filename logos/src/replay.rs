@@ -0,0 +1,184 @@
+//! Snapshots of a lexer's state, for reproducing bugs by replaying recorded traces.
+//!
+//! A parser bug that only shows up on some inputs - or only after a callback has put the lexer into an unusual mode -
+//! can be hard to pin down. A [LexerState] captures everything that decides how the lexer carries on: the position of
+//! the current token, the enabled [contextual definitions](crate::Lexer::enable_contextual), the
+//! [options](crate::LexerOptions) and a digest of the extras. States can be compared, so a trace recorded with
+//! [LexerExt::trace] when the bug happened can be checked against a fresh run with [Trace::divergence], which points out
+//! the first token where the two runs went their separate ways. [Lexer::restore] then puts a lexer back into any
+//! recorded state, to replay from just before that token.
+//!
+//! ```
+//! use logos::{Lexer, LexerExt, Logos};
+//!
+//! #[derive(Logos, Debug, PartialEq)]
+//! #[logos(extras = usize)]
+//! enum Token {
+//!     #[regex(r"[ \t\n]+", logos::skip)]
+//!     Whitespace,
+//!
+//!     #[regex("[a-z]+", |lex| lex.extras += 1)]
+//!     Word,
+//! }
+//!
+//! let mut recorded = Token::lexer("one two three").trace();
+//!
+//! recorded.by_ref().for_each(drop);
+//!
+//! let states = recorded.into_states();
+//!
+//! // A later run starts off with different extras, so its states diverge from the first token onwards.
+//! let mut replay = Token::lexer_with_extras("one two three", 10).trace();
+//!
+//! replay.by_ref().for_each(drop);
+//!
+//! assert_eq!(replay.divergence(&states), Some(0));
+//!
+//! // Restoring a recorded state picks up from where it was recorded.
+//! let mut lexer = Token::lexer("one two three");
+//!
+//! lexer.restore(&states[1]);
+//!
+//! assert_eq!(lexer.span(), 4..7);
+//! assert_eq!(lexer.next(), Some(Ok(Token::Word)));
+//! assert_eq!(lexer.slice(), "three");
+//! ```
+//!
+//! [Lexer::restore]: crate::Lexer::restore
+
+use crate::{Lexer, LexerExt, LexerOptions, Logos, Span};
+
+use core::hash::{Hash, Hasher};
+use core::marker::PhantomData;
+use std::collections::hash_map::DefaultHasher;
+
+/// A comparable snapshot of a [Lexer]'s state, created with [Lexer::state].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LexerState {
+    /// The span of the current token, including the lexer's [span offset](Lexer::span_offset).
+    pub span: Span,
+
+    /// The start of the current token's leading trivia, including the lexer's span offset.
+    pub trivia_start: usize,
+
+    /// The bits of the contextual definitions that were enabled.
+    pub contextual: u64,
+
+    /// The lexer's options.
+    pub options: LexerOptions,
+
+    /// A digest of the lexer's extras. Digests are only comparable between runs of the same build, since the hash
+    /// that they're computed with can change between Rust versions.
+    pub extras: u64,
+}
+
+impl LexerState {
+    pub(crate) fn new<'source, Token>(lexer: &Lexer<'source, Token>) -> Self
+    where
+        Token: Logos<'source>,
+        Token::Extras: Hash,
+    {
+        let mut hasher = DefaultHasher::new();
+
+        lexer.extras.hash(&mut hasher);
+
+        LexerState {
+            span: lexer.span(),
+            trivia_start: lexer.span_offset + lexer.trivia_start,
+            contextual: lexer.contextual,
+            options: lexer.options,
+            extras: hasher.finish(),
+        }
+    }
+}
+
+/// An iterator that records the [state](LexerState) of the lexer after each item.
+///
+/// Since this type contains a [Lexer], it implements the [LexerExt] trait, and allows you to access information from
+/// the underlying lexer. See the [trait's documentation][LexerExt] for more information.
+///
+/// This struct is created by the [LexerExt::trace] method. See the [module documentation](self) for more details.
+pub struct Trace<'source, L> {
+    pub(crate) inner: L,
+    states: Vec<LexerState>,
+    phantom: PhantomData<&'source ()>,
+}
+
+impl<'source, L> Trace<'source, L> {
+    pub(crate) fn new(inner: L) -> Self {
+        Trace {
+            inner,
+            states: Vec::new(),
+            phantom: PhantomData,
+        }
+    }
+
+    /// The states that have been recorded so far, one for each item.
+    #[inline]
+    pub fn states(&self) -> &[LexerState] {
+        &self.states
+    }
+
+    /// Consume the iterator, and return the states that were recorded.
+    #[inline]
+    pub fn into_states(self) -> Vec<LexerState> {
+        self.states
+    }
+
+    /// Compare the states recorded so far with a `recorded` trace, and return the index of the first item where they
+    /// differ. If one trace is a prefix of the other, this is the length of the shorter one. Returns `None` if the
+    /// traces are the same.
+    pub fn divergence(&self, recorded: &[LexerState]) -> Option<usize> {
+        match self.states.iter().zip(recorded).position(|(a, b)| a != b) {
+            Some(index) => Some(index),
+            None if self.states.len() != recorded.len() => {
+                Some(self.states.len().min(recorded.len()))
+            }
+            None => None,
+        }
+    }
+}
+
+impl<'source, L> Iterator for Trace<'source, L>
+where
+    L: LexerExt<'source> + Iterator,
+    <L::Token as Logos<'source>>::Extras: Hash,
+{
+    type Item = L::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.inner.next()?;
+
+        self.states.push(self.inner.as_lexer().state());
+
+        Some(item)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'source, L> LexerExt<'source> for Trace<'source, L>
+where
+    L: LexerExt<'source>,
+{
+    type Token = L::Token;
+
+    #[inline]
+    fn as_lexer(&self) -> &Lexer<'source, Self::Token> {
+        self.inner.as_lexer()
+    }
+
+    #[inline]
+    fn as_lexer_mut(&mut self) -> &mut Lexer<'source, Self::Token> {
+        self.inner.as_lexer_mut()
+    }
+
+    #[inline]
+    fn into_lexer(self) -> Lexer<'source, Self::Token> {
+        self.inner.into_lexer()
+    }
+}
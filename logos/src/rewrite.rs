@@ -0,0 +1,209 @@
+//! Tools for rewriting source code, one token at a time.
+//!
+//! Formatters and refactoring tools tend to share a common problem: they want to change a handful of tokens, but
+//! leave everything else - whitespace, comments, and all other kinds of trivia - exactly as it was written. The
+//! [Rewriter] type takes care of the fiddly parts of this process. You describe *what* should change using spans
+//! produced by a [Lexer][crate::Lexer], and it splices the replacements into the original source for you.
+//!
+//! ```
+//! use logos::{Logos, rewrite::Rewriter};
+//!
+//! #[derive(Logos, Debug, PartialEq)]
+//! enum Token {
+//!     #[regex(r"[ \n\t]+", logos::skip)]
+//!     Whitespace,
+//!
+//!     #[regex(r"#[^\n]*", logos::skip)]
+//!     Comment,
+//!
+//!     #[token("let")]
+//!     Let,
+//!
+//!     #[regex("[a-z]+")]
+//!     Ident,
+//! }
+//!
+//! let source = "let  foo # a comment\nlet bar";
+//! let mut rewriter = Rewriter::new(source);
+//!
+//! rewriter.replace_tokens(Token::lexer(source).spanned().flatten(), |token, slice| match token {
+//!     Token::Let => Some("const"),
+//!     Token::Ident if slice == "foo" => Some("baz"),
+//!     _ => None,
+//! });
+//!
+//! assert_eq!(rewriter.finish().unwrap(), "const  baz # a comment\nconst bar");
+//! ```
+
+use crate::Span;
+
+use alloc::borrow::Cow;
+use alloc::string::String;
+use alloc::vec::Vec;
+use std::fmt::{self, Display, Formatter};
+
+/// A single change to the source, replacing the text within `span` with `replacement`.
+///
+/// Insertions are represented by an empty span, and deletions by an empty replacement.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Edit<'r> {
+    /// The source position of the text that should be replaced.
+    pub span: Span,
+
+    /// The text to insert in place of `span`.
+    pub replacement: Cow<'r, str>,
+}
+
+/// Collects a set of [Edit]s, and applies them to the original source.
+///
+/// Edits can be added in any order, since they're sorted by their position when [finishing][Rewriter::finish].
+/// Multiple insertions at the same position are applied in the order that they were added, and insertions always come
+/// before a replacement that starts at the same position.
+///
+/// See the [module documentation][self] for an example.
+#[derive(Debug, Clone)]
+pub struct Rewriter<'source, 'r> {
+    source: &'source str,
+    edits: Vec<Edit<'r>>,
+}
+
+impl<'source, 'r> Rewriter<'source, 'r> {
+    /// Create a new `Rewriter` for `source`, without any edits.
+    pub fn new(source: &'source str) -> Self {
+        Rewriter {
+            source,
+            edits: Vec::new(),
+        }
+    }
+
+    /// The original source that this `Rewriter` will apply edits to.
+    #[inline]
+    pub fn source(&self) -> &'source str {
+        self.source
+    }
+
+    /// The edits that have been added so far, in the order they were added.
+    #[inline]
+    pub fn edits(&self) -> &[Edit<'r>] {
+        &self.edits
+    }
+
+    /// Replace the text within `span` with `replacement`.
+    pub fn replace(&mut self, span: Span, replacement: impl Into<Cow<'r, str>>) -> &mut Self {
+        self.edits.push(Edit {
+            span,
+            replacement: replacement.into(),
+        });
+
+        self
+    }
+
+    /// Insert `text` at the byte offset `at`, leaving the surrounding source untouched.
+    pub fn insert(&mut self, at: usize, text: impl Into<Cow<'r, str>>) -> &mut Self {
+        self.replace(at..at, text)
+    }
+
+    /// Remove the text within `span`.
+    pub fn delete(&mut self, span: Span) -> &mut Self {
+        self.replace(span, "")
+    }
+
+    /// Walk over a stream of spanned tokens, replacing each token for which `op` returns `Some`.
+    ///
+    /// `op` is called with each token and the slice of source it was read from. Lexers produce a suitable stream with
+    /// [Lexer::spanned][crate::Lexer::spanned], though you'll usually want to deal with (or discard) errors first.
+    pub fn replace_tokens<I, T, F, R>(&mut self, tokens: I, mut op: F) -> &mut Self
+    where
+        I: IntoIterator<Item = (T, Span)>,
+        F: FnMut(&T, &'source str) -> Option<R>,
+        R: Into<Cow<'r, str>>,
+    {
+        for (token, span) in tokens {
+            let slice = match self.source.get(span.clone()) {
+                Some(slice) => slice,
+                None => {
+                    // Keep the span around, so that `finish` can report it.
+                    self.edits.push(Edit {
+                        span,
+                        replacement: Cow::Borrowed(""),
+                    });
+                    continue;
+                }
+            };
+
+            if let Some(replacement) = op(&token, slice) {
+                self.replace(span, replacement);
+            }
+        }
+
+        self
+    }
+
+    /// Apply all edits, producing the rewritten source.
+    ///
+    /// Any text that isn't covered by an edit is copied over verbatim. This fails if an edit's span is out of bounds
+    /// or doesn't fall on a UTF-8 character boundary, or if two edits would replace the same text.
+    pub fn finish(mut self) -> Result<String, RewriteError> {
+        // A stable sort keeps insertions at the same position in the order they were added.
+        self.edits
+            .sort_by_key(|edit| (edit.span.start, edit.span.end));
+
+        let mut out = String::with_capacity(self.source.len());
+        let mut cursor = 0;
+        let mut previous: Option<&Span> = None;
+
+        for edit in &self.edits {
+            let span = &edit.span;
+
+            if span.start > span.end || self.source.get(span.clone()).is_none() {
+                return Err(RewriteError::InvalidSpan(span.clone()));
+            }
+
+            if let Some(previous) = previous {
+                if span.start < previous.end {
+                    return Err(RewriteError::Overlapping(previous.clone(), span.clone()));
+                }
+            }
+
+            out.push_str(&self.source[cursor..span.start]);
+            out.push_str(&edit.replacement);
+
+            cursor = span.end;
+            previous = Some(span);
+        }
+
+        out.push_str(&self.source[cursor..]);
+
+        Ok(out)
+    }
+}
+
+/// An error produced when [Rewriter::finish] fails to apply a set of edits.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum RewriteError {
+    /// The span is out of bounds, or does not fall on a UTF-8 character boundary.
+    InvalidSpan(Span),
+
+    /// Two edits would replace the same part of the source.
+    Overlapping(Span, Span),
+}
+
+impl Display for RewriteError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            RewriteError::InvalidSpan(span) => {
+                write!(
+                    f,
+                    "span {:?} is not valid for the source being rewritten",
+                    span
+                )
+            }
+            RewriteError::Overlapping(a, b) => {
+                write!(f, "edits at {:?} and {:?} overlap", a, b)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for RewriteError {}
@@ -0,0 +1,424 @@
+//! Lexing a stream of bytes through a fixed-size ring buffer, without allocating.
+//!
+//! Firmware that reads commands from a UART or a socket gets its input a few bytes at a time, and usually can't
+//! allocate a buffer large enough for the whole stream (or at all). A [RingLexer] works on a buffer owned by the caller
+//! instead: bytes are added with [push_bytes][RingLexer::push_bytes] as they arrive, and tokens are taken out with
+//! [next_token][RingLexer::next_token] once they're complete. The space used by a token is reused as soon as the next
+//! token is requested.
+//!
+//! Since more bytes could always arrive, a token that reaches the end of the buffered bytes might not be complete yet -
+//! `foo` could turn out to be the start of `foobar`. In that case `next_token` returns [Next::Incomplete], and the
+//! token is lexed again once more bytes have been pushed. Once the stream has ended, calling
+//! [finish][RingLexer::finish] lets the remaining bytes be lexed as they are.
+//!
+//! ```
+//! use logos::Logos;
+//! use logos::ring::{Next, RingLexer};
+//!
+//! #[derive(Logos, Debug, PartialEq)]
+//! #[logos(source = [u8])]
+//! enum Command {
+//!     #[regex(r"[ \r\n]+", logos::skip)]
+//!     Whitespace,
+//!
+//!     #[token("led")]
+//!     Led,
+//!
+//!     #[token("on")]
+//!     On,
+//!
+//!     #[regex("[0-9]+", |lex| core::str::from_utf8(lex.slice()).ok()?.parse().ok())]
+//!     Number(u8),
+//! }
+//!
+//! let mut buffer = [0; 16];
+//! let mut lexer = RingLexer::<Command>::new(&mut buffer);
+//!
+//! lexer.push_bytes(b"le");
+//! assert_eq!(lexer.next_token(), Next::Incomplete);
+//!
+//! lexer.push_bytes(b"d 1");
+//! assert_eq!(lexer.next_token(), Next::Token(Ok(Command::Led)));
+//! assert_eq!(lexer.span(), 0..3);
+//!
+//! // There may be more digits to come.
+//! assert_eq!(lexer.next_token(), Next::Incomplete);
+//!
+//! lexer.push_bytes(b"2 on\n");
+//! assert_eq!(lexer.next_token(), Next::Token(Ok(Command::Number(12))));
+//! assert_eq!(lexer.next_token(), Next::Token(Ok(Command::On)));
+//! assert_eq!(lexer.next_token(), Next::Incomplete);
+//!
+//! lexer.finish();
+//! assert_eq!(lexer.next_token(), Next::End);
+//! ```
+//!
+//! Like an [OwnedLexer](crate::owned::OwnedLexer), a `RingLexer` only works with token types that don't borrow from
+//! the source. Callbacks run each time a token is lexed, so a token that turns out to be incomplete (and any skipped
+//! tokens before it) will run its callback again when it's lexed for a second time. The same goes for the tokens after
+//! an error, which are lexed to check that the error isn't just the start of a token that hasn't fully arrived. To
+//! keep the extras consistent, each attempt works on a clone of them, and the changes are only kept once a token is
+//! returned - so the extras need to implement [Clone]. The `on_eof` hook only runs once, when the end of the stream is
+//! reached, and not whenever the buffered bytes run out.
+
+use crate::owned::OwnedToken;
+use crate::{Lexer, LexerOptions, Span};
+
+use core::fmt::{self, Debug};
+use core::mem::ManuallyDrop;
+use core::task::Poll;
+
+type ExtrasOf<T> = <T as OwnedToken>::OwnedExtras;
+type ErrorOf<T> = <T as OwnedToken>::OwnedError;
+
+/// The result of [RingLexer::next_token].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Next<Token, Error> {
+    /// A complete token (or an error).
+    Token(Result<Token, Error>),
+
+    /// The buffered bytes end partway through a token, so more bytes need to be pushed before it can be lexed.
+    Incomplete,
+
+    /// The stream has [finished][RingLexer::finish], and every token has been lexed.
+    End,
+}
+
+/// A lexer that reads from a fixed-size ring buffer. See the [module documentation](self) for details.
+pub struct RingLexer<'buf, Token>
+where
+    Token: OwnedToken<OwnedSource = [u8]>,
+{
+    buffer: &'buf mut [u8],
+
+    /// The position of the first buffered byte in `buffer`.
+    start: usize,
+
+    /// The number of buffered bytes, including the bytes of the last token.
+    len: usize,
+
+    /// The number of bytes that were read (and discarded) before `start`.
+    offset: usize,
+
+    /// The span of the last token, relative to `start`.
+    token: Span,
+
+    /// The minimum number of bytes that must follow a token for it to count as complete.
+    lookahead: usize,
+    finished: bool,
+
+    /// Whether the end of the stream has been reached, and the `on_eof` hook has been run.
+    ended: bool,
+    extras: ExtrasOf<Token>,
+    contextual: u64,
+    options: LexerOptions,
+}
+
+impl<'buf, Token> RingLexer<'buf, Token>
+where
+    Token: OwnedToken<OwnedSource = [u8]>,
+{
+    /// Create a new `RingLexer` that stores bytes in `buffer`.
+    ///
+    /// The buffer needs to be large enough to hold the longest token, plus any whitespace or other skipped tokens
+    /// before it.
+    pub fn new(buffer: &'buf mut [u8]) -> Self
+    where
+        ExtrasOf<Token>: Default,
+    {
+        Self::with_extras(buffer, Default::default())
+    }
+
+    /// Create a new `RingLexer` that stores bytes in `buffer`, with the provided extras.
+    pub fn with_extras(buffer: &'buf mut [u8], extras: ExtrasOf<Token>) -> Self {
+        RingLexer {
+            buffer,
+            start: 0,
+            len: 0,
+            offset: 0,
+            token: 0..0,
+            lookahead: 1,
+            finished: false,
+            ended: false,
+            extras,
+            contextual: 0,
+            options: LexerOptions::EMPTY,
+        }
+    }
+
+    /// Replace the lexer's [options][LexerOptions], returning the lexer.
+    #[inline]
+    pub fn with_options(mut self, options: LexerOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Set the number of bytes that must follow a token before it's considered complete, returning the lexer. This is
+    /// 1 by default.
+    ///
+    /// Logos sometimes has to look past the end of a token to know that it's over. For example, with the definitions
+    /// `#[token("a")]` and `#[token("abc")]`, the input `ab` is lexed as `a` followed by an error, but it would be lexed
+    /// as a single token if the next byte were `c`. A lookahead of 2 makes sure that case is handled correctly.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `lookahead` is 0.
+    #[inline]
+    pub fn with_lookahead(mut self, lookahead: usize) -> Self {
+        assert!(lookahead > 0, "the lookahead must be at least 1 byte");
+
+        self.lookahead = lookahead;
+        self
+    }
+
+    /// Add as many bytes from `bytes` to the buffer as will fit, and return how many were added.
+    ///
+    /// The bytes of the last token returned by [next_token][Self::next_token] stay in the buffer until the next call to
+    /// `next_token`, so they don't count as free space.
+    pub fn push_bytes(&mut self, bytes: &[u8]) -> usize {
+        let capacity = self.buffer.len();
+        let count = bytes.len().min(capacity - self.len);
+        let end = (self.start + self.len) % capacity.max(1);
+
+        // The free space may wrap around the end of the buffer.
+        let (first, second) = bytes[..count].split_at(count.min(capacity - end));
+
+        self.buffer[end..end + first.len()].copy_from_slice(first);
+        self.buffer[..second.len()].copy_from_slice(second);
+        self.len += count;
+
+        count
+    }
+
+    /// Mark the end of the stream, so that the remaining bytes are lexed without waiting for more.
+    #[inline]
+    pub fn finish(&mut self) {
+        self.finished = true;
+    }
+
+    /// Returns `true` if [finish][Self::finish] has been called.
+    #[inline]
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// The number of bytes that can currently be pushed.
+    #[inline]
+    pub fn free(&self) -> usize {
+        self.buffer.len() - self.len
+    }
+
+    /// Lex the next token from the buffered bytes.
+    ///
+    /// This returns [Next::Incomplete] if the token might continue past the bytes that have been pushed so far. If the
+    /// buffer is full, the token is returned anyway - a token that doesn't fit in the buffer will be split up.
+    pub fn next_token(&mut self) -> Next<Token, ErrorOf<Token>>
+    where
+        ExtrasOf<Token>: Clone,
+    {
+        // The last token isn't needed anymore, so its space can be reused.
+        self.consume(self.token.end);
+
+        if self.len == 0 {
+            return match self.finished {
+                true => self.end(),
+                false => Next::Incomplete,
+            };
+        }
+
+        // Tokens are lexed from a contiguous slice, so the buffered bytes can't wrap around the end of the buffer.
+        if self.start + self.len > self.buffer.len() {
+            self.buffer.rotate_left(self.start);
+            self.start = 0;
+        }
+
+        // Whatever the callbacks change is thrown away if the token turns out to be incomplete.
+        let mut lexer = self.lexer();
+
+        // The end of the buffered bytes isn't the end of the stream, so the hooks for the end of input aren't run here.
+        let token = lexer.lex_token();
+        let span = lexer.token_start..lexer.token_end;
+        let (contextual, options) = (lexer.contextual, lexer.options);
+        let is_complete = |end: usize| {
+            self.finished
+                || self.len == self.buffer.len()
+                || end.saturating_add(self.lookahead) <= self.len
+        };
+
+        let mut complete = is_complete(span.end);
+        let mut extras = None;
+
+        // An unrecognised byte is often just the start of a token that hasn't fully arrived yet, so errors are only
+        // reported once whatever follows them is complete too. The tokens after the error are lexed again by the
+        // next call, so the changes their callbacks make aren't kept.
+        if complete && matches!(token, Some(Err(_))) {
+            extras = Some(lexer.extras.clone());
+            complete = loop {
+                match lexer.lex_token() {
+                    Some(Err(_)) => continue,
+                    _ => break is_complete(lexer.token_end),
+                }
+            };
+        }
+
+        let extras = extras.unwrap_or(lexer.extras);
+
+        match token {
+            Some(token) if complete => {
+                self.extras = extras;
+                self.contextual = contextual;
+                self.options = options;
+                self.token = span;
+
+                Next::Token(token)
+            }
+            // Everything left was skipped, but it can only be thrown away if no more bytes could extend it.
+            None if complete => {
+                self.extras = extras;
+                self.consume(self.len);
+
+                match self.finished {
+                    true => self.end(),
+                    false => Next::Incomplete,
+                }
+            }
+            _ => Next::Incomplete,
+        }
+    }
+
+    /// Lex the next token from the buffered bytes, in the shape of `Stream::poll_next` from the futures ecosystem.
+    ///
+    /// `data_available` says whether more bytes may still be pushed. Passing `false` is the same as calling
+    /// [finish][Self::finish] first. This never blocks: [Poll::Pending] means that more bytes need to be pushed before
+    /// the next token can be lexed, so a runtime can wait for its source to become readable, push what it reads and
+    /// poll again. `Poll::Ready(None)` means that every token has been lexed.
+    ///
+    /// ```
+    /// use core::task::Poll;
+    /// use logos::Logos;
+    /// use logos::ring::RingLexer;
+    ///
+    /// #[derive(Logos, Debug, PartialEq)]
+    /// #[logos(source = [u8])]
+    /// enum Token {
+    ///     #[regex("[a-z]+")]
+    ///     Word,
+    /// }
+    ///
+    /// let mut buffer = [0; 8];
+    /// let mut lexer = RingLexer::<Token>::new(&mut buffer);
+    ///
+    /// lexer.push_bytes(b"ab");
+    /// assert_eq!(lexer.poll_next_token(true), Poll::Pending);
+    /// assert_eq!(lexer.poll_next_token(false), Poll::Ready(Some(Ok(Token::Word))));
+    /// assert_eq!(lexer.poll_next_token(false), Poll::Ready(None));
+    /// ```
+    pub fn poll_next_token(
+        &mut self,
+        data_available: bool,
+    ) -> Poll<Option<Result<Token, ErrorOf<Token>>>>
+    where
+        ExtrasOf<Token>: Clone,
+    {
+        if !data_available {
+            self.finish();
+        }
+
+        match self.next_token() {
+            Next::Token(token) => Poll::Ready(Some(token)),
+            Next::Incomplete => Poll::Pending,
+            Next::End => Poll::Ready(None),
+        }
+    }
+
+    /// The position of the last token in the stream.
+    #[inline]
+    pub fn span(&self) -> Span {
+        self.offset + self.token.start..self.offset + self.token.end
+    }
+
+    /// The bytes of the last token.
+    #[inline]
+    pub fn slice(&self) -> &[u8] {
+        &self.buffer[self.start + self.token.start..self.start + self.token.end]
+    }
+
+    /// Get a reference to the lexer's extras.
+    #[inline]
+    pub fn extras(&self) -> &ExtrasOf<Token> {
+        &self.extras
+    }
+
+    /// Get a mutable reference to the lexer's extras.
+    #[inline]
+    pub fn extras_mut(&mut self) -> &mut ExtrasOf<Token> {
+        &mut self.extras
+    }
+
+    /// A lexer over the buffered bytes, working on a clone of the extras.
+    fn lexer(&self) -> Lexer<'_, Token>
+    where
+        ExtrasOf<Token>: Clone,
+    {
+        Lexer {
+            source: &self.buffer[self.start..self.start + self.len],
+            token: ManuallyDrop::new(None),
+            token_start: 0,
+            token_end: 0,
+            trivia_start: 0,
+            captures: None,
+            matched_rule: 0,
+            contextual: self.contextual,
+            options: self.options,
+            span_offset: self.offset,
+            // The end of the buffered bytes isn't the end of the stream, which is reported with `Next::End` instead.
+            eof_emitted: true,
+            // The extras never see the whole stream, so their lifecycle hooks aren't run.
+            finished: true,
+            segmenting: false,
+            segment: None,
+            extras: self.extras.clone(),
+        }
+    }
+
+    /// Report the end of the stream, running the `on_eof` hook the first time it's reached.
+    fn end(&mut self) -> Next<Token, ErrorOf<Token>>
+    where
+        ExtrasOf<Token>: Clone,
+    {
+        if !self.ended {
+            let mut lexer = self.lexer();
+
+            Token::on_eof(&mut lexer);
+
+            self.extras = lexer.extras;
+            self.ended = true;
+        }
+
+        Next::End
+    }
+
+    /// Discard the first `count` buffered bytes.
+    fn consume(&mut self, count: usize) {
+        self.start = (self.start + count) % self.buffer.len().max(1);
+        self.len -= count;
+        self.offset += count;
+        self.token = 0..0;
+    }
+}
+
+impl<'buf, Token> Debug for RingLexer<'buf, Token>
+where
+    Token: OwnedToken<OwnedSource = [u8]>,
+    ExtrasOf<Token>: Debug,
+{
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("RingLexer")
+            .field("capacity", &self.buffer.len())
+            .field("len", &self.len)
+            .field("offset", &self.offset)
+            .field("finished", &self.finished)
+            .field("extras", &self.extras)
+            .finish()
+    }
+}
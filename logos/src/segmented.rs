@@ -0,0 +1,457 @@
+//! Lexing from sources that are split into several segments, like ropes.
+//!
+//! Text editors usually keep documents in a rope, which stores the text as a tree of smaller chunks. Copying the whole
+//! document into a [String] every time it changes (just to lex it) would defeat the point of having a fast lexer, so
+//! the [Segmented] source type can lex the chunks directly.
+//!
+//! A `Segmented` source is created from the segments in order, and can be used with a lexer through the derive macro's
+//! `source` option. For example, a [ropey](https://docs.rs/ropey) rope could be lexed using
+//! `Segmented::new(rope.chunks())`.
+//!
+//! ```
+//! use logos::Logos;
+//! use logos::segmented::Segmented;
+//!
+//! #[derive(Logos, Debug, PartialEq)]
+//! #[logos(source = Segmented<'s>)]
+//! enum Token {
+//!     #[regex(r"[ \t\n]+", logos::skip)]
+//!     Whitespace,
+//!
+//!     #[token("let")]
+//!     Let,
+//!
+//!     #[regex("[a-z]+")]
+//!     Ident,
+//! }
+//!
+//! let source = Segmented::new(["let fo", "o = b", "", "ar"]);
+//! let mut lexer = Token::lexer(&source);
+//!
+//! assert_eq!(lexer.next(), Some(Ok(Token::Let)));
+//! assert_eq!(lexer.next(), Some(Ok(Token::Ident)));
+//! assert_eq!(lexer.slice(), "foo");
+//! assert_eq!(lexer.span(), 4..7);
+//! ```
+//!
+//! Segments can be bytes too, for binary formats that arrive in several buffers, like the `iovec`s of a vectored read
+//! or the two halves of a ring buffer ([VecDeque::as_slices](std::collections::VecDeque::as_slices)):
+//!
+//! ```
+//! use std::collections::VecDeque;
+//!
+//! use logos::Logos;
+//! use logos::segmented::Segmented;
+//!
+//! #[derive(Logos, Debug, PartialEq)]
+//! #[logos(source = Segmented<'s, [u8]>)]
+//! enum Frame<'s> {
+//!     #[regex(b"\x01[^\x00]*\x00")]
+//!     Text(&'s [u8]),
+//!
+//!     #[token(b"\x02")]
+//!     Ping,
+//! }
+//!
+//! let mut buffer = VecDeque::with_capacity(8);
+//!
+//! // Fill the buffer, and drain part of it, so that what's left wraps around its end.
+//! buffer.extend(b"\x02\x02\x02\x02\x02\x02");
+//! buffer.drain(..5);
+//! buffer.extend(b"\x01hi\x00");
+//!
+//! let (front, back) = buffer.as_slices();
+//! let source = Segmented::from(&[front, back][..]);
+//! let mut lexer = Frame::lexer(&source);
+//!
+//! assert_eq!(lexer.next(), Some(Ok(Frame::Ping)));
+//! assert_eq!(lexer.next(), Some(Ok(Frame::Text(b"\x01hi\x00"))));
+//! assert_eq!(lexer.next(), None);
+//! ```
+//!
+//! Tokens that lie within a single segment are sliced directly from it. The (rare) tokens that straddle two or more
+//! segments are copied into a buffer owned by the `Segmented` source, which lives as long as the source does.
+//!
+//! That buffer keeps growing while the source is lexed, which might not be what you want for a long-lived source with
+//! lots of long tokens, like comments. A [SliceView] field borrows from the source where it can, and only copies the
+//! tokens that it can't borrow into the token itself:
+//!
+//! ```
+//! use logos::Logos;
+//! use logos::segmented::{Segmented, SliceView};
+//!
+//! #[derive(Logos, Debug, PartialEq)]
+//! #[logos(source = Segmented<'s>)]
+//! enum Token<'s> {
+//!     #[regex(r"[ \t\n]+", logos::skip)]
+//!     Whitespace,
+//!
+//!     #[regex("#[^\n]*")]
+//!     Comment(SliceView<'s>),
+//! }
+//!
+//! let comment = "# a comment long enough to not fit into the seams between segments";
+//! let source = Segmented::new([&comment[..10], &comment[10..]]);
+//! let mut lexer = Token::lexer(&source);
+//!
+//! match lexer.next() {
+//!     Some(Ok(Token::Comment(SliceView::Owned(text)))) => assert_eq!(text, comment),
+//!     token => panic!("unexpected token: {:?}", token),
+//! }
+//! ```
+
+use crate::internal::FromSlice;
+use crate::source::{Chunk, Source};
+use crate::{Lexer, Logos};
+
+use std::borrow::Cow;
+use std::fmt::{self, Debug};
+use std::ops::Range;
+use std::sync::Mutex;
+
+/// The number of bytes on either side of a segment boundary that are kept in one piece, so that small reads which
+/// straddle the boundary don't need to allocate.
+const SEAM: usize = 32;
+
+/// A slice of a [Segmented] source, which is borrowed from it unless it's long and straddles segments, in which case
+/// it's copied.
+///
+/// Fields of this type are filled in by the derive macro in the same way as `&str` fields, when their variant doesn't
+/// have a callback. See the [module documentation][self] for an example.
+pub type SliceView<'source, S = str> = Cow<'source, S>;
+
+/// Types that a [Segmented] source can be made of.
+///
+/// This is implemented for [str] and `[u8]`.
+pub trait Segment: PartialEq + Eq + Debug {
+    /// The contents of the segment, as bytes.
+    fn as_bytes(&self) -> &[u8];
+
+    /// Convert bytes back into a segment.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that `bytes` upholds all of this type's invariants. For example, the bytes must be valid
+    /// UTF-8 for [str].
+    unsafe fn from_bytes_unchecked(bytes: &[u8]) -> &Self;
+
+    /// Check if `index` is a valid place to split the segment, in the same way as [Source::is_boundary].
+    fn is_boundary(&self, index: usize) -> bool;
+}
+
+impl Segment for str {
+    #[inline]
+    fn as_bytes(&self) -> &[u8] {
+        str::as_bytes(self)
+    }
+
+    #[inline]
+    unsafe fn from_bytes_unchecked(bytes: &[u8]) -> &str {
+        std::str::from_utf8_unchecked(bytes)
+    }
+
+    #[inline]
+    fn is_boundary(&self, index: usize) -> bool {
+        self.is_char_boundary(index)
+    }
+}
+
+impl Segment for [u8] {
+    #[inline]
+    fn as_bytes(&self) -> &[u8] {
+        self
+    }
+
+    #[inline]
+    unsafe fn from_bytes_unchecked(bytes: &[u8]) -> &[u8] {
+        bytes
+    }
+
+    #[inline]
+    fn is_boundary(&self, index: usize) -> bool {
+        index <= self.len()
+    }
+}
+
+/// A source made up of several segments, which are lexed as though they were one contiguous source.
+///
+/// See the [module documentation][self] for more details.
+pub struct Segmented<'source, S: Segment + ?Sized = str> {
+    segments: Vec<&'source S>,
+
+    /// The offset of the first byte of each segment.
+    starts: Vec<usize>,
+    len: usize,
+
+    /// A contiguous copy of the bytes surrounding each boundary between two segments.
+    seams: Vec<Box<[u8]>>,
+
+    /// Copies of larger ranges that straddle segments. These are never removed, so the references handed out to them
+    /// remain valid for as long as the source is borrowed.
+    spilled: Mutex<Vec<Box<[u8]>>>,
+}
+
+impl<'source, S: Segment + ?Sized> Segmented<'source, S> {
+    /// Create a source from `segments`, in order. Empty segments are ignored.
+    pub fn new<I>(segments: I) -> Self
+    where
+        I: IntoIterator<Item = &'source S>,
+    {
+        let segments: Vec<&S> = segments
+            .into_iter()
+            .filter(|segment| !segment.as_bytes().is_empty())
+            .collect();
+
+        let mut starts = Vec::with_capacity(segments.len());
+        let mut len = 0;
+
+        for segment in &segments {
+            starts.push(len);
+            len += segment.as_bytes().len();
+        }
+
+        let mut source = Segmented {
+            segments,
+            starts,
+            len,
+            seams: Vec::new(),
+            spilled: Mutex::new(Vec::new()),
+        };
+
+        source.seams = source.starts[1.min(source.starts.len())..]
+            .iter()
+            .map(|&boundary| source.copy(source.seam_range(boundary)))
+            .collect();
+
+        source
+    }
+
+    /// The segments that make up this source, not including any empty ones.
+    #[inline]
+    pub fn segments(&self) -> &[&'source S] {
+        &self.segments
+    }
+
+    /// The range of bytes kept in the seam for `boundary`.
+    fn seam_range(&self, boundary: usize) -> Range<usize> {
+        boundary.saturating_sub(SEAM - 1)..(boundary + SEAM - 1).min(self.len)
+    }
+
+    /// The index of the segment containing the byte at `offset`.
+    #[inline]
+    fn segment_at(&self, offset: usize) -> usize {
+        self.starts.partition_point(|&start| start <= offset) - 1
+    }
+
+    /// Copy `range` into a new buffer, which may straddle any number of segments.
+    fn copy(&self, range: Range<usize>) -> Box<[u8]> {
+        let mut out = Vec::with_capacity(range.len());
+        let mut index = self.segment_at(range.start);
+
+        while out.len() < range.len() {
+            let start = self.starts[index];
+            let bytes = self.segments[index].as_bytes();
+            let from = (range.start + out.len()) - start;
+            let to = (range.end - start).min(bytes.len());
+
+            out.extend_from_slice(&bytes[from..to]);
+            index += 1;
+        }
+
+        out.into_boxed_slice()
+    }
+
+    /// Get `range` as a contiguous slice of bytes without copying it, if it's within a segment or a seam. The range
+    /// must be in bounds.
+    fn borrowed(&self, range: Range<usize>) -> Option<&[u8]> {
+        if range.is_empty() {
+            return Some(&[]);
+        }
+
+        let index = self.segment_at(range.start);
+        let start = self.starts[index];
+        let bytes = self.segments[index].as_bytes();
+
+        if range.end <= start + bytes.len() {
+            return Some(&bytes[range.start - start..range.end - start]);
+        }
+
+        if range.len() <= SEAM {
+            // Any range that's no longer than a seam, and starts before a boundary, fits within that boundary's seam.
+            let seam = self.seam_range(start + bytes.len());
+
+            return Some(&self.seams[index][range.start - seam.start..range.end - seam.start]);
+        }
+
+        None
+    }
+
+    /// Get `range` as a contiguous slice of bytes. The range must be in bounds.
+    fn contiguous(&self, range: Range<usize>) -> &[u8] {
+        if let Some(bytes) = self.borrowed(range.clone()) {
+            return bytes;
+        }
+
+        let mut spilled = self
+            .spilled
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        spilled.push(self.copy(range));
+
+        let ptr: *const [u8] = &*spilled[spilled.len() - 1];
+
+        // SAFETY: The boxed copy is never dropped or mutated while `self` is alive, and growing the vector only moves
+        // the box, not the data that it points to.
+        unsafe { &*ptr }
+    }
+}
+
+impl<'source, S: Segment + ToOwned + ?Sized> Segmented<'source, S> {
+    /// Get `range` as a [SliceView], returning `None` if it's out of bounds or not on boundaries.
+    ///
+    /// Unlike [Source::slice], which keeps a copy of every long range that straddles segments for as long as the source
+    /// is alive, this only copies those ranges into the view that's returned.
+    ///
+    /// ```
+    /// use logos::segmented::{Segmented, SliceView};
+    ///
+    /// let source = Segmented::new(["foo", "bar"]);
+    ///
+    /// assert_eq!(source.view(1..5), Some(SliceView::Borrowed("ooba")));
+    /// ```
+    pub fn view(&self, range: Range<usize>) -> Option<SliceView<'_, S>> {
+        if range.start > range.end || !self.is_boundary(range.start) || !self.is_boundary(range.end)
+        {
+            return None;
+        }
+
+        // SAFETY: The range is on boundaries, so the bytes are a valid `S`.
+        let view = match self.borrowed(range.clone()) {
+            Some(bytes) => SliceView::Borrowed(unsafe { S::from_bytes_unchecked(bytes) }),
+            None => {
+                SliceView::Owned(unsafe { S::from_bytes_unchecked(&self.copy(range)) }.to_owned())
+            }
+        };
+
+        Some(view)
+    }
+}
+
+impl<'source, S: Segment + ?Sized> FromIterator<&'source S> for Segmented<'source, S> {
+    fn from_iter<I: IntoIterator<Item = &'source S>>(segments: I) -> Self {
+        Segmented::new(segments)
+    }
+}
+
+impl<'source, S: Segment + ?Sized> From<&[&'source S]> for Segmented<'source, S> {
+    fn from(segments: &[&'source S]) -> Self {
+        Segmented::new(segments.iter().copied())
+    }
+}
+
+impl<'source, S: Segment + ?Sized> Debug for Segmented<'source, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Segmented").field(&self.segments).finish()
+    }
+}
+
+impl<'source, S: Segment + ?Sized> Source for Segmented<'source, S> {
+    type Slice = S;
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    #[inline]
+    fn read<'a, C>(&'a self, offset: usize) -> Option<C>
+    where
+        C: Chunk<'a>,
+    {
+        if offset + C::SIZE <= self.len {
+            Some(unsafe { self.read_unchecked(offset) })
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    unsafe fn read_unchecked<'a, C>(&'a self, offset: usize) -> C
+    where
+        C: Chunk<'a>,
+    {
+        C::from_ptr(self.contiguous(offset..offset + C::SIZE).as_ptr())
+    }
+
+    #[inline]
+    fn slice(&self, range: Range<usize>) -> Option<&S> {
+        if range.start <= range.end && self.is_boundary(range.start) && self.is_boundary(range.end)
+        {
+            Some(unsafe { self.slice_unchecked(range) })
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    unsafe fn slice_unchecked(&self, range: Range<usize>) -> &S {
+        debug_assert!(
+            range.start <= self.len && range.end <= self.len,
+            "Reading out of bounds {:?} for {}!",
+            range,
+            self.len
+        );
+
+        S::from_bytes_unchecked(self.contiguous(range))
+    }
+
+    #[inline]
+    fn find_boundary(&self, mut index: usize) -> usize {
+        while !self.is_boundary(index) {
+            index += 1;
+        }
+
+        index
+    }
+
+    #[inline]
+    fn is_boundary(&self, index: usize) -> bool {
+        if index >= self.len {
+            return index == self.len;
+        }
+
+        let segment = self.segment_at(index);
+
+        self.segments[segment].is_boundary(index - self.starts[segment])
+    }
+}
+
+impl<'source, S, Token> Lexer<'source, Token>
+where
+    S: Segment + ToOwned + ?Sized,
+    Token: Logos<'source, Source = Segmented<'source, S>>,
+{
+    /// A [SliceView] of the current token. This is like [Lexer::slice], except that tokens which straddle segments
+    /// (and are too long to borrow from the source) aren't kept around by the source after they've been dropped.
+    #[inline]
+    pub fn slice_view(&self) -> SliceView<'source, S> {
+        let source: &'source Segmented<'source, S> = self.source;
+
+        // Tokens always start and end on boundaries.
+        source
+            .view(self.token_start..self.token_end)
+            .unwrap_or_else(|| unreachable!("the token is not on boundaries"))
+    }
+}
+
+impl<'source, S, Token> FromSlice<'source, Token> for SliceView<'source, S>
+where
+    S: Segment + ToOwned + ?Sized,
+    Token: Logos<'source, Source = Segmented<'source, S>>,
+{
+    #[inline]
+    fn from_slice(lex: &Lexer<'source, Token>) -> Self {
+        lex.slice_view()
+    }
+}
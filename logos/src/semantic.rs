@@ -0,0 +1,300 @@
+//! Encoding tokens for the semantic highlighting of a language server.
+//!
+//! The [Language Server Protocol](https://microsoft.github.io/language-server-protocol/) sends semantic tokens to the
+//! editor as a flat array of integers, five for each token: the line it's on relative to the previous token, its start
+//! (relative to the previous token if they're on the same line), its length, its type, and a bit set of its modifiers.
+//! Lines and columns are counted in UTF-16 code units by default, and tokens can't span lines unless the client says
+//! that it supports them.
+//!
+//! [encode] does all of that for a stream of spanned tokens, given a function that picks the [Semantic] kind of each
+//! token. Tokens that the function returns `None` for aren't highlighted. The types and modifiers are indices into the
+//! legend that the server sent in its capabilities, so they're plain numbers here.
+//!
+//! ```
+//! use logos::Logos;
+//! use logos::semantic::{encode, Semantic};
+//!
+//! #[derive(Logos, Debug, PartialEq)]
+//! enum Token {
+//!     #[regex(r"[ \t\n]+", logos::skip)]
+//!     Whitespace,
+//!
+//!     #[token("let")]
+//!     Let,
+//!
+//!     #[regex("[a-z]+")]
+//!     Ident,
+//!
+//!     #[token("=")]
+//!     Equals,
+//! }
+//!
+//! // The legend's token types are ["keyword", "variable"].
+//! const KEYWORD: u32 = 0;
+//! const VARIABLE: u32 = 1;
+//!
+//! let source = "let x = y\nlet z";
+//! let tokens = Token::lexer(source).spanned().filter_map(Result::ok);
+//! let data = encode(source, tokens, |token| match token {
+//!     Token::Let => Some(Semantic::new(KEYWORD)),
+//!     Token::Ident => Some(Semantic::new(VARIABLE)),
+//!     Token::Equals | Token::Whitespace => None,
+//! });
+//!
+//! assert_eq!(
+//!     data,
+//!     &[
+//!         0, 0, 3, KEYWORD, 0,
+//!         0, 4, 1, VARIABLE, 0,
+//!         0, 4, 1, VARIABLE, 0,
+//!         1, 0, 3, KEYWORD, 0,
+//!         0, 4, 1, VARIABLE, 0,
+//!     ],
+//! );
+//! ```
+//!
+//! An [Encoder] gives more control, over the position encoding, or over which tokens get highlighted based on the tokens
+//! around them.
+
+use crate::Span;
+use alloc::vec::Vec;
+
+/// How the semantic kind of a token is reported to the editor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Semantic {
+    /// The index of the token's type in the legend.
+    pub token_type: u32,
+
+    /// The token's modifiers, as a bit set of indices in the legend.
+    pub modifiers: u32,
+}
+
+impl Semantic {
+    /// A semantic kind with a type and no modifiers.
+    #[inline]
+    pub fn new(token_type: u32) -> Self {
+        Semantic {
+            token_type,
+            modifiers: 0,
+        }
+    }
+
+    /// Add the modifiers in the bit set `modifiers`.
+    #[inline]
+    pub fn with_modifiers(mut self, modifiers: u32) -> Self {
+        self.modifiers |= modifiers;
+        self
+    }
+}
+
+/// The units that lines are measured in, as agreed on with the client using the `positionEncoding` capability.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum PositionEncoding {
+    /// Bytes of UTF-8, `"utf-8"` in the protocol.
+    Utf8,
+
+    /// UTF-16 code units, which every client supports, and the default.
+    #[default]
+    Utf16,
+
+    /// Unicode scalar values (`char`s), `"utf-32"` in the protocol.
+    Utf32,
+}
+
+impl PositionEncoding {
+    fn len(self, text: &str) -> u32 {
+        let len = match self {
+            PositionEncoding::Utf8 => text.len(),
+            PositionEncoding::Utf16 => text.chars().map(char::len_utf16).sum(),
+            PositionEncoding::Utf32 => text.chars().count(),
+        };
+
+        len as u32
+    }
+}
+
+/// Encode the spanned `tokens` from `source` into LSP semantic tokens, highlighting the ones that `semantic` returns a
+/// kind for. Positions are counted in UTF-16 code units, and tokens that span lines are split into one token per line.
+///
+/// See the [module documentation](self) for an example.
+///
+/// # Panics
+///
+/// Panics if the tokens aren't in order, or overlap.
+pub fn encode<T, I, F>(source: &str, tokens: I, mut semantic: F) -> Vec<u32>
+where
+    I: IntoIterator<Item = (T, Span)>,
+    F: FnMut(&T) -> Option<Semantic>,
+{
+    let mut encoder = Encoder::new(source);
+
+    for (token, span) in tokens {
+        if let Some(semantic) = semantic(&token) {
+            encoder.push(span, semantic);
+        }
+    }
+
+    encoder.finish()
+}
+
+/// Encodes tokens into LSP semantic tokens one at a time, keeping track of where the previous token was.
+///
+/// ```
+/// use logos::semantic::{Encoder, PositionEncoding, Semantic};
+///
+/// let source = "é = \"a\nb\"";
+/// let mut encoder = Encoder::new(source).position_encoding(PositionEncoding::Utf8);
+///
+/// encoder.push(0..2, Semantic::new(0));
+/// // A string that spans two lines is split in two, unless multiline tokens are enabled.
+/// encoder.push(5..10, Semantic::new(1).with_modifiers(0b10));
+///
+/// assert_eq!(encoder.finish(), &[0, 0, 2, 0, 0, 0, 5, 2, 1, 2, 1, 0, 2, 1, 2]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Encoder<'source> {
+    source: &'source str,
+    encoding: PositionEncoding,
+    multiline: bool,
+
+    /// How far into the source lines have been counted, the line there, and where that line starts.
+    offset: usize,
+    line: u32,
+    line_start: usize,
+
+    /// The line and column of the previous token, and where it ends, so columns on the same line are only measured
+    /// from there.
+    prev_line: u32,
+    prev_column: u32,
+    prev_end: usize,
+    prev_end_column: u32,
+
+    data: Vec<u32>,
+}
+
+impl<'source> Encoder<'source> {
+    /// Create an encoder for tokens from `source`, counting positions in UTF-16 code units.
+    pub fn new(source: &'source str) -> Self {
+        Encoder {
+            source,
+            encoding: PositionEncoding::Utf16,
+            multiline: false,
+            offset: 0,
+            line: 0,
+            line_start: 0,
+            prev_line: 0,
+            prev_column: 0,
+            prev_end: 0,
+            prev_end_column: 0,
+            data: Vec::new(),
+        }
+    }
+
+    /// Count positions in the units of `encoding`.
+    pub fn position_encoding(mut self, encoding: PositionEncoding) -> Self {
+        self.encoding = encoding;
+        self
+    }
+
+    /// Keep tokens that span lines in one piece, for clients with the `multilineTokenSupport` capability. Their length
+    /// includes the line breaks then.
+    pub fn multiline(mut self, multiline: bool) -> Self {
+        self.multiline = multiline;
+        self
+    }
+
+    /// Add the token at `span`, which has to come after every token pushed so far.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `span` starts before the end of the previous token, or isn't a valid range of the source.
+    pub fn push(&mut self, span: Span, semantic: Semantic) {
+        assert!(
+            span.start >= self.offset && span.end <= self.source.len() && span.start <= span.end,
+            "the span {:?} isn't in the source after the previous token",
+            span,
+        );
+
+        if self.multiline {
+            self.advance(span.start);
+            self.emit(span.start, span.end, semantic);
+            self.advance(span.end);
+
+            return;
+        }
+
+        let mut start = span.start;
+
+        self.advance(start);
+
+        for (at, byte) in self.source.as_bytes()[span.clone()].iter().enumerate() {
+            let at = span.start + at;
+
+            if *byte == b'\n' || *byte == b'\r' {
+                self.emit(start, at, semantic);
+                self.advance(at + 1);
+
+                start = self.offset;
+            }
+        }
+
+        self.emit(start, span.end, semantic);
+        self.advance(span.end);
+    }
+
+    /// Return the encoded tokens.
+    pub fn finish(self) -> Vec<u32> {
+        self.data
+    }
+
+    /// Count the lines up to `offset`.
+    fn advance(&mut self, offset: usize) {
+        let bytes = self.source.as_bytes();
+
+        while self.offset < offset {
+            let at = self.offset;
+
+            self.offset += 1;
+
+            match bytes[at] {
+                b'\n' => (),
+                // A lone `\r` is a line break too, but `\r\n` is only one.
+                b'\r' if bytes.get(at + 1) != Some(&b'\n') => (),
+                _ => continue,
+            }
+
+            self.line += 1;
+            self.line_start = self.offset;
+        }
+    }
+
+    /// Add the part of a token from `start` to `end`, which are both on the current line, unless it's empty.
+    fn emit(&mut self, start: usize, end: usize, semantic: Semantic) {
+        if start >= end {
+            return;
+        }
+
+        let delta_line = self.line - self.prev_line;
+        let column = match delta_line {
+            0 => self.prev_end_column + self.encoding.len(&self.source[self.prev_end..start]),
+            _ => self.encoding.len(&self.source[self.line_start..start]),
+        };
+        let length = self.encoding.len(&self.source[start..end]);
+        let delta_column = match delta_line {
+            0 => column - self.prev_column,
+            _ => column,
+        };
+
+        self.data.extend([
+            delta_line,
+            delta_column,
+            length,
+            semantic.token_type,
+            semantic.modifiers,
+        ]);
+        self.prev_line = self.line;
+        self.prev_column = column;
+        self.prev_end = end;
+        self.prev_end_column = column + length;
+    }
+}
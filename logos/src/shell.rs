@@ -0,0 +1,165 @@
+//! A ready-made lexer for splitting a command line into words, the way a POSIX shell does.
+//!
+//! Plenty of tools take a command line in a config file or an environment variable (like `EDITOR="code --wait"`), and
+//! need to split it into arguments before running it. Splitting on whitespace breaks as soon as an argument has a space
+//! in it, so [split] follows the shell's quoting rules instead:
+//!
+//! - Words are separated by spaces, tabs and line breaks.
+//! - A backslash keeps the character after it as it is, and a backslash before a line break joins the two lines.
+//! - Everything between single quotes is kept as it is, backslashes included.
+//! - Between double quotes, a backslash only escapes `$`, `` ` ``, `"`, `\` and line breaks, and is kept before
+//!   anything else.
+//! - Quoted and unquoted parts next to each other make up one word, so `--name="Jane Doe"` is one argument.
+//!
+//! There are no variables, globs or other expansions: `$HOME` and `*.rs` are left as they are.
+//!
+//! ```
+//! use logos::shell::split;
+//!
+//! assert_eq!(
+//!     split(r#"grep -e 'fn main' --include="*.rs" src\ dir"#).unwrap(),
+//!     &["grep", "-e", "fn main", "--include=*.rs", "src dir"],
+//! );
+//! ```
+//!
+//! Words that don't need any unescaping borrow from the command line. The [Piece] lexer that [split] is built on can be
+//! used on its own too, to highlight a command line say.
+//!
+//! This module needs the `export_derive` feature.
+
+use crate::{Lexer, Logos, Span};
+
+use alloc::borrow::Cow;
+use alloc::string::String;
+use alloc::vec::Vec;
+use std::fmt::{self, Display, Formatter};
+
+/// The parts that words on a command line are made of.
+#[derive(Logos, Debug, Clone, PartialEq)]
+pub enum Piece<'s> {
+    /// Whitespace between words.
+    #[regex(r"[ \t\n]+")]
+    Space,
+
+    /// Characters without any special meaning.
+    #[regex(r#"[^ \t\n'"\\]+"#)]
+    Bare(&'s str),
+
+    /// A character escaped with a backslash, given without the backslash.
+    #[regex(r"\\.", |lex| &lex.slice()[1..])]
+    Escaped(&'s str),
+
+    /// A backslash before a line break, which joins the lines.
+    #[token("\\\n")]
+    Continuation,
+
+    /// Text between single quotes, given without the quotes.
+    #[regex("'[^']*'", |lex| &lex.slice()[1..lex.slice().len() - 1])]
+    SingleQuoted(&'s str),
+
+    /// Text between double quotes, given without the quotes and with its escapes resolved.
+    #[regex(r#""([^"\\]|\\(.|\n))*""#, double_quoted)]
+    DoubleQuoted(Cow<'s, str>),
+}
+
+fn double_quoted<'s>(lex: &mut Lexer<'s, Piece<'s>>) -> Cow<'s, str> {
+    let text = &lex.slice()[1..lex.slice().len() - 1];
+
+    if !text.contains('\\') {
+        return Cow::Borrowed(text);
+    }
+
+    let mut value = String::with_capacity(text.len());
+    let mut chars = text.chars();
+
+    while let Some(ch) = chars.next() {
+        if ch != '\\' {
+            value.push(ch);
+            continue;
+        }
+
+        // The regex makes sure that a backslash is always followed by something.
+        match chars.next() {
+            Some('\n') => (),
+            Some(ch @ ('$' | '`' | '"' | '\\')) => value.push(ch),
+            Some(ch) => {
+                value.push('\\');
+                value.push(ch);
+            }
+            None => value.push('\\'),
+        }
+    }
+
+    Cow::Owned(value)
+}
+
+/// The reasons that a command line can't be split.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum SplitError {
+    /// A quote was opened at this span, and never closed.
+    UnterminatedQuote(Span),
+
+    /// The command line ends with a backslash, which has nothing to escape.
+    TrailingBackslash(Span),
+}
+
+impl SplitError {
+    /// Where the problem is in the command line.
+    pub fn span(&self) -> Span {
+        match self {
+            SplitError::UnterminatedQuote(span) | SplitError::TrailingBackslash(span) => {
+                span.clone()
+            }
+        }
+    }
+}
+
+impl Display for SplitError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            SplitError::UnterminatedQuote(span) => write!(f, "unterminated quote at {:?}", span),
+            SplitError::TrailingBackslash(span) => write!(f, "trailing backslash at {:?}", span),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for SplitError {}
+
+/// Split `line` into words, following the quoting rules in the [module documentation](self).
+pub fn split(line: &str) -> Result<Vec<Cow<'_, str>>, SplitError> {
+    let mut lexer = Piece::lexer(line);
+    let mut words = Vec::new();
+    let mut word: Option<Cow<str>> = None;
+
+    while let Some(piece) = lexer.next() {
+        let text = match piece {
+            Ok(Piece::Space) => {
+                words.extend(word.take());
+                continue;
+            }
+            Ok(Piece::Continuation) => continue,
+            Ok(Piece::Bare(text) | Piece::Escaped(text) | Piece::SingleQuoted(text)) => {
+                Cow::Borrowed(text)
+            }
+            Ok(Piece::DoubleQuoted(text)) => text,
+            Err(_) if lexer.slice().starts_with('\\') => {
+                return Err(SplitError::TrailingBackslash(lexer.span()));
+            }
+            Err(_) => {
+                let start = lexer.span().start;
+
+                return Err(SplitError::UnterminatedQuote(start..start + 1));
+            }
+        };
+
+        word = Some(match word.take() {
+            Some(word) => Cow::Owned(word.into_owned() + &text),
+            None => text,
+        });
+    }
+
+    words.extend(word);
+
+    Ok(words)
+}
@@ -0,0 +1,740 @@
+//! Storing lexed tokens in columns, rather than as a list of `(Token, Span)` pairs.
+//!
+//! A `Vec<(Token, Span)>` stores each token right next to its span, so a parser that only looks at the *kinds* of
+//! tokens (which is most of the time) still has to pull every span and payload through the cache. For large inputs,
+//! it's much friendlier to keep each part in its own dense array. [TokenSoA] (a "struct of arrays") does exactly that:
+//! token kinds, span starts, and span lengths are each stored contiguously, and the payloads of the few tokens that
+//! carry data are kept in a separate side table.
+//!
+//! Separating a token into its kind and payload is up to the [Split] trait, which you implement for your token type.
+//! A `TokenSoA` can then be collected from any iterator of `(Token, Span)` pairs, such as [Lexer::spanned]:
+//!
+//! ```
+//! use logos::Logos;
+//! use logos::soa::{Split, TokenSoA};
+//!
+//! #[derive(Logos, Debug, PartialEq)]
+//! enum Token<'a> {
+//!     #[regex(r"[ \t\n]+", logos::skip)]
+//!     Whitespace,
+//!
+//!     #[token("=")]
+//!     Equals,
+//!
+//!     #[regex("[a-z]+")]
+//!     Ident(&'a str),
+//! }
+//!
+//! #[derive(Debug, Clone, Copy, PartialEq)]
+//! enum Kind {
+//!     Equals,
+//!     Ident,
+//! }
+//!
+//! impl<'a> Split for Token<'a> {
+//!     type Kind = Kind;
+//!     type Payload = &'a str;
+//!
+//!     fn split(self) -> (Kind, Option<&'a str>) {
+//!         match self {
+//!             Token::Whitespace => unreachable!(),
+//!             Token::Equals => (Kind::Equals, None),
+//!             Token::Ident(ident) => (Kind::Ident, Some(ident)),
+//!         }
+//!     }
+//! }
+//!
+//! let tokens: TokenSoA<Token> = Token::lexer("x = y")
+//!     .spanned()
+//!     .collect::<Result<_, _>>()
+//!     .unwrap();
+//!
+//! assert_eq!(tokens.kinds(), &[Kind::Ident, Kind::Equals, Kind::Ident]);
+//! assert_eq!(tokens.starts(), &[0, 2, 4]);
+//! assert_eq!(tokens.lens(), &[1, 1, 1]);
+//! assert_eq!(tokens.payload(2), Some(&"y"));
+//! assert_eq!(tokens.get(1), Some((Kind::Equals, 2..3, None)));
+//! ```
+//!
+//! When there are so many tokens that even their spans take too much memory, [DeltaTokens] stores the spans as a
+//! stream of [delta-encoded](DeltaSpans) offsets instead, which usually takes two bytes per span. They're decoded back
+//! into [Span]s when they're iterated over.
+//!
+//! [Lexer::spanned]: crate::Lexer::spanned
+
+use crate::varint;
+use crate::Span;
+
+use alloc::vec::Vec;
+use std::fmt::{self, Debug};
+
+/// Tokens that can be separated into a kind and an optional payload, so that they can be stored in a [TokenSoA].
+///
+/// See the [module documentation][self] for an example.
+pub trait Split {
+    /// The kind of a token, without any data. This is usually a field-less enum with one variant per token.
+    type Kind: Copy;
+
+    /// The data carried by tokens that have any.
+    type Payload;
+
+    /// Separate the token into its kind and payload.
+    fn split(self) -> (Self::Kind, Option<Self::Payload>);
+}
+
+/// The kind, span, and payload of a token in a [TokenSoA].
+pub type Entry<'soa, Token> = (
+    <Token as Split>::Kind,
+    Span,
+    Option<&'soa <Token as Split>::Payload>,
+);
+
+/// A list of tokens, stored as separate arrays of kinds, span starts, and span lengths.
+///
+/// Payloads are kept in a side table, sorted by the index of the token that they belong to. See the
+/// [module documentation][self] for more details.
+pub struct TokenSoA<Token: Split> {
+    kinds: Vec<Token::Kind>,
+    starts: Vec<usize>,
+    lens: Vec<usize>,
+    payloads: Vec<(usize, Token::Payload)>,
+}
+
+impl<Token: Split> TokenSoA<Token> {
+    /// Create an empty `TokenSoA`.
+    pub fn new() -> Self {
+        TokenSoA {
+            kinds: Vec::new(),
+            starts: Vec::new(),
+            lens: Vec::new(),
+            payloads: Vec::new(),
+        }
+    }
+
+    /// Create an empty `TokenSoA` with space for at least `capacity` tokens, without reallocating.
+    ///
+    /// No space is reserved for payloads, since it's unknown how many tokens will carry one.
+    pub fn with_capacity(capacity: usize) -> Self {
+        TokenSoA {
+            kinds: Vec::with_capacity(capacity),
+            starts: Vec::with_capacity(capacity),
+            lens: Vec::with_capacity(capacity),
+            payloads: Vec::new(),
+        }
+    }
+
+    /// Add a token to the end of the list.
+    pub fn push(&mut self, token: Token, span: Span) {
+        let (kind, payload) = token.split();
+
+        if let Some(payload) = payload {
+            self.payloads.push((self.kinds.len(), payload));
+        }
+
+        self.kinds.push(kind);
+        self.starts.push(span.start);
+        self.lens.push(span.end - span.start);
+    }
+
+    /// The number of tokens in the list.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.kinds.len()
+    }
+
+    /// Returns `true` if the list contains no tokens.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.kinds.is_empty()
+    }
+
+    /// The kind of every token, in order.
+    #[inline]
+    pub fn kinds(&self) -> &[Token::Kind] {
+        &self.kinds
+    }
+
+    /// The start of every token's span, in order.
+    #[inline]
+    pub fn starts(&self) -> &[usize] {
+        &self.starts
+    }
+
+    /// The length of every token's span, in order.
+    #[inline]
+    pub fn lens(&self) -> &[usize] {
+        &self.lens
+    }
+
+    /// The payloads of all tokens that have one, each paired with the index of its token.
+    #[inline]
+    pub fn payloads(&self) -> &[(usize, Token::Payload)] {
+        &self.payloads
+    }
+
+    /// The kind of the token at `index`, or `None` if it's out of bounds.
+    #[inline]
+    pub fn kind(&self, index: usize) -> Option<Token::Kind> {
+        self.kinds.get(index).copied()
+    }
+
+    /// The span of the token at `index`, or `None` if it's out of bounds.
+    #[inline]
+    pub fn span(&self, index: usize) -> Option<Span> {
+        let start = *self.starts.get(index)?;
+
+        Some(start..start + self.lens[index])
+    }
+
+    /// The payload of the token at `index`, or `None` if it doesn't have one.
+    ///
+    /// This is a binary search through the payload table, so it takes `O(log n)` time in the number of payloads.
+    pub fn payload(&self, index: usize) -> Option<&Token::Payload> {
+        let at = self
+            .payloads
+            .binary_search_by_key(&index, |&(index, _)| index)
+            .ok()?;
+
+        Some(&self.payloads[at].1)
+    }
+
+    /// The kind, span, and payload of the token at `index`, or `None` if it's out of bounds.
+    pub fn get(&self, index: usize) -> Option<Entry<'_, Token>> {
+        Some((self.kind(index)?, self.span(index)?, self.payload(index)))
+    }
+
+    /// Iterate over the kind, span, and payload of every token, in order.
+    pub fn iter(&self) -> Iter<'_, Token> {
+        Iter {
+            soa: self,
+            index: 0,
+            payload: 0,
+        }
+    }
+
+    /// Remove all tokens from the list, keeping the allocated memory.
+    pub fn clear(&mut self) {
+        self.kinds.clear();
+        self.starts.clear();
+        self.lens.clear();
+        self.payloads.clear();
+    }
+}
+
+impl<Token: Split> Default for TokenSoA<Token> {
+    fn default() -> Self {
+        TokenSoA::new()
+    }
+}
+
+impl<Token> Clone for TokenSoA<Token>
+where
+    Token: Split,
+    Token::Payload: Clone,
+{
+    fn clone(&self) -> Self {
+        TokenSoA {
+            kinds: self.kinds.clone(),
+            starts: self.starts.clone(),
+            lens: self.lens.clone(),
+            payloads: self.payloads.clone(),
+        }
+    }
+}
+
+impl<Token> Debug for TokenSoA<Token>
+where
+    Token: Split,
+    Token::Kind: Debug,
+    Token::Payload: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<Token: Split> Extend<(Token, Span)> for TokenSoA<Token> {
+    fn extend<I: IntoIterator<Item = (Token, Span)>>(&mut self, tokens: I) {
+        let tokens = tokens.into_iter();
+        let (additional, _) = tokens.size_hint();
+
+        self.kinds.reserve(additional);
+        self.starts.reserve(additional);
+        self.lens.reserve(additional);
+
+        for (token, span) in tokens {
+            self.push(token, span);
+        }
+    }
+}
+
+impl<Token: Split> FromIterator<(Token, Span)> for TokenSoA<Token> {
+    fn from_iter<I: IntoIterator<Item = (Token, Span)>>(tokens: I) -> Self {
+        let mut soa = TokenSoA::new();
+
+        soa.extend(tokens);
+        soa
+    }
+}
+
+impl<'soa, Token: Split> IntoIterator for &'soa TokenSoA<Token> {
+    type Item = Entry<'soa, Token>;
+    type IntoIter = Iter<'soa, Token>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// An iterator over the tokens in a [TokenSoA].
+///
+/// This struct is created by the [TokenSoA::iter] method.
+pub struct Iter<'soa, Token: Split> {
+    soa: &'soa TokenSoA<Token>,
+    index: usize,
+
+    /// The index of the next payload that hasn't been produced yet.
+    payload: usize,
+}
+
+impl<'soa, Token: Split> Iterator for Iter<'soa, Token> {
+    type Item = Entry<'soa, Token>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let index = self.index;
+        let kind = self.soa.kind(index)?;
+        let span = self.soa.span(index)?;
+
+        // Payloads are in token order, so there's no need to search for them.
+        let payload = match self.soa.payloads.get(self.payload) {
+            Some((owner, payload)) if *owner == index => {
+                self.payload += 1;
+                Some(payload)
+            }
+            _ => None,
+        };
+
+        self.index += 1;
+        Some((kind, span, payload))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.soa.len() - self.index;
+
+        (remaining, Some(remaining))
+    }
+}
+
+impl<'soa, Token: Split> ExactSizeIterator for Iter<'soa, Token> {}
+
+/// How many spans there are between two entries of a [DeltaSpans] index.
+const INDEX_STRIDE: usize = 64;
+
+/// A list of spans, delta-encoded into a stream of bytes.
+///
+/// Each span is stored as the distance of its start from the end of the previous span, followed by its length, both
+/// as variable-length integers. Tokens are usually short and close together, so most spans take two bytes instead of
+/// the sixteen that a [Span] takes on 64-bit targets. Spans are turned back into [Span]s as they're iterated over.
+///
+/// Every 64th span is indexed, so that [DeltaSpans::get] only has to decode a few spans to find any of them.
+#[derive(Clone, Default, PartialEq, Eq)]
+pub struct DeltaSpans {
+    bytes: Vec<u8>,
+    len: usize,
+    end: usize,
+
+    /// The position in `bytes` of every 64th span, along with the end of the span before it.
+    index: Vec<(usize, usize)>,
+}
+
+impl DeltaSpans {
+    /// Create an empty `DeltaSpans`.
+    pub fn new() -> Self {
+        DeltaSpans::default()
+    }
+
+    /// Add a span to the end of the list. Spans don't have to be in order, although they take the least space when
+    /// each one starts soon after the previous one ends.
+    pub fn push(&mut self, span: Span) {
+        if self.len == self.index.len() * INDEX_STRIDE {
+            self.index.push((self.bytes.len(), self.end));
+        }
+
+        let delta = span.start.wrapping_sub(self.end) as isize;
+
+        // Zigzag encoding, so that small negative distances are small too.
+        varint::write(
+            &mut self.bytes,
+            ((delta << 1) ^ (delta >> (isize::BITS - 1))) as usize as u64,
+        );
+        varint::write(&mut self.bytes, (span.end - span.start) as u64);
+
+        self.len += 1;
+        self.end = span.end;
+    }
+
+    /// The number of spans in the list.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the list contains no spans.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The encoded spans, which take `byte_len` bytes (plus a little for the index).
+    #[inline]
+    pub fn byte_len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    /// The span at `index`, or `None` if it's out of bounds.
+    pub fn get(&self, index: usize) -> Option<Span> {
+        if index >= self.len {
+            return None;
+        }
+
+        let (at, end) = self.index[index / INDEX_STRIDE];
+        let mut spans = SpanIter {
+            bytes: &self.bytes[at..],
+            end,
+            remaining: self.len - index / INDEX_STRIDE * INDEX_STRIDE,
+        };
+
+        spans.nth(index % INDEX_STRIDE)
+    }
+
+    /// Iterate over the spans, in order.
+    pub fn iter(&self) -> SpanIter<'_> {
+        SpanIter {
+            bytes: &self.bytes,
+            end: 0,
+            remaining: self.len,
+        }
+    }
+
+    /// Remove all spans from the list, keeping the allocated memory.
+    pub fn clear(&mut self) {
+        self.bytes.clear();
+        self.index.clear();
+        self.len = 0;
+        self.end = 0;
+    }
+}
+
+impl Debug for DeltaSpans {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl Extend<Span> for DeltaSpans {
+    fn extend<I: IntoIterator<Item = Span>>(&mut self, spans: I) {
+        for span in spans {
+            self.push(span);
+        }
+    }
+}
+
+impl FromIterator<Span> for DeltaSpans {
+    fn from_iter<I: IntoIterator<Item = Span>>(spans: I) -> Self {
+        let mut delta = DeltaSpans::new();
+
+        delta.extend(spans);
+        delta
+    }
+}
+
+impl<'spans> IntoIterator for &'spans DeltaSpans {
+    type Item = Span;
+    type IntoIter = SpanIter<'spans>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// An iterator decoding the spans in a [DeltaSpans].
+///
+/// This struct is created by the [DeltaSpans::iter] method.
+#[derive(Clone)]
+pub struct SpanIter<'spans> {
+    bytes: &'spans [u8],
+    end: usize,
+    remaining: usize,
+}
+
+impl<'spans> Iterator for SpanIter<'spans> {
+    type Item = Span;
+
+    fn next(&mut self) -> Option<Span> {
+        if self.remaining == 0 {
+            return None;
+        }
+
+        // The bytes were written by `push`, so they always hold whole integers.
+        let zigzag = varint::read(&mut self.bytes).unwrap_or_default() as usize;
+        let delta = ((zigzag >> 1) as isize) ^ -((zigzag & 1) as isize);
+        let start = self.end.wrapping_add(delta as usize);
+        let end = start + varint::read(&mut self.bytes).unwrap_or_default() as usize;
+
+        self.remaining -= 1;
+        self.end = end;
+
+        Some(start..end)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'spans> ExactSizeIterator for SpanIter<'spans> {}
+
+/// A list of tokens, stored as an array of kinds and a stream of delta-encoded spans.
+///
+/// This is a smaller alternative to [TokenSoA] for keeping a very large number of tokens around, at the cost of
+/// decoding the spans again when they're needed. Like in a `TokenSoA`, payloads are kept in a side table, sorted by
+/// the index of the token that they belong to.
+///
+/// ```
+/// use logos::Logos;
+/// use logos::soa::{DeltaTokens, Split};
+///
+/// #[derive(Logos, Debug, Clone, Copy, PartialEq)]
+/// enum Token {
+///     #[regex(r"[ \t\n]+", logos::skip)]
+///     Whitespace,
+///
+///     #[token("=")]
+///     Equals,
+///
+///     #[regex("[a-z]+")]
+///     Ident,
+/// }
+///
+/// impl Split for Token {
+///     type Kind = Token;
+///     type Payload = ();
+///
+///     fn split(self) -> (Token, Option<()>) {
+///         (self, None)
+///     }
+/// }
+///
+/// let tokens: DeltaTokens<Token> = Token::lexer("x = yz")
+///     .spanned()
+///     .collect::<Result<_, _>>()
+///     .unwrap();
+///
+/// assert_eq!(tokens.kinds(), &[Token::Ident, Token::Equals, Token::Ident]);
+/// assert_eq!(tokens.spans().iter().collect::<Vec<_>>(), &[0..1, 2..3, 4..6]);
+/// assert_eq!(tokens.spans().byte_len(), 6);
+/// assert_eq!(tokens.get(2), Some((Token::Ident, 4..6, None)));
+/// ```
+pub struct DeltaTokens<Token: Split> {
+    kinds: Vec<Token::Kind>,
+    spans: DeltaSpans,
+    payloads: Vec<(usize, Token::Payload)>,
+}
+
+impl<Token: Split> DeltaTokens<Token> {
+    /// Create an empty `DeltaTokens`.
+    pub fn new() -> Self {
+        DeltaTokens {
+            kinds: Vec::new(),
+            spans: DeltaSpans::new(),
+            payloads: Vec::new(),
+        }
+    }
+
+    /// Add a token to the end of the list.
+    pub fn push(&mut self, token: Token, span: Span) {
+        let (kind, payload) = token.split();
+
+        if let Some(payload) = payload {
+            self.payloads.push((self.kinds.len(), payload));
+        }
+
+        self.kinds.push(kind);
+        self.spans.push(span);
+    }
+
+    /// The number of tokens in the list.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.kinds.len()
+    }
+
+    /// Returns `true` if the list contains no tokens.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.kinds.is_empty()
+    }
+
+    /// The kind of every token, in order.
+    #[inline]
+    pub fn kinds(&self) -> &[Token::Kind] {
+        &self.kinds
+    }
+
+    /// The span of every token, in order.
+    #[inline]
+    pub fn spans(&self) -> &DeltaSpans {
+        &self.spans
+    }
+
+    /// The payloads of all tokens that have one, each paired with the index of its token.
+    #[inline]
+    pub fn payloads(&self) -> &[(usize, Token::Payload)] {
+        &self.payloads
+    }
+
+    /// The kind of the token at `index`, or `None` if it's out of bounds.
+    #[inline]
+    pub fn kind(&self, index: usize) -> Option<Token::Kind> {
+        self.kinds.get(index).copied()
+    }
+
+    /// The span of the token at `index`, or `None` if it's out of bounds. See [DeltaSpans::get].
+    #[inline]
+    pub fn span(&self, index: usize) -> Option<Span> {
+        self.spans.get(index)
+    }
+
+    /// The payload of the token at `index`, or `None` if it doesn't have one.
+    ///
+    /// This is a binary search through the payload table, so it takes `O(log n)` time in the number of payloads.
+    pub fn payload(&self, index: usize) -> Option<&Token::Payload> {
+        let at = self
+            .payloads
+            .binary_search_by_key(&index, |&(index, _)| index)
+            .ok()?;
+
+        Some(&self.payloads[at].1)
+    }
+
+    /// The kind, span, and payload of the token at `index`, or `None` if it's out of bounds.
+    pub fn get(&self, index: usize) -> Option<Entry<'_, Token>> {
+        Some((self.kind(index)?, self.span(index)?, self.payload(index)))
+    }
+
+    /// Iterate over the kind, span, and payload of every token, in order.
+    pub fn iter(&self) -> DeltaIter<'_, Token> {
+        DeltaIter {
+            kinds: self.kinds.iter(),
+            spans: self.spans.iter(),
+            payloads: &self.payloads,
+            index: 0,
+        }
+    }
+
+    /// Remove all tokens from the list, keeping the allocated memory.
+    pub fn clear(&mut self) {
+        self.kinds.clear();
+        self.spans.clear();
+        self.payloads.clear();
+    }
+}
+
+impl<Token: Split> Default for DeltaTokens<Token> {
+    fn default() -> Self {
+        DeltaTokens::new()
+    }
+}
+
+impl<Token> Clone for DeltaTokens<Token>
+where
+    Token: Split,
+    Token::Payload: Clone,
+{
+    fn clone(&self) -> Self {
+        DeltaTokens {
+            kinds: self.kinds.clone(),
+            spans: self.spans.clone(),
+            payloads: self.payloads.clone(),
+        }
+    }
+}
+
+impl<Token> Debug for DeltaTokens<Token>
+where
+    Token: Split,
+    Token::Kind: Debug,
+    Token::Payload: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<Token: Split> Extend<(Token, Span)> for DeltaTokens<Token> {
+    fn extend<I: IntoIterator<Item = (Token, Span)>>(&mut self, tokens: I) {
+        let tokens = tokens.into_iter();
+        let (additional, _) = tokens.size_hint();
+
+        self.kinds.reserve(additional);
+
+        for (token, span) in tokens {
+            self.push(token, span);
+        }
+    }
+}
+
+impl<Token: Split> FromIterator<(Token, Span)> for DeltaTokens<Token> {
+    fn from_iter<I: IntoIterator<Item = (Token, Span)>>(tokens: I) -> Self {
+        let mut delta = DeltaTokens::new();
+
+        delta.extend(tokens);
+        delta
+    }
+}
+
+impl<'delta, Token: Split> IntoIterator for &'delta DeltaTokens<Token> {
+    type Item = Entry<'delta, Token>;
+    type IntoIter = DeltaIter<'delta, Token>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// An iterator over the tokens in a [DeltaTokens].
+///
+/// This struct is created by the [DeltaTokens::iter] method.
+pub struct DeltaIter<'delta, Token: Split> {
+    kinds: core::slice::Iter<'delta, Token::Kind>,
+    spans: SpanIter<'delta>,
+    payloads: &'delta [(usize, Token::Payload)],
+    index: usize,
+}
+
+impl<'delta, Token: Split> Iterator for DeltaIter<'delta, Token> {
+    type Item = Entry<'delta, Token>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let kind = *self.kinds.next()?;
+        let span = self.spans.next()?;
+
+        // Payloads are in token order, so there's no need to search for them.
+        let payload = match self.payloads.split_first() {
+            Some(((owner, payload), rest)) if *owner == self.index => {
+                self.payloads = rest;
+                Some(payload)
+            }
+            _ => None,
+        };
+
+        self.index += 1;
+        Some((kind, span, payload))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.kinds.size_hint()
+    }
+}
+
+impl<'delta, Token: Split> ExactSizeIterator for DeltaIter<'delta, Token> {}
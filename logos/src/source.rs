@@ -12,8 +12,8 @@ use std::ops::Range;
 
 /// Types the `Lexer` can read from.
 ///
-/// This trait is already implemented for `&str` and `&[u8]`, so it's unlikely that you'll want to implement it
-/// yourself.
+/// This trait is already implemented for `&str` and `&[u8]`, as well as for owned byte buffers (`Vec<u8>` and
+/// `Box<[u8]>`), so it's unlikely that you'll want to implement it yourself.
 ///
 /// That being said, you can pass a type implementing this trait to the derive macro's `source` option to use your own
 /// source type. See the documentation on the [Logos][crate::Logos] trait for more information.
@@ -229,6 +229,57 @@ impl Source for [u8] {
     }
 }
 
+/// Owned byte buffers read the same way as the bytes they hold, so that a buffer received from somewhere else (like a
+/// network payload) can be lexed with `#[logos(source = Vec<u8>)]` as it is.
+macro_rules! impl_owned_bytes {
+    ($($ty:ty),*) => {
+        $(
+            #[cfg(feature = "alloc")]
+            impl Source for $ty {
+                type Slice = [u8];
+
+                #[inline]
+                fn len(&self) -> usize {
+                    <[u8]>::len(self)
+                }
+
+                #[inline]
+                fn read<'a, C>(&'a self, offset: usize) -> Option<C>
+                where
+                    C: Chunk<'a>,
+                {
+                    <[u8] as Source>::read(self, offset)
+                }
+
+                #[inline]
+                unsafe fn read_unchecked<'a, C>(&'a self, offset: usize) -> C
+                where
+                    C: Chunk<'a>,
+                {
+                    <[u8] as Source>::read_unchecked(self, offset)
+                }
+
+                #[inline]
+                fn slice(&self, range: Range<usize>) -> Option<&[u8]> {
+                    self.get(range)
+                }
+
+                #[inline]
+                unsafe fn slice_unchecked(&self, range: Range<usize>) -> &[u8] {
+                    <[u8] as Source>::slice_unchecked(self, range)
+                }
+
+                #[inline]
+                fn is_boundary(&self, index: usize) -> bool {
+                    index <= <[u8]>::len(self)
+                }
+            }
+        )*
+    };
+}
+
+impl_owned_bytes!(alloc::vec::Vec<u8>, alloc::boxed::Box<[u8]>);
+
 /// A fixed, statically sized chunk of data that can be read from a `Source`.
 ///
 /// This is implemented for `u8`, as well as borrowed byte arrays of any size.
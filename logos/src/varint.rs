@@ -0,0 +1,42 @@
+//! LEB128 variable-length integers, used by the compact encodings in the [cache](crate::cache) and [soa](crate::soa)
+//! modules: seven bits to a byte, with the high bit set on all but the last.
+
+use alloc::vec::Vec;
+
+/// Why an integer couldn't be read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Error {
+    /// The bytes ended in the middle of the integer.
+    UnexpectedEnd,
+
+    /// The integer doesn't fit in 64 bits.
+    TooLong,
+}
+
+/// Write `value` to the end of `bytes`.
+pub(crate) fn write(bytes: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        bytes.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+
+    bytes.push(value as u8);
+}
+
+/// Read an integer written by [write] from the start of `bytes`, and skip past it.
+pub(crate) fn read(bytes: &mut &[u8]) -> Result<u64, Error> {
+    let mut value = 0u64;
+
+    for shift in (0..64).step_by(7) {
+        let (&byte, rest) = bytes.split_first().ok_or(Error::UnexpectedEnd)?;
+
+        *bytes = rest;
+        value |= ((byte & 0x7F) as u64) << shift;
+
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+
+    Err(Error::TooLong)
+}
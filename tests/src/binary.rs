@@ -31,9 +31,11 @@
 //! Token::lexer("This shouldn't work with a string literal!");
 //! ```
 
+#[allow(unused_imports)]
 pub use super::assert_lex;
 use logos::Logos;
 
+#[allow(dead_code)]
 #[derive(Logos, Debug, Clone, Copy, PartialEq)]
 enum Token {
     #[token("foo")]
@@ -121,4 +121,83 @@ mod adaptors {
         // We've reached the end of input, so this should be `None`.
         assert!(lexer.next().is_none());
     }
+
+    #[test]
+    fn triples() {
+        let mut lexer = Token::lexer("beta ? alpha")
+            .with_span_offset(10)
+            .triples()
+            .lookahead();
+
+        assert_eq!(lexer.peek(), Some(&Ok((10, Token::Beta, 14))));
+        assert_eq!(lexer.next(), Some(Ok((10, Token::Beta, 14))));
+        assert_eq!(lexer.next(), Some(Err(logos::UnknownToken)));
+        assert_eq!(lexer.span(), 15..16);
+        assert_eq!(lexer.next(), Some(Ok((17, Token::Alpha, 22))));
+        assert!(lexer.next().is_none());
+    }
+
+    #[test]
+    fn triples_with_other_errors() {
+        let tokens: Vec<_> = Token::lexer("gamma ?")
+            .map_with_lexer(|result, lexer| result.map_err(|_| lexer.slice().to_owned()))
+            .triples()
+            .collect();
+
+        assert_eq!(tokens, &[Ok((0, Token::Gamma, 5)), Err(String::from("?"))]);
+    }
+
+    #[test]
+    fn spanned_after_adaptors() {
+        let mut lexer = Token::lexer("alpha beta").lookahead().boxed().spanned();
+
+        assert_eq!(lexer.next(), Some(Ok((Token::Alpha, 0..5))));
+        assert_eq!(lexer.next(), Some(Ok((Token::Beta, 6..10))));
+        assert!(lexer.next().is_none());
+
+        let tokens: Vec<_> = Token::lexer("gamma ?")
+            .map_with_lexer(|result, lexer| result.map_err(|_| lexer.slice().to_owned()))
+            .spanned()
+            .collect();
+
+        assert_eq!(tokens, &[Ok((Token::Gamma, 0..5)), Err(String::from("?"))]);
+    }
+
+    #[test]
+    fn inspected() {
+        let mut spans = Vec::new();
+        let tokens: Vec<_> = Token::lexer("alpha ? gamma")
+            .inspect_with_lexer(|_, lexer| spans.push(lexer.span()))
+            .spanned()
+            .collect();
+
+        assert_eq!(
+            tokens,
+            &[
+                Ok((Token::Alpha, 0..5)),
+                Err(logos::UnknownToken),
+                Ok((Token::Gamma, 8..13))
+            ]
+        );
+        assert_eq!(spans, &[0..5, 6..7, 8..13]);
+    }
+
+    #[test]
+    fn taken_while() {
+        let mut lexer = Token::lexer("alpha beta gamma alpha")
+            .take_while_with_lexer(|token, _| token != &Ok(Token::Gamma));
+
+        assert_eq!(lexer.next(), Some(Ok(Token::Alpha)));
+        assert_eq!(lexer.next(), Some(Ok(Token::Beta)));
+        assert!(!lexer.is_done());
+        assert!(lexer.next().is_none());
+        assert!(lexer.is_done());
+        assert_eq!(lexer.span(), 11..16);
+        // Stopping is final, even though the lexer has more tokens.
+        assert!(lexer.next().is_none());
+
+        let mut lexer = lexer.into_lexer();
+
+        assert_eq!(lexer.next(), Some(Ok(Token::Alpha)));
+    }
 }
@@ -0,0 +1,170 @@
+use logos::{Logos, UnknownToken};
+use tests::assert_lex;
+
+mod word_boundaries {
+    use super::*;
+
+    #[derive(Logos, Debug, PartialEq)]
+    #[logos(test_match)]
+    enum Token {
+        #[regex(r"[ \t\n]+", logos::skip)]
+        Whitespace,
+
+        #[regex(r"if\b")]
+        If,
+
+        #[regex("[a-z]+")]
+        Ident,
+
+        #[regex(r"[0-9]+\b")]
+        Number,
+
+        #[regex(r"\B\.[a-z]+")]
+        Field,
+    }
+
+    #[test]
+    fn keywords() {
+        assert_lex(
+            "if iffy if(",
+            &[
+                (Ok(Token::If), "if", 0..2),
+                (Ok(Token::Ident), "iffy", 3..7),
+                (Ok(Token::If), "if", 8..10),
+                (Err(UnknownToken), "(", 10..11),
+            ],
+        );
+    }
+
+    #[test]
+    fn falls_back_when_boundary_fails() {
+        // `é` is a word character, but isn't part of an identifier.
+        assert_lex(
+            "ifé",
+            &[
+                (Ok(Token::Ident), "if", 0..2),
+                (Err(UnknownToken), "é", 2..4),
+            ],
+        );
+    }
+
+    #[test]
+    fn errors_without_fallback() {
+        assert_lex(
+            "12 34ab",
+            &[
+                (Ok(Token::Number), "12", 0..2),
+                (Err(UnknownToken), "34", 3..5),
+                (Ok(Token::Ident), "ab", 5..7),
+            ],
+        );
+    }
+
+    #[test]
+    fn not_word_boundary() {
+        assert_lex(
+            " .b a.c",
+            &[
+                (Ok(Token::Field), ".b", 1..3),
+                (Ok(Token::Ident), "a", 4..5),
+                (Err(UnknownToken), ".c", 5..7),
+            ],
+        );
+    }
+
+    #[test]
+    fn test_match() {
+        assert_eq!(Token::test_match_If("if x"), Some(2));
+        assert_eq!(Token::test_match_If("if"), Some(2));
+        assert_eq!(Token::test_match_If("ifé"), None);
+        assert_eq!(Token::test_match_Number("42"), Some(2));
+    }
+}
+
+mod anchors {
+    use super::*;
+
+    #[derive(Logos, Debug, PartialEq)]
+    enum Token {
+        #[regex(r"[ \t\n]+", logos::skip)]
+        Whitespace,
+
+        #[regex("^#!.*")]
+        Shebang,
+
+        #[regex("(?m)^#[a-z]+")]
+        Directive,
+
+        #[regex("#")]
+        Hash,
+
+        #[regex("[a-z]+")]
+        Word,
+
+        #[regex(r"\.$")]
+        Stop,
+    }
+
+    #[test]
+    fn start_of_text() {
+        assert_lex(
+            "#!/bin/sh\n#!",
+            &[
+                (Ok(Token::Shebang), "#!/bin/sh", 0..9),
+                (Err(UnknownToken), "#!", 10..12),
+            ],
+        );
+    }
+
+    #[test]
+    fn start_of_line() {
+        assert_lex(
+            "#if a #if\n#end",
+            &[
+                (Ok(Token::Directive), "#if", 0..3),
+                (Ok(Token::Word), "a", 4..5),
+                // Only definitions matching all of `#if` are considered when the anchor doesn't hold.
+                (Err(UnknownToken), "#if", 6..9),
+                (Ok(Token::Directive), "#end", 10..14),
+            ],
+        );
+    }
+
+    #[test]
+    fn end_of_text() {
+        assert_lex(
+            "a. b.",
+            &[
+                (Ok(Token::Word), "a", 0..1),
+                (Err(UnknownToken), ".", 1..2),
+                (Ok(Token::Word), "b", 3..4),
+                (Ok(Token::Stop), ".", 4..5),
+            ],
+        );
+    }
+}
+
+mod bytes {
+    use super::*;
+
+    #[derive(Logos, Debug, PartialEq)]
+    enum Token {
+        #[regex(b" +", logos::skip)]
+        Whitespace,
+
+        #[regex(b"(?-u)[a-z]+\\b")]
+        Word,
+    }
+
+    #[test]
+    fn ascii_word_boundary() {
+        assert_lex(
+            b"ab cd\xFF" as &[u8],
+            &[
+                (Ok(Token::Word), b"ab" as &[u8], 0..2),
+                (Ok(Token::Word), b"cd", 3..5),
+                (Err(UnknownToken), b"\xFF", 5..6),
+            ],
+        );
+    }
+}
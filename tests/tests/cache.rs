@@ -0,0 +1,138 @@
+use logos::cache::{self, Cacheable, DecodeError};
+use logos::Logos;
+
+#[derive(Logos, Debug, Clone, PartialEq)]
+enum Token<'a> {
+    #[regex(r"[ \n\t]+", logos::skip)]
+    Whitespace,
+
+    #[regex("[a-zA-Zé]+")]
+    Ident(&'a str),
+
+    #[regex("[0-9]+", |lex| lex.slice().parse().ok())]
+    Number(u64),
+
+    #[token("+")]
+    Plus,
+}
+
+impl<'a> Cacheable<'a> for Token<'a> {
+    fn kind(&self) -> u32 {
+        match self {
+            Token::Whitespace => 0,
+            Token::Ident(_) => 1,
+            Token::Number(_) => 2,
+            Token::Plus => 3,
+        }
+    }
+
+    fn write_payload(&self, out: &mut Vec<u8>) {
+        if let Token::Number(n) = self {
+            out.extend_from_slice(&n.to_le_bytes());
+        }
+    }
+
+    fn read(kind: u32, slice: &'a str, payload: &[u8]) -> Option<Self> {
+        match kind {
+            0 => Some(Token::Whitespace),
+            1 => Some(Token::Ident(slice)),
+            2 => Some(Token::Number(u64::from_le_bytes(payload.try_into().ok()?))),
+            3 => Some(Token::Plus),
+            _ => None,
+        }
+    }
+}
+
+fn lex(source: &str) -> Vec<(Token<'_>, logos::Span)> {
+    Token::lexer(source).spanned().flatten().collect()
+}
+
+mod roundtrip {
+    use super::*;
+
+    #[test]
+    fn tokens() {
+        let source = "café + 1000000 +\n\n  x";
+        let tokens = lex(source);
+        let bytes = cache::encode(&tokens);
+
+        assert_eq!(cache::decode::<Token>(source, &bytes), Ok(tokens));
+    }
+
+    #[test]
+    fn empty() {
+        let bytes = cache::encode::<Token, _>(&[]);
+
+        assert_eq!(cache::decode::<Token>("", &bytes), Ok(vec![]));
+    }
+
+    #[test]
+    fn large_offsets() {
+        let source = format!("{}foo", " ".repeat(100_000));
+        let tokens = lex(&source);
+        let bytes = cache::encode(&tokens);
+
+        // Header, kind, offset (3 bytes), length, and payload length.
+        assert_eq!(bytes.len(), 4 + 1 + 3 + 1 + 1);
+        assert_eq!(cache::decode::<Token>(&source, &bytes), Ok(tokens));
+    }
+
+    #[test]
+    fn into_existing_collection() {
+        let source = "a + 2";
+        let tokens = lex(source);
+        let bytes = cache::encode(&tokens);
+        let mut decoded = vec![(Token::Plus, 100..101)];
+
+        assert_eq!(
+            cache::decode_into::<Token, _>(source, &bytes, &mut decoded),
+            Ok(())
+        );
+        assert_eq!(decoded[0], (Token::Plus, 100..101));
+        assert_eq!(decoded[1..], tokens[..]);
+    }
+}
+
+mod errors {
+    use super::*;
+
+    #[test]
+    fn header() {
+        assert_eq!(
+            cache::decode::<Token>("foo", b"nope"),
+            Err(DecodeError::InvalidHeader)
+        );
+    }
+
+    #[test]
+    fn truncated() {
+        let bytes = cache::encode(&lex("1 + 2"));
+
+        assert_eq!(
+            cache::decode::<Token>("1 + 2", &bytes[..bytes.len() - 1]),
+            Err(DecodeError::UnexpectedEnd)
+        );
+    }
+
+    #[test]
+    fn wrong_source() {
+        let bytes = cache::encode(&lex("foo bar"));
+
+        assert_eq!(
+            cache::decode::<Token>("foo", &bytes),
+            Err(DecodeError::InvalidSpan)
+        );
+    }
+
+    #[test]
+    fn unknown_kind() {
+        let mut bytes = cache::encode(&lex("+"));
+
+        bytes[4] = 42;
+
+        assert_eq!(
+            cache::decode::<Token>("+", &bytes),
+            Err(DecodeError::InvalidToken)
+        );
+    }
+}
@@ -124,3 +124,167 @@ mod rust {
         );
     }
 }
+
+mod reparse {
+    use super::*;
+    use logos::callback::Output;
+    use logos::UnknownToken;
+
+    #[derive(Default)]
+    struct Extras {
+        keywords: bool,
+    }
+
+    type Out<'a> = Output<(), Token<'a>, UnknownToken>;
+
+    fn keyword<'a>(lex: &mut Lexer<'a, Token<'a>>) -> Out<'a> {
+        match lex.extras.keywords {
+            true => Output::Construct(()),
+            false => Output::Reparse(Token::RULE_Ident),
+        }
+    }
+
+    fn toggle<'a>(lex: &mut Lexer<'a, Token<'a>>) -> Out<'a> {
+        lex.extras.keywords = !lex.extras.keywords;
+
+        // The second definition of `Whitespace` skips the `!` token.
+        Output::Reparse(Token::RULE_Whitespace_1)
+    }
+
+    #[derive(Logos, Debug, PartialEq)]
+    #[logos(extras = Extras)]
+    enum Token<'a> {
+        #[regex(r"[ \t\n\f]+", logos::skip)]
+        #[token("!", logos::skip)]
+        Whitespace,
+
+        #[token("async", keyword)]
+        #[token("await", keyword)]
+        Keyword,
+
+        #[token("#", toggle)]
+        Toggle,
+
+        #[regex("[a-z]+", |lex| lex.slice())]
+        Ident(&'a str),
+    }
+
+    #[test]
+    fn contextual_keywords() {
+        assert_lex(
+            "async await # await ! async # await",
+            &[
+                (Ok(Token::Ident("async")), "async", 0..5),
+                (Ok(Token::Ident("await")), "await", 6..11),
+                (Ok(Token::Keyword), "await", 14..19),
+                (Ok(Token::Keyword), "async", 22..27),
+                (Ok(Token::Ident("await")), "await", 30..35),
+            ],
+        );
+    }
+
+    #[test]
+    fn rules_are_numbered_in_order() {
+        assert_eq!(Token::RULE_Whitespace_0.index(), 0);
+        assert_eq!(Token::RULE_Whitespace_1.index(), 1);
+        assert_eq!(Token::RULE_Keyword_0.index(), 2);
+        assert_eq!(Token::RULE_Keyword_1.index(), 3);
+        assert_eq!(Token::RULE_Toggle.index(), 4);
+        assert_eq!(Token::RULE_Ident.index(), 5);
+    }
+}
+
+mod filter_result {
+    use super::*;
+    use logos::FilterResult;
+
+    #[derive(Debug, Clone, PartialEq)]
+    enum Error {
+        Unknown,
+        Shouting,
+    }
+
+    impl<'s> logos::Error<'s, Token> for Error {
+        fn unknown_token(_: &Lexer<'s, Token>) -> Self {
+            Error::Unknown
+        }
+    }
+
+    fn word(lex: &mut Lexer<Token>) -> FilterResult<(), Error> {
+        match lex.slice() {
+            "um" => FilterResult::Skip,
+            word if word.chars().all(|c| c.is_ascii_uppercase()) => {
+                FilterResult::Error(Error::Shouting)
+            }
+            _ => FilterResult::Accept(()),
+        }
+    }
+
+    #[derive(Logos, Debug, PartialEq)]
+    #[logos(error = Error)]
+    enum Token {
+        #[regex(r"[ \t\n\f]+", logos::skip)]
+        Whitespace,
+
+        #[regex("[a-zA-Z]+", word)]
+        Word,
+    }
+
+    #[test]
+    fn accept_skip_or_error() {
+        assert_lex(
+            "well um HEY ?",
+            &[
+                (Ok(Token::Word), "well", 0..4),
+                (Err(Error::Shouting), "HEY", 8..11),
+                (Err(Error::Unknown), "?", 12..13),
+            ],
+        );
+    }
+}
+
+mod control_flow {
+    use super::*;
+    use logos::UnknownToken;
+    use std::ops::ControlFlow;
+
+    fn number(lex: &mut Lexer<Token>) -> Result<Option<u64>, UnknownToken> {
+        match lex.slice().parse::<u64>() {
+            Ok(0) => Ok(None),
+            Ok(n) => Ok(Some(n)),
+            Err(_) => Err(UnknownToken),
+        }
+    }
+
+    fn word(lex: &mut Lexer<Token>) -> ControlFlow<UnknownToken, usize> {
+        match lex.slice().len() {
+            1 => ControlFlow::Break(UnknownToken),
+            len => ControlFlow::Continue(len),
+        }
+    }
+
+    #[derive(Logos, Debug, PartialEq)]
+    enum Token {
+        #[regex(r"[ \t\n\f]+", logos::skip)]
+        Whitespace,
+
+        #[regex("[0-9]+", number)]
+        Number(u64),
+
+        #[regex("[a-z]+", word)]
+        Word(usize),
+    }
+
+    #[test]
+    fn fallible_and_optional() {
+        assert_lex(
+            "12 0 99999999999999999999 abc x",
+            &[
+                (Ok(Token::Number(12)), "12", 0..2),
+                (Err(UnknownToken), "99999999999999999999", 5..25),
+                (Ok(Token::Word(3)), "abc", 26..29),
+                (Err(UnknownToken), "x", 30..31),
+            ],
+        );
+    }
+}
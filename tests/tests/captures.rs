@@ -0,0 +1,97 @@
+use logos::Logos;
+use tests::assert_lex;
+
+#[derive(Logos, Debug, Clone, Copy, PartialEq)]
+enum Token<'a> {
+    #[regex(r"[ \t\n]+", logos::skip)]
+    Whitespace,
+
+    #[regex(r"([0-9]+)(\.([0-9]+))?", |lex| {
+        let captures = lex.captures().unwrap();
+
+        (captures.get(1).unwrap(), captures.get(3))
+    })]
+    Number((&'a str, Option<&'a str>)),
+
+    #[regex(r"(?P<key>[a-z]+)=(?P<value>[a-z]*)", |lex| {
+        let captures = lex.captures().unwrap();
+
+        (captures.name_span("key").unwrap().start, captures.name("value").unwrap())
+    })]
+    Pair((usize, &'a str)),
+
+    #[regex("(ab|a)(bc|c)*", |lex| lex.captures().unwrap().get(1))]
+    Greedy(&'a str),
+
+    #[regex("\"(ä*)\"", |lex| {
+        lex.bump(1);
+        lex.captures().unwrap().get(1)
+    })]
+    Unicode(&'a str),
+
+    #[regex("[A-Z]+", |lex| lex.captures().is_none())]
+    NoGroups(bool),
+}
+
+#[test]
+fn numbered() {
+    assert_lex(
+        "10 3.14",
+        &[
+            (Ok(Token::Number(("10", None))), "10", 0..2),
+            (Ok(Token::Number(("3", Some("14")))), "3.14", 3..7),
+        ],
+    );
+}
+
+#[test]
+fn named() {
+    assert_lex(
+        "foo= x=bar",
+        &[
+            (Ok(Token::Pair((0, ""))), "foo=", 0..4),
+            (Ok(Token::Pair((5, "bar"))), "x=bar", 5..10),
+        ],
+    );
+}
+
+#[test]
+fn leftmost_first() {
+    assert_lex(
+        "abc abcc",
+        &[
+            (Ok(Token::Greedy("ab")), "abc", 0..3),
+            (Ok(Token::Greedy("ab")), "abcc", 4..8),
+        ],
+    );
+}
+
+#[test]
+fn after_bump() {
+    assert_lex("\"ää\"!", &[(Ok(Token::Unicode("ää")), "\"ää\"!", 0..7)]);
+}
+
+#[test]
+fn without_groups() {
+    let mut lex = Token::lexer("ab=c ABC");
+
+    assert_eq!(lex.next(), Some(Ok(Token::Pair((0, "c")))));
+    assert!(lex.captures().is_some());
+    assert_eq!(lex.next(), Some(Ok(Token::NoGroups(true))));
+    assert!(lex.captures().is_none());
+}
+
+#[test]
+fn with_span_offset() {
+    let mut lex = Token::lexer("x ab=cd").with_span_offset(100);
+
+    assert!(lex.next().is_some());
+    assert_eq!(lex.next(), Some(Ok(Token::Pair((102, "cd")))));
+    assert_eq!(lex.span(), 102..107);
+
+    let captures = lex.captures().unwrap();
+
+    assert_eq!(captures.span(0), Some(102..107));
+    assert_eq!(captures.name_span("value"), Some(105..107));
+    assert_eq!(captures.get(0), Some("ab=cd"));
+}
@@ -0,0 +1,121 @@
+use logos::columns::Columns;
+use logos::Logos;
+
+#[derive(Logos, Debug, Clone, Copy, PartialEq)]
+enum Token {
+    #[regex(r"[ \t\r]+", logos::skip)]
+    Whitespace,
+
+    #[regex("[A-Z]+")]
+    Word,
+
+    #[regex("[0-9]+")]
+    Number,
+
+    #[token("=")]
+    Equals,
+}
+
+mod fixed_form {
+    use super::*;
+
+    #[test]
+    fn ignores_trailing_columns() {
+        let source = format!(
+            "{:<72}{}\n{:<72}{}\n",
+            "      X = 10", "SEQ00010", "      Y = X", "SEQ00020",
+        );
+        let mut lex = Columns::<Token>::new(&source, 0..72);
+
+        assert_eq!(lex.next(), Some(Ok((Token::Word, 6..7))));
+        assert_eq!(lex.slice(), "X");
+        assert_eq!(lex.next(), Some(Ok((Token::Equals, 8..9))));
+        assert_eq!(lex.next(), Some(Ok((Token::Number, 10..12))));
+        assert_eq!(lex.slice(), "10");
+        assert_eq!(lex.next(), Some(Ok((Token::Word, 87..88))));
+        assert_eq!(lex.next(), Some(Ok((Token::Equals, 89..90))));
+        assert_eq!(lex.next(), Some(Ok((Token::Word, 91..92))));
+        assert_eq!(&source[lex.span()], "X");
+        assert_eq!(lex.next(), None);
+    }
+
+    #[test]
+    fn splits_tokens_at_window_edges() {
+        let source = "ABCDEF\nAB\n\n123456";
+        let tokens: Vec<_> = Columns::<Token>::new(source, 2..4).collect();
+
+        assert_eq!(
+            tokens,
+            &[Ok((Token::Word, 2..4)), Ok((Token::Number, 13..15)),]
+        );
+    }
+
+    #[test]
+    fn empty_source() {
+        let tokens: Vec<_> = Columns::<Token>::new("", 0..72).collect();
+
+        assert_eq!(tokens, &[]);
+    }
+
+    #[test]
+    fn unicode_boundaries() {
+        // The window ends in the middle of `é`, so it's extended to include the whole character.
+        let source = "Aé B";
+        let mut lex = Columns::<Token>::new(source, 0..2);
+
+        assert_eq!(lex.next(), Some(Ok((Token::Word, 0..1))));
+        assert!(matches!(lex.next(), Some(Err(_))));
+        assert_eq!(lex.span(), 1..3);
+        assert_eq!(lex.next(), None);
+    }
+}
+
+mod lifecycle {
+    use super::*;
+    use logos::Lifecycle;
+
+    #[derive(Default, Debug, PartialEq)]
+    struct Events {
+        log: Vec<String>,
+    }
+
+    impl<'s> Lifecycle<'s, str> for Events {
+        fn on_start(&mut self, source: &'s str) {
+            self.log.push(format!("start {:?}", source));
+        }
+
+        fn on_eof(&mut self) {
+            self.log.push(String::from("finish"));
+        }
+    }
+
+    #[derive(Logos, Debug, PartialEq)]
+    #[logos(extras = Events, lifecycle)]
+    #[logos(on_eof = |lex| lex.extras.log.push(String::from("eof")))]
+    enum Token {
+        #[regex(r"[ \t]+", logos::skip)]
+        Whitespace,
+
+        #[regex("[a-z]+", |lex| lex.extras.log.push(String::from(lex.slice())))]
+        Word,
+    }
+
+    #[test]
+    fn hooks_see_the_whole_input() {
+        let mut lex = Columns::<Token>::new("ab xx\ncd yy\nef", 0..2);
+
+        assert_eq!(lex.extras().log, &["start \"ab xx\\ncd yy\\nef\""]);
+        assert_eq!(lex.by_ref().count(), 3);
+        assert_eq!(
+            lex.extras().log,
+            &[
+                "start \"ab xx\\ncd yy\\nef\"",
+                "ab",
+                "cd",
+                "ef",
+                "finish",
+                "eof"
+            ]
+        );
+    }
+}
@@ -0,0 +1,161 @@
+use logos::{Lexer, Logos, UnknownToken};
+use tests::assert_lex;
+
+// The same grammar, once for each codegen mode.
+macro_rules! grammar {
+    ($name:ident, $codegen:literal) => {
+        #[derive(Logos, Debug, Clone, PartialEq)]
+        #[logos(codegen = $codegen)]
+        enum $name {
+            #[regex(r"[ \t\n]+", logos::skip)]
+            Whitespace,
+
+            #[token("let")]
+            Let,
+
+            #[token("letter")]
+            Letter,
+
+            #[regex("[a-zA-Z_][a-zA-Z0-9_]*")]
+            Ident,
+
+            #[regex("[0-9]+", |lex| lex.slice().parse().ok())]
+            #[regex("0x[0-9a-f]+", |lex| u64::from_str_radix(&lex.slice()[2..], 16).ok())]
+            Integer(u64),
+
+            #[regex(r"[0-9]+\.[0-9]+(e[+-]?[0-9]+)?")]
+            Float,
+
+            #[regex(r#""([^"\\]|\\.)*""#)]
+            String,
+
+            #[token(".")]
+            Dot,
+
+            #[token("...")]
+            Ellipsis,
+
+            #[token("=")]
+            Assign,
+
+            #[token("==")]
+            Equals,
+
+            #[token("===")]
+            StrictEquals,
+
+            #[regex("//[^\n]*")]
+            Comment,
+        }
+    };
+}
+
+grammar!(Fast, "fast");
+grammar!(Compact, "compact");
+
+fn lex<'s, Token>(source: &'s str) -> Vec<(String, std::ops::Range<usize>)>
+where
+    Token: Logos<'s, Source = str> + std::fmt::Debug,
+    Token::Extras: Default,
+    Token::Error: std::fmt::Debug,
+{
+    let mut lex: Lexer<'s, Token> = Token::lexer(source);
+    let mut tokens = Vec::new();
+
+    while let Some(token) = lex.next() {
+        tokens.push((format!("{:?}", token), lex.span()));
+    }
+
+    tokens
+}
+
+#[test]
+fn modes_agree() {
+    let pieces = [
+        "let",
+        "letter",
+        "lett",
+        "le",
+        "x",
+        "_y1",
+        "42",
+        "0x1f",
+        "0x",
+        "1.5",
+        "1.",
+        "2.5e10",
+        "3.0e",
+        "\"hi\"",
+        "\"a\\\"b\"",
+        "\"open",
+        ".",
+        "..",
+        "...",
+        "....",
+        "=",
+        "==",
+        "===",
+        "====",
+        "// c",
+        "//",
+        "/",
+        "*",
+        "#",
+        " ",
+        "\n",
+        "é",
+    ];
+
+    // A simple xorshift, so that every run checks the same sources.
+    let mut state = 0x2545_F491_4F6C_DD1Du64;
+    let mut random = || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state
+    };
+
+    for _ in 0..2000 {
+        let source: String = (0..random() % 8)
+            .map(|_| pieces[random() as usize % pieces.len()])
+            .collect();
+
+        assert_eq!(
+            lex::<Compact>(&source),
+            lex::<Fast>(&source),
+            "{:?}",
+            source
+        );
+    }
+}
+
+#[test]
+fn compact_tokens() {
+    assert_lex(
+        "let letter lett 0x2a 4.5 ... == \"a\\\"b\" // x",
+        &[
+            (Ok(Compact::Let), "let", 0..3),
+            (Ok(Compact::Letter), "letter", 4..10),
+            (Ok(Compact::Ident), "lett", 11..15),
+            (Ok(Compact::Integer(42)), "0x2a", 16..20),
+            (Ok(Compact::Float), "4.5", 21..24),
+            (Ok(Compact::Ellipsis), "...", 25..28),
+            (Ok(Compact::Equals), "==", 29..31),
+            (Ok(Compact::String), "\"a\\\"b\"", 32..38),
+            (Ok(Compact::Comment), "// x", 39..43),
+        ],
+    );
+}
+
+#[test]
+fn compact_errors() {
+    assert_lex(
+        "1. # \"open",
+        &[
+            (Ok(Compact::Integer(1)), "1", 0..1),
+            (Ok(Compact::Dot), ".", 1..2),
+            (Err(UnknownToken), "#", 3..4),
+            (Err(UnknownToken), "\"open", 5..10),
+        ],
+    );
+}
@@ -0,0 +1,95 @@
+//! Each lexer here generates a `__logos_conformance_*` test, which is run along with the tests in this file.
+
+use logos::Logos;
+
+#[derive(Logos, Debug, PartialEq)]
+#[logos(conformance_tests = "target/conformance/token.txt")]
+enum Token<'s> {
+    #[regex(r"[ \t\n]+", logos::skip)]
+    Whitespace,
+
+    #[token("fn")]
+    Fn,
+
+    #[token("for")]
+    For,
+
+    #[token("=")]
+    Equals,
+
+    #[token("==")]
+    EqualsEquals,
+
+    #[token("..")]
+    Range,
+
+    #[token("...")]
+    Ellipsis,
+
+    #[regex("[a-zA-Z_][a-zA-Z0-9_]*")]
+    Ident(&'s str),
+
+    #[regex(r"[0-9]+(\.[0-9]+)?([eE][+-]?[0-9]+)?", |lex| lex.slice().parse().ok())]
+    Number(f64),
+
+    #[regex(r#""([^"\\]|\\.)*""#)]
+    String,
+
+    #[regex(r"//[^\n]*")]
+    Comment,
+
+    #[regex(r"\p{Greek}+")]
+    Greek,
+}
+
+#[derive(Logos, Debug, PartialEq)]
+#[logos(source = [u8], conformance_tests)]
+enum Bytes {
+    #[token(b"\x00\x01")]
+    Magic,
+
+    #[regex(b"\xFF[\x00-\x7F]*")]
+    Run,
+
+    #[regex(b"[a-z]+")]
+    Word,
+}
+
+#[derive(Logos, Debug, PartialEq)]
+#[logos(conformance_tests)]
+enum Contextual {
+    #[regex("[a-z]+")]
+    Ident,
+
+    #[token("union", contextual)]
+    Union,
+
+    #[regex(r"\bx[0-9]+\b")]
+    Register,
+}
+
+#[test]
+fn exported_cases() {
+    let path = format!(
+        "{}/target/conformance/token.txt",
+        env!("CARGO_MANIFEST_DIR")
+    );
+    let report = std::fs::read_to_string(path).unwrap();
+    let mut lines = report.lines();
+
+    assert_eq!(
+        lines.next(),
+        Some("Conformance cases for the lexer of `Token`:")
+    );
+
+    let cases: Vec<(&str, &str)> = lines.map(|line| line.split_once(" => ").unwrap()).collect();
+
+    assert!(cases.len() > 50);
+    assert!(cases.contains(&("b\"fn\"", "Fn 2")));
+    assert!(cases.contains(&("b\"fo\"", "Ident 2")));
+    assert!(cases.contains(&("b\"...\"", "Ellipsis 3")));
+    assert!(cases.iter().any(|&(_, expected)| expected == "error"));
+    assert!(cases
+        .iter()
+        .any(|&(_, expected)| expected.starts_with("Greek")));
+}
@@ -0,0 +1,62 @@
+use logos::Logos;
+use tests::assert_lex;
+
+#[derive(Logos, Debug, Clone, Copy, PartialEq)]
+enum Token<'a> {
+    #[regex(r"[ \t\n]+", logos::skip)]
+    Whitespace,
+
+    #[token("await", contextual)]
+    Await,
+
+    #[token("union", contextual)]
+    #[token("struct")]
+    Union,
+
+    #[regex("[a-z]+", |lex| lex.slice())]
+    Ident(&'a str),
+
+    #[regex("y(ie)+ld", contextual)]
+    Yield,
+}
+
+#[test]
+fn disabled_by_default() {
+    assert_lex(
+        "await union struct yieield awaits",
+        &[
+            (Ok(Token::Ident("await")), "await", 0..5),
+            (Ok(Token::Ident("union")), "union", 6..11),
+            (Ok(Token::Union), "struct", 12..18),
+            (Ok(Token::Ident("yieield")), "yieield", 19..26),
+            (Ok(Token::Ident("awaits")), "awaits", 27..33),
+        ],
+    );
+}
+
+#[test]
+fn enabled() {
+    let mut lex = Token::lexer("await union yield awaits await");
+
+    lex.enable_contextual(Token::Await);
+    lex.enable_contextual(Token::Yield);
+
+    assert!(lex.is_contextual_enabled(Token::Await));
+    assert!(!lex.is_contextual_enabled(Token::Union));
+
+    assert_eq!(lex.next(), Some(Ok(Token::Await)));
+    assert_eq!(lex.next(), Some(Ok(Token::Ident("union"))));
+    assert_eq!(lex.next(), Some(Ok(Token::Yield)));
+    assert_eq!(lex.next(), Some(Ok(Token::Ident("awaits"))));
+
+    lex.disable_contextual(Token::Await);
+
+    assert_eq!(lex.next(), Some(Ok(Token::Ident("await"))));
+    assert_eq!(lex.next(), None);
+}
+
+#[test]
+#[should_panic(expected = "token does not have any contextual definitions")]
+fn not_contextual() {
+    Token::lexer("").enable_contextual(Token::Whitespace);
+}
@@ -0,0 +1,107 @@
+use std::ops::Range;
+
+use logos::source::{Chunk, Source};
+use logos::{LexerExt, Logos, UnknownToken};
+
+#[derive(Logos, Debug, PartialEq)]
+enum Token {
+    #[regex(r"[ \t]+", logos::skip)]
+    Whitespace,
+
+    #[regex("[a-z]+")]
+    Word,
+}
+
+/// A source that leaves the last byte off every slice it makes without checking the range.
+struct Skewed(Vec<u8>);
+
+impl Source for Skewed {
+    type Slice = [u8];
+
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    fn read<'a, C>(&'a self, offset: usize) -> Option<C>
+    where
+        C: Chunk<'a>,
+    {
+        self.0.read(offset)
+    }
+
+    unsafe fn read_unchecked<'a, C>(&'a self, offset: usize) -> C
+    where
+        C: Chunk<'a>,
+    {
+        self.0.read_unchecked(offset)
+    }
+
+    fn slice(&self, range: Range<usize>) -> Option<&[u8]> {
+        self.0.get(range)
+    }
+
+    unsafe fn slice_unchecked(&self, range: Range<usize>) -> &[u8] {
+        &self.0[range.start..range.end.saturating_sub(1).max(range.start)]
+    }
+
+    fn is_boundary(&self, index: usize) -> bool {
+        index <= self.0.len()
+    }
+}
+
+#[derive(Logos, Debug, PartialEq)]
+#[logos(source = Skewed)]
+enum Bytes {
+    #[regex(b"[a-z]+")]
+    Word,
+}
+
+#[test]
+fn passes_tokens_through() {
+    let tokens: Vec<_> = Token::lexer("foo 1 bar")
+        .with_span_offset(10)
+        .debug_validate()
+        .spanned()
+        .collect();
+
+    assert_eq!(
+        tokens,
+        &[
+            Ok((Token::Word, 10..13)),
+            Err(UnknownToken),
+            Ok((Token::Word, 16..19)),
+        ]
+    );
+}
+
+#[test]
+fn starts_after_the_lexer() {
+    let mut lexer = Token::lexer("foo bar");
+
+    assert_eq!(lexer.next(), Some(Ok(Token::Word)));
+
+    let tokens: Vec<_> = lexer.debug_validate().collect();
+
+    assert_eq!(tokens, &[Ok(Token::Word)]);
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "the span 0..3 starts before the end of the previous token at 3")]
+fn overlapping() {
+    let mut lexer = Token::lexer("foo bar").debug_validate();
+
+    assert_eq!(lexer.next(), Some(Ok(Token::Word)));
+
+    lexer.as_lexer_mut().seek(0);
+    lexer.next();
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "the slice [102, 111] of the span 0..3 doesn't match the source")]
+fn mismatched_slice() {
+    let source = Skewed(b"foo".to_vec());
+
+    Bytes::lexer(&source).debug_validate().for_each(drop);
+}
@@ -0,0 +1,141 @@
+use logos::Logos;
+
+#[derive(Logos, Debug, PartialEq)]
+#[logos(determinism_report = "target/determinism/token.txt")]
+enum Token {
+    #[regex(r"[ \t\n]+", logos::skip)]
+    Whitespace,
+
+    #[token("fn")]
+    Fn,
+
+    #[token("fnord")]
+    Fnord,
+
+    #[regex("[a-z]+")]
+    Ident,
+
+    #[regex("[0-9]+(\\.[0-9]*)?")]
+    Number,
+
+    #[token("é")]
+    Accent,
+}
+
+#[derive(Logos, Debug, PartialEq)]
+#[logos(
+    determinism_report = "target/determinism/compact.txt",
+    codegen = "compact"
+)]
+enum Compact {
+    #[regex(r"[ \t\n]+", logos::skip)]
+    Whitespace,
+
+    #[regex("[a-z]+")]
+    Ident,
+}
+
+/// Repeat the checks of the report in `file` from the transitions it lists, returning its header.
+fn check(file: &str) -> Vec<String> {
+    let path = format!("{}/target/determinism/{}", env!("CARGO_MANIFEST_DIR"), file);
+    let report = std::fs::read_to_string(path).unwrap();
+    let mut lines = report.lines().peekable();
+    let mut header = Vec::new();
+
+    while let Some(line) = lines.next_if(|line| !line.starts_with("state ")) {
+        header.push(line.to_owned());
+    }
+
+    // Each state, with its partitions of all bytes into outcomes
+    let mut states: Vec<(usize, Vec<Vec<String>>)> = Vec::new();
+
+    for line in lines {
+        if let Some(state) = line.strip_prefix("state ") {
+            let id = state.split(' ').next().unwrap().parse().unwrap();
+            let partitions = match state.contains("leaf") {
+                true => vec![],
+                false => vec![vec![]],
+            };
+
+            states.push((id, partitions));
+        } else if line == "  position 0" {
+            continue;
+        } else if line.starts_with("  position ") {
+            states.last_mut().unwrap().1.push(Vec::new());
+        } else {
+            let (_, partitions) = states.last_mut().unwrap();
+
+            partitions.last_mut().unwrap().push(line.trim().to_owned());
+        }
+    }
+
+    let exists = |id: &str| states.iter().any(|(other, _)| other.to_string() == id);
+    let root = header[2].strip_prefix("root: ").unwrap();
+
+    assert!(exists(root));
+
+    for (id, partitions) in &states {
+        for partition in partitions {
+            let mut next = 0;
+
+            for range in partition {
+                let (bytes, outcome) = range.split_once(' ').unwrap();
+                let (start, end) = bytes.split_once('-').unwrap();
+                let start = usize::from_str_radix(start, 16).unwrap();
+                let end = usize::from_str_radix(end, 16).unwrap();
+
+                assert_eq!(start, next, "state {} skips or repeats a byte", id);
+                assert!(start <= end);
+
+                next = end + 1;
+
+                if let Some(target) = outcome.strip_prefix("-> ").or(outcome.strip_prefix("~> ")) {
+                    assert!(
+                        target == "next" || exists(target),
+                        "state {} leads to {}",
+                        id,
+                        target
+                    );
+                }
+            }
+
+            assert_eq!(next, 256, "state {} doesn't cover every byte", id);
+        }
+    }
+
+    header
+}
+
+#[test]
+fn fast() {
+    let header = check("token.txt");
+
+    assert_eq!(header[0], "logos determinism report v1");
+    assert_eq!(header[1], "lexer: Token");
+    assert_eq!(
+        &header[3..],
+        &[
+            "deterministic: yes",
+            "complete: yes",
+            "loop-free on empty input: yes"
+        ]
+    );
+
+    let mut lex = Token::lexer("fn fnord fno 1.5 é");
+
+    assert_eq!(lex.next(), Some(Ok(Token::Fn)));
+    assert_eq!(lex.next(), Some(Ok(Token::Fnord)));
+    assert_eq!(lex.next(), Some(Ok(Token::Ident)));
+    assert_eq!(lex.next(), Some(Ok(Token::Number)));
+    assert_eq!(lex.next(), Some(Ok(Token::Accent)));
+}
+
+#[test]
+fn compact() {
+    let header = check("compact.txt");
+
+    assert_eq!(header[1], "lexer: Compact");
+    assert_eq!(header[3], "deterministic: yes");
+
+    assert_eq!(Compact::lexer("ab").next(), Some(Ok(Compact::Ident)));
+}
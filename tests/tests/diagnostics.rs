@@ -0,0 +1,160 @@
+use logos::diagnostics::{Diagnostic, Diagnostics, Severity};
+use logos::{Lexer, LexerExt, Logos};
+
+#[derive(Debug, Clone, PartialEq)]
+enum Error {
+    Unknown,
+    Overflow,
+    LeadingZero,
+    Unclosed,
+}
+
+impl<'s, T: Logos<'s>> logos::Error<'s, T> for Error {
+    fn unknown_token(_: &Lexer<'s, T>) -> Self {
+        Error::Unknown
+    }
+}
+
+#[derive(Default)]
+struct State {
+    numbers: usize,
+    diagnostics: Diagnostics<Error>,
+}
+
+impl AsMut<Diagnostics<Error>> for State {
+    fn as_mut(&mut self) -> &mut Diagnostics<Error> {
+        &mut self.diagnostics
+    }
+}
+
+fn number(lex: &mut Lexer<Token>) -> Result<u8, Error> {
+    lex.extras.numbers += 1;
+
+    if lex.slice().len() > 1 && lex.slice().starts_with('0') {
+        lex.warn(Error::LeadingZero);
+    }
+
+    lex.slice().parse().map_err(|_| Error::Overflow)
+}
+
+fn string(lex: &mut Lexer<Token>) -> Option<()> {
+    if !lex.slice().ends_with('"') {
+        // Point at the opening quote, rather than the whole rest of the input.
+        let start = lex.span().start;
+
+        lex.extras
+            .diagnostics
+            .warn(start..start + 1, Error::Unclosed);
+    }
+
+    Some(())
+}
+
+#[derive(Logos, Debug, PartialEq)]
+#[logos(error = Error, extras = State)]
+enum Token {
+    #[regex(r"[ \t\n]+", logos::skip)]
+    Whitespace,
+
+    #[regex("[0-9]+", number)]
+    Number(u8),
+
+    #[regex(r#""[^"]*"?"#, string)]
+    String,
+
+    #[token("mode", |lex| lex.warn(Error::Unknown))]
+    Mode,
+}
+
+fn lex(source: &str) -> (Vec<Token>, State) {
+    let mut lexer = Token::lexer(source).diagnose();
+    let tokens = lexer.by_ref().collect();
+
+    (tokens, lexer.into_lexer().extras)
+}
+
+fn summary(diagnostics: &Diagnostics<Error>) -> Vec<(Severity, logos::Span, Error)> {
+    diagnostics
+        .iter()
+        .map(|d| (d.severity, d.span.clone(), d.error.clone()))
+        .collect()
+}
+
+#[test]
+fn warnings_and_errors() {
+    let (tokens, state) = lex("007 ? 300 12 05");
+
+    assert_eq!(
+        tokens,
+        &[Token::Number(7), Token::Number(12), Token::Number(5)]
+    );
+    assert_eq!(state.numbers, 4);
+    assert_eq!(
+        summary(&state.diagnostics),
+        &[
+            (Severity::Warning, 0..3, Error::LeadingZero),
+            (Severity::Error, 4..5, Error::Unknown),
+            (Severity::Error, 6..9, Error::Overflow),
+            (Severity::Warning, 13..15, Error::LeadingZero),
+        ]
+    );
+    assert!(state.diagnostics.has_errors());
+    assert_eq!(state.diagnostics.warnings().count(), 2);
+    assert_eq!(state.diagnostics.errors().count(), 2);
+}
+
+#[test]
+fn no_diagnostics() {
+    let (tokens, state) = lex("1 \"two\" 3");
+
+    assert_eq!(tokens, &[Token::Number(1), Token::String, Token::Number(3)]);
+    assert!(state.diagnostics.is_empty());
+    assert!(!state.diagnostics.has_errors());
+}
+
+#[test]
+fn out_of_order_reports_are_sorted() {
+    let mut diagnostics = Diagnostics::new();
+
+    diagnostics.error(5..6, Error::Unknown);
+    diagnostics.warn(0..1, Error::Unclosed);
+    diagnostics.warn(5..7, Error::LeadingZero);
+    diagnostics.warn(2..3, Error::Overflow);
+
+    assert_eq!(
+        summary(&diagnostics),
+        &[
+            (Severity::Warning, 0..1, Error::Unclosed),
+            (Severity::Warning, 2..3, Error::Overflow),
+            (Severity::Error, 5..6, Error::Unknown),
+            (Severity::Warning, 5..7, Error::LeadingZero),
+        ]
+    );
+
+    let taken = diagnostics.take();
+
+    assert_eq!(taken.len(), 4);
+    assert!(diagnostics.is_empty());
+}
+
+#[test]
+fn warnings_from_tokens_without_fields() {
+    let (tokens, state) = lex("mode 1 \"open");
+
+    assert_eq!(tokens, &[Token::Mode, Token::Number(1), Token::String]);
+    assert_eq!(
+        state.diagnostics.into_iter().collect::<Vec<_>>(),
+        &[
+            Diagnostic {
+                severity: Severity::Warning,
+                span: 0..4,
+                error: Error::Unknown,
+            },
+            Diagnostic {
+                severity: Severity::Warning,
+                span: 7..8,
+                error: Error::Unclosed,
+            },
+        ]
+    );
+}
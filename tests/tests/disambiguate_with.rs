@@ -0,0 +1,42 @@
+use logos::Logos;
+use tests::assert_lex;
+
+#[derive(Logos, Debug, Clone, Copy, PartialEq)]
+enum Token {
+    #[regex(r"[ \t\n]+", logos::skip)]
+    Whitespace,
+
+    #[token("let", disambiguate_with = "let")]
+    Let,
+
+    #[regex("[a-z]+", disambiguate_with = "lets", disambiguate_with = "le")]
+    Ident,
+
+    #[regex("[a-z]+", followed_by = r"\(", disambiguate_with = "print(")]
+    Call,
+
+    #[token("(")]
+    Open,
+
+    #[regex(r"[0-9]+\.[0-9]+", disambiguate_with = b"1.5")]
+    Float,
+
+    #[regex("[0-9]+", disambiguate_with = "15")]
+    Integer,
+}
+
+#[test]
+fn witnesses_lex_as_declared() {
+    assert_lex(
+        "let lets le print( 1.5 15",
+        &[
+            (Ok(Token::Let), "let", 0..3),
+            (Ok(Token::Ident), "lets", 4..8),
+            (Ok(Token::Ident), "le", 9..11),
+            (Ok(Token::Call), "print", 12..17),
+            (Ok(Token::Open), "(", 17..18),
+            (Ok(Token::Float), "1.5", 19..22),
+            (Ok(Token::Integer), "15", 23..25),
+        ],
+    );
+}
@@ -0,0 +1,53 @@
+use logos::Logos;
+
+#[derive(Logos, Debug, PartialEq)]
+#[logos(display)]
+enum Token<'a> {
+    #[regex(r"[ \t\n]+", logos::skip)]
+    Whitespace,
+
+    #[token("(")]
+    Open,
+
+    #[token(")")]
+    Close,
+
+    #[token("+")]
+    #[token("plus")]
+    Plus,
+
+    #[regex("[a-z]+")]
+    Ident(&'a str),
+
+    #[regex("[0-9]+")]
+    #[logos(name = "number literal")]
+    Number,
+}
+
+#[test]
+fn names() {
+    assert_eq!(Token::NAME_Open, "(");
+    assert_eq!(Token::NAME_Close, ")");
+    assert_eq!(Token::NAME_Plus, "Plus");
+    assert_eq!(Token::NAME_Ident, "Ident");
+    assert_eq!(Token::NAME_Number, "number literal");
+    assert_eq!(Token::NAME_Whitespace, "Whitespace");
+}
+
+#[test]
+fn display() {
+    assert_eq!(Token::Close.to_string(), ")");
+    assert_eq!(Token::Ident("foo").to_string(), "Ident");
+    assert_eq!(Token::Ident("foo").name(), "Ident");
+}
+
+#[test]
+fn diagnostics() {
+    let expected = Token::Close;
+    let found = Token::lexer("( x + 1").nth(2).unwrap().unwrap();
+
+    assert_eq!(
+        format!("expected `{}`, found `{}`", expected, found),
+        "expected `)`, found `Plus`"
+    );
+}
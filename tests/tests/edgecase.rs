@@ -476,8 +476,6 @@ mod maybe_in_loop {
 }
 
 mod unicode_error_split {
-    use super::*;
-
     #[test]
     fn test() {
         use logos::Logos;
@@ -504,6 +502,7 @@ mod merging_asymmetric_loops {
 
     #[test]
     fn must_compile() {
+        #[allow(dead_code)]
         #[derive(Logos)]
         pub enum Token2 {
             #[regex(r#"[!#$%&*+-./<=>?@\\^|~:]+"#)]
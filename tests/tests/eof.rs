@@ -0,0 +1,92 @@
+use logos::{Logos, UnknownToken};
+use tests::assert_lex;
+
+#[derive(Logos, Debug, Clone, PartialEq)]
+enum Token {
+    #[regex(r"[ \t\n]+", logos::skip)]
+    Whitespace,
+
+    #[regex("[a-z]+")]
+    Word,
+
+    #[logos(eof)]
+    Eof,
+}
+
+#[derive(Logos, Debug, Clone, PartialEq)]
+#[logos(codegen = "compact")]
+enum Compact {
+    #[regex(r"[ \t\n]+", logos::skip)]
+    Whitespace,
+
+    #[regex("[a-z]+")]
+    Word,
+
+    #[logos(eof)]
+    Eof,
+}
+
+#[test]
+fn empty_source() {
+    assert_lex("", &[(Ok(Token::Eof), "", 0..0)]);
+    assert_lex("", &[(Ok(Compact::Eof), "", 0..0)]);
+}
+
+#[test]
+fn after_tokens() {
+    assert_lex(
+        "ab cd",
+        &[
+            (Ok(Token::Word), "ab", 0..2),
+            (Ok(Token::Word), "cd", 3..5),
+            (Ok(Token::Eof), "", 5..5),
+        ],
+    );
+    assert_lex(
+        "ab cd",
+        &[
+            (Ok(Compact::Word), "ab", 0..2),
+            (Ok(Compact::Word), "cd", 3..5),
+            (Ok(Compact::Eof), "", 5..5),
+        ],
+    );
+}
+
+#[test]
+fn after_skipped_input() {
+    assert_lex(
+        "ab \n\t",
+        &[(Ok(Token::Word), "ab", 0..2), (Ok(Token::Eof), "", 5..5)],
+    );
+}
+
+#[test]
+fn after_error() {
+    assert_lex(
+        "ab!",
+        &[
+            (Ok(Token::Word), "ab", 0..2),
+            (Err(UnknownToken), "!", 2..3),
+            (Ok(Token::Eof), "", 3..3),
+        ],
+    );
+}
+
+#[test]
+fn only_once() {
+    let mut lexer = Token::lexer("ab");
+
+    assert_eq!(lexer.next(), Some(Ok(Token::Word)));
+    assert_eq!(lexer.next(), Some(Ok(Token::Eof)));
+    assert_eq!(lexer.next(), None);
+    assert_eq!(lexer.next(), None);
+
+    // Clones remember that the end was reached, while a reset starts over.
+    assert_eq!(lexer.clone().next(), None);
+
+    lexer.reset("cd");
+
+    assert_eq!(lexer.next(), Some(Ok(Token::Word)));
+    assert_eq!(lexer.next(), Some(Ok(Token::Eof)));
+    assert_eq!(lexer.next(), None);
+}
@@ -0,0 +1,98 @@
+use logos::{Logos, SpannedError};
+use tests::assert_lex;
+
+mod spanned_error {
+    use super::*;
+
+    #[derive(Logos, Debug, Clone, Copy, PartialEq)]
+    #[logos(error = SpannedError<'a>)]
+    enum Token<'a> {
+        #[regex(r"[ \t\n]+", logos::skip)]
+        Whitespace,
+
+        #[regex("[a-z]+")]
+        Ident(&'a str),
+
+        #[token("->")]
+        Arrow,
+    }
+
+    #[test]
+    fn records_span_and_slice() {
+        assert_lex(
+            "foo ? bar",
+            &[
+                (Ok(Token::Ident("foo")), "foo", 0..3),
+                (
+                    Err(SpannedError {
+                        span: 4..5,
+                        slice: "?",
+                        at_end: false,
+                    }),
+                    "?",
+                    4..5,
+                ),
+                (Ok(Token::Ident("bar")), "bar", 6..9),
+            ],
+        );
+    }
+
+    #[test]
+    fn at_end() {
+        assert_lex(
+            "foo -",
+            &[
+                (Ok(Token::Ident("foo")), "foo", 0..3),
+                (
+                    Err(SpannedError {
+                        span: 4..5,
+                        slice: "-",
+                        at_end: true,
+                    }),
+                    "-",
+                    4..5,
+                ),
+            ],
+        );
+    }
+
+    #[test]
+    fn display() {
+        let error = Token::lexer("%").next().unwrap().unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "unknown token \"%\" at 0..1 (at end of input)"
+        );
+    }
+}
+
+mod bytes {
+    use super::*;
+
+    type ByteError<'s> = SpannedError<'s, [u8]>;
+
+    #[derive(Logos, Debug, Clone, Copy, PartialEq)]
+    #[logos(error = ByteError<'s>)]
+    enum Token {
+        #[token(b"\x00")]
+        Zero,
+    }
+
+    #[test]
+    fn byte_slices() {
+        let mut lex = Token::lexer(b"\x00\xFF\x00");
+
+        assert_eq!(lex.next(), Some(Ok(Token::Zero)));
+        assert_eq!(
+            lex.next(),
+            Some(Err(SpannedError {
+                span: 1..2,
+                slice: &b"\xFF"[..],
+                at_end: false,
+            }))
+        );
+        assert_eq!(lex.next(), Some(Ok(Token::Zero)));
+        assert_eq!(lex.next(), None);
+    }
+}
@@ -0,0 +1,81 @@
+use logos::{Logos, UnknownToken};
+use tests::assert_lex;
+
+#[derive(Logos, Debug, PartialEq)]
+#[logos(test_match)]
+enum Token {
+    #[regex(r"[ \t\n]+", logos::skip)]
+    Whitespace,
+
+    #[regex("[a-z]+", followed_by = r"[ \t]*\(")]
+    Call,
+
+    #[regex("[a-z]+")]
+    Name,
+
+    #[token("(")]
+    Open,
+
+    #[token(")")]
+    Close,
+
+    #[regex("[0-9]+", followed_by = "[^a-z]")]
+    Number,
+
+    #[token("..")]
+    Range,
+
+    #[token("1", followed_by = r"\.\.")]
+    One,
+}
+
+#[test]
+fn trailing_context_is_not_consumed() {
+    assert_lex(
+        "print (x) f(y)",
+        &[
+            (Ok(Token::Call), "print", 0..5),
+            (Ok(Token::Open), "(", 6..7),
+            (Ok(Token::Name), "x", 7..8),
+            (Ok(Token::Close), ")", 8..9),
+            (Ok(Token::Call), "f", 10..11),
+            (Ok(Token::Open), "(", 11..12),
+            (Ok(Token::Name), "y", 12..13),
+            (Ok(Token::Close), ")", 13..14),
+        ],
+    );
+}
+
+#[test]
+fn errors_without_fallback() {
+    assert_lex(
+        "12 34ab 56",
+        &[
+            (Ok(Token::Number), "12", 0..2),
+            (Err(UnknownToken), "34", 3..5),
+            (Ok(Token::Name), "ab", 5..7),
+            // There's nothing after the last number, so its trailing context doesn't match either.
+            (Err(UnknownToken), "56", 8..10),
+        ],
+    );
+}
+
+#[test]
+fn tokens() {
+    assert_lex(
+        "1..2 1\n",
+        &[
+            (Ok(Token::One), "1", 0..1),
+            (Ok(Token::Range), "..", 1..3),
+            (Ok(Token::Number), "2", 3..4),
+            (Ok(Token::Number), "1", 5..6),
+        ],
+    );
+}
+
+#[test]
+fn test_match() {
+    assert_eq!(Token::test_match_Call("f("), Some(1));
+    assert_eq!(Token::test_match_Call("f"), None);
+    assert_eq!(Token::test_match_One("1.."), Some(1));
+}
@@ -0,0 +1,78 @@
+use logos::fuzz::{check, roundtrip, roundtrip_utf8, Violation};
+use logos::Logos;
+
+#[derive(Logos, Debug, PartialEq)]
+enum Token {
+    #[regex(r"[ \t\n]+", logos::skip)]
+    Whitespace,
+
+    #[regex("[a-z]+")]
+    Word,
+
+    #[regex("[0-9]+")]
+    Number,
+
+    #[logos(eof)]
+    Eof,
+}
+
+// Goes back to the start of the source the first time it sees a `!`.
+#[derive(Logos, Debug, PartialEq)]
+#[logos(extras = bool)]
+enum Restart {
+    #[regex("[a-z ]+")]
+    Text,
+
+    #[token("!", |lex| {
+        if !lex.extras {
+            lex.extras = true;
+            let source = lex.source();
+            lex.reset(source);
+        }
+    })]
+    Bang,
+}
+
+#[test]
+fn well_behaved() {
+    assert_eq!(check::<Token>(""), Ok(1));
+    assert_eq!(check::<Token>("  "), Ok(1));
+    assert_eq!(check::<Token>("ab 12 ? é  "), Ok(5));
+
+    roundtrip::<Token>("ab\n\t12");
+}
+
+#[test]
+fn arbitrary_bytes() {
+    roundtrip_utf8::<Token>(b"ab 12 \xFF cd");
+    roundtrip_utf8::<Token>(b"\xC3\xA9 \xC3");
+
+    for byte in 0..=255u8 {
+        roundtrip_utf8::<Token>(&[byte, b' ', byte]);
+    }
+}
+
+#[test]
+fn violations() {
+    assert_eq!(
+        check::<Restart>("ab!"),
+        Err(Violation::Gap {
+            token: 1,
+            expected: 2,
+            found: 0,
+        })
+    );
+    assert_eq!(
+        check::<Restart>("!ab"),
+        Err(Violation::Stuck {
+            token: 0,
+            span: 0..0,
+        })
+    );
+}
+
+#[test]
+#[should_panic(expected = "token 1 starts at 0, but the previous token ended at 2")]
+fn roundtrip_panics() {
+    roundtrip::<Restart>("ab!");
+}
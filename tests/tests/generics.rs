@@ -0,0 +1,135 @@
+use logos::{Lexer, Logos};
+use std::collections::HashMap;
+
+/// Something that turns identifiers into handles, like a string interner.
+trait Interner: Default {
+    type Handle: Copy + PartialEq + std::fmt::Debug;
+
+    fn intern(&mut self, name: &str) -> Self::Handle;
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct Numbered(HashMap<String, u32>);
+
+impl Interner for Numbered {
+    type Handle = u32;
+
+    fn intern(&mut self, name: &str) -> u32 {
+        let next = self.0.len() as u32;
+
+        *self.0.entry(name.to_owned()).or_insert(next)
+    }
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct Echo;
+
+impl Interner for Echo {
+    type Handle = usize;
+
+    fn intern(&mut self, name: &str) -> usize {
+        name.len()
+    }
+}
+
+fn intern<'s, I: Interner>(lex: &mut Lexer<'s, Token<I>>) -> I::Handle {
+    lex.extras.intern(lex.slice())
+}
+
+#[derive(Logos, Debug, PartialEq)]
+#[logos(extras = I)]
+enum Token<I: Interner> {
+    #[regex(r"[ \t\n]+", logos::skip)]
+    Whitespace,
+
+    #[token("=")]
+    Equals,
+
+    #[regex("[a-z]+", intern)]
+    Ident(I::Handle),
+
+    #[regex("[0-9]+", |lex| lex.slice().parse().ok())]
+    Number(u64),
+}
+
+#[test]
+fn one_enum_for_several_interners() {
+    let tokens: Vec<_> = Token::<Numbered>::lexer("a = b a").collect();
+
+    assert_eq!(
+        tokens,
+        &[
+            Ok(Token::Ident(0)),
+            Ok(Token::Equals),
+            Ok(Token::Ident(1)),
+            Ok(Token::Ident(0)),
+        ]
+    );
+
+    let tokens: Vec<_> = Token::<Echo>::lexer("abc = 12").collect();
+
+    assert_eq!(
+        tokens,
+        &[
+            Ok(Token::Ident(3)),
+            Ok(Token::Equals),
+            Ok(Token::Number(12))
+        ]
+    );
+}
+
+mod lifetimes {
+    use super::*;
+
+    #[derive(Logos, Debug, PartialEq)]
+    #[logos(display)]
+    enum Token<'a, T>
+    where
+        T: From<&'a str>,
+    {
+        #[regex(r"[ \t\n]+", logos::skip)]
+        Whitespace,
+
+        #[regex("[a-z]+", |lex| T::from(lex.slice()))]
+        Word(T),
+
+        #[regex("[0-9]+")]
+        Number(&'a str),
+    }
+
+    #[test]
+    fn bounds_can_use_the_lifetime() {
+        let tokens: Vec<_> = Token::<String>::lexer("foo 42").collect();
+
+        assert_eq!(
+            tokens,
+            &[
+                Ok(Token::Word(String::from("foo"))),
+                Ok(Token::Number("42"))
+            ]
+        );
+        assert_eq!(Token::<&str>::Word("foo").to_string(), "Word");
+    }
+}
+
+mod mixed {
+    use super::*;
+
+    #[derive(Logos, Debug, PartialEq)]
+    #[logos(type S = String, test_match)]
+    enum Token<S: Default, T: Default> {
+        #[token("s", |_| String::from("s"))]
+        S(S),
+
+        #[token("t", |_| T::default())]
+        T(T),
+    }
+
+    #[test]
+    fn concrete_and_generic_parameters() {
+        let tokens: Vec<_> = Token::<String, u8>::lexer("st").collect();
+
+        assert_eq!(tokens, &[Ok(Token::S(String::from("s"))), Ok(Token::T(0))]);
+        assert_eq!(Token::<String, u8>::test_match_T("t"), Some(1));
+    }
+}
@@ -0,0 +1,92 @@
+use logos::guard::Histogram;
+use logos::{LexerExt, Logos, Span, UnknownToken};
+
+#[derive(Logos, Debug, Clone, PartialEq)]
+enum Token {
+    #[regex(r"[ \t\n]+", logos::skip)]
+    Whitespace,
+
+    #[regex("[a-z]+")]
+    Word,
+
+    #[logos(eof)]
+    Eof,
+}
+
+type Tokens = Vec<(Result<Token, UnknownToken>, Span)>;
+
+fn lex<'s, L>(lexer: &mut L) -> Tokens
+where
+    L: LexerExt<'s, Token = Token> + Iterator<Item = Result<Token, UnknownToken>>,
+{
+    let mut tokens = Vec::new();
+
+    while let Some(token) = lexer.next() {
+        tokens.push((token, lexer.span()));
+    }
+
+    tokens
+}
+
+#[test]
+fn ordinary_input() {
+    let source = "some words ! and ? more words";
+    let mut lexer = Token::lexer(source).guard(2, 1);
+
+    assert_eq!(lex(&mut lexer), lex(&mut Token::lexer(source)));
+    assert!(!lexer.is_coalescing());
+    assert_eq!(lexer.histogram().tokens(), 8);
+    assert_eq!(lexer.histogram().errors(), 2);
+    assert_eq!(lexer.histogram().bytes(), 23);
+}
+
+#[test]
+fn coalescing() {
+    let mut lexer = Token::lexer("!! a !?! ! bc #").guard(2, 1000);
+
+    lexer.set_coalescing(true);
+
+    assert_eq!(
+        lex(&mut lexer),
+        &[
+            (Err(UnknownToken), 0..2),
+            (Ok(Token::Word), 3..4),
+            (Err(UnknownToken), 5..10),
+            (Ok(Token::Word), 11..13),
+            (Err(UnknownToken), 14..15),
+            (Ok(Token::Eof), 15..15),
+        ],
+    );
+    assert_eq!(lexer.histogram().errors(), 7);
+}
+
+#[test]
+fn flood() {
+    let source = "!".repeat(10_000);
+    let tokens = lex(&mut Token::lexer(&source).guard(2, 16));
+
+    // The 16th error is the first one that's judged, and starts the run.
+    assert_eq!(tokens.len(), 17);
+    assert_eq!(tokens[14], (Err(UnknownToken), 14..15));
+    assert_eq!(tokens[15], (Err(UnknownToken), 15..10_000));
+    assert_eq!(tokens[16], (Ok(Token::Eof), 10_000..10_000));
+}
+
+#[test]
+fn histogram() {
+    let mut histogram = Histogram::new();
+
+    assert_eq!(histogram.average(), None);
+
+    for len in [0, 1, 2, 3, 4, 7, 8, 1 << 20] {
+        histogram.record(len, false);
+    }
+
+    assert_eq!(&histogram.buckets()[..5], &[1, 1, 2, 2, 1]);
+    assert_eq!(histogram.bucket(Histogram::BUCKETS - 1), 1);
+    assert_eq!(histogram.bucket(Histogram::BUCKETS), 0);
+    assert_eq!(histogram.average(), Some(((1 << 20) + 25) as f64 / 8.0));
+    assert!(!histogram.is_degenerate(2, 1));
+    assert!(histogram.is_degenerate(1 << 20, 1));
+    assert!(!histogram.is_degenerate(1 << 20, 9));
+}
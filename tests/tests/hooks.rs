@@ -0,0 +1,64 @@
+use logos::{Lexer, Logos};
+
+#[derive(Default, Debug, PartialEq)]
+struct Log {
+    events: Vec<String>,
+}
+
+fn on_token<'s>(lex: &mut Lexer<'s, Token<'s>>) {
+    let event = format!("token {}", lex.slice());
+
+    lex.extras.events.push(event);
+}
+
+#[derive(Logos, Debug, PartialEq)]
+#[logos(extras = Log, on_token = on_token)]
+#[logos(on_error = |lex| {
+    let event = format!("error {:?}", lex.span());
+
+    lex.extras.events.push(event);
+})]
+#[logos(on_eof = |lex| lex.extras.events.push(String::from("eof")))]
+enum Token<'s> {
+    #[regex(r"[ \t\n]+", logos::skip)]
+    Whitespace,
+
+    #[regex("[a-z]+")]
+    Word(&'s str),
+
+    #[token("(", |lex| { lex.extras.events.push(String::from("callback")); })]
+    Open,
+}
+
+#[test]
+fn hooks_run_after_each_token() {
+    let mut lexer = Token::lexer("ab ( ? c");
+
+    assert_eq!(lexer.by_ref().count(), 4);
+    assert_eq!(lexer.next(), None);
+    assert_eq!(
+        lexer.extras.events,
+        &[
+            "token ab",
+            "callback",
+            "token (",
+            "error 5..6",
+            "token c",
+            "eof",
+        ],
+    );
+}
+
+#[test]
+fn segments_count_as_tokens() {
+    let mut slices = Token::lexer("a ?").slices();
+
+    assert_eq!(slices.by_ref().count(), 3);
+
+    let lexer = logos::LexerExt::into_lexer(slices);
+
+    assert_eq!(
+        lexer.extras.events,
+        &["token a", "token  ", "error 2..3", "eof"]
+    );
+}
@@ -0,0 +1,138 @@
+//! Items with the same names that generated code used to define for itself, to make sure that none of them are
+//! shadowed by (or clash with) the derive's output.
+
+#![allow(dead_code, non_upper_case_globals)]
+
+use logos::Logos;
+use tests::assert_lex;
+
+struct Lexer;
+struct CallbackResult;
+trait LexerInternal {}
+enum Jump {
+    J1,
+}
+
+const COMPACT_TABLE_0: [u8; 256] = [0; 256];
+const LUT: u64 = 0;
+
+macro_rules! _fast_loop {
+    () => {
+        compile_error!("This macro should never be called")
+    };
+}
+
+fn _end(_: &mut logos::Lexer<Token>) -> u8 {
+    1
+}
+
+fn _error(_: &mut logos::Lexer<Token>) -> u8 {
+    2
+}
+
+fn goto1(_: &mut logos::Lexer<Token>) -> u8 {
+    3
+}
+
+fn pattern0(_: &mut logos::Lexer<Token>) -> u8 {
+    4
+}
+
+fn callback() -> u8 {
+    5
+}
+
+#[derive(Logos, Debug, PartialEq)]
+enum Token {
+    #[regex(r"[ \t\n]+", logos::skip)]
+    Whitespace,
+
+    #[token("end", _end)]
+    #[token("error", _error)]
+    #[token("goto", goto1)]
+    #[token("pattern", pattern0)]
+    #[token("callback", |_| callback())]
+    Value(u8),
+
+    #[regex("[a-z]+")]
+    Ident,
+
+    #[regex("[0-9]+")]
+    Number,
+}
+
+#[derive(Logos, Debug, PartialEq)]
+enum Other {
+    #[regex("[a-z]+", |_| Lexer)]
+    Lexer(Lexer),
+
+    #[regex("[0-9]+", |_| CallbackResult)]
+    CallbackResult(CallbackResult),
+
+    #[regex(r"[ \t\n]+", |_| Jump::J1)]
+    Jump(Jump),
+}
+
+impl PartialEq for Lexer {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+impl PartialEq for CallbackResult {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+impl PartialEq for Jump {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+impl std::fmt::Debug for Lexer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Lexer")
+    }
+}
+
+impl std::fmt::Debug for CallbackResult {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("CallbackResult")
+    }
+}
+
+impl std::fmt::Debug for Jump {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Jump")
+    }
+}
+
+#[test]
+fn callbacks_are_not_shadowed() {
+    assert_lex(
+        "end error goto pattern callback foo 10",
+        &[
+            (Ok(Token::Value(1)), "end", 0..3),
+            (Ok(Token::Value(2)), "error", 4..9),
+            (Ok(Token::Value(3)), "goto", 10..14),
+            (Ok(Token::Value(4)), "pattern", 15..22),
+            (Ok(Token::Value(5)), "callback", 23..31),
+            (Ok(Token::Ident), "foo", 32..35),
+            (Ok(Token::Number), "10", 36..38),
+        ],
+    );
+}
+
+#[test]
+fn types_are_not_shadowed() {
+    assert_lex(
+        "foo 42",
+        &[
+            (Ok(Other::Lexer(Lexer)), "foo", 0..3),
+            (Ok(Other::Jump(Jump::J1)), " ", 3..4),
+            (Ok(Other::CallbackResult(CallbackResult)), "42", 4..6),
+        ],
+    );
+}
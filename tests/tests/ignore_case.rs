@@ -226,3 +226,119 @@ mod ignore_case {
         )
     }
 }
+
+mod full_case_folding {
+    use logos::{Logos, UnknownToken};
+    use tests::assert_lex;
+
+    #[derive(Logos, Debug, PartialEq, Eq)]
+    enum Words {
+        #[regex(" +", logos::skip)]
+        Whitespace,
+
+        #[token("straße", ignore(case))]
+        Strasse,
+        #[token("ﬁn", ignore(case))]
+        Fin,
+        #[regex("[aßo]+!", ignore(case))]
+        Letters,
+    }
+
+    #[test]
+    fn tokens() {
+        assert_lex(
+            "straße STRASSE Strasse STRAẞE fin FIN ﬁn strase",
+            &[
+                (Ok(Words::Strasse), "straße", 0..7),
+                (Ok(Words::Strasse), "STRASSE", 8..15),
+                (Ok(Words::Strasse), "Strasse", 16..23),
+                (Ok(Words::Strasse), "STRAẞE", 24..32),
+                (Ok(Words::Fin), "fin", 33..36),
+                (Ok(Words::Fin), "FIN", 37..40),
+                (Ok(Words::Fin), "ﬁn", 41..45),
+                (Err(UnknownToken), "s", 46..47),
+                (Err(UnknownToken), "t", 47..48),
+                (Err(UnknownToken), "r", 48..49),
+                (Err(UnknownToken), "a", 49..50),
+                (Err(UnknownToken), "s", 50..51),
+                (Err(UnknownToken), "e", 51..52),
+            ],
+        )
+    }
+
+    #[test]
+    fn classes() {
+        assert_lex(
+            "aßo! ASSO! ssSS! ẞ!",
+            &[
+                (Ok(Words::Letters), "aßo!", 0..5),
+                (Ok(Words::Letters), "ASSO!", 6..11),
+                (Ok(Words::Letters), "ssSS!", 12..17),
+                (Ok(Words::Letters), "ẞ!", 18..22),
+            ],
+        )
+    }
+}
+
+mod subpatterns {
+    use logos::{Logos, UnknownToken};
+    use tests::assert_lex;
+
+    #[derive(Logos, Debug, PartialEq, Eq)]
+    #[logos(subpattern hex = "0x[0-9a-f]+", ignore(ascii_case))]
+    #[logos(subpattern greek = "λ[αβ]", ignore(case))]
+    #[logos(subpattern bytes = b"0b[01]+", ignore(ascii_case))]
+    enum Mixed {
+        #[regex(" +", logos::skip)]
+        Whitespace,
+
+        // Only the prefix ignores case, the identifier that follows it doesn't.
+        #[regex("(?&hex)_[a-z]+")]
+        Hex,
+
+        #[regex("(?&greek)!")]
+        Greek,
+
+        #[regex("(?&bytes)u")]
+        Binary,
+    }
+
+    #[test]
+    fn only_subpattern_ignores_case() {
+        assert_lex(
+            "0xFF_abc 0XaB_cd 0xff_ABC ΛΑ! λβ! 0B01u 0b1U",
+            &[
+                (Ok(Mixed::Hex), "0xFF_abc", 0..8),
+                (Ok(Mixed::Hex), "0XaB_cd", 9..16),
+                (Err(UnknownToken), "0xff", 17..21),
+                (Err(UnknownToken), "_", 21..22),
+                (Err(UnknownToken), "A", 22..23),
+                (Err(UnknownToken), "B", 23..24),
+                (Err(UnknownToken), "C", 24..25),
+                (Ok(Mixed::Greek), "ΛΑ!", 26..31),
+                (Ok(Mixed::Greek), "λβ!", 32..37),
+                (Ok(Mixed::Binary), "0B01u", 38..43),
+                (Err(UnknownToken), "0b1", 44..47),
+                (Err(UnknownToken), "U", 47..48),
+            ],
+        )
+    }
+
+    #[derive(Logos, Debug, PartialEq, Eq)]
+    #[logos(subpattern kw = "kelvin", ignore(ascii_case))]
+    enum Ascii {
+        #[regex("(?&kw)")]
+        Kelvin,
+    }
+
+    #[test]
+    fn ascii_case_only_folds_ascii() {
+        // U+212A KELVIN SIGN case folds to `k` with Unicode rules, but not ASCII ones.
+        let mut lex = Ascii::lexer("KeLvIn\u{212A}elvin");
+
+        assert_eq!(lex.next(), Some(Ok(Ascii::Kelvin)));
+        assert_eq!(lex.slice(), "KeLvIn");
+        assert_eq!(lex.next(), Some(Err(UnknownToken)));
+        assert_eq!(lex.span().start, 6);
+    }
+}
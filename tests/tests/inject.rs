@@ -0,0 +1,149 @@
+use logos::{Lexer, LexerExt, Logos, Span, UnknownToken};
+
+#[derive(Logos, Debug, Clone, PartialEq)]
+enum Token {
+    #[regex(r"[ \t\n]+", logos::skip)]
+    Whitespace,
+
+    #[regex("[0-9]+")]
+    Number,
+
+    #[regex("[a-z]")]
+    Variable,
+
+    #[token("(")]
+    Open,
+
+    #[token(")")]
+    Close,
+
+    #[token("*")]
+    Times,
+
+    #[token(";")]
+    Semicolon,
+}
+
+fn collect<'s, L>(mut lexer: L) -> Vec<(Result<Token, UnknownToken>, Span)>
+where
+    L: LexerExt<'s, Token = Token> + Iterator<Item = Result<Token, UnknownToken>>,
+{
+    let mut out = Vec::new();
+
+    while let Some(token) = lexer.next() {
+        out.push((token, lexer.span()));
+    }
+
+    out
+}
+
+/// Insert `*` between two operands that are written next to each other, like `2x` or `(a)(b)`.
+fn implicit_times(prev: Option<&Token>, next: Option<&Token>, _: &Lexer<Token>) -> Option<Token> {
+    use Token::*;
+
+    match (prev?, next?) {
+        (Number | Variable | Close, Number | Variable | Open) => Some(Times),
+        _ => None,
+    }
+}
+
+#[test]
+fn implicit_multiplication() {
+    let tokens = collect(Token::lexer("2x(y + 1)(z)").inject_with(implicit_times));
+
+    assert_eq!(
+        tokens,
+        &[
+            (Ok(Token::Number), 0..1),
+            (Ok(Token::Times), 1..1),
+            (Ok(Token::Variable), 1..2),
+            (Ok(Token::Times), 2..2),
+            (Ok(Token::Open), 2..3),
+            (Ok(Token::Variable), 3..4),
+            (Err(UnknownToken), 5..6),
+            (Ok(Token::Number), 7..8),
+            (Ok(Token::Close), 8..9),
+            (Ok(Token::Times), 9..9),
+            (Ok(Token::Open), 9..10),
+            (Ok(Token::Variable), 10..11),
+            (Ok(Token::Close), 11..12),
+        ]
+    );
+}
+
+#[test]
+fn slices_of_injected_tokens_are_empty() {
+    let mut lexer = Token::lexer("2 x").inject_with(implicit_times);
+
+    assert_eq!(lexer.next(), Some(Ok(Token::Number)));
+    assert_eq!(lexer.slice(), "2");
+    assert_eq!(lexer.next(), Some(Ok(Token::Times)));
+    assert_eq!(lexer.slice(), "");
+    assert_eq!(lexer.remainder(), " x");
+    assert_eq!(lexer.next(), Some(Ok(Token::Variable)));
+    assert_eq!(lexer.slice(), "x");
+    assert_eq!(lexer.next(), None);
+}
+
+#[test]
+fn gap_is_visible_to_the_callback() {
+    let mut gaps = Vec::new();
+    let tokens: Vec<_> = Token::lexer(" 1 \n2\n\n")
+        .inject_with(|prev, next, lexer| {
+            gaps.push((prev.cloned(), next.cloned(), lexer.slice().to_owned()));
+
+            match prev {
+                Some(Token::Number) if lexer.slice().contains('\n') => Some(Token::Semicolon),
+                _ => None,
+            }
+        })
+        .collect();
+
+    assert_eq!(
+        tokens,
+        &[
+            Ok(Token::Number),
+            Ok(Token::Semicolon),
+            Ok(Token::Number),
+            Ok(Token::Semicolon),
+        ]
+    );
+    assert_eq!(
+        gaps,
+        &[
+            (None, Some(Token::Number), " ".to_owned()),
+            (Some(Token::Number), Some(Token::Number), " \n".to_owned()),
+            (Some(Token::Number), None, "\n\n".to_owned()),
+        ]
+    );
+}
+
+#[test]
+fn at_end_of_input() {
+    let mut lexer = Token::lexer("x ").inject_with(|prev, next, _| match (prev, next) {
+        (Some(_), None) => Some(Token::Semicolon),
+        _ => None,
+    });
+
+    assert_eq!(lexer.next(), Some(Ok(Token::Variable)));
+    assert_eq!(lexer.next(), Some(Ok(Token::Semicolon)));
+    assert_eq!(lexer.span(), 1..1);
+    assert_eq!(lexer.next(), None);
+    assert_eq!(lexer.next(), None);
+}
+
+#[test]
+fn empty_input() {
+    let mut calls = 0;
+    let tokens: Vec<_> = Token::lexer("")
+        .inject_with(|prev, next, _| {
+            calls += 1;
+            assert_eq!((prev, next), (None, None));
+
+            None
+        })
+        .collect();
+
+    assert!(tokens.is_empty());
+    assert_eq!(calls, 1);
+}
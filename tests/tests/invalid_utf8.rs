@@ -0,0 +1,115 @@
+use logos::{Logos, UnknownToken};
+use tests::assert_lex;
+
+#[derive(Logos, Debug, PartialEq)]
+#[logos(source = [u8], invalid_utf8 = "error")]
+enum Error {
+    #[regex(r"[ \t]+", logos::skip)]
+    Whitespace,
+
+    #[regex(r"\w+")]
+    Word,
+
+    #[token(b"\xFF\xFE")]
+    Bom,
+}
+
+#[derive(Logos, Debug, PartialEq)]
+#[logos(source = [u8], invalid_utf8 = "skip")]
+enum Skip {
+    #[regex(r"[ \t]+", logos::skip)]
+    Whitespace,
+
+    #[regex(r"\w+")]
+    Word,
+}
+
+#[derive(Logos, Debug, PartialEq)]
+#[logos(source = [u8], invalid_utf8 = "replace")]
+enum Replace {
+    #[regex(r"[ \t]+", logos::skip)]
+    Whitespace,
+
+    #[regex(r"\w+")]
+    Word,
+
+    #[regex(r"[^\w\s]", |lex| lex.slice().len())]
+    Other(usize),
+}
+
+const SOURCE: &[u8] = b"ab\xFFcd \xE2\x82 e\xF0\x9F\x98x \xED\xA0\x80";
+
+#[test]
+fn error_per_sequence() {
+    assert_lex(
+        SOURCE,
+        &[
+            (Ok(Error::Word), b"ab", 0..2),
+            (Err(UnknownToken), b"\xFF", 2..3),
+            (Ok(Error::Word), b"cd", 3..5),
+            (Err(UnknownToken), b"\xE2\x82", 6..8),
+            (Ok(Error::Word), b"e", 9..10),
+            (Err(UnknownToken), b"\xF0\x9F\x98", 10..13),
+            (Ok(Error::Word), b"x", 13..14),
+            // Surrogates aren't valid UTF-8, so no prefix of one is either.
+            (Err(UnknownToken), b"\xED", 15..16),
+            (Err(UnknownToken), b"\xA0", 16..17),
+            (Err(UnknownToken), b"\x80", 17..18),
+        ],
+    );
+}
+
+#[test]
+fn definitions_matching_bytes_go_first() {
+    assert_lex(
+        &b"\xFF\xFE\xFF"[..],
+        &[
+            (Ok(Error::Bom), b"\xFF\xFE", 0..2),
+            (Err(UnknownToken), b"\xFF", 2..3),
+        ],
+    );
+}
+
+#[test]
+fn skipped() {
+    assert_lex(
+        SOURCE,
+        &[
+            (Ok(Skip::Word), b"ab", 0..2),
+            (Ok(Skip::Word), b"cd", 3..5),
+            (Ok(Skip::Word), b"e", 9..10),
+            (Ok(Skip::Word), b"x", 13..14),
+        ],
+    );
+}
+
+#[test]
+fn replaced() {
+    assert_lex(
+        SOURCE,
+        &[
+            (Ok(Replace::Word), b"ab", 0..2),
+            (Ok(Replace::Other(1)), b"\xFF", 2..3),
+            (Ok(Replace::Word), b"cd", 3..5),
+            (Ok(Replace::Other(2)), b"\xE2\x82", 6..8),
+            (Ok(Replace::Word), b"e", 9..10),
+            (Ok(Replace::Other(3)), b"\xF0\x9F\x98", 10..13),
+            (Ok(Replace::Word), b"x", 13..14),
+            (Ok(Replace::Other(1)), b"\xED", 15..16),
+            (Ok(Replace::Other(1)), b"\xA0", 16..17),
+            (Ok(Replace::Other(1)), b"\x80", 17..18),
+        ],
+    );
+}
+
+#[test]
+fn valid_input_is_unaffected() {
+    assert_lex(
+        "é ☃ €".as_bytes(),
+        &[
+            (Ok(Replace::Word), "é".as_bytes(), 0..2),
+            (Ok(Replace::Other(3)), "☃".as_bytes(), 3..6),
+            (Ok(Replace::Other(3)), "€".as_bytes(), 7..10),
+        ],
+    );
+}
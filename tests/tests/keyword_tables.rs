@@ -0,0 +1,159 @@
+use logos::Logos;
+use tests::assert_lex;
+
+macro_rules! keywords {
+    ($(#[$attr:meta])*) => {
+        #[derive(Logos, Debug, PartialEq)]
+        $(#[$attr])*
+        pub enum Token {
+            #[regex(r"[ \t\n]+", logos::skip)]
+            Whitespace,
+
+            #[token("as")] As,
+            #[token("async")] Async,
+            #[token("await")] Await,
+            #[token("break")] Break,
+            #[token("const")] Const,
+            #[token("continue")] Continue,
+            #[token("crate")] Crate,
+            #[token("dyn")] Dyn,
+            #[token("else")] Else,
+            #[token("enum")] Enum,
+            #[token("extern")] Extern,
+            #[token("false")] False,
+            #[token("fn")] Fn,
+            #[token("for")] For,
+            #[token("if")] If,
+            #[token("impl")] Impl,
+            #[token("in")] In,
+            #[token("let")] Let,
+            #[token("loop")] Loop,
+            #[token("match")] Match,
+            #[token("mod")] Mod,
+            #[token("move")] Move,
+            #[token("mut")] Mut,
+            #[token("pub")] Pub,
+            #[token("ref")] Ref,
+            #[token("return")] Return,
+            #[token("self")] SelfValue,
+            #[token("Self")] SelfType,
+            #[token("static")] Static,
+            #[token("struct")] Struct,
+            #[token("super")] Super,
+            #[token("trait")] Trait,
+            #[token("true")] True,
+            #[token("type")] Type,
+            #[token("unsafe")] Unsafe,
+            #[token("use")] Use,
+            #[token("where")] Where,
+            #[token("while")] While,
+
+            // A keyword with a callback is looked up like any other.
+            #[token("yield", |lex| lex.span().start)]
+            Yield(usize),
+
+            // Another definition also matches these, so they stay in the state machine.
+            #[token("u8")]
+            U8,
+
+            #[regex("[a-z]+[0-9]+")]
+            Numbered,
+
+            // Identifiers win over this one, so it stays in the state machine too.
+            #[token("macro_rules", priority = 0)]
+            MacroRules,
+
+            // Longer tokens starting with a keyword still go through the state machine.
+            #[token("if!")]
+            IfBang,
+
+            #[regex("[a-zA-Z_][a-zA-Z0-9_]*")]
+            Ident,
+        }
+    };
+}
+
+mod table {
+    use super::*;
+
+    keywords!(#[logos(keyword_tables = 8, stats = "target/stats/keyword_tables.txt")]);
+
+    #[test]
+    fn keywords() {
+        assert_lex(
+            "fn main if! iff yield Self self u8 u16 macro_rules",
+            &[
+                (Ok(Token::Fn), "fn", 0..2),
+                (Ok(Token::Ident), "main", 3..7),
+                (Ok(Token::IfBang), "if!", 8..11),
+                (Ok(Token::Ident), "iff", 12..15),
+                (Ok(Token::Yield(16)), "yield", 16..21),
+                (Ok(Token::SelfType), "Self", 22..26),
+                (Ok(Token::SelfValue), "self", 27..31),
+                (Ok(Token::U8), "u8", 32..34),
+                (Ok(Token::Numbered), "u16", 35..38),
+                (Ok(Token::Ident), "macro_rules", 39..50),
+            ],
+        );
+    }
+
+    #[test]
+    fn same_tokens_as_the_state_machine() {
+        mod state_machine {
+            use super::*;
+
+            keywords!(#[logos(keyword_tables = 0, stats = "target/stats/keyword_tables_off.txt")]);
+        }
+
+        let source = "pub async fn f(x: &mut Self) -> impl Trait where Self: Sized { if! x.await else loop continue \
+                      break whiles _ as_ref u8 u8x Self_ matches yields }";
+        let expected: Vec<_> = state_machine::Token::lexer(source).spanned().collect();
+        let actual: Vec<_> = Token::lexer(source).spanned().collect();
+
+        // The two token types are different, but their variants have the same names.
+        assert_eq!(format!("{:?}", actual), format!("{:?}", expected));
+
+        let states = |file: &str| {
+            let path = format!("{}/target/stats/{}", env!("CARGO_MANIFEST_DIR"), file);
+            let report = std::fs::read_to_string(path).unwrap();
+            let line = report
+                .lines()
+                .find(|line| line.starts_with("states:"))
+                .unwrap();
+
+            line.split_whitespace()
+                .nth(1)
+                .unwrap()
+                .parse::<usize>()
+                .unwrap()
+        };
+
+        assert!(states("keyword_tables.txt") * 2 < states("keyword_tables_off.txt"));
+    }
+}
+
+mod compact {
+    use super::*;
+
+    keywords!(#[logos(keyword_tables = 8, codegen = "compact")]);
+
+    #[test]
+    fn keywords() {
+        assert_lex(
+            "let x = match y { _ => yield }",
+            &[
+                (Ok(Token::Let), "let", 0..3),
+                (Ok(Token::Ident), "x", 4..5),
+                (Err(Default::default()), "=", 6..7),
+                (Ok(Token::Match), "match", 8..13),
+                (Ok(Token::Ident), "y", 14..15),
+                (Err(Default::default()), "{", 16..17),
+                (Ok(Token::Ident), "_", 18..19),
+                (Err(Default::default()), "=", 20..21),
+                (Err(Default::default()), ">", 21..22),
+                (Ok(Token::Yield(23)), "yield", 23..28),
+                (Err(Default::default()), "}", 29..30),
+            ],
+        );
+    }
+}
@@ -0,0 +1,114 @@
+use logos::compat::lalrpop::{LexicalError, Spanned, Tokens};
+use logos::{LexerExt, Logos, UnknownToken};
+
+#[derive(Logos, Debug, Clone, PartialEq)]
+enum Token {
+    #[regex(r"[ \t\n]+", logos::skip)]
+    Whitespace,
+
+    #[token("+")]
+    Plus,
+
+    #[regex("[0-9]+", |lex| lex.slice().parse().ok())]
+    Number(u32),
+}
+
+#[derive(Debug, PartialEq)]
+enum ParseError<E> {
+    User(E),
+    Unexpected(Token, usize),
+    Eof,
+}
+
+/// A stand-in for a generated LALRPOP parser for `Sum = Number ("+" Number)*`, which only relies on the shape of the
+/// external lexer interface.
+fn parse<I, E>(tokens: I) -> Result<u32, ParseError<E>>
+where
+    I: IntoIterator<Item = Spanned<Token, usize, E>>,
+{
+    let mut tokens = tokens.into_iter();
+    let mut sum = 0;
+
+    loop {
+        match tokens.next() {
+            Some(Ok((_, Token::Number(number), _))) => sum += number,
+            Some(Ok((start, token, _))) => return Err(ParseError::Unexpected(token, start)),
+            Some(Err(error)) => return Err(ParseError::User(error)),
+            None => return Err(ParseError::Eof),
+        }
+
+        match tokens.next() {
+            Some(Ok((_, Token::Plus, _))) => continue,
+            Some(Ok((start, token, _))) => return Err(ParseError::Unexpected(token, start)),
+            Some(Err(error)) => return Err(ParseError::User(error)),
+            None => return Ok(sum),
+        }
+    }
+}
+
+#[test]
+fn triples() {
+    let tokens: Vec<_> = Tokens::new(Token::lexer("1 + 23")).collect();
+
+    assert_eq!(
+        tokens,
+        &[
+            Ok((0, Token::Number(1), 1)),
+            Ok((2, Token::Plus, 3)),
+            Ok((4, Token::Number(23), 6)),
+        ]
+    );
+}
+
+#[test]
+fn parses() {
+    assert_eq!(parse(Tokens::new(Token::lexer("1 + 2 + 39"))), Ok(42));
+    assert_eq!(
+        parse(Tokens::new(Token::lexer("1 + + 2"))),
+        Err(ParseError::Unexpected(Token::Plus, 4))
+    );
+}
+
+#[test]
+fn errors_carry_spans() {
+    assert_eq!(
+        parse(Tokens::new(Token::lexer("1 + x"))),
+        Err(ParseError::User(LexicalError {
+            error: UnknownToken,
+            span: 4..5,
+        }))
+    );
+}
+
+#[test]
+fn custom_errors() {
+    #[derive(Debug, PartialEq)]
+    enum Error {
+        InvalidToken { at: usize, text: String },
+    }
+
+    let source = "1 + 2 $$";
+    let tokens = Tokens::with_error(Token::lexer(source), |_, span: logos::Span| {
+        Error::InvalidToken {
+            at: span.start,
+            text: source[span].to_owned(),
+        }
+    });
+
+    assert_eq!(
+        parse(tokens),
+        Err(ParseError::User(Error::InvalidToken {
+            at: 6,
+            text: "$".to_owned(),
+        }))
+    );
+}
+
+#[test]
+fn lexer_access() {
+    let mut tokens = Tokens::new(Token::lexer("10 + 20"));
+
+    assert_eq!(tokens.next(), Some(Ok((0, Token::Number(10), 2))));
+    assert_eq!(tokens.slice(), "10");
+    assert_eq!(tokens.remainder(), " + 20");
+}
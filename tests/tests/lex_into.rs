@@ -0,0 +1,112 @@
+use logos::{Logos, Span, UnknownToken};
+
+#[derive(Logos, Debug, Clone, Copy, PartialEq)]
+#[logos(extras = usize)]
+enum Token {
+    #[regex(r"[ \t\n]+", logos::skip)]
+    Whitespace,
+
+    #[regex("[a-z]+", |lex| lex.extras += 1)]
+    Word,
+
+    #[regex("[0-9]+", |lex| lex.slice().parse().ok())]
+    Number(u32),
+}
+
+/// Tokens and spans stored in separate columns, like a parser might want for cache efficiency.
+#[derive(Default)]
+struct Columns {
+    tokens: Vec<Token>,
+    starts: Vec<usize>,
+    ends: Vec<usize>,
+    errors: usize,
+}
+
+impl Extend<Result<(Token, Span), UnknownToken>> for Columns {
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = Result<(Token, Span), UnknownToken>>,
+    {
+        for item in iter {
+            match item {
+                Ok((token, span)) => {
+                    self.tokens.push(token);
+                    self.starts.push(span.start);
+                    self.ends.push(span.end);
+                }
+                Err(_) => self.errors += 1,
+            }
+        }
+    }
+}
+
+/// A fixed-capacity buffer that doesn't allocate, standing in for a `SmallVec` or an arena.
+struct Fixed<const N: usize> {
+    items: [Option<Result<Token, UnknownToken>>; N],
+    len: usize,
+}
+
+impl<const N: usize> Extend<Result<Token, UnknownToken>> for Fixed<N> {
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = Result<Token, UnknownToken>>,
+    {
+        for item in iter {
+            self.items[self.len] = Some(item);
+            self.len += 1;
+        }
+    }
+}
+
+#[test]
+fn into_vec() {
+    let mut tokens = vec![Ok(Token::Number(0))];
+    let words = Token::lex_into("a 1 b", &mut tokens);
+
+    assert_eq!(words, 2);
+    assert_eq!(
+        tokens,
+        &[
+            Ok(Token::Number(0)),
+            Ok(Token::Word),
+            Ok(Token::Number(1)),
+            Ok(Token::Word),
+        ]
+    );
+}
+
+#[test]
+fn into_fixed_buffer() {
+    let mut buffer = Fixed::<4> {
+        items: [None; 4],
+        len: 0,
+    };
+
+    Token::lex_into("x ? 7", &mut buffer);
+
+    assert_eq!(buffer.len, 3);
+    assert_eq!(
+        buffer.items,
+        [
+            Some(Ok(Token::Word)),
+            Some(Err(UnknownToken)),
+            Some(Ok(Token::Number(7))),
+            None,
+        ]
+    );
+}
+
+#[test]
+fn spanned_into_columns() {
+    let mut columns = Columns::default();
+    let words = Token::lex_spanned_into("foo 42 ? bar", &mut columns);
+
+    assert_eq!(words, 2);
+    assert_eq!(
+        columns.tokens,
+        &[Token::Word, Token::Number(42), Token::Word]
+    );
+    assert_eq!(columns.starts, &[0, 4, 9]);
+    assert_eq!(columns.ends, &[3, 6, 12]);
+    assert_eq!(columns.errors, 1);
+}
@@ -0,0 +1,107 @@
+use logos::owned::OwnedLexer;
+use logos::{Lexer, Lifecycle, Logos};
+
+#[derive(Default, Debug, PartialEq)]
+struct Events {
+    log: Vec<String>,
+}
+
+impl<'s> Lifecycle<'s, str> for Events {
+    fn on_start(&mut self, source: &'s str) {
+        self.log.push(format!("start {:?}", source));
+    }
+
+    fn on_eof(&mut self) {
+        self.log.push(String::from("finish"));
+    }
+}
+
+#[derive(Logos, Debug, PartialEq)]
+#[logos(extras = Events, lifecycle)]
+#[logos(on_eof = |lex| lex.extras.log.push(String::from("eof")))]
+enum Token {
+    #[regex(r"[ \t\n]+", logos::skip)]
+    Whitespace,
+
+    #[regex("[a-z]+", |lex| lex.extras.log.push(String::from(lex.slice())))]
+    Word,
+}
+
+#[test]
+fn start_and_finish() {
+    let mut lexer = Token::lexer("ab cd");
+
+    assert_eq!(lexer.extras.log, &["start \"ab cd\""]);
+
+    lexer.by_ref().for_each(drop);
+
+    assert_eq!(lexer.next(), None);
+    assert_eq!(
+        lexer.extras.log,
+        &["start \"ab cd\"", "ab", "cd", "finish", "eof"]
+    );
+}
+
+#[test]
+fn reset_starts_again() {
+    let mut lexer = Token::lexer("ab");
+
+    lexer.by_ref().for_each(drop);
+    lexer.reset("cd");
+    lexer.by_ref().for_each(drop);
+
+    assert_eq!(
+        lexer.extras.log,
+        &[
+            "start \"ab\"",
+            "ab",
+            "finish",
+            "eof",
+            "start \"cd\"",
+            "cd",
+            "finish",
+            "eof"
+        ]
+    );
+}
+
+#[test]
+fn rebind_starts_again() {
+    let lexer: Lexer<'static, Token> = Token::lexer("");
+    let source = String::from("ab");
+    let lexer = lexer.rebind(&source);
+
+    assert_eq!(lexer.extras.log, &["start \"\"", "start \"ab\""]);
+}
+
+#[test]
+fn owned_lexers() {
+    let mut lexer = OwnedLexer::<Token, String>::new(String::from("ab"));
+
+    assert_eq!(lexer.extras().log, &["start \"ab\""]);
+
+    lexer.by_ref().for_each(drop);
+    lexer.next();
+    lexer.reset(String::from("cd"));
+
+    assert_eq!(
+        lexer.extras().log,
+        &["start \"ab\"", "ab", "finish", "eof", "start \"cd\""]
+    );
+}
+
+#[test]
+fn without_lifecycle() {
+    #[derive(Logos, Debug, PartialEq)]
+    #[logos(extras = Events)]
+    enum Plain {
+        #[regex("[a-z]+")]
+        Word,
+    }
+
+    let mut lexer = Plain::lexer("ab");
+
+    lexer.by_ref().for_each(drop);
+
+    assert!(lexer.extras.log.is_empty());
+}
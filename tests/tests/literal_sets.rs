@@ -0,0 +1,114 @@
+use logos::Logos;
+use tests::assert_lex;
+
+// Every pair of letters from A to N, and two longer codes: more than enough literals for a binary search.
+macro_rules! codes {
+    ($(#[$attr:meta])*) => {
+        #[derive(Logos, Debug, PartialEq)]
+        $(#[$attr])*
+        pub enum Token {
+            #[regex(r"[ \t\n]+", logos::skip)]
+            Whitespace,
+
+            #[regex("\
+        AA|AB|AC|AD|AE|AF|AG|AH|AI|AJ|AK|AL|AM|AN|BA|BB|BC|BD|BE|BF|BG|BH|BI|BJ|BK|BL|BM|BN|CA|CB|\
+        CC|CD|CE|CF|CG|CH|CI|CJ|CK|CL|CM|CN|DA|DB|DC|DD|DE|DF|DG|DH|DI|DJ|DK|DL|DM|DN|EA|EB|EC|ED|\
+        EE|EF|EG|EH|EI|EJ|EK|EL|EM|EN|FA|FB|FC|FD|FE|FF|FG|FH|FI|FJ|FK|FL|FM|FN|GA|GB|GC|GD|GE|GF|\
+        GG|GH|GI|GJ|GK|GL|GM|GN|HA|HB|HC|HD|HE|HF|HG|HH|HI|HJ|HK|HL|HM|HN|IA|IB|IC|ID|IE|IF|IG|IH|\
+        II|IJ|IK|IL|IM|IN|JA|JB|JC|JD|JE|JF|JG|JH|JI|JJ|JK|JL|JM|JN|KA|KB|KC|KD|KE|KF|KG|KH|KI|KJ|\
+        KK|KL|KM|KN|LA|LB|LC|LD|LE|LF|LG|LH|LI|LJ|LK|LL|LM|LN|MA|MB|MC|MD|ME|MF|MG|MH|MI|MJ|MK|ML|\
+        MM|MN|NA|NB|NC|ND|NE|NF|NG|NH|NI|NJ|NK|NL|NM|NN|ABCD|NNN")]
+            Code,
+
+            #[regex("[0-9]+")]
+            Number,
+        }
+    };
+}
+
+mod binary_search {
+    use super::*;
+
+    codes!();
+
+    #[test]
+    fn codes() {
+        assert_lex(
+            "AB NN 12 ABCD NNN ABC",
+            &[
+                (Ok(Token::Code), "AB", 0..2),
+                (Ok(Token::Code), "NN", 3..5),
+                (Ok(Token::Number), "12", 6..8),
+                (Ok(Token::Code), "ABCD", 9..13),
+                (Ok(Token::Code), "NNN", 14..17),
+                (Ok(Token::Code), "AB", 18..20),
+                (Err(Default::default()), "C", 20..21),
+            ],
+        );
+    }
+
+    #[test]
+    fn same_tokens_as_the_state_machine() {
+        mod state_machine {
+            use super::*;
+
+            codes!(#[logos(literal_sets = 0)]);
+        }
+
+        let source = "AB NO ABC\nAAO ZZ NNNN N 42A ABCDE";
+        let expected: Vec<_> = state_machine::Token::lexer(source).spanned().collect();
+        let actual: Vec<_> = Token::lexer(source).spanned().collect();
+
+        // The two token types are different, but their variants have the same names.
+        assert_eq!(format!("{:?}", actual), format!("{:?}", expected));
+    }
+}
+
+mod compact {
+    use super::*;
+
+    codes!(#[logos(codegen = "compact")]);
+
+    #[test]
+    fn codes() {
+        assert_lex(
+            "MN ABCD 7",
+            &[
+                (Ok(Token::Code), "MN", 0..2),
+                (Ok(Token::Code), "ABCD", 3..7),
+                (Ok(Token::Number), "7", 8..9),
+            ],
+        );
+    }
+}
+
+mod overlapping {
+    use super::*;
+
+    // Identifiers can start with the same letters as the codes, so they're compiled into the state machine.
+    #[derive(Logos, Debug, PartialEq)]
+    #[logos(literal_sets = 3)]
+    enum Token {
+        #[regex(r"[ \t\n]+", logos::skip)]
+        Whitespace,
+
+        #[regex("USD|EUR|GBP|JPY", priority = 10)]
+        Currency,
+
+        #[regex("[A-Z]+")]
+        Ident,
+    }
+
+    #[test]
+    fn longest_match_wins() {
+        assert_lex(
+            "USD USDC EU GBP",
+            &[
+                (Ok(Token::Currency), "USD", 0..3),
+                (Ok(Token::Ident), "USDC", 4..8),
+                (Ok(Token::Ident), "EU", 9..11),
+                (Ok(Token::Currency), "GBP", 12..15),
+            ],
+        );
+    }
+}
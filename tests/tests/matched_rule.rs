@@ -0,0 +1,56 @@
+use logos::{Lexer, Logos};
+
+fn rule<'s>(lex: &mut Lexer<'s, Token>) -> usize {
+    lex.matched_rule()
+}
+
+#[derive(Logos, Debug, PartialEq)]
+enum Token {
+    #[regex(r"[ \t\n]+", logos::skip)]
+    Whitespace,
+
+    #[token("true", rule)]
+    #[token("yes", rule, priority = 10, ignore(case))]
+    #[regex("on|enabled", rule)]
+    True(usize),
+
+    #[token("<>", rule)]
+    #[token("!=", rule)]
+    NotEqual(usize),
+
+    #[regex("[a-z]+", rule)]
+    #[regex("[a-z]+", rule, followed_by = ":")]
+    Word(usize),
+
+    #[token(":")]
+    Colon,
+}
+
+#[test]
+fn callbacks_see_the_matched_definition() {
+    let tokens: Vec<_> = Token::lexer("true YES on enabled != <> key: value").collect();
+
+    assert_eq!(
+        tokens,
+        &[
+            Ok(Token::True(0)),
+            Ok(Token::True(1)),
+            Ok(Token::True(2)),
+            Ok(Token::True(2)),
+            Ok(Token::NotEqual(1)),
+            Ok(Token::NotEqual(0)),
+            Ok(Token::Word(1)),
+            Ok(Token::Colon),
+            Ok(Token::Word(0)),
+        ]
+    );
+}
+
+#[test]
+fn single_definitions() {
+    let mut lexer = Token::lexer("yes :");
+
+    assert_eq!(lexer.next(), Some(Ok(Token::True(1))));
+    assert_eq!(lexer.next(), Some(Ok(Token::Colon)));
+    assert_eq!(lexer.matched_rule(), 0);
+}
@@ -0,0 +1,114 @@
+use logos::iter::Morph;
+use logos::{LexerExt, Logos};
+
+#[derive(Logos, Debug, Clone, PartialEq)]
+#[logos(extras = usize)]
+enum Outer {
+    #[regex(r"[ \n]+", logos::skip)]
+    Whitespace,
+
+    #[token("\"")]
+    Quote,
+
+    #[regex("[a-z]+", |lex| lex.extras += 1)]
+    Word,
+
+    #[token(";")]
+    Semicolon,
+}
+
+#[derive(Logos, Debug, Clone, PartialEq)]
+#[logos(extras = usize)]
+enum Inner {
+    #[regex(r#"[^"]+"#)]
+    Text,
+
+    #[token("\"")]
+    Quote,
+}
+
+const SOURCE: &str = "say \"hi there\" now";
+
+#[test]
+fn spanned() {
+    let mut outer = Outer::lexer(SOURCE).spanned();
+
+    assert_eq!(outer.next(), Some(Ok((Outer::Word, 0..3))));
+    assert_eq!(outer.next(), Some(Ok((Outer::Quote, 4..5))));
+
+    let mut inner = Morph::<Inner>::morph(outer);
+
+    assert_eq!(inner.next(), Some(Ok((Inner::Text, 5..13))));
+    assert_eq!(inner.next(), Some(Ok((Inner::Quote, 13..14))));
+    assert_eq!(inner.extras(), &1);
+
+    let mut outer = Morph::<Outer>::morph(inner);
+
+    assert_eq!(outer.next(), Some(Ok((Outer::Word, 15..18))));
+    assert_eq!(outer.extras(), &2);
+    assert_eq!(outer.next(), None);
+}
+
+#[test]
+fn triples_in_lookahead() {
+    let mut outer = Outer::lexer(SOURCE).triples().lookahead();
+
+    assert_eq!(outer.next(), Some(Ok((0, Outer::Word, 3))));
+    assert_eq!(outer.peek(), Some(&Ok((4, Outer::Quote, 5))));
+
+    // The peeked quote is lexed again by `Inner`.
+    let mut inner = Morph::<Inner>::morph(outer);
+
+    assert_eq!(inner.next(), Some(Ok((3, Inner::Text, 4))));
+    assert_eq!(inner.next(), Some(Ok((4, Inner::Quote, 5))));
+    assert_eq!(inner.peek(), Some(&Ok((5, Inner::Text, 13))));
+}
+
+#[test]
+fn lookahead_at_the_end() {
+    let mut outer = Outer::lexer("end").lookahead();
+
+    assert_eq!(outer.next(), Some(Ok(Outer::Word)));
+    assert_eq!(outer.peek(), None);
+
+    let mut inner = Morph::<Inner>::morph(outer);
+
+    assert_eq!(inner.next(), None);
+}
+
+#[test]
+fn map_with_lexer() {
+    let mut outer = Outer::lexer(SOURCE).map_with_lexer(|token, lexer| (token, lexer.slice()));
+
+    assert_eq!(outer.next(), Some((Ok(Outer::Word), "say")));
+    assert_eq!(outer.next(), Some((Ok(Outer::Quote), "\"")));
+
+    let mut inner =
+        outer.morph_with(|token, lexer: &logos::Lexer<Inner>| (token, lexer.span().len()));
+
+    assert_eq!(inner.next(), Some((Ok(Inner::Text), 8)));
+}
+
+#[test]
+fn inject() {
+    let mut outer = Outer::lexer("a\nb \"c").inject_with(|prev, _, lexer| match prev {
+        Some(Outer::Word) if lexer.slice().contains('\n') => Some(Outer::Semicolon),
+        _ => None,
+    });
+
+    assert_eq!(outer.next(), Some(Ok(Outer::Word)));
+    assert_eq!(outer.next(), Some(Ok(Outer::Semicolon)));
+    assert_eq!(outer.next(), Some(Ok(Outer::Word)));
+    assert_eq!(outer.next(), Some(Ok(Outer::Quote)));
+
+    let mut inner = outer.morph_with(|prev, next, _| match (prev, next) {
+        (Some(Inner::Text), None) => Some(Inner::Quote),
+        _ => None,
+    });
+
+    assert_eq!(inner.next(), Some(Ok(Inner::Text)));
+    assert_eq!(inner.slice(), "c");
+    assert_eq!(inner.next(), Some(Ok(Inner::Quote)));
+    assert_eq!(inner.span(), 6..6);
+    assert_eq!(inner.next(), None);
+}
@@ -0,0 +1,166 @@
+//! Token enums declared inside function bodies, methods and macros, rather than at the module level.
+
+use logos::{Logos, UnknownToken};
+use tests::assert_lex;
+
+#[test]
+fn inside_function() {
+    #[derive(Default)]
+    struct Extras {
+        numbers: usize,
+    }
+
+    fn number(lex: &mut logos::Lexer<Token>) -> u32 {
+        lex.extras.numbers += 1;
+        lex.slice().parse().unwrap()
+    }
+
+    #[derive(Logos, Debug, PartialEq)]
+    #[logos(extras = Extras)]
+    enum Token {
+        #[regex(r"[ \t\n]+", logos::skip)]
+        Whitespace,
+
+        #[regex("[0-9]+", number)]
+        Number(u32),
+
+        #[token("?", contextual)]
+        #[regex("[?!]")]
+        Mark,
+    }
+
+    // A second token type in the same scope, to make sure their generated items don't clash.
+    #[derive(Logos, Debug, PartialEq)]
+    #[logos(test_match)]
+    enum Other<'a> {
+        #[regex("[a-z]+")]
+        Word(&'a str),
+
+        #[token("?")]
+        Mark,
+    }
+
+    assert_lex(
+        "1 ? 23",
+        &[
+            (Ok(Token::Number(1)), "1", 0..1),
+            (Ok(Token::Mark), "?", 2..3),
+            (Ok(Token::Number(23)), "23", 4..6),
+        ],
+    );
+    assert_lex(
+        "foo?",
+        &[
+            (Ok(Other::Word("foo")), "foo", 0..3),
+            (Ok(Other::Mark), "?", 3..4),
+        ],
+    );
+
+    assert_eq!(Other::test_match_Word("abc1"), Some(3));
+
+    let mut lex = Token::lexer("4 5");
+    lex.by_ref().for_each(drop);
+
+    assert_eq!(lex.extras.numbers, 2);
+}
+
+struct Parser;
+
+impl Parser {
+    fn values(&self, source: &str) -> Vec<Result<u8, UnknownToken>> {
+        #[derive(Logos, Debug, PartialEq)]
+        enum Token {
+            #[token(" ", logos::skip)]
+            Whitespace,
+
+            #[token("a", |_| 1)]
+            #[token("b", |_| 2)]
+            Value(u8),
+        }
+
+        Token::lexer(source)
+            .map(|token| {
+                token.map(|token| match token {
+                    Token::Value(value) => value,
+                    Token::Whitespace => unreachable!(),
+                })
+            })
+            .collect()
+    }
+}
+
+#[test]
+fn inside_method() {
+    assert_eq!(Parser.values("a b a"), &[Ok(1), Ok(2), Ok(1)]);
+}
+
+macro_rules! throwaway_lexer {
+    ($source:expr, $($pattern:literal),*) => {{
+        #[derive(Logos, Debug, PartialEq)]
+        enum Throwaway {
+            #[regex(r"\s+", logos::skip)]
+            Whitespace,
+
+            #[regex(r"[0-9]+")]
+            Number,
+
+            $(#[token($pattern)])*
+            Punct,
+        }
+
+        Throwaway::lexer($source)
+            .map(|token| token.map(|token| token == Throwaway::Number))
+            .collect::<Vec<_>>()
+    }};
+}
+
+#[test]
+fn inside_macro() {
+    assert_eq!(
+        throwaway_lexer!("1 + 2", "+", "-"),
+        &[Ok(true), Ok(false), Ok(true)]
+    );
+    assert_eq!(
+        throwaway_lexer!("3 * 4", "*"),
+        &[Ok(true), Ok(false), Ok(true)]
+    );
+}
+
+#[test]
+fn prelude_names_shadowed() {
+    #[derive(Logos, Debug, PartialEq)]
+    enum Answer {
+        #[regex(r"[ \t\n]+", logos::skip)]
+        Whitespace,
+
+        #[token("ok")]
+        Ok,
+
+        #[token("err", |_| 1)]
+        #[regex("[0-9]+", |lex| lex.slice().parse().ok())]
+        Err(u8),
+
+        #[token("some", contextual)]
+        #[regex("[a-z]+")]
+        Some,
+
+        #[token("none")]
+        None,
+    }
+
+    use Answer::*;
+
+    let tokens: Vec<_> = Answer::lexer("ok err 42 some none ?").collect();
+
+    assert_eq!(
+        tokens,
+        &[
+            Result::Ok(Ok),
+            Result::Ok(Err(1)),
+            Result::Ok(Err(42)),
+            Result::Ok(Some),
+            Result::Ok(None),
+            Result::Err(UnknownToken),
+        ]
+    );
+}
@@ -0,0 +1,78 @@
+use logos::error::{Error, NoPanicError};
+use logos::{Lexer, Logos, UnknownToken};
+use tests::assert_lex;
+
+mod unknown_token {
+    use super::*;
+
+    #[derive(Logos, Debug, PartialEq)]
+    #[logos(no_panic_error, recover_to = ";", sentinel = b"\0")]
+    enum Token {
+        #[regex(r"[ \t\n]+", logos::skip)]
+        Whitespace,
+
+        #[token(";")]
+        Semicolon,
+
+        #[regex("[a-z]+")]
+        Word,
+    }
+
+    #[test]
+    fn errors() {
+        assert_lex(
+            "foo ?? bar; baz\0",
+            &[
+                (Ok(Token::Word), "foo", 0..3),
+                (Err(UnknownToken), "?? bar", 4..10),
+                (Ok(Token::Semicolon), ";", 10..11),
+                (Ok(Token::Word), "baz", 12..15),
+                (Err(UnknownToken), "\0", 15..16),
+            ],
+        );
+    }
+}
+
+mod custom {
+    use super::*;
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    enum Unexpected {
+        Char(Option<char>),
+        Sentinel,
+    }
+
+    impl<'s> Error<'s, Token> for Unexpected {
+        fn unknown_token(lex: &Lexer<'s, Token>) -> Self {
+            Unexpected::Char(lex.slice().chars().next())
+        }
+
+        fn sentinel(_: &Lexer<'s, Token>) -> Self {
+            Unexpected::Sentinel
+        }
+    }
+
+    impl<'s> NoPanicError<'s, Token> for Unexpected {}
+
+    #[derive(Logos, Debug, PartialEq)]
+    #[logos(error = Unexpected, no_panic_error, sentinel = b"\0")]
+    enum Token {
+        #[regex(r"[ \t\n]+", logos::skip)]
+        Whitespace,
+
+        #[regex("[a-z]+")]
+        Word,
+    }
+
+    #[test]
+    fn errors() {
+        assert_lex(
+            "foo é\0",
+            &[
+                (Ok(Token::Word), "foo", 0..3),
+                (Err(Unexpected::Char(Some('é'))), "é", 4..6),
+                (Err(Unexpected::Sentinel), "\0", 6..7),
+            ],
+        );
+    }
+}
@@ -0,0 +1,130 @@
+use logos::Logos;
+use tests::assert_lex;
+
+#[derive(Logos, Debug, PartialEq)]
+enum Fields {
+    #[regex(r"[ \t\n]+", logos::skip)]
+    Whitespace,
+
+    // `type` is a keyword, unless it's the name of a field.
+    #[token("type", not_followed_by = r"[ \t]*:")]
+    Type,
+
+    #[regex("[a-z]+")]
+    Name,
+
+    #[token(":")]
+    Colon,
+}
+
+#[derive(Logos, Debug, PartialEq)]
+enum Calls {
+    #[regex(r"[ \t\n]+", logos::skip)]
+    Whitespace,
+
+    // A call with at least one argument.
+    #[regex("[a-z]+", followed_by = r"\(", not_followed_by = r"\(\)")]
+    Call,
+
+    #[regex("[a-z]+")]
+    Name,
+
+    #[token("(")]
+    Open,
+
+    #[token(")")]
+    Close,
+}
+
+#[derive(Logos, Debug, PartialEq)]
+enum Numbers {
+    #[regex(r"[ \t\n]+", logos::skip)]
+    Whitespace,
+
+    // There's nothing to fall back to, so a number with a suffix is an error.
+    #[regex("[0-9]+", not_followed_by = "[a-z_]")]
+    Number,
+
+    #[regex("[a-z]+")]
+    Name,
+}
+
+#[derive(Logos, Debug, PartialEq)]
+enum Ranges {
+    // A float can't end in a dot that starts a range, so `1..2` is a range of integers.
+    #[regex(r"[0-9]+\.[0-9]*", not_followed_by = r"\.")]
+    Float,
+
+    #[regex("[0-9]+")]
+    Int,
+
+    #[token("..")]
+    Range,
+}
+
+#[test]
+fn keyword_or_field() {
+    assert_lex(
+        "type: type",
+        &[
+            (Ok(Fields::Name), "type", 0..4),
+            (Ok(Fields::Colon), ":", 4..5),
+            (Ok(Fields::Type), "type", 6..10),
+        ],
+    );
+    assert_lex(
+        "type \t: types",
+        &[
+            (Ok(Fields::Name), "type", 0..4),
+            (Ok(Fields::Colon), ":", 6..7),
+            (Ok(Fields::Name), "types", 8..13),
+        ],
+    );
+}
+
+#[test]
+fn end_of_source() {
+    assert_lex("type", &[(Ok(Fields::Type), "type", 0..4)]);
+}
+
+#[test]
+fn with_followed_by() {
+    assert_lex(
+        "f() g(x)",
+        &[
+            (Ok(Calls::Name), "f", 0..1),
+            (Ok(Calls::Open), "(", 1..2),
+            (Ok(Calls::Close), ")", 2..3),
+            (Ok(Calls::Call), "g", 4..5),
+            (Ok(Calls::Open), "(", 5..6),
+            (Ok(Calls::Name), "x", 6..7),
+            (Ok(Calls::Close), ")", 7..8),
+        ],
+    );
+}
+
+#[test]
+fn no_fallback() {
+    assert_lex(
+        "12 34px",
+        &[
+            (Ok(Numbers::Number), "12", 0..2),
+            (Err(logos::UnknownToken), "34", 3..5),
+            (Ok(Numbers::Name), "px", 5..7),
+        ],
+    );
+}
+
+#[test]
+fn backs_off_to_shorter_match() {
+    assert_lex(
+        "1..2 1.5",
+        &[
+            (Ok(Ranges::Int), "1", 0..1),
+            (Ok(Ranges::Range), "..", 1..3),
+            (Ok(Ranges::Int), "2", 3..4),
+            (Err(logos::UnknownToken), " ", 4..5),
+            (Ok(Ranges::Float), "1.5", 5..8),
+        ],
+    );
+}
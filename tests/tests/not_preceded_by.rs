@@ -0,0 +1,80 @@
+use logos::{Logos, UnknownToken};
+use tests::assert_lex;
+
+#[derive(Logos, Debug, PartialEq)]
+enum Token {
+    #[regex(r"[ \t\n]+", logos::skip)]
+    Whitespace,
+
+    #[regex("[a-z]+")]
+    Name,
+
+    #[regex("[0-9]+", not_preceded_by = "[a-z_]")]
+    Number,
+
+    #[token("-", not_preceded_by = b"[0-9]")]
+    Negate,
+
+    #[token("-", priority = 1)]
+    Minus,
+}
+
+#[derive(Logos, Debug, PartialEq)]
+enum Fallback {
+    #[regex("[a-z]+")]
+    Name,
+
+    #[regex("[0-9]+", not_preceded_by = "[a-z]", priority = 3)]
+    Number,
+
+    #[regex("[0-9]+")]
+    Suffix,
+}
+
+#[test]
+fn start_of_source() {
+    assert_lex("12", &[(Ok(Token::Number), "12", 0..2)]);
+    assert_lex("-", &[(Ok(Token::Negate), "-", 0..1)]);
+}
+
+#[test]
+fn errors_without_fallback() {
+    assert_lex(
+        "ab12 _3 4",
+        &[
+            (Ok(Token::Name), "ab", 0..2),
+            (Err(UnknownToken), "12", 2..4),
+            (Err(UnknownToken), "_", 5..6),
+            (Err(UnknownToken), "3", 6..7),
+            (Ok(Token::Number), "4", 8..9),
+        ],
+    );
+}
+
+#[test]
+fn tokens() {
+    assert_lex(
+        "-1 1-1 - -",
+        &[
+            (Ok(Token::Negate), "-", 0..1),
+            (Ok(Token::Number), "1", 1..2),
+            (Ok(Token::Number), "1", 3..4),
+            (Ok(Token::Minus), "-", 4..5),
+            (Ok(Token::Number), "1", 5..6),
+            (Ok(Token::Negate), "-", 7..8),
+            (Ok(Token::Negate), "-", 9..10),
+        ],
+    );
+}
+
+#[test]
+fn falls_back() {
+    assert_lex(
+        "x1",
+        &[
+            (Ok(Fallback::Name), "x", 0..1),
+            (Ok(Fallback::Suffix), "1", 1..2),
+        ],
+    );
+    assert_lex("2", &[(Ok(Fallback::Number), "2", 0..1)]);
+}
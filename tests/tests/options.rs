@@ -0,0 +1,77 @@
+use logos::{Filter, Lexer, LexerOptions, Logos};
+
+const NEWLINES: LexerOptions = LexerOptions::bit(0);
+const SHOUTING: LexerOptions = LexerOptions::bit(63);
+
+fn newline(lex: &mut Lexer<Token>) -> Filter<()> {
+    match lex.options().contains(NEWLINES) {
+        true => Filter::Accept(()),
+        false => Filter::Skip,
+    }
+}
+
+#[derive(Logos, Debug, Clone, Copy, PartialEq)]
+enum Token {
+    #[regex(r"[ \t]+", logos::skip)]
+    Whitespace,
+
+    #[token("\n", newline)]
+    Newline,
+
+    #[token("!", |lex| lex.options_mut().toggle(NEWLINES))]
+    Bang,
+
+    #[regex("[a-z]+", |lex| lex.options().contains(SHOUTING))]
+    Word(bool),
+}
+
+#[test]
+fn flags() {
+    let mut options = NEWLINES | SHOUTING;
+
+    assert!(options.contains(NEWLINES));
+    assert!(options.intersects(NEWLINES | LexerOptions::bit(5)));
+    assert!(!options.contains(NEWLINES | LexerOptions::bit(5)));
+    assert_eq!(options.bits(), 1 << 63 | 1);
+
+    options.remove(NEWLINES);
+    assert_eq!(options, SHOUTING);
+    assert_eq!(!options - LexerOptions::ALL, LexerOptions::EMPTY);
+
+    options.set(NEWLINES, true);
+    options ^= SHOUTING;
+    assert_eq!(options, NEWLINES);
+    assert!(LexerOptions::default().is_empty());
+}
+
+#[test]
+fn from_callbacks() {
+    let tokens: Vec<_> = Token::lexer("a\n! b\n!c\n")
+        .with_options(SHOUTING)
+        .collect();
+
+    assert_eq!(
+        tokens,
+        &[
+            Ok(Token::Word(true)),
+            Ok(Token::Bang),
+            Ok(Token::Word(true)),
+            Ok(Token::Newline),
+            Ok(Token::Bang),
+            Ok(Token::Word(true)),
+        ],
+    );
+}
+
+#[test]
+fn kept_by_morph() {
+    #[derive(Logos, Debug, PartialEq)]
+    enum Other {
+        #[regex("[a-z]+", |lex| lex.options().contains(SHOUTING))]
+        Word(bool),
+    }
+
+    let mut lex = Token::lexer("a").with_options(SHOUTING).morph::<Other>();
+
+    assert_eq!(lex.next(), Some(Ok(Other::Word(true))));
+}
@@ -0,0 +1,173 @@
+use logos::owned::OwnedLexer;
+use logos::Logos;
+use std::cell::Cell;
+use std::ops::Deref;
+use std::rc::Rc;
+use std::sync::Arc;
+
+#[derive(Default, Debug, Clone, PartialEq)]
+struct Counter {
+    words: usize,
+}
+
+#[derive(Logos, Debug, Clone, PartialEq)]
+#[logos(extras = Counter)]
+enum Token {
+    #[regex(r"[ \t\n]+", logos::skip)]
+    Whitespace,
+
+    #[regex("[a-z]+", |lex| {
+        lex.extras.words += 1;
+        lex.slice().to_owned()
+    })]
+    Word(String),
+
+    #[regex("[0-9]+", |lex| lex.slice().parse().ok())]
+    Number(u64),
+
+    #[token("(?", |lex| lex.enable_contextual(Token::Question))]
+    Open,
+
+    #[token("?", contextual)]
+    #[regex("[?!]")]
+    Question,
+}
+
+struct Document {
+    lexer: OwnedLexer<Token, String>,
+}
+
+#[test]
+fn string_owner() {
+    let mut document = Document {
+        lexer: OwnedLexer::new(String::from("foo 12 bar")),
+    };
+
+    assert_eq!(document.lexer.next(), Some(Ok(Token::Word("foo".into()))));
+    assert_eq!(document.lexer.span(), 0..3);
+    assert_eq!(document.lexer.slice(), "foo");
+    assert_eq!(document.lexer.remainder(), " 12 bar");
+
+    assert_eq!(document.lexer.next(), Some(Ok(Token::Number(12))));
+    assert_eq!(document.lexer.slice(), "12");
+
+    assert_eq!(document.lexer.next(), Some(Ok(Token::Word("bar".into()))));
+    assert_eq!(document.lexer.next(), None);
+    assert_eq!(document.lexer.extras().words, 2);
+
+    let (owner, extras) = document.lexer.into_parts();
+
+    assert_eq!(owner, "foo 12 bar");
+    assert_eq!(extras.words, 2);
+}
+
+#[test]
+fn shared_owners() {
+    let source: Rc<str> = Rc::from("a b");
+    let lexer = OwnedLexer::<Token, _>::with_extras(source.clone(), Counter { words: 10 });
+    let tokens: Vec<_> = lexer.clone().collect();
+
+    assert_eq!(
+        tokens,
+        &[Ok(Token::Word("a".into())), Ok(Token::Word("b".into()))]
+    );
+    assert_eq!(lexer.extras().words, 10);
+    assert!(Rc::ptr_eq(lexer.owner(), &source));
+}
+
+#[test]
+fn send_to_thread() {
+    let sources: Vec<Arc<str>> = vec![Arc::from("one 1"), Arc::from("two 2 three")];
+
+    let handles: Vec<_> = sources
+        .into_iter()
+        .map(|source| {
+            let mut lexer = OwnedLexer::<Token, _>::new(source);
+
+            std::thread::spawn(move || {
+                let tokens: Vec<_> = lexer.by_ref().collect();
+
+                (tokens, lexer.extras().words)
+            })
+        })
+        .collect();
+
+    let results: Vec<_> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+
+    assert_eq!(
+        results,
+        &[
+            (vec![Ok(Token::Word("one".into())), Ok(Token::Number(1))], 1),
+            (
+                vec![
+                    Ok(Token::Word("two".into())),
+                    Ok(Token::Number(2)),
+                    Ok(Token::Word("three".into())),
+                ],
+                2
+            ),
+        ]
+    );
+}
+
+#[test]
+fn with_lexer_keeps_state() {
+    let mut lexer = OwnedLexer::<Token, Box<str>>::new("? (? ?".into());
+
+    assert_eq!(lexer.next(), Some(Ok(Token::Question)));
+    assert_eq!(lexer.next(), Some(Ok(Token::Open)));
+    assert!(lexer.with_lexer(|lex| lex.is_contextual_enabled(Token::Question)));
+
+    lexer.with_lexer(|lex| lex.bump(1));
+
+    assert_eq!(lexer.span(), 2..5);
+    assert_eq!(lexer.next(), Some(Ok(Token::Question)));
+    assert_eq!(lexer.span(), 5..6);
+    assert_eq!(lexer.next(), None);
+}
+
+#[test]
+fn with_lexer_keeps_span_offset() {
+    let mut lexer = OwnedLexer::<Token, String>::new("foo bar".into());
+
+    assert_eq!(lexer.next(), Some(Ok(Token::Word("foo".into()))));
+
+    lexer.with_lexer(|lex| lex.set_span_offset(10));
+
+    assert_eq!(lexer.span(), 10..13);
+    assert_eq!(lexer.slice(), "foo");
+    assert_eq!(lexer.next(), Some(Ok(Token::Word("bar".into()))));
+    assert_eq!(lexer.span(), 14..17);
+    assert_eq!(lexer.with_lexer(|lex| lex.span()), 14..17);
+}
+
+/// An owner that can be made to hand out a shorter source than before.
+struct Shrinking {
+    source: &'static str,
+    len: Rc<Cell<usize>>,
+}
+
+impl Deref for Shrinking {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.source[..self.len.get()]
+    }
+}
+
+#[test]
+#[should_panic(expected = "different source")]
+fn owner_changing_source() {
+    let source = "foo bar baz";
+    let len = Rc::new(Cell::new(source.len()));
+    let mut lexer = OwnedLexer::<Token, _>::new(Shrinking {
+        source,
+        len: len.clone(),
+    });
+
+    assert_eq!(lexer.next(), Some(Ok(Token::Word("foo".into()))));
+    assert_eq!(lexer.next(), Some(Ok(Token::Word("bar".into()))));
+
+    len.set(5);
+    lexer.slice();
+}
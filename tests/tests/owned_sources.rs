@@ -0,0 +1,69 @@
+use logos::{Logos, Source};
+
+#[derive(Logos, Debug, Clone, PartialEq)]
+#[logos(source = Vec<u8>)]
+enum Vectored<'s> {
+    #[regex(b"[0-9]+", |lex| lex.slice())]
+    Number(&'s [u8]),
+
+    #[token(b",")]
+    Comma,
+
+    #[token(b"\xFF\xFE")]
+    Marker,
+}
+
+#[derive(Logos, Debug, Clone, PartialEq)]
+#[logos(source = Box<[u8]>)]
+enum Boxed {
+    #[regex(b"[0-9]+")]
+    Number,
+
+    #[token(b"\x00")]
+    Nul,
+}
+
+#[test]
+fn vec() {
+    let source = b"12,\xFF\xFE,3\x80".to_vec();
+    let mut lexer = Vectored::lexer(&source);
+    let copy = lexer.clone();
+
+    assert_eq!(lexer.next(), Some(Ok(Vectored::Number(b"12"))));
+    assert_eq!(lexer.next(), Some(Ok(Vectored::Comma)));
+    assert_eq!(lexer.next(), Some(Ok(Vectored::Marker)));
+    assert_eq!(lexer.span(), 3..5);
+    assert_eq!(lexer.next(), Some(Ok(Vectored::Comma)));
+    assert_eq!(lexer.next(), Some(Ok(Vectored::Number(b"3"))));
+    assert_eq!(lexer.next(), Some(Err(Default::default())));
+    assert_eq!(lexer.slice(), b"\x80");
+    assert_eq!(lexer.next(), None);
+
+    assert_eq!(copy.count(), 6);
+}
+
+#[test]
+fn boxed() {
+    let source: Box<[u8]> = Box::from(&b"1\x0023"[..]);
+    let tokens: Vec<_> = Boxed::lexer(&source).spanned().collect();
+
+    assert_eq!(
+        tokens,
+        &[
+            Ok((Boxed::Number, 0..1)),
+            Ok((Boxed::Nul, 1..2)),
+            Ok((Boxed::Number, 2..4)),
+        ]
+    );
+}
+
+#[test]
+fn reads() {
+    let source = b"abc".to_vec();
+
+    assert_eq!(source.read::<&[u8; 2]>(1), Some(b"bc"));
+    assert_eq!(source.read::<&[u8; 2]>(2), None);
+    assert_eq!(Source::slice(&source, 1..3), Some(&b"bc"[..]));
+    assert!(source.is_boundary(3));
+    assert!(!source.is_boundary(4));
+}
@@ -0,0 +1,53 @@
+use logos::Logos;
+
+#[derive(Logos, Debug, PartialEq)]
+#[logos(owned = OwnedToken, owned_derive(Debug, Clone, PartialEq))]
+pub enum Token<'s> {
+    #[regex(r"[ \t\n]+", logos::skip)]
+    Whitespace,
+
+    #[regex("[a-z]+")]
+    Ident(&'s str),
+
+    #[regex("[0-9]+", |lex| lex.slice().parse().ok())]
+    Number(u64),
+
+    #[regex(r#""[^"]*""#, |lex| lex.slice().as_bytes())]
+    Bytes(&'s [u8]),
+
+    #[token("+")]
+    Plus,
+}
+
+#[derive(Logos, Debug, PartialEq)]
+#[logos(owned = OwnedUnit)]
+enum Unit {
+    #[token("a")]
+    A,
+}
+
+fn owned(source: &str) -> Vec<OwnedToken> {
+    let source = source.to_owned();
+
+    Token::lexer(&source)
+        .map(|token| token.unwrap().to_owned())
+        .collect()
+}
+
+#[test]
+fn outlives_source() {
+    assert_eq!(
+        owned("abc + 12 \"hi\""),
+        &[
+            OwnedToken::Ident(String::from("abc")),
+            OwnedToken::Plus,
+            OwnedToken::Number(12),
+            OwnedToken::Bytes(b"\"hi\"".to_vec()),
+        ],
+    );
+}
+
+#[test]
+fn without_lifetime() {
+    assert!(matches!(Unit::A.to_owned(), OwnedUnit::A));
+}
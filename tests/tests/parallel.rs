@@ -0,0 +1,94 @@
+use logos::parallel::{lex, lex_chunked};
+use logos::{LexerExt, Logos, Span, UnknownToken};
+
+#[derive(Logos, Debug, PartialEq)]
+enum Token<'s> {
+    #[regex(r"[ \t\n]+", logos::skip)]
+    #[regex(r"/\*[^*]*\*/", logos::skip)]
+    Trivia,
+
+    #[regex("[a-z]+")]
+    Ident(&'s str),
+
+    #[regex("[0-9]+", |lex| lex.slice().parse().ok())]
+    Number(u64),
+
+    #[regex(r#""[^"]*""#)]
+    String(&'s str),
+
+    #[token(";")]
+    Semicolon,
+
+    #[logos(eof)]
+    Eof,
+}
+
+type Tokens<'s> = Vec<(Result<Token<'s>, UnknownToken>, Span)>;
+
+fn serial(source: &str) -> Tokens<'_> {
+    Token::lexer(source)
+        .map_with_lexer(|token, lexer| (token, lexer.span()))
+        .collect()
+}
+
+fn chunked(source: &str, chunks: usize) -> Tokens<'_> {
+    lex_chunked::<Token>(source, chunks)
+}
+
+const SOURCE: &str = "\
+let x = 10;
+/* a comment
+   over lines; with \"quotes\" */
+let s = \"a string
+over ; lines\";
+emoji 🦀 here; 42
+";
+
+#[test]
+fn same_as_serial() {
+    let source = SOURCE.repeat(50);
+    let expected = serial(&source);
+
+    for chunks in 0..64 {
+        assert_eq!(chunked(&source, chunks), expected, "{} chunks", chunks);
+    }
+}
+
+#[test]
+fn chunks_without_line_breaks() {
+    let source = "abc 12 \"x y\" /* z */ ; ".repeat(100);
+    let expected = serial(&source);
+
+    for chunks in [2, 3, 7, 16, 100, 1000, 10_000] {
+        assert_eq!(chunked(&source, chunks), expected, "{} chunks", chunks);
+    }
+}
+
+#[test]
+fn chunks_in_one_token() {
+    // Every chunk but the first starts in the middle of the string.
+    let source = format!("a \"{}\" b", "x\n".repeat(1000));
+
+    assert_eq!(
+        chunked(&source, 8),
+        &[
+            (Ok(Token::Ident("a")), 0..1),
+            (Ok(Token::String(&source[2..2004])), 2..2004),
+            (Ok(Token::Ident("b")), 2005..2006),
+            (Ok(Token::Eof), 2006..2006),
+        ]
+    );
+}
+
+#[test]
+fn small_sources() {
+    assert_eq!(chunked("", 4), &[(Ok(Token::Eof), 0..0)]);
+    assert_eq!(
+        chunked("a", 4),
+        &[(Ok(Token::Ident("a")), 0..1), (Ok(Token::Eof), 1..1)]
+    );
+
+    let source = SOURCE.repeat(2);
+
+    assert_eq!(lex::<Token>(&source).len(), serial(&source).len());
+}
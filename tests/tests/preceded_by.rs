@@ -0,0 +1,131 @@
+use logos::Logos;
+use tests::assert_lex;
+
+#[derive(Logos, Debug, PartialEq)]
+enum Js {
+    #[regex(r"[ \t\n]+", logos::skip)]
+    Whitespace,
+
+    #[regex("[a-z]+")]
+    Name,
+
+    #[regex("[0-9]+")]
+    Number,
+
+    // A slash starts a regex literal after an operator or an opening bracket, and is a division anywhere else. Only
+    // the slash goes into the state machine, so that it's lexed on its own when the check fails.
+    #[regex(
+        r"/[^/\n]+/[a-z]*",
+        preceded_by = r"[(,=:\[!&|?{};][ \t\n]*",
+        strategy = "callback"
+    )]
+    Regex,
+
+    #[token("/")]
+    Slash,
+
+    #[token("=")]
+    Assign,
+
+    #[token("(")]
+    Open,
+
+    #[token(")")]
+    Close,
+}
+
+#[derive(Logos, Debug, PartialEq)]
+enum Units {
+    #[regex(r"[ \t]+", logos::skip)]
+    Whitespace,
+
+    #[regex("[0-9]+")]
+    Number,
+
+    // A unit right after a number, with no space in between.
+    #[token("px", preceded_by = "[0-9]")]
+    Pixels,
+
+    #[regex("[a-z]+")]
+    Name,
+
+    #[regex("[０-９]+")]
+    Wide,
+
+    // Preceding text is read backwards, so multibyte characters have to come out the right way round.
+    #[token("em", preceded_by = "[０-９]")]
+    Em,
+}
+
+#[test]
+fn regex_and_division() {
+    assert_lex(
+        "x = /ab+/g",
+        &[
+            (Ok(Js::Name), "x", 0..1),
+            (Ok(Js::Assign), "=", 2..3),
+            (Ok(Js::Regex), "/ab+/g", 4..10),
+        ],
+    );
+    assert_lex(
+        "f(/a/)",
+        &[
+            (Ok(Js::Name), "f", 0..1),
+            (Ok(Js::Open), "(", 1..2),
+            (Ok(Js::Regex), "/a/", 2..5),
+            (Ok(Js::Close), ")", 5..6),
+        ],
+    );
+}
+
+#[test]
+fn not_preceded() {
+    assert_lex(
+        "a / b",
+        &[
+            (Ok(Js::Name), "a", 0..1),
+            (Ok(Js::Slash), "/", 2..3),
+            (Ok(Js::Name), "b", 4..5),
+        ],
+    );
+    assert_lex(
+        "a / b / c",
+        &[
+            (Ok(Js::Name), "a", 0..1),
+            (Ok(Js::Slash), "/", 2..3),
+            (Ok(Js::Name), "b", 4..5),
+            (Ok(Js::Slash), "/", 6..7),
+            (Ok(Js::Name), "c", 8..9),
+        ],
+    );
+}
+
+#[test]
+fn start_of_source() {
+    assert_lex(
+        "/a/",
+        &[
+            (Ok(Js::Slash), "/", 0..1),
+            (Ok(Js::Name), "a", 1..2),
+            (Ok(Js::Slash), "/", 2..3),
+        ],
+    );
+}
+
+#[test]
+fn units() {
+    assert_lex(
+        "12px px 3em",
+        &[
+            (Ok(Units::Number), "12", 0..2),
+            (Ok(Units::Pixels), "px", 2..4),
+            (Ok(Units::Name), "px", 5..7),
+            (Ok(Units::Number), "3", 8..9),
+            (Ok(Units::Name), "em", 9..11),
+        ],
+    );
+    assert_lex(
+        "３em",
+        &[(Ok(Units::Wide), "３", 0..3), (Ok(Units::Em), "em", 3..5)],
+    );
+}
@@ -0,0 +1,92 @@
+use logos::compat::proc_macro2::{str_literals, StrLiteral, Tokens};
+use logos::{LexerExt, Logos};
+use proc_macro2::{Literal, TokenStream};
+
+#[derive(Logos, Debug, PartialEq)]
+#[logos(extras = usize)]
+enum Token {
+    #[regex(r"[ \t\n]+", logos::skip)]
+    Whitespace,
+
+    #[regex("[a-zé]+", |lex| lex.extras += 1)]
+    Word,
+}
+
+fn literal(source: &str) -> StrLiteral {
+    StrLiteral::new(source.parse().unwrap()).unwrap()
+}
+
+#[test]
+fn finds_nested_string_literals() {
+    let stream: TokenStream = r#"a("one", 2, { "two" b"three" 'c' }) [r"four"]"#.parse().unwrap();
+    let values: Vec<_> = str_literals(stream)
+        .iter()
+        .map(|literal| literal.value().to_owned())
+        .collect();
+
+    assert_eq!(values, &["one", "two", "four"]);
+}
+
+#[test]
+fn escapes() {
+    let literal = literal(
+        r#""a\tb\x41\u{1F_600}\"é \
+        c""#,
+    );
+
+    assert_eq!(literal.value(), "a\tbA\u{1F600}\"é c");
+    // Every byte of an escaped character points at its backslash.
+    assert_eq!(literal.source_range(1..2), 2..4);
+    assert_eq!(literal.source_range(4..8), 9..19);
+    // Unescaped characters keep their own lengths.
+    assert_eq!(literal.source_range(9..11), 21..23);
+    // The line break and indentation after a trailing backslash are skipped.
+    assert_eq!(literal.source_range(12..13), 34..35);
+    assert_eq!(literal.source_range(13..13), 35..35);
+}
+
+#[test]
+fn raw_strings() {
+    let literal = literal(r###"r#"say "hi"\n"#"###);
+
+    assert_eq!(literal.value(), r#"say "hi"\n"#);
+    assert_eq!(literal.source_range(4..8), 7..11);
+}
+
+#[test]
+fn not_string_literals() {
+    for source in ["1", "'a'", r#"b"bytes""#, r#"c"c string""#] {
+        let literal: Literal = source
+            .parse::<TokenStream>()
+            .map(|stream| match stream.into_iter().next() {
+                Some(proc_macro2::TokenTree::Literal(literal)) => literal,
+                tree => panic!("unexpected {:?}", tree),
+            })
+            .unwrap();
+
+        assert!(StrLiteral::new(literal).is_none(), "{}", source);
+    }
+}
+
+#[test]
+fn lexes_values() {
+    let literal = literal(r#""one\ttwo ?""#);
+    let tokens: Vec<_> = literal.lexer::<Token>().map(|(token, _)| token).collect();
+
+    assert_eq!(
+        tokens,
+        &[Ok(Token::Word), Ok(Token::Word), Err(logos::UnknownToken)]
+    );
+}
+
+#[test]
+fn lexers_with_extras_and_offsets() {
+    let literal = literal(r#""a b""#);
+    let lexer = Token::lexer_with_extras(literal.value(), 10).with_span_offset(100);
+    let mut tokens = Tokens::new(&literal, lexer);
+
+    assert_eq!(tokens.nth(1).map(|(token, _)| token), Some(Ok(Token::Word)));
+    assert_eq!(tokens.span(), 102..103);
+    assert_eq!(tokens.extras(), &12);
+    assert!(tokens.next().is_none());
+}
@@ -0,0 +1,90 @@
+use logos::{Logos, UnknownToken};
+use tests::assert_lex;
+
+mod recover_to {
+    use super::*;
+
+    #[derive(Logos, Debug, Clone, Copy, PartialEq)]
+    #[logos(recover_to = ";", recover_to = "\n")]
+    enum Token {
+        #[regex(r"[ \t]+", logos::skip)]
+        Whitespace,
+
+        #[token("\n")]
+        Newline,
+
+        #[token(";")]
+        Semicolon,
+
+        #[token("let")]
+        Let,
+
+        #[regex("[0-9]+")]
+        Number,
+    }
+
+    #[test]
+    fn skips_to_sync_point() {
+        assert_lex(
+            "let $$ 10; 10\n?? ?;",
+            &[
+                (Ok(Token::Let), "let", 0..3),
+                (Err(UnknownToken), "$$ 10", 4..9),
+                (Ok(Token::Semicolon), ";", 9..10),
+                (Ok(Token::Number), "10", 11..13),
+                (Ok(Token::Newline), "\n", 13..14),
+                (Err(UnknownToken), "?? ?", 14..18),
+                (Ok(Token::Semicolon), ";", 18..19),
+            ],
+        );
+    }
+
+    #[test]
+    fn until_end() {
+        assert_lex(
+            "1 ¯\\_(ツ)_/¯",
+            &[
+                (Ok(Token::Number), "1", 0..1),
+                (Err(UnknownToken), "¯\\_(ツ)_/¯", 2..15),
+            ],
+        );
+    }
+}
+
+mod multi_byte_sync {
+    use super::*;
+
+    #[derive(Logos, Debug, Clone, Copy, PartialEq)]
+    #[logos(recover_to = b"*/")]
+    enum Token {
+        #[token("/*")]
+        Open,
+
+        #[token("*/")]
+        Close,
+    }
+
+    #[test]
+    fn bytes() {
+        assert_lex(
+            "/* ** / */",
+            &[
+                (Ok(Token::Open), "/*", 0..2),
+                (Err(UnknownToken), " ** / ", 2..8),
+                (Ok(Token::Close), "*/", 8..10),
+            ],
+        );
+    }
+
+    #[test]
+    fn partial_match() {
+        // `/` is a prefix of `/*`, so the error starts at the beginning of the partial match.
+        assert_lex(
+            "/ x */",
+            &[
+                (Err(UnknownToken), "/ x ", 0..4),
+                (Ok(Token::Close), "*/", 4..6),
+            ],
+        );
+    }
+}
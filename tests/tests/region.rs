@@ -0,0 +1,166 @@
+use logos::region::{lex_region, lex_region_from};
+use logos::{LexerExt, Logos, Span, UnknownToken};
+
+#[derive(Logos, Debug, Clone, Copy, PartialEq)]
+enum Token {
+    #[regex(r"[ \t\n]+", logos::skip)]
+    Whitespace,
+
+    #[token("fn")]
+    Fn,
+
+    #[regex("[a-z]+")]
+    Ident,
+
+    #[regex(r"/\*([^*]|\*[^/])*\*/")]
+    Comment,
+}
+
+const SOURCE: &str = "fn foo\nbar /* a\nb */ baz\n?\nqux";
+
+type Tokens = Vec<(Result<Token, UnknownToken>, Span)>;
+
+/// Every token in the source, with its span.
+fn all() -> Tokens {
+    let mut lexer = Token::lexer(SOURCE);
+    let mut tokens = Vec::new();
+
+    while let Some(token) = lexer.next() {
+        tokens.push((token, lexer.span()));
+    }
+
+    tokens
+}
+
+/// The tokens overlapping `region`, from a lex of the whole source.
+fn expected(region: Span) -> Tokens {
+    all()
+        .into_iter()
+        .filter(|(_, span)| span.end > region.start && span.start < region.end)
+        .collect()
+}
+
+#[test]
+fn single_lines() {
+    for region in [0..0, 0..2, 3..5, 4..9, 0..7, 25..26, 24..30, 30..30] {
+        assert_eq!(
+            lex_region::<Token>(SOURCE, region.clone()),
+            expected(region.clone()),
+            "{:?}",
+            region
+        );
+    }
+}
+
+#[test]
+fn tokens_spanning_lines() {
+    // The second line of the comment isn't a safe place to start, so `b` and `*` are lexed on their own.
+    assert_eq!(
+        lex_region::<Token>(SOURCE, 16..19),
+        &[(Ok(Token::Ident), 16..17), (Err(UnknownToken), 18..19)],
+    );
+
+    // Starting from the start of the comment is safe, though.
+    let start = all()
+        .into_iter()
+        .find(|(token, _)| *token == Ok(Token::Comment))
+        .unwrap()
+        .1
+        .start;
+
+    assert_eq!(
+        lex_region_from(Token::lexer(SOURCE), start, 16..19),
+        expected(16..19)
+    );
+    assert_eq!(expected(16..19), &[(Ok(Token::Comment), 11..20)]);
+}
+
+#[test]
+fn span_offset() {
+    let lexer = Token::lexer(SOURCE).with_span_offset(100);
+
+    assert_eq!(
+        lex_region_from(lexer, 107, 108..120),
+        &[(Ok(Token::Ident), 107..110), (Ok(Token::Comment), 111..120),],
+    );
+}
+
+#[test]
+#[should_panic(expected = "isn't within the source")]
+fn out_of_bounds() {
+    lex_region::<Token>(SOURCE, 20..31);
+}
+
+#[test]
+#[should_panic(expected = "can't lex the region")]
+fn start_after_region() {
+    lex_region_from(Token::lexer(SOURCE), 10, 5..12);
+}
+
+#[test]
+fn checkpoints() {
+    let mut lexer = Token::lexer(SOURCE).checkpointed(5);
+
+    lexer.by_ref().for_each(drop);
+
+    let checkpoints = lexer.into_checkpoints();
+
+    assert_eq!(checkpoints.interval(), 5);
+    assert_eq!(checkpoints.positions().collect::<Vec<_>>(), &[0, 6, 20, 26]);
+
+    for start in 0..SOURCE.len() {
+        for end in start..=SOURCE.len() {
+            assert_eq!(
+                checkpoints.lex_region::<Token>(SOURCE, start..end),
+                expected(start..end),
+                "{:?}",
+                start..end
+            );
+        }
+    }
+}
+
+#[derive(Logos, Debug, PartialEq)]
+#[logos(extras = usize)]
+enum Lines {
+    #[regex(r"[ \t]+", logos::skip)]
+    Whitespace,
+
+    #[token("\n", |lex| { lex.extras += 1; logos::Skip })]
+    Newline,
+
+    #[regex("[a-z]+", |lex| lex.extras)]
+    Word(usize),
+}
+
+#[test]
+fn checkpoints_restore_extras() {
+    let source = "a b\nc d\ne f";
+    let mut lexer = Lines::lexer(source).with_span_offset(10).checkpointed(4);
+
+    lexer.by_ref().for_each(drop);
+
+    let checkpoints = lexer.into_checkpoints();
+    let at = |position| {
+        checkpoints
+            .before(position)
+            .map(|checkpoint| checkpoint.extras)
+    };
+
+    assert_eq!(at(9), None);
+    assert_eq!(at(14), Some(0));
+    assert_eq!(at(15), Some(1));
+    assert_eq!(
+        checkpoints.lex_region::<Lines>(source, 16..21),
+        &[
+            (Ok(Lines::Word(1)), 16..17),
+            (Ok(Lines::Word(2)), 18..19),
+            (Ok(Lines::Word(2)), 20..21),
+        ],
+    );
+
+    let mut lexer = checkpoints.lexer::<Lines>(source, 21);
+
+    assert_eq!(lexer.next(), Some(Ok(Lines::Word(2))));
+    assert_eq!(lexer.span(), 20..21);
+}
@@ -0,0 +1,69 @@
+use logos::{Logos, UnknownToken};
+use tests::assert_lex;
+
+#[derive(Logos, Debug, PartialEq)]
+enum Token {
+    #[regex(r"[ \t\n]+", logos::skip)]
+    Whitespace,
+
+    #[regex(r"[0-9]{1,3}(\.[0-9]{1,3}){3}")]
+    Ipv4,
+
+    #[regex(r"\\u\{[0-9a-fA-F]{1,6}\}")]
+    Escape,
+
+    #[regex(r"#[0-9a-f]{6}")]
+    Color,
+
+    #[regex(r"\w{8,}")]
+    LongWord,
+
+    #[regex("[0-9]+")]
+    Number,
+
+    #[token(".")]
+    Dot,
+}
+
+#[test]
+fn counted() {
+    assert_lex(
+        r"127.0.0.1 10.20.30.4000 \u{1F600} #ff00aa",
+        &[
+            (Ok(Token::Ipv4), "127.0.0.1", 0..9),
+            (Ok(Token::Ipv4), "10.20.30.400", 10..22),
+            (Ok(Token::Number), "0", 22..23),
+            (Ok(Token::Escape), r"\u{1F600}", 24..33),
+            (Ok(Token::Color), "#ff00aa", 34..41),
+        ],
+    );
+}
+
+#[test]
+fn at_least() {
+    assert_lex(
+        "identifiers ok 12345678",
+        &[
+            (Ok(Token::LongWord), "identifiers", 0..11),
+            (Err(UnknownToken), "o", 12..13),
+            (Err(UnknownToken), "k", 13..14),
+            (Ok(Token::LongWord), "12345678", 15..23),
+        ],
+    );
+}
+
+#[test]
+fn beyond_the_count() {
+    assert_lex(
+        "1.2.3 #ff00aa1",
+        &[
+            (Ok(Token::Number), "1", 0..1),
+            (Ok(Token::Dot), ".", 1..2),
+            (Ok(Token::Number), "2", 2..3),
+            (Ok(Token::Dot), ".", 3..4),
+            (Ok(Token::Number), "3", 4..5),
+            (Ok(Token::Color), "#ff00aa", 6..13),
+            (Ok(Token::Number), "1", 13..14),
+        ],
+    );
+}
@@ -0,0 +1,114 @@
+use logos::{Lexer, LexerExt, Logos};
+
+fn enter_async(lex: &mut Lexer<Token>) {
+    lex.enable_contextual(Token::Await);
+}
+
+#[derive(Logos, Debug, PartialEq)]
+enum Token {
+    #[regex(r"[ \t\n]+", logos::skip)]
+    Whitespace,
+
+    #[token("async", enter_async)]
+    Async,
+
+    #[token("await", contextual)]
+    Await,
+
+    #[regex("[a-z]+")]
+    Ident,
+}
+
+#[test]
+fn divergence_points_at_the_first_different_state() {
+    let mut recorded = Token::lexer("x await async await").trace();
+    let tokens: Vec<_> = recorded.by_ref().collect();
+
+    assert_eq!(
+        tokens,
+        &[
+            Ok(Token::Ident),
+            Ok(Token::Ident),
+            Ok(Token::Async),
+            Ok(Token::Await),
+        ],
+    );
+
+    let states = recorded.into_states();
+    let mut same = Token::lexer("x await async await").trace();
+
+    same.by_ref().for_each(drop);
+    assert_eq!(same.divergence(&states), None);
+
+    // Starting in "async" mode changes the state from the first token.
+    let mut lexer = Token::lexer("x await async await");
+
+    lexer.enable_contextual(Token::Await);
+
+    let mut other = lexer.trace();
+
+    assert_eq!(other.next(), Some(Ok(Token::Ident)));
+    assert_eq!(other.divergence(&states), Some(0));
+
+    // A trace that's a prefix of another diverges where it ends.
+    assert_eq!(
+        Token::lexer("x await").trace().divergence(&states[..2]),
+        Some(0)
+    );
+}
+
+#[test]
+fn restore_replays_from_a_state() {
+    let mut recorded = Token::lexer("x await async await").trace();
+
+    recorded.by_ref().for_each(drop);
+
+    let states = recorded.into_states();
+    let mut lexer = Token::lexer("x await async await");
+
+    lexer.restore(&states[2]);
+
+    assert_eq!(lexer.state(), states[2]);
+    assert_eq!(lexer.next(), Some(Ok(Token::Await)));
+    assert_eq!(lexer.state(), states[3]);
+}
+
+#[test]
+fn restore_after_the_end() {
+    #[derive(Logos, Debug, PartialEq)]
+    enum Words {
+        #[regex(r"[ \t\n]+", logos::skip)]
+        Whitespace,
+
+        #[regex("[a-z]+")]
+        Word,
+
+        #[logos(eof)]
+        Eof,
+    }
+
+    let mut recorded = Words::lexer("ab cd").trace();
+
+    recorded.by_ref().for_each(drop);
+
+    let states = recorded.into_states();
+    let mut lexer = Words::lexer("ab cd");
+
+    // Run the lexer to the end of its input first.
+    lexer.by_ref().for_each(drop);
+    lexer.restore(&states[0]);
+
+    let tokens: Vec<_> = lexer.collect();
+
+    assert_eq!(tokens, &[Ok(Words::Word), Ok(Words::Eof)]);
+}
+
+#[test]
+#[should_panic(expected = "doesn't belong to this lexer's source")]
+fn restore_checks_bounds() {
+    let mut recorded = Token::lexer("async await").trace();
+
+    recorded.by_ref().for_each(drop);
+
+    Token::lexer("x").restore(&recorded.states()[1]);
+}
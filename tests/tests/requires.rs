@@ -0,0 +1,77 @@
+use logos::{LexerOptions, Logos, UnknownToken};
+
+struct Flag;
+
+impl Flag {
+    const TYPE_POSITION: LexerOptions = LexerOptions::bit(0);
+    const ITEM_POSITION: LexerOptions = LexerOptions::bit(1);
+    const UNSAFE: LexerOptions = LexerOptions::bit(2);
+}
+
+#[derive(Logos, Debug, Clone, Copy, PartialEq)]
+enum Token<'a> {
+    #[regex(r"[ \t\n]+", logos::skip)]
+    Whitespace,
+
+    // Within generics, `>>` closes two of them, so it's only a shift elsewhere. It still needs a priority to shadow
+    // `CloseTwo`, which it falls back to while it doesn't apply.
+    #[token(">>", requires(!Flag::TYPE_POSITION), priority = 5)]
+    Shr,
+
+    #[token(">>", requires(Flag::TYPE_POSITION))]
+    CloseTwo,
+
+    #[token(">")]
+    Gt,
+
+    #[token("union", requires(Flag::ITEM_POSITION))]
+    #[regex("raw_[a-z]+", requires(Flag::ITEM_POSITION, Flag::UNSAFE))]
+    Keyword,
+
+    #[regex("[a-z_]+", |lex| lex.slice())]
+    Ident(&'a str),
+}
+
+#[test]
+fn flags_pick_definitions() {
+    let mut lex = Token::lexer(">> >> union union raw_ptr raw_ptr");
+
+    assert!(!lex.flag(Flag::TYPE_POSITION));
+    assert_eq!(lex.next(), Some(Ok(Token::Shr)));
+
+    lex.set_flag(Flag::TYPE_POSITION, true);
+    assert!(lex.flag(Flag::TYPE_POSITION));
+    assert_eq!(lex.next(), Some(Ok(Token::CloseTwo)));
+
+    assert_eq!(lex.next(), Some(Ok(Token::Ident("union"))));
+    lex.set_flag(Flag::ITEM_POSITION, true);
+    assert_eq!(lex.next(), Some(Ok(Token::Keyword)));
+
+    // Every flag has to be set.
+    assert_eq!(lex.next(), Some(Ok(Token::Ident("raw_ptr"))));
+    lex.set_flag(Flag::UNSAFE, true);
+    assert_eq!(lex.next(), Some(Ok(Token::Keyword)));
+    assert_eq!(lex.next(), None);
+}
+
+#[derive(Logos, Debug, Clone, Copy, PartialEq)]
+#[logos(codegen = "compact")]
+enum Compact {
+    #[token("union", requires(Flag::ITEM_POSITION))]
+    Union,
+
+    #[token("(")]
+    Paren,
+}
+
+#[test]
+fn nothing_to_fall_back_to() {
+    let mut lex = Compact::lexer("union(union");
+
+    assert_eq!(lex.next(), Some(Err(UnknownToken)));
+    assert_eq!(lex.span(), 0..5);
+    assert_eq!(lex.next(), Some(Ok(Compact::Paren)));
+
+    lex.set_flag(Flag::ITEM_POSITION, true);
+    assert_eq!(lex.next(), Some(Ok(Compact::Union)));
+}
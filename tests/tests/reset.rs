@@ -0,0 +1,116 @@
+use logos::owned::OwnedLexer;
+use logos::{Lexer, LexerOptions, Logos};
+
+const UPPERCASE: LexerOptions = LexerOptions::bit(0);
+
+#[derive(Default, Debug)]
+struct Interner {
+    words: Vec<String>,
+}
+
+impl Interner {
+    fn intern(&mut self, word: &str) -> usize {
+        match self.words.iter().position(|w| w == word) {
+            Some(index) => index,
+            None => {
+                self.words.push(word.to_owned());
+                self.words.len() - 1
+            }
+        }
+    }
+}
+
+fn word(lex: &mut Lexer<Token>) -> usize {
+    let slice = lex.slice();
+
+    match lex.options().contains(UPPERCASE) {
+        true => lex.extras.intern(&slice.to_uppercase()),
+        false => lex.extras.intern(slice),
+    }
+}
+
+#[derive(Logos, Debug, PartialEq)]
+#[logos(extras = Interner)]
+enum Token {
+    #[regex(r"[ \t\n]+", logos::skip)]
+    Whitespace,
+
+    #[regex("[a-z]+", word)]
+    Word(usize),
+
+    #[token("(", |lex| lex.enable_contextual(Token::Keyword))]
+    Open,
+
+    #[token("if", contextual)]
+    Keyword,
+}
+
+#[test]
+fn reset_keeps_extras() {
+    let mut lexer = Token::lexer("foo bar (if");
+
+    assert_eq!(lexer.by_ref().count(), 4);
+    assert!(lexer.is_contextual_enabled(Token::Keyword));
+
+    lexer.reset("bar if baz");
+
+    assert_eq!(lexer.span(), 0..0);
+    assert_eq!(lexer.remainder(), "bar if baz");
+    assert!(!lexer.is_contextual_enabled(Token::Keyword));
+
+    let tokens: Vec<_> = lexer.by_ref().collect();
+
+    assert_eq!(
+        tokens,
+        &[Ok(Token::Word(1)), Ok(Token::Word(2)), Ok(Token::Word(3))]
+    );
+    assert_eq!(lexer.extras.words, &["foo", "bar", "if", "baz"]);
+}
+
+#[test]
+fn reset_keeps_options() {
+    let mut lexer = Token::lexer("a").with_options(UPPERCASE);
+
+    assert_eq!(lexer.next(), Some(Ok(Token::Word(0))));
+
+    lexer.reset("b a");
+
+    assert_eq!(lexer.options(), UPPERCASE);
+    assert_eq!(lexer.next(), Some(Ok(Token::Word(1))));
+    assert_eq!(lexer.next(), Some(Ok(Token::Word(0))));
+    assert_eq!(lexer.extras.words, &["A", "B"]);
+}
+
+#[test]
+fn rebind_to_shorter_lifetime() {
+    let mut pooled: Lexer<'static, Token> = Token::lexer("");
+
+    for input in ["x y", "y z"] {
+        let source = input.to_owned();
+        let mut lexer = pooled.rebind(&source);
+        let tokens: Vec<_> = lexer.by_ref().collect();
+
+        assert_eq!(tokens.len(), 2);
+
+        pooled = lexer.rebind("");
+    }
+
+    assert_eq!(pooled.extras.words, &["x", "y", "z"]);
+}
+
+#[test]
+fn owned_reset() {
+    let mut lexer = OwnedLexer::<Token, String>::new("foo".into());
+
+    assert_eq!(lexer.next(), Some(Ok(Token::Word(0))));
+
+    let previous = lexer.reset("bar foo".into());
+
+    assert_eq!(previous, "foo");
+    assert_eq!(lexer.span(), 0..0);
+
+    let tokens: Vec<_> = lexer.by_ref().collect();
+
+    assert_eq!(tokens, &[Ok(Token::Word(1)), Ok(Token::Word(0))]);
+    assert_eq!(lexer.extras().words, &["foo", "bar"]);
+}
@@ -0,0 +1,119 @@
+use logos::rewrite::{RewriteError, Rewriter};
+use logos::Logos;
+
+#[derive(Logos, Debug, Clone, Copy, PartialEq)]
+enum Token {
+    #[regex(r"[ \t\n]+", logos::skip)]
+    Whitespace,
+
+    #[regex(r"//[^\n]*", logos::skip)]
+    Comment,
+
+    #[regex("[0-9]+")]
+    Number,
+
+    #[regex("[a-zA-Zé]+")]
+    Ident,
+
+    #[token("+")]
+    Plus,
+}
+
+mod tokens {
+    use super::*;
+
+    #[test]
+    fn preserves_trivia() {
+        let source = "a  +\tb // sum\n+ 10";
+        let mut rewriter = Rewriter::new(source);
+
+        rewriter.replace_tokens(
+            Token::lexer(source).spanned().flatten(),
+            |token, slice| match token {
+                Token::Ident => Some(slice.to_uppercase()),
+                Token::Number => Some(format!("0x{:x}", slice.parse::<u32>().unwrap())),
+                _ => None,
+            },
+        );
+
+        assert_eq!(rewriter.finish().unwrap(), "A  +\tB // sum\n+ 0xa");
+    }
+
+    #[test]
+    fn unicode() {
+        let source = "café + é";
+        let mut rewriter = Rewriter::new(source);
+
+        rewriter.replace_tokens(Token::lexer(source).spanned().flatten(), |token, _| {
+            (*token == Token::Ident).then_some("x")
+        });
+
+        assert_eq!(rewriter.finish().unwrap(), "x + x");
+    }
+}
+
+mod edits {
+    use super::*;
+
+    #[test]
+    fn insert_and_delete() {
+        let mut rewriter = Rewriter::new("a + b");
+
+        rewriter
+            .delete(1..3)
+            .insert(0, "(")
+            .insert(5, ")")
+            .insert(0, "(")
+            .replace(0..1, "c");
+
+        assert_eq!(rewriter.finish().unwrap(), "((c b)");
+    }
+
+    #[test]
+    fn empty_source() {
+        let mut rewriter = Rewriter::new("");
+
+        rewriter.insert(0, "hello");
+
+        assert_eq!(rewriter.finish().unwrap(), "hello");
+    }
+
+    #[test]
+    fn overlapping() {
+        let mut rewriter = Rewriter::new("foo bar");
+
+        rewriter.replace(4..7, "baz").replace(0..5, "qux");
+
+        assert_eq!(
+            rewriter.finish(),
+            Err(RewriteError::Overlapping(0..5, 4..7))
+        );
+    }
+
+    #[test]
+    fn insert_inside_replacement() {
+        let mut rewriter = Rewriter::new("foo bar");
+
+        rewriter.replace(0..3, "baz").insert(1, "!");
+
+        assert_eq!(
+            rewriter.finish(),
+            Err(RewriteError::Overlapping(0..3, 1..1))
+        );
+    }
+
+    #[test]
+    fn invalid_span() {
+        let mut rewriter = Rewriter::new("é");
+
+        rewriter.replace(0..1, "e");
+
+        assert_eq!(rewriter.finish(), Err(RewriteError::InvalidSpan(0..1)));
+
+        let mut rewriter = Rewriter::new("foo");
+
+        rewriter.delete(2..4);
+
+        assert_eq!(rewriter.finish(), Err(RewriteError::InvalidSpan(2..4)));
+    }
+}
@@ -0,0 +1,319 @@
+use logos::ring::{Next, RingLexer};
+use logos::{Logos, UnknownToken};
+use std::task::Poll;
+
+#[derive(Logos, Debug, PartialEq)]
+#[logos(source = [u8])]
+enum Token {
+    #[regex(r"[ \r\n]+", logos::skip)]
+    Whitespace,
+
+    #[token("set")]
+    Set,
+
+    #[token("settings")]
+    Settings,
+
+    #[regex("[a-z]+")]
+    Word,
+
+    #[regex("[0-9]+")]
+    Number,
+}
+
+/// Push `input` in chunks of (at most) `chunk` bytes, collecting every token along with its span and slice.
+fn lex_chunks(
+    buffer: &mut [u8],
+    input: &[u8],
+    chunk: usize,
+) -> Vec<(Result<Token, UnknownToken>, Vec<u8>)> {
+    let mut lexer = RingLexer::<Token>::new(buffer);
+    let mut remaining = input;
+    let mut tokens = Vec::new();
+
+    loop {
+        match lexer.next_token() {
+            Next::Token(token) => {
+                assert_eq!(&input[lexer.span()], lexer.slice());
+
+                tokens.push((token, lexer.slice().to_vec()));
+            }
+            Next::Incomplete if remaining.is_empty() => lexer.finish(),
+            Next::Incomplete => {
+                let pushed = lexer.push_bytes(&remaining[..chunk.min(remaining.len())]);
+
+                remaining = &remaining[pushed..];
+            }
+            Next::End => return tokens,
+        }
+    }
+}
+
+#[test]
+fn chunk_size_does_not_matter() {
+    let input = b"set 42 settings\r\nsetting set\n7";
+    let expected = lex_chunks(&mut [0; 64], input, input.len());
+
+    assert_eq!(
+        expected,
+        &[
+            (Ok(Token::Set), b"set".to_vec()),
+            (Ok(Token::Number), b"42".to_vec()),
+            (Ok(Token::Settings), b"settings".to_vec()),
+            (Ok(Token::Word), b"setting".to_vec()),
+            (Ok(Token::Set), b"set".to_vec()),
+            (Ok(Token::Number), b"7".to_vec()),
+        ]
+    );
+
+    for chunk in 1..8 {
+        assert_eq!(
+            lex_chunks(&mut [0; 12], input, chunk),
+            expected,
+            "chunk size {chunk}"
+        );
+    }
+}
+
+#[test]
+fn errors_wait_for_the_next_token() {
+    let mut buffer = [0; 8];
+    let mut lexer = RingLexer::<Token>::new(&mut buffer);
+
+    lexer.push_bytes(b"set?");
+    assert_eq!(lexer.next_token(), Next::Token(Ok(Token::Set)));
+    assert_eq!(lexer.next_token(), Next::Incomplete);
+
+    lexer.push_bytes(b"!1 ");
+    assert_eq!(lexer.next_token(), Next::Token(Err(UnknownToken)));
+    assert_eq!(lexer.slice(), b"?");
+    assert_eq!(lexer.next_token(), Next::Token(Err(UnknownToken)));
+    assert_eq!(lexer.span(), 4..5);
+    assert_eq!(lexer.next_token(), Next::Token(Ok(Token::Number)));
+    assert_eq!(lexer.next_token(), Next::Incomplete);
+}
+
+#[test]
+fn full_buffer_splits_tokens() {
+    let mut buffer = [0; 4];
+    let mut lexer = RingLexer::<Token>::new(&mut buffer);
+
+    assert_eq!(lexer.push_bytes(b"abcdef"), 4);
+    assert_eq!(lexer.free(), 0);
+    assert_eq!(lexer.next_token(), Next::Token(Ok(Token::Word)));
+    assert_eq!(lexer.slice(), b"abcd");
+
+    // The bytes of the last token are kept until the next one is requested.
+    assert_eq!(lexer.push_bytes(b"ef"), 0);
+    assert_eq!(lexer.next_token(), Next::Incomplete);
+    assert_eq!(lexer.push_bytes(b"ef"), 2);
+
+    lexer.finish();
+
+    assert_eq!(lexer.next_token(), Next::Token(Ok(Token::Word)));
+    assert_eq!(lexer.span(), 4..6);
+    assert_eq!(lexer.next_token(), Next::End);
+    assert_eq!(lexer.next_token(), Next::End);
+}
+
+#[test]
+fn trailing_skipped_bytes() {
+    let mut buffer = [0; 8];
+    let mut lexer = RingLexer::<Token>::new(&mut buffer);
+
+    lexer.push_bytes(b"1  ");
+    assert_eq!(lexer.next_token(), Next::Token(Ok(Token::Number)));
+    assert_eq!(lexer.next_token(), Next::Incomplete);
+
+    lexer.finish();
+    assert_eq!(lexer.next_token(), Next::End);
+    assert!(lexer.is_finished());
+}
+
+#[test]
+fn poll_next_token() {
+    let mut buffer = [0; 8];
+    let mut lexer = RingLexer::<Token>::new(&mut buffer);
+    let mut chunks = [&b"se"[..], b"t 4", b"2"].into_iter();
+    let mut tokens = Vec::new();
+
+    loop {
+        match lexer.poll_next_token(chunks.len() > 0) {
+            Poll::Ready(Some(token)) => tokens.push((token, lexer.span())),
+            Poll::Ready(None) => break,
+            Poll::Pending => {
+                lexer.push_bytes(chunks.next().unwrap());
+            }
+        }
+    }
+
+    assert_eq!(tokens, &[(Ok(Token::Set), 0..3), (Ok(Token::Number), 4..6)]);
+}
+
+mod lookahead {
+    use super::*;
+
+    #[derive(Logos, Debug, PartialEq)]
+    #[logos(source = [u8])]
+    enum Token {
+        #[token("a")]
+        A,
+
+        #[token("b")]
+        B,
+
+        #[token("abc")]
+        Abc,
+    }
+
+    #[test]
+    fn longer_lookahead() {
+        let mut buffer = [0; 8];
+        let mut lexer = RingLexer::<Token>::new(&mut buffer).with_lookahead(2);
+
+        lexer.push_bytes(b"ab");
+        assert_eq!(lexer.next_token(), Next::Incomplete);
+
+        lexer.push_bytes(b"c");
+        assert_eq!(lexer.next_token(), Next::Incomplete);
+
+        lexer.push_bytes(b"ab");
+        assert_eq!(lexer.next_token(), Next::Token(Ok(Token::Abc)));
+        assert_eq!(lexer.next_token(), Next::Incomplete);
+
+        lexer.finish();
+        assert_eq!(lexer.next_token(), Next::Token(Ok(Token::A)));
+        assert_eq!(lexer.next_token(), Next::Token(Ok(Token::B)));
+        assert_eq!(lexer.next_token(), Next::End);
+    }
+}
+
+mod extras {
+    use super::*;
+    use logos::Lexer;
+
+    fn count(lex: &mut Lexer<Token>) {
+        lex.extras += 1;
+    }
+
+    #[derive(Logos, Debug, PartialEq)]
+    #[logos(source = [u8], extras = usize)]
+    enum Token {
+        #[token(b";", count)]
+        Semicolon,
+    }
+
+    #[test]
+    fn extras_are_kept() {
+        let mut buffer = [0; 8];
+        let mut lexer = RingLexer::<Token>::with_extras(&mut buffer, 10);
+
+        lexer.push_bytes(b";;;;;;");
+
+        for _ in 0..5 {
+            assert_eq!(lexer.next_token(), Next::Token(Ok(Token::Semicolon)));
+        }
+
+        assert_eq!(*lexer.extras(), 15);
+        assert_eq!(lexer.span(), 4..5);
+    }
+
+    fn word(lex: &mut Lexer<Words>) {
+        lex.extras += 1;
+    }
+
+    #[derive(Logos, Debug, PartialEq)]
+    #[logos(source = [u8], extras = usize)]
+    enum Words {
+        #[regex(" +", logos::skip)]
+        Space,
+
+        #[regex("[a-z]+", word)]
+        Word,
+    }
+
+    #[test]
+    fn callbacks_count_once() {
+        let mut buffer = [0; 8];
+        let mut lexer = RingLexer::<Words>::new(&mut buffer);
+
+        for chunk in [&b"ab"[..], b"c"] {
+            lexer.push_bytes(chunk);
+            assert_eq!(lexer.next_token(), Next::Incomplete);
+        }
+
+        assert_eq!(*lexer.extras(), 0);
+
+        lexer.push_bytes(b" ");
+        assert_eq!(lexer.next_token(), Next::Token(Ok(Words::Word)));
+        assert_eq!(lexer.next_token(), Next::Incomplete);
+
+        lexer.finish();
+        assert_eq!(lexer.next_token(), Next::End);
+        assert_eq!(*lexer.extras(), 1);
+    }
+
+    #[test]
+    fn callbacks_after_an_error_count_once() {
+        let mut buffer = [0; 8];
+        let mut lexer = RingLexer::<Words>::new(&mut buffer);
+
+        lexer.push_bytes(b"1ab c");
+        assert_eq!(lexer.next_token(), Next::Token(Err(UnknownToken)));
+        assert_eq!(*lexer.extras(), 0);
+        assert_eq!(lexer.next_token(), Next::Token(Ok(Words::Word)));
+        assert_eq!(*lexer.extras(), 1);
+
+        lexer.finish();
+        assert_eq!(lexer.next_token(), Next::Token(Ok(Words::Word)));
+        assert_eq!(lexer.next_token(), Next::End);
+        assert_eq!(*lexer.extras(), 2);
+    }
+
+    #[derive(Default, Clone)]
+    struct Ends {
+        eofs: usize,
+    }
+
+    #[derive(Logos, Debug, PartialEq)]
+    #[logos(source = [u8], extras = Ends)]
+    #[logos(on_eof = |lex| lex.extras.eofs += 1)]
+    enum Hooked {
+        #[regex(" +", logos::skip)]
+        Space,
+
+        #[regex("[a-z]+")]
+        Word,
+    }
+
+    #[test]
+    fn on_eof_waits_for_the_end_of_the_stream() {
+        let mut buffer = [0; 4];
+        let mut lexer = RingLexer::<Hooked>::new(&mut buffer);
+
+        for _ in 0..2 {
+            assert_eq!(lexer.push_bytes(b"    "), 4);
+            assert_eq!(lexer.next_token(), Next::Incomplete);
+        }
+
+        assert_eq!(lexer.extras().eofs, 0);
+
+        lexer.finish();
+        assert_eq!(lexer.next_token(), Next::End);
+        assert_eq!(lexer.next_token(), Next::End);
+        assert_eq!(lexer.extras().eofs, 1);
+    }
+
+    #[test]
+    fn on_eof_after_the_last_token() {
+        let mut buffer = [0; 4];
+        let mut lexer = RingLexer::<Hooked>::new(&mut buffer);
+
+        lexer.push_bytes(b"ab");
+        lexer.finish();
+        assert_eq!(lexer.next_token(), Next::Token(Ok(Hooked::Word)));
+        assert_eq!(lexer.extras().eofs, 0);
+        assert_eq!(lexer.next_token(), Next::End);
+        assert_eq!(lexer.extras().eofs, 1);
+    }
+}
@@ -0,0 +1,101 @@
+use logos::{Lexer, Logos};
+
+#[derive(Logos, Debug, PartialEq)]
+enum Token<'a> {
+    #[regex(r"[ \t\n]+", logos::skip)]
+    Whitespace,
+
+    #[regex(r"\p{Alphabetic}+")]
+    Word(&'a str),
+
+    #[token("{")]
+    Open,
+
+    #[token("}")]
+    Close,
+
+    #[logos(eof)]
+    Eof,
+}
+
+#[test]
+fn with_offset_keeps_spans_absolute() {
+    let source = "fn main { body here }";
+    let mut lexer = Lexer::<Token>::with_offset(source, 10);
+
+    assert_eq!(lexer.span(), 10..10);
+    assert_eq!(lexer.next(), Some(Ok(Token::Word("body"))));
+    assert_eq!(lexer.span(), 10..14);
+    assert_eq!(lexer.remainder(), " here }");
+
+    let rest: Vec<_> = lexer.spanned().collect();
+
+    assert_eq!(
+        rest,
+        &[
+            Ok((Token::Word("here"), 15..19)),
+            Ok((Token::Close, 20..21)),
+            Ok((Token::Eof, 21..21)),
+        ]
+    );
+}
+
+#[test]
+fn seek_resumes_after_the_end() {
+    let mut lexer = Token::lexer("one two");
+
+    assert_eq!(lexer.by_ref().count(), 3);
+
+    lexer.seek(4);
+
+    assert_eq!(lexer.next(), Some(Ok(Token::Word("two"))));
+    assert_eq!(lexer.span(), 4..7);
+    assert_eq!(lexer.next(), Some(Ok(Token::Eof)));
+    assert_eq!(lexer.next(), None);
+}
+
+#[test]
+fn seek_backwards() {
+    let mut lexer = Token::lexer("{ one }");
+
+    assert_eq!(lexer.next(), Some(Ok(Token::Open)));
+    assert_eq!(lexer.next(), Some(Ok(Token::Word("one"))));
+
+    lexer.seek(lexer.span().start);
+
+    assert_eq!(lexer.leading_trivia(), "");
+    assert_eq!(lexer.next(), Some(Ok(Token::Word("one"))));
+    assert_eq!(lexer.leading_trivia(), "");
+
+    lexer.seek(0);
+
+    assert_eq!(lexer.next(), Some(Ok(Token::Open)));
+}
+
+#[test]
+fn seek_with_span_offset() {
+    let mut lexer = Token::lexer("ab cd").with_span_offset(100);
+
+    lexer.seek(103);
+
+    assert_eq!(lexer.next(), Some(Ok(Token::Word("cd"))));
+    assert_eq!(lexer.span(), 103..105);
+}
+
+#[test]
+#[should_panic(expected = "cannot seek to byte 1")]
+fn seek_into_a_character() {
+    Token::lexer("éa").seek(1);
+}
+
+#[test]
+#[should_panic(expected = "cannot seek to byte 4")]
+fn seek_out_of_bounds() {
+    Token::lexer("abc").seek(4);
+}
+
+#[test]
+#[should_panic(expected = "cannot seek to byte 5")]
+fn seek_before_the_span_offset() {
+    Token::lexer("abc").with_span_offset(10).seek(5);
+}
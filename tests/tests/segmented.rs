@@ -0,0 +1,302 @@
+use logos::segmented::{Segmented, SliceView};
+use logos::{Logos, Source, Span, UnknownToken};
+
+macro_rules! token {
+    ($name:ident, $source:ty) => {
+        #[derive(Logos, Debug, Clone, PartialEq)]
+        #[logos(source = $source)]
+        enum $name<'a> {
+            #[regex(r"[ \t\n]+", logos::skip)]
+            Whitespace,
+
+            #[token("let")]
+            Let,
+
+            #[token("extraordinarily_long_keyword_that_spills_over_seams")]
+            Long,
+
+            #[regex(r"\p{Alphabetic}+")]
+            Ident(&'a str),
+
+            #[regex("[0-9]+", |lex| lex.slice().parse().ok())]
+            Number(u64),
+
+            #[regex(r#""[^"]*""#)]
+            String(&'a str),
+        }
+    };
+}
+
+token!(Contiguous, str);
+token!(Chunked, Segmented<'a>);
+
+fn convert(token: Chunked) -> Contiguous {
+    match token {
+        Chunked::Whitespace => Contiguous::Whitespace,
+        Chunked::Let => Contiguous::Let,
+        Chunked::Long => Contiguous::Long,
+        Chunked::Ident(ident) => Contiguous::Ident(ident),
+        Chunked::Number(number) => Contiguous::Number(number),
+        Chunked::String(string) => Contiguous::String(string),
+    }
+}
+
+type Lexed<'a> = Vec<(Result<Contiguous<'a>, UnknownToken>, String, Span)>;
+
+fn lex_contiguous(source: &str) -> Lexed<'_> {
+    let mut lexer = Contiguous::lexer(source);
+    let mut out = Vec::new();
+
+    while let Some(token) = lexer.next() {
+        out.push((token, lexer.slice().to_owned(), lexer.span()));
+    }
+
+    out
+}
+
+fn lex_chunked<'a>(source: &'a Segmented<'a>) -> Lexed<'a> {
+    let mut lexer = Chunked::lexer(source);
+    let mut out = Vec::new();
+
+    while let Some(token) = lexer.next() {
+        out.push((token.map(convert), lexer.slice().to_owned(), lexer.span()));
+    }
+
+    out
+}
+
+/// Split `source` into segments of (roughly) `size` bytes, without splitting any characters.
+fn split(source: &str, size: usize) -> Vec<&str> {
+    let mut segments = Vec::new();
+    let mut rest = source;
+
+    while !rest.is_empty() {
+        let mut at = size.min(rest.len());
+
+        while !rest.is_char_boundary(at) {
+            at += 1;
+        }
+
+        let (segment, tail) = rest.split_at(at);
+        segments.push(segment);
+        rest = tail;
+    }
+
+    segments
+}
+
+const SOURCE: &str = "let x = \"a string literal that is longer than a seam\" 12345 \
+                      extraordinarily_long_keyword_that_spills_over_seams ünïcödé ? let 42";
+
+#[test]
+fn same_as_contiguous() {
+    let expected = lex_contiguous(SOURCE);
+
+    for size in 1..=SOURCE.len() {
+        let source = Segmented::new(split(SOURCE, size));
+
+        assert_eq!(lex_chunked(&source), expected, "segment size {}", size);
+    }
+}
+
+#[test]
+fn slices_borrow_from_segments() {
+    let segments = ["let foo", "bar 1", "2"];
+    let source: Segmented = segments.iter().copied().collect();
+    let tokens: Vec<_> = Chunked::lexer(&source).collect();
+
+    assert_eq!(
+        tokens,
+        &[
+            Ok(Chunked::Let),
+            Ok(Chunked::Ident("foobar")),
+            Ok(Chunked::Number(12)),
+        ]
+    );
+
+    // `foo` lies within the first segment, so it's sliced directly from it.
+    let foo = source.slice(4..7).unwrap();
+
+    assert_eq!(foo, "foo");
+    assert!(std::ptr::eq(foo.as_ptr(), segments[0][4..].as_ptr()));
+}
+
+#[test]
+fn source_methods() {
+    let source = Segmented::new(["", "ab", "", "cdé", "f"]);
+
+    assert_eq!(source.segments(), &["ab", "cdé", "f"]);
+    assert_eq!(source.len(), 7);
+    assert_eq!(source.read::<u8>(2), Some(b'c'));
+    assert_eq!(source.read::<&[u8; 3]>(1), Some(b"bcd"));
+    assert_eq!(source.read::<&[u8; 2]>(6), None);
+    assert_eq!(source.slice(1..4), Some("bcd"));
+    assert_eq!(source.slice(3..5), None);
+    assert_eq!(source.slice(0..7), Some("abcdéf"));
+    assert_eq!(source.find_boundary(5), 6);
+    assert!(source.is_boundary(7));
+    assert!(!source.is_boundary(8));
+}
+
+#[test]
+fn empty() {
+    let source = Segmented::new(Vec::<&str>::new());
+
+    assert!(source.is_empty());
+    assert_eq!(source.slice(0..0), Some(""));
+    assert_eq!(lex_chunked(&source), &[]);
+}
+
+#[derive(Logos, Debug, Clone, PartialEq)]
+#[logos(source = Segmented<'s>, owned = OwnedViewed, owned_derive(Debug, PartialEq))]
+enum Viewed<'s> {
+    #[regex(r"[ \t\n]+", logos::skip)]
+    Whitespace,
+
+    #[regex(r"\p{Alphabetic}+")]
+    Ident(SliceView<'s>),
+
+    #[regex(r#""[^"]*""#)]
+    String(SliceView<'s, str>),
+}
+
+#[test]
+fn views() {
+    for size in 1..=SOURCE.len() {
+        let source = Segmented::new(split(SOURCE, size));
+        let mut lexer = Viewed::lexer(&source);
+
+        while let Some(token) = lexer.next() {
+            let view = match token {
+                Ok(Viewed::Ident(view) | Viewed::String(view)) => view,
+                _ => continue,
+            };
+
+            assert_eq!(view, lexer.slice(), "segment size {}", size);
+            // Only tokens that are longer than a seam need to be copied.
+            if let SliceView::Owned(_) = view {
+                assert!(view.len() > 32, "segment size {}", size);
+            }
+        }
+    }
+}
+
+#[test]
+fn views_borrow_from_segments() {
+    let segments = [
+        "\"a string literal",
+        " that is longer than a seam\" foo",
+        "bar",
+    ];
+    let source: Segmented = segments.iter().copied().collect();
+    let tokens: Vec<_> = Viewed::lexer(&source).collect();
+
+    assert_eq!(
+        tokens,
+        &[
+            Ok(Viewed::String(SliceView::Owned(
+                "\"a string literal that is longer than a seam\"".to_owned()
+            ))),
+            Ok(Viewed::Ident(SliceView::Borrowed("foobar"))),
+        ]
+    );
+
+    // `foobar` straddles segments, but fits in the seam between them, while `a s` is within the first segment.
+    let view = source.view(1..4).unwrap();
+
+    assert_eq!(view, "a s");
+    assert!(std::ptr::eq(view.as_ptr(), segments[0][1..].as_ptr()));
+    assert_eq!(source.view(0..100), None);
+    assert_eq!(
+        tokens[0].as_ref().unwrap().to_owned(),
+        OwnedViewed::String("\"a string literal that is longer than a seam\"".to_owned())
+    );
+}
+
+mod bytes {
+    use super::*;
+
+    macro_rules! frame {
+        ($name:ident, $source:ty) => {
+            #[derive(Logos, Debug, Clone, PartialEq)]
+            #[logos(source = $source)]
+            enum $name<'a> {
+                #[regex(b"\x01[^\x00]*\x00")]
+                Text(&'a [u8]),
+
+                #[regex(b"\x02(?-u:.)")]
+                Byte(&'a [u8]),
+
+                #[token(b"\x03")]
+                Ping,
+            }
+        };
+    }
+
+    frame!(Contiguous, [u8]);
+    frame!(Chunked, Segmented<'a, [u8]>);
+
+    const SOURCE: &[u8] =
+        b"\x03\x01a message that is longer than a seam\x00\x02\xFF\x03\x02\x80\x01\x00\x03";
+
+    fn lex_contiguous(source: &[u8]) -> Vec<(Result<Contiguous<'_>, UnknownToken>, Span)> {
+        let mut lexer = Contiguous::lexer(source);
+        let mut out = Vec::new();
+
+        while let Some(token) = lexer.next() {
+            out.push((token, lexer.span()));
+        }
+
+        out
+    }
+
+    fn lex_chunked<'a>(
+        source: &'a Segmented<'a, [u8]>,
+    ) -> Vec<(Result<Contiguous<'a>, UnknownToken>, Span)> {
+        let mut lexer = Chunked::lexer(source);
+        let mut out = Vec::new();
+
+        while let Some(token) = lexer.next() {
+            let token = token.map(|token| match token {
+                Chunked::Text(text) => Contiguous::Text(text),
+                Chunked::Byte(byte) => Contiguous::Byte(byte),
+                Chunked::Ping => Contiguous::Ping,
+            });
+
+            out.push((token, lexer.span()));
+        }
+
+        out
+    }
+
+    #[test]
+    fn same_as_contiguous() {
+        let expected = lex_contiguous(SOURCE);
+
+        assert_eq!(expected.len(), 7);
+
+        // Segments of bytes can be split anywhere.
+        for size in 1..=SOURCE.len() {
+            let segments: Vec<&[u8]> = SOURCE.chunks(size).collect();
+            let source = Segmented::from(&segments[..]);
+
+            assert_eq!(lex_chunked(&source), expected, "segment size {}", size);
+        }
+    }
+
+    #[test]
+    fn source_methods() {
+        let segments: [&[u8]; 3] = [b"\xE2\x82", b"", b"\xACa"];
+        let source = Segmented::from(&segments[..]);
+
+        assert_eq!(source.len(), 4);
+        assert_eq!(source.read::<&[u8; 2]>(1), Some(b"\x82\xAC"));
+        assert_eq!(source.slice(1..3), Some(&b"\x82\xAC"[..]));
+        assert!(source.is_boundary(1));
+        assert_eq!(source.find_boundary(1), 1);
+        assert_eq!(
+            source.view(0..4),
+            Some(SliceView::Borrowed(&b"\xE2\x82\xACa"[..]))
+        );
+    }
+}
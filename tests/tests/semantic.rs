@@ -0,0 +1,107 @@
+use logos::semantic::{encode, Encoder, PositionEncoding, Semantic};
+use logos::Logos;
+
+#[derive(Logos, Debug, PartialEq)]
+enum Token<'s> {
+    #[regex(r"[ \t\r\n]+", logos::skip)]
+    Whitespace,
+
+    #[regex(r#""[^"]*""#)]
+    String(&'s str),
+
+    #[regex(r"[^ \t\r\n\x22]+")]
+    Word(&'s str),
+}
+
+fn semantic(token: &Token) -> Option<Semantic> {
+    match token {
+        Token::String(_) => Some(Semantic::new(1)),
+        Token::Word("fn") => Some(Semantic::new(0).with_modifiers(0b101)),
+        _ => None,
+    }
+}
+
+fn lex(source: &str) -> Vec<(Token<'_>, logos::Span)> {
+    Token::lexer(source).spanned().map(Result::unwrap).collect()
+}
+
+#[test]
+fn utf16_columns() {
+    let source = "😀 fn \"é\" fn";
+
+    assert_eq!(
+        encode(source, lex(source), semantic),
+        &[0, 3, 2, 0, 0b101, 0, 3, 3, 1, 0, 0, 4, 2, 0, 0b101]
+    );
+}
+
+#[test]
+fn other_encodings() {
+    let source = "😀 \"é\"";
+    let encoded = |encoding| {
+        let mut encoder = Encoder::new(source).position_encoding(encoding);
+
+        for (token, span) in lex(source) {
+            if let Some(semantic) = semantic(&token) {
+                encoder.push(span, semantic);
+            }
+        }
+
+        encoder.finish()
+    };
+
+    assert_eq!(encoded(PositionEncoding::Utf8), &[0, 5, 4, 1, 0]);
+    assert_eq!(encoded(PositionEncoding::Utf32), &[0, 2, 3, 1, 0]);
+}
+
+#[test]
+fn tokens_spanning_lines() {
+    let source = "fn \"a\r\n\r\nbc\rd\"\nfn";
+
+    assert_eq!(
+        encode(source, lex(source), semantic),
+        &[
+            0, 0, 2, 0, 0b101, // fn
+            0, 3, 2, 1, 0, // "a
+            2, 0, 2, 1, 0, // bc, after an empty line
+            1, 0, 2, 1, 0, // d"
+            1, 0, 2, 0, 0b101, // fn
+        ]
+    );
+
+    let mut encoder = Encoder::new(source).multiline(true);
+
+    for (token, span) in lex(source) {
+        if let Some(semantic) = semantic(&token) {
+            encoder.push(span, semantic);
+        }
+    }
+
+    assert_eq!(
+        encoder.finish(),
+        &[0, 0, 2, 0, 0b101, 0, 3, 11, 1, 0, 4, 0, 2, 0, 0b101]
+    );
+}
+
+#[test]
+fn tokens_after_a_multiline_token() {
+    let source = "\"a\nb\" fn";
+    let mut encoder = Encoder::new(source).multiline(true);
+
+    for (token, span) in lex(source) {
+        if let Some(semantic) = semantic(&token) {
+            encoder.push(span, semantic);
+        }
+    }
+
+    assert_eq!(encoder.finish(), &[0, 0, 5, 1, 0, 1, 3, 2, 0, 0b101]);
+}
+
+#[test]
+#[should_panic(expected = "isn't in the source after the previous token")]
+fn tokens_out_of_order() {
+    let mut encoder = Encoder::new("a b");
+
+    encoder.push(2..3, Semantic::new(0));
+    encoder.push(0..1, Semantic::new(0));
+}
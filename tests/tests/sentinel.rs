@@ -0,0 +1,130 @@
+use logos::{Logos, UnknownToken};
+use tests::assert_lex;
+
+mod text {
+    use super::*;
+
+    #[derive(Logos, Debug, PartialEq)]
+    #[logos(sentinel = b"\0")]
+    enum Token {
+        #[regex(r"[ \t\n]+", logos::skip)]
+        Whitespace,
+
+        #[regex(r#""[^"]*""#)]
+        String,
+
+        #[regex("[a-z]+")]
+        Word,
+
+        #[regex(r"#.*")]
+        Comment,
+    }
+
+    #[test]
+    fn sentinels_end_tokens() {
+        assert_lex(
+            "foo\0bar # note\0 \"a\0b\"",
+            &[
+                (Ok(Token::Word), "foo", 0..3),
+                (Err(UnknownToken), "\0", 3..4),
+                (Ok(Token::Word), "bar", 4..7),
+                (Ok(Token::Comment), "# note", 8..14),
+                (Err(UnknownToken), "\0", 14..15),
+                // The string can't contain the sentinel, so it's cut short.
+                (Err(UnknownToken), "\"a", 16..18),
+                (Err(UnknownToken), "\0", 18..19),
+                (Ok(Token::Word), "b", 19..20),
+                (Err(UnknownToken), "\"", 20..21),
+            ],
+        );
+    }
+}
+
+mod errors {
+    use super::*;
+    use logos::Lexer;
+
+    #[derive(Debug, Clone, PartialEq)]
+    enum Error {
+        Unknown,
+        Sentinel(u8),
+    }
+
+    impl<'s> logos::Error<'s, Token> for Error {
+        fn unknown_token(_: &Lexer<'s, Token>) -> Self {
+            Error::Unknown
+        }
+
+        fn sentinel(lex: &Lexer<'s, Token>) -> Self {
+            Error::Sentinel(lex.slice()[0])
+        }
+    }
+
+    #[derive(Logos, Debug, Clone, PartialEq)]
+    #[logos(error = Error, sentinel = b"\0", sentinel = b"\xFF")]
+    #[logos(recover_to = b";")]
+    enum Token {
+        #[regex(b"[a-z]+", priority = 2)]
+        Word,
+
+        #[token(b";")]
+        Semicolon,
+
+        #[regex(b"(?-u).")]
+        Any,
+    }
+
+    #[test]
+    fn dedicated_error() {
+        assert_lex(
+            b"ab\xFF\0;" as &[u8],
+            &[
+                (Ok(Token::Word), b"ab" as &[u8], 0..2),
+                (Err(Error::Sentinel(0xFF)), b"\xFF", 2..3),
+                (Err(Error::Sentinel(0)), b"\0", 3..4),
+                (Ok(Token::Semicolon), b";", 4..5),
+            ],
+        );
+    }
+
+    #[test]
+    fn any_byte_excludes_sentinels() {
+        assert_lex(
+            b"a-\0" as &[u8],
+            &[
+                (Ok(Token::Word), b"a" as &[u8], 0..1),
+                (Ok(Token::Any), b"-", 1..2),
+                (Err(Error::Sentinel(0)), b"\0", 2..3),
+            ],
+        );
+    }
+}
+
+mod recovery {
+    use super::*;
+
+    #[derive(Logos, Debug, PartialEq)]
+    #[logos(sentinel = b"\0", recover_to = ";")]
+    enum Token {
+        #[regex("[a-z]+")]
+        Word,
+
+        #[token(";")]
+        Semicolon,
+    }
+
+    #[test]
+    fn recovery_stops_at_sentinels() {
+        assert_lex(
+            "a%%\0%;b",
+            &[
+                (Ok(Token::Word), "a", 0..1),
+                (Err(UnknownToken), "%%", 1..3),
+                (Err(UnknownToken), "\0", 3..4),
+                (Err(UnknownToken), "%", 4..5),
+                (Ok(Token::Semicolon), ";", 5..6),
+                (Ok(Token::Word), "b", 6..7),
+            ],
+        );
+    }
+}
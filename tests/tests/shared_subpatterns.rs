@@ -0,0 +1,84 @@
+use logos::Logos;
+use tests::assert_lex;
+
+logos::subpatterns! {
+    /// Subpatterns shared by both modes of the lexer.
+    shared {
+        ident = "[a-zA-Z_][a-zA-Z0-9_]*",
+        number = "[0-9]+(\\.[0-9]+)?",
+        keyword = "let|fn" ignore(case),
+    }
+}
+
+shared! {
+    #[derive(Logos, Debug, PartialEq)]
+    pub enum Outer<'s> {
+        #[regex(r"[ \t\n]+", logos::skip)]
+        Whitespace,
+
+        #[regex("(?&keyword)")]
+        Keyword,
+
+        #[regex("(?&ident)", |lex| lex.slice())]
+        Ident(&'s str),
+
+        #[regex("(?&number)")]
+        Number,
+
+        #[token("\"")]
+        Quote,
+    }
+}
+
+mod inner {
+    use super::*;
+
+    shared! {
+        /// Tokens inside of string interpolations.
+        #[derive(Logos, Debug, PartialEq)]
+        #[logos(subpattern escape = r#"\\["\\nt]"#)]
+        // Local subpatterns can use the shared ones.
+        #[logos(subpattern placeholder = r"\{(?&ident)\}")]
+        pub(crate) enum Inner {
+            #[regex(r#"([^"\\{]|(?&escape))+"#)]
+            Text,
+
+            #[regex("(?&placeholder)")]
+            Interpolation,
+
+            #[regex("\\{(?&number)\\}")]
+            Positional,
+
+            #[token("\"")]
+            Quote,
+        }
+    }
+}
+
+#[test]
+fn outer() {
+    assert_lex(
+        "LET x = 1.5 \"",
+        &[
+            (Ok(Outer::Keyword), "LET", 0..3),
+            (Ok(Outer::Ident("x")), "x", 4..5),
+            (Err(logos::UnknownToken), "=", 6..7),
+            (Ok(Outer::Number), "1.5", 8..11),
+            (Ok(Outer::Quote), "\"", 12..13),
+        ],
+    );
+}
+
+#[test]
+fn inner() {
+    assert_lex(
+        r#"a \"b\" {name} {0}""#,
+        &[
+            (Ok(inner::Inner::Text), r#"a \"b\" "#, 0..8),
+            (Ok(inner::Inner::Interpolation), "{name}", 8..14),
+            (Ok(inner::Inner::Text), " ", 14..15),
+            (Ok(inner::Inner::Positional), "{0}", 15..18),
+            (Ok(inner::Inner::Quote), "\"", 18..19),
+        ],
+    );
+}
@@ -0,0 +1,83 @@
+use logos::shell::{split, Piece, SplitError};
+use logos::Logos;
+use std::borrow::Cow;
+
+#[test]
+fn whitespace() {
+    assert_eq!(split("  a\tb \n c  ").unwrap(), &["a", "b", "c"]);
+    assert!(split("").unwrap().is_empty());
+    assert!(split(" \t\n").unwrap().is_empty());
+}
+
+#[test]
+fn quotes() {
+    assert_eq!(
+        split(r#"'a b' "c d" e'f'"g" '' """#).unwrap(),
+        &["a b", "c d", "efg", "", ""]
+    );
+    assert_eq!(
+        split(r#"'"' "'" '\n' "\n""#).unwrap(),
+        &["\"", "'", r"\n", r"\n"]
+    );
+}
+
+#[test]
+fn escapes() {
+    assert_eq!(
+        split(r#"a\ b \'c\" \\ \é"#).unwrap(),
+        &["a b", "'c\"", "\\", "é"]
+    );
+    assert_eq!(split(r#""\$ \` \" \\ \a""#).unwrap(), &[r#"$ ` " \ \a"#]);
+}
+
+#[test]
+fn line_continuations() {
+    assert_eq!(split("a\\\nb c \\\n d").unwrap(), &["ab", "c", "d"]);
+    assert_eq!(split("\"a\\\nb\"").unwrap(), &["ab"]);
+    assert_eq!(split("'a\\\nb'").unwrap(), &["a\\\nb"]);
+}
+
+#[test]
+fn borrows_where_possible() {
+    let words = split(r#"plain 'quoted' "double" a\ b"#).unwrap();
+
+    assert!(matches!(words[0], Cow::Borrowed("plain")));
+    assert!(matches!(words[1], Cow::Borrowed("quoted")));
+    assert!(matches!(words[2], Cow::Borrowed("double")));
+    assert!(matches!(words[3], Cow::Owned(_)));
+}
+
+#[test]
+fn errors() {
+    assert_eq!(
+        split("echo 'oops"),
+        Err(SplitError::UnterminatedQuote(5..6))
+    );
+    assert_eq!(
+        split(r#"echo "a\" b"#),
+        Err(SplitError::UnterminatedQuote(5..6))
+    );
+    assert_eq!(split("echo a\\"), Err(SplitError::TrailingBackslash(6..7)));
+    assert_eq!(
+        split("echo 'oops").unwrap_err().to_string(),
+        "unterminated quote at 5..6"
+    );
+}
+
+#[test]
+fn pieces() {
+    let pieces: Vec<_> = Piece::lexer(r#"a"b\"" 'c'\ "#)
+        .map(Result::unwrap)
+        .collect();
+
+    assert_eq!(
+        pieces,
+        &[
+            Piece::Bare("a"),
+            Piece::DoubleQuoted(Cow::Owned(String::from("b\""))),
+            Piece::Space,
+            Piece::SingleQuoted("c"),
+            Piece::Escaped(" "),
+        ]
+    );
+}
@@ -0,0 +1,169 @@
+use logos::internal::{leading_in_ranges, leading_outside_ranges};
+use logos::Logos;
+use tests::assert_lex;
+
+fn leading_in_ranges_slowly(chunk: &[u8; 16], ranges: &[(u8, u8)]) -> usize {
+    chunk
+        .iter()
+        .take_while(|&&byte| ranges.iter().any(|&(lo, hi)| (lo..=hi).contains(&byte)))
+        .count()
+}
+
+#[test]
+fn scanning_agrees_with_a_byte_loop() {
+    let classes: [&[(u8, u8)]; 4] = [
+        &[(b'a', b'z')],
+        &[(0, b'!'), (b'#', 0xFF)],
+        &[(b'0', b'9'), (b'A', b'Z'), (b'_', b'_'), (b'a', b'z')],
+        &[(0x80, 0xBF), (b'\t', b'\n')],
+    ];
+
+    // A simple xorshift, so that every run checks the same chunks.
+    let mut state = 0x2545_F491_4F6C_DD1Du64;
+    let mut random = || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state
+    };
+
+    for _ in 0..2000 {
+        let mut chunk = [0u8; 16];
+
+        for byte in &mut chunk {
+            // Mostly bytes that are interesting to at least one of the classes, with the odd random byte.
+            let pick = random();
+
+            *byte = match pick % 8 {
+                0 => pick.to_le_bytes()[1],
+                1 => b'"',
+                2 => 0x80 + (pick >> 8) as u8 % 0x40,
+                3 => b'\t',
+                4 => b'_',
+                _ => b"az09AZ`{/:@["[(pick >> 8) as usize % 12],
+            };
+        }
+
+        for class in classes {
+            let expected = leading_in_ranges_slowly(&chunk, class);
+            let actual = match *class {
+                [a] => leading_in_ranges(&chunk, &[a]),
+                [a, b] => leading_in_ranges(&chunk, &[a, b]),
+                [a, b, c, d] => leading_in_ranges(&chunk, &[a, b, c, d]),
+                _ => unreachable!(),
+            };
+
+            assert_eq!(actual, expected, "{:?} in {:?}", chunk, class);
+
+            let expected = chunk
+                .iter()
+                .take_while(|&&byte| !class.iter().any(|&(lo, hi)| (lo..=hi).contains(&byte)))
+                .count();
+            let actual = match *class {
+                [a] => leading_outside_ranges(&chunk, &[a]),
+                [a, b] => leading_outside_ranges(&chunk, &[a, b]),
+                [a, b, c, d] => leading_outside_ranges(&chunk, &[a, b, c, d]),
+                _ => unreachable!(),
+            };
+
+            assert_eq!(actual, expected, "{:?} outside {:?}", chunk, class);
+        }
+    }
+}
+
+#[derive(Logos, Debug, Clone, Copy, PartialEq)]
+enum Token {
+    #[regex(r"[ \t\r\n]+", logos::skip)]
+    Whitespace,
+
+    #[regex("[a-zA-Z_][a-zA-Z0-9_]*")]
+    Ident,
+
+    #[regex(r#""[^"]*""#)]
+    String,
+}
+
+#[test]
+fn long_tokens() {
+    let ident = "some_rather_long_identifier_that_needs_several_chunks_0123456789";
+    let string = "\"a string with ünïcödé in it, long enough for a few chunks\"";
+    let source = format!("{ident}\n\n\t                   {string} x{ident}");
+
+    let space = ident.len() + 22;
+    let end = space + string.len();
+
+    assert_lex(
+        &source[..],
+        &[
+            (Ok(Token::Ident), ident, 0..ident.len()),
+            (Ok(Token::String), string, space..end),
+            (Ok(Token::Ident), &source[end + 1..], end + 1..source.len()),
+        ],
+    );
+
+    for len in 0..ident.len() {
+        assert_lex(
+            &ident[..len],
+            &[(Ok(Token::Ident), &ident[..len], 0..len)][..len.min(1)],
+        );
+    }
+}
+
+mod negated {
+    use super::*;
+
+    #[derive(Logos, Debug, Clone, Copy, PartialEq)]
+    enum Token {
+        #[regex(b"[ \t\n]+", logos::skip)]
+        Whitespace,
+
+        // Matches every byte but three, so loops stop at `"`, a backslash or a newline instead.
+        #[regex(b"(?-u)\"[^\"\\\\\n]*\"")]
+        String,
+
+        #[token(b"\\")]
+        Backslash,
+    }
+
+    #[test]
+    fn long_strings() {
+        let mut string = b"\"".to_vec();
+
+        for byte in (0..=0xFFu8).cycle().take(600) {
+            if !matches!(byte, b'"' | b'\\' | b'\n') {
+                string.push(byte);
+            }
+        }
+        string.push(b'"');
+
+        for len in [2, 3, 17, 18, 40, string.len()] {
+            let mut source = string[..len - 1].to_vec();
+
+            source.extend_from_slice(b"\" \\ ");
+
+            assert_lex(
+                &source[..],
+                &[
+                    (Ok(Token::String), &source[..len], 0..len),
+                    (Ok(Token::Backslash), b"\\", len + 1..len + 2),
+                ],
+            );
+        }
+    }
+
+    #[test]
+    fn stops_at_a_newline() {
+        let mut source = vec![b'"'];
+
+        source.extend((0..40).map(|n| b'a' + n % 26));
+        source.extend_from_slice(b"\n\\");
+
+        let mut lex = Token::lexer(&source[..]);
+
+        assert_eq!(lex.next(), Some(Err(logos::UnknownToken)));
+        assert_eq!(lex.span(), 0..41);
+        assert_eq!(lex.next(), Some(Ok(Token::Backslash)));
+        assert_eq!(lex.span(), 42..43);
+        assert_eq!(lex.next(), None);
+    }
+}
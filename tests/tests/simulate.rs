@@ -0,0 +1,79 @@
+use logos::{Logos, UnknownToken};
+use tests::assert_lex;
+
+#[derive(Logos, Debug, PartialEq)]
+enum Token {
+    #[regex(r"[ \t\n]+", logos::skip)]
+    Whitespace,
+
+    // Remembering the last 12 bytes takes thousands of states.
+    #[regex(
+        "[ab]*a[ab][ab][ab][ab][ab][ab][ab][ab][ab][ab][ab][ab]",
+        strategy = "callback"
+    )]
+    Tail,
+
+    #[token("a")]
+    A,
+
+    #[regex("[0-9]+")]
+    Number,
+}
+
+#[derive(Logos, Debug, PartialEq)]
+enum Simulated {
+    #[regex(r"[ \t\n]+", logos::skip)]
+    Whitespace,
+
+    #[regex(r#""([^"\\]|\\.)*""#, strategy = "callback")]
+    String,
+
+    #[regex("(foo|foobar)(baz)?", strategy = "callback")]
+    Foo,
+
+    #[regex("[g-z]+")]
+    Word,
+}
+
+#[test]
+fn longest_match() {
+    assert_lex(
+        "bbbabbbbbbbbbbbbab 12 aaaaaaaaaaaaa",
+        &[
+            // The match has to end 12 bytes after an `a`, so the last two bytes aren't part of it.
+            (Ok(Token::Tail), "bbbabbbbbbbbbbbb", 0..16),
+            (Ok(Token::A), "a", 16..17),
+            (Err(UnknownToken), "b", 17..18),
+            (Ok(Token::Number), "12", 19..21),
+            (Ok(Token::Tail), "aaaaaaaaaaaaa", 22..35),
+        ],
+    );
+}
+
+#[test]
+fn falls_back_or_errors() {
+    assert_lex(
+        "a b 1",
+        &[
+            (Ok(Token::A), "a", 0..1),
+            (Err(UnknownToken), "b", 2..3),
+            (Ok(Token::Number), "1", 4..5),
+        ],
+    );
+}
+
+#[test]
+fn same_tokens_as_the_state_machine() {
+    assert_lex(
+        r#""a\"b" "onyx foobarbaz foox foo"#,
+        &[
+            (Ok(Simulated::String), r#""a\"b""#, 0..6),
+            (Err(UnknownToken), "\"", 7..8),
+            (Ok(Simulated::Word), "onyx", 8..12),
+            (Ok(Simulated::Foo), "foobarbaz", 13..22),
+            (Ok(Simulated::Foo), "foo", 23..26),
+            (Ok(Simulated::Word), "x", 26..27),
+            (Ok(Simulated::Foo), "foo", 28..31),
+        ],
+    );
+}
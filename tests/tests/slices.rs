@@ -0,0 +1,81 @@
+use logos::{LexerExt, Logos};
+
+#[derive(Logos, Debug, PartialEq)]
+#[logos(extras = usize)]
+enum Token {
+    #[regex(r"[ \t\n]+", logos::skip)]
+    Whitespace,
+
+    #[regex("[0-9]+", |lex| { lex.extras += 1; lex.slice().parse().ok() })]
+    Number(u64),
+
+    #[regex("[a-z]+", |lex| { lex.extras += 1; })]
+    Word,
+
+    #[token("+")]
+    Plus,
+}
+
+#[derive(Logos, Debug, PartialEq)]
+#[logos(codegen = "compact")]
+enum Compact {
+    #[regex(r"[ \t\n]+", logos::skip)]
+    Whitespace,
+
+    #[regex("[0-9]+", |lex| lex.slice().parse().ok())]
+    Number(u64),
+
+    #[token("+")]
+    Plus,
+}
+
+#[test]
+fn kinds_and_spans() {
+    let slices: Vec<_> = Token::lexer("12 + ab?").slices().collect();
+
+    assert_eq!(
+        slices,
+        &[
+            ("12", 0..2, Some(1)),
+            (" ", 2..3, Some(0)),
+            ("+", 3..4, Some(3)),
+            (" ", 4..5, Some(0)),
+            ("ab", 5..7, Some(2)),
+            ("?", 7..8, None),
+        ],
+    );
+}
+
+#[test]
+fn callbacks_are_not_run() {
+    let mut slices = Token::lexer("1 a 2 b").slices();
+
+    assert_eq!(slices.by_ref().count(), 7);
+    assert_eq!(*slices.extras(), 0);
+}
+
+#[test]
+fn into_lexer_constructs_tokens_again() {
+    let mut slices = Token::lexer("1 2").slices();
+
+    assert_eq!(slices.next(), Some(("1", 0..1, Some(1))));
+
+    let mut lexer = slices.into_lexer();
+
+    assert_eq!(lexer.next(), Some(Ok(Token::Number(2))));
+    assert_eq!(lexer.extras, 1);
+}
+
+#[test]
+fn compact() {
+    let slices: Vec<_> = Compact::lexer("3+45").slices().collect();
+
+    assert_eq!(
+        slices,
+        &[
+            ("3", 0..1, Some(1)),
+            ("+", 1..2, Some(2)),
+            ("45", 2..4, Some(1))
+        ],
+    );
+}
@@ -0,0 +1,83 @@
+use logos::{Lexer, Logos};
+
+#[derive(Logos, Debug, PartialEq)]
+#[logos(sniff)]
+enum Token<'a> {
+    #[regex(r"[ \t\n]+", logos::skip)]
+    Whitespace,
+
+    #[regex(r"//[^\n]*", logos::skip)]
+    Comment,
+
+    #[token("let")]
+    Let,
+
+    #[regex("[a-z]+")]
+    Ident(&'a str),
+
+    #[regex("[0-9]+")]
+    Number,
+}
+
+#[test]
+fn sniff() {
+    assert_eq!(Token::sniff("let x"), Some((Token::Let, 3)));
+    assert_eq!(Token::sniff("lettuce"), Some((Token::Ident("lettuce"), 7)));
+    assert_eq!(Token::sniff("42abc"), Some((Token::Number, 2)));
+    assert_eq!(Token::sniff(""), None);
+    assert_eq!(Token::sniff("!let"), None);
+    assert_eq!(Token::sniff(" let"), None);
+    assert_eq!(Token::sniff("// let\nlet"), None);
+}
+
+#[test]
+fn sniff_skipping() {
+    assert_eq!(Token::sniff_skipping("let"), Some((Token::Let, 0..3)));
+    assert_eq!(
+        Token::sniff_skipping("  // note\n\tx = 1"),
+        Some((Token::Ident("x"), 11..12))
+    );
+    assert_eq!(Token::sniff_skipping(" // only a comment"), None);
+    assert_eq!(Token::sniff_skipping(" !"), None);
+}
+
+struct Extras(usize);
+
+fn count(lex: &mut Lexer<Hooked>) {
+    lex.extras.0 += 1;
+}
+
+#[derive(Logos, Debug, PartialEq)]
+#[logos(sniff, extras = Extras, on_token = count)]
+enum Hooked {
+    #[regex("[a-z]+")]
+    Word,
+
+    #[regex("[0-9]+", |lex| lex.slice().len())]
+    Number(usize),
+
+    #[token("<", not_followed_by = "<")]
+    Less,
+
+    #[token("<<<")]
+    Shift,
+}
+
+#[test]
+fn no_extras_or_hooks() {
+    // `Extras` has no `Default`, so this would fail to compile if a `Lexer<Hooked>` were built.
+    assert_eq!(Hooked::sniff("abc1"), Some((Hooked::Word, 3)));
+}
+
+#[test]
+fn callbacks_are_not_sniffed() {
+    assert_eq!(Hooked::sniff("123"), None);
+    assert_eq!(Hooked::sniff_skipping("123"), None);
+}
+
+#[test]
+fn back_off() {
+    assert_eq!(Hooked::sniff("<a"), Some((Hooked::Less, 1)));
+    assert_eq!(Hooked::sniff("<<<"), Some((Hooked::Shift, 3)));
+    assert_eq!(Hooked::sniff("<<"), None);
+}
@@ -0,0 +1,212 @@
+use logos::soa::{DeltaSpans, DeltaTokens, Split, TokenSoA};
+use logos::{Logos, Span, UnknownToken};
+
+#[derive(Logos, Debug, Clone, PartialEq)]
+enum Token<'a> {
+    #[regex(r"[ \t\n]+", logos::skip)]
+    Whitespace,
+
+    #[token("(")]
+    Open,
+
+    #[token(")")]
+    Close,
+
+    #[regex("[a-z]+")]
+    Symbol(&'a str),
+
+    #[regex("[0-9]+", |lex| lex.slice().parse().ok())]
+    Number(u64),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Kind {
+    Open,
+    Close,
+    Symbol,
+    Number,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Payload<'a> {
+    Symbol(&'a str),
+    Number(u64),
+}
+
+impl<'a> Split for Token<'a> {
+    type Kind = Kind;
+    type Payload = Payload<'a>;
+
+    fn split(self) -> (Kind, Option<Payload<'a>>) {
+        match self {
+            Token::Whitespace => unreachable!(),
+            Token::Open => (Kind::Open, None),
+            Token::Close => (Kind::Close, None),
+            Token::Symbol(symbol) => (Kind::Symbol, Some(Payload::Symbol(symbol))),
+            Token::Number(number) => (Kind::Number, Some(Payload::Number(number))),
+        }
+    }
+}
+
+fn lex(source: &str) -> Result<TokenSoA<Token<'_>>, UnknownToken> {
+    Token::lexer(source).spanned().collect()
+}
+
+#[test]
+fn columns() {
+    let tokens = lex("(add 10 (neg 2))").unwrap();
+
+    assert_eq!(tokens.len(), 8);
+    assert_eq!(
+        tokens.kinds(),
+        &[
+            Kind::Open,
+            Kind::Symbol,
+            Kind::Number,
+            Kind::Open,
+            Kind::Symbol,
+            Kind::Number,
+            Kind::Close,
+            Kind::Close,
+        ]
+    );
+    assert_eq!(tokens.starts(), &[0, 1, 5, 8, 9, 13, 14, 15]);
+    assert_eq!(tokens.lens(), &[1, 3, 2, 1, 3, 1, 1, 1]);
+    assert_eq!(
+        tokens.payloads(),
+        &[
+            (1, Payload::Symbol("add")),
+            (2, Payload::Number(10)),
+            (4, Payload::Symbol("neg")),
+            (5, Payload::Number(2)),
+        ]
+    );
+}
+
+#[test]
+fn lookup() {
+    let tokens = lex("(add 10 (neg 2))").unwrap();
+
+    assert_eq!(tokens.get(0), Some((Kind::Open, 0..1, None)));
+    assert_eq!(
+        tokens.get(4),
+        Some((Kind::Symbol, 9..12, Some(&Payload::Symbol("neg"))))
+    );
+    assert_eq!(tokens.get(8), None);
+    assert_eq!(tokens.kind(6), Some(Kind::Close));
+    assert_eq!(tokens.span(2), Some(5..7));
+    assert_eq!(tokens.payload(3), None);
+    assert_eq!(tokens.payload(5), Some(&Payload::Number(2)));
+}
+
+#[test]
+fn iter_matches_get() {
+    let tokens = lex("a (b c) 1 2 (3)").unwrap();
+    let iterated: Vec<_> = tokens.iter().collect();
+    let indexed: Vec<_> = (0..tokens.len()).map(|i| tokens.get(i).unwrap()).collect();
+
+    assert_eq!(tokens.iter().len(), tokens.len());
+    assert_eq!(iterated, indexed);
+}
+
+#[test]
+fn same_tokens_as_spanned() {
+    let source = "(define (square x) (mul x x))";
+    let tokens = lex(source).unwrap();
+    let spanned: Vec<(Token, Span)> = Token::lexer(source)
+        .spanned()
+        .collect::<Result<_, _>>()
+        .unwrap();
+
+    assert_eq!(tokens.len(), spanned.len());
+
+    for ((kind, span, payload), (token, expected)) in tokens.iter().zip(spanned) {
+        assert_eq!((kind, payload.cloned()), token.split());
+        assert_eq!(span, expected);
+    }
+}
+
+#[test]
+fn stops_at_errors() {
+    assert_eq!(lex("(a ? b)").unwrap_err(), UnknownToken);
+}
+
+#[test]
+fn extend_existing() {
+    let mut tokens = TokenSoA::with_capacity(4);
+
+    tokens.extend(Token::lexer("a 1").spanned().map(Result::unwrap));
+    tokens.extend([(Token::Close, 3..4)]);
+
+    assert_eq!(tokens.kinds(), &[Kind::Symbol, Kind::Number, Kind::Close]);
+
+    tokens.clear();
+
+    assert!(tokens.is_empty());
+    assert!(tokens.payloads().is_empty());
+}
+
+mod delta {
+    use super::*;
+
+    #[test]
+    fn matches_token_soa() {
+        let source = "(add 10 (neg 2)) ".repeat(50);
+        let soa = lex(&source).unwrap();
+        let delta: DeltaTokens<Token> = Token::lexer(&source)
+            .spanned()
+            .collect::<Result<_, _>>()
+            .unwrap();
+
+        assert_eq!(delta.len(), soa.len());
+        assert_eq!(delta.kinds(), soa.kinds());
+        assert_eq!(delta.payloads(), soa.payloads());
+        assert!(delta.iter().eq(soa.iter()));
+
+        for index in 0..=soa.len() {
+            assert_eq!(delta.get(index), soa.get(index), "token {}", index);
+        }
+
+        // Every span is at most 3 bytes long, and at most a byte after the previous one.
+        assert_eq!(delta.spans().byte_len(), delta.len() * 2);
+    }
+
+    #[test]
+    fn large_and_backwards_spans() {
+        let spans = [
+            0..0,
+            5..300,
+            300..300,
+            usize::MAX - 10..usize::MAX,
+            3..4,
+            0..1_000_000,
+            999_999..1_000_001,
+        ];
+        let delta: DeltaSpans = spans.iter().cloned().collect();
+
+        assert_eq!(delta.len(), spans.len());
+        assert_eq!(delta.iter().collect::<Vec<Span>>(), spans);
+        assert_eq!(delta.iter().len(), spans.len());
+        assert_eq!(delta.get(3), Some(usize::MAX - 10..usize::MAX));
+        assert_eq!(delta.get(7), None);
+    }
+
+    #[test]
+    fn get_across_the_index() {
+        let spans: Vec<Span> = (0..1000).map(|i| i * 3..i * 3 + i % 7).collect();
+        let mut delta: DeltaSpans = spans.iter().cloned().collect();
+
+        for (index, span) in spans.iter().enumerate() {
+            assert_eq!(delta.get(index).as_ref(), Some(span));
+        }
+
+        delta.clear();
+
+        assert!(delta.is_empty());
+        assert_eq!(delta.get(0), None);
+
+        delta.push(10..12);
+
+        assert_eq!(format!("{:?}", delta), "[10..12]");
+    }
+}
@@ -0,0 +1,98 @@
+use logos::{LexerExt, Logos, Span, Span32};
+
+#[derive(Logos, Debug, Clone, PartialEq)]
+enum Token {
+    #[regex(r"[ \t\n]+", logos::skip)]
+    Whitespace,
+
+    #[regex("[a-z]+")]
+    Ident,
+
+    #[token("=")]
+    Equals,
+}
+
+/// A span that only keeps the start of a token, for a parser that finds the rest again when it needs it.
+#[derive(Debug, PartialEq)]
+struct Start(u32);
+
+impl TryFrom<Span> for Start {
+    type Error = ();
+
+    fn try_from(span: Span) -> Result<Start, ()> {
+        u32::try_from(span.start).map(Start).map_err(drop)
+    }
+}
+
+#[test]
+fn compact() {
+    assert_eq!(std::mem::size_of::<Span32>(), 8);
+    assert_eq!(std::mem::size_of::<(Token, Span32)>(), 12);
+}
+
+#[test]
+fn same_as_spanned() {
+    let source = "foo = bar baz\n= qux";
+    let spanned: Vec<_> = Token::lexer(source).spanned().collect();
+    let span32: Vec<_> = Token::lexer(source).spanned_as::<Span32, _, _>().collect();
+
+    assert_eq!(spanned.len(), span32.len());
+
+    for (spanned, span32) in spanned.into_iter().zip(span32) {
+        let (token, span) = spanned.unwrap();
+        let (token32, span32) = span32.unwrap();
+
+        assert_eq!(token, token32);
+        assert_eq!(Span::from(span32), span);
+        assert_eq!(span32.len() as usize, span.len());
+        assert_eq!(&source[span32.range()], &source[span]);
+    }
+}
+
+#[test]
+fn errors_pass_through() {
+    let tokens: Vec<_> = Token::lexer("a ? b").spanned_as::<Span32, _, _>().collect();
+
+    assert_eq!(
+        tokens,
+        &[
+            Ok((Token::Ident, Span32::new(0, 1))),
+            Err(logos::UnknownToken),
+            Ok((Token::Ident, Span32::new(4, 5))),
+        ]
+    );
+}
+
+#[test]
+fn custom_span_type() {
+    let tokens: Vec<_> = Token::lexer("a = b")
+        .lookahead()
+        .spanned_as::<Start, _, _>()
+        .collect();
+
+    assert_eq!(
+        tokens,
+        &[
+            Ok((Token::Ident, Start(0))),
+            Ok((Token::Equals, Start(2))),
+            Ok((Token::Ident, Start(4))),
+        ]
+    );
+}
+
+#[test]
+fn conversions() {
+    assert_eq!(Span32::try_from(3..5), Ok(Span32::new(3, 5)));
+    assert!(Span32::try_from(0..u32::MAX as usize + 1).is_err());
+    assert!(Span32::new(2, 2).is_empty());
+}
+
+#[test]
+#[should_panic(expected = "the span 4294967295..4294967296 can't be converted")]
+fn too_long() {
+    let mut tokens = Token::lexer("a")
+        .with_span_offset(u32::MAX as usize)
+        .spanned_as::<Span32, _, _>();
+
+    tokens.next();
+}
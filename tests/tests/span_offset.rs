@@ -0,0 +1,133 @@
+use logos::diagnostics::Diagnostics;
+use logos::{Lexer, LexerExt, Logos, SpannedError};
+
+#[derive(Logos, Debug, Clone, PartialEq)]
+#[logos(error = SpannedError<'s>, extras = Diagnostics<SpannedError<'s>>)]
+enum Token<'s> {
+    #[regex(r"[ \t\n]+", logos::skip)]
+    Whitespace,
+
+    #[regex("[a-z]+")]
+    Ident(&'s str),
+
+    #[regex("[0-9]+", |lex| lex.span().start)]
+    Number(usize),
+}
+
+const DOCUMENT: &str = "Some prose.\n\n```\nfoo 12 ? bar\n```\n";
+
+fn snippet() -> Lexer<'static, Token<'static>> {
+    let start = DOCUMENT.find("foo").unwrap();
+    let end = DOCUMENT.rfind("```").unwrap();
+
+    Token::lexer(&DOCUMENT[start..end]).with_span_offset(start)
+}
+
+#[test]
+fn spans_are_shifted() {
+    let tokens: Vec<_> = snippet().spanned().collect();
+
+    assert_eq!(
+        tokens,
+        &[
+            Ok((Token::Ident("foo"), 17..20)),
+            Ok((Token::Number(21), 21..23)),
+            Err(SpannedError {
+                span: 24..25,
+                slice: "?",
+                at_end: false,
+            }),
+            Ok((Token::Ident("bar"), 26..29)),
+        ]
+    );
+}
+
+#[test]
+fn slices_are_not_shifted() {
+    let mut lexer = snippet();
+
+    assert_eq!(lexer.span_offset(), 17);
+    assert_eq!(lexer.next(), Some(Ok(Token::Ident("foo"))));
+    assert_eq!(lexer.slice(), "foo");
+    assert_eq!(&DOCUMENT[lexer.span()], "foo");
+    assert_eq!(lexer.remainder(), " 12 ? bar\n");
+}
+
+#[test]
+fn diagnostics_are_shifted() {
+    let mut lexer = snippet().diagnose();
+
+    while lexer.next().is_some() {}
+
+    let diagnostics = lexer.extras();
+
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics.iter().next().unwrap().span, 24..25);
+}
+
+#[test]
+fn end_is_relative_to_the_snippet() {
+    let mut lexer = Token::lexer("foo ?").with_span_offset(100);
+
+    assert_eq!(lexer.next(), Some(Ok(Token::Ident("foo"))));
+    assert_eq!(
+        lexer.next(),
+        Some(Err(SpannedError {
+            span: 104..105,
+            slice: "?",
+            at_end: true,
+        }))
+    );
+}
+
+#[test]
+fn reset_clears_the_offset() {
+    let mut lexer = snippet();
+
+    lexer.reset("baz");
+
+    assert_eq!(lexer.span_offset(), 0);
+    assert_eq!(lexer.next(), Some(Ok(Token::Ident("baz"))));
+    assert_eq!(lexer.span(), 0..3);
+}
+
+#[test]
+fn set_for_each_snippet() {
+    let document = "```\nfoo\n```\n\n```\nbar 7\n```\n";
+    let mut lexer = Token::lexer("");
+    let mut spans = Vec::new();
+
+    for (start, _) in document.match_indices("```\n").step_by(2) {
+        let start = start + 4;
+        let end = start + document[start..].find("```").unwrap();
+
+        lexer.reset(&document[start..end]);
+        lexer.set_span_offset(start);
+
+        while let Some(token) = lexer.next() {
+            spans.push((token.unwrap(), lexer.span()));
+        }
+    }
+
+    assert_eq!(
+        spans,
+        &[
+            (Token::Ident("foo"), 4..7),
+            (Token::Ident("bar"), 17..20),
+            (Token::Number(21), 21..22),
+        ]
+    );
+}
+
+#[test]
+fn set_while_lexing() {
+    let mut lexer = Token::lexer("foo bar");
+
+    assert_eq!(lexer.next(), Some(Ok(Token::Ident("foo"))));
+
+    lexer.set_span_offset(100);
+
+    assert_eq!(lexer.span(), 100..103);
+    assert_eq!(lexer.next(), Some(Ok(Token::Ident("bar"))));
+    assert_eq!(lexer.span(), 104..107);
+}
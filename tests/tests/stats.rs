@@ -0,0 +1,90 @@
+use logos::Logos;
+
+#[derive(Logos, Debug, PartialEq)]
+#[logos(stats = "target/stats/fast.txt")]
+enum Fast {
+    #[regex(r"[ \t\n]+", logos::skip)]
+    Whitespace,
+
+    #[token("fn")]
+    Fn,
+
+    #[regex("[a-z]+")]
+    Ident,
+}
+
+#[derive(Logos, Debug, PartialEq)]
+#[logos(stats = "target/stats/compact.txt", codegen = "compact")]
+enum Compact {
+    #[regex(r"[ \t\n]+", logos::skip)]
+    Whitespace,
+
+    #[token("fn")]
+    Fn,
+
+    #[regex("[a-z]+")]
+    Ident,
+}
+
+/// Read the report in `file`, returning its title and the first number on each of the lines after it.
+fn read(file: &str) -> (String, Vec<(String, usize)>) {
+    let path = format!("{}/target/stats/{}", env!("CARGO_MANIFEST_DIR"), file);
+    let report = std::fs::read_to_string(path).unwrap();
+    let mut lines = report.lines();
+    let title = lines.next().unwrap().to_owned();
+    let stats = lines
+        .map(|line| {
+            let (name, value) = line.split_once(':').unwrap();
+            let value = value.split_whitespace().next().unwrap();
+
+            (name.to_owned(), value.parse().unwrap())
+        })
+        .collect();
+
+    (title, stats)
+}
+
+fn get(stats: &[(String, usize)], name: &str) -> usize {
+    stats.iter().find(|(stat, _)| stat == name).unwrap().1
+}
+
+#[test]
+fn fast() {
+    let (title, stats) = read("fast.txt");
+
+    assert_eq!(title, "Stats for the lexer of `Fast`:");
+    assert_eq!(get(&stats, "variants"), 3);
+    assert_eq!(get(&stats, "states"), 8);
+    assert_eq!(get(&stats, "transitions"), 14);
+    assert_eq!(get(&stats, "functions"), 8);
+    assert!(get(&stats, "code tokens") > 0);
+}
+
+#[test]
+fn compact() {
+    let (_, fast) = read("fast.txt");
+    let (title, compact) = read("compact.txt");
+
+    assert_eq!(title, "Stats for the lexer of `Compact`:");
+
+    // The state machine is the same, it's just turned into tables instead of functions.
+    for stat in ["variants", "states", "transitions"] {
+        assert_eq!(get(&compact, stat), get(&fast, stat), "{}", stat);
+    }
+
+    assert_eq!(get(&compact, "functions"), 0);
+    assert!(get(&compact, "table bytes") > 256);
+    assert!(get(&compact, "code tokens") < get(&fast, "code tokens"));
+}
+
+#[test]
+fn lexes() {
+    assert_eq!(
+        Fast::lexer("fn main").collect::<Vec<_>>(),
+        &[Ok(Fast::Fn), Ok(Fast::Ident)]
+    );
+    assert_eq!(
+        Compact::lexer("fn main").collect::<Vec<_>>(),
+        &[Ok(Compact::Fn), Ok(Compact::Ident)]
+    );
+}
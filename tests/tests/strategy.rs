@@ -0,0 +1,126 @@
+use logos::{Lexer, Logos, UnknownToken};
+use tests::assert_lex;
+
+// The same grammar, with and without the tables and unrolling that the default strategy uses.
+macro_rules! grammar {
+    ($name:ident $(, $($strategy:tt)*)?) => {
+        #[derive(Logos, Debug, Clone, PartialEq)]
+        $(#[logos($($strategy)*)])?
+        enum $name {
+            #[regex(r"[ \t\n]+", logos::skip)]
+            Whitespace,
+
+            #[regex("[a-zA-Z_$][a-zA-Z0-9_$]*")]
+            Ident,
+
+            #[regex("[0-9]+")]
+            Integer,
+
+            #[regex(r#""([^"\\]|\\.)*""#)]
+            String,
+
+            #[regex("[!#%&*+,-./:;<=>?@^|~]+")]
+            Operator,
+
+            #[token("(")]
+            Open,
+
+            #[token(")")]
+            Close,
+
+            #[token("[")]
+            OpenBracket,
+
+            #[token("]")]
+            CloseBracket,
+        }
+    };
+}
+
+grammar!(Tabled);
+grammar!(
+    Small,
+    jump_tables = false,
+    lookup_tables = false,
+    unroll = 1
+);
+grammar!(Unrolled, unroll = 5);
+grammar!(Split, max_inline = 2);
+
+fn lex<'s, Token>(source: &'s str) -> Vec<(String, std::ops::Range<usize>)>
+where
+    Token: Logos<'s, Source = str> + std::fmt::Debug,
+    Token::Extras: Default,
+    Token::Error: std::fmt::Debug,
+{
+    let mut lex: Lexer<'s, Token> = Token::lexer(source);
+    let mut tokens = Vec::new();
+
+    while let Some(token) = lex.next() {
+        tokens.push((format!("{:?}", token), lex.span()));
+    }
+
+    tokens
+}
+
+#[test]
+fn strategies_agree() {
+    let pieces = [
+        "x",
+        "identifier_longer_than_sixteen_bytes",
+        "$a1",
+        "0123456789",
+        "42",
+        "\"short\"",
+        "\"a string that is a fair bit longer\"",
+        "\"\\\"",
+        "\"open",
+        "+",
+        "<<=",
+        "->",
+        "(",
+        ")",
+        "[",
+        "]",
+        " ",
+        "\n\t ",
+        "`",
+        "é",
+    ];
+
+    // A simple xorshift, so that every run checks the same sources.
+    let mut state = 0x9E37_79B9_7F4A_7C15u64;
+    let mut random = || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state
+    };
+
+    for _ in 0..2000 {
+        let source: String = (0..random() % 8)
+            .map(|_| pieces[random() as usize % pieces.len()])
+            .collect();
+        let expected = lex::<Tabled>(&source);
+
+        assert_eq!(lex::<Small>(&source), expected, "{:?}", source);
+        assert_eq!(lex::<Unrolled>(&source), expected, "{:?}", source);
+        assert_eq!(lex::<Split>(&source), expected, "{:?}", source);
+    }
+}
+
+#[test]
+fn small_tokens() {
+    assert_lex(
+        "abc_def (x) <=> \"s\" `",
+        &[
+            (Ok(Small::Ident), "abc_def", 0..7),
+            (Ok(Small::Open), "(", 8..9),
+            (Ok(Small::Ident), "x", 9..10),
+            (Ok(Small::Close), ")", 10..11),
+            (Ok(Small::Operator), "<=>", 12..15),
+            (Ok(Small::String), "\"s\"", 16..19),
+            (Err(UnknownToken), "`", 20..21),
+        ],
+    );
+}
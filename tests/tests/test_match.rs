@@ -0,0 +1,60 @@
+use logos::Logos;
+
+#[derive(Logos, Debug, PartialEq)]
+#[logos(test_match, subpattern digit = "[0-9]")]
+enum Token<'a> {
+    #[regex(r"[ \t\n]+", logos::skip)]
+    Whitespace,
+
+    #[token("let")]
+    #[token("SET", ignore(ascii_case))]
+    Let,
+
+    #[regex("(?&digit)+", |lex| lex.slice().parse().ok())]
+    #[regex("0x[0-9a-f]+", |lex| u64::from_str_radix(&lex.slice()[2..], 16).ok())]
+    Number(u64),
+
+    #[regex("[a-z]+", |lex| lex.slice())]
+    Ident(&'a str),
+}
+
+#[derive(Logos, Debug, PartialEq)]
+#[logos(test_match)]
+enum Bytes {
+    #[token(b"\x00\xFF")]
+    Magic,
+
+    #[regex(b"[\x00-\x7F]+")]
+    Ascii,
+}
+
+#[test]
+fn in_isolation() {
+    assert_eq!(Token::test_match_Ident("let"), Some(3));
+    assert_eq!(Token::test_match_Ident("letter1"), Some(6));
+    assert_eq!(Token::test_match_Let("sEt"), Some(3));
+    assert_eq!(Token::test_match_Let("letter"), Some(3));
+    assert_eq!(Token::test_match_Let("le"), None);
+    assert_eq!(Token::test_match_Whitespace(" \n x"), Some(3));
+}
+
+#[test]
+fn multiple_definitions() {
+    assert_eq!(Token::test_match_Number("123abc"), Some(3));
+    assert_eq!(Token::test_match_Number("0x1fz"), Some(4));
+    assert_eq!(Token::test_match_Number("abc"), None);
+}
+
+#[test]
+fn callbacks_are_not_run() {
+    assert_eq!(
+        Token::test_match_Number("99999999999999999999999"),
+        Some(23)
+    );
+}
+
+#[test]
+fn bytes() {
+    assert_eq!(Bytes::test_match_Magic(b"\x00\xFF\x00"), Some(2));
+    assert_eq!(Bytes::test_match_Ascii(b"\x00\xFF"), Some(1));
+}
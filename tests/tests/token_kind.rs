@@ -0,0 +1,109 @@
+use logos::Logos;
+use std::collections::HashSet;
+
+#[derive(Logos, Debug, PartialEq)]
+#[logos(kind = Kind, kind_derive(PartialOrd, Ord))]
+enum Token<'a> {
+    #[regex(" +", logos::skip)]
+    Whitespace,
+
+    #[regex("[a-z]+")]
+    Ident(&'a str),
+
+    #[regex("[0-9]+", |lex| lex.slice().parse().ok())]
+    Number(u64),
+
+    #[token("=")]
+    Equals,
+
+    #[token(";")]
+    Semicolon,
+}
+
+#[test]
+fn kinds() {
+    let kinds: Vec<Kind> = Token::lexer("x = 10; y = x;")
+        .map(|token| token.unwrap().kind())
+        .collect();
+
+    assert_eq!(
+        kinds,
+        &[
+            Kind::Ident,
+            Kind::Equals,
+            Kind::Number,
+            Kind::Semicolon,
+            Kind::Ident,
+            Kind::Equals,
+            Kind::Ident,
+            Kind::Semicolon,
+        ]
+    );
+
+    let unique: HashSet<Kind> = kinds.into_iter().collect();
+
+    assert_eq!(unique.len(), 4);
+    assert!(Kind::Ident < Kind::Semicolon);
+}
+
+#[test]
+fn tables() {
+    assert_eq!(Kind::COUNT, 5);
+    assert_eq!(
+        Kind::ALL,
+        [
+            Kind::Whitespace,
+            Kind::Ident,
+            Kind::Number,
+            Kind::Equals,
+            Kind::Semicolon
+        ]
+    );
+    assert_eq!(
+        Kind::NAMES,
+        ["Whitespace", "Ident", "Number", "Equals", "Semicolon"]
+    );
+
+    for (index, kind) in Kind::ALL.into_iter().enumerate() {
+        assert_eq!(kind.index(), index);
+        assert_eq!(Kind::from_index(index), Some(kind));
+        assert_eq!(kind.name(), Kind::NAMES[index]);
+    }
+
+    assert_eq!(Kind::from_index(Kind::COUNT), None);
+}
+
+#[test]
+fn const_tables() {
+    const EQUALS: usize = Kind::Equals.index();
+    const LAST: Option<Kind> = Kind::from_index(4);
+    const NAME: &str = Kind::Number.name();
+
+    assert_eq!(EQUALS, 3);
+    assert_eq!(LAST, Some(Kind::Semicolon));
+    assert_eq!(NAME, "Number");
+}
+
+mod generic {
+    use logos::Logos;
+
+    #[derive(Logos, Debug, PartialEq)]
+    #[logos(type T = u8)]
+    #[logos(kind = Kind)]
+    enum Token<T> {
+        #[regex("[0-9]", |lex| lex.slice().parse().ok())]
+        Digit(T),
+
+        #[token(",")]
+        Comma,
+    }
+
+    #[test]
+    fn kinds() {
+        let kinds: Vec<Kind> = Token::lexer("1,2")
+            .map(|token| token.unwrap().kind())
+            .collect();
+
+        assert_eq!(kinds, &[Kind::Digit, Kind::Comma, Kind::Digit]);
+    }
+}
@@ -0,0 +1,104 @@
+use logos::iter::TokenWithTrivia;
+use logos::{LexerExt, Logos, UnknownToken};
+
+#[derive(Logos, Debug, PartialEq)]
+enum Token {
+    #[regex(r"[ \t\n]+", logos::skip)]
+    Whitespace,
+
+    #[regex(r"//[^\n]*", logos::skip)]
+    Comment,
+
+    #[regex("[a-z]+")]
+    Word,
+
+    #[token(";")]
+    Semicolon,
+}
+
+fn pieces(source: &str) -> Vec<(&str, &str, &str)> {
+    Token::lexer(source)
+        .with_trivia()
+        .map(|token| {
+            (
+                &source[token.leading],
+                &source[token.span],
+                &source[token.trailing],
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn leading_trivia() {
+    let mut lexer = Token::lexer("  // a\nfoo;bar");
+
+    assert_eq!(lexer.next(), Some(Ok(Token::Word)));
+    assert_eq!(lexer.leading_trivia(), "  // a\n");
+    assert_eq!(lexer.next(), Some(Ok(Token::Semicolon)));
+    assert_eq!(lexer.leading_trivia(), "");
+    assert_eq!(lexer.next(), Some(Ok(Token::Word)));
+    assert_eq!(lexer.leading_trivia(), "");
+    assert_eq!(lexer.next(), None);
+}
+
+#[test]
+fn split_at_end_of_line() {
+    assert_eq!(
+        pieces("\n// doc\nfoo; // trailing\n\n  // leading\nbar\n"),
+        &[
+            ("\n// doc\n", "foo", ""),
+            ("", ";", " // trailing\n"),
+            ("\n  // leading\n", "bar", "\n"),
+        ],
+    );
+}
+
+#[test]
+fn same_line() {
+    assert_eq!(pieces("foo  bar"), &[("", "foo", "  "), ("", "bar", "")],);
+}
+
+#[test]
+fn covers_the_whole_source() {
+    let source = "  a // x\n ?b;\n\n// end\n";
+    let tokens: Vec<_> = Token::lexer(source).with_trivia().collect();
+
+    assert_eq!(
+        tokens[1],
+        TokenWithTrivia {
+            token: Err(UnknownToken),
+            span: 10..11,
+            leading: 9..10,
+            trailing: 11..11,
+        },
+    );
+
+    let mut rebuilt = String::new();
+
+    for token in &tokens {
+        rebuilt.push_str(&source[token.leading.clone()]);
+        rebuilt.push_str(&source[token.span.clone()]);
+        rebuilt.push_str(&source[token.trailing.clone()]);
+    }
+
+    assert_eq!(rebuilt, source);
+}
+
+#[test]
+fn span_offset() {
+    let tokens: Vec<_> = Token::lexer(" a ")
+        .with_span_offset(10)
+        .with_trivia()
+        .collect();
+
+    assert_eq!(
+        tokens,
+        &[TokenWithTrivia {
+            token: Ok(Token::Word),
+            span: 11..12,
+            leading: 10..11,
+            trailing: 12..13,
+        }],
+    );
+}
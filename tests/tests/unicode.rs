@@ -0,0 +1,112 @@
+use logos::{Logos, UnknownToken};
+use tests::assert_lex;
+
+mod characters_in_bytes {
+    use super::*;
+
+    #[derive(Logos, Debug, PartialEq)]
+    enum Token {
+        #[token(b" ", logos::skip)]
+        Whitespace,
+
+        #[regex(b"[^ ]", unicode = true)]
+        Char,
+
+        #[regex(b"(?-u)\\xFF")]
+        Invalid,
+    }
+
+    #[test]
+    fn classes_match_whole_characters() {
+        assert_lex(
+            "é ж\u{1F980}".as_bytes(),
+            &[
+                (Ok(Token::Char), "é".as_bytes(), 0..2),
+                (Ok(Token::Char), "ж".as_bytes(), 3..5),
+                (Ok(Token::Char), "\u{1F980}".as_bytes(), 5..9),
+            ],
+        );
+        assert_lex(
+            b"a\xFF" as &[u8],
+            &[
+                (Ok(Token::Char), b"a" as &[u8], 0..1),
+                (Ok(Token::Invalid), b"\xFF", 1..2),
+            ],
+        );
+    }
+}
+
+mod characters_in_strings {
+    use super::*;
+
+    #[derive(Logos, Debug, PartialEq)]
+    enum Token {
+        #[token(" ", logos::skip)]
+        Whitespace,
+
+        #[regex("[^ ]", unicode = true)]
+        Char,
+
+        #[regex("<.>", unicode = true)]
+        Tag,
+    }
+
+    #[test]
+    fn classes_match_whole_characters() {
+        assert_lex(
+            "aé <ж> \u{1F980}",
+            &[
+                (Ok(Token::Char), "a", 0..1),
+                (Ok(Token::Char), "é", 1..3),
+                (Ok(Token::Tag), "<ж>", 4..8),
+                (Ok(Token::Char), "\u{1F980}", 9..13),
+            ],
+        );
+    }
+}
+
+mod bytes_in_strings {
+    use super::*;
+
+    #[derive(Logos, Debug, PartialEq)]
+    #[logos(unicode = false)]
+    enum Token {
+        #[regex(r"\s+", logos::skip)]
+        Whitespace,
+
+        #[regex(r"\w+", priority = 2)]
+        Word,
+
+        // Longer matches win, so words with other letters in them are `Text`.
+        #[regex(r"\w+", unicode = true)]
+        Text,
+    }
+
+    #[test]
+    fn classes_match_ascii() {
+        assert_lex(
+            "abc_1 héllo",
+            &[
+                (Ok(Token::Word), "abc_1", 0..5),
+                (Ok(Token::Text), "héllo", 6..12),
+            ],
+        );
+    }
+
+    #[derive(Logos, Debug, PartialEq)]
+    enum Ascii {
+        #[regex(r"[\x00-`b-\x7F]+", unicode = false)]
+        NotA,
+    }
+
+    #[test]
+    fn other_characters_are_errors() {
+        assert_lex(
+            "xyé",
+            &[
+                (Ok(Ascii::NotA), "xy", 0..2),
+                (Err(UnknownToken), "é", 2..4),
+            ],
+        );
+    }
+}
@@ -0,0 +1,89 @@
+use logos::{Lexer, Logos};
+use tests::assert_lex;
+
+#[derive(Logos, Debug, Clone, Copy, PartialEq)]
+enum Token {
+    #[regex(r"[ \t\n]+", logos::skip)]
+    Whitespace,
+
+    #[regex("[a-z]+")]
+    Word,
+
+    #[token("/")]
+    Slash,
+
+    #[regex(r"/\*([^*]|\*[^/])*\*/", unterminated = UnterminatedComment)]
+    Comment,
+
+    UnterminatedComment,
+}
+
+#[test]
+fn terminated() {
+    assert_lex(
+        "a /* b */ / c",
+        &[
+            (Ok(Token::Word), "a", 0..1),
+            (Ok(Token::Comment), "/* b */", 2..9),
+            (Ok(Token::Slash), "/", 10..11),
+            (Ok(Token::Word), "c", 12..13),
+        ],
+    );
+}
+
+#[test]
+fn unterminated_variant() {
+    assert_lex(
+        "a /* b\nc * d",
+        &[
+            (Ok(Token::Word), "a", 0..1),
+            (Ok(Token::UnterminatedComment), "/* b\nc * d", 2..12),
+        ],
+    );
+    assert_lex("/*", &[(Ok(Token::UnterminatedComment), "/*", 0..2)]);
+    assert_lex("/* *", &[(Ok(Token::UnterminatedComment), "/* *", 0..4)]);
+
+    // The opening of the comment has to be there.
+    assert_lex(
+        "a /",
+        &[(Ok(Token::Word), "a", 0..1), (Ok(Token::Slash), "/", 2..3)],
+    );
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+enum Error {
+    #[default]
+    Unknown,
+    UnterminatedString,
+}
+
+impl<'s> logos::Error<'s, Text<'s>> for Error {
+    fn unknown_token(_: &Lexer<'s, Text<'s>>) -> Self {
+        Error::Unknown
+    }
+}
+
+#[derive(Logos, Debug, Clone, Copy, PartialEq)]
+#[logos(error = Error)]
+enum Text<'s> {
+    #[regex(r"[ \t\n]+", logos::skip)]
+    Whitespace,
+
+    #[regex(r#""([^"\\]|\\.)*""#, |lex| lex.slice(), unterminated = Error::UnterminatedString)]
+    String(&'s str),
+}
+
+#[test]
+fn unterminated_error() {
+    let mut lexer = Text::lexer(r#""a" "b\" c"#);
+
+    assert_eq!(lexer.next(), Some(Ok(Text::String(r#""a""#))));
+    assert_eq!(lexer.next(), Some(Err(Error::UnterminatedString)));
+    assert_eq!(lexer.span(), 4..10);
+    assert_eq!(lexer.next(), None);
+
+    let mut lexer = Text::lexer(r#""a\"#);
+
+    assert_eq!(lexer.next(), Some(Err(Error::UnterminatedString)));
+    assert_eq!(lexer.span(), 0..3);
+}
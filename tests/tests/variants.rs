@@ -0,0 +1,95 @@
+use logos::{Logos, VariantInfo};
+
+#[derive(Logos, Debug, PartialEq)]
+enum Token<'a> {
+    #[regex(r"[ \t\n]+", logos::skip)]
+    Whitespace,
+
+    #[regex("[a-z]+")]
+    Ident(&'a str),
+
+    #[regex("[0-9]+", |lex| lex.slice().parse().ok())]
+    Number(u64),
+
+    #[token("+")]
+    Plus,
+
+    #[token("*")]
+    #[logos(name = "times")]
+    Star,
+}
+
+#[test]
+fn variant_count() {
+    assert_eq!(<Token as Logos>::VARIANT_COUNT, 5);
+    assert_eq!(Token::ALL.len(), Token::VARIANT_COUNT);
+}
+
+#[test]
+fn indices() {
+    let indices: Vec<usize> = Token::lexer("a + 2 * b")
+        .map(|token| token.unwrap().index())
+        .collect();
+
+    assert_eq!(indices, &[1, 3, 2, 4, 1]);
+}
+
+#[test]
+fn all() {
+    assert_eq!(
+        Token::ALL,
+        &[
+            VariantInfo {
+                name: "Whitespace",
+                display_name: "Whitespace",
+                index: 0,
+                has_field: false,
+            },
+            VariantInfo {
+                name: "Ident",
+                display_name: "Ident",
+                index: 1,
+                has_field: true,
+            },
+            VariantInfo {
+                name: "Number",
+                display_name: "Number",
+                index: 2,
+                has_field: true,
+            },
+            VariantInfo {
+                name: "Plus",
+                display_name: "+",
+                index: 3,
+                has_field: false,
+            },
+            VariantInfo {
+                name: "Star",
+                display_name: "times",
+                index: 4,
+                has_field: false,
+            },
+        ]
+    );
+
+    for token in Token::lexer("x*1") {
+        let token = token.unwrap();
+
+        assert_eq!(Token::ALL[token.index()].index, token.index());
+    }
+}
+
+#[test]
+fn precedence_table() {
+    const PRECEDENCE: [Option<u8>; Token::VARIANT_COUNT] = [None, None, None, Some(1), Some(2)];
+
+    let operators: Vec<(&str, u8)> = Token::lexer("a + b * c")
+        .filter_map(|token| {
+            let index = token.unwrap().index();
+
+            Some((Token::ALL[index].display_name, PRECEDENCE[index]?))
+        })
+        .collect();
+
+    assert_eq!(operators, &[("+", 1), ("times", 2)]);
+}
@@ -0,0 +1,46 @@
+//! Every file in `tests/corpus` must lex without errors. The tests themselves are generated by `verify_corpus`.
+
+use logos::Logos;
+
+#[derive(Logos, Debug, PartialEq)]
+#[logos(verify_corpus = "corpus/*.src")]
+enum Token<'a> {
+    #[regex(r"[ \t\r\n]+", logos::skip)]
+    Whitespace,
+
+    #[token("let")]
+    Let,
+
+    #[regex("[a-z]+")]
+    Ident(&'a str),
+
+    #[regex("[0-9]+")]
+    Number,
+
+    #[regex(r#""[^"]*""#)]
+    String,
+
+    #[regex(r"[=+\-*/;()]")]
+    Punct,
+}
+
+#[derive(Logos, Debug, PartialEq)]
+#[logos(verify_corpus = "corpus/arith*.src")]
+enum Bytes {
+    #[regex(br"[ \n]+", logos::skip)]
+    Whitespace,
+
+    #[regex(b"[a-z0-9]+")]
+    Word,
+
+    #[regex(br"[=+\-*;()]")]
+    Punct,
+}
+
+#[test]
+fn corpus_is_lexed() {
+    let source = include_str!("../corpus/arithmetic.src");
+
+    assert_eq!(Token::lexer(source).next(), Some(Ok(Token::Let)));
+    assert_eq!(Bytes::lexer(source.as_bytes()).count(), 18);
+}